@@ -38,9 +38,8 @@ pub fn connect(db_path: &Path) -> Result<Connection> {
 
 pub fn init_db(db_path: &Path) -> Result<()> {
     if let Some(parent) = db_path.parent() {
-        std::fs::create_dir_all(parent).map_err(|err| {
-            rusqlite::Error::ToSqlConversionFailure(Box::new(err))
-        })?;
+        std::fs::create_dir_all(parent)
+            .map_err(|err| rusqlite::Error::ToSqlConversionFailure(Box::new(err)))?;
     }
     let conn = connect(db_path)?;
     conn.execute_batch(
@@ -129,3 +128,69 @@ pub fn init_db(db_path: &Path) -> Result<()> {
     )?;
     Ok(())
 }
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NewRun {
+    pub runtime: String,
+    pub model_label: Option<String>,
+    pub prompt_text: String,
+    pub settings_json: Option<String>,
+    pub selected_skills_json: Option<String>,
+    pub status: String,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub latency_ms: Option<i64>,
+    pub error_text: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NewRunOutput {
+    pub output_text: Option<String>,
+    pub stdout_text: Option<String>,
+    pub stderr_text: Option<String>,
+    pub return_code: Option<i64>,
+}
+
+pub fn insert_run(conn: &Connection, run: &NewRun) -> Result<i64> {
+    conn.execute(
+        r#"
+        INSERT INTO runs (
+            created_at, runtime, model_label, prompt_text, settings_json,
+            selected_skills_json, status, started_at, finished_at, latency_ms, error_text
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+        "#,
+        rusqlite::params![
+            utc_now().to_rfc3339(),
+            run.runtime,
+            run.model_label,
+            run.prompt_text,
+            run.settings_json,
+            run.selected_skills_json,
+            run.status,
+            run.started_at.map(|t| t.to_rfc3339()),
+            run.finished_at.map(|t| t.to_rfc3339()),
+            run.latency_ms,
+            run.error_text,
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn insert_run_output(conn: &Connection, run_id: i64, output: &NewRunOutput) -> Result<i64> {
+    conn.execute(
+        r#"
+        INSERT INTO run_outputs (
+            run_id, output_text, stdout_text, stderr_text, return_code, created_at
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+        "#,
+        rusqlite::params![
+            run_id,
+            output.output_text,
+            output.stdout_text,
+            output.stderr_text,
+            output.return_code,
+            utc_now().to_rfc3339(),
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
@@ -0,0 +1,80 @@
+/* Skills MCP C API. Generated by cbindgen from src/lib.rs; do not edit. */
+
+#ifndef SKILLS_MCP_H
+#define SKILLS_MCP_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// An opened skills directory.
+typedef struct SkillsIndex SkillsIndex;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Open and index the skills directory at `skills_dir`.
+//
+// Returns null on failure; see `skills_last_error()`. Close the index
+// with `skills_index_close()`.
+//
+// # Safety
+//
+// `skills_dir` must be null or a valid NUL-terminated string.
+struct SkillsIndex *skills_index_open(const char *skills_dir);
+
+// Re-read the skills directory. Returns false on failure; see
+// `skills_last_error()`.
+//
+// # Safety
+//
+// `index` must be null or a pointer returned by `skills_index_open()`
+// and not yet closed.
+bool skills_index_reload(const struct SkillsIndex *index);
+
+// Search skill metadata and content together, returning at most `limit`
+// results as JSON, or null on failure.
+//
+// # Safety
+//
+// `index` must be null or an open index, and `query` null or a valid
+// NUL-terminated string.
+char *skills_search(const struct SkillsIndex *index, const char *query, size_t limit);
+
+// A skill's content and metadata as JSON, or null if it can't be read;
+// see `skills_last_error()`.
+//
+// # Safety
+//
+// `index` must be null or an open index, and `name` null or a valid
+// NUL-terminated string.
+char *skills_get_skill(const struct SkillsIndex *index, const char *name);
+
+// Free a string returned by this library. Null is ignored.
+//
+// # Safety
+//
+// `s` must be null or a string returned by this library, freed once.
+void skills_free(char *s);
+
+// Close an index. Null is ignored.
+//
+// # Safety
+//
+// `index` must be null or an open index, closed once.
+void skills_index_close(struct SkillsIndex *index);
+
+// The calling thread's last error message, or null if there was none.
+//
+// The string is owned by the library and valid until the thread's next
+// call into it.
+const char *skills_last_error(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* SKILLS_MCP_H */
@@ -0,0 +1,271 @@
+//! C ABI for embedding the Skills MCP Rust core.
+//!
+//! The header is generated into `include/skills_mcp.h` on every build.
+//! Results are JSON strings shaped like the HTTP API's; each returned
+//! string is owned by the caller and released with `skills_free()`.
+//!
+//! ```c
+//! SkillsIndex *index = skills_index_open("./skills");
+//! if (!index) {
+//!     fprintf(stderr, "%s\n", skills_last_error());
+//!     return 1;
+//! }
+//! char *results = skills_search(index, "forms", 5);
+//! puts(results);
+//! skills_free(results);
+//! skills_index_close(index);
+//! ```
+//!
+//! An index may be shared between threads; errors are reported per
+//! thread through `skills_last_error()`.
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+use std::sync::Arc;
+
+use skills_mcp::index::SkillIndexer;
+use skills_mcp::models::SearchOptions;
+use skills_mcp::search::SearchService;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// An opened skills directory.
+pub struct SkillsIndex {
+    indexer: Arc<SkillIndexer>,
+    search: SearchService,
+}
+
+fn set_error(message: impl Into<String>) {
+    let message = CString::new(message.into().replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(message));
+}
+
+/// Read a non-null UTF-8 argument, recording an error otherwise.
+///
+/// # Safety
+///
+/// `arg` must be null or a valid NUL-terminated string.
+unsafe fn read_str<'a>(arg: *const c_char, name: &str) -> Option<&'a str> {
+    if arg.is_null() {
+        set_error(format!("{} is null", name));
+        return None;
+    }
+    match CStr::from_ptr(arg).to_str() {
+        Ok(s) => Some(s),
+        Err(_) => {
+            set_error(format!("{} is not valid UTF-8", name));
+            None
+        }
+    }
+}
+
+/// Serialize `value` into a caller-owned string.
+fn to_c_json(value: impl serde::Serialize) -> *mut c_char {
+    match serde_json::to_string(&value) {
+        // JSON escapes control characters, so there is no interior NUL
+        Ok(json) => CString::new(json).map_or(ptr::null_mut(), CString::into_raw),
+        Err(e) => {
+            set_error(format!("Failed to encode result: {}", e));
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Open and index the skills directory at `skills_dir`.
+///
+/// Returns null on failure; see `skills_last_error()`. Close the index
+/// with `skills_index_close()`.
+///
+/// # Safety
+///
+/// `skills_dir` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn skills_index_open(skills_dir: *const c_char) -> *mut SkillsIndex {
+    let Some(skills_dir) = read_str(skills_dir, "skills_dir") else {
+        return ptr::null_mut();
+    };
+
+    let indexer = Arc::new(SkillIndexer::new(skills_dir));
+    if let Err(e) = indexer.reload() {
+        set_error(e.to_string());
+        return ptr::null_mut();
+    }
+
+    let search = SearchService::new(Arc::clone(&indexer));
+    Box::into_raw(Box::new(SkillsIndex { indexer, search }))
+}
+
+/// Re-read the skills directory. Returns false on failure; see
+/// `skills_last_error()`.
+///
+/// # Safety
+///
+/// `index` must be null or a pointer returned by `skills_index_open()`
+/// and not yet closed.
+#[no_mangle]
+pub unsafe extern "C" fn skills_index_reload(index: *const SkillsIndex) -> bool {
+    let Some(index) = index.as_ref() else {
+        set_error("index is null");
+        return false;
+    };
+    match index.indexer.reload() {
+        Ok(()) => true,
+        Err(e) => {
+            set_error(e.to_string());
+            false
+        }
+    }
+}
+
+/// Search skill metadata and content together, returning at most `limit`
+/// results as JSON, or null on failure.
+///
+/// # Safety
+///
+/// `index` must be null or an open index, and `query` null or a valid
+/// NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn skills_search(
+    index: *const SkillsIndex,
+    query: *const c_char,
+    limit: usize,
+) -> *mut c_char {
+    let Some(index) = index.as_ref() else {
+        set_error("index is null");
+        return ptr::null_mut();
+    };
+    let Some(query) = read_str(query, "query") else {
+        return ptr::null_mut();
+    };
+
+    to_c_json(
+        index
+            .search
+            .search_all(query, SearchOptions::with_limit(limit)),
+    )
+}
+
+/// A skill's content and metadata as JSON, or null if it can't be read;
+/// see `skills_last_error()`.
+///
+/// # Safety
+///
+/// `index` must be null or an open index, and `name` null or a valid
+/// NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn skills_get_skill(
+    index: *const SkillsIndex,
+    name: *const c_char,
+) -> *mut c_char {
+    let Some(index) = index.as_ref() else {
+        set_error("index is null");
+        return ptr::null_mut();
+    };
+    let Some(name) = read_str(name, "name") else {
+        return ptr::null_mut();
+    };
+
+    match index.indexer.read_skill_content(name) {
+        Ok(content) => to_c_json(serde_json::json!({
+            "meta": index.indexer.get_skill_meta(name),
+            "content": content,
+        })),
+        Err(e) => {
+            set_error(e.to_string());
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Free a string returned by this library. Null is ignored.
+///
+/// # Safety
+///
+/// `s` must be null or a string returned by this library, freed once.
+#[no_mangle]
+pub unsafe extern "C" fn skills_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// Close an index. Null is ignored.
+///
+/// # Safety
+///
+/// `index` must be null or an open index, closed once.
+#[no_mangle]
+pub unsafe extern "C" fn skills_index_close(index: *mut SkillsIndex) {
+    if !index.is_null() {
+        drop(Box::from_raw(index));
+    }
+}
+
+/// The calling thread's last error message, or null if there was none.
+///
+/// The string is owned by the library and valid until the thread's next
+/// call into it.
+#[no_mangle]
+pub extern "C" fn skills_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map_or(ptr::null(), |s| s.as_ptr()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn c(s: &str) -> CString {
+        CString::new(s).unwrap()
+    }
+
+    unsafe fn take(s: *mut c_char) -> serde_json::Value {
+        assert!(!s.is_null());
+        let json = serde_json::from_str(CStr::from_ptr(s).to_str().unwrap()).unwrap();
+        skills_free(s);
+        json
+    }
+
+    #[test]
+    fn test_c_api() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        fs::create_dir_all(dir.join("forms")).unwrap();
+        fs::write(
+            dir.join("forms/_meta.json"),
+            r#"{"name": "forms", "description": "Form handling", "tags": ["input"]}"#,
+        )
+        .unwrap();
+        fs::write(dir.join("forms/SKILL.md"), "# Forms\n\nValidate input.").unwrap();
+
+        unsafe {
+            let index = skills_index_open(c(dir.to_str().unwrap()).as_ptr());
+            assert!(!index.is_null());
+
+            let results = take(skills_search(index, c("forms").as_ptr(), 5));
+            assert_eq!(results["results"][0]["domain"], "forms");
+
+            let skill = take(skills_get_skill(index, c("forms").as_ptr()));
+            assert_eq!(skill["meta"]["name"], "forms");
+            assert!(skill["content"]["content"]
+                .as_str()
+                .unwrap()
+                .contains("Validate input."));
+
+            assert!(skills_get_skill(index, c("missing").as_ptr()).is_null());
+            assert!(!skills_last_error().is_null());
+
+            assert!(skills_search(index, ptr::null(), 5).is_null());
+            let error = CStr::from_ptr(skills_last_error()).to_str().unwrap();
+            assert_eq!(error, "query is null");
+
+            assert!(skills_index_reload(index));
+            skills_index_close(index);
+            skills_free(ptr::null_mut());
+        }
+    }
+}
@@ -0,0 +1,257 @@
+//! Node.js bindings for the Skills MCP Rust core.
+//!
+//! Mirrors the TypeScript server's `SkillIndexer`, `SearchService` and
+//! `Validator` so it can swap them for these without changing callers.
+//! Methods are synchronous; awaiting them, as the TypeScript callers do,
+//! works unchanged.
+//!
+//! ```js
+//! import { SkillIndexer, SearchService } from '@skills-mcp/native';
+//!
+//! const indexer = new SkillIndexer('./skills');
+//! indexer.reload();
+//! const results = new SearchService(indexer).searchSkills('forms', 5);
+//! ```
+
+#![deny(clippy::all)]
+
+use std::sync::Arc;
+
+use napi::{Error, Result};
+use napi_derive::napi;
+use serde_json::Value;
+
+use skills_mcp::index::SkillIndexer as Indexer;
+use skills_mcp::models::{SearchOptions, SearchResults};
+use skills_mcp::search::SearchService as Search;
+use skills_mcp::validation::SkillValidator;
+
+/// Default result limit, as in the TypeScript services.
+const DEFAULT_LIMIT: u32 = 10;
+
+fn js_error(e: impl std::fmt::Display) -> Error {
+    Error::from_reason(e.to_string())
+}
+
+fn to_json(value: impl serde::Serialize) -> Result<Value> {
+    serde_json::to_value(value).map_err(js_error)
+}
+
+/// Counts returned by `SkillIndexer.reload()`.
+#[napi(object)]
+pub struct ReloadResult {
+    /// Skills indexed.
+    pub skill_count: u32,
+    /// Content files indexed.
+    pub content_files_indexed: u32,
+}
+
+/// A search hit, shaped like the TypeScript `SearchResult`.
+#[napi(object)]
+pub struct SearchResult {
+    /// Skill domain name.
+    pub domain: String,
+    /// Sub-skill name, for sub-skill hits.
+    pub sub_skill: Option<String>,
+    /// Relevance score.
+    pub score: f64,
+    /// `name`, `description`, `tags`, `triggers` or `content`.
+    pub match_type: String,
+    /// Content snippet.
+    pub snippet: Option<String>,
+    /// File path, for content hits.
+    pub file: Option<String>,
+}
+
+/// Validation outcome, shaped like the TypeScript `ValidationResult`.
+#[napi(object)]
+pub struct ValidationResult {
+    /// Whether validation passed.
+    pub valid: bool,
+    /// Critical errors.
+    pub errors: Vec<String>,
+    /// Non-critical warnings.
+    pub warnings: Vec<String>,
+    /// Number of skills checked.
+    pub skills_checked: u32,
+}
+
+impl From<skills_mcp::models::ValidationResult> for ValidationResult {
+    fn from(result: skills_mcp::models::ValidationResult) -> Self {
+        Self {
+            valid: result.valid,
+            errors: result.errors,
+            warnings: result.warnings,
+            skills_checked: result.skills_checked as u32,
+        }
+    }
+}
+
+/// Indexes a skills directory.
+#[napi]
+pub struct SkillIndexer {
+    inner: Arc<Indexer>,
+}
+
+#[napi]
+impl SkillIndexer {
+    /// An indexer over `skills_dir`; call `reload()` to load it.
+    #[napi(constructor)]
+    pub fn new(skills_dir: String) -> Self {
+        Self {
+            inner: Arc::new(Indexer::new(skills_dir)),
+        }
+    }
+
+    /// Rebuild both indexes from disk.
+    #[napi]
+    pub fn reload(&self) -> Result<ReloadResult> {
+        self.inner.reload().map_err(js_error)?;
+        Ok(ReloadResult {
+            skill_count: self.inner.get_skill_index().skills.len() as u32,
+            content_files_indexed: self.inner.get_content_index().len() as u32,
+        })
+    }
+
+    /// Index version; changes whenever the index does.
+    #[napi(getter)]
+    pub fn version(&self) -> i64 {
+        self.inner.version() as i64
+    }
+
+    /// The skill metadata index.
+    #[napi]
+    pub fn get_skill_index(&self) -> Result<Value> {
+        to_json(self.inner.get_skill_index())
+    }
+
+    /// The content index.
+    #[napi]
+    pub fn get_content_index(&self) -> Result<Value> {
+        to_json(self.inner.get_content_index())
+    }
+
+    /// A skill's metadata, or null.
+    #[napi]
+    pub fn get_skill_meta(&self, name: String) -> Result<Option<Value>> {
+        self.inner.get_skill_meta(&name).map(to_json).transpose()
+    }
+
+    /// Whether a skill exists on disk.
+    #[napi]
+    pub fn skill_exists(&self, name: String) -> bool {
+        self.inner.skill_exists(&name)
+    }
+
+    /// A skill's SKILL.md, or null.
+    #[napi]
+    pub fn read_skill_content(&self, name: String) -> Option<String> {
+        self.inner
+            .read_skill_content(&name)
+            .ok()
+            .map(|skill| skill.content)
+    }
+
+    /// A sub-skill's document, or null.
+    #[napi]
+    pub fn read_sub_skill_content(&self, domain: String, sub_skill: String) -> Option<String> {
+        self.inner
+            .read_sub_skill_content(&domain, &sub_skill)
+            .ok()
+            .map(|sub| sub.content)
+    }
+
+    /// Whether a skill has a references directory.
+    #[napi]
+    pub fn has_references(&self, name: String) -> bool {
+        self.inner.has_references(&name)
+    }
+}
+
+/// Searches an indexer's skills and content.
+#[napi]
+pub struct SearchService {
+    inner: Search,
+}
+
+#[napi]
+impl SearchService {
+    /// Search over `indexer`, following its reloads.
+    #[napi(constructor)]
+    pub fn new(indexer: &SkillIndexer) -> Self {
+        Self {
+            inner: Search::new(Arc::clone(&indexer.inner)),
+        }
+    }
+
+    /// Search skills by metadata (name, description, tags, triggers).
+    #[napi]
+    pub fn search_skills(&self, query: String, limit: Option<u32>) -> Vec<SearchResult> {
+        let options = SearchOptions::with_limit(limit.unwrap_or(DEFAULT_LIMIT) as usize);
+        results(self.inner.search_skills(&query, options))
+    }
+
+    /// Search content by full-text matching.
+    #[napi]
+    pub fn search_content(&self, query: String, limit: Option<u32>) -> Vec<SearchResult> {
+        let options = SearchOptions::with_limit(limit.unwrap_or(DEFAULT_LIMIT) as usize);
+        results(self.inner.search_content(&query, options))
+    }
+
+    /// Search with the full Rust result page (cursor, suggestions,
+    /// summaries), as `GET /api/search` returns it.
+    #[napi]
+    pub fn search_all(&self, query: String, limit: Option<u32>) -> Result<Value> {
+        let options = SearchOptions::with_limit(limit.unwrap_or(DEFAULT_LIMIT) as usize);
+        to_json(self.inner.search_all(&query, options))
+    }
+}
+
+fn results(results: SearchResults) -> Vec<SearchResult> {
+    results
+        .results
+        .into_iter()
+        .map(|result| SearchResult {
+            domain: result.domain,
+            sub_skill: result.sub_skill,
+            score: result.score,
+            match_type: serde_json::to_value(result.match_type)
+                .ok()
+                .and_then(|v| v.as_str().map(str::to_string))
+                .unwrap_or_default(),
+            snippet: result.snippet,
+            file: result.file,
+        })
+        .collect()
+}
+
+/// Validates skills on disk.
+#[napi]
+pub struct Validator {
+    skills_dir: String,
+}
+
+#[napi]
+impl Validator {
+    /// A validator for `skills_dir`.
+    #[napi(constructor)]
+    pub fn new(skills_dir: String) -> Self {
+        Self { skills_dir }
+    }
+
+    /// Validate every skill, or only `skill_name`.
+    #[napi]
+    pub fn validate(&self, skill_name: Option<String>) -> Result<ValidationResult> {
+        let indexer = Arc::new(Indexer::new(&self.skills_dir));
+        indexer.reload().map_err(js_error)?;
+        let validator = SkillValidator::new(indexer);
+
+        let result = match skill_name {
+            Some(name) => validator
+                .validate_one(&name)
+                .ok_or_else(|| js_error(format!("Skill '{}' not found", name)))?,
+            None => validator.validate_all(),
+        };
+        Ok(result.into())
+    }
+}
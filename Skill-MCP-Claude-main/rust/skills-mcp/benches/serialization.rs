@@ -0,0 +1,72 @@
+//! Encoding large tool results for MCP clients.
+//!
+//! Each group compares the old path, which built a `serde_json::Value`
+//! tree and then printed it, with `call_tool_text`, which writes the
+//! response straight to a string.
+//!
+//! Run with: cargo bench --bench serialization
+
+use std::sync::Arc;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use serde_json::{json, Value};
+
+use skills_mcp::index::SkillIndexer;
+use skills_mcp::mcp::{call_tool, call_tool_text, ServiceContext};
+use skills_mcp::store::SkillFixture;
+
+const SKILL_COUNTS: &[usize] = &[100, 1000];
+
+fn context(skills: usize) -> ServiceContext {
+    let fixtures = (0..skills)
+        .map(|i| {
+            SkillFixture::new(
+                format!("skill-{:04}", i),
+                format!(
+                    "Patterns for working with component {} in forms and layouts",
+                    i
+                ),
+            )
+            .with_tags(["frontend", "forms", "layout"])
+            .with_content(format!(
+                "# Skill {}\n\nForm validation, layout grids and accessible inputs.\n",
+                i
+            ))
+            .with_sub_skill(
+                "validation",
+                "# Validation\n\nSchema-driven form validation.",
+            )
+        })
+        .collect();
+    ServiceContext::new(Arc::new(SkillIndexer::from_memory(fixtures).unwrap()))
+}
+
+fn bench_tool(c: &mut Criterion, group: &str, tool: &str, arguments: Value) {
+    let mut group = c.benchmark_group(group);
+    for &skills in SKILL_COUNTS {
+        let ctx = context(skills);
+        group.bench_with_input(BenchmarkId::new("value", skills), &ctx, |b, ctx| {
+            b.iter(|| call_tool(ctx, tool, arguments.clone()).unwrap().to_string())
+        });
+        group.bench_with_input(BenchmarkId::new("direct", skills), &ctx, |b, ctx| {
+            b.iter(|| call_tool_text(ctx, tool, arguments.clone()).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn list_skills(c: &mut Criterion) {
+    bench_tool(c, "list_skills", "list_skills", Value::Null);
+}
+
+fn search_content(c: &mut Criterion) {
+    bench_tool(
+        c,
+        "search_content",
+        "search_content",
+        json!({"query": "form validation", "limit": 100}),
+    );
+}
+
+criterion_group!(benches, list_skills, search_content);
+criterion_main!(benches);
@@ -0,0 +1,64 @@
+//! `?case=camel`: JSON responses with the camelCase field names of the
+//! TypeScript server, for clients written against it.
+
+use axum::{
+    body::Body,
+    extract::{Query, Request},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::models::{ErrorResponse, FieldCase};
+
+/// Query parameter choosing the field case of a response.
+#[derive(Debug, Default, Deserialize)]
+pub struct CaseQuery {
+    /// `snake` (the default) or `camel`.
+    #[serde(default)]
+    pub case: FieldCase,
+}
+
+/// Middleware rewriting JSON responses into the requested field case.
+///
+/// Requests without `case`, and responses that aren't JSON, pass straight
+/// through; rewritten responses are buffered whole.
+pub async fn rewrite_field_case(
+    Query(query): Query<CaseQuery>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let response = next.run(request).await;
+    let json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"));
+    if query.case == FieldCase::Snake || !json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let body = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(e) => {
+            let message = format!("Failed to read response: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::new(message)),
+            )
+                .into_response();
+        }
+    };
+    let mut value: Value = match serde_json::from_slice(&body) {
+        Ok(value) => value,
+        Err(_) => return Response::from_parts(parts, Body::from(body)),
+    };
+    query.case.apply(&mut value);
+
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(value.to_string()))
+}
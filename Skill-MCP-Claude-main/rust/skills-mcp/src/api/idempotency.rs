@@ -0,0 +1,380 @@
+//! Idempotency keys: replay the first response to a retried mutation.
+//!
+//! A client sends the same `Idempotency-Key` header on every attempt of a
+//! POST, PUT, PATCH or DELETE. The first attempt runs and its response is
+//! kept for the TTL; later attempts with the same key and request get that
+//! response back instead of running again. Reusing a key for a different
+//! request is an error, as is retrying while the first attempt still runs.
+//! A first attempt that never finishes, because its client went away, frees
+//! its key so the retry can run.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use axum::{
+    body::{Body, Bytes, HttpBody},
+    extract::{Request, State},
+    http::{header, HeaderMap, HeaderName, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use parking_lot::Mutex;
+use sha2::{Digest, Sha256};
+
+use crate::models::ErrorResponse;
+
+use super::routes::MAX_UPLOAD_BODY_SIZE;
+
+/// Header carrying the client's key for a request.
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Header set on responses replayed for a repeated key.
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+
+/// How long a response is kept for replay unless configured otherwise.
+pub const DEFAULT_IDEMPOTENCY_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Longest key accepted.
+const MAX_KEY_LENGTH: usize = 255;
+
+/// Largest response body kept for replay; bigger responses, and streamed
+/// ones of unknown size, aren't kept, so their keys can be retried.
+const MAX_STORED_BODY: usize = 1024 * 1024;
+
+/// Most keys remembered at once unless configured otherwise.
+pub const DEFAULT_MAX_IDEMPOTENCY_KEYS: usize = 10_000;
+
+/// Response headers replayed along with the status and body.
+const REPLAYED_HEADERS: [HeaderName; 3] = [header::CONTENT_TYPE, header::LOCATION, header::ETAG];
+
+/// A key's request and, once it has finished, its response.
+struct Entry {
+    /// Hash of the method, URI and body the key was first used with.
+    request: [u8; 32],
+    created: Instant,
+    /// `None` while the first request is still running.
+    response: Option<StoredResponse>,
+}
+
+#[derive(Clone)]
+struct StoredResponse {
+    status: StatusCode,
+    /// The [`REPLAYED_HEADERS`] the response had.
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+impl StoredResponse {
+    fn replay(self) -> Response {
+        let mut response = (self.status, self.body).into_response();
+        let headers = response.headers_mut();
+        headers.extend(self.headers);
+        headers.insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+        response
+    }
+}
+
+/// Responses to mutating requests, by idempotency key, kept for a TTL.
+pub struct IdempotencyStore {
+    ttl: Duration,
+    max_entries: usize,
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+impl IdempotencyStore {
+    /// Keep responses for `ttl`.
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            max_entries: DEFAULT_MAX_IDEMPOTENCY_KEYS,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Remember at most `max_entries` keys; when full, the oldest finished
+    /// response is forgotten to make room.
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    /// Number of keys currently remembered.
+    pub fn len(&self) -> usize {
+        self.entries.lock().len()
+    }
+
+    /// Whether no keys are remembered.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for IdempotencyStore {
+    fn default() -> Self {
+        Self::new(DEFAULT_IDEMPOTENCY_TTL)
+    }
+}
+
+fn error(status: StatusCode, message: String) -> Response {
+    (status, Json(ErrorResponse::new(message))).into_response()
+}
+
+/// A key whose first request is running; forgotten when dropped unless
+/// its response was kept, so a request abandoned midway doesn't hold the
+/// key until the TTL runs out.
+struct PendingKey {
+    store: Arc<IdempotencyStore>,
+    key: String,
+    created: Instant,
+    done: bool,
+}
+
+impl PendingKey {
+    /// Keep `response` for replay.
+    fn keep(mut self, response: StoredResponse) {
+        if let Some(entry) = self.store.entries.lock().get_mut(&self.key) {
+            if entry.created == self.created {
+                entry.response = Some(response);
+            }
+        }
+        self.done = true;
+    }
+}
+
+impl Drop for PendingKey {
+    fn drop(&mut self) {
+        if self.done {
+            return;
+        }
+        let mut entries = self.store.entries.lock();
+        if entries
+            .get(&self.key)
+            .is_some_and(|entry| entry.created == self.created)
+        {
+            entries.remove(&self.key);
+        }
+    }
+}
+
+/// Middleware running each keyed mutating request at most once per TTL.
+///
+/// Requests without the header, and reads, pass straight through. Server
+/// errors, failures to read the body and requests dropped before they
+/// finish aren't kept, so the client can retry them with the same key.
+pub async fn remember_responses(
+    State(store): State<Arc<IdempotencyStore>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let mutating = matches!(
+        *request.method(),
+        Method::POST | Method::PUT | Method::PATCH | Method::DELETE
+    );
+    let key = match request.headers().get(IDEMPOTENCY_KEY_HEADER) {
+        Some(key) if mutating => key,
+        _ => return next.run(request).await,
+    };
+    let key = match key.to_str() {
+        Ok(key) if !key.is_empty() && key.len() <= MAX_KEY_LENGTH => key.to_string(),
+        _ => {
+            return error(
+                StatusCode::BAD_REQUEST,
+                format!(
+                    "Idempotency-Key must be 1 to {} visible ASCII characters",
+                    MAX_KEY_LENGTH
+                ),
+            )
+        }
+    };
+
+    let (parts, body) = request.into_parts();
+    let body = match axum::body::to_bytes(body, MAX_UPLOAD_BODY_SIZE).await {
+        Ok(body) => body,
+        Err(e) => {
+            return error(
+                StatusCode::BAD_REQUEST,
+                format!("Failed to read body: {}", e),
+            )
+        }
+    };
+    let mut hasher = Sha256::new();
+    hasher.update(parts.method.as_str().as_bytes());
+    hasher.update(b" ");
+    hasher.update(parts.uri.to_string().as_bytes());
+    hasher.update(b"\n");
+    hasher.update(&body);
+    let hash: [u8; 32] = hasher.finalize().into();
+
+    let pending = {
+        let mut entries = store.entries.lock();
+        entries.retain(|_, entry| entry.created.elapsed() < store.ttl);
+        match entries.get(&key) {
+            Some(entry) if entry.request != hash => {
+                return error(
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    "Idempotency-Key was already used for a different request".to_string(),
+                );
+            }
+            Some(Entry {
+                response: Some(response),
+                ..
+            }) => return response.clone().replay(),
+            Some(_) => {
+                return error(
+                    StatusCode::CONFLICT,
+                    "A request with this Idempotency-Key is still being processed".to_string(),
+                );
+            }
+            None => {}
+        }
+
+        if entries.len() >= store.max_entries {
+            let oldest = entries
+                .iter()
+                .filter(|(_, entry)| entry.response.is_some())
+                .min_by_key(|(_, entry)| entry.created)
+                .map(|(key, _)| key.clone());
+            let Some(oldest) = oldest else {
+                return error(
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    "Too many requests with an Idempotency-Key are in progress".to_string(),
+                );
+            };
+            entries.remove(&oldest);
+        }
+        let created = Instant::now();
+        entries.insert(
+            key.clone(),
+            Entry {
+                request: hash,
+                created,
+                response: None,
+            },
+        );
+        PendingKey {
+            store: Arc::clone(&store),
+            key,
+            created,
+            done: false,
+        }
+    };
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+    // Server errors and responses too big, or of unknown size, to keep go
+    // straight back, and the pending key is forgotten
+    let fits = response
+        .body()
+        .size_hint()
+        .upper()
+        .is_some_and(|upper| upper <= MAX_STORED_BODY as u64);
+    if response.status().is_server_error() || !fits {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = match axum::body::to_bytes(body, MAX_STORED_BODY).await {
+        Ok(body) => body,
+        Err(e) => {
+            return error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to read response: {}", e),
+            );
+        }
+    };
+
+    let headers = REPLAYED_HEADERS
+        .iter()
+        .filter_map(|name| Some((name.clone(), parts.headers.get(name)?.clone())))
+        .collect();
+    pending.keep(StoredResponse {
+        status: parts.status,
+        headers,
+        body: body.clone(),
+    });
+    Response::from_parts(parts, Body::from(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{middleware, routing::post, Router};
+    use tower::ServiceExt;
+
+    async fn created() -> impl IntoResponse {
+        (
+            StatusCode::CREATED,
+            [(header::LOCATION, "/items/1")],
+            "made",
+        )
+    }
+
+    /// Never finishes, like a handler whose client gave up on it.
+    async fn slow() -> &'static str {
+        std::future::pending().await
+    }
+
+    fn app(store: &Arc<IdempotencyStore>) -> Router {
+        Router::new()
+            .route("/created", post(created))
+            .route("/slow", post(slow))
+            .layer(middleware::from_fn_with_state(
+                Arc::clone(store),
+                remember_responses,
+            ))
+    }
+
+    fn keyed(uri: &str, key: &str) -> Request {
+        Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header(IDEMPOTENCY_KEY_HEADER, key)
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_replayed_headers() {
+        let store = Arc::new(IdempotencyStore::default());
+        app(&store).oneshot(keyed("/created", "a")).await.unwrap();
+
+        let retry = app(&store).oneshot(keyed("/created", "a")).await.unwrap();
+        assert_eq!(retry.status(), StatusCode::CREATED);
+        assert_eq!(retry.headers()[header::LOCATION], "/items/1");
+        assert_eq!(retry.headers()[IDEMPOTENT_REPLAYED_HEADER], "true");
+    }
+
+    #[tokio::test]
+    async fn test_abandoned_request_frees_key() {
+        let store = Arc::new(IdempotencyStore::default());
+        let request = app(&store).oneshot(keyed("/slow", "a"));
+        let timeout = tokio::time::timeout(Duration::from_millis(20), request).await;
+        assert!(timeout.is_err());
+        assert!(store.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_max_entries() {
+        let store = Arc::new(IdempotencyStore::default().with_max_entries(2));
+        for key in ["a", "b", "c"] {
+            let response = app(&store).oneshot(keyed("/created", key)).await.unwrap();
+            assert_eq!(response.status(), StatusCode::CREATED);
+        }
+        assert_eq!(store.len(), 2);
+
+        // The oldest was forgotten, so its key runs again
+        let response = app(&store).oneshot(keyed("/created", "a")).await.unwrap();
+        assert!(response.headers().get(IDEMPOTENT_REPLAYED_HEADER).is_none());
+
+        // With every slot taken by a running request, new keys wait
+        let store = Arc::new(IdempotencyStore::default().with_max_entries(1));
+        let slow = tokio::spawn(app(&store).oneshot(keyed("/slow", "a")));
+        while store.is_empty() {
+            tokio::task::yield_now().await;
+        }
+        let response = app(&store).oneshot(keyed("/created", "b")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        slow.abort();
+    }
+}
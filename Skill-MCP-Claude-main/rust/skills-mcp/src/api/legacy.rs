@@ -0,0 +1,177 @@
+//! `/skills_manager/*`: the routes of the old Flask dashboard.
+//!
+//! Each route hands its request to the matching `/api` handler and
+//! reshapes the result as the Flask server did: listings wrapped in a
+//! `skills` object and writes acknowledged with `success`. Errors keep the
+//! `{"error": ...}` body both servers share. Mounted only when
+//! [`ApiServer::with_legacy_routes`](super::ApiServer::with_legacy_routes)
+//! is set.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{delete, get, post, put},
+    Json, Router,
+};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::models::ErrorResponse;
+
+use super::routes::{
+    self, AppState, CreateSkillRequest, DryRunQuery, ListSkillsQuery, UpdateSkillRequest,
+};
+
+type LegacyResult = Result<Response, (StatusCode, Json<ErrorResponse>)>;
+
+/// The legacy routes, to be nested at `/skills_manager`.
+pub fn legacy_routes() -> Router<AppState> {
+    Router::new()
+        .route("/list", get(list_skills))
+        .route("/skill/:name", get(routes::get_skill))
+        .route("/create", post(create_skill))
+        .route("/update/:name", put(update_skill))
+        .route("/delete/:name", delete(delete_skill))
+        .route("/reload", post(reload_index))
+}
+
+/// `GET /skills_manager/list`: the listing as `{"skills": [...]}`.
+async fn list_skills(State(state): State<AppState>) -> LegacyResult {
+    let response = routes::list_skills(State(state), Query(ListSkillsQuery::default())).await?;
+    let skills = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::new(format!("Failed to list skills: {}", e))),
+            )
+        })?;
+
+    let mut body = Vec::with_capacity(skills.len() + 12);
+    body.extend_from_slice(br#"{"skills":"#);
+    body.extend_from_slice(&skills);
+    body.push(b'}');
+    Ok(([(header::CONTENT_TYPE, "application/json")], body).into_response())
+}
+
+/// Body of `POST /skills_manager/create`, as the Flask dashboard sent it.
+#[derive(Debug, Deserialize)]
+pub struct LegacyCreateRequest {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub content: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Replace an existing skill of the same name.
+    #[serde(default)]
+    pub overwrite: bool,
+}
+
+/// `POST /skills_manager/create`: create a skill, or update it when it
+/// exists and `overwrite` is set.
+async fn create_skill(
+    State(state): State<AppState>,
+    Json(req): Json<LegacyCreateRequest>,
+) -> LegacyResult {
+    let name = req.name.clone();
+    let path = state.indexer.skills_dir().join(&name);
+
+    if req.overwrite && state.indexer.skill_exists(&name) {
+        let update = UpdateSkillRequest {
+            description: Some(req.description),
+            content: Some(req.content),
+            tags: Some(req.tags),
+            pinned: None,
+        };
+        routes::update_skill(
+            State(state),
+            Path(name.clone()),
+            Query(DryRunQuery::default()),
+            Json(update),
+        )
+        .await?;
+    } else {
+        let create = CreateSkillRequest {
+            name: req.name,
+            description: req.description,
+            content: Some(req.content),
+            tags: req.tags,
+            flat: false,
+            template: None,
+            variables: Default::default(),
+            pinned: false,
+        };
+        routes::create_skill(State(state), Query(DryRunQuery::default()), Json(create)).await?;
+    }
+
+    Ok(Json(json!({
+        "success": true,
+        "name": name,
+        "path": path.display().to_string(),
+    }))
+    .into_response())
+}
+
+/// Body of `PUT /skills_manager/update/:name`.
+#[derive(Debug, Deserialize)]
+pub struct LegacyUpdateRequest {
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub content: String,
+    /// Kept as they are when left out.
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+}
+
+/// `PUT /skills_manager/update/:name`: rewrite a skill's description and
+/// content.
+async fn update_skill(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(req): Json<LegacyUpdateRequest>,
+) -> LegacyResult {
+    let update = UpdateSkillRequest {
+        description: Some(req.description),
+        content: Some(req.content),
+        tags: req.tags,
+        pinned: None,
+    };
+    routes::update_skill(
+        State(state),
+        Path(name.clone()),
+        Query(DryRunQuery::default()),
+        Json(update),
+    )
+    .await?;
+    Ok(success(name))
+}
+
+/// `DELETE /skills_manager/delete/:name`.
+async fn delete_skill(State(state): State<AppState>, Path(name): Path<String>) -> LegacyResult {
+    routes::delete_skill(
+        State(state),
+        Path(name.clone()),
+        Query(DryRunQuery::default()),
+    )
+    .await?;
+    Ok(success(name))
+}
+
+/// `POST /skills_manager/reload`: reload and wait for it to finish.
+async fn reload_index(State(state): State<AppState>) -> LegacyResult {
+    if let Some(error) = state.reloads.reload().await.error {
+        return Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::new(error)),
+        ));
+    }
+    Ok(Json(json!({"success": true, "message": "Skills reloaded"})).into_response())
+}
+
+fn success(name: String) -> Response {
+    Json(json!({"success": true, "name": name})).into_response()
+}
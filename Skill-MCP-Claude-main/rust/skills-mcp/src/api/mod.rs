@@ -3,7 +3,15 @@
 //! Provides REST endpoints for skill management, matching the Flask API
 //! in skills_manager_api.py.
 
+mod case;
+mod idempotency;
+mod legacy;
 mod routes;
 mod server;
+mod webhook;
 
+pub use idempotency::{
+    IdempotencyStore, DEFAULT_IDEMPOTENCY_TTL, DEFAULT_MAX_IDEMPOTENCY_KEYS,
+    IDEMPOTENCY_KEY_HEADER, IDEMPOTENT_REPLAYED_HEADER,
+};
 pub use server::ApiServer;
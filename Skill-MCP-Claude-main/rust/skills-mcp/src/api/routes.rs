@@ -2,97 +2,56 @@
 //!
 //! These handlers correspond to the Flask routes in skills_manager_api.py.
 
+use std::collections::HashMap;
 use std::path::Path as StdPath;
 use std::sync::Arc;
 
 use axum::{
+    body::Body,
     extract::{Path, State},
-    http::StatusCode,
-    response::IntoResponse,
+    http::{header, StatusCode},
+    response::{IntoResponse, Redirect, Response},
     Json,
 };
 use serde::{Deserialize, Serialize};
 use tokio::fs as async_fs;
+use tokio_util::io::ReaderStream;
 
+use crate::import::{
+    apply_update, check_install_skills, install_skills, GitHubRepo, ImportError, ImportedSkill,
+    SkillUpdate,
+};
+use crate::index::{IndexError, ReloadJob};
+use crate::maintenance::{
+    apply_gc, apply_merge, apply_replace, apply_split, line_diff, plan_gc, plan_merge,
+    plan_replace, plan_split, FileDiff, GcPlan, MaintenanceError, MergePlan, ReplacePlan,
+    SplitPlan, DEFAULT_GC_RETENTION,
+};
 use crate::mcp::tools::ServiceContext;
-use crate::models::{ErrorResponse, SkillMeta};
+use crate::models::{
+    check_batch_queries, parse_flat_skill, preview_reader, render_flat_skill, DataFormat,
+    DataPreview, ErrorResponse, ImageAsset, ImageFormat, QualityScore, RenderedTemplate, SkillMeta,
+    SkillOrigin, SkillStats, TemplateInfo, ValidationResult, CATEGORY_SEPARATOR,
+    MAX_SEARCH_QUERY_LENGTH,
+};
+use crate::search::{suggest_triggers, SuggestedTriggers, DEFAULT_MIN_SEARCHES};
+use crate::validation::{
+    check_skill_fields, check_skill_name, check_skill_path, normalize_separators, validate_meta,
+    BrokenLink, SkillAnalysis, SkillValidator,
+};
 
 // ============================================================================
 // Path Traversal Protection
 // ============================================================================
 
-/// Maximum allowed skill name length
-const MAX_SKILL_NAME_LENGTH: usize = 100;
-
-/// Maximum allowed description length
-const MAX_DESCRIPTION_LENGTH: usize = 1000;
-
-/// Maximum allowed content length (1 MB)
-const MAX_CONTENT_LENGTH: usize = 1_000_000;
-
-/// Maximum number of tags per skill
-const MAX_TAGS_COUNT: usize = 20;
-
-/// Maximum length of each tag
-const MAX_TAG_LENGTH: usize = 50;
-
-/// Characters that are not allowed in skill names
-const FORBIDDEN_CHARS: &[char] = &['/', '\\', '\0', ':', '*', '?', '"', '<', '>', '|'];
-
 /// Validates that a skill name is safe and doesn't contain path traversal sequences.
 ///
+/// Namespaced names like `frontend/forms` are accepted; clients send them
+/// percent-encoded (`frontend%2Fforms`) in URL paths.
+///
 /// Returns `Ok(())` if the name is valid, or an error response if not.
 fn validate_skill_name(name: &str) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
-    // Check for empty name
-    if name.is_empty() {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse::new("Skill name cannot be empty".to_string())),
-        ));
-    }
-
-    // Check length
-    if name.len() > MAX_SKILL_NAME_LENGTH {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse::new(format!(
-                "Skill name too long (max {} characters)",
-                MAX_SKILL_NAME_LENGTH
-            ))),
-        ));
-    }
-
-    // Check for path traversal sequences
-    if name.contains("..") {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse::new(
-                "Skill name cannot contain '..'".to_string(),
-            )),
-        ));
-    }
-
-    // Check for forbidden characters
-    if name.chars().any(|c| FORBIDDEN_CHARS.contains(&c)) {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse::new(
-                "Skill name contains invalid characters".to_string(),
-            )),
-        ));
-    }
-
-    // Check name doesn't start with a dot (hidden files)
-    if name.starts_with('.') {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse::new(
-                "Skill name cannot start with '.'".to_string(),
-            )),
-        ));
-    }
-
-    Ok(())
+    check_skill_name(name).map_err(|e| (StatusCode::BAD_REQUEST, Json(ErrorResponse::new(e))))
 }
 
 /// Validates that a resolved path is within the skills directory.
@@ -102,109 +61,407 @@ fn validate_skill_path(
     skill_path: &StdPath,
     skills_dir: &StdPath,
 ) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
-    // Canonicalize both paths to resolve any symlinks and relative components
-    let canonical_skills_dir = match skills_dir.canonicalize() {
-        Ok(p) => p,
-        Err(_) => {
-            // If skills_dir doesn't exist or can't be canonicalized, use it as-is
-            skills_dir.to_path_buf()
-        }
+    check_skill_path(skill_path, skills_dir)
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(ErrorResponse::new(e))))
+}
+
+/// Map an indexer error to an HTTP error response.
+fn index_error(e: IndexError) -> (StatusCode, Json<ErrorResponse>) {
+    let status = match e {
+        IndexError::NotFound(_) => StatusCode::NOT_FOUND,
+        IndexError::ValidationError(_) => StatusCode::BAD_REQUEST,
+        IndexError::AlreadyExists(_) => StatusCode::CONFLICT,
+        IndexError::QuotaExceeded(_) => StatusCode::PAYLOAD_TOO_LARGE,
+        IndexError::Pinned(_) => StatusCode::LOCKED,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
     };
+    (status, Json(ErrorResponse::from(e)))
+}
 
-    // For skill_path, it may not exist yet (for create operations)
-    // So we canonicalize the parent (skills_dir) and check the name component
-    let skill_name = match skill_path.file_name() {
-        Some(name) => name,
-        None => {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse::new("Invalid skill path".to_string())),
-            ));
-        }
+/// Map a maintenance error to an HTTP error response.
+fn maintenance_error(e: MaintenanceError) -> (StatusCode, Json<ErrorResponse>) {
+    let status = match e {
+        MaintenanceError::Invalid(_) => StatusCode::BAD_REQUEST,
+        MaintenanceError::NotFound(_) => StatusCode::NOT_FOUND,
+        MaintenanceError::Conflict(_) => StatusCode::CONFLICT,
+        MaintenanceError::Io(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        MaintenanceError::Pinned(_) => StatusCode::LOCKED,
     };
+    (status, Json(ErrorResponse::new(e.to_string())))
+}
 
-    // Build expected path from canonical skills dir
-    let expected_path = canonical_skills_dir.join(skill_name);
+/// Map an import error to an HTTP error response.
+fn import_error(e: ImportError) -> (StatusCode, Json<ErrorResponse>) {
+    let status = match e {
+        ImportError::Invalid(_) => StatusCode::BAD_REQUEST,
+        ImportError::NotFound(_) => StatusCode::NOT_FOUND,
+        ImportError::Fetch(_) => StatusCode::BAD_GATEWAY,
+        ImportError::Archive(_) => StatusCode::UNPROCESSABLE_ENTITY,
+    };
+    (status, Json(ErrorResponse::new(e.to_string())))
+}
 
-    // If the skill path exists, canonicalize it and compare
-    if skill_path.exists() {
-        let canonical_skill_path = match skill_path.canonicalize() {
-            Ok(p) => p,
-            Err(e) => {
-                return Err((
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ErrorResponse::new(format!(
-                        "Failed to resolve skill path: {}",
-                        e
-                    ))),
-                ));
-            }
-        };
+/// Application state shared across routes.
+pub type AppState = Arc<ServiceContext>;
 
-        // Ensure the canonical path starts with the skills directory
-        if !canonical_skill_path.starts_with(&canonical_skills_dir) {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse::new(
-                    "Skill path is outside skills directory".to_string(),
-                )),
-            ));
-        }
-    } else {
-        // For paths that don't exist yet, verify the constructed path matches
-        if skill_path != expected_path {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse::new(
-                    "Invalid skill path construction".to_string(),
-                )),
-            ));
+// ============================================================================
+// Dry runs
+// ============================================================================
+
+/// `?dry_run=true` on a mutating route: make every check and return what
+/// would change, without writing anything.
+#[derive(Debug, Default, Deserialize)]
+pub struct DryRunQuery {
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// What a mutating request made with `?dry_run=true` would have done.
+#[derive(Debug, Serialize)]
+pub struct DryRunResponse<T> {
+    /// The response the request would have returned.
+    pub result: T,
+    pub dry_run: bool,
+    /// Files that would be created, relative to the skills directory.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub created: Vec<String>,
+    /// Changes to existing files.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub diffs: Vec<FileDiff>,
+    /// Files that would be removed, relative to the skills directory.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub deleted: Vec<String>,
+    /// Metadata problems the change would introduce.
+    pub issues: Vec<String>,
+}
+
+impl<T> DryRunResponse<T> {
+    fn new(result: T) -> Self {
+        Self {
+            result,
+            dry_run: true,
+            created: Vec::new(),
+            diffs: Vec::new(),
+            deleted: Vec::new(),
+            issues: Vec::new(),
         }
     }
+}
 
-    Ok(())
+/// Validation errors in `after` that `before` didn't already have.
+fn new_issues(before: Option<&SkillMeta>, after: &SkillMeta) -> Vec<String> {
+    let existing = before
+        .and_then(|meta| validate_meta(meta).err())
+        .unwrap_or_default();
+    validate_meta(after)
+        .err()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|issue| !existing.contains(issue))
+        .collect()
 }
 
-/// Application state shared across routes.
-pub type AppState = Arc<ServiceContext>;
+/// Files under a skill directory, relative to the skills directory and
+/// sorted.
+fn skill_files(skills_dir: &StdPath, skill_dir: &StdPath) -> Vec<String> {
+    let mut files: Vec<String> = walkdir::WalkDir::new(skill_dir)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| !entry.file_type().is_dir())
+        .filter_map(|entry| {
+            let relative = entry.path().strip_prefix(skills_dir).ok()?;
+            Some(normalize_separators(&relative.to_string_lossy()))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Diffs of the files whose contents would change, given as
+/// `(file, old, new)` with files relative to the skills directory.
+fn file_diffs(changes: &[(String, &str, &str)]) -> Vec<FileDiff> {
+    changes
+        .iter()
+        .filter(|(_, old, new)| old != new)
+        .map(|(file, old, new)| FileDiff {
+            file: file.clone(),
+            diff: line_diff(old, new),
+        })
+        .collect()
+}
 
 // ============================================================================
 // GET /api/skills - List all skills
 // ============================================================================
 
-#[derive(Debug, Serialize)]
-pub struct SkillListItem {
-    pub name: String,
-    pub description: String,
-    pub tags: Vec<String>,
-    pub sub_skills: Vec<String>,
-    pub file_count: usize,
+/// One skill in a listing, borrowed from the index. Fields left out of a
+/// `fields=` projection are `None` and not serialized.
+#[derive(Debug, Default, Serialize)]
+pub struct SkillListItem<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<&'a [String]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub_skills: Option<Vec<&'a str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_count: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quality: Option<QualityScore>,
+    /// Word, token and file totals across the skill and its sub-skills.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stats: Option<SkillStats>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
+    /// Imported skills whose upstream ref has moved on since.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub update_available: bool,
 }
 
-pub async fn list_skills(State(state): State<AppState>) -> impl IntoResponse {
-    let index = state.indexer.get_skill_index();
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ListSort {
+    /// Index order, which is alphabetical by name.
+    #[default]
+    Name,
+    /// Latest change to any of the skill's files, oldest first unless
+    /// `order=desc`.
+    Updated,
+    /// Approximate tokens across the skill's documents, smallest first
+    /// unless `order=desc`.
+    Size,
+    /// Quality score, weakest first unless `order=desc`.
+    Quality,
+    /// Loads of the skill and its sub-skills, least loaded first unless
+    /// `order=desc`.
+    Popularity,
+}
 
-    let skills: Vec<SkillListItem> = index
-        .skills
-        .iter()
-        .map(|s| {
-            let file_count = if s.has_sub_skills() {
-                s.sub_skills.as_ref().map(|ss| ss.len()).unwrap_or(0) + 1
-            } else {
-                1
-            };
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
 
-            SkillListItem {
-                name: s.name.clone(),
-                description: s.description.clone(),
-                tags: s.tags.clone(),
-                sub_skills: s.sub_skill_names().iter().map(|n| n.to_string()).collect(),
-                file_count,
-            }
+/// A field of [`SkillListItem`] that `fields=` can select.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListField {
+    Name,
+    Category,
+    Description,
+    Tags,
+    SubSkills,
+    FileCount,
+    Quality,
+    Stats,
+    Pinned,
+    UpdateAvailable,
+}
+
+impl ListField {
+    /// Every field, in response order.
+    pub const ALL: [ListField; 10] = [
+        ListField::Name,
+        ListField::Category,
+        ListField::Description,
+        ListField::Tags,
+        ListField::SubSkills,
+        ListField::FileCount,
+        ListField::Quality,
+        ListField::Stats,
+        ListField::Pinned,
+        ListField::UpdateAvailable,
+    ];
+
+    /// Parse a comma-separated `fields=` value; empty selects every field.
+    pub fn parse_list(fields: &str) -> Result<Vec<ListField>, String> {
+        let fields: Vec<ListField> = fields
+            .split(',')
+            .map(str::trim)
+            .filter(|f| !f.is_empty())
+            .map(str::parse)
+            .collect::<Result<_, _>>()?;
+
+        Ok(if fields.is_empty() {
+            Self::ALL.to_vec()
+        } else {
+            fields
         })
-        .collect();
+    }
+}
+
+impl std::str::FromStr for ListField {
+    type Err = String;
+
+    fn from_str(field: &str) -> Result<Self, Self::Err> {
+        Ok(match field {
+            "name" => ListField::Name,
+            "category" => ListField::Category,
+            "description" => ListField::Description,
+            "tags" => ListField::Tags,
+            "sub_skills" => ListField::SubSkills,
+            "file_count" => ListField::FileCount,
+            "quality" => ListField::Quality,
+            "stats" => ListField::Stats,
+            "pinned" => ListField::Pinned,
+            "update_available" => ListField::UpdateAvailable,
+            _ => return Err(format!("Unknown field '{}'", field)),
+        })
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ListSkillsQuery {
+    #[serde(default)]
+    pub sort: ListSort,
+    #[serde(default)]
+    pub order: SortOrder,
+    /// Comma-separated fields to return, e.g. `name,tags`; all by default.
+    #[serde(default)]
+    pub fields: Option<String>,
+}
+
+/// The selected fields of `skill`.
+fn list_item<'a>(
+    skill: &'a SkillMeta,
+    fields: &[ListField],
+    quality: Option<&HashMap<String, QualityScore>>,
+    stats: Option<&HashMap<String, SkillStats>>,
+    state: &ServiceContext,
+) -> SkillListItem<'a> {
+    let mut item = SkillListItem::default();
+    for field in fields {
+        match field {
+            ListField::Name => item.name = Some(&skill.name),
+            ListField::Category => item.category = skill.category(),
+            ListField::Description => item.description = Some(&skill.description),
+            ListField::Tags => item.tags = Some(&skill.tags),
+            ListField::SubSkills => item.sub_skills = Some(skill.sub_skill_names()),
+            ListField::FileCount => {
+                item.file_count = Some(if skill.has_sub_skills() {
+                    skill.all_sub_skills().len() + 1
+                } else {
+                    1
+                })
+            }
+            ListField::Quality => item.quality = quality.and_then(|q| q.get(&skill.name)).copied(),
+            ListField::Stats => item.stats = stats.and_then(|s| s.get(&skill.name)).cloned(),
+            ListField::Pinned => item.pinned = skill.pinned,
+            ListField::UpdateAvailable => {
+                item.update_available = state.updates.update_available(skill)
+            }
+        }
+    }
+    item
+}
+
+/// List skills, with only the `fields=` requested.
+///
+/// Items borrow from the index and are serialized under its read lock, so
+/// listings never clone skill metadata. Quality is only scored when it is
+/// returned or sorted by; other sorts use the stats and load counts kept as
+/// skills are indexed and loaded.
+pub async fn list_skills(
+    State(state): State<AppState>,
+    axum::extract::Query(query): axum::extract::Query<ListSkillsQuery>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    let fields = ListField::parse_list(query.fields.as_deref().unwrap_or_default())
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(ErrorResponse::new(e))))?;
+
+    let quality = if query.sort == ListSort::Quality || fields.contains(&ListField::Quality) {
+        let indexer = Arc::clone(&state.indexer);
+        let quality =
+            tokio::task::spawn_blocking(move || SkillValidator::new(indexer).quality_all())
+                .await
+                .map_err(|e| {
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(ErrorResponse::new(format!("Quality scoring failed: {}", e))),
+                    )
+                })?;
+        Some(quality)
+    } else {
+        None
+    };
+
+    let stats = (fields.contains(&ListField::Stats)
+        || matches!(query.sort, ListSort::Updated | ListSort::Size))
+    .then(|| state.indexer.skill_stats());
+    let loads = if query.sort == ListSort::Popularity {
+        state.stats.read().loads_by_skill()
+    } else {
+        HashMap::new()
+    };
+
+    let body = state.indexer.with_skill_index(|index| {
+        let mut skills: Vec<&SkillMeta> = match &quality {
+            // Skills added since scoring started are left for the next listing
+            Some(quality) => index
+                .skills
+                .iter()
+                .filter(|s| quality.contains_key(&s.name))
+                .collect(),
+            None => index.skills.iter().collect(),
+        };
+
+        let stats_of = |skill: &SkillMeta| stats.as_ref().and_then(|s| s.get(&skill.name));
+        match (query.sort, &quality) {
+            (ListSort::Updated, _) => {
+                sort_skills_by(&mut skills, |s| stats_of(s).and_then(|s| s.last_modified))
+            }
+            (ListSort::Size, _) => {
+                sort_skills_by(&mut skills, |s| stats_of(s).map_or(0, |s| s.approx_tokens))
+            }
+            (ListSort::Quality, Some(quality)) => {
+                sort_skills_by(&mut skills, |s| quality[&s.name].score)
+            }
+            (ListSort::Popularity, _) => {
+                sort_skills_by(&mut skills, |s| loads.get(&s.name).copied().unwrap_or(0))
+            }
+            _ => skills.sort_by(|a, b| a.name.cmp(&b.name)),
+        }
+        if query.order == SortOrder::Desc {
+            skills.reverse();
+        }
+
+        let items: Vec<SkillListItem> = skills
+            .into_iter()
+            .map(|s| list_item(s, &fields, quality.as_ref(), stats.as_ref(), &state))
+            .collect();
+        serde_json::to_vec(&items)
+    });
+
+    let body = body.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::new(format!(
+                "Failed to serialize skills: {}",
+                e
+            ))),
+        )
+    })?;
+    Ok(([(header::CONTENT_TYPE, "application/json")], body).into_response())
+}
+
+/// Sort `skills` by `key`, ties in name order so the listing is stable.
+fn sort_skills_by<K: Ord>(skills: &mut [&SkillMeta], key: impl Fn(&SkillMeta) -> K) {
+    skills.sort_by(|a, b| key(a).cmp(&key(b)).then_with(|| a.name.cmp(&b.name)));
+}
 
-    Json(skills)
+// ============================================================================
+// GET /api/categories - List skills grouped by category
+// ============================================================================
+
+pub async fn list_categories(
+    State(state): State<AppState>,
+) -> Json<Vec<crate::models::SkillCategory>> {
+    Json(state.indexer.get_skill_index().categories())
 }
 
 // ============================================================================
@@ -219,55 +476,86 @@ pub struct SkillDetails {
     pub tags: Vec<String>,
     pub sub_skills: Vec<SubSkillInfo>,
     pub has_references: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<ImageAsset>,
 }
 
+/// A sub-skill at any depth; nested ones are named by path, e.g. `react/hooks`.
 #[derive(Debug, Serialize)]
 pub struct SubSkillInfo {
     pub name: String,
     pub file: String,
     pub triggers: Vec<String>,
+    #[serde(rename = "ref", skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
 }
 
+/// Every sub-skill of `meta`, parents before their children.
+fn sub_skill_infos(meta: &SkillMeta) -> Vec<SubSkillInfo> {
+    meta.all_sub_skills()
+        .into_iter()
+        .map(|(path, s)| SubSkillInfo {
+            name: path,
+            file: s.file.clone(),
+            triggers: s.triggers.clone(),
+            reference: s.reference.clone(),
+        })
+        .collect()
+}
+
+/// SKILL.md files larger than this are streamed instead of buffered.
+const STREAM_THRESHOLD_BYTES: u64 = 256 * 1024;
+
+/// Read size when streaming file content.
+const STREAM_CHUNK_SIZE: usize = 16 * 1024;
+
 pub async fn get_skill(
     State(state): State<AppState>,
     Path(name): Path<String>,
-) -> Result<Json<SkillDetails>, (StatusCode, Json<ErrorResponse>)> {
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
     // Validate skill name to prevent path traversal
     validate_skill_name(&name)?;
 
-    let meta = state
-        .indexer
-        .get_skill_meta(&name)
-        .ok_or_else(|| {
-            (
-                StatusCode::NOT_FOUND,
-                Json(ErrorResponse::new(format!("Skill '{}' not found", name))),
-            )
-        })?;
+    let Some(meta) = state.indexer.get_skill_meta(&name) else {
+        // Skills merged into another one live on as its aliases
+        if let Some(target) = state.indexer.resolve_alias(&name) {
+            let uri = format!("/api/skills/{}", target.replace(CATEGORY_SEPARATOR, "%2F"));
+            return Ok(Redirect::permanent(&uri).into_response());
+        }
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new(format!("Skill '{}' not found", name))),
+        ));
+    };
 
-    let content = state
-        .indexer
-        .read_skill_content(&name)
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse::new(e.to_string())),
-            )
-        })?;
+    let sub_skills = sub_skill_infos(&meta);
+
+    // Large files go out chunk by chunk rather than through one String
+    if let Ok(path) = state.indexer.resolve_skill_file(&name, "SKILL.md") {
+        let size = async_fs::metadata(&path)
+            .await
+            .map(|m| m.len())
+            .unwrap_or(0);
+        if size > STREAM_THRESHOLD_BYTES {
+            let details = SkillDetails {
+                name: meta.name,
+                description: meta.description,
+                content: String::new(),
+                tags: meta.tags,
+                sub_skills,
+                has_references: state.indexer.has_references(&name),
+                images: state.indexer.list_images(&name).unwrap_or_default(),
+            };
+            return stream_skill_details(details, &path).await;
+        }
+    }
 
-    let sub_skills = meta
-        .sub_skills
-        .as_ref()
-        .map(|subs| {
-            subs.iter()
-                .map(|s| SubSkillInfo {
-                    name: s.name.clone(),
-                    file: s.file.clone(),
-                    triggers: s.triggers.clone(),
-                })
-                .collect()
-        })
-        .unwrap_or_default();
+    let content = state.indexer.read_skill_content(&name).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::new(e.to_string())),
+        )
+    })?;
 
     Ok(Json(SkillDetails {
         name: meta.name,
@@ -276,421 +564,2043 @@ pub async fn get_skill(
         tags: meta.tags,
         sub_skills,
         has_references: content.has_references,
-    }))
+        images: content.images,
+    })
+    .into_response())
 }
 
-// ============================================================================
-// POST /api/skills - Create skill
-// ============================================================================
+/// Respond with `details` as JSON whose `content` field is streamed from `path`.
+async fn stream_skill_details(
+    details: SkillDetails,
+    path: &StdPath,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    let internal = |e: String| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::new(e)),
+        )
+    };
 
-#[derive(Debug, Deserialize)]
-pub struct CreateSkillRequest {
-    pub name: String,
-    pub description: String,
-    pub content: String,
-    #[serde(default)]
-    pub tags: Vec<String>,
-}
+    let file = async_fs::File::open(path)
+        .await
+        .map_err(|e| internal(format!("Failed to open file: {}", e)))?;
 
-impl CreateSkillRequest {
-    /// Validate the request fields.
-    fn validate(&self) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
-        // Validate description length
-        if self.description.len() > MAX_DESCRIPTION_LENGTH {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse::new(format!(
-                    "Description too long (max {} characters)",
-                    MAX_DESCRIPTION_LENGTH
-                ))),
-            ));
+    // Everything but the content, with the content string left open at the end
+    let mut fields = serde_json::to_value(&details).map_err(|e| internal(e.to_string()))?;
+    if let Some(map) = fields.as_object_mut() {
+        map.remove("content");
+    }
+    let mut head = fields.to_string();
+    head.pop();
+    head.push_str(",\"content\":\"");
+
+    let (mut writer, reader) = tokio::io::duplex(STREAM_CHUNK_SIZE);
+    let path = path.to_path_buf();
+    tokio::spawn(async move {
+        use tokio::io::AsyncWriteExt;
+
+        let result = async {
+            writer.write_all(head.as_bytes()).await?;
+            copy_as_json_string(file, &mut writer).await?;
+            writer.write_all(b"\"}").await?;
+            writer.shutdown().await
         }
+        .await;
 
-        // Validate content length
-        if self.content.len() > MAX_CONTENT_LENGTH {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse::new(format!(
-                    "Content too long (max {} bytes)",
-                    MAX_CONTENT_LENGTH
-                ))),
-            ));
+        if let Err(e) = result {
+            tracing::warn!("Streaming {} failed: {}", path.display(), e);
         }
+    });
 
-        // Validate tags count
-        if self.tags.len() > MAX_TAGS_COUNT {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse::new(format!(
-                    "Too many tags (max {})",
-                    MAX_TAGS_COUNT
-                ))),
-            ));
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from_stream(ReaderStream::new(reader)))
+        .map_err(|e| internal(e.to_string()))
+}
+
+/// Copy a file into a JSON string body, escaping as it goes.
+///
+/// UTF-8 sequences split across reads are carried over to the next chunk;
+/// invalid bytes become U+FFFD, as with buffered reads.
+async fn copy_as_json_string(
+    mut file: async_fs::File,
+    writer: &mut tokio::io::DuplexStream,
+) -> std::io::Result<()> {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let mut buf = vec![0; STREAM_CHUNK_SIZE];
+    let mut pending = Vec::new();
+
+    loop {
+        let n = file.read(&mut buf).await?;
+        pending.extend_from_slice(&buf[..n]);
+
+        let text = take_utf8(&mut pending, n == 0);
+        if !text.is_empty() {
+            let escaped = serde_json::to_string(&text).map_err(std::io::Error::other)?;
+            writer
+                .write_all(&escaped.as_bytes()[1..escaped.len() - 1])
+                .await?;
         }
 
-        // Validate individual tag lengths
-        for tag in &self.tags {
-            if tag.len() > MAX_TAG_LENGTH {
-                return Err((
-                    StatusCode::BAD_REQUEST,
-                    Json(ErrorResponse::new(format!(
-                        "Tag '{}' too long (max {} characters)",
-                        tag, MAX_TAG_LENGTH
-                    ))),
-                ));
+        if n == 0 {
+            return Ok(());
+        }
+    }
+}
+
+/// Drain the longest decodable prefix of `pending`.
+///
+/// An incomplete trailing sequence is left in place unless `eof` is set.
+fn take_utf8(pending: &mut Vec<u8>, eof: bool) -> String {
+    let mut out = String::new();
+
+    loop {
+        match std::str::from_utf8(pending) {
+            Ok(s) => {
+                out.push_str(s);
+                pending.clear();
+                return out;
             }
-            if tag.is_empty() {
-                return Err((
-                    StatusCode::BAD_REQUEST,
-                    Json(ErrorResponse::new("Tags cannot be empty".to_string())),
-                ));
+            Err(e) => {
+                let valid = e.valid_up_to();
+                out.push_str(&String::from_utf8_lossy(&pending[..valid]));
+
+                match e.error_len() {
+                    Some(len) => {
+                        out.push(char::REPLACEMENT_CHARACTER);
+                        pending.drain(..valid + len);
+                    }
+                    None if eof => {
+                        out.push(char::REPLACEMENT_CHARACTER);
+                        pending.clear();
+                        return out;
+                    }
+                    None => {
+                        pending.drain(..valid);
+                        return out;
+                    }
+                }
             }
         }
+    }
+}
+
+// ============================================================================
+// GET /api/skills/:name/raw/*path - Stream a file from a skill
+// ============================================================================
 
-        Ok(())
+/// Content type for a skill file, by extension.
+fn content_type_for(path: &StdPath) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("md") | Some("markdown") => "text/markdown; charset=utf-8",
+        Some("json") => "application/json",
+        Some("txt") => "text/plain; charset=utf-8",
+        _ => "application/octet-stream",
     }
 }
 
-pub async fn create_skill(
+/// Stream a file's raw bytes without loading it into memory.
+///
+/// Unlike the content index, this has no size cap and does not decode the
+/// file, so it is the way to fetch large or non-UTF-8 files.
+pub async fn get_skill_file(
     State(state): State<AppState>,
-    Json(req): Json<CreateSkillRequest>,
-) -> Result<(StatusCode, Json<SkillDetails>), (StatusCode, Json<ErrorResponse>)> {
+    Path((name, file)): Path<(String, String)>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
     // Validate skill name to prevent path traversal
-    validate_skill_name(&req.name)?;
+    validate_skill_name(&name)?;
 
-    // Validate request fields
-    req.validate()?;
+    let path = state
+        .indexer
+        .resolve_skill_file(&name, &file)
+        .map_err(index_error)?;
 
-    // Check if skill already exists
-    if state.indexer.skill_exists(&req.name) {
-        return Err((
-            StatusCode::CONFLICT,
-            Json(ErrorResponse::new(format!(
-                "Skill '{}' already exists",
-                req.name
-            ))),
-        ));
-    }
-
-    // Create skill directory and files
-    let skills_dir = state.indexer.skills_dir();
-    let skill_dir = skills_dir.join(&req.name);
-
-    // Validate the constructed path is within skills directory
-    validate_skill_path(&skill_dir, skills_dir)?;
-
-    async_fs::create_dir_all(&skill_dir).await.map_err(|e| {
+    let handle = async_fs::File::open(&path).await.map_err(|e| {
         (
             StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse::new(format!("Failed to create directory: {}", e))),
+            Json(ErrorResponse::new(format!("Failed to open file: {}", e))),
         )
     })?;
+    let len = handle.metadata().await.map(|m| m.len()).ok();
 
-    // Create _meta.json
-    let meta = SkillMeta {
-        name: req.name.clone(),
-        description: req.description.clone(),
-        tags: req.tags.clone(),
-        sub_skills: None,
-        source: None,
-    };
+    let mut response = Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, content_type_for(&path));
+    if let Some(len) = len {
+        response = response.header(header::CONTENT_LENGTH, len);
+    }
 
-    let meta_json = serde_json::to_string_pretty(&meta).map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse::new(format!("Failed to serialize meta: {}", e))),
-        )
-    })?;
+    response
+        .body(Body::from_stream(ReaderStream::new(handle)))
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::new(e.to_string())),
+            )
+        })
+}
 
-    async_fs::write(skill_dir.join("_meta.json"), meta_json).await.map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse::new(format!("Failed to write _meta.json: {}", e))),
-        )
-    })?;
+// ============================================================================
+// POST /api/skills/:name/references - Upload reference assets
+// ============================================================================
 
-    // Create SKILL.md
-    async_fs::write(skill_dir.join("SKILL.md"), &req.content).await.map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse::new(format!("Failed to write SKILL.md: {}", e))),
-        )
-    })?;
+/// Largest single uploaded file (10 MiB).
+const MAX_UPLOAD_FILE_SIZE: usize = 10 * 1024 * 1024;
 
-    // Reload index
-    state.indexer.reload().map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse::new(format!("Failed to reload index: {}", e))),
-        )
-    })?;
+/// Most files per upload.
+const MAX_UPLOAD_FILES: usize = 20;
 
-    Ok((
-        StatusCode::CREATED,
-        Json(SkillDetails {
-            name: req.name,
-            description: req.description,
-            content: req.content,
-            tags: req.tags,
-            sub_skills: vec![],
-            has_references: false,
-        }),
-    ))
-}
+/// Largest upload request body (50 MiB).
+pub const MAX_UPLOAD_BODY_SIZE: usize = 50 * 1024 * 1024;
 
-// ============================================================================
-// PUT /api/skills/:name - Update skill
-// ============================================================================
+/// MIME type an uploaded file is stored as, by extension, or `None` if
+/// files of that kind can't be uploaded.
+fn upload_type_for(file_name: &str) -> Option<&'static str> {
+    if let Some(format) = ImageFormat::from_extension(file_name) {
+        return Some(format.mime_type());
+    }
+    let extension = file_name.rsplit_once('.')?.1.to_ascii_lowercase();
+    match extension.as_str() {
+        "md" | "markdown" => Some("text/markdown"),
+        "txt" => Some("text/plain"),
+        "csv" => Some("text/csv"),
+        "tsv" => Some("text/tab-separated-values"),
+        "json" => Some("application/json"),
+        "jsonl" => Some("application/jsonl"),
+        "pdf" => Some("application/pdf"),
+        _ => None,
+    }
+}
 
-#[derive(Debug, Deserialize)]
-pub struct UpdateSkillRequest {
-    #[serde(default)]
-    pub description: Option<String>,
-    #[serde(default)]
-    pub content: Option<String>,
-    #[serde(default)]
-    pub tags: Option<Vec<String>>,
+#[derive(Debug, Serialize)]
+pub struct UploadReferencesResponse {
+    pub skill: String,
+    /// Written paths, relative to the skill directory.
+    pub files: Vec<String>,
 }
 
-impl UpdateSkillRequest {
-    /// Validate the request fields.
-    fn validate(&self) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
-        // Validate description length if provided
-        if let Some(ref desc) = self.description {
-            if desc.len() > MAX_DESCRIPTION_LENGTH {
-                return Err((
-                    StatusCode::BAD_REQUEST,
-                    Json(ErrorResponse::new(format!(
-                        "Description too long (max {} characters)",
-                        MAX_DESCRIPTION_LENGTH
-                    ))),
-                ));
-            }
+/// Store each file part of a multipart upload under the skill's
+/// `references/`, at its file name (which may include subdirectories).
+///
+/// A part's extension must be an allowed type and its declared content
+/// type, unless generic, must match it. Nothing is written unless every
+/// part is acceptable.
+pub async fn upload_references(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    mut multipart: axum::extract::Multipart,
+) -> Result<(StatusCode, Json<UploadReferencesResponse>), (StatusCode, Json<ErrorResponse>)> {
+    validate_skill_name(&name)?;
+
+    let error = |status: StatusCode, message: String| (status, Json(ErrorResponse::new(message)));
+    let bad_request = |e: axum::extract::multipart::MultipartError| {
+        error(StatusCode::BAD_REQUEST, format!("Invalid upload: {}", e))
+    };
+
+    let mut files: Vec<(String, Vec<u8>)> = Vec::new();
+    while let Some(mut field) = multipart.next_field().await.map_err(bad_request)? {
+        let Some(file_name) = field.file_name().map(str::to_string) else {
+            continue;
+        };
+        if files.len() == MAX_UPLOAD_FILES {
+            return Err(error(
+                StatusCode::BAD_REQUEST,
+                format!("Too many files (max {})", MAX_UPLOAD_FILES),
+            ));
         }
 
-        // Validate content length if provided
-        if let Some(ref content) = self.content {
-            if content.len() > MAX_CONTENT_LENGTH {
-                return Err((
-                    StatusCode::BAD_REQUEST,
-                    Json(ErrorResponse::new(format!(
-                        "Content too long (max {} bytes)",
-                        MAX_CONTENT_LENGTH
-                    ))),
+        let Some(expected) = upload_type_for(&file_name) else {
+            return Err(error(
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                format!("File type not allowed: {}", file_name),
+            ));
+        };
+        let declared = field
+            .content_type()
+            .and_then(|t| t.split(';').next())
+            .map(|t| t.trim().to_ascii_lowercase());
+        if let Some(declared) = declared.filter(|t| t != "application/octet-stream") {
+            if declared != expected {
+                return Err(error(
+                    StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                    format!(
+                        "Content type {} doesn't match {} (expected {})",
+                        declared, file_name, expected
+                    ),
                 ));
             }
         }
 
-        // Validate tags if provided
-        if let Some(ref tags) = self.tags {
-            if tags.len() > MAX_TAGS_COUNT {
-                return Err((
-                    StatusCode::BAD_REQUEST,
-                    Json(ErrorResponse::new(format!(
-                        "Too many tags (max {})",
-                        MAX_TAGS_COUNT
-                    ))),
+        let mut bytes = Vec::new();
+        while let Some(chunk) = field.chunk().await.map_err(bad_request)? {
+            if bytes.len() + chunk.len() > MAX_UPLOAD_FILE_SIZE {
+                return Err(error(
+                    StatusCode::PAYLOAD_TOO_LARGE,
+                    format!(
+                        "File too large: {} (max {} bytes)",
+                        file_name, MAX_UPLOAD_FILE_SIZE
+                    ),
                 ));
             }
-
-            for tag in tags {
-                if tag.len() > MAX_TAG_LENGTH {
-                    return Err((
-                        StatusCode::BAD_REQUEST,
-                        Json(ErrorResponse::new(format!(
-                            "Tag '{}' too long (max {} characters)",
-                            tag, MAX_TAG_LENGTH
-                        ))),
-                    ));
-                }
-                if tag.is_empty() {
-                    return Err((
-                        StatusCode::BAD_REQUEST,
-                        Json(ErrorResponse::new("Tags cannot be empty".to_string())),
-                    ));
-                }
-            }
+            bytes.extend_from_slice(&chunk);
         }
+        files.push((file_name, bytes));
+    }
 
-        Ok(())
+    if files.is_empty() {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "No files uploaded".to_string(),
+        ));
     }
+
+    let indexer = Arc::clone(&state.indexer);
+    let skill = name.clone();
+    let written = tokio::task::spawn_blocking(move || indexer.add_references(&skill, &files))
+        .await
+        .map_err(|e| {
+            error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Upload failed: {}", e),
+            )
+        })?
+        .map_err(index_error)?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(UploadReferencesResponse {
+            skill: name,
+            files: written,
+        }),
+    ))
 }
 
-pub async fn update_skill(
-    State(state): State<AppState>,
-    Path(name): Path<String>,
-    Json(req): Json<UpdateSkillRequest>,
-) -> Result<Json<SkillDetails>, (StatusCode, Json<ErrorResponse>)> {
-    // Validate skill name to prevent path traversal
-    validate_skill_name(&name)?;
+// ============================================================================
+// GET /api/skills/:name/preview/*path - Preview a CSV or JSON data file
+// ============================================================================
 
-    // Validate request fields
-    req.validate()?;
+/// Default number of rows or keys in a preview.
+const DEFAULT_PREVIEW_LIMIT: usize = 20;
 
-    let skills_dir = state.indexer.skills_dir();
-    let skill_dir = skills_dir.join(&name);
+/// Maximum number of rows or keys in a preview.
+const MAX_PREVIEW_LIMIT: usize = 1000;
 
-    // Validate the constructed path is within skills directory
-    validate_skill_path(&skill_dir, skills_dir)?;
+#[derive(Debug, Deserialize)]
+pub struct PreviewQuery {
+    /// Rows (CSV, JSON Lines) or top-level keys/items (JSON) to return.
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
 
-    if !skill_dir.exists() {
+#[derive(Debug, Serialize)]
+pub struct FilePreviewResponse {
+    pub path: String,
+    pub size: u64,
+    #[serde(flatten)]
+    pub preview: DataPreview,
+}
+
+/// Return the first rows or keys of a data file.
+///
+/// CSV, TSV and JSON Lines are read only as far as needed. JSON documents
+/// are parsed whole, so they are subject to the index file size limit.
+pub async fn preview_skill_file(
+    State(state): State<AppState>,
+    Path((name, file)): Path<(String, String)>,
+    axum::extract::Query(query): axum::extract::Query<PreviewQuery>,
+) -> Result<Json<FilePreviewResponse>, (StatusCode, Json<ErrorResponse>)> {
+    validate_skill_name(&name)?;
+
+    let limit = query.limit.unwrap_or(DEFAULT_PREVIEW_LIMIT);
+    if limit == 0 || limit > MAX_PREVIEW_LIMIT {
         return Err((
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse::new(format!("Skill '{}' not found", name))),
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::new(format!(
+                "limit must be between 1 and {}",
+                MAX_PREVIEW_LIMIT
+            ))),
         ));
     }
 
-    // Load existing meta
-    let meta_path = skill_dir.join("_meta.json");
-    let meta_content = async_fs::read_to_string(&meta_path).await.map_err(|e| {
+    let format = DataFormat::detect(&file).ok_or_else(|| {
         (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse::new(format!("Failed to read _meta.json: {}", e))),
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::new(format!(
+                "'{}' is not a CSV, TSV, JSON or JSON Lines file",
+                file
+            ))),
         )
     })?;
 
-    let mut meta: SkillMeta = serde_json::from_str(&meta_content).map_err(|e| {
+    let path = state
+        .indexer
+        .resolve_skill_file(&name, &file)
+        .map_err(index_error)?;
+    let max_size = state.indexer.options().max_file_size;
+
+    let preview = tokio::task::spawn_blocking(move || {
+        let handle = std::fs::File::open(&path).map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to open file: {}", e),
+            )
+        })?;
+        let size = handle.metadata().map(|m| m.len()).unwrap_or(0);
+
+        if !format.is_streaming() && size > max_size {
+            return Err((
+                StatusCode::PAYLOAD_TOO_LARGE,
+                format!(
+                    "JSON file is {} bytes; previews are limited to {} bytes",
+                    size, max_size
+                ),
+            ));
+        }
+
+        preview_reader(format, std::io::BufReader::new(handle), limit)
+            .map(|preview| (size, preview))
+            .map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, e))
+    })
+    .await
+    .map_err(|e| {
         (
             StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse::new(format!("Failed to parse _meta.json: {}", e))),
+            Json(ErrorResponse::new(e.to_string())),
         )
-    })?;
+    })?
+    .map_err(|(status, message)| (status, Json(ErrorResponse::new(message))))?;
+
+    let (size, preview) = preview;
+    Ok(Json(FilePreviewResponse {
+        path: normalize_separators(&file),
+        size,
+        preview,
+    }))
+}
 
-    // Update fields
-    if let Some(description) = req.description {
-        meta.description = description;
+// ============================================================================
+// GET /api/skills/:name/thumb/*path - Thumbnail of an image asset
+// ============================================================================
+
+/// Default longest side of a thumbnail, in pixels.
+const DEFAULT_THUMB_SIZE: u32 = 128;
+
+/// Largest thumbnail that can be requested, in pixels.
+const MAX_THUMB_SIZE: u32 = 512;
+
+#[derive(Debug, Deserialize)]
+pub struct ThumbQuery {
+    /// Longest side of the thumbnail in pixels.
+    #[serde(default)]
+    pub size: Option<u32>,
+}
+
+/// Serve a PNG thumbnail of an image, generated on demand.
+///
+/// Thumbnails aren't stored; the ETag covers the source file and size so
+/// clients can cache them. Needs the `thumbnails` feature.
+pub async fn get_skill_thumbnail(
+    State(state): State<AppState>,
+    Path((name, file)): Path<(String, String)>,
+    axum::extract::Query(query): axum::extract::Query<ThumbQuery>,
+    headers: axum::http::HeaderMap,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    let error = |status, message: String| (status, Json(ErrorResponse::new(message)));
+
+    validate_skill_name(&name)?;
+
+    let size = query.size.unwrap_or(DEFAULT_THUMB_SIZE);
+    if size == 0 || size > MAX_THUMB_SIZE {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            format!("size must be between 1 and {}", MAX_THUMB_SIZE),
+        ));
     }
-    if let Some(tags) = req.tags {
-        meta.tags = tags;
+
+    if ImageFormat::from_extension(&file).is_none() {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            format!("'{}' is not a PNG, JPEG, GIF or WebP image", file),
+        ));
     }
 
-    // Save updated meta
-    let meta_json = serde_json::to_string_pretty(&meta).unwrap();
-    async_fs::write(&meta_path, meta_json).await.map_err(|e| {
-        (
+    let path = state
+        .indexer
+        .resolve_skill_file(&name, &file)
+        .map_err(index_error)?;
+    let metadata = async_fs::metadata(&path).await.map_err(|e| {
+        error(
             StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse::new(format!("Failed to write _meta.json: {}", e))),
+            format!("Failed to read file: {}", e),
         )
     })?;
 
-    // Update content if provided
-    let content = if let Some(new_content) = req.content {
-        async_fs::write(skill_dir.join("SKILL.md"), &new_content).await.map_err(|e| {
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let etag = format!("\"{:x}-{:x}-{}\"", metadata.len(), modified, size);
+
+    let cached = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.split(',').any(|tag| tag.trim() == etag));
+    if cached {
+        return Response::builder()
+            .status(StatusCode::NOT_MODIFIED)
+            .header(header::ETAG, etag)
+            .body(Body::empty())
+            .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()));
+    }
+
+    if metadata.len() > state.indexer.options().max_file_size {
+        return Err(error(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!("Image is {} bytes; too large to thumbnail", metadata.len()),
+        ));
+    }
+
+    let png = render_thumbnail(path, size)
+        .await
+        .map_err(|(status, message)| error(status, message))?;
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "image/png")
+        .header(header::CACHE_CONTROL, "public, max-age=3600")
+        .header(header::ETAG, etag)
+        .body(Body::from(png))
+        .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Decode an image and encode a PNG no larger than `size` on either side.
+#[cfg(feature = "thumbnails")]
+async fn render_thumbnail(
+    path: std::path::PathBuf,
+    size: u32,
+) -> Result<Vec<u8>, (StatusCode, String)> {
+    tokio::task::spawn_blocking(move || {
+        let invalid = |e: image::ImageError| {
             (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse::new(format!("Failed to write SKILL.md: {}", e))),
+                StatusCode::UNPROCESSABLE_ENTITY,
+                format!("Failed to decode image: {}", e),
             )
-        })?;
-        new_content
-    } else {
-        async_fs::read_to_string(skill_dir.join("SKILL.md")).await.unwrap_or_default()
-    };
+        };
 
-    // Reload index
-    let _ = state.indexer.reload();
-
-    let sub_skills = meta
-        .sub_skills
-        .as_ref()
-        .map(|subs| {
-            subs.iter()
-                .map(|s| SubSkillInfo {
-                    name: s.name.clone(),
-                    file: s.file.clone(),
-                    triggers: s.triggers.clone(),
-                })
-                .collect()
-        })
-        .unwrap_or_default();
+        let mut reader = image::ImageReader::open(&path)
+            .map_err(|e| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Failed to open file: {}", e),
+                )
+            })?
+            .with_guessed_format()
+            .map_err(|e| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Failed to read file: {}", e),
+                )
+            })?;
+        // Small files can still decode to huge bitmaps
+        reader.limits(image::Limits::default());
+
+        let thumbnail = reader.decode().map_err(invalid)?.thumbnail(size, size);
+        let mut png = std::io::Cursor::new(Vec::new());
+        thumbnail
+            .write_to(&mut png, image::ImageFormat::Png)
+            .map_err(invalid)?;
+        Ok(png.into_inner())
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+}
 
-    Ok(Json(SkillDetails {
-        name: meta.name,
-        description: meta.description,
-        content,
-        tags: meta.tags,
-        sub_skills,
-        has_references: state.indexer.has_references(&name),
-    }))
+#[cfg(not(feature = "thumbnails"))]
+async fn render_thumbnail(
+    _path: std::path::PathBuf,
+    _size: u32,
+) -> Result<Vec<u8>, (StatusCode, String)> {
+    Err((
+        StatusCode::NOT_IMPLEMENTED,
+        "Thumbnails require the `thumbnails` feature".to_string(),
+    ))
 }
 
 // ============================================================================
-// DELETE /api/skills/:name - Delete skill
+// GET /api/skills/:name/analysis - Readability findings for a skill
 // ============================================================================
 
-pub async fn delete_skill(
+pub async fn analyze_skill(
     State(state): State<AppState>,
     Path(name): Path<String>,
-) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
-    // Validate skill name to prevent path traversal
+) -> Result<Json<SkillAnalysis>, (StatusCode, Json<ErrorResponse>)> {
     validate_skill_name(&name)?;
 
-    let skills_dir = state.indexer.skills_dir();
-    let skill_dir = skills_dir.join(&name);
-
-    // Validate the constructed path is within skills directory
-    validate_skill_path(&skill_dir, skills_dir)?;
-
-    if !skill_dir.exists() {
+    if !state.indexer.skill_exists(&name) {
         return Err((
             StatusCode::NOT_FOUND,
             Json(ErrorResponse::new(format!("Skill '{}' not found", name))),
         ));
     }
 
-    async_fs::remove_dir_all(&skill_dir).await.map_err(|e| {
+    let indexer = Arc::clone(&state.indexer);
+    let analysis =
+        tokio::task::spawn_blocking(move || crate::validation::analyze_skill(&indexer, &name))
+            .await
+            .map_err(|e| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ErrorResponse::new(format!("Analysis failed: {}", e))),
+                )
+            })?;
+
+    Ok(Json(analysis))
+}
+
+// ============================================================================
+// GET /api/skills/:name/suggested-triggers - Trigger words mined from searches
+// ============================================================================
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SuggestedTriggersQuery {
+    #[serde(default)]
+    pub min_searches: Option<usize>,
+}
+
+pub async fn suggested_triggers(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    axum::extract::Query(query): axum::extract::Query<SuggestedTriggersQuery>,
+) -> Result<Json<SuggestedTriggers>, (StatusCode, Json<ErrorResponse>)> {
+    validate_skill_name(&name)?;
+
+    let meta = state.indexer.get_skill_meta(&name).ok_or_else(|| {
         (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse::new(format!("Failed to delete skill: {}", e))),
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new(format!("Skill '{}' not found", name))),
         )
     })?;
 
-    // Reload index
-    let _ = state.indexer.reload();
-
-    Ok(StatusCode::NO_CONTENT)
+    let min_searches = query.min_searches.unwrap_or(DEFAULT_MIN_SEARCHES);
+    Ok(Json(suggest_triggers(
+        &state.stats.read(),
+        &meta,
+        min_searches,
+    )))
 }
 
 // ============================================================================
-// POST /api/reload - Reload index
+// GET /api/templates - List skill templates
 // ============================================================================
 
-#[derive(Debug, Serialize)]
-pub struct ReloadResponse {
-    pub success: bool,
-    pub skill_count: usize,
-}
-
-pub async fn reload_index(State(state): State<AppState>) -> impl IntoResponse {
-    match state.indexer.reload() {
-        Ok(()) => {
-            let count = state.indexer.get_skill_index().len();
-            Json(ReloadResponse {
-                success: true,
-                skill_count: count,
-            })
-        }
-        Err(_) => Json(ReloadResponse {
-            success: false,
-            skill_count: 0,
-        }),
-    }
+pub async fn list_templates(State(state): State<AppState>) -> Json<Vec<TemplateInfo>> {
+    Json(
+        state
+            .indexer
+            .list_templates()
+            .iter()
+            .map(|t| t.info())
+            .collect(),
+    )
 }
 
 // ============================================================================
-// GET /api/search - Search skills
+// POST /api/skills - Create skill
 // ============================================================================
 
-/// Maximum allowed search query length
-const MAX_SEARCH_QUERY_LENGTH: usize = 1000;
-
-/// Maximum allowed search limit
-const MAX_SEARCH_LIMIT: usize = 100;
-
 #[derive(Debug, Deserialize)]
-pub struct SearchQuery {
-    pub q: String,
-    #[serde(default = "default_limit")]
+pub struct CreateSkillRequest {
+    pub name: String,
+    pub description: String,
+    /// SKILL.md content; required unless `template` is given.
+    #[serde(default)]
+    pub content: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Write a single `name.md` with frontmatter instead of a directory.
+    #[serde(default)]
+    pub flat: bool,
+    /// Template to fill in instead of giving content.
+    #[serde(default)]
+    pub template: Option<String>,
+    /// Values for the template's placeholders.
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+    /// Protect the skill from bulk edits and updates.
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+impl CreateSkillRequest {
+    /// Validate the request fields.
+    fn validate(&self) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
+        check_skill_fields(
+            Some(&self.description),
+            self.content.as_deref(),
+            Some(&self.tags),
+        )
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(ErrorResponse::new(e))))
+    }
+}
+
+pub async fn create_skill(
+    State(state): State<AppState>,
+    axum::extract::Query(query): axum::extract::Query<DryRunQuery>,
+    Json(req): Json<CreateSkillRequest>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    // Validate skill name to prevent path traversal
+    validate_skill_name(&req.name)?;
+
+    // Validate request fields
+    req.validate()?;
+
+    let bad_request =
+        |message: String| (StatusCode::BAD_REQUEST, Json(ErrorResponse::new(message)));
+
+    let skill = match (&req.template, req.content) {
+        (Some(_), Some(_)) => {
+            return Err(bad_request(
+                "Give either content or a template, not both".to_string(),
+            ))
+        }
+        (Some(template), None) => state
+            .indexer
+            .load_template(template)
+            .map_err(index_error)?
+            .render(&req.name, &req.description, &req.tags, &req.variables)
+            .map_err(bad_request)?,
+        (None, Some(content)) => RenderedTemplate {
+            content,
+            tags: req.tags.clone(),
+            sub_skills: Vec::new(),
+        },
+        (None, None) => {
+            return Err(bad_request(
+                "Either content or a template is required".to_string(),
+            ))
+        }
+    };
+
+    let meta = SkillMeta {
+        name: req.name.clone(),
+        description: req.description.clone(),
+        tags: skill.tags.clone(),
+        sub_skills: None,
+        source: None,
+        pinned: req.pinned,
+        ..Default::default()
+    };
+
+    let issues = new_issues(None, &meta);
+    let indexer = Arc::clone(&state.indexer);
+    let flat = req.flat;
+    let dry_run = query.dry_run;
+    let (skill, files) = tokio::task::spawn_blocking(move || {
+        if dry_run {
+            indexer.check_create(&meta, &skill, flat)
+        } else {
+            indexer.create_skill(&meta, &skill, flat)
+        }
+        .map(|files| (skill, files))
+    })
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::new(format!("Failed to create skill: {}", e))),
+        )
+    })?
+    .map_err(index_error)?;
+
+    let details = SkillDetails {
+        name: req.name,
+        description: req.description,
+        content: skill.content,
+        tags: skill.tags,
+        sub_skills: skill
+            .sub_skills
+            .into_iter()
+            .map(|(s, _)| SubSkillInfo {
+                name: s.name,
+                file: s.file,
+                triggers: s.triggers,
+                reference: s.reference,
+            })
+            .collect(),
+        has_references: false,
+        images: vec![],
+    };
+
+    if dry_run {
+        return Ok(Json(DryRunResponse {
+            created: files,
+            issues,
+            ..DryRunResponse::new(details)
+        })
+        .into_response());
+    }
+    Ok((StatusCode::CREATED, Json(details)).into_response())
+}
+
+/// Write a flat-file skill: frontmatter followed by the markdown body.
+async fn write_flat_skill(
+    path: &StdPath,
+    skills_dir: &StdPath,
+    meta: &SkillMeta,
+    content: &str,
+) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
+    // Validate the constructed path is within skills directory
+    validate_skill_path(path, skills_dir)?;
+
+    async_fs::write(path, render_flat_skill(meta, content))
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::new(format!(
+                    "Failed to write {}.md: {}",
+                    meta.name, e
+                ))),
+            )
+        })
+}
+
+// ============================================================================
+// PUT /api/skills/:name - Update skill
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateSkillRequest {
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub content: Option<String>,
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    /// Pin or unpin the skill.
+    #[serde(default)]
+    pub pinned: Option<bool>,
+}
+
+impl UpdateSkillRequest {
+    /// Validate the request fields.
+    fn validate(&self) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
+        check_skill_fields(
+            self.description.as_deref(),
+            self.content.as_deref(),
+            self.tags.as_deref(),
+        )
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(ErrorResponse::new(e))))
+    }
+}
+
+pub async fn update_skill(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    axum::extract::Query(query): axum::extract::Query<DryRunQuery>,
+    Json(req): Json<UpdateSkillRequest>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    // Validate skill name to prevent path traversal
+    validate_skill_name(&name)?;
+
+    // Validate request fields
+    req.validate()?;
+
+    let skills_dir = state.indexer.skills_dir();
+    let skill_dir = skills_dir.join(&name);
+
+    if let Some(path) = state.indexer.flat_skill_path(&name) {
+        return update_flat_skill(&state, &path, req, query.dry_run).await;
+    }
+
+    // Validate the constructed path is within skills directory
+    validate_skill_path(&skill_dir, skills_dir)?;
+
+    if !skill_dir.exists() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new(format!("Skill '{}' not found", name))),
+        ));
+    }
+
+    // Load existing meta
+    let meta_path = skill_dir.join("_meta.json");
+    let meta_content = async_fs::read_to_string(&meta_path).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::new(format!(
+                "Failed to read _meta.json: {}",
+                e
+            ))),
+        )
+    })?;
+
+    let mut meta: SkillMeta = serde_json::from_str(&meta_content).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::new(format!(
+                "Failed to parse _meta.json: {}",
+                e
+            ))),
+        )
+    })?;
+    let previous = meta.clone();
+
+    // Update fields
+    if let Some(description) = req.description {
+        meta.description = description;
+    }
+    if let Some(tags) = req.tags {
+        meta.tags = tags;
+    }
+    if let Some(pinned) = req.pinned {
+        meta.pinned = pinned;
+    }
+
+    // Leave _meta.json as written when no field changed
+    let meta_json = if meta == previous {
+        meta_content.clone()
+    } else {
+        serde_json::to_string_pretty(&meta).unwrap()
+    };
+    let mut writes = vec![(meta_path.clone(), meta_json.len() as u64)];
+    if let Some(new_content) = &req.content {
+        writes.push((skill_dir.join("SKILL.md"), new_content.len() as u64));
+    }
+    state
+        .indexer
+        .check_quota(&name, &writes)
+        .map_err(index_error)?;
+
+    if query.dry_run {
+        let old_content = async_fs::read_to_string(skill_dir.join("SKILL.md"))
+            .await
+            .unwrap_or_default();
+        let content = req.content.unwrap_or_else(|| old_content.clone());
+        let diffs = file_diffs(&[
+            (
+                format!("{}/_meta.json", name),
+                meta_content.as_str(),
+                meta_json.as_str(),
+            ),
+            (
+                format!("{}/SKILL.md", name),
+                old_content.as_str(),
+                content.as_str(),
+            ),
+        ]);
+        let issues = new_issues(Some(&previous), &meta);
+        return Ok(Json(DryRunResponse {
+            diffs,
+            issues,
+            ..DryRunResponse::new(skill_details(&state, &name, meta, content))
+        })
+        .into_response());
+    }
+
+    // Save updated meta
+    async_fs::write(&meta_path, meta_json).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::new(format!(
+                "Failed to write _meta.json: {}",
+                e
+            ))),
+        )
+    })?;
+
+    // Update content if provided
+    let content = if let Some(new_content) = req.content {
+        async_fs::write(skill_dir.join("SKILL.md"), &new_content)
+            .await
+            .map_err(|e| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ErrorResponse::new(format!(
+                        "Failed to write SKILL.md: {}",
+                        e
+                    ))),
+                )
+            })?;
+        new_content
+    } else {
+        async_fs::read_to_string(skill_dir.join("SKILL.md"))
+            .await
+            .unwrap_or_default()
+    };
+
+    // Reload index
+    state.reloads.reload().await;
+
+    Ok(Json(skill_details(&state, &name, meta, content)).into_response())
+}
+
+/// Details of a skill directory as updated to `meta` and `content`.
+fn skill_details(state: &AppState, name: &str, meta: SkillMeta, content: String) -> SkillDetails {
+    let sub_skills = sub_skill_infos(&meta);
+
+    SkillDetails {
+        name: meta.name,
+        description: meta.description,
+        content,
+        tags: meta.tags,
+        sub_skills,
+        has_references: state.indexer.has_references(name),
+        images: state.indexer.list_images(name).unwrap_or_default(),
+    }
+}
+
+/// Apply an update to a flat-file skill, rewriting its frontmatter and body.
+async fn update_flat_skill(
+    state: &AppState,
+    path: &StdPath,
+    req: UpdateSkillRequest,
+    dry_run: bool,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    let text = async_fs::read_to_string(path).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::new(format!(
+                "Failed to read {}: {}",
+                path.display(),
+                e
+            ))),
+        )
+    })?;
+
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default();
+    let (mut meta, body) = parse_flat_skill(stem, &text).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::new(format!(
+                "Failed to parse {}: {}",
+                path.display(),
+                e
+            ))),
+        )
+    })?;
+    let previous = meta.clone();
+
+    // Update fields
+    if let Some(description) = req.description {
+        meta.description = description;
+    }
+    if let Some(tags) = req.tags {
+        meta.tags = tags;
+    }
+    if let Some(pinned) = req.pinned {
+        meta.pinned = pinned;
+    }
+    let content = req.content.unwrap_or_else(|| body.to_string());
+
+    let rendered = render_flat_skill(&meta, &content);
+    state
+        .indexer
+        .check_quota(stem, &[(path.to_path_buf(), rendered.len() as u64)])
+        .map_err(index_error)?;
+
+    let details = SkillDetails {
+        name: meta.name.clone(),
+        description: meta.description.clone(),
+        content: content.clone(),
+        tags: meta.tags.clone(),
+        sub_skills: vec![],
+        has_references: false,
+        images: vec![],
+    };
+
+    if dry_run {
+        let diffs = file_diffs(&[(format!("{}.md", stem), text.as_str(), rendered.as_str())]);
+        return Ok(Json(DryRunResponse {
+            diffs,
+            issues: new_issues(Some(&previous), &meta),
+            ..DryRunResponse::new(details)
+        })
+        .into_response());
+    }
+
+    write_flat_skill(path, state.indexer.skills_dir(), &meta, &content).await?;
+
+    // Reload index
+    state.reloads.reload().await;
+
+    Ok(Json(details).into_response())
+}
+
+// ============================================================================
+// DELETE /api/skills/:name - Delete skill
+// ============================================================================
+
+pub async fn delete_skill(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    axum::extract::Query(query): axum::extract::Query<DryRunQuery>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    // Validate skill name to prevent path traversal
+    validate_skill_name(&name)?;
+
+    let skills_dir = state.indexer.skills_dir();
+    let skill_dir = skills_dir.join(&name);
+
+    if let Some(path) = state.indexer.flat_skill_path(&name) {
+        validate_skill_path(&path, skills_dir)?;
+
+        if query.dry_run {
+            return Ok(Json(DryRunResponse {
+                deleted: vec![format!("{}.md", name)],
+                ..DryRunResponse::new(())
+            })
+            .into_response());
+        }
+
+        async_fs::remove_file(&path).await.map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::new(format!("Failed to delete skill: {}", e))),
+            )
+        })?;
+
+        state.reloads.reload().await;
+        return Ok(StatusCode::NO_CONTENT.into_response());
+    }
+
+    // Validate the constructed path is within skills directory
+    validate_skill_path(&skill_dir, skills_dir)?;
+
+    if !skill_dir.exists() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new(format!("Skill '{}' not found", name))),
+        ));
+    }
+
+    if query.dry_run {
+        return Ok(Json(DryRunResponse {
+            deleted: skill_files(skills_dir, &skill_dir),
+            ..DryRunResponse::new(())
+        })
+        .into_response());
+    }
+
+    async_fs::remove_dir_all(&skill_dir).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::new(format!("Failed to delete skill: {}", e))),
+        )
+    })?;
+
+    // Reload index
+    state.reloads.reload().await;
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+// ============================================================================
+// POST /api/skills/merge - Combine several skills into one
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct MergeRequest {
+    pub sources: Vec<String>,
+    pub target: String,
+    /// Replaces the target's description.
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Only preview unless explicitly set to false.
+    #[serde(default = "default_dry_run")]
+    pub dry_run: bool,
+    /// Change pinned skills too.
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MergeResponse {
+    #[serde(flatten)]
+    pub plan: MergePlan,
+    pub dry_run: bool,
+    /// Where the replaced and merged-away files were saved, under
+    /// `.versions/`, when applied.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+pub async fn merge_skills(
+    State(state): State<AppState>,
+    axum::extract::Query(query): axum::extract::Query<DryRunQuery>,
+    Json(req): Json<MergeRequest>,
+) -> Result<Json<MergeResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let dry_run = req.dry_run || query.dry_run;
+    let response = tokio::task::spawn_blocking(move || {
+        let plan = plan_merge(
+            &state.indexer,
+            &req.sources,
+            &req.target,
+            req.description.as_deref(),
+            req.force,
+        )?;
+        if dry_run {
+            return Ok(MergeResponse {
+                plan,
+                dry_run: true,
+                version: None,
+            });
+        }
+
+        let version = apply_merge(&state.indexer, &plan)?;
+        tracing::info!(
+            "Merged {} into '{}' (previous files in version {})",
+            plan.sources.join(", "),
+            plan.target,
+            version
+        );
+        if let Err(e) = state.indexer.reload() {
+            tracing::error!("Failed to reload index after merge: {}", e);
+        }
+
+        Ok(MergeResponse {
+            plan,
+            dry_run: false,
+            version: Some(version),
+        })
+    })
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::new(format!("Merge failed: {}", e))),
+        )
+    })?
+    .map_err(maintenance_error)?;
+
+    Ok(Json(response))
+}
+
+// ============================================================================
+// POST /api/import/github - Install skills from a GitHub repository
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct GitHubImportRequest {
+    /// Repository URL, e.g. `https://github.com/acme/skills`.
+    pub url: String,
+    /// Branch, tag or commit; the default branch if unset.
+    #[serde(default, rename = "ref")]
+    pub git_ref: Option<String>,
+    /// Directory of the repository holding the skills.
+    #[serde(default)]
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GitHubImportResponse {
+    pub skills: Vec<String>,
+    pub url: String,
+    #[serde(rename = "ref")]
+    pub git_ref: String,
+    pub commit: String,
+}
+
+/// Fetch the repository's tarball at the ref and install every skill
+/// under the path, recording where each came from in its metadata.
+pub async fn import_github(
+    State(state): State<AppState>,
+    axum::extract::Query(query): axum::extract::Query<DryRunQuery>,
+    Json(req): Json<GitHubImportRequest>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    let repo = GitHubRepo::parse(&req.url).map_err(import_error)?;
+    // The commits API resolves HEAD to the default branch
+    let git_ref = req.git_ref.unwrap_or_else(|| "HEAD".to_string());
+    let fetched = state
+        .github
+        .fetch(&repo, &git_ref, req.path.as_deref().unwrap_or_default())
+        .await
+        .map_err(import_error)?;
+
+    let origin = SkillOrigin {
+        url: repo.url(),
+        git_ref: git_ref.clone(),
+        path: String::new(),
+        commit: fetched.commit.clone(),
+        imported_at: chrono::Utc::now(),
+    };
+
+    if query.dry_run {
+        let skills: Vec<String> = fetched.skills.iter().map(|s| s.meta.name.clone()).collect();
+        let indexer = Arc::clone(&state.indexer);
+        let created = tokio::task::spawn_blocking(move || {
+            check_install_skills(&indexer, &fetched.skills, &origin)
+        })
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::new(format!("Import failed: {}", e))),
+            )
+        })?
+        .map_err(index_error)?;
+
+        return Ok(Json(DryRunResponse {
+            created,
+            ..DryRunResponse::new(GitHubImportResponse {
+                skills,
+                url: repo.url(),
+                git_ref,
+                commit: fetched.commit,
+            })
+        })
+        .into_response());
+    }
+
+    let indexer = Arc::clone(&state.indexer);
+    let skills =
+        tokio::task::spawn_blocking(move || install_skills(&indexer, fetched.skills, &origin))
+            .await
+            .map_err(|e| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ErrorResponse::new(format!("Import failed: {}", e))),
+                )
+            })?
+            .map_err(index_error)?;
+
+    tracing::info!(
+        "Imported {} from {} at {}",
+        skills.join(", "),
+        repo.url(),
+        fetched.commit
+    );
+    Ok((
+        StatusCode::CREATED,
+        Json(GitHubImportResponse {
+            skills,
+            url: repo.url(),
+            git_ref,
+            commit: fetched.commit,
+        }),
+    )
+        .into_response())
+}
+
+// ============================================================================
+// POST /api/updates/check - Check imported skills against upstream
+// ============================================================================
+
+pub async fn check_updates(State(state): State<AppState>) -> Json<Vec<SkillUpdate>> {
+    Json(state.updates.check(&state.github, &state.indexer).await)
+}
+
+// ============================================================================
+// POST /api/skills/:name/update - Pull an imported skill's upstream version
+// ============================================================================
+
+#[derive(Debug, Serialize)]
+pub struct SkillUpdateResponse {
+    pub skill: String,
+    pub updated: bool,
+    /// Commit the skill was at before.
+    pub from: String,
+    /// Commit it is at now.
+    pub to: String,
+    /// Where the replaced files were saved, under `.versions/`, if updated.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateImportedQuery {
+    /// Update the skill even if it's pinned.
+    #[serde(default)]
+    pub force: bool,
+    /// Fetch and check the update but don't apply it.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Re-import the skill from its origin's ref, replacing it if the ref has
+/// moved on. The previous files are kept as a version. Pinned skills need
+/// `?force=true`.
+pub async fn update_imported_skill(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    axum::extract::Query(query): axum::extract::Query<UpdateImportedQuery>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    validate_skill_name(&name)?;
+    let meta = state.indexer.get_skill_meta(&name).ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new(format!("Skill '{}' not found", name))),
+        )
+    })?;
+    let origin = meta.origin.clone().ok_or_else(|| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::new(format!(
+                "Skill '{}' was not imported",
+                name
+            ))),
+        )
+    })?;
+    if meta.pinned && !query.force {
+        return Err(index_error(IndexError::Pinned(format!(
+            "Skill '{}' is pinned; force the update to change it",
+            name
+        ))));
+    }
+
+    let repo = GitHubRepo::parse(&origin.url).map_err(import_error)?;
+    let latest = state
+        .github
+        .resolve(&repo, &origin.git_ref)
+        .await
+        .map_err(import_error)?;
+    if latest == origin.commit {
+        let response = SkillUpdateResponse {
+            skill: name,
+            updated: false,
+            from: origin.commit,
+            to: latest,
+            version: None,
+        };
+        if query.dry_run {
+            return Ok(Json(DryRunResponse::new(response)).into_response());
+        }
+        return Ok(Json(response).into_response());
+    }
+
+    let fetched_skills = state
+        .github
+        .fetch(&repo, &latest, &origin.path)
+        .await
+        .map_err(import_error)?;
+    let skill = fetched_skills
+        .skills
+        .into_iter()
+        .find(|skill| skill.meta.name == name)
+        .ok_or_else(|| {
+            import_error(ImportError::Archive(format!(
+                "No skill '{}' in {} at {}",
+                name, origin.path, latest
+            )))
+        })?;
+
+    if query.dry_run {
+        let response = SkillUpdateResponse {
+            skill: name,
+            updated: true,
+            from: origin.commit,
+            to: latest,
+            version: None,
+        };
+        return Ok(Json(preview_update(&state, &meta, &skill, response)).into_response());
+    }
+
+    let indexer = Arc::clone(&state.indexer);
+    let commit = latest.clone();
+    let force = query.force;
+    let version =
+        tokio::task::spawn_blocking(move || apply_update(&indexer, skill, &commit, force))
+            .await
+            .map_err(|e| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ErrorResponse::new(format!("Update failed: {}", e))),
+                )
+            })?
+            .map_err(index_error)?;
+
+    tracing::info!(
+        "Updated '{}' from {} to {} (previous files in version {})",
+        name,
+        origin.commit,
+        latest,
+        version
+    );
+    Ok(Json(SkillUpdateResponse {
+        skill: name,
+        updated: true,
+        from: origin.commit,
+        to: latest,
+        version: Some(version),
+    })
+    .into_response())
+}
+
+/// What replacing a skill with `skill`, fetched at the commit `response`
+/// moves to, would change.
+fn preview_update(
+    state: &AppState,
+    current: &SkillMeta,
+    skill: &ImportedSkill,
+    response: SkillUpdateResponse,
+) -> DryRunResponse<SkillUpdateResponse> {
+    let mut meta = skill.meta.clone();
+    meta.origin = current.origin.clone().map(|origin| SkillOrigin {
+        commit: response.to.clone(),
+        imported_at: chrono::Utc::now(),
+        ..origin
+    });
+    meta.pinned = current.pinned;
+    let meta_json = serde_json::to_string_pretty(&meta).unwrap_or_default();
+
+    let skills_dir = state.indexer.skills_dir();
+    let skill_dir = skills_dir.join(&meta.name);
+    let mut replaced = vec![(format!("{}/_meta.json", meta.name), meta_json.into_bytes())];
+    replaced.extend(
+        skill
+            .files
+            .iter()
+            .map(|(file, bytes)| (format!("{}/{}", meta.name, file), bytes.clone())),
+    );
+
+    let mut preview = DryRunResponse::new(response);
+    let mut changes = Vec::new();
+    for (file, bytes) in &replaced {
+        match std::fs::read(skills_dir.join(file)) {
+            Ok(old) if old == *bytes => {}
+            Ok(old) => changes.push((file.clone(), old, bytes)),
+            Err(_) => preview.created.push(file.clone()),
+        }
+    }
+    preview.diffs = changes
+        .iter()
+        .map(|(file, old, new)| FileDiff {
+            file: file.clone(),
+            diff: match (std::str::from_utf8(old), std::str::from_utf8(new)) {
+                (Ok(old), Ok(new)) => line_diff(old, new),
+                _ => "Binary file changed".to_string(),
+            },
+        })
+        .collect();
+    preview.deleted = skill_files(skills_dir, &skill_dir)
+        .into_iter()
+        .filter(|file| !replaced.iter().any(|(kept, _)| kept == file))
+        .collect();
+    preview.issues = new_issues(Some(current), &meta);
+    preview
+}
+
+// ============================================================================
+// POST /api/reload - Reload index
+// ============================================================================
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ReloadQuery {
+    /// Wait for the reload to finish instead of returning the queued job.
+    #[serde(default)]
+    pub wait: bool,
+}
+
+/// Start a reload on the blocking pool and return its job. Reloads
+/// requested while one is running are coalesced into a single follow-up.
+pub async fn reload_index(
+    State(state): State<AppState>,
+    axum::extract::Query(query): axum::extract::Query<ReloadQuery>,
+) -> Response {
+    if query.wait {
+        return Json(state.reloads.reload().await).into_response();
+    }
+
+    let job = state.reloads.request();
+    (
+        StatusCode::ACCEPTED,
+        [(header::LOCATION, format!("/api/reload/{}", job.id))],
+        Json(job),
+    )
+        .into_response()
+}
+
+// ============================================================================
+// GET /api/reload/:id - Poll a reload job
+// ============================================================================
+
+pub async fn get_reload_job(
+    State(state): State<AppState>,
+    Path(id): Path<u64>,
+) -> Result<Json<ReloadJob>, (StatusCode, Json<ErrorResponse>)> {
+    state.reloads.get(id).map(Json).ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new(format!("Reload job {} not found", id))),
+        )
+    })
+}
+
+// ============================================================================
+// POST /api/validate/links - Check external links in skill documents
+// ============================================================================
+
+#[derive(Debug, Serialize)]
+pub struct CheckLinksResponse {
+    #[serde(flatten)]
+    pub result: ValidationResult,
+    pub links_checked: usize,
+    pub from_cache: usize,
+    pub broken: Vec<BrokenLink>,
+}
+
+pub async fn check_links(State(state): State<AppState>) -> Json<CheckLinksResponse> {
+    let skills_checked = state.indexer.get_skill_index().len();
+    let report = state
+        .link_checker
+        .check_skills(Arc::clone(&state.indexer))
+        .await;
+
+    Json(CheckLinksResponse {
+        result: report.validation_result(skills_checked),
+        links_checked: report.links_checked,
+        from_cache: report.from_cache,
+        broken: report.broken,
+    })
+}
+
+// ============================================================================
+// GET /api/sessions/:id/trace - Read a session's journal
+// ============================================================================
+
+#[derive(Debug, Serialize)]
+pub struct SessionTraceResponse {
+    pub session_id: String,
+    pub events: Vec<crate::mcp::TraceEvent>,
+}
+
+pub async fn get_session_trace(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<SessionTraceResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let journal = state.journal.as_ref().ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new(
+                "Session journaling is not enabled".to_string(),
+            )),
+        )
+    })?;
+
+    if !crate::mcp::is_valid_session_id(&id) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::new(format!("Invalid session id '{}'", id))),
+        ));
+    }
+
+    let events = journal
+        .trace(&id)
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::new(format!("Failed to read trace: {}", e))),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse::new(format!("No trace for session '{}'", id))),
+            )
+        })?;
+
+    Ok(Json(SessionTraceResponse {
+        session_id: id,
+        events,
+    }))
+}
+
+// ============================================================================
+// Admin authentication
+// ============================================================================
+
+/// Header carrying the admin token, as an alternative to `Authorization: Bearer`.
+pub const ADMIN_TOKEN_HEADER: &str = "x-admin-token";
+
+/// Compare tokens without short-circuiting on the first differing byte.
+fn tokens_match(expected: &str, given: &str) -> bool {
+    expected.len() == given.len()
+        && expected
+            .bytes()
+            .zip(given.bytes())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+/// Reject requests that do not carry the admin token.
+pub async fn require_admin(
+    State(token): State<Arc<str>>,
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> Response {
+    let headers = request.headers();
+    let given = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .or_else(|| {
+            headers
+                .get(ADMIN_TOKEN_HEADER)
+                .and_then(|v| v.to_str().ok())
+        });
+
+    match given {
+        Some(given) if tokens_match(&token, given) => next.run(request).await,
+        _ => (
+            StatusCode::UNAUTHORIZED,
+            Json(ErrorResponse::new("Admin token required".to_string())),
+        )
+            .into_response(),
+    }
+}
+
+// ============================================================================
+// POST /api/maintenance/replace - Regex replace across all skills (admin)
+// ============================================================================
+
+fn default_dry_run() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReplaceRequest {
+    pub pattern: String,
+    pub replacement: String,
+    /// Only preview unless explicitly set to false.
+    #[serde(default = "default_dry_run")]
+    pub dry_run: bool,
+    /// Change pinned skills too.
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReplaceResponse {
+    #[serde(flatten)]
+    pub plan: ReplacePlan,
+    pub dry_run: bool,
+    /// Where the originals were saved, under `.versions/`, when applied.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+pub async fn replace_content(
+    State(state): State<AppState>,
+    axum::extract::Query(query): axum::extract::Query<DryRunQuery>,
+    Json(req): Json<ReplaceRequest>,
+) -> Result<Json<ReplaceResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let dry_run = req.dry_run || query.dry_run;
+    let response = tokio::task::spawn_blocking(move || {
+        let plan = plan_replace(&state.indexer, &req.pattern, &req.replacement, req.force)?;
+        if dry_run || plan.changes.is_empty() {
+            return Ok(ReplaceResponse {
+                plan,
+                dry_run,
+                version: None,
+            });
+        }
+
+        let version = apply_replace(&state.indexer, &plan)?;
+        tracing::info!(
+            "Replaced {} matches of '{}' in {} files (originals in version {})",
+            plan.replacements,
+            plan.pattern,
+            plan.changes.len(),
+            version
+        );
+        if let Err(e) = state.indexer.reload() {
+            tracing::error!("Failed to reload index after replace: {}", e);
+        }
+
+        Ok(ReplaceResponse {
+            plan,
+            dry_run: false,
+            version: Some(version),
+        })
+    })
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::new(format!("Replace failed: {}", e))),
+        )
+    })?
+    .map_err(maintenance_error)?;
+
+    Ok(Json(response))
+}
+
+// ============================================================================
+// POST /api/maintenance/gc - Prune records of deleted skills (admin)
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct GcRequest {
+    /// Keep orphaned versions younger than this many days.
+    #[serde(default)]
+    pub retention_days: Option<u64>,
+    /// Only preview unless explicitly set to false.
+    #[serde(default = "default_dry_run")]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GcResponse {
+    #[serde(flatten)]
+    pub plan: GcPlan,
+    pub dry_run: bool,
+}
+
+pub async fn collect_garbage(
+    State(state): State<AppState>,
+    axum::extract::Query(query): axum::extract::Query<DryRunQuery>,
+    Json(req): Json<GcRequest>,
+) -> Result<Json<GcResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let dry_run = req.dry_run || query.dry_run;
+    let retention = req.retention_days.map_or(DEFAULT_GC_RETENTION, |days| {
+        std::time::Duration::from_secs(days.saturating_mul(24 * 60 * 60))
+    });
+
+    let response = tokio::task::spawn_blocking(move || {
+        let plan = plan_gc(&state.indexer, &state.stats.read(), retention)?;
+        if dry_run || plan.is_empty() {
+            return Ok(GcResponse { plan, dry_run });
+        }
+
+        apply_gc(&state.indexer, &mut state.stats.write(), &plan)?;
+        tracing::info!(
+            "Removed {} orphaned versions ({} bytes) and {} stats keys",
+            plan.versions.len(),
+            plan.bytes,
+            plan.stats_keys.len()
+        );
+
+        Ok(GcResponse {
+            plan,
+            dry_run: false,
+        })
+    })
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::new(format!(
+                "Garbage collection failed: {}",
+                e
+            ))),
+        )
+    })?
+    .map_err(maintenance_error)?;
+
+    Ok(Json(response))
+}
+
+// ============================================================================
+// POST /api/maintenance/split - Split a skill at its headings (admin)
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct SplitRequest {
+    pub skill: String,
+    /// Only preview unless explicitly set to false.
+    #[serde(default = "default_dry_run")]
+    pub dry_run: bool,
+    /// Change pinned skills too.
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SplitResponse {
+    #[serde(flatten)]
+    pub plan: SplitPlan,
+    pub dry_run: bool,
+    /// Where the original files were saved, under `.versions/`, when applied.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+pub async fn split_skill(
+    State(state): State<AppState>,
+    axum::extract::Query(query): axum::extract::Query<DryRunQuery>,
+    Json(req): Json<SplitRequest>,
+) -> Result<Json<SplitResponse>, (StatusCode, Json<ErrorResponse>)> {
+    validate_skill_name(&req.skill)?;
+    let dry_run = req.dry_run || query.dry_run;
+
+    let response = tokio::task::spawn_blocking(move || {
+        let plan = plan_split(&state.indexer, &req.skill, req.force)?;
+        if dry_run {
+            return Ok(SplitResponse {
+                plan,
+                dry_run: true,
+                version: None,
+            });
+        }
+
+        let version = apply_split(&state.indexer, &plan)?;
+        tracing::info!(
+            "Split '{}' into {} sub-skills (originals in version {})",
+            plan.skill,
+            plan.sub_skills.len(),
+            version
+        );
+        if let Err(e) = state.indexer.reload() {
+            tracing::error!("Failed to reload index after split: {}", e);
+        }
+
+        Ok(SplitResponse {
+            plan,
+            dry_run: false,
+            version: Some(version),
+        })
+    })
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::new(format!("Split failed: {}", e))),
+        )
+    })?
+    .map_err(maintenance_error)?;
+
+    Ok(Json(response))
+}
+
+// ============================================================================
+// GET /api/debug/index - Inspect the live index (admin)
+// ============================================================================
+
+#[derive(Debug, Serialize)]
+pub struct DebugIndexResponse {
+    pub version: u64,
+    pub skill_index: crate::models::SkillIndex,
+    pub content_index: crate::models::ContentIndexSummary,
+}
+
+pub async fn debug_index(State(state): State<AppState>) -> Json<DebugIndexResponse> {
+    Json(DebugIndexResponse {
+        version: state.indexer.version(),
+        skill_index: state.indexer.get_skill_index(),
+        content_index: state.indexer.get_content_index().summary(),
+    })
+}
+
+// ============================================================================
+// GET /api/debug/diff - Compare the index with disk without reloading (admin)
+// ============================================================================
+
+pub async fn debug_index_diff(
+    State(state): State<AppState>,
+) -> Result<Json<crate::mcp::tools::DiffIndexResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let diff = tokio::task::spawn_blocking(move || crate::mcp::tools::diff_index(&state))
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::new(e.to_string())),
+            )
+        })?
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, Json(e)))?;
+
+    Ok(Json(diff))
+}
+
+// ============================================================================
+// GET /api/debug/index/*key - Inspect one content index entry (admin)
+// ============================================================================
+
+pub async fn debug_index_entry(
+    State(state): State<AppState>,
+    Path(key): Path<String>,
+) -> Result<Json<crate::models::ContentIndexEntry>, (StatusCode, Json<ErrorResponse>)> {
+    state
+        .indexer
+        .get_content_index()
+        .get(&key)
+        .cloned()
+        .map(Json)
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse::new(format!("No index entry '{}'", key))),
+            )
+        })
+}
+
+// ============================================================================
+// GET /api/debug/memory - Approximate memory use (admin)
+// ============================================================================
+
+pub async fn debug_memory(State(state): State<AppState>) -> Json<crate::models::MemoryUsage> {
+    Json(crate::models::MemoryUsage {
+        index: state.indexer.memory(),
+        search: state.search.memory(),
+        process_rss_bytes: process_rss_bytes(),
+        measured_at: chrono::Utc::now(),
+    })
+}
+
+/// Resident set size of this process, from `/proc/self/status`.
+#[cfg(target_os = "linux")]
+fn process_rss_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let kib = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))?
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    Some(kib * 1024)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn process_rss_bytes() -> Option<u64> {
+    None
+}
+
+// ============================================================================
+// GET /api/search - Search skills
+// ============================================================================
+
+/// Maximum allowed search limit
+const MAX_SEARCH_LIMIT: usize = 100;
+
+#[derive(Debug, Deserialize)]
+pub struct SearchQuery {
+    pub q: String,
+    #[serde(default = "default_limit")]
     pub limit: usize,
+    #[serde(default)]
+    pub offset: Option<usize>,
+    /// Opaque cursor from a previous response; overrides `offset`.
+    #[serde(default)]
+    pub cursor: Option<String>,
+    /// Terms that must match: `all`, a count, or a percentage like `75%`.
+    #[serde(default)]
+    pub min_should_match: Option<String>,
+    #[serde(default)]
+    pub source: Option<String>,
+    #[serde(default)]
+    pub language: Option<String>,
+    /// draft, published or deprecated.
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default)]
+    pub owner: Option<String>,
+    /// Tag expression, e.g. `react AND (forms OR validation) NOT deprecated`.
+    #[serde(default)]
+    pub tags: Option<String>,
+    /// Category folder, e.g. `frontend`; includes subcategories.
+    #[serde(default)]
+    pub category: Option<String>,
+    /// `suggest` or `auto` spelling correction when nothing matches.
+    #[serde(default)]
+    pub spelling: Option<String>,
 }
 
 fn default_limit() -> usize {
@@ -701,13 +2611,15 @@ pub async fn search_skills(
     State(state): State<AppState>,
     axum::extract::Query(query): axum::extract::Query<SearchQuery>,
 ) -> Result<Json<crate::models::SearchResults>, (StatusCode, Json<ErrorResponse>)> {
-    use crate::models::SearchOptions;
+    use crate::models::{MinimumShouldMatch, SearchOptions, SkillStatus, SpellingMode, TagExpr};
 
     // Validate query length
     if query.q.is_empty() {
         return Err((
             StatusCode::BAD_REQUEST,
-            Json(ErrorResponse::new("Search query cannot be empty".to_string())),
+            Json(ErrorResponse::new(
+                "Search query cannot be empty".to_string(),
+            )),
         ));
     }
 
@@ -724,8 +2636,205 @@ pub async fn search_skills(
     // Clamp limit to valid range
     let limit = query.limit.clamp(1, MAX_SEARCH_LIMIT);
 
-    let options = SearchOptions::with_limit(limit);
-    let results = state.search.search_skills(&query.q, options);
+    let offset = match &query.cursor {
+        Some(cursor) => Some(state.search.resolve_cursor(cursor).map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse::new(e.to_string())),
+            )
+        })?),
+        None => query.offset,
+    };
+
+    let mut options = SearchOptions::with_limit(limit);
+    options.offset = offset;
+
+    if let Some(msm) = &query.min_should_match {
+        let msm = msm
+            .parse::<MinimumShouldMatch>()
+            .map_err(|e| (StatusCode::BAD_REQUEST, Json(ErrorResponse::new(e))))?;
+        options = options.min_should_match(msm);
+    }
+
+    if let Some(status) = &query.status {
+        let status = status
+            .parse::<SkillStatus>()
+            .map_err(|e| (StatusCode::BAD_REQUEST, Json(ErrorResponse::new(e))))?;
+        options = options.status(status);
+    }
+
+    if let Some(tags) = &query.tags {
+        let expr = tags
+            .parse::<TagExpr>()
+            .map_err(|e| (StatusCode::BAD_REQUEST, Json(ErrorResponse::new(e))))?;
+        options = options.tags(expr);
+    }
+
+    if let Some(spelling) = &query.spelling {
+        let mode = spelling
+            .parse::<SpellingMode>()
+            .map_err(|e| (StatusCode::BAD_REQUEST, Json(ErrorResponse::new(e))))?;
+        options = options.spelling(mode);
+    }
+
+    options.source = query.source.clone();
+    options.language = query.language.clone();
+    options.owner = query.owner.clone();
+    options.category = query.category.clone();
+
+    // Searches are CPU-bound and may wait on the reranker; keep them off
+    // the async workers
+    let results =
+        tokio::task::spawn_blocking(move || state.search.search_skills(&query.q, options))
+            .await
+            .map_err(|e| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ErrorResponse::new(format!("Search failed: {}", e))),
+                )
+            })?;
 
     Ok(Json(results))
 }
+
+// ============================================================================
+// GET /api/search/index - The searchable index, for client-side search
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct SearchIndexQuery {
+    /// `json` (default) or `bin` for the versioned binary encoding.
+    #[serde(default)]
+    pub format: Option<String>,
+}
+
+pub async fn search_index(
+    State(state): State<AppState>,
+    axum::extract::Query(query): axum::extract::Query<SearchIndexQuery>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    let snapshot = state.indexer.snapshot();
+
+    match query.format.as_deref() {
+        None | Some("json") => Ok(Json(snapshot).into_response()),
+        Some("bin") => Ok((
+            [(header::CONTENT_TYPE, "application/octet-stream")],
+            snapshot.to_bytes(),
+        )
+            .into_response()),
+        Some(other) => Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::new(format!(
+                "Invalid format: '{}' (use 'json' or 'bin')",
+                other
+            ))),
+        )),
+    }
+}
+
+// ============================================================================
+// GET /api/usage/disk - Space taken by each skill
+// ============================================================================
+
+pub async fn disk_usage(
+    State(state): State<AppState>,
+) -> Result<Json<crate::models::DiskUsage>, (StatusCode, Json<ErrorResponse>)> {
+    let usage = tokio::task::spawn_blocking(move || state.indexer.disk_usage())
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::new(format!("Disk usage failed: {}", e))),
+            )
+        })?;
+
+    Ok(Json(usage))
+}
+
+// ============================================================================
+// GET /readyz - Readiness probe
+// ============================================================================
+
+#[derive(Debug, Serialize)]
+pub struct ReadyResponse {
+    /// Whether the startup warm-up has finished.
+    pub ready: bool,
+}
+
+/// 200 once search structures are warmed up, 503 until then.
+pub async fn readyz(State(state): State<AppState>) -> (StatusCode, Json<ReadyResponse>) {
+    let ready = state.is_ready();
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(ReadyResponse { ready }))
+}
+
+// ============================================================================
+// GET /api/manifest - Skill content hashes and the latest changes
+// ============================================================================
+
+#[derive(Debug, Serialize)]
+pub struct ManifestResponse {
+    pub manifest: crate::models::IndexManifest,
+    /// How `manifest` differs from the one before the latest update.
+    pub changes: crate::models::ManifestDiff,
+}
+
+pub async fn get_manifest(State(state): State<AppState>) -> Json<ManifestResponse> {
+    Json(ManifestResponse {
+        manifest: state.indexer.manifest(),
+        changes: state.indexer.last_changes(),
+    })
+}
+
+// ============================================================================
+// POST /api/search/batch - Run several searches at once
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct SearchBatchBody {
+    pub queries: Vec<String>,
+    #[serde(default)]
+    pub scope: crate::models::SearchScope,
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchBatchResponse {
+    pub results: std::collections::BTreeMap<String, crate::models::SearchResults>,
+}
+
+pub async fn search_batch(
+    State(state): State<AppState>,
+    Json(body): Json<SearchBatchBody>,
+) -> Result<Json<SearchBatchResponse>, (StatusCode, Json<ErrorResponse>)> {
+    use crate::models::SearchOptions;
+
+    if let Err(e) = check_batch_queries(&body.queries) {
+        return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse::new(e))));
+    }
+
+    // Clamp limit to valid range
+    let limit = body.limit.clamp(1, MAX_SEARCH_LIMIT);
+    let options = SearchOptions::with_limit(limit);
+
+    // Searches are CPU-bound and may wait on the reranker; keep them off
+    // the async workers
+    let results = tokio::task::spawn_blocking(move || {
+        state
+            .search
+            .search_batch(&body.queries, body.scope, &options)
+    })
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::new(format!("Search failed: {}", e))),
+        )
+    })?;
+
+    Ok(Json(SearchBatchResponse { results }))
+}
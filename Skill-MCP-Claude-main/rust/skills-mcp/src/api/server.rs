@@ -11,8 +11,11 @@
 
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use axum::{
+    extract::DefaultBodyLimit,
+    middleware,
     routing::{delete, get, post, put},
     Router,
 };
@@ -20,15 +23,27 @@ use tower_http::cors::{Any, CorsLayer};
 use tower_http::trace::TraceLayer;
 use tracing::info;
 
-use crate::index::SkillIndexer;
+use crate::import::{schedule_update_checks, GitHubImporter, UpdateSchedule};
+use crate::index::{ChangeSubscription, IndexerOptions, SkillIndexer};
+use crate::maintenance::{schedule_gc, GcSchedule};
 use crate::mcp::tools::ServiceContext;
+use crate::mcp::Journal;
 
+use super::case;
+use super::idempotency::{self, IdempotencyStore};
+use super::legacy;
 use super::routes::{self, AppState};
 
 /// HTTP API Server.
 pub struct ApiServer {
     state: AppState,
     port: u16,
+    admin_token: Option<Arc<str>>,
+    gc: Option<GcSchedule>,
+    webhooks: Vec<ChangeSubscription>,
+    update_interval: Option<Duration>,
+    idempotency: Arc<IdempotencyStore>,
+    legacy_routes: bool,
 }
 
 impl ApiServer {
@@ -42,7 +57,16 @@ impl ApiServer {
 
     /// Create a new API server with a specific port.
     pub fn with_port(skills_dir: impl AsRef<std::path::Path>, port: u16) -> Self {
-        let indexer = Arc::new(SkillIndexer::new(skills_dir));
+        Self::with_options(skills_dir, port, IndexerOptions::default())
+    }
+
+    /// Create a new API server with a specific port and indexing options.
+    pub fn with_options(
+        skills_dir: impl AsRef<std::path::Path>,
+        port: u16,
+        options: IndexerOptions,
+    ) -> Self {
+        let indexer = Arc::new(SkillIndexer::with_options(skills_dir, options));
 
         // Initial index load
         if let Err(e) = indexer.reload() {
@@ -52,7 +76,117 @@ impl ApiServer {
         let ctx = ServiceContext::new(indexer);
         let state = Arc::new(ctx);
 
-        Self { state, port }
+        Self {
+            state,
+            port,
+            admin_token: None,
+            gc: None,
+            webhooks: Vec::new(),
+            update_interval: None,
+            idempotency: Arc::new(IdempotencyStore::default()),
+            legacy_routes: false,
+        }
+    }
+
+    /// Serve session traces from a journal.
+    ///
+    /// Fails with [`ApiError::StateShared`] once the state is shared, e.g.
+    /// by a router from [`ApiServer::router`].
+    pub fn with_journal(self, journal: Journal) -> Result<Self, ApiError> {
+        self.configure("journal", |ctx| ctx.journal = Some(journal))
+    }
+
+    /// Import skills from GitHub through this client.
+    ///
+    /// Fails once the state is shared, as with [`ApiServer::with_journal`].
+    pub fn with_github(self, importer: GitHubImporter) -> Result<Self, ApiError> {
+        self.configure("GitHub importer", |ctx| ctx.github = importer)
+    }
+
+    /// Log and count searches slower than `threshold`; `Duration::ZERO`
+    /// turns this off.
+    ///
+    /// Fails once the state is shared, as with [`ApiServer::with_journal`].
+    pub fn with_slow_search(self, threshold: Duration) -> Result<Self, ApiError> {
+        self.configure("slow search threshold", |ctx| {
+            ctx.search.set_slow_search(threshold)
+        })
+    }
+
+    /// Change the service context, if nothing else holds it yet.
+    fn configure(
+        mut self,
+        setting: &'static str,
+        apply: impl FnOnce(&mut ServiceContext),
+    ) -> Result<Self, ApiError> {
+        let ctx = Arc::get_mut(&mut self.state).ok_or(ApiError::StateShared(setting))?;
+        apply(ctx);
+        Ok(self)
+    }
+
+    /// Enable the admin-only `/api/debug` and `/api/maintenance` routes,
+    /// guarded by this token.
+    ///
+    /// Without a token the debug routes are not mounted at all.
+    pub fn with_admin_token(mut self, token: impl Into<String>) -> Self {
+        self.admin_token = Some(Arc::from(token.into()));
+        self
+    }
+
+    /// POST each manifest diff, as JSON, to `url` after index updates that
+    /// add, remove or change skills. Posting stops with the server.
+    pub fn with_change_webhook(mut self, url: impl Into<String>) -> Self {
+        let webhook = self
+            .state
+            .indexer
+            .on_change(super::webhook::change_webhook(url.into()));
+        self.webhooks.push(webhook);
+        self
+    }
+
+    /// Prune records of deleted skills every `interval`, keeping orphaned
+    /// versions for `retention`. Collection stops with the server.
+    pub fn with_gc_schedule(mut self, interval: Duration, retention: Duration) -> Self {
+        self.gc = Some(schedule_gc(
+            Arc::clone(&self.state.indexer),
+            Arc::clone(&self.state.stats),
+            interval,
+            retention,
+        ));
+        self
+    }
+
+    /// Check imported skills for upstream updates every `interval` while
+    /// the server runs.
+    pub fn with_update_checks(mut self, interval: Duration) -> Self {
+        self.update_interval = Some(interval);
+        self
+    }
+
+    /// Keep responses to requests with an `Idempotency-Key` for `ttl`
+    /// rather than the default of a day.
+    pub fn with_idempotency_ttl(mut self, ttl: Duration) -> Self {
+        self.idempotency = Arc::new(IdempotencyStore::new(ttl));
+        self
+    }
+
+    /// Also serve the Flask dashboard's routes under `/skills_manager`, in
+    /// the shapes it expects.
+    pub fn with_legacy_routes(mut self) -> Self {
+        self.legacy_routes = true;
+        self
+    }
+
+    /// Start the scheduled update checks, if enabled.
+    fn schedule_update_checks(&self) -> Option<UpdateSchedule> {
+        self.update_interval.map(|interval| {
+            schedule_update_checks(
+                Arc::clone(&self.state.updates),
+                self.state.github.clone(),
+                Arc::clone(&self.state.indexer),
+                interval,
+            )
+        })
     }
 
     /// Get the application state.
@@ -69,17 +203,77 @@ impl ApiServer {
             .allow_headers(Any);
 
         // API routes
-        let api_routes = Router::new()
+        let mut api_routes = Router::new()
             .route("/skills", get(routes::list_skills))
             .route("/skills", post(routes::create_skill))
+            .route("/skills/merge", post(routes::merge_skills))
+            .route("/import/github", post(routes::import_github))
+            .route("/updates/check", post(routes::check_updates))
             .route("/skills/:name", get(routes::get_skill))
             .route("/skills/:name", put(routes::update_skill))
             .route("/skills/:name", delete(routes::delete_skill))
+            .route("/skills/:name/update", post(routes::update_imported_skill))
+            .route("/skills/:name/raw/*path", get(routes::get_skill_file))
+            .route(
+                "/skills/:name/references",
+                post(routes::upload_references)
+                    .layer(DefaultBodyLimit::max(routes::MAX_UPLOAD_BODY_SIZE)),
+            )
+            .route(
+                "/skills/:name/preview/*path",
+                get(routes::preview_skill_file),
+            )
+            .route(
+                "/skills/:name/thumb/*path",
+                get(routes::get_skill_thumbnail),
+            )
+            .route("/skills/:name/analysis", get(routes::analyze_skill))
+            .route(
+                "/skills/:name/suggested-triggers",
+                get(routes::suggested_triggers),
+            )
+            .route("/categories", get(routes::list_categories))
+            .route("/templates", get(routes::list_templates))
             .route("/reload", post(routes::reload_index))
-            .route("/search", get(routes::search_skills));
+            .route("/reload/:id", get(routes::get_reload_job))
+            .route("/validate/links", post(routes::check_links))
+            .route("/search", get(routes::search_skills))
+            .route("/search/batch", post(routes::search_batch))
+            .route("/search/index", get(routes::search_index))
+            .route("/manifest", get(routes::get_manifest))
+            .route("/usage/disk", get(routes::disk_usage))
+            .route("/sessions/:id/trace", get(routes::get_session_trace));
+
+        if let Some(token) = &self.admin_token {
+            let debug_routes = Router::new()
+                .route("/debug/index", get(routes::debug_index))
+                .route("/debug/diff", get(routes::debug_index_diff))
+                .route("/debug/index/*key", get(routes::debug_index_entry))
+                .route("/debug/memory", get(routes::debug_memory))
+                .route("/maintenance/replace", post(routes::replace_content))
+                .route("/maintenance/split", post(routes::split_skill))
+                .route("/maintenance/gc", post(routes::collect_garbage))
+                .route_layer(middleware::from_fn_with_state(
+                    Arc::clone(token),
+                    routes::require_admin,
+                ));
+            api_routes = api_routes.merge(debug_routes);
+        }
+        let api_routes = api_routes
+            .layer(middleware::from_fn_with_state(
+                Arc::clone(&self.idempotency),
+                idempotency::remember_responses,
+            ))
+            .layer(middleware::from_fn(case::rewrite_field_case));
 
-        Router::new()
-            .nest("/api", api_routes)
+        let mut router = Router::new()
+            .route("/readyz", get(routes::readyz))
+            .nest("/api", api_routes);
+        if self.legacy_routes {
+            router = router.nest("/skills_manager", legacy::legacy_routes());
+        }
+
+        router
             .layer(cors)
             .layer(TraceLayer::new_for_http())
             .with_state(Arc::clone(&self.state))
@@ -95,6 +289,7 @@ impl ApiServer {
         let listener = tokio::net::TcpListener::bind(addr)
             .await
             .map_err(|e| ApiError::Bind(e.to_string()))?;
+        let _updates = self.schedule_update_checks();
 
         axum::serve(listener, app)
             .await
@@ -104,7 +299,10 @@ impl ApiServer {
     }
 
     /// Start the server with graceful shutdown.
-    pub async fn run_with_shutdown(&self, shutdown: impl std::future::Future<Output = ()> + Send + 'static) -> Result<(), ApiError> {
+    pub async fn run_with_shutdown(
+        &self,
+        shutdown: impl std::future::Future<Output = ()> + Send + 'static,
+    ) -> Result<(), ApiError> {
         let app = self.router();
         let addr = SocketAddr::from(([0, 0, 0, 0], self.port));
 
@@ -113,6 +311,7 @@ impl ApiServer {
         let listener = tokio::net::TcpListener::bind(addr)
             .await
             .map_err(|e| ApiError::Bind(e.to_string()))?;
+        let _updates = self.schedule_update_checks();
 
         // Run server with graceful shutdown using tokio::select
         tokio::select! {
@@ -137,6 +336,9 @@ pub enum ApiError {
 
     #[error("Server error: {0}")]
     Serve(String),
+
+    #[error("Can't set the {0}: server state is already shared")]
+    StateShared(&'static str),
 }
 
 #[cfg(test)]
@@ -184,6 +386,191 @@ mod tests {
         assert_eq!(response.status(), StatusCode::OK);
     }
 
+    #[tokio::test]
+    async fn test_list_skills_projection() {
+        let (_temp, app) = create_test_server().await;
+        let get = |uri: &str| {
+            app.clone()
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        };
+
+        let response = get("/api/skills?fields=name,tags").await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let skills: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            skills,
+            serde_json::json!([{"name": "test-skill", "tags": ["test"]}])
+        );
+
+        let response = get("/api/skills").await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let skills: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(skills[0]["description"], "A test skill");
+        assert_eq!(skills[0]["file_count"], 1);
+        assert!(skills[0]["quality"]["score"].is_u64());
+        assert_eq!(skills[0]["stats"]["total_words"], 4);
+        assert_eq!(skills[0]["stats"]["reference_count"], 0);
+        assert!(skills[0]["stats"]["last_modified"].is_string());
+
+        let response = get("/api/skills?fields=name,secrets").await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_readyz_after_warm_up() {
+        let (_temp, app) = create_test_server().await;
+
+        // Warm-up runs in the background; the probe turns ready on its own
+        let mut status = StatusCode::SERVICE_UNAVAILABLE;
+        for _ in 0..100 {
+            let response = app
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .uri("/readyz")
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            status = response.status();
+            if status == StatusCode::OK {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_list_skills_sorted_by_quality() {
+        let (temp, app) = create_test_server().await;
+        let strong = temp.path().join("strong-skill");
+        fs::create_dir_all(&strong).unwrap();
+        fs::write(
+            strong.join("_meta.json"),
+            r#"{"name": "strong-skill", "description": "A thoroughly documented skill", "tags": ["test"], "owner": "docs"}"#,
+        )
+        .unwrap();
+        fs::write(
+            strong.join("SKILL.md"),
+            "# Strong\n\n## Usage\n\nSteps.\n\n## Examples\n\n```\nrun\n```\n",
+        )
+        .unwrap();
+
+        let list = |uri: &'static str| {
+            let app = app.clone();
+            async move {
+                let response = app
+                    .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                    .await
+                    .unwrap();
+                let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+            }
+        };
+
+        app.clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/reload?wait=true")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let weakest_first = list("/api/skills?sort=quality").await;
+        assert_eq!(weakest_first[0]["name"], "test-skill");
+        assert_eq!(weakest_first[1]["name"], "strong-skill");
+        assert!(
+            weakest_first[0]["quality"]["score"].as_u64()
+                < weakest_first[1]["quality"]["score"].as_u64()
+        );
+        assert_eq!(weakest_first[1]["quality"]["factors"]["examples"], 1.0);
+
+        let strongest_first = list("/api/skills?sort=quality&order=desc").await;
+        assert_eq!(strongest_first[0]["name"], "strong-skill");
+    }
+
+    #[tokio::test]
+    async fn test_list_skills_sorted_by_aggregates() {
+        let (temp, _) = create_test_server().await;
+        let large = temp.path().join("large-skill");
+        fs::create_dir_all(&large).unwrap();
+        fs::write(
+            large.join("_meta.json"),
+            r#"{"name": "large-skill", "description": "A long skill"}"#,
+        )
+        .unwrap();
+        fs::write(
+            large.join("SKILL.md"),
+            "# Large\n\n".to_string() + &"Words. ".repeat(200),
+        )
+        .unwrap();
+
+        // test-skill was last changed long ago
+        let long_ago = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000_000);
+        for file in ["_meta.json", "SKILL.md"] {
+            fs::File::options()
+                .write(true)
+                .open(temp.path().join("test-skill").join(file))
+                .unwrap()
+                .set_modified(long_ago)
+                .unwrap();
+        }
+
+        let server = ApiServer::new(temp.path());
+        for _ in 0..3 {
+            server.state().stats.write().record_skill_load("test-skill");
+        }
+        let app = server.router();
+        let names = |uri: &'static str| {
+            let app = app.clone();
+            async move {
+                let response = app
+                    .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                    .await
+                    .unwrap();
+                let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                let skills: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                skills
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|s| s["name"].as_str().unwrap().to_string())
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        assert_eq!(
+            names("/api/skills?sort=size&order=desc&fields=name").await,
+            ["large-skill", "test-skill"]
+        );
+        assert_eq!(
+            names("/api/skills?sort=updated&order=desc&fields=name").await,
+            ["large-skill", "test-skill"]
+        );
+        assert_eq!(
+            names("/api/skills?sort=updated&fields=name").await,
+            ["test-skill", "large-skill"]
+        );
+        assert_eq!(
+            names("/api/skills?sort=popularity&order=desc&fields=name").await,
+            ["test-skill", "large-skill"]
+        );
+    }
+
     #[tokio::test]
     async fn test_get_skill() {
         let (_temp, app) = create_test_server().await;
@@ -202,19 +589,1831 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_get_nonexistent_skill() {
+    async fn test_camel_case_fields() {
+        let (temp, _) = create_test_server().await;
+        // Metadata written for the TypeScript server
+        let skill_dir = temp.path().join("legacy");
+        fs::create_dir_all(&skill_dir).unwrap();
+        fs::write(
+            skill_dir.join("_meta.json"),
+            r#"{"name": "legacy", "description": "Legacy skill",
+                "subSkills": [{"name": "intro", "file": "references/intro.md"}]}"#,
+        )
+        .unwrap();
+        fs::create_dir_all(skill_dir.join("references")).unwrap();
+        fs::write(skill_dir.join("SKILL.md"), "# Legacy").unwrap();
+        fs::write(skill_dir.join("references/intro.md"), "# Intro").unwrap();
+        fs::write(
+            skill_dir.join("references/users.json"),
+            r#"{"user_id": 1, "sub_team": "forms"}"#,
+        )
+        .unwrap();
+        let app = ApiServer::new(temp.path()).router();
+        let get = |uri: &str| {
+            app.clone()
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        };
+
+        let response = get("/api/skills/legacy").await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let skill: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(skill["sub_skills"][0]["name"], "intro");
+        assert_eq!(skill["has_references"], true);
+
+        let response = get("/api/skills/legacy?case=camel").await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let skill: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(skill["subSkills"][0]["name"], "intro");
+        assert_eq!(skill["hasReferences"], true);
+        assert!(skill.get("sub_skills").is_none());
+
+        // Keys of the skill's own files are data, not model fields
+        let response = get("/api/skills/legacy/preview/references/users.json?case=camel")
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let preview: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(preview["value"]["user_id"], 1, "{}", preview);
+        assert_eq!(preview["value"]["sub_team"], "forms");
+
+        let response = get("/api/skills/legacy?case=pascal").await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_legacy_routes() {
+        let (temp, app) = create_test_server().await;
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/skills_manager/list")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let app = ApiServer::new(temp.path()).with_legacy_routes().router();
+        let send = |method: &str, uri: &str, body: &'static str| {
+            app.clone().oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .header("content-type", "application/json")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+        };
+        let json = |response: axum::response::Response| async move {
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+        };
+
+        let response = send("GET", "/skills_manager/list", "").await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let list = json(response).await;
+        assert_eq!(list["skills"][0]["name"], "test-skill");
+
+        let body = r##"{"name": "notes", "description": "Notes", "content": "# Notes"}"##;
+        let response = send("POST", "/skills_manager/create", body).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let created = json(response).await;
+        assert_eq!(created["success"], true);
+        assert_eq!(created["name"], "notes");
+        assert!(temp.path().join("notes/SKILL.md").exists());
+
+        // Flask refused duplicates unless asked to overwrite
+        let response = send("POST", "/skills_manager/create", body).await.unwrap();
+        assert!(json(response).await["error"].is_string());
+        let body = r##"{"name": "notes", "description": "New", "content": "# New",
+                        "overwrite": true}"##;
+        let response = send("POST", "/skills_manager/create", body).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = send("POST", "/skills_manager/reload", "").await.unwrap();
+        assert_eq!(
+            json(response).await,
+            serde_json::json!({"success": true, "message": "Skills reloaded"})
+        );
+
+        let response = send("DELETE", "/skills_manager/delete/notes", "")
+            .await
+            .unwrap();
+        assert_eq!(json(response).await["success"], true);
+        assert!(!temp.path().join("notes").exists());
+    }
+
+    #[tokio::test]
+    async fn test_create_windows_reserved_name() {
         let (_temp, app) = create_test_server().await;
 
         let response = app
             .oneshot(
                 Request::builder()
-                    .uri("/api/skills/nonexistent")
+                    .method("POST")
+                    .uri("/api/skills")
+                    .header("content-type", "application/json")
+                    .body(Body::from(
+                        r#"{"name": "aux", "description": "Reserved", "content": "Aux"}"#,
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_flat_file_skill_lifecycle() {
+        let (temp, app) = create_test_server().await;
+
+        let send = |method: &str, uri: &str, body: &'static str| {
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("content-type", "application/json")
+                .body(Body::from(body))
+                .unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(send(
+                "POST",
+                "/api/skills",
+                r#"{"name": "notes", "description": "Note taking", "content": "Notes", "tags": ["text"], "flat": true}"#,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert!(temp.path().join("notes.md").is_file());
+        assert!(!temp.path().join("notes").exists());
+
+        let response = app
+            .clone()
+            .oneshot(send(
+                "PUT",
+                "/api/skills/notes",
+                r#"{"description": "Better notes"}"#,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let text = fs::read_to_string(temp.path().join("notes.md")).unwrap();
+        assert!(text.contains("description: \"Better notes\""));
+        assert!(text.ends_with("\n\nNotes"));
+
+        let response = app
+            .clone()
+            .oneshot(send("GET", "/api/skills/notes", ""))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["description"], "Better notes");
+        assert_eq!(json["content"], "Notes");
+
+        let response = app
+            .oneshot(send("DELETE", "/api/skills/notes", ""))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert!(!temp.path().join("notes.md").exists());
+    }
+
+    #[tokio::test]
+    async fn test_dry_run() {
+        let (temp, app) = create_test_server().await;
+
+        let send = |method: &str, uri: &str, body: &'static str| {
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("content-type", "application/json")
+                .body(Body::from(body))
+                .unwrap()
+        };
+        let json = |response: axum::response::Response| async move {
+            let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            serde_json::from_slice::<serde_json::Value>(&bytes).unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(send(
+                "POST",
+                "/api/skills?dry_run=true",
+                r#"{"name": "notes", "description": "", "content": "Notes"}"#,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = json(response).await;
+        assert_eq!(body["dry_run"], true);
+        assert_eq!(body["result"]["name"], "notes");
+        assert_eq!(
+            body["created"],
+            serde_json::json!(["notes/_meta.json", "notes/SKILL.md"])
+        );
+        assert_eq!(
+            body["issues"],
+            serde_json::json!(["description: cannot be empty"])
+        );
+        assert!(!temp.path().join("notes").exists());
+
+        // Checks still run
+        let response = app
+            .clone()
+            .oneshot(send(
+                "POST",
+                "/api/skills?dry_run=true",
+                r#"{"name": "test-skill", "description": "Again", "content": "x"}"#,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        let response = app
+            .clone()
+            .oneshot(send(
+                "PUT",
+                "/api/skills/test-skill?dry_run=true",
+                r##"{"content": "# Test Skill\n\nNew content."}"##,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = json(response).await;
+        assert_eq!(body["result"]["content"], "# Test Skill\n\nNew content.");
+        assert_eq!(body["diffs"].as_array().unwrap().len(), 1);
+        assert_eq!(body["diffs"][0]["file"], "test-skill/SKILL.md");
+        assert!(body["diffs"][0]["diff"]
+            .as_str()
+            .unwrap()
+            .contains("+New content."));
+        assert_eq!(
+            fs::read_to_string(temp.path().join("test-skill/SKILL.md")).unwrap(),
+            "# Test Skill\n\nContent."
+        );
+
+        let response = app
+            .clone()
+            .oneshot(send("DELETE", "/api/skills/test-skill?dry_run=true", ""))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = json(response).await;
+        assert_eq!(
+            body["deleted"],
+            serde_json::json!(["test-skill/SKILL.md", "test-skill/_meta.json"])
+        );
+        assert!(temp.path().join("test-skill").is_dir());
+
+        // The query overrides a body asking to apply
+        let response = app
+            .oneshot(send(
+                "POST",
+                "/api/skills/merge?dry_run=true",
+                r#"{"sources": ["test-skill"], "target": "combined", "dry_run": false}"#,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(json(response).await["dry_run"], true);
+        assert!(!temp.path().join("combined").exists());
+    }
+
+    #[tokio::test]
+    async fn test_idempotency_key() {
+        let (temp, app) = create_test_server().await;
+
+        let create = |key: &str, body: &'static str| {
+            Request::builder()
+                .method("POST")
+                .uri("/api/skills")
+                .header("content-type", "application/json")
+                .header("idempotency-key", key)
+                .body(Body::from(body))
+                .unwrap()
+        };
+        let body = r#"{"name": "notes", "description": "Notes", "content": "Notes"}"#;
+
+        let first = app.clone().oneshot(create("abc", body)).await.unwrap();
+        assert_eq!(first.status(), StatusCode::CREATED);
+        assert!(first.headers().get("idempotent-replayed").is_none());
+        let first = axum::body::to_bytes(first.into_body(), usize::MAX)
+            .await
+            .unwrap();
+
+        // A retry gets the original response instead of a conflict
+        let retry = app.clone().oneshot(create("abc", body)).await.unwrap();
+        assert_eq!(retry.status(), StatusCode::CREATED);
+        assert_eq!(retry.headers()["idempotent-replayed"], "true");
+        assert_eq!(retry.headers()["content-type"], "application/json");
+        let retry = axum::body::to_bytes(retry.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(retry, first);
+
+        let reused = app
+            .clone()
+            .oneshot(create(
+                "abc",
+                r#"{"name": "other", "description": "Other", "content": "Other"}"#,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(reused.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(!temp.path().join("other").exists());
+
+        // Without a key the duplicate is an error as before
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/skills")
+                    .header("content-type", "application/json")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn test_update_over_quota() {
+        let (temp, _) = create_test_server().await;
+        let options = IndexerOptions::default().skill_quota(1024);
+        let app = ApiServer::with_options(temp.path(), 0, options).router();
+
+        let body = format!(r#"{{"content": "{}"}}"#, "x".repeat(2048));
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("PUT")
+                    .uri("/api/skills/test-skill")
+                    .header("content-type", "application/json")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert!(json["error"].as_str().unwrap().contains("currently"));
+        assert_eq!(
+            fs::read_to_string(temp.path().join("test-skill/SKILL.md")).unwrap(),
+            "# Test Skill\n\nContent."
+        );
+    }
+
+    #[tokio::test]
+    async fn test_upload_references() {
+        let (temp, app) = create_test_server().await;
+        let upload = |parts: &[(&str, &str, &str)]| {
+            let mut body = String::new();
+            for (file_name, content_type, content) in parts {
+                body.push_str(&format!(
+                    "--XBOUNDARY\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{}\"\r\nContent-Type: {}\r\n\r\n{}\r\n",
+                    file_name, content_type, content
+                ));
+            }
+            body.push_str("--XBOUNDARY--\r\n");
+            Request::builder()
+                .method("POST")
+                .uri("/api/skills/test-skill/references")
+                .header("content-type", "multipart/form-data; boundary=XBOUNDARY")
+                .body(Body::from(body))
+                .unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(upload(&[
+                ("guide.md", "text/markdown", "# Guide"),
+                ("shots/form.png", "image/png", "png"),
+            ]))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            json["files"],
+            serde_json::json!(["references/guide.md", "references/shots/form.png"])
+        );
+        let references = temp.path().join("test-skill/references");
+        assert_eq!(
+            fs::read_to_string(references.join("guide.md")).unwrap(),
+            "# Guide"
+        );
+        assert!(!references.join("shots/form.png").exists());
+
+        // Images are stored as shared blobs and served through the skill
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/skills/test-skill/raw/references/shots/form.png")
                     .body(Body::empty())
                     .unwrap(),
             )
             .await
             .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"png");
 
-        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let rejected = [
+            (
+                ("page.html", "text/html", "<p>"),
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            ),
+            (
+                ("notes.md", "image/png", "png"),
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            ),
+            (
+                ("../escape.md", "text/markdown", "x"),
+                StatusCode::BAD_REQUEST,
+            ),
+        ];
+        for (part, status) in rejected {
+            let response = app.clone().oneshot(upload(&[part])).await.unwrap();
+            assert_eq!(response.status(), status, "{}", part.0);
+        }
+        assert!(!temp.path().join("test-skill/escape.md").exists());
+        assert!(!references.join("notes.md").exists());
+    }
+
+    #[tokio::test]
+    async fn test_import_github() {
+        use crate::import::GitHubImporter;
+
+        let api = crate::import::github::tests::fake_github(&[
+            (
+                "skills/forms/_meta.json",
+                r#"{"name": "forms", "description": "Forms"}"#,
+            ),
+            ("skills/forms/SKILL.md", "# Forms\n\nImported."),
+        ])
+        .await;
+        let temp = TempDir::new().unwrap();
+        let app = ApiServer::new(temp.path())
+            .with_github(GitHubImporter::new().with_api_base(api))
+            .unwrap()
+            .router();
+
+        let import = |body: &'static str| {
+            Request::builder()
+                .method("POST")
+                .uri("/api/import/github")
+                .header("content-type", "application/json")
+                .body(Body::from(body))
+                .unwrap()
+        };
+        let response = app
+            .clone()
+            .oneshot(import(
+                r#"{"url": "https://github.com/acme/skills", "ref": "main", "path": "skills"}"#,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["skills"], serde_json::json!(["forms"]));
+        assert_eq!(json["commit"], crate::import::github::tests::COMMIT);
+
+        let meta: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(temp.path().join("forms/_meta.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(meta["origin"]["url"], "https://github.com/acme/skills");
+        assert_eq!(meta["origin"]["ref"], "main");
+        assert_eq!(meta["origin"]["path"], "skills/forms");
+
+        let rejected = [
+            (
+                r#"{"url": "https://github.com/acme/skills", "ref": "main", "path": "skills"}"#,
+                StatusCode::CONFLICT,
+            ),
+            (
+                r#"{"url": "https://github.com/acme/skills", "ref": "gone"}"#,
+                StatusCode::NOT_FOUND,
+            ),
+            (
+                r#"{"url": "https://github.com/acme/skills", "ref": "main", "path": "docs"}"#,
+                StatusCode::UNPROCESSABLE_ENTITY,
+            ),
+            (
+                r#"{"url": "https://example.com/acme/skills"}"#,
+                StatusCode::BAD_REQUEST,
+            ),
+        ];
+        for (body, status) in rejected {
+            let response = app.clone().oneshot(import(body)).await.unwrap();
+            assert_eq!(response.status(), status, "{}", body);
+        }
+
+        // Pretend the skill was imported at an older commit
+        let meta_path = temp.path().join("forms/_meta.json");
+        let mut meta = meta;
+        meta["origin"]["commit"] = "1111111".into();
+        fs::write(&meta_path, meta.to_string()).unwrap();
+        let post = |uri: &str| {
+            Request::builder()
+                .method("POST")
+                .uri(uri)
+                .body(Body::empty())
+                .unwrap()
+        };
+        app.clone()
+            .oneshot(post("/api/reload?wait=true"))
+            .await
+            .unwrap();
+
+        let response = app
+            .clone()
+            .oneshot(post("/api/updates/check"))
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let updates: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(updates[0]["update_available"], true);
+
+        let listing = |app: Router| async move {
+            let response = app
+                .oneshot(
+                    Request::builder()
+                        .uri("/api/skills")
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+        };
+        assert_eq!(listing(app.clone()).await[0]["update_available"], true);
+
+        let response = app
+            .clone()
+            .oneshot(post("/api/skills/forms/update"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["updated"], true);
+        assert_eq!(json["from"], "1111111");
+        assert!(temp
+            .path()
+            .join(".versions")
+            .join(json["version"].as_str().unwrap())
+            .join("forms/_meta.json")
+            .is_file());
+        assert!(listing(app.clone()).await[0]
+            .get("update_available")
+            .is_none());
+
+        let response = app
+            .clone()
+            .oneshot(post("/api/skills/forms/update"))
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["updated"], false);
+    }
+
+    #[tokio::test]
+    async fn test_create_from_template() {
+        let (temp, app) = create_test_server().await;
+
+        fs::create_dir_all(temp.path().join("_templates")).unwrap();
+        fs::write(
+            temp.path().join("_templates/runbook.md"),
+            "---\ndescription: Ops runbook\ntags: [ops]\n---\n# {{title}}\n\nOn call: {{team}}\n",
+        )
+        .unwrap();
+
+        let send = |method: &str, uri: &str, body: &'static str| {
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("content-type", "application/json")
+                .body(Body::from(body))
+                .unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(send("GET", "/api/templates", ""))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let names: Vec<&str> = json
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            vec!["minimal", "runbook", "standard", "with_sub_skills"]
+        );
+        assert_eq!(json[1]["placeholders"], serde_json::json!(["team"]));
+
+        let response = app
+            .clone()
+            .oneshot(send(
+                "POST",
+                "/api/skills",
+                r#"{"name": "db-failover", "description": "Failover", "template": "runbook"}"#,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = app
+            .clone()
+            .oneshot(send(
+                "POST",
+                "/api/skills",
+                r#"{"name": "db-failover", "description": "Failover", "template": "runbook", "variables": {"team": "storage"}}"#,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(
+            fs::read_to_string(temp.path().join("db-failover/SKILL.md")).unwrap(),
+            "# Db Failover\n\nOn call: storage\n"
+        );
+
+        let response = app
+            .oneshot(send(
+                "POST",
+                "/api/skills",
+                r#"{"name": "other", "description": "Both", "template": "minimal", "content": "Body"}"#,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_merge_skills() {
+        let (temp, app) = create_test_server().await;
+
+        let send = |method: &str, uri: &str, body: &'static str| {
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("content-type", "application/json")
+                .body(Body::from(body))
+                .unwrap()
+        };
+        let json = |response: axum::response::Response| async move {
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(send(
+                "POST",
+                "/api/skills",
+                r##"{"name": "test-extras", "description": "Extras", "content": "# Extras\n\nMore.", "tags": ["extra"]}"##,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let response = app
+            .clone()
+            .oneshot(send(
+                "POST",
+                "/api/skills/merge",
+                r#"{"sources": ["test-extras", "missing"], "target": "test-skill"}"#,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = app
+            .clone()
+            .oneshot(send(
+                "POST",
+                "/api/skills/merge",
+                r#"{"sources": ["test-extras"], "target": "test-skill"}"#,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let plan = json(response).await;
+        assert_eq!(plan["dry_run"], true);
+        assert_eq!(plan["meta"]["tags"], serde_json::json!(["test", "extra"]));
+        assert_eq!(
+            plan["diffs"][1]["diff"],
+            "@@ -3,0 +4,4 @@\n+\n+## Extras\n+\n+More.\n"
+        );
+        assert!(temp.path().join("test-extras").exists());
+
+        let response = app
+            .clone()
+            .oneshot(send(
+                "POST",
+                "/api/skills/merge",
+                r#"{"sources": ["test-extras"], "target": "test-skill", "dry_run": false}"#,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(json(response).await["version"].is_string());
+        assert!(!temp.path().join("test-extras").exists());
+
+        let response = app
+            .oneshot(send("GET", "/api/skills/test-extras", ""))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PERMANENT_REDIRECT);
+        assert_eq!(response.headers()["location"], "/api/skills/test-skill");
+    }
+
+    #[tokio::test]
+    async fn test_namespaced_skills() {
+        let (temp, app) = create_test_server().await;
+
+        let send = |method: &str, uri: &str, body: &'static str| {
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("content-type", "application/json")
+                .body(Body::from(body))
+                .unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(send(
+                "POST",
+                "/api/skills",
+                r#"{"name": "frontend/forms", "description": "Forms", "content": "Forms body"}"#,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert!(temp.path().join("frontend/forms/_meta.json").is_file());
+
+        // Skills can't be nested inside other skills
+        let response = app
+            .clone()
+            .oneshot(send(
+                "POST",
+                "/api/skills",
+                r#"{"name": "frontend/forms/inner", "description": "Inner", "content": "x"}"#,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = app
+            .clone()
+            .oneshot(send("GET", "/api/skills/frontend%2Fforms", ""))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app
+            .clone()
+            .oneshot(send("GET", "/api/categories", ""))
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json[0]["name"], "frontend");
+        assert_eq!(json[0]["skills"][0], "frontend/forms");
+
+        let response = app
+            .oneshot(send("GET", "/api/search?q=forms&category=frontend", ""))
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["results"][0]["domain"], "frontend/forms");
+    }
+
+    #[tokio::test]
+    async fn test_get_skill_file_streams_raw_bytes() {
+        let (temp, app) = create_test_server().await;
+        let refs = temp.path().join("test-skill/references");
+        fs::create_dir_all(&refs).unwrap();
+        fs::write(refs.join("latin1.md"), b"Caf\xe9").unwrap();
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/skills/test-skill/raw/references/latin1.md")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"Caf\xe9");
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/skills/test-skill/raw/..%2F..%2Fetc%2Fpasswd")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/skills/test-skill/raw/missing.md")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_preview_data_files() {
+        let (temp, app) = create_test_server().await;
+        let data = temp.path().join("test-skill/data");
+        fs::create_dir_all(&data).unwrap();
+        fs::write(data.join("rows.csv"), "id,name\n1,ada\n2,bob\n3,cy\n").unwrap();
+        fs::write(data.join("config.json"), r#"{"b": 2, "a": 1}"#).unwrap();
+
+        let get = |uri: &'static str| {
+            let app = app.clone();
+            async move {
+                app.oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                    .await
+                    .unwrap()
+            }
+        };
+
+        let response = get("/api/skills/test-skill/preview/data/rows.csv?limit=2").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["format"], "table");
+        assert_eq!(json["columns"], serde_json::json!(["id", "name"]));
+        assert_eq!(json["rows"][1][1], "bob");
+        assert_eq!(json["truncated"], true);
+
+        let response = get("/api/skills/test-skill/preview/data/config.json?limit=1").await;
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["kind"], "object");
+        assert_eq!(json["length"], 2);
+        assert_eq!(json["value"], serde_json::json!({"a": 1}));
+
+        let response = get("/api/skills/test-skill/preview/SKILL.md").await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = get("/api/skills/test-skill/preview/data/missing.csv").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_check_links() {
+        let (temp, app) = create_test_server().await;
+        fs::write(
+            temp.path().join("test-skill/SKILL.md"),
+            "# Test Skill\n\nSee http://127.0.0.1:1/closed for details.",
+        )
+        .unwrap();
+
+        let post = |uri: &'static str| {
+            let app = app.clone();
+            async move {
+                app.oneshot(
+                    Request::builder()
+                        .method("POST")
+                        .uri(uri)
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap()
+            }
+        };
+
+        post("/api/reload?wait=true").await;
+        let response = post("/api/validate/links").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["valid"], true);
+        assert_eq!(json["links_checked"], 1);
+        assert_eq!(json["broken"][0]["url"], "http://127.0.0.1:1/closed");
+        assert_eq!(json["broken"][0]["status"]["state"], "unreachable");
+        assert!(json["warnings"][0]
+            .as_str()
+            .unwrap()
+            .contains("Dead link in SKILL.md"));
+    }
+
+    #[tokio::test]
+    async fn test_skill_analysis() {
+        let (temp, app) = create_test_server().await;
+        fs::write(
+            temp.path().join("test-skill/SKILL.md"),
+            "# Test Skill\n\n- a\n  - b\n    - c\n      - d\n",
+        )
+        .unwrap();
+
+        let get = |uri: &'static str| {
+            let app = app.clone();
+            async move {
+                app.oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                    .await
+                    .unwrap()
+            }
+        };
+
+        let response = get("/api/skills/test-skill/analysis").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["files_analyzed"], 1);
+        assert_eq!(json["findings"][0]["kind"], "deep_nesting");
+        assert_eq!(json["findings"][0]["file"], "SKILL.md");
+        assert_eq!(json["findings"][0]["line"], 3);
+
+        let response = get("/api/skills/missing/analysis").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_suggested_triggers() {
+        let (temp, _) = create_test_server().await;
+        let server = ApiServer::new(temp.path());
+        {
+            let mut stats = server.state().stats.write();
+            for query in ["unit test fixtures", "fixtures for mocks", "mocks"] {
+                stats.record_search(query.to_string(), 1);
+                stats.record_skill_load("test-skill");
+            }
+        }
+        let app = server.router();
+
+        let get = |uri: &'static str| {
+            let app = app.clone();
+            async move {
+                app.oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                    .await
+                    .unwrap()
+            }
+        };
+
+        let response = get("/api/skills/test-skill/suggested-triggers").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["searches"], 3);
+        assert_eq!(json["suggestions"][0]["term"], "fixtures");
+        assert_eq!(json["suggestions"][1]["term"], "mocks");
+        assert_eq!(json["suggestions"].as_array().unwrap().len(), 2);
+
+        let response = get("/api/skills/test-skill/suggested-triggers?min_searches=1").await;
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["suggestions"].as_array().unwrap().len(), 3);
+
+        let response = get("/api/skills/missing/suggested-triggers").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_image_thumbnails() {
+        let (temp, app) = create_test_server().await;
+        let refs = temp.path().join("test-skill/references");
+        fs::create_dir_all(&refs).unwrap();
+        #[cfg(feature = "thumbnails")]
+        image::RgbImage::new(300, 150)
+            .save(refs.join("shot.png"))
+            .unwrap();
+        #[cfg(not(feature = "thumbnails"))]
+        fs::write(refs.join("shot.png"), b"png").unwrap();
+
+        let get = |uri: &'static str, etag: Option<String>| {
+            let app = app.clone();
+            async move {
+                let mut request = Request::builder().uri(uri);
+                if let Some(etag) = etag {
+                    request = request.header("if-none-match", etag);
+                }
+                app.oneshot(request.body(Body::empty()).unwrap())
+                    .await
+                    .unwrap()
+            }
+        };
+
+        let response = get(
+            "/api/skills/test-skill/thumb/references/shot.png?size=64",
+            None,
+        )
+        .await;
+        #[cfg(not(feature = "thumbnails"))]
+        assert_eq!(response.status(), StatusCode::NOT_IMPLEMENTED);
+        #[cfg(feature = "thumbnails")]
+        {
+            assert_eq!(response.status(), StatusCode::OK);
+            let etag = response.headers()["etag"].to_str().unwrap().to_string();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let thumb = image::load_from_memory(&body).unwrap();
+            assert_eq!((thumb.width(), thumb.height()), (64, 32));
+
+            let response = get(
+                "/api/skills/test-skill/thumb/references/shot.png?size=64",
+                Some(etag),
+            )
+            .await;
+            assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        }
+
+        let response = get("/api/skills/test-skill/thumb/SKILL.md", None).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = get(
+            "/api/skills/test-skill/thumb/references/shot.png?size=4096",
+            None,
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_search_tag_expression() {
+        let (_temp, app) = create_test_server().await;
+
+        let search = |uri: &'static str| {
+            let app = app.clone();
+            async move {
+                app.oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                    .await
+                    .unwrap()
+            }
+        };
+
+        let response = search("/api/search?q=test&tags=test%20AND%20NOT%20deprecated").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["total_matches"], 1);
+
+        let response = search("/api/search?q=test&tags=deprecated").await;
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["total_matches"], 0);
+
+        let response = search("/api/search?q=test&tags=%28test").await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_search_batch() {
+        let (_temp, app) = create_test_server().await;
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/search/batch")
+                    .header("content-type", "application/json")
+                    .body(Body::from(
+                        r#"{"queries": ["test", "content"], "scope": "all"}"#,
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert!(json["results"]["test"]["total_matches"].as_u64().unwrap() > 0);
+        assert!(
+            json["results"]["content"]["total_matches"]
+                .as_u64()
+                .unwrap()
+                > 0
+        );
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/search/batch")
+                    .header("content-type", "application/json")
+                    .body(Body::from(r#"{"queries": []}"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_search_index_snapshot() {
+        let (_temp, app) = create_test_server().await;
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/search/index")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let snapshot: crate::search::IndexSnapshot = serde_json::from_slice(&body).unwrap();
+        let results = snapshot.search_all("test", crate::models::SearchOptions::default());
+        assert_eq!(results.top().unwrap().domain, "test-skill");
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/search/index?format=bin")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let decoded = crate::search::IndexSnapshot::from_bytes(&body).unwrap();
+        assert_eq!(decoded.version, snapshot.version);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/search/index?format=xml")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_manifest() {
+        let (temp, app) = create_test_server().await;
+        let get = || async {
+            let response = app
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .uri("/api/manifest")
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+        };
+
+        let before = get().await;
+        assert!(before["manifest"]["skills"]["test-skill"].is_string());
+        assert_eq!(before["changes"]["added"][0], "test-skill");
+
+        fs::write(
+            temp.path().join("test-skill/SKILL.md"),
+            "# Test Skill\n\nNew content.",
+        )
+        .unwrap();
+        app.clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/reload?wait=true")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let after = get().await;
+        assert_eq!(after["changes"]["changed"][0], "test-skill");
+        assert_ne!(
+            after["manifest"]["skills"]["test-skill"],
+            before["manifest"]["skills"]["test-skill"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_disk_usage() {
+        let (_temp, app) = create_test_server().await;
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/usage/disk")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["skills"][0]["name"], "test-skill");
+        assert_eq!(json["skills"][0]["files"], 2);
+        assert_eq!(json["total_files"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_reload_returns_pollable_job() {
+        let (_temp, app) = create_test_server().await;
+        let request = |method: &str, uri: &str| {
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(request("POST", "/api/reload"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        let location = response.headers()[axum::http::header::LOCATION]
+            .to_str()
+            .unwrap()
+            .to_string();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let job: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(location, format!("/api/reload/{}", job["id"]));
+
+        let mut polled = serde_json::Value::Null;
+        for _ in 0..100 {
+            let response = app
+                .clone()
+                .oneshot(request("GET", &location))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            polled = serde_json::from_slice(&body).unwrap();
+            if polled["state"] == "succeeded" || polled["state"] == "failed" {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(polled["state"], "succeeded");
+        assert_eq!(polled["skill_count"], 1);
+
+        let response = app
+            .oneshot(request("GET", "/api/reload/999"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_get_nonexistent_skill() {
+        let (_temp, app) = create_test_server().await;
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/skills/nonexistent")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_get_large_skill_is_streamed() {
+        let (temp, app) = create_test_server().await;
+
+        // Multi-byte characters land on chunk boundaries at this size
+        let content = "Ünïcödé \"quoted\" \\ line\n".repeat(20_000);
+        fs::write(temp.path().join("test-skill/SKILL.md"), &content).unwrap();
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/skills/test-skill")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().get("content-length").is_none());
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["name"], "test-skill");
+        assert_eq!(json["tags"][0], "test");
+        assert_eq!(json["content"].as_str().unwrap(), content);
+    }
+
+    #[tokio::test]
+    async fn test_session_trace() {
+        let temp_dir = TempDir::new().unwrap();
+        let skill_dir = temp_dir.path().join("skills/test-skill");
+        fs::create_dir_all(&skill_dir).unwrap();
+        fs::write(
+            skill_dir.join("_meta.json"),
+            r#"{"name": "test-skill", "description": "A test skill"}"#,
+        )
+        .unwrap();
+        fs::write(skill_dir.join("SKILL.md"), "# Test Skill").unwrap();
+
+        let journal = Journal::open(temp_dir.path().join("traces")).unwrap();
+        journal.record(&crate::mcp::TraceEvent::skill_load("s1", "test-skill"));
+
+        let server = ApiServer::new(temp_dir.path().join("skills"))
+            .with_journal(journal)
+            .unwrap();
+        let app = server.router();
+
+        let get = |uri: &str| {
+            Request::builder()
+                .uri(uri.to_string())
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(get("/api/sessions/s1/trace"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["events"][0]["event"], "skill_load");
+        assert_eq!(json["events"][0]["skill"], "test-skill");
+
+        let response = app
+            .clone()
+            .oneshot(get("/api/sessions/s2/trace"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = app.oneshot(get("/api/sessions/..bad/trace")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        // A router holding the state keeps it from changing under it
+        let server = ApiServer::new(temp_dir.path().join("skills"));
+        let _app = server.router();
+        let journal = Journal::open(temp_dir.path().join("traces")).unwrap();
+        assert!(matches!(
+            server.with_journal(journal),
+            Err(ApiError::StateShared("journal"))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_maintenance_replace() {
+        let (temp, _) = create_test_server().await;
+        let skill_md = temp.path().join("test-skill/SKILL.md");
+        fs::write(&skill_md, "# Test Skill\n\nUse AcmeDB v1.\n").unwrap();
+
+        let post = |body: &str, token: Option<&str>| {
+            let mut request = Request::builder()
+                .method("POST")
+                .uri("/api/maintenance/replace")
+                .header("content-type", "application/json");
+            if let Some(token) = token {
+                request = request.header("authorization", format!("Bearer {}", token));
+            }
+            request.body(Body::from(body.to_string())).unwrap()
+        };
+        let body = r#"{"pattern": "AcmeDB v(\\d)", "replacement": "NovaDB v$1"}"#;
+
+        let app = ApiServer::new(temp.path()).router();
+        let response = app.oneshot(post(body, None)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let app = ApiServer::new(temp.path())
+            .with_admin_token("secret")
+            .router();
+        let response = app.clone().oneshot(post(body, None)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        // Dry run by default
+        let response = app
+            .clone()
+            .oneshot(post(body, Some("secret")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(json["dry_run"], true);
+        assert_eq!(json["replacements"], 1);
+        assert_eq!(
+            json["changes"][0]["diff"],
+            "@@ -3,1 +3,1 @@\n-Use AcmeDB v1.\n+Use NovaDB v1.\n"
+        );
+        assert!(json.get("version").is_none());
+        assert!(fs::read_to_string(&skill_md).unwrap().contains("AcmeDB"));
+
+        let apply = r#"{"pattern": "AcmeDB", "replacement": "NovaDB", "dry_run": false}"#;
+        let response = app
+            .clone()
+            .oneshot(post(apply, Some("secret")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        let version = json["version"].as_str().unwrap();
+        assert!(fs::read_to_string(&skill_md).unwrap().contains("NovaDB"));
+        assert!(temp
+            .path()
+            .join(crate::maintenance::VERSIONS_DIR)
+            .join(version)
+            .join("test-skill/SKILL.md")
+            .is_file());
+
+        let invalid = r#"{"pattern": "(", "replacement": ""}"#;
+        let response = app.oneshot(post(invalid, Some("secret"))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_maintenance_split() {
+        let (temp, _) = create_test_server().await;
+        fs::write(
+            temp.path().join("test-skill/SKILL.md"),
+            "# Test Skill\n\nIntro.\n\n## Setup\n\nInstall it.\n\n## Usage\n\nRun it.\n",
+        )
+        .unwrap();
+
+        let app = ApiServer::new(temp.path())
+            .with_admin_token("secret")
+            .router();
+        let post = |body: &'static str| {
+            Request::builder()
+                .method("POST")
+                .uri("/api/maintenance/split")
+                .header("content-type", "application/json")
+                .header("authorization", "Bearer secret")
+                .body(Body::from(body))
+                .unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(post(r#"{"skill": "test-skill"}"#))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(json["dry_run"], true);
+        assert_eq!(json["sub_skills"][1]["file"], "references/usage.md");
+        assert_eq!(
+            json["diffs"][3]["diff"],
+            "@@ -0,0 +1,3 @@\n+# Usage\n+\n+Run it.\n"
+        );
+        assert!(!temp.path().join("test-skill/references").exists());
+
+        let response = app
+            .clone()
+            .oneshot(post(r#"{"skill": "test-skill", "dry_run": false}"#))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            fs::read_to_string(temp.path().join("test-skill/references/setup.md")).unwrap(),
+            "# Setup\n\nInstall it.\n"
+        );
+
+        let response = app.oneshot(post(r#"{"skill": "missing"}"#)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_pinned_skill() {
+        let (temp, _) = create_test_server().await;
+        fs::write(
+            temp.path().join("test-skill/SKILL.md"),
+            "# Test Skill\n\nIntro.\n\n## Setup\n\nInstall it.\n\n## Usage\n\nRun it.\n",
+        )
+        .unwrap();
+        let app = ApiServer::new(temp.path())
+            .with_admin_token("secret")
+            .router();
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("PUT")
+                    .uri("/api/skills/test-skill")
+                    .header("content-type", "application/json")
+                    .body(Body::from(r#"{"pinned": true}"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app
+            .clone()
+            .oneshot(Request::get("/api/skills").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(json[0]["pinned"], true);
+
+        let split = |body: &'static str| {
+            Request::builder()
+                .method("POST")
+                .uri("/api/maintenance/split")
+                .header("content-type", "application/json")
+                .header("authorization", "Bearer secret")
+                .body(Body::from(body))
+                .unwrap()
+        };
+        let response = app
+            .clone()
+            .oneshot(split(r#"{"skill": "test-skill"}"#))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::LOCKED);
+        let response = app
+            .oneshot(split(r#"{"skill": "test-skill", "force": true}"#))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_maintenance_gc() {
+        let (temp, _) = create_test_server().await;
+        let saved = temp
+            .path()
+            .join(crate::maintenance::VERSIONS_DIR)
+            .join("20200101T000000.000Z/deleted-skill/SKILL.md");
+        fs::create_dir_all(saved.parent().unwrap()).unwrap();
+        fs::write(&saved, "# Deleted").unwrap();
+
+        let app = ApiServer::new(temp.path())
+            .with_admin_token("secret")
+            .router();
+        let post = |body: &'static str| {
+            Request::builder()
+                .method("POST")
+                .uri("/api/maintenance/gc")
+                .header("content-type", "application/json")
+                .header("authorization", "Bearer secret")
+                .body(Body::from(body))
+                .unwrap()
+        };
+
+        let response = app.clone().oneshot(post("{}")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(json["dry_run"], true);
+        assert_eq!(json["versions"][0]["skill"], "deleted-skill");
+        assert!(saved.is_file());
+
+        let response = app.oneshot(post(r#"{"dry_run": false}"#)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(!saved.exists());
+    }
+
+    #[tokio::test]
+    async fn test_debug_index_requires_admin_token() {
+        let temp_dir = TempDir::new().unwrap();
+        let skill_dir = temp_dir.path().join("test-skill");
+        fs::create_dir_all(&skill_dir).unwrap();
+        fs::write(
+            skill_dir.join("_meta.json"),
+            r#"{"name": "test-skill", "description": "A test skill"}"#,
+        )
+        .unwrap();
+        fs::write(skill_dir.join("SKILL.md"), "# Test Skill").unwrap();
+
+        let get = |uri: &str, token: Option<&str>| {
+            let mut request = Request::builder().uri(uri.to_string());
+            if let Some(token) = token {
+                request = request.header("authorization", format!("Bearer {}", token));
+            }
+            request.body(Body::empty()).unwrap()
+        };
+
+        // Not mounted without a token
+        let app = ApiServer::new(temp_dir.path()).router();
+        let response = app.oneshot(get("/api/debug/index", None)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let app = ApiServer::new(temp_dir.path())
+            .with_admin_token("secret")
+            .router();
+
+        let response = app
+            .clone()
+            .oneshot(get("/api/debug/index", None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let response = app
+            .clone()
+            .oneshot(get("/api/debug/index", Some("wrong")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = app
+            .clone()
+            .oneshot(get("/api/debug/index", Some("secret")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["skill_index"]["skills"][0]["name"], "test-skill");
+        let keys: Vec<&str> = json["content_index"]["entries"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["key"].as_str().unwrap())
+            .collect();
+        assert_eq!(keys, vec!["test-skill", "test-skill:_meta.json"]);
+        assert!(json["content_index"]["entries"][0].get("content").is_none());
+
+        let response = app
+            .clone()
+            .oneshot(get("/api/debug/index/test-skill", Some("secret")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["content"], "# test skill");
+
+        let response = app
+            .clone()
+            .oneshot(get("/api/debug/index/nope", Some("secret")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = app
+            .clone()
+            .oneshot(get("/api/debug/diff", Some("secret")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["in_sync"], true);
+
+        let response = app
+            .oneshot(get("/api/debug/memory", Some("secret")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["index"]["skills"], 1);
+        assert_eq!(json["index"]["content_entries"], 2);
+        assert!(json["index"]["total_bytes"].as_u64().unwrap() > 0);
+        #[cfg(target_os = "linux")]
+        assert!(json["process_rss_bytes"].as_u64().unwrap() > 0);
     }
 }
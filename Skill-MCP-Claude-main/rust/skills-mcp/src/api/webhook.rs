@@ -0,0 +1,106 @@
+//! Change webhooks: POST each manifest diff to a URL.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tracing::{debug, warn};
+
+use crate::index::ChangeListener;
+use crate::models::ManifestDiff;
+
+/// Time limit per delivery.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A listener that POSTs each [`ManifestDiff`] as JSON to `url`.
+///
+/// Deliveries are fire-and-forget on their own thread, so a slow receiver
+/// never holds up a reload; failures are logged and not retried.
+pub(crate) fn change_webhook(url: String) -> ChangeListener {
+    let client = reqwest::Client::builder()
+        .timeout(WEBHOOK_TIMEOUT)
+        .user_agent(concat!(
+            "skills-mcp/",
+            env!("CARGO_PKG_VERSION"),
+            " webhook"
+        ))
+        // Each delivery runs on a fresh runtime, so pooled connections
+        // would outlive the runtime that owns them
+        .pool_max_idle_per_host(0)
+        .build()
+        .unwrap_or_default();
+    let url: Arc<str> = Arc::from(url);
+
+    Arc::new(move |changes: &ManifestDiff| {
+        let body = match serde_json::to_vec(changes) {
+            Ok(body) => body,
+            Err(e) => {
+                warn!("Failed to encode change webhook body: {}", e);
+                return;
+            }
+        };
+        let client = client.clone();
+        let url = Arc::clone(&url);
+
+        std::thread::spawn(move || {
+            let runtime = match tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+            {
+                Ok(runtime) => runtime,
+                Err(e) => {
+                    warn!("Failed to start change webhook runtime: {}", e);
+                    return;
+                }
+            };
+
+            // Build the request inside the runtime; its timeout needs a reactor
+            let sent = runtime.block_on(async {
+                client
+                    .post(&*url)
+                    .header(reqwest::header::CONTENT_TYPE, "application/json")
+                    .body(body)
+                    .send()
+                    .await
+            });
+            match sent.and_then(|response| response.error_for_status()) {
+                Ok(_) => debug!("Delivered change webhook to {}", url),
+                Err(e) => warn!("Change webhook to {} failed: {}", url, e),
+            }
+        });
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::post, Json, Router};
+    use tokio::sync::mpsc;
+
+    #[tokio::test]
+    async fn test_change_webhook() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let app = Router::new().route(
+            "/hook",
+            post(move |Json(diff): Json<ManifestDiff>| async move {
+                tx.send(diff).unwrap();
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let changes = ManifestDiff {
+            from_version: 1,
+            to_version: 2,
+            changed: vec!["forms".to_string()],
+            ..Default::default()
+        };
+        change_webhook(format!("http://{}/hook", addr))(&changes);
+
+        let received = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(received, changes);
+    }
+}
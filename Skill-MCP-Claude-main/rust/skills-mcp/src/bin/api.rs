@@ -3,12 +3,16 @@
 //! Run with: cargo run --bin skills-api-server -- [OPTIONS]
 
 use std::path::PathBuf;
+use std::time::Duration;
 
 use clap::Parser;
 use tracing::info;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use skills_mcp::api::ApiServer;
+use skills_mcp::import::GitHubImporter;
+use skills_mcp::index::IndexerOptions;
+use skills_mcp::mcp::Journal;
 
 /// Skills API Server
 #[derive(Parser, Debug)]
@@ -24,6 +28,67 @@ struct Args {
     #[arg(short, long, default_value = "5050", env = "PORT")]
     port: u16,
 
+    /// Do not follow symlinked skills or reference files
+    #[arg(long, env = "SKILLS_NO_FOLLOW_LINKS")]
+    no_follow_links: bool,
+
+    /// Largest file, in bytes, loaded into the content index
+    #[arg(long, default_value_t = IndexerOptions::DEFAULT_MAX_FILE_SIZE, env = "SKILLS_MAX_FILE_SIZE")]
+    max_file_size: u64,
+
+    /// Searches slower than this many milliseconds are logged and counted (0 to disable)
+    #[arg(long, default_value_t = 500, env = "SKILLS_SLOW_SEARCH_MS")]
+    slow_search_ms: u64,
+
+    /// Reloads slower than this many milliseconds are logged and counted (0 to disable)
+    #[arg(long, default_value_t = 5000, env = "SKILLS_SLOW_RELOAD_MS")]
+    slow_reload_ms: u64,
+
+    /// Largest size, in bytes, a single skill may grow to through the API
+    #[arg(long, env = "SKILLS_SKILL_QUOTA")]
+    skill_quota: Option<u64>,
+
+    /// Largest size, in bytes, all skills together may grow to through the API
+    #[arg(long, env = "SKILLS_TOTAL_QUOTA")]
+    total_quota: Option<u64>,
+
+    /// Directory of per-session JSONL traces of skill loads and searches
+    #[arg(long, env = "SKILLS_JOURNAL_DIR")]
+    journal_dir: Option<PathBuf>,
+
+    /// Token required by the /api/debug routes; they are disabled without one
+    #[arg(long, env = "SKILLS_ADMIN_TOKEN", hide_env_values = true)]
+    admin_token: Option<String>,
+
+    /// Hours between scheduled cleanups of records of deleted skills;
+    /// disabled when unset
+    #[arg(long, env = "SKILLS_GC_INTERVAL_HOURS")]
+    gc_interval_hours: Option<u64>,
+
+    /// Days orphaned versions are kept before garbage collection
+    #[arg(long, default_value_t = 30, env = "SKILLS_GC_RETENTION_DAYS")]
+    gc_retention_days: u64,
+
+    /// URL to POST the changed skills to after each index update
+    #[arg(long, env = "SKILLS_CHANGE_WEBHOOK")]
+    change_webhook: Option<String>,
+
+    /// Token for GitHub imports, for private repositories and higher rate limits
+    #[arg(long, env = "SKILLS_GITHUB_TOKEN", hide_env_values = true)]
+    github_token: Option<String>,
+
+    /// Hours between checks of imported skills for upstream updates
+    #[arg(long, env = "SKILLS_UPDATE_CHECK_HOURS")]
+    update_check_hours: Option<u64>,
+
+    /// Hours a response is replayed for retries with the same Idempotency-Key
+    #[arg(long, default_value_t = 24, env = "SKILLS_IDEMPOTENCY_TTL_HOURS")]
+    idempotency_ttl_hours: u64,
+
+    /// Also serve the old Flask dashboard's routes under /skills_manager
+    #[arg(long, env = "SKILLS_LEGACY_ROUTES")]
+    legacy_routes: bool,
+
     /// Enable debug logging
     #[arg(short, long)]
     debug: bool,
@@ -41,7 +106,9 @@ async fn main() -> anyhow::Result<()> {
     };
 
     tracing_subscriber::registry()
-        .with(tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| filter.into()))
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| filter.into()),
+        )
         .with(tracing_subscriber::fmt::layer().with_target(false))
         .init();
 
@@ -69,7 +136,50 @@ async fn main() -> anyhow::Result<()> {
         args.port
     );
 
-    let server = ApiServer::with_port(&skills_dir, args.port);
+    let mut options = IndexerOptions::default()
+        .follow_links(!args.no_follow_links)
+        .max_file_size(args.max_file_size)
+        .slow_reload(Duration::from_millis(args.slow_reload_ms));
+    if let Some(bytes) = args.skill_quota {
+        options = options.skill_quota(bytes);
+    }
+    if let Some(bytes) = args.total_quota {
+        options = options.total_quota(bytes);
+    }
+    let mut server = ApiServer::with_options(&skills_dir, args.port, options)
+        .with_idempotency_ttl(Duration::from_secs(args.idempotency_ttl_hours * 60 * 60))
+        .with_slow_search(Duration::from_millis(args.slow_search_ms))?;
+    if let Some(token) = args.admin_token {
+        server = server.with_admin_token(token);
+    }
+    if let Some(hours) = args.gc_interval_hours {
+        info!(
+            "Collecting garbage every {}h, keeping orphaned versions {} days",
+            hours, args.gc_retention_days
+        );
+        server = server.with_gc_schedule(
+            Duration::from_secs(hours.max(1) * 60 * 60),
+            Duration::from_secs(args.gc_retention_days * 24 * 60 * 60),
+        );
+    }
+    if let Some(url) = args.change_webhook {
+        info!("Posting index changes to {}", url);
+        server = server.with_change_webhook(url);
+    }
+    if let Some(token) = args.github_token {
+        server = server.with_github(GitHubImporter::new().with_token(token))?;
+    }
+    if let Some(hours) = args.update_check_hours {
+        info!("Checking imported skills for updates every {}h", hours);
+        server = server.with_update_checks(Duration::from_secs(hours.max(1) * 60 * 60));
+    }
+    if args.legacy_routes {
+        server = server.with_legacy_routes();
+    }
+    if let Some(dir) = &args.journal_dir {
+        info!("Serving session traces from {:?}", dir);
+        server = server.with_journal(Journal::open(dir)?)?;
+    }
 
     // Set up graceful shutdown
     let shutdown = async {
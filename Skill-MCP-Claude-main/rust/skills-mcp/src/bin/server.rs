@@ -2,13 +2,20 @@
 //!
 //! Run with: cargo run --bin skills-mcp-server -- [OPTIONS]
 
+use std::net::SocketAddr;
 use std::path::PathBuf;
+use std::time::Duration;
 
 use clap::Parser;
 use tracing::info;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
-use skills_mcp::mcp::McpServer;
+use skills_mcp::index::IndexerOptions;
+use skills_mcp::mcp::{
+    BatchLimits, ConfidenceThresholds, HttpOptions, Journal, McpServer, ToolAllowlist,
+};
+#[cfg(feature = "llm")]
+use skills_mcp::search::RerankBudget;
 
 /// Skills MCP Server
 #[derive(Parser, Debug)]
@@ -20,6 +27,125 @@ struct Args {
     #[arg(short, long, env = "SKILLS_DIR")]
     skills_dir: Option<PathBuf>,
 
+    /// Do not follow symlinked skills or reference files
+    #[arg(long, env = "SKILLS_NO_FOLLOW_LINKS")]
+    no_follow_links: bool,
+
+    /// Do not watch the skills directory for changes
+    #[arg(long, env = "SKILLS_NO_WATCH")]
+    no_watch: bool,
+
+    /// Serve MCP over streamable HTTP on this address instead of stdio
+    #[arg(long, value_name = "ADDR", env = "SKILLS_MCP_HTTP")]
+    http: Option<SocketAddr>,
+
+    /// Browser origin allowed to use the HTTP transport besides localhost; may be repeated
+    #[arg(
+        long = "allowed-origin",
+        value_name = "ORIGIN",
+        value_delimiter = ',',
+        env = "SKILLS_MCP_ALLOWED_ORIGINS"
+    )]
+    allowed_origins: Vec<String>,
+
+    /// Most MCP sessions open at once over HTTP
+    #[arg(long, default_value_t = HttpOptions::DEFAULT_MAX_SESSIONS, env = "SKILLS_MCP_MAX_SESSIONS")]
+    max_sessions: usize,
+
+    /// Seconds without a request before an HTTP session ends
+    #[arg(long, default_value_t = HttpOptions::DEFAULT_SESSION_IDLE_TIMEOUT.as_secs(), env = "SKILLS_MCP_SESSION_IDLE_SECS")]
+    session_idle_secs: u64,
+
+    /// Largest file, in bytes, loaded into the content index
+    #[arg(long, default_value_t = IndexerOptions::DEFAULT_MAX_FILE_SIZE, env = "SKILLS_MAX_FILE_SIZE")]
+    max_file_size: u64,
+
+    /// Searches slower than this many milliseconds are logged and counted (0 to disable)
+    #[arg(long, default_value_t = 500, env = "SKILLS_SLOW_SEARCH_MS")]
+    slow_search_ms: u64,
+
+    /// Reloads slower than this many milliseconds are logged and counted (0 to disable)
+    #[arg(long, default_value_t = 5000, env = "SKILLS_SLOW_RELOAD_MS")]
+    slow_reload_ms: u64,
+
+    /// Maximum number of items in one get_skills_batch call
+    #[arg(long, default_value_t = BatchLimits::DEFAULT_MAX_ITEMS, env = "SKILLS_MAX_BATCH_ITEMS")]
+    max_batch_items: usize,
+
+    /// Number of get_skills_batch items loaded concurrently
+    #[arg(long, default_value_t = BatchLimits::DEFAULT_PARALLELISM, env = "SKILLS_BATCH_PARALLELISM")]
+    batch_parallelism: usize,
+
+    /// Maximum total content bytes in one get_skills_batch response
+    #[arg(long, default_value_t = BatchLimits::DEFAULT_MAX_RESPONSE_BYTES, env = "SKILLS_MAX_BATCH_BYTES")]
+    max_batch_bytes: usize,
+
+    /// Lowest confidence (0-1) a route_task route needs to be returned
+    #[arg(long, default_value_t = ConfidenceThresholds::DEFAULT_ROUTE, env = "SKILLS_MIN_ROUTE_CONFIDENCE")]
+    min_route_confidence: f64,
+
+    /// Lowest confidence (0-1) of the top search hit; weaker results are withheld
+    #[arg(long, default_value_t = ConfidenceThresholds::DEFAULT_SEARCH, env = "SKILLS_MIN_SEARCH_CONFIDENCE")]
+    min_search_confidence: f64,
+
+    /// Comma-separated tools to expose, e.g. list_skills,get_skill,search_*; all by default
+    #[arg(long, value_name = "TOOLS", env = "SKILLS_MCP_TOOLS")]
+    tools: Option<ToolAllowlist>,
+
+    /// Directory of per-session JSONL traces of skill loads and searches
+    #[arg(long, env = "SKILLS_JOURNAL_DIR")]
+    journal_dir: Option<PathBuf>,
+
+    /// Allow run_skill_script to execute bundled skill scripts
+    #[cfg(feature = "scripts")]
+    #[arg(long, env = "SKILLS_ENABLE_SCRIPTS")]
+    enable_scripts: bool,
+
+    /// Seconds before a running skill script is killed
+    #[cfg(feature = "scripts")]
+    #[arg(long, default_value_t = 30, env = "SKILLS_SCRIPT_TIMEOUT_SECS")]
+    script_timeout_secs: u64,
+
+    /// Creation Station database to record script runs in
+    #[cfg(feature = "scripts")]
+    #[arg(long, env = "SKILLS_RUNS_DB")]
+    runs_db: Option<PathBuf>,
+
+    /// Base URL of an OpenAI-compatible API for drafting tools
+    #[cfg(feature = "llm")]
+    #[arg(long, env = "SKILLS_LLM_URL")]
+    llm_url: Option<String>,
+
+    /// Model to request from the LLM API
+    #[cfg(feature = "llm")]
+    #[arg(long, default_value = "gpt-4o-mini", env = "SKILLS_LLM_MODEL")]
+    llm_model: String,
+
+    /// API key for the LLM API
+    #[cfg(feature = "llm")]
+    #[arg(long, env = "SKILLS_LLM_API_KEY", hide_env_values = true)]
+    llm_api_key: Option<String>,
+
+    /// Rerank the top search results with the LLM backend
+    #[cfg(feature = "llm")]
+    #[arg(long, env = "SKILLS_RERANK")]
+    rerank: bool,
+
+    /// Number of top search results reranked
+    #[cfg(feature = "llm")]
+    #[arg(long, default_value_t = RerankBudget::DEFAULT_TOP_K, env = "SKILLS_RERANK_TOP_K")]
+    rerank_top_k: usize,
+
+    /// Milliseconds a search waits for the reranker before keeping its own order
+    #[cfg(feature = "llm")]
+    #[arg(long, default_value_t = 1500, env = "SKILLS_RERANK_TIMEOUT_MS")]
+    rerank_timeout_ms: u64,
+
+    /// Most reranker calls running at once; searches past it keep their own order
+    #[cfg(feature = "llm")]
+    #[arg(long, default_value_t = RerankBudget::DEFAULT_MAX_WORKERS, env = "SKILLS_RERANK_WORKERS")]
+    rerank_workers: usize,
+
     /// Enable debug logging
     #[arg(short, long)]
     debug: bool,
@@ -29,7 +155,7 @@ struct Args {
 async fn main() -> anyhow::Result<()> {
     let args = Args::parse();
 
-    // Initialize tracing
+    // Initialize tracing; stdout carries the MCP protocol, so log to stderr
     let filter = if args.debug {
         "skills_mcp=debug,info"
     } else {
@@ -37,8 +163,14 @@ async fn main() -> anyhow::Result<()> {
     };
 
     tracing_subscriber::registry()
-        .with(tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| filter.into()))
-        .with(tracing_subscriber::fmt::layer().with_target(false))
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| filter.into()),
+        )
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_target(false)
+                .with_writer(std::io::stderr),
+        )
         .init();
 
     // Determine skills directory
@@ -61,8 +193,88 @@ async fn main() -> anyhow::Result<()> {
     info!("Skills directory: {:?}", skills_dir);
     info!("Starting Skills MCP Server v{}", skills_mcp::VERSION);
 
-    let server = McpServer::new(&skills_dir);
-    server.run().await?;
+    let options = IndexerOptions::default()
+        .follow_links(!args.no_follow_links)
+        .max_file_size(args.max_file_size)
+        .slow_reload(Duration::from_millis(args.slow_reload_ms));
+    let server = McpServer::with_options(&skills_dir, options)
+        .with_batch_limits(BatchLimits {
+            max_items: args.max_batch_items,
+            parallelism: args.batch_parallelism,
+            max_response_bytes: args.max_batch_bytes,
+        })
+        .with_confidence_thresholds(ConfidenceThresholds {
+            route: args.min_route_confidence,
+            search: args.min_search_confidence,
+        })
+        .with_slow_search(Duration::from_millis(args.slow_search_ms))
+        .with_http_options(HttpOptions {
+            session_idle_timeout: Duration::from_secs(args.session_idle_secs),
+            max_sessions: args.max_sessions,
+            allowed_origins: args.allowed_origins,
+        });
+    let server = if args.no_watch {
+        server.without_file_watcher()
+    } else {
+        server
+    };
+    let server = match args.tools {
+        Some(allowlist) => server.with_tool_allowlist(allowlist),
+        None => server,
+    };
+    let server = match &args.journal_dir {
+        Some(dir) => {
+            info!("Journaling sessions to {:?}", dir);
+            server.with_journal(Journal::open(dir)?)
+        }
+        None => server,
+    };
+    #[cfg(feature = "scripts")]
+    let server = if args.enable_scripts {
+        info!("Skill script execution enabled");
+        let mut sandbox = skills_mcp::mcp::ScriptSandbox::default()
+            .with_timeout(std::time::Duration::from_secs(args.script_timeout_secs));
+        if let Some(db) = &args.runs_db {
+            sandbox = sandbox.with_runs_db(db);
+        }
+        server.with_script_sandbox(sandbox)
+    } else {
+        server
+    };
+    #[cfg(feature = "llm")]
+    let server = match &args.llm_url {
+        Some(url) => {
+            info!("Using LLM backend {} at {}", args.llm_model, url);
+            let mut backend = skills_mcp::llm::OpenAiBackend::new(url, &args.llm_model);
+            if let Some(key) = &args.llm_api_key {
+                backend = backend.with_api_key(key);
+            }
+            let backend: std::sync::Arc<dyn skills_mcp::llm::LlmBackend> =
+                std::sync::Arc::new(backend);
+            let server = server.with_llm(std::sync::Arc::clone(&backend));
+            if args.rerank {
+                info!(
+                    "Reranking the top {} results within {} ms",
+                    args.rerank_top_k, args.rerank_timeout_ms
+                );
+                server.with_reranker(
+                    std::sync::Arc::new(skills_mcp::search::LlmReranker::new(backend)),
+                    RerankBudget {
+                        top_k: args.rerank_top_k,
+                        timeout: std::time::Duration::from_millis(args.rerank_timeout_ms),
+                        max_workers: args.rerank_workers,
+                    },
+                )
+            } else {
+                server
+            }
+        }
+        None => server,
+    };
+    match args.http {
+        Some(addr) => server.run_http(addr).await?,
+        None => server.run().await?,
+    }
 
     Ok(())
 }
@@ -0,0 +1,525 @@
+//! Command-line interface for working with a skills directory.
+//!
+//! Run with: cargo run --bin skills -- [OPTIONS] <COMMAND>
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+use skills_mcp::index::{IndexerOptions, SkillIndexer};
+use skills_mcp::maintenance::{apply_format, apply_split, plan_format, plan_split};
+use skills_mcp::models::{
+    MinimumShouldMatch, SearchOptions, SearchScope, SkillMeta, SkillStatus, SpellingMode, TagExpr,
+};
+use skills_mcp::search::SearchService;
+use skills_mcp::validation::LinkChecker;
+
+/// Skills CLI
+#[derive(Parser, Debug)]
+#[command(name = "skills")]
+#[command(about = "Inspect and search a skills directory")]
+#[command(version)]
+struct Cli {
+    /// Path to the skills directory
+    #[arg(short, long, global = true, env = "SKILLS_DIR")]
+    skills_dir: Option<PathBuf>,
+
+    /// Do not follow symlinked skills or reference files
+    #[arg(long, global = true, env = "SKILLS_NO_FOLLOW_LINKS")]
+    no_follow_links: bool,
+
+    /// Enable debug logging
+    #[arg(short, long, global = true)]
+    debug: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Search skills by metadata and content
+    Search(SearchArgs),
+    /// Write the index as a versioned snapshot for offline search
+    Index(IndexArgs),
+    /// Check external links in skill documents
+    CheckLinks(CheckLinksArgs),
+    /// Create a skill from a template
+    New(NewArgs),
+    /// List the templates skills can be created from
+    Templates,
+    /// Split a skill into sub-skills at its top-level headings
+    Split(SplitArgs),
+    /// Fix heading levels and sub-skill titles in skill documents
+    Fmt(FmtArgs),
+    /// Browse the index in the terminal
+    #[cfg(feature = "tui")]
+    Tui,
+    /// Print a shell completion script
+    Completions(CompletionsArgs),
+    /// Generate man pages
+    Man(ManArgs),
+}
+
+#[derive(clap::Args, Debug)]
+struct CompletionsArgs {
+    /// Shell to complete for
+    shell: Shell,
+}
+
+#[derive(clap::Args, Debug)]
+struct ManArgs {
+    /// Write `skills.1` and a page per subcommand here instead of printing
+    /// `skills.1`
+    #[arg(long)]
+    out_dir: Option<PathBuf>,
+}
+
+#[derive(clap::Args, Debug)]
+struct SplitArgs {
+    /// Skill to split
+    name: String,
+
+    /// Write the changes instead of only showing them
+    #[arg(long)]
+    apply: bool,
+
+    /// Split the skill even if it's pinned
+    #[arg(long)]
+    force: bool,
+
+    /// Print the plan as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(clap::Args, Debug)]
+struct FmtArgs {
+    /// Skill to format; every skill if left out
+    name: Option<String>,
+
+    /// Write the changes instead of only showing them
+    #[arg(long)]
+    apply: bool,
+
+    /// Format pinned skills too
+    #[arg(long)]
+    force: bool,
+
+    /// Print the plan as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(clap::Args, Debug)]
+struct NewArgs {
+    /// Skill name, optionally namespaced like `frontend/forms`
+    name: String,
+
+    /// Skill description
+    #[arg(long)]
+    description: String,
+
+    /// Built-in or `_templates/` template name
+    #[arg(long, default_value = "standard")]
+    template: String,
+
+    /// Tag for the skill; repeat for more. Defaults to the template's tags
+    #[arg(long = "tag")]
+    tags: Vec<String>,
+
+    /// Template placeholder value as `key=value`; repeat for more
+    #[arg(long = "var", value_parser = parse_variable)]
+    variables: Vec<(String, String)>,
+
+    /// Write a single `name.md` file instead of a directory
+    #[arg(long)]
+    flat: bool,
+}
+
+fn parse_variable(arg: &str) -> Result<(String, String), String> {
+    arg.split_once('=')
+        .map(|(key, value)| (key.trim().to_string(), value.to_string()))
+        .ok_or_else(|| format!("expected key=value, got '{}'", arg))
+}
+
+#[derive(clap::Args, Debug)]
+struct IndexArgs {
+    /// File to write the snapshot to
+    #[arg(short, long)]
+    output: PathBuf,
+
+    /// Write plain JSON instead of the binary encoding
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(clap::Args, Debug)]
+struct CheckLinksArgs {
+    /// Seconds to wait for each link
+    #[arg(long, default_value = "10")]
+    timeout_secs: u64,
+
+    /// Print the report as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(clap::Args, Debug)]
+struct SearchArgs {
+    /// Search query
+    query: String,
+
+    /// Which index to search
+    #[arg(long, value_enum, default_value = "all")]
+    scope: Scope,
+
+    /// Maximum number of results
+    #[arg(short, long, default_value = "10")]
+    limit: usize,
+
+    /// Terms that must match: `all`, a count, or a percentage like `75%`
+    #[arg(long)]
+    min_should_match: Option<MinimumShouldMatch>,
+
+    /// Tag expression, e.g. "react AND (forms OR validation) NOT deprecated"
+    #[arg(long)]
+    tags: Option<TagExpr>,
+
+    /// Only skills with this source
+    #[arg(long)]
+    source: Option<String>,
+
+    /// Only skills in this language
+    #[arg(long)]
+    language: Option<String>,
+
+    /// Only skills with this status (draft, published, deprecated)
+    #[arg(long)]
+    status: Option<SkillStatus>,
+
+    /// Only skills owned by this person or team
+    #[arg(long)]
+    owner: Option<String>,
+
+    /// Only skills in this category folder (e.g. frontend) or below it
+    #[arg(long)]
+    category: Option<String>,
+
+    /// Spelling correction when nothing matches (suggest, auto)
+    #[arg(long)]
+    spelling: Option<SpellingMode>,
+
+    /// Print results as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Scope {
+    Skills,
+    Content,
+    All,
+}
+
+impl From<Scope> for SearchScope {
+    fn from(scope: Scope) -> Self {
+        match scope {
+            Scope::Skills => SearchScope::Skills,
+            Scope::Content => SearchScope::Content,
+            Scope::All => SearchScope::All,
+        }
+    }
+}
+
+fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+
+    // Logs go to stderr so command output stays pipeable
+    let filter = if cli.debug {
+        "skills_mcp=debug,info"
+    } else {
+        "warn"
+    };
+
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| filter.into()),
+        )
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_target(false)
+                .with_writer(std::io::stderr),
+        )
+        .init();
+
+    // These only describe the CLI, so they don't need a skills directory
+    match &cli.command {
+        Command::Completions(args) => return completions(args),
+        Command::Man(args) => return man(args),
+        _ => {}
+    }
+
+    // Determine skills directory
+    let skills_dir = cli.skills_dir.clone().unwrap_or_else(|| {
+        // Try common locations
+        let candidates = [
+            PathBuf::from("./skills"),
+            PathBuf::from("../skills"),
+            dirs::home_dir()
+                .map(|h| h.join(".skills"))
+                .unwrap_or_default(),
+        ];
+
+        candidates
+            .into_iter()
+            .find(|p| p.exists())
+            .unwrap_or_else(|| PathBuf::from("./skills"))
+    });
+
+    let options = IndexerOptions::default().follow_links(!cli.no_follow_links);
+    let indexer = Arc::new(SkillIndexer::with_options(&skills_dir, options));
+    indexer.reload()?;
+
+    match cli.command {
+        Command::Search(args) => search(indexer, args),
+        Command::Index(args) => write_index(&indexer, args),
+        Command::CheckLinks(args) => check_links(indexer, args),
+        Command::New(args) => new_skill(&indexer, args),
+        Command::Templates => templates(&indexer),
+        Command::Split(args) => split(&indexer, args),
+        Command::Fmt(args) => format_skills(&indexer, args),
+        #[cfg(feature = "tui")]
+        Command::Tui => Ok(skills_mcp::tui::run(indexer)?),
+        Command::Completions(_) | Command::Man(_) => unreachable!("handled before indexing"),
+    }
+}
+
+fn completions(args: &CompletionsArgs) -> anyhow::Result<()> {
+    let mut command = Cli::command();
+    clap_complete::generate(args.shell, &mut command, "skills", &mut std::io::stdout());
+    Ok(())
+}
+
+fn man(args: &ManArgs) -> anyhow::Result<()> {
+    let command = Cli::command();
+
+    let Some(out_dir) = &args.out_dir else {
+        clap_mangen::Man::new(command).render(&mut std::io::stdout())?;
+        return Ok(());
+    };
+
+    std::fs::create_dir_all(out_dir)?;
+    clap_mangen::generate_to(command, out_dir)?;
+    eprintln!("Wrote man pages to {}", out_dir.display());
+    Ok(())
+}
+
+fn split(indexer: &SkillIndexer, args: SplitArgs) -> anyhow::Result<()> {
+    let plan = plan_split(indexer, &args.name, args.force)?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&plan)?);
+    } else {
+        for change in &plan.diffs {
+            println!("--- {}", change.file);
+            print!("{}", change.diff);
+        }
+    }
+
+    if args.apply {
+        let version = apply_split(indexer, &plan)?;
+        eprintln!(
+            "Split '{}' into {} sub-skills; originals saved in version {}",
+            plan.skill,
+            plan.sub_skills.len(),
+            version
+        );
+    } else {
+        eprintln!("Dry run; pass --apply to write these changes");
+    }
+
+    Ok(())
+}
+
+fn format_skills(indexer: &SkillIndexer, args: FmtArgs) -> anyhow::Result<()> {
+    let plan = plan_format(indexer, args.name.as_deref(), args.force)?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&plan)?);
+    } else {
+        for change in &plan.changes {
+            println!("--- {}/{}", change.skill, change.file);
+            print!("{}", change.diff);
+        }
+    }
+    for skill in &plan.pinned {
+        eprintln!(
+            "Skipped pinned skill '{}'; pass --force to format it",
+            skill
+        );
+    }
+
+    if plan.changes.is_empty() {
+        eprintln!("{} files already formatted", plan.files_scanned);
+    } else if args.apply {
+        let version = apply_format(indexer, &plan)?;
+        eprintln!(
+            "Fixed {} heading problems in {} files; originals saved in version {}",
+            plan.fixes,
+            plan.changes.len(),
+            version
+        );
+    } else {
+        eprintln!("Dry run; pass --apply to write these changes");
+    }
+
+    Ok(())
+}
+
+fn new_skill(indexer: &SkillIndexer, args: NewArgs) -> anyhow::Result<()> {
+    let template = indexer.load_template(&args.template)?;
+    let variables: HashMap<String, String> = args.variables.into_iter().collect();
+    let skill = template
+        .render(&args.name, &args.description, &args.tags, &variables)
+        .map_err(anyhow::Error::msg)?;
+
+    let meta = SkillMeta {
+        name: args.name,
+        description: args.description,
+        tags: skill.tags.clone(),
+        ..Default::default()
+    };
+    for file in indexer.create_skill(&meta, &skill, args.flat)? {
+        println!("Created {}", file);
+    }
+
+    Ok(())
+}
+
+fn templates(indexer: &SkillIndexer) -> anyhow::Result<()> {
+    for template in indexer.list_templates() {
+        let info = template.info();
+        let source = if info.builtin { "built-in" } else { "custom" };
+        println!("{} ({}): {}", info.name, source, info.description);
+        if !info.placeholders.is_empty() {
+            println!("  needs: {}", info.placeholders.join(", "));
+        }
+    }
+
+    Ok(())
+}
+
+fn write_index(indexer: &SkillIndexer, args: IndexArgs) -> anyhow::Result<()> {
+    let snapshot = indexer.snapshot();
+    let bytes = if args.json {
+        serde_json::to_vec(&snapshot)?
+    } else {
+        snapshot.to_bytes()
+    };
+    std::fs::write(&args.output, &bytes)?;
+
+    eprintln!(
+        "Wrote {} skills and {} content entries ({} bytes) to {}",
+        snapshot.skill_index.len(),
+        snapshot.content_index.len(),
+        bytes.len(),
+        args.output.display()
+    );
+    Ok(())
+}
+
+fn check_links(indexer: Arc<SkillIndexer>, args: CheckLinksArgs) -> anyhow::Result<()> {
+    let skills_checked = indexer.get_skill_index().len();
+    let checker = Arc::new(LinkChecker::with_timeout(Duration::from_secs(
+        args.timeout_secs,
+    )));
+
+    let runtime = tokio::runtime::Runtime::new()?;
+    let report = runtime.block_on(checker.check_skills(indexer));
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        for warning in report.validation_result(skills_checked).warnings {
+            println!("{}", warning);
+        }
+        println!(
+            "Checked {} links, {} broken",
+            report.links_checked,
+            report.broken.len()
+        );
+    }
+
+    if !report.broken.is_empty() {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+fn search(indexer: Arc<SkillIndexer>, args: SearchArgs) -> anyhow::Result<()> {
+    let service = SearchService::new(indexer);
+
+    let options = SearchOptions {
+        limit: Some(args.limit),
+        min_should_match: args.min_should_match,
+        source: args.source,
+        language: args.language,
+        status: args.status,
+        owner: args.owner,
+        tags: args.tags,
+        category: args.category,
+        spelling: args.spelling,
+        ..Default::default()
+    };
+
+    let results = service.search(args.scope.into(), &args.query, options);
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&results)?);
+        return Ok(());
+    }
+
+    if results.is_empty() {
+        println!("No results for '{}'", results.query);
+        if !results.suggestions.is_empty() {
+            println!("Did you mean: {}", results.suggestions.join(", "));
+        }
+        return Ok(());
+    }
+
+    if let Some(corrected) = &results.corrected_query {
+        println!(
+            "Showing results for '{}' (no results for '{}')",
+            corrected, results.query
+        );
+    }
+
+    for result in &results.results {
+        let file = result
+            .file
+            .as_deref()
+            .map(|f| format!(" ({})", f))
+            .unwrap_or_default();
+        println!("{:>6.2}  {}{}", result.score, result.display_id(), file);
+        if let Some(snippet) = &result.snippet {
+            println!("        {}", snippet.replace('\n', " "));
+        }
+    }
+
+    if results.truncated {
+        println!(
+            "Showing {} of {} results",
+            results.len(),
+            results.total_matches
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,328 @@
+//! Fetching repositories from GitHub as tarballs, without a git binary.
+
+use std::time::Duration;
+
+use super::{extract_skills, ImportError, ImportedSkill, MAX_IMPORT_SIZE};
+
+/// GitHub's REST API.
+pub const DEFAULT_GITHUB_API: &str = "https://api.github.com";
+
+/// Time limit per request, including the tarball download.
+const FETCH_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A repository on GitHub.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitHubRepo {
+    /// User or organization owning the repository.
+    pub owner: String,
+    /// Repository name.
+    pub repo: String,
+}
+
+impl GitHubRepo {
+    /// Parse a repository URL such as `https://github.com/acme/skills`,
+    /// with or without a trailing `.git`.
+    pub fn parse(url: &str) -> Result<Self, ImportError> {
+        let invalid = || {
+            ImportError::Invalid(format!(
+                "Expected a repository URL like https://github.com/owner/repo, got '{}'",
+                url
+            ))
+        };
+
+        let rest = url
+            .trim()
+            .strip_prefix("https://github.com/")
+            .or_else(|| url.trim().strip_prefix("https://www.github.com/"))
+            .ok_or_else(invalid)?;
+        let mut parts = rest.trim_end_matches('/').split('/');
+        let (Some(owner), Some(repo), None) = (parts.next(), parts.next(), parts.next()) else {
+            return Err(invalid());
+        };
+        let repo = repo.strip_suffix(".git").unwrap_or(repo);
+
+        let valid = |part: &str| {
+            !part.is_empty()
+                && part != "."
+                && part != ".."
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        };
+        if !valid(owner) || !valid(repo) {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+        })
+    }
+
+    /// The repository's web URL.
+    pub fn url(&self) -> String {
+        format!("https://github.com/{}/{}", self.owner, self.repo)
+    }
+}
+
+/// Skills fetched from a repository at one commit.
+#[derive(Debug, Clone)]
+pub struct FetchedSkills {
+    /// Commit the ref resolved to.
+    pub commit: String,
+    /// Skills found under the requested path.
+    pub skills: Vec<ImportedSkill>,
+}
+
+/// Client for the GitHub API.
+#[derive(Clone)]
+pub struct GitHubImporter {
+    client: reqwest::Client,
+    api_base: String,
+    token: Option<String>,
+}
+
+impl GitHubImporter {
+    /// Create an importer for github.com.
+    pub fn new() -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(FETCH_TIMEOUT)
+            .user_agent(concat!("skills-mcp/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .unwrap_or_default();
+        Self {
+            client,
+            api_base: DEFAULT_GITHUB_API.to_string(),
+            token: None,
+        }
+    }
+
+    /// Use another API endpoint, e.g. GitHub Enterprise's `/api/v3`.
+    pub fn with_api_base(mut self, api_base: impl Into<String>) -> Self {
+        self.api_base = api_base.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Authenticate with a token, for private repositories and higher
+    /// rate limits.
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Resolve a branch, tag or commit to a full commit hash.
+    pub async fn resolve(&self, repo: &GitHubRepo, git_ref: &str) -> Result<String, ImportError> {
+        let valid = !git_ref.is_empty()
+            && !git_ref.contains("..")
+            && git_ref
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/'));
+        if !valid {
+            return Err(ImportError::Invalid(format!("Invalid ref '{}'", git_ref)));
+        }
+
+        let url = format!(
+            "{}/repos/{}/{}/commits/{}",
+            self.api_base, repo.owner, repo.repo, git_ref
+        );
+        let response = self
+            .get(&url)
+            .header(reqwest::header::ACCEPT, "application/vnd.github.sha")
+            .send()
+            .await
+            .map_err(|e| ImportError::Fetch(e.to_string()))?;
+        let response = check_status(response, || format!("{} at '{}'", repo.url(), git_ref))?;
+        let commit = response
+            .text()
+            .await
+            .map_err(|e| ImportError::Fetch(e.to_string()))?;
+
+        let commit = commit.trim();
+        if commit.len() != 40 || !commit.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(ImportError::Fetch(format!(
+                "Unexpected commit hash for '{}': {}",
+                git_ref, commit
+            )));
+        }
+        Ok(commit.to_string())
+    }
+
+    /// Download the repository's gzipped tarball at `commit`.
+    pub async fn download(&self, repo: &GitHubRepo, commit: &str) -> Result<Vec<u8>, ImportError> {
+        let url = format!(
+            "{}/repos/{}/{}/tarball/{}",
+            self.api_base, repo.owner, repo.repo, commit
+        );
+        let response = self
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| ImportError::Fetch(e.to_string()))?;
+        let mut response = check_status(response, || format!("{} at {}", repo.url(), commit))?;
+
+        let too_large = || {
+            ImportError::Fetch(format!(
+                "Tarball of {} is larger than {} bytes",
+                repo.url(),
+                MAX_IMPORT_SIZE
+            ))
+        };
+        if response
+            .content_length()
+            .is_some_and(|len| len > MAX_IMPORT_SIZE)
+        {
+            return Err(too_large());
+        }
+
+        let mut tarball = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| ImportError::Fetch(e.to_string()))?
+        {
+            if (tarball.len() + chunk.len()) as u64 > MAX_IMPORT_SIZE {
+                return Err(too_large());
+            }
+            tarball.extend_from_slice(&chunk);
+        }
+        Ok(tarball)
+    }
+
+    /// Resolve `git_ref`, download the repository at that commit and
+    /// unpack the skills under `path`.
+    pub async fn fetch(
+        &self,
+        repo: &GitHubRepo,
+        git_ref: &str,
+        path: &str,
+    ) -> Result<FetchedSkills, ImportError> {
+        let commit = self.resolve(repo, git_ref).await?;
+        let tarball = self.download(repo, &commit).await?;
+        let path = path.to_string();
+        let skills = tokio::task::spawn_blocking(move || extract_skills(&tarball, &path))
+            .await
+            .map_err(|e| ImportError::Archive(e.to_string()))??;
+
+        Ok(FetchedSkills { commit, skills })
+    }
+
+    fn get(&self, url: &str) -> reqwest::RequestBuilder {
+        let request = self.client.get(url);
+        match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+}
+
+impl Default for GitHubImporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for GitHubImporter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GitHubImporter")
+            .field("api_base", &self.api_base)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Turn error statuses into import errors; GitHub answers 404 for
+/// repositories that are private as well as missing, and 422 for unknown
+/// refs.
+fn check_status(
+    response: reqwest::Response,
+    what: impl Fn() -> String,
+) -> Result<reqwest::Response, ImportError> {
+    match response.status() {
+        status if status.is_success() => Ok(response),
+        reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::UNPROCESSABLE_ENTITY => {
+            Err(ImportError::NotFound(what()))
+        }
+        status => Err(ImportError::Fetch(format!("{}: HTTP {}", what(), status))),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::import::tests::tarball;
+    use axum::{extract::Path, http::StatusCode, routing::get, Router};
+
+    pub(crate) const COMMIT: &str = "0123456789abcdef0123456789abcdef01234567";
+
+    /// Serve `acme/skills` at [`COMMIT`], also ref `main`, returning the API base.
+    pub(crate) async fn fake_github(files: &'static [(&'static str, &'static str)]) -> String {
+        let app = Router::new()
+            .route(
+                "/repos/acme/skills/commits/*git_ref",
+                get(|Path(git_ref): Path<String>| async move {
+                    match git_ref.as_str() {
+                        "main" | COMMIT => Ok(COMMIT),
+                        _ => Err(StatusCode::UNPROCESSABLE_ENTITY),
+                    }
+                }),
+            )
+            .route(
+                "/repos/acme/skills/tarball/:commit",
+                get(move |Path(commit): Path<String>| async move {
+                    match commit.as_str() {
+                        COMMIT => Ok(tarball(files)),
+                        _ => Err(StatusCode::NOT_FOUND),
+                    }
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    #[test]
+    fn test_parse_repo() {
+        let repo = GitHubRepo::parse("https://github.com/acme/skills.git/").unwrap();
+        assert_eq!(repo.owner, "acme");
+        assert_eq!(repo.repo, "skills");
+        assert_eq!(repo.url(), "https://github.com/acme/skills");
+
+        for url in [
+            "http://github.com/acme/skills",
+            "https://gitlab.com/acme/skills",
+            "https://github.com/acme",
+            "https://github.com/acme/skills/tree/main",
+            "https://github.com/../skills",
+        ] {
+            assert!(GitHubRepo::parse(url).is_err(), "{}", url);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch() {
+        let api = fake_github(&[
+            (
+                "skills/forms/_meta.json",
+                r#"{"name": "forms", "description": "Forms"}"#,
+            ),
+            ("skills/forms/SKILL.md", "# Forms"),
+        ])
+        .await;
+        let importer = GitHubImporter::new().with_api_base(api);
+        let repo = GitHubRepo::parse("https://github.com/acme/skills").unwrap();
+
+        let fetched = importer.fetch(&repo, "main", "skills").await.unwrap();
+        assert_eq!(fetched.commit, COMMIT);
+        assert_eq!(fetched.skills.len(), 1);
+        assert_eq!(fetched.skills[0].meta.name, "forms");
+
+        assert!(matches!(
+            importer.fetch(&repo, "missing", "").await,
+            Err(ImportError::NotFound(_))
+        ));
+        assert!(matches!(
+            importer.fetch(&repo, "main; rm", "").await,
+            Err(ImportError::Invalid(_))
+        ));
+    }
+}
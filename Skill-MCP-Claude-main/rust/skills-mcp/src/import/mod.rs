@@ -0,0 +1,404 @@
+//! Importing skills from outside the skills directory.
+//!
+//! Skills are fetched as an archive, unpacked in memory, and installed
+//! only once every skill in it has validated. Each installed skill records
+//! where it came from in its metadata's `origin`, which is checked
+//! against upstream to find and apply updates.
+
+pub(crate) mod github;
+mod update;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Read;
+
+use crate::index::{IndexError, SkillIndexer};
+use crate::models::{SkillMeta, SkillOrigin};
+use crate::validation::{normalize_separators, validate_meta};
+
+pub use github::{FetchedSkills, GitHubImporter, GitHubRepo, DEFAULT_GITHUB_API};
+pub use update::{
+    apply_update, schedule_update_checks, SkillUpdate, UpdateChecker, UpdateSchedule,
+};
+
+/// Largest archive, unpacked, that an import will read.
+pub const MAX_IMPORT_SIZE: u64 = 100 * 1024 * 1024;
+
+/// Most files an import will read from an archive.
+pub const MAX_IMPORT_FILES: usize = 10_000;
+
+/// Errors from fetching and unpacking skills to import.
+#[derive(Debug, thiserror::Error)]
+pub enum ImportError {
+    /// The request cannot be carried out as given.
+    #[error("Invalid request: {0}")]
+    Invalid(String),
+
+    /// The repository or ref doesn't exist, or isn't visible.
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    /// Fetching from the remote failed.
+    #[error("Fetch failed: {0}")]
+    Fetch(String),
+
+    /// The archive couldn't be read, or held no skills.
+    #[error("Invalid archive: {0}")]
+    Archive(String),
+}
+
+/// A skill unpacked from an archive, ready to install.
+#[derive(Debug, Clone)]
+pub struct ImportedSkill {
+    /// Metadata from the skill's `_meta.json`.
+    pub meta: SkillMeta,
+    /// Directory of the skill within the archive.
+    pub dir: String,
+    /// The skill's other files, relative to its directory.
+    pub files: Vec<(String, Vec<u8>)>,
+}
+
+/// Unpack the skills under `path` in a gzipped tarball.
+///
+/// The archive's single top-level directory, as in GitHub tarballs, is
+/// stripped first. Every directory with a `_meta.json` is a skill, and
+/// takes all the files beneath it; only regular files are read.
+pub fn extract_skills(tarball: &[u8], path: &str) -> Result<Vec<ImportedSkill>, ImportError> {
+    let prefix = normalize_separators(path).trim_matches('/').to_string();
+    if prefix.split('/').any(|part| part == "..") {
+        return Err(ImportError::Invalid(format!(
+            "Path contains '..': {}",
+            path
+        )));
+    }
+
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(tarball));
+    let entries = archive
+        .entries()
+        .map_err(|e| ImportError::Archive(e.to_string()))?;
+
+    let mut files: BTreeMap<String, Vec<u8>> = BTreeMap::new();
+    let mut total = 0;
+    for entry in entries {
+        let mut entry = entry.map_err(|e| ImportError::Archive(e.to_string()))?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+
+        let entry_path = entry
+            .path()
+            .map_err(|e| ImportError::Archive(e.to_string()))?
+            .to_string_lossy()
+            .into_owned();
+        let entry_path = normalize_separators(&entry_path);
+        let Some((_, inner)) = entry_path.split_once('/') else {
+            continue;
+        };
+        let relative = match prefix.as_str() {
+            "" => inner.to_string(),
+            prefix => match inner.strip_prefix(prefix).and_then(|r| r.strip_prefix('/')) {
+                Some(relative) => relative.to_string(),
+                None => continue,
+            },
+        };
+        let unsafe_path = relative
+            .split('/')
+            .any(|part| part.is_empty() || part == "." || part == "..");
+        if unsafe_path {
+            return Err(ImportError::Archive(format!("Unsafe path: {}", entry_path)));
+        }
+
+        if files.len() >= MAX_IMPORT_FILES {
+            return Err(ImportError::Archive(format!(
+                "More than {} files under '{}'",
+                MAX_IMPORT_FILES, prefix
+            )));
+        }
+        let mut content = Vec::new();
+        (&mut entry)
+            .take(MAX_IMPORT_SIZE - total + 1)
+            .read_to_end(&mut content)
+            .map_err(|e| ImportError::Archive(e.to_string()))?;
+        total += content.len() as u64;
+        if total > MAX_IMPORT_SIZE {
+            return Err(ImportError::Archive(format!(
+                "Skills under '{}' unpack to more than {} bytes",
+                prefix, MAX_IMPORT_SIZE
+            )));
+        }
+        files.insert(relative, content);
+    }
+
+    // Skill directories, outermost first; a skill nested in another is
+    // part of it
+    let mut candidates: Vec<&str> = files
+        .keys()
+        .filter_map(|file| file.strip_suffix("_meta.json"))
+        .filter(|dir| dir.is_empty() || dir.ends_with('/'))
+        .collect();
+    candidates.sort_by_key(|dir| (dir.len(), *dir));
+    let mut dirs: Vec<String> = Vec::new();
+    for dir in candidates {
+        if !dirs.iter().any(|outer| dir.starts_with(outer.as_str())) {
+            dirs.push(dir.to_string());
+        }
+    }
+    dirs.sort();
+    if dirs.is_empty() {
+        return Err(ImportError::Archive(format!(
+            "No skills found under '{}'",
+            if prefix.is_empty() { "/" } else { &prefix }
+        )));
+    }
+
+    let mut skills = Vec::new();
+    for dir in dirs {
+        let meta_file = format!("{}_meta.json", dir);
+        let mut meta: SkillMeta = serde_json::from_slice(&files[&meta_file])
+            .map_err(|e| ImportError::Archive(format!("{}/{}: {}", prefix, meta_file, e)))?;
+        for sub in meta.sub_skills.iter_mut().flatten() {
+            sub.file = normalize_separators(&sub.file);
+        }
+
+        let skill_files = files
+            .iter()
+            .filter(|(file, _)| **file != meta_file)
+            .filter_map(|(file, content)| {
+                let relative = file.strip_prefix(dir.as_str())?;
+                Some((relative.to_string(), content.clone()))
+            })
+            .collect();
+        let dir = [prefix.as_str(), dir.trim_end_matches('/')]
+            .iter()
+            .filter(|part| !part.is_empty())
+            .copied()
+            .collect::<Vec<_>>()
+            .join("/");
+        skills.push(ImportedSkill {
+            meta,
+            dir,
+            files: skill_files,
+        });
+    }
+
+    Ok(skills)
+}
+
+/// Validate and install imported skills, recording `origin` in each with
+/// its own directory as the path. Nothing is installed unless every skill
+/// is valid and none already exists.
+///
+/// Returns the names of the installed skills.
+pub fn install_skills(
+    indexer: &SkillIndexer,
+    skills: Vec<ImportedSkill>,
+    origin: &SkillOrigin,
+) -> Result<Vec<String>, IndexError> {
+    check_skills(indexer, &skills)?;
+
+    let mut installed = Vec::new();
+    for mut skill in skills {
+        skill.meta.origin = Some(skill_origin(&skill, origin));
+        indexer.install_skill(&skill.meta, &skill.files)?;
+        installed.push(skill.meta.name);
+    }
+    Ok(installed)
+}
+
+/// Make every check [`install_skills`] does without installing anything.
+///
+/// Returns the files that would be created, relative to the skills
+/// directory.
+pub fn check_install_skills(
+    indexer: &SkillIndexer,
+    skills: &[ImportedSkill],
+    origin: &SkillOrigin,
+) -> Result<Vec<String>, IndexError> {
+    check_skills(indexer, skills)?;
+
+    let mut files = Vec::new();
+    for skill in skills {
+        let meta = SkillMeta {
+            origin: Some(skill_origin(skill, origin)),
+            ..skill.meta.clone()
+        };
+        files.extend(indexer.check_install(&meta, &skill.files)?);
+    }
+    Ok(files)
+}
+
+/// `origin` with the path of the skill's own directory.
+fn skill_origin(skill: &ImportedSkill, origin: &SkillOrigin) -> SkillOrigin {
+    SkillOrigin {
+        path: skill.dir.clone(),
+        ..origin.clone()
+    }
+}
+
+/// Check every skill in an import is valid, named once, and new.
+fn check_skills(indexer: &SkillIndexer, skills: &[ImportedSkill]) -> Result<(), IndexError> {
+    let mut names = BTreeSet::new();
+    for skill in skills {
+        let name = &skill.meta.name;
+        if let Err(errors) = validate_meta(&skill.meta) {
+            return Err(IndexError::ValidationError(format!(
+                "{}: {}",
+                skill.dir,
+                errors.join("; ")
+            )));
+        }
+        if !names.insert(name.clone()) {
+            return Err(IndexError::ValidationError(format!(
+                "Skill '{}' is in the import more than once",
+                name
+            )));
+        }
+        if indexer.skill_exists(name) {
+            return Err(IndexError::AlreadyExists(format!(
+                "Skill '{}' already exists",
+                name
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use chrono::Utc;
+    use std::fs;
+    use tempfile::TempDir;
+
+    /// A gzipped tarball with `files` under a GitHub-style top directory.
+    pub(crate) fn tarball(files: &[(&str, &str)]) -> Vec<u8> {
+        let encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        let mut builder = tar::Builder::new(encoder);
+        for (path, content) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            builder
+                .append_data(
+                    &mut header,
+                    format!("acme-skills-abc1234/{}", path),
+                    content.as_bytes(),
+                )
+                .unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    fn origin() -> SkillOrigin {
+        SkillOrigin {
+            url: "https://github.com/acme/skills".to_string(),
+            git_ref: "main".to_string(),
+            path: String::new(),
+            commit: "abc1234".to_string(),
+            imported_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_extract_skills() {
+        let archive = tarball(&[
+            ("README.md", "# Skills"),
+            (
+                "skills/forms/_meta.json",
+                r#"{"name": "forms", "description": "Forms"}"#,
+            ),
+            ("skills/forms/SKILL.md", "# Forms"),
+            (
+                "skills/forms/examples/_meta.json",
+                r#"{"name": "x", "description": "X"}"#,
+            ),
+            (
+                "skills/charts/_meta.json",
+                r#"{"name": "charts", "description": "Charts"}"#,
+            ),
+            ("skills/charts/SKILL.md", "# Charts"),
+            (
+                "other/_meta.json",
+                r#"{"name": "other", "description": "Other"}"#,
+            ),
+        ]);
+
+        let skills = extract_skills(&archive, "/skills/").unwrap();
+        let names: Vec<&str> = skills.iter().map(|s| s.meta.name.as_str()).collect();
+        assert_eq!(names, vec!["charts", "forms"]);
+        assert_eq!(skills[1].dir, "skills/forms");
+        let files: Vec<&str> = skills[1].files.iter().map(|(f, _)| f.as_str()).collect();
+        assert_eq!(files, vec!["SKILL.md", "examples/_meta.json"]);
+
+        let root = extract_skills(&archive, "skills/forms").unwrap();
+        assert_eq!(root.len(), 1);
+        assert_eq!(root[0].dir, "skills/forms");
+
+        assert!(matches!(
+            extract_skills(&archive, "docs"),
+            Err(ImportError::Archive(_))
+        ));
+        assert!(matches!(
+            extract_skills(&archive, "../etc"),
+            Err(ImportError::Invalid(_))
+        ));
+        assert!(extract_skills(b"not a tarball", "").is_err());
+    }
+
+    #[test]
+    fn test_install_skills() {
+        let temp = TempDir::new().unwrap();
+        let indexer = SkillIndexer::new(temp.path());
+        indexer.reload().unwrap();
+        let archive = tarball(&[
+            (
+                "forms/_meta.json",
+                r#"{"name": "forms", "description": "Forms"}"#,
+            ),
+            ("forms/SKILL.md", "# Forms"),
+            ("forms/references/guide.md", "# Guide"),
+        ]);
+
+        let skills = extract_skills(&archive, "").unwrap();
+        let planned = check_install_skills(&indexer, &skills, &origin()).unwrap();
+        assert_eq!(
+            planned,
+            vec![
+                "forms/_meta.json",
+                "forms/SKILL.md",
+                "forms/references/guide.md"
+            ]
+        );
+        assert!(!temp.path().join("forms").exists());
+
+        let installed = install_skills(&indexer, skills.clone(), &origin()).unwrap();
+        assert_eq!(installed, vec!["forms"]);
+        let meta = indexer.get_skill_meta("forms").unwrap();
+        let recorded = meta.origin.as_ref().unwrap();
+        assert_eq!(recorded.path, "forms");
+        assert_eq!(recorded.commit, "abc1234");
+        assert_eq!(
+            fs::read_to_string(temp.path().join("forms/references/guide.md")).unwrap(),
+            "# Guide"
+        );
+
+        assert!(matches!(
+            install_skills(&indexer, skills, &origin()),
+            Err(IndexError::AlreadyExists(_))
+        ));
+
+        // One invalid skill stops the whole import
+        let archive = tarball(&[
+            (
+                "charts/_meta.json",
+                r#"{"name": "charts", "description": "Charts"}"#,
+            ),
+            ("Bad/_meta.json", r#"{"name": "Bad", "description": "Bad"}"#),
+        ]);
+        let skills = extract_skills(&archive, "").unwrap();
+        assert!(matches!(
+            install_skills(&indexer, skills, &origin()),
+            Err(IndexError::ValidationError(_))
+        ));
+        assert!(!temp.path().join("charts").exists());
+    }
+}
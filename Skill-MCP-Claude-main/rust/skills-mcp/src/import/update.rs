@@ -0,0 +1,313 @@
+//! Checking imported skills against their upstream ref, and updating them.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use parking_lot::RwLock;
+use serde::Serialize;
+use tracing::warn;
+
+use crate::index::{IndexError, SkillIndexer};
+use crate::maintenance::VERSIONS_DIR;
+use crate::models::SkillMeta;
+
+use super::{GitHubImporter, GitHubRepo, ImportedSkill};
+
+/// Upstream state of one imported skill.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SkillUpdate {
+    /// Skill name.
+    pub name: String,
+    /// Repository it was imported from.
+    pub url: String,
+    /// Ref it follows.
+    #[serde(rename = "ref")]
+    pub git_ref: String,
+    /// Commit it was imported at.
+    pub current: String,
+    /// Commit the ref points to now.
+    pub latest: String,
+    /// Whether `latest` differs from `current`.
+    pub update_available: bool,
+}
+
+/// Latest known upstream commits of imported skills.
+#[derive(Debug, Default)]
+pub struct UpdateChecker {
+    latest: RwLock<HashMap<String, String>>,
+    checked_at: RwLock<Option<DateTime<Utc>>>,
+}
+
+impl UpdateChecker {
+    /// Create a checker that knows of no upstream commits yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolve the ref of every imported skill, remembering the commits.
+    ///
+    /// Skills sharing a repository and ref are resolved once. Repositories
+    /// that can't be reached are logged and skipped, keeping what was known.
+    pub async fn check(
+        &self,
+        importer: &GitHubImporter,
+        indexer: &SkillIndexer,
+    ) -> Vec<SkillUpdate> {
+        let mut by_ref: BTreeMap<(String, String), Vec<SkillMeta>> = BTreeMap::new();
+        for meta in indexer.get_skill_index().skills {
+            if let Some(origin) = &meta.origin {
+                let key = (origin.url.clone(), origin.git_ref.clone());
+                by_ref.entry(key).or_default().push(meta);
+            }
+        }
+
+        let mut updates = Vec::new();
+        for ((url, git_ref), skills) in by_ref {
+            let resolved = match GitHubRepo::parse(&url) {
+                Ok(repo) => importer.resolve(&repo, &git_ref).await,
+                Err(e) => Err(e),
+            };
+            let latest = match resolved {
+                Ok(latest) => latest,
+                Err(e) => {
+                    warn!("Update check of {} at '{}' failed: {}", url, git_ref, e);
+                    continue;
+                }
+            };
+
+            let mut known = self.latest.write();
+            for meta in skills {
+                let Some(origin) = meta.origin else {
+                    continue;
+                };
+                known.insert(meta.name.clone(), latest.clone());
+                updates.push(SkillUpdate {
+                    name: meta.name,
+                    url: url.clone(),
+                    git_ref: git_ref.clone(),
+                    update_available: origin.commit != latest,
+                    current: origin.commit,
+                    latest: latest.clone(),
+                });
+            }
+        }
+
+        *self.checked_at.write() = Some(Utc::now());
+        updates
+    }
+
+    /// Latest upstream commit last seen for a skill.
+    pub fn latest(&self, name: &str) -> Option<String> {
+        self.latest.read().get(name).cloned()
+    }
+
+    /// Whether the last check found a newer commit than the skill's.
+    pub fn update_available(&self, meta: &SkillMeta) -> bool {
+        match (&meta.origin, self.latest.read().get(&meta.name)) {
+            (Some(origin), Some(latest)) => origin.commit != *latest,
+            _ => false,
+        }
+    }
+
+    /// When the last check finished.
+    pub fn checked_at(&self) -> Option<DateTime<Utc>> {
+        *self.checked_at.read()
+    }
+}
+
+/// Handle returned by [`schedule_update_checks`]; checks stop when it's
+/// dropped.
+#[derive(Debug)]
+pub struct UpdateSchedule {
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl Drop for UpdateSchedule {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Check imported skills for updates now and then every `interval`, on
+/// the current Tokio runtime.
+pub fn schedule_update_checks(
+    checker: Arc<UpdateChecker>,
+    importer: GitHubImporter,
+    indexer: Arc<SkillIndexer>,
+    interval: Duration,
+) -> UpdateSchedule {
+    let task = tokio::spawn(async move {
+        let mut ticks = tokio::time::interval(interval);
+        loop {
+            ticks.tick().await;
+            let updates = checker.check(&importer, &indexer).await;
+            let outdated = updates.iter().filter(|u| u.update_available).count();
+            if outdated > 0 {
+                tracing::info!("{} imported skills have updates available", outdated);
+            }
+        }
+    });
+    UpdateSchedule { task }
+}
+
+/// Replace an imported skill with a newer copy of it, keeping the current
+/// files under `.versions/`. The new copy keeps `commit`.
+///
+/// Pinned skills are left alone unless `force` is set.
+///
+/// Returns the version directory name the old files were moved to.
+pub fn apply_update(
+    indexer: &SkillIndexer,
+    mut skill: ImportedSkill,
+    commit: &str,
+    force: bool,
+) -> Result<String, IndexError> {
+    let name = skill.meta.name.clone();
+    let current = indexer
+        .get_skill_meta(&name)
+        .ok_or_else(|| IndexError::NotFound(format!("Skill '{}' not found", name)))?;
+    if current.pinned && !force {
+        return Err(IndexError::Pinned(format!(
+            "Skill '{}' is pinned; force the update to change it",
+            name
+        )));
+    }
+    let Some(mut origin) = current.origin else {
+        return Err(IndexError::ValidationError(format!(
+            "Skill '{}' was not imported",
+            name
+        )));
+    };
+    origin.commit = commit.to_string();
+    origin.imported_at = Utc::now();
+    skill.meta.origin = Some(origin);
+    skill.meta.pinned = current.pinned;
+
+    let version = Utc::now().format("%Y%m%dT%H%M%S%.3fZ").to_string();
+    let skill_dir = indexer.skills_dir().join(&name);
+    let saved = indexer
+        .skills_dir()
+        .join(VERSIONS_DIR)
+        .join(&version)
+        .join(&name);
+    if let Some(parent) = saved.parent() {
+        fs::create_dir_all(parent).map_err(|e| {
+            IndexError::WriteError(format!("Failed to create {}: {}", parent.display(), e))
+        })?;
+    }
+    fs::rename(&skill_dir, &saved)
+        .map_err(|e| IndexError::WriteError(format!("Failed to move {} aside: {}", name, e)))?;
+
+    if let Err(e) = indexer.install_skill(&skill.meta, &skill.files) {
+        if let Err(restore) = fs::rename(&saved, &skill_dir) {
+            tracing::error!("Failed to restore {} from {}: {}", name, version, restore);
+        }
+        return Err(e);
+    }
+    Ok(version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import::github::tests::{fake_github, COMMIT};
+    use crate::import::{extract_skills, install_skills, tests::tarball};
+    use crate::models::SkillOrigin;
+    use tempfile::TempDir;
+
+    fn import(indexer: &SkillIndexer, url: &str, commit: &str, content: &'static str) {
+        let archive = tarball(&[
+            (
+                "forms/_meta.json",
+                r#"{"name": "forms", "description": "Forms"}"#,
+            ),
+            ("forms/SKILL.md", content),
+        ]);
+        let origin = SkillOrigin {
+            url: url.to_string(),
+            git_ref: "main".to_string(),
+            path: String::new(),
+            commit: commit.to_string(),
+            imported_at: Utc::now(),
+        };
+        let skills = extract_skills(&archive, "").unwrap();
+        install_skills(indexer, skills, &origin).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_check_updates() {
+        let api = fake_github(&[]).await;
+        let importer = GitHubImporter::new().with_api_base(api);
+        let temp = TempDir::new().unwrap();
+        let indexer = SkillIndexer::new(temp.path());
+        indexer.reload().unwrap();
+        import(
+            &indexer,
+            "https://github.com/acme/skills",
+            "1111111",
+            "# Forms",
+        );
+
+        let checker = UpdateChecker::new();
+        let meta = indexer.get_skill_meta("forms").unwrap();
+        assert!(!checker.update_available(&meta));
+
+        let updates = checker.check(&importer, &indexer).await;
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].latest, COMMIT);
+        assert!(updates[0].update_available);
+        assert!(checker.update_available(&meta));
+        assert!(checker.checked_at().is_some());
+    }
+
+    #[test]
+    fn test_apply_update() {
+        let temp = TempDir::new().unwrap();
+        let indexer = SkillIndexer::new(temp.path());
+        indexer.reload().unwrap();
+        import(
+            &indexer,
+            "https://github.com/acme/skills",
+            "1111111",
+            "# Old",
+        );
+
+        let archive = tarball(&[
+            (
+                "forms/_meta.json",
+                r#"{"name": "forms", "description": "New forms"}"#,
+            ),
+            ("forms/SKILL.md", "# New"),
+        ]);
+        let skill = extract_skills(&archive, "forms").unwrap().remove(0);
+
+        let mut pinned = indexer.get_skill_meta("forms").unwrap();
+        pinned.pinned = true;
+        fs::write(
+            temp.path().join("forms/_meta.json"),
+            serde_json::to_string(&pinned).unwrap(),
+        )
+        .unwrap();
+        indexer.reload().unwrap();
+        assert!(matches!(
+            apply_update(&indexer, skill.clone(), "2222222", false),
+            Err(IndexError::Pinned(_))
+        ));
+
+        let version = apply_update(&indexer, skill, "2222222", true).unwrap();
+
+        let meta = indexer.get_skill_meta("forms").unwrap();
+        assert_eq!(meta.description, "New forms");
+        assert_eq!(meta.origin.unwrap().commit, "2222222");
+        assert_eq!(
+            fs::read_to_string(temp.path().join("forms/SKILL.md")).unwrap(),
+            "# New"
+        );
+        assert!(meta.pinned);
+        let saved = temp.path().join(VERSIONS_DIR).join(version).join("forms");
+        assert_eq!(fs::read_to_string(saved.join("SKILL.md")).unwrap(), "# Old");
+    }
+}
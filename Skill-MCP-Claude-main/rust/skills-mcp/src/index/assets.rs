@@ -0,0 +1,142 @@
+//! Content-addressed storage for binary skill assets.
+//!
+//! Uploaded images are stored once, as `.assets/<hash>` blobs
+//! named by their SHA-256, however many skills use them. Each skill maps
+//! its asset paths to blobs in `_assets.json`, so `references/form.png`
+//! resolves to the shared blob wherever a skill file is read.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+use crate::models::ImageFormat;
+
+/// Directory of asset blobs, at the root of the skills directory.
+pub const ASSETS_DIR: &str = ".assets";
+
+/// File in a skill directory mapping its asset paths to blob hashes.
+pub const ASSET_MAP_FILE: &str = "_assets.json";
+
+/// Whether an uploaded file is stored as a shared blob rather than in the
+/// skill. Only images are: other references are indexed where they lie.
+pub(crate) fn is_binary_asset(file: &str) -> bool {
+    ImageFormat::from_extension(file).is_some()
+}
+
+/// Blobs under a skills directory's [`ASSETS_DIR`].
+pub(crate) struct AssetStore {
+    root: PathBuf,
+}
+
+impl AssetStore {
+    pub(crate) fn new(skills_dir: &Path) -> Self {
+        Self {
+            root: skills_dir.join(ASSETS_DIR),
+        }
+    }
+
+    /// Path of the blob with `hash`, sharded by its first two characters.
+    pub(crate) fn path(&self, hash: &str) -> PathBuf {
+        let shard = hash.get(..2).unwrap_or(hash);
+        self.root.join(shard).join(hash)
+    }
+
+    /// Store `bytes`, if not already stored, returning their hash.
+    pub(crate) fn put(&self, bytes: &[u8]) -> io::Result<String> {
+        let hash: String = Sha256::digest(bytes)
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+
+        let path = self.path(&hash);
+        if !path.is_file() {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let temp = path.with_extension("tmp");
+            fs::write(&temp, bytes)
+                .and_then(|_| fs::rename(&temp, &path))
+                .inspect_err(|_| {
+                    let _ = fs::remove_file(&temp);
+                })?;
+        }
+        Ok(hash)
+    }
+
+    /// Hashes of every stored blob, with their paths.
+    pub(crate) fn blobs(&self) -> Vec<(String, PathBuf)> {
+        let mut blobs = Vec::new();
+        let Ok(shards) = fs::read_dir(&self.root) else {
+            return blobs;
+        };
+        for shard in shards.flatten() {
+            let Ok(entries) = fs::read_dir(shard.path()) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let is_blob = path.extension().is_none() && path.is_file();
+                if let (true, Ok(hash)) = (is_blob, entry.file_name().into_string()) {
+                    blobs.push((hash, path));
+                }
+            }
+        }
+        blobs.sort();
+        blobs
+    }
+}
+
+/// A skill's asset paths and the blobs they resolve to.
+pub(crate) fn read_asset_map(skill_dir: &Path) -> BTreeMap<String, String> {
+    fs::read_to_string(skill_dir.join(ASSET_MAP_FILE))
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+/// Save a skill's asset map, removing the file once it's empty.
+pub(crate) fn write_asset_map(skill_dir: &Path, map: &BTreeMap<String, String>) -> io::Result<()> {
+    let path = skill_dir.join(ASSET_MAP_FILE);
+    if map.is_empty() {
+        return match fs::remove_file(&path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        };
+    }
+
+    let json = serde_json::to_string_pretty(map).map_err(io::Error::other)?;
+    let temp = skill_dir.join(format!(".{}.tmp", ASSET_MAP_FILE));
+    fs::write(&temp, json).and_then(|_| fs::rename(&temp, &path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_asset_store_dedupes() {
+        let temp = TempDir::new().unwrap();
+        let store = AssetStore::new(temp.path());
+
+        let first = store.put(b"png bytes").unwrap();
+        let second = store.put(b"png bytes").unwrap();
+        assert_eq!(first, second);
+        assert_eq!(store.blobs().len(), 1);
+        assert_eq!(fs::read(store.path(&first)).unwrap(), b"png bytes");
+
+        let skill_dir = temp.path().join("forms");
+        fs::create_dir_all(&skill_dir).unwrap();
+        let map = BTreeMap::from([("references/form.png".to_string(), first)]);
+        write_asset_map(&skill_dir, &map).unwrap();
+        assert_eq!(read_asset_map(&skill_dir), map);
+
+        write_asset_map(&skill_dir, &BTreeMap::new()).unwrap();
+        assert!(!skill_dir.join(ASSET_MAP_FILE).exists());
+        assert!(is_binary_asset("shots/Form.PNG"));
+        assert!(!is_binary_asset("guide.md"));
+    }
+}
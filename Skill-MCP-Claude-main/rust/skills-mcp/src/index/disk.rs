@@ -0,0 +1,71 @@
+//! Measuring skill directories on disk.
+
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+
+use crate::models::DiskUsage;
+
+/// How long measured usage is reused while the index is unchanged. Files
+/// the index doesn't track, such as images, can change without a version
+/// bump.
+pub(crate) const DISK_USAGE_TTL: Duration = Duration::from_secs(60);
+
+/// Last measured [`DiskUsage`], reused until the index changes or it's
+/// older than [`DISK_USAGE_TTL`].
+#[derive(Default)]
+pub(crate) struct DiskUsageCache {
+    cached: Mutex<Option<(u64, Instant, DiskUsage)>>,
+}
+
+impl DiskUsageCache {
+    /// The cached usage for index `version`, or a fresh `measure()`.
+    pub(crate) fn get_or_measure(
+        &self,
+        version: u64,
+        measure: impl FnOnce() -> DiskUsage,
+    ) -> DiskUsage {
+        let mut cached = self.cached.lock();
+        if let Some((at_version, at, usage)) = cached.as_ref() {
+            if *at_version == version && at.elapsed() < DISK_USAGE_TTL {
+                return usage.clone();
+            }
+        }
+
+        let usage = measure();
+        *cached = Some((version, Instant::now(), usage.clone()));
+        usage
+    }
+}
+
+/// Total size and number of files under `dir`, not descending into
+/// directories for which `skip` is true. Symbolic links are not followed.
+pub(crate) fn measure_dir(dir: &Path, skip: &dyn Fn(&Path) -> bool) -> (u64, usize) {
+    let mut bytes = 0;
+    let mut files = 0;
+    let mut pending = vec![dir.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                let path = entry.path();
+                if !skip(&path) {
+                    pending.push(path);
+                }
+            } else if file_type.is_file() {
+                bytes += entry.metadata().map(|m| m.len()).unwrap_or(0);
+                files += 1;
+            }
+        }
+    }
+
+    (bytes, files)
+}
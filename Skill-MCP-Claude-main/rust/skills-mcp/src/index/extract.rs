@@ -0,0 +1,147 @@
+//! Plain-text extraction for non-markdown reference documents.
+//!
+//! Notebooks are always supported. PDF and HTML exports need the `pdf` and
+//! `html` features; without them those files are left out of the index.
+
+use std::path::Path;
+
+use crate::models::{is_notebook, notebook_to_markdown};
+
+/// Heading prefix for each extracted PDF page.
+#[cfg(feature = "pdf")]
+const PAGE_HEADING: &str = "## Page";
+
+/// Line width used when rendering HTML as text.
+#[cfg(feature = "html")]
+const HTML_WIDTH: usize = 100;
+
+/// A reference document format the content index understands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceFormat {
+    /// Markdown, indexed as-is.
+    Markdown,
+    /// Jupyter notebook, indexed by cell.
+    Notebook,
+    /// PDF, indexed by page (`pdf` feature).
+    Pdf,
+    /// HTML export, indexed as rendered text (`html` feature).
+    Html,
+}
+
+impl ReferenceFormat {
+    /// Detect the format from a path's extension.
+    ///
+    /// Returns `None` for unknown files and for formats whose feature is
+    /// not enabled.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?;
+        if is_notebook(name) {
+            return Some(Self::Notebook);
+        }
+
+        let extension = name.rsplit_once('.')?.1.to_ascii_lowercase();
+        match extension.as_str() {
+            "md" | "markdown" => Some(Self::Markdown),
+            "pdf" if cfg!(feature = "pdf") => Some(Self::Pdf),
+            "html" | "htm" if cfg!(feature = "html") => Some(Self::Html),
+            _ => None,
+        }
+    }
+
+    /// Whether the file is read as UTF-8 text rather than raw bytes.
+    pub fn is_text(self) -> bool {
+        !matches!(self, Self::Pdf)
+    }
+
+    /// Convert a document's bytes into markdown-like text for indexing.
+    ///
+    /// Markdown and notebooks are expected to be UTF-8 already; callers
+    /// decode them lossily first.
+    pub fn extract(self, bytes: &[u8]) -> Result<String, String> {
+        match self {
+            Self::Markdown => Ok(String::from_utf8_lossy(bytes).into_owned()),
+            Self::Notebook => notebook_to_markdown(&String::from_utf8_lossy(bytes)),
+            Self::Pdf => extract_pdf(bytes),
+            Self::Html => extract_html(bytes),
+        }
+    }
+}
+
+/// Extract PDF text with one section per page.
+#[cfg(feature = "pdf")]
+fn extract_pdf(bytes: &[u8]) -> Result<String, String> {
+    // pdf-extract panics on some malformed files instead of returning errors
+    let pages = std::panic::catch_unwind(|| pdf_extract::extract_text_from_mem_by_pages(bytes))
+        .map_err(|_| "PDF parser panicked".to_string())?
+        .map_err(|e| format!("Invalid PDF: {}", e))?;
+
+    let sections: Vec<String> = pages
+        .iter()
+        .enumerate()
+        .filter(|(_, text)| !text.trim().is_empty())
+        .map(|(i, text)| format!("{} {}\n\n{}", PAGE_HEADING, i + 1, text.trim()))
+        .collect();
+
+    if sections.is_empty() {
+        return Err("No extractable text (scanned PDF?)".to_string());
+    }
+
+    Ok(sections.join("\n\n"))
+}
+
+#[cfg(not(feature = "pdf"))]
+fn extract_pdf(_bytes: &[u8]) -> Result<String, String> {
+    Err("PDF extraction requires the `pdf` feature".to_string())
+}
+
+/// Render HTML as plain text, keeping headings as `#` lines.
+#[cfg(feature = "html")]
+fn extract_html(bytes: &[u8]) -> Result<String, String> {
+    html2text::config::plain()
+        .string_from_read(bytes, HTML_WIDTH)
+        .map_err(|e| format!("Invalid HTML: {}", e))
+}
+
+#[cfg(not(feature = "html"))]
+fn extract_html(_bytes: &[u8]) -> Result<String, String> {
+    Err("HTML extraction requires the `html` feature".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_from_path() {
+        let format = |p: &str| ReferenceFormat::from_path(Path::new(p));
+
+        assert_eq!(format("guide.md"), Some(ReferenceFormat::Markdown));
+        assert_eq!(format("walkthrough.ipynb"), Some(ReferenceFormat::Notebook));
+        assert_eq!(format("data.csv"), None);
+        assert_eq!(format("README"), None);
+        assert_eq!(
+            format("manual.PDF"),
+            cfg!(feature = "pdf").then_some(ReferenceFormat::Pdf)
+        );
+        assert_eq!(
+            format("export.htm"),
+            cfg!(feature = "html").then_some(ReferenceFormat::Html)
+        );
+    }
+
+    #[cfg(feature = "html")]
+    #[test]
+    fn test_extract_html() {
+        let html = b"<html><body><h1>Forms</h1><p>Use <b>labels</b>.</p><script>x()</script></body></html>";
+        let text = ReferenceFormat::Html.extract(html).unwrap();
+
+        assert!(text.contains("# Forms"));
+        assert!(text.contains("Use labels."));
+    }
+
+    #[cfg(feature = "pdf")]
+    #[test]
+    fn test_extract_invalid_pdf() {
+        assert!(ReferenceFormat::Pdf.extract(b"not a pdf").is_err());
+    }
+}
@@ -1,8 +1,8 @@
 //! File system watcher for skill directory changes.
 
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
 use std::path::Path;
 use std::sync::Arc;
-use notify::{RecommendedWatcher, RecursiveMode, Watcher};
 use tokio::sync::mpsc;
 use tracing::{debug, error, info, warn};
 
@@ -24,53 +24,15 @@ impl FileWatcher {
     pub fn new(indexer: Arc<SkillIndexer>) -> Result<Self, WatchError> {
         let indexer_clone = Arc::clone(&indexer);
 
-        let watcher = notify::recommended_watcher(move |res: Result<notify::Event, _>| {
-            match res {
-                Ok(event) => {
-                    // Only trigger on file modifications, creations, or deletions
-                    if !matches!(
-                        event.kind,
-                        notify::EventKind::Create(_)
-                            | notify::EventKind::Modify(_)
-                            | notify::EventKind::Remove(_)
-                    ) {
-                        return;
-                    }
-
-                    // Try to determine which skill(s) were affected
-                    let mut affected_skills = std::collections::HashSet::new();
-
-                    for path in &event.paths {
-                        if let Some(skill_name) = indexer_clone.skill_from_path(path) {
-                            affected_skills.insert(skill_name);
-                        }
-                    }
-
-                    if affected_skills.is_empty() {
-                        // Couldn't determine affected skills, do a full reload
-                        debug!("File change outside skill directories, doing full reload");
-                        if let Err(e) = indexer_clone.reload() {
-                            error!("Failed to reload index: {}", e);
-                        }
-                    } else {
-                        // Incremental update for each affected skill
-                        for skill_name in affected_skills {
-                            debug!("Incrementally updating skill: {}", skill_name);
-                            if let Err(e) = indexer_clone.update_skill(&skill_name) {
-                                warn!("Failed to update skill {}: {}", skill_name, e);
-                                // Fall back to full reload on error
-                                if let Err(e) = indexer_clone.reload() {
-                                    error!("Failed to reload index: {}", e);
-                                }
-                                break;
-                            }
-                        }
-                    }
-                }
-                Err(e) => {
-                    warn!("Watch error: {:?}", e);
+        let watcher = notify::recommended_watcher(move |res: Result<notify::Event, _>| match res {
+            Ok(event) => {
+                for _ in 0..event_repeats(&indexer_clone) {
+                    handle_event(&indexer_clone, &event);
                 }
             }
+            Err(e) => {
+                warn!("Watch error: {:?}", e);
+            }
         })
         .map_err(|e| WatchError::Setup(format!("Failed to create watcher: {}", e)))?;
 
@@ -100,6 +62,71 @@ impl FileWatcher {
     }
 }
 
+/// Apply one watcher event to the index.
+fn handle_event(indexer: &SkillIndexer, event: &notify::Event) {
+    // Only trigger on file modifications, creations, or deletions
+    if !matches!(
+        event.kind,
+        notify::EventKind::Create(_) | notify::EventKind::Modify(_) | notify::EventKind::Remove(_)
+    ) {
+        return;
+    }
+
+    // Drop paths excluded by .skillsignore
+    let paths: Vec<_> = event
+        .paths
+        .iter()
+        .filter(|p| !indexer.is_ignored(p, p.is_dir()))
+        .collect();
+
+    if paths.is_empty() {
+        debug!("Ignoring change to excluded paths");
+        return;
+    }
+
+    // Try to determine which skill(s) were affected
+    let mut affected_skills = std::collections::HashSet::new();
+
+    for path in paths {
+        if let Some(skill_name) = indexer.skill_from_path(path) {
+            affected_skills.insert(skill_name);
+        }
+    }
+
+    if affected_skills.is_empty() {
+        // Couldn't determine affected skills, do a full reload
+        debug!("File change outside skill directories, doing full reload");
+        if let Err(e) = indexer.reload() {
+            error!("Failed to reload index: {}", e);
+        }
+    } else {
+        // Incremental update for each affected skill
+        for skill_name in affected_skills {
+            debug!("Incrementally updating skill: {}", skill_name);
+            if let Err(e) = indexer.update_skill(&skill_name) {
+                warn!("Failed to update skill {}: {}", skill_name, e);
+                // Fall back to full reload on error
+                if let Err(e) = indexer.reload() {
+                    error!("Failed to reload index: {}", e);
+                }
+                break;
+            }
+        }
+    }
+}
+
+/// How many times each event is handled: once, unless an event storm is
+/// injected.
+#[cfg(any(test, feature = "testing"))]
+fn event_repeats(indexer: &SkillIndexer) -> usize {
+    indexer.options().faults.event_repeats()
+}
+
+#[cfg(not(any(test, feature = "testing")))]
+fn event_repeats(_indexer: &SkillIndexer) -> usize {
+    1
+}
+
 /// Errors that can occur with file watching.
 #[derive(Debug, thiserror::Error)]
 pub enum WatchError {
@@ -115,6 +142,8 @@ pub enum WatchError {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::index::IndexerOptions;
+    use crate::testing::{test_skill, Faults, SkillFixture, TestSkills};
     use tempfile::TempDir;
 
     #[test]
@@ -137,4 +166,63 @@ mod tests {
         let mut watcher = FileWatcher::new(indexer).unwrap();
         watcher.watch(temp_dir.path()).unwrap();
     }
+
+    fn modified(path: std::path::PathBuf) -> notify::Event {
+        notify::Event::new(notify::EventKind::Modify(notify::event::ModifyKind::Any)).add_path(path)
+    }
+
+    #[test]
+    fn test_failed_update_falls_back_to_reload() {
+        let skills = TestSkills::new()
+            .with_skill(test_skill())
+            .with_skill(SkillFixture::new("forms", "Form handling"));
+
+        // The first reload and the incremental update fail to read the
+        // metadata; the fallback reload gets through
+        let faults = Faults::new()
+            .fail_reads("forms/_meta.json")
+            .read_error_limit(2);
+        let options = IndexerOptions::default().faults(faults.clone());
+        let indexer = SkillIndexer::with_options(skills.path(), options);
+        indexer.reload().unwrap();
+
+        // The read error is reported for the one skill; the rest is indexed
+        let index = indexer.get_skill_index();
+        assert_eq!(index.len(), 1);
+        assert!(index
+            .validation_errors
+            .iter()
+            .any(|e| e.starts_with("forms: ") && e.contains("injected read error")));
+
+        skills.write("forms/SKILL.md", "# Forms\n\nUpdated.");
+        handle_event(&indexer, &modified(skills.path().join("forms/SKILL.md")));
+
+        assert_eq!(faults.failed_reads(), 2);
+        let index = indexer.get_skill_index();
+        assert_eq!(index.len(), 2);
+        assert!(index.validation_errors.is_empty());
+    }
+
+    #[test]
+    fn test_event_storm() {
+        let skills = TestSkills::new().with_skill(test_skill());
+        let faults = Faults::new()
+            .event_storm(20)
+            .slow_reads(std::time::Duration::from_millis(1));
+        let options = IndexerOptions::default().faults(faults);
+        let indexer = SkillIndexer::with_options(skills.path(), options);
+        indexer.reload().unwrap();
+        let version = indexer.version();
+        let entries = indexer.get_content_index().len();
+
+        skills.write("test-skill/SKILL.md", "# Test Skill\n\nChanged.");
+        let event = modified(skills.path().join("test-skill/SKILL.md"));
+        for _ in 0..event_repeats(&indexer) {
+            handle_event(&indexer, &event);
+        }
+
+        assert_eq!(indexer.version(), version + 20);
+        assert_eq!(indexer.get_skill_index().len(), 1);
+        assert_eq!(indexer.get_content_index().len(), entries);
+    }
 }
@@ -1,17 +1,40 @@
 //! Skill indexer implementation.
 
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fs;
+use std::io::Read;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
 
 use parking_lot::RwLock;
-use tracing::{debug, error, info};
+use tracing::{debug, info, warn};
 use walkdir::WalkDir;
 
 use crate::models::{
-    ContentIndex, ContentIndexEntry, SkillContent, SkillIndex, SkillMeta, SubSkillContent,
+    format_size, parse_flat_skill, render_flat_skill, split_frontmatter, update_skill_stats,
+    ContentIndex, ContentIndexEntry, DiskQuota, DiskUsage, ErrorCode, ErrorResponse, ImageAsset,
+    ImageFormat, IndexDiff, IndexManifest, IndexMemory, ManifestDiff, ReferenceContent,
+    ReferenceInfo, RenderedTemplate, SkillContent, SkillDiskUsage, SkillIndex, SkillMeta,
+    SkillScript, SkillStats, SkillTemplate, SubSkillContent, SubSkillMeta, TemplateDefinition,
+    CATEGORY_SEPARATOR, SUB_SKILL_REF_SEPARATOR, TEMPLATES_DIR,
 };
-use crate::validation::validate_meta;
+use crate::search::IndexSnapshot;
+use crate::validation::{
+    check_skill_name, find_windows_reserved_component, normalize_separators, validate_meta,
+};
+
+use super::assets::{is_binary_asset, write_asset_map};
+use super::disk::{measure_dir, DiskUsageCache};
+use super::extract::ReferenceFormat;
+use super::summary::{ExtractiveSummarizer, Summarizer};
+use super::SkillsIgnore;
+use super::{read_asset_map, AssetStore, ASSET_MAP_FILE};
+use crate::store::{MemoryStore, ScratchDir, SkillFixture, StoreMirror};
+#[cfg(any(test, feature = "testing"))]
+use crate::testing::Faults;
 
 /// Combined index structure for atomic updates.
 ///
@@ -21,6 +44,16 @@ use crate::validation::validate_meta;
 struct CombinedIndex {
     skill_index: SkillIndex,
     content_index: ContentIndex,
+    /// Incremented on every change to either index.
+    version: u64,
+    /// Skill hashes as of `version`.
+    manifest: IndexManifest,
+    /// How `manifest` differs from the one before it.
+    changes: ManifestDiff,
+    /// Totals of each skill's files as of `version`.
+    stats: HashMap<String, SkillStats>,
+    /// Model-written summaries, by skill.
+    summaries: HashMap<String, CachedSummary>,
 }
 
 impl CombinedIndex {
@@ -28,44 +61,136 @@ impl CombinedIndex {
         Self {
             skill_index: SkillIndex::new(),
             content_index: ContentIndex::new(),
+            version: 0,
+            manifest: IndexManifest::default(),
+            changes: ManifestDiff::default(),
+            stats: HashMap::new(),
+            summaries: HashMap::new(),
         }
     }
+
+    /// Bump the version after the skills passing `touched` changed,
+    /// rehashing them, updating their stats, dropping the summaries of
+    /// those removed and recording the manifest diff.
+    fn bump(&mut self, touched: impl Fn(&str) -> bool) -> ManifestDiff {
+        self.version += 1;
+
+        update_skill_stats(
+            &mut self.stats,
+            &self.skill_index,
+            &self.content_index,
+            &touched,
+        );
+        let skill_index = &self.skill_index;
+        self.summaries
+            .retain(|name, _| !touched(name) || skill_index.find(name).is_some());
+
+        let mut manifest = self.manifest.clone();
+        manifest.version = self.version;
+        manifest.rehash(&self.skill_index, &self.content_index, touched);
+
+        self.changes = manifest.diff(&self.manifest);
+        self.manifest = manifest;
+        self.changes.clone()
+    }
 }
 
-/// Validates that a file path from metadata doesn't escape the skill directory.
-///
-/// Returns `Ok(canonical_path)` if the path is safe, `Err` otherwise.
-fn validate_sub_skill_path(skill_dir: &Path, file: &str) -> Result<PathBuf, IndexError> {
-    // Check for obvious path traversal sequences
-    if file.contains("..") {
-        return Err(IndexError::ValidationError(format!(
-            "Sub-skill file path contains '..': {}",
-            file
-        )));
+/// A summary of a skill's main document written by a language model.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedSummary {
+    /// The summary.
+    pub summary: String,
+    /// Model that wrote it.
+    pub model: String,
+    /// Content hash of the document it summarizes.
+    pub hash: String,
+}
+
+/// A new skill that has passed every check, ready to write.
+enum PreparedSkill {
+    /// A flat-file skill: its file name, path and rendered contents.
+    Flat {
+        file: String,
+        path: PathBuf,
+        rendered: String,
+    },
+    /// A skill directory, for [`SkillIndexer::install_skill`].
+    Dir {
+        meta: Box<SkillMeta>,
+        files: Vec<(String, Vec<u8>)>,
+    },
+}
+
+/// A file to write for a new skill directory, relative to it.
+type InstallTarget<'a> = (String, Cow<'a, [u8]>);
+
+/// Called with the manifest diff after each index change that touched a
+/// skill.
+pub type ChangeListener = Arc<dyn Fn(&ManifestDiff) + Send + Sync>;
+
+/// Registered change listeners, keyed by registration id.
+type Listeners = RwLock<Vec<(u64, ChangeListener)>>;
+
+/// Handle returned by [`SkillIndexer::on_change`]; the listener is removed
+/// when it's dropped.
+#[must_use = "the listener is removed when the subscription is dropped"]
+pub struct ChangeSubscription {
+    listeners: Option<Weak<Listeners>>,
+    id: u64,
+}
+
+impl ChangeSubscription {
+    /// Keep the listener for as long as the indexer lives.
+    pub fn detach(mut self) {
+        self.listeners = None;
     }
+}
 
-    // Check for absolute paths
-    if file.starts_with('/') || file.starts_with('\\') {
-        return Err(IndexError::ValidationError(format!(
-            "Sub-skill file path cannot be absolute: {}",
-            file
-        )));
+impl Drop for ChangeSubscription {
+    fn drop(&mut self) {
+        if let Some(listeners) = self.listeners.take().and_then(|l| l.upgrade()) {
+            listeners.write().retain(|(id, _)| *id != self.id);
+        }
     }
+}
 
-    // On Windows, also check for drive letters
-    if file.len() >= 2 && file.chars().nth(1) == Some(':') {
-        return Err(IndexError::ValidationError(format!(
-            "Sub-skill file path cannot be absolute: {}",
-            file
-        )));
+impl std::fmt::Debug for ChangeSubscription {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChangeSubscription")
+            .field("id", &self.id)
+            .finish_non_exhaustive()
     }
+}
+
+/// Deepest category nesting scanned for skills (`a/b/c/skill` is three).
+pub const MAX_CATEGORY_DEPTH: usize = 4;
+
+/// Bytes read from the start of a script to find its docstring.
+const SCRIPT_HEAD_BYTES: u64 = 16 * 1024;
+
+/// Bytes read from the start of an image to find its dimensions; JPEG
+/// frame headers can sit behind large EXIF blocks.
+const IMAGE_HEAD_BYTES: u64 = 64 * 1024;
 
+/// Validates that a relative file path doesn't escape the skill directory.
+///
+/// Used for sub-skill paths from metadata and for direct file reads.
+/// Backslash separators are accepted and treated as `/`.
+///
+/// Returns `Ok(canonical_path)` if the path is safe, `Err` otherwise.
+fn validate_sub_skill_path(skill_dir: &Path, file: &str) -> Result<PathBuf, IndexError> {
+    let file = check_relative_path(file)?;
+    let file = file.as_str();
     let file_path = skill_dir.join(file);
 
     // If the file exists, canonicalize and verify it's within skill_dir
     if file_path.exists() {
         let canonical_path = file_path.canonicalize().map_err(|e| {
-            IndexError::ReadError(format!("Failed to resolve path {}: {}", file_path.display(), e))
+            IndexError::ReadError(format!(
+                "Failed to resolve path {}: {}",
+                file_path.display(),
+                e
+            ))
         })?;
 
         let canonical_skill_dir = skill_dir.canonicalize().map_err(|e| {
@@ -78,7 +203,7 @@ fn validate_sub_skill_path(skill_dir: &Path, file: &str) -> Result<PathBuf, Inde
 
         if !canonical_path.starts_with(&canonical_skill_dir) {
             return Err(IndexError::ValidationError(format!(
-                "Sub-skill file path escapes skill directory: {}",
+                "File path escapes skill directory: {}",
                 file
             )));
         }
@@ -87,29 +212,265 @@ fn validate_sub_skill_path(skill_dir: &Path, file: &str) -> Result<PathBuf, Inde
     } else {
         // File doesn't exist - this is an error anyway
         Err(IndexError::NotFound(format!(
-            "Sub-skill file not found: {}",
+            "File not found: {}",
             file_path.display()
         )))
     }
 }
 
+/// Checks a relative path for traversal, absolute and reserved-name forms
+/// without touching the filesystem, returning it with `/` separators.
+fn check_relative_path(file: &str) -> Result<String, IndexError> {
+    let normalized = normalize_separators(file);
+    let file = normalized.as_str();
+
+    // Check for obvious path traversal sequences
+    if file.contains("..") {
+        return Err(IndexError::ValidationError(format!(
+            "File path contains '..': {}",
+            file
+        )));
+    }
+
+    // Check for absolute paths
+    if file.starts_with('/') {
+        return Err(IndexError::ValidationError(format!(
+            "File path cannot be absolute: {}",
+            file
+        )));
+    }
+
+    // On Windows, also check for drive letters
+    if file.len() >= 2 && file.chars().nth(1) == Some(':') {
+        return Err(IndexError::ValidationError(format!(
+            "File path cannot be absolute: {}",
+            file
+        )));
+    }
+
+    // Reserved device names like CON or aux.md can't be opened as files on Windows
+    if let Some(component) = find_windows_reserved_component(file) {
+        return Err(IndexError::ValidationError(format!(
+            "File path uses a reserved name '{}': {}",
+            component, file
+        )));
+    }
+
+    Ok(normalized)
+}
+
+/// Write through a hidden temporary file and rename it into place,
+/// creating parent directories as needed.
+fn write_file_atomic(path: &Path, bytes: &[u8]) -> Result<(), IndexError> {
+    let write_error = |e: std::io::Error| {
+        IndexError::WriteError(format!("Failed to write {}: {}", path.display(), e))
+    };
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(write_error)?;
+    }
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let temp = path.with_file_name(format!(".{}.tmp", name));
+
+    fs::write(&temp, bytes)
+        .and_then(|_| fs::rename(&temp, path))
+        .map_err(|e| {
+            let _ = fs::remove_file(&temp);
+            write_error(e)
+        })
+}
+
+/// Read a file as UTF-8, replacing invalid sequences instead of failing.
+///
+/// Returns the decoded text and whether any replacement was needed.
+fn read_lossy(path: &Path) -> std::io::Result<(String, bool)> {
+    let bytes = fs::read(path)?;
+    match String::from_utf8(bytes) {
+        Ok(text) => Ok((text, false)),
+        Err(e) => Ok((String::from_utf8_lossy(e.as_bytes()).into_owned(), true)),
+    }
+}
+
+/// Configuration for a [`SkillIndexer`].
+#[derive(Debug, Clone)]
+pub struct IndexerOptions {
+    /// Follow symbolic links when scanning skill and reference directories.
+    ///
+    /// When enabled, linked skills are deduplicated by canonical path and
+    /// symlink cycles are reported as validation errors. When disabled,
+    /// symlinked skills and reference files are skipped entirely.
+    pub follow_links: bool,
+
+    /// Largest file, in bytes, that will be loaded into the content index.
+    ///
+    /// Larger files are skipped with a validation error. They can still be
+    /// read directly through the content endpoints.
+    pub max_file_size: u64,
+
+    /// Size limits on skill directories. Writes through the indexer and the
+    /// API are rejected past them; `GET /api/usage/disk` warns about skills
+    /// already over.
+    pub quota: DiskQuota,
+
+    /// Reloads taking longer than this are logged as warnings and counted
+    /// in [`SkillIndexer::slow_reloads`]. `Duration::ZERO` turns this off.
+    pub slow_reload: Duration,
+
+    /// Failures injected into reads and watcher events, taken from the
+    /// `SKILLS_FAULT_*` variables by default.
+    #[cfg(any(test, feature = "testing"))]
+    pub faults: Faults,
+}
+
+impl IndexerOptions {
+    /// Default per-file size cap for indexing (5 MiB).
+    pub const DEFAULT_MAX_FILE_SIZE: u64 = 5 * 1024 * 1024;
+
+    /// Default slow reload threshold.
+    pub const DEFAULT_SLOW_RELOAD: Duration = Duration::from_secs(5);
+
+    /// Set whether symbolic links are followed.
+    pub fn follow_links(mut self, follow: bool) -> Self {
+        self.follow_links = follow;
+        self
+    }
+
+    /// Set the per-file size cap for indexing.
+    pub fn max_file_size(mut self, bytes: u64) -> Self {
+        self.max_file_size = bytes;
+        self
+    }
+
+    /// Set the largest size, in bytes, of a single skill.
+    pub fn skill_quota(mut self, bytes: u64) -> Self {
+        self.quota.per_skill = Some(bytes);
+        self
+    }
+
+    /// Set the largest size, in bytes, of all skills together.
+    pub fn total_quota(mut self, bytes: u64) -> Self {
+        self.quota.total = Some(bytes);
+        self
+    }
+
+    /// Set how long a reload may take before it is logged as slow.
+    pub fn slow_reload(mut self, threshold: Duration) -> Self {
+        self.slow_reload = threshold;
+        self
+    }
+
+    /// Set the failures to inject.
+    #[cfg(any(test, feature = "testing"))]
+    pub fn faults(mut self, faults: Faults) -> Self {
+        self.faults = faults;
+        self
+    }
+}
+
+impl Default for IndexerOptions {
+    fn default() -> Self {
+        Self {
+            follow_links: true,
+            max_file_size: Self::DEFAULT_MAX_FILE_SIZE,
+            quota: DiskQuota::default(),
+            slow_reload: Self::DEFAULT_SLOW_RELOAD,
+            #[cfg(any(test, feature = "testing"))]
+            faults: Faults::from_env(),
+        }
+    }
+}
+
 /// Skill indexer that manages metadata and content indexes.
 pub struct SkillIndexer {
     /// Path to the skills directory.
     skills_dir: PathBuf,
 
+    /// Indexing configuration.
+    options: IndexerOptions,
+
     /// Combined index protected by a single lock for atomic updates.
     /// This ensures skill_index and content_index are always consistent.
     index: Arc<RwLock<CombinedIndex>>,
+
+    /// Exclusion rules from `.skillsignore`, refreshed on every reload.
+    ignore: RwLock<SkillsIgnore>,
+
+    /// Summarizer for main documents; summaries are skipped when unset.
+    summarizer: Option<Arc<dyn Summarizer>>,
+
+    /// Notified of manifest changes.
+    listeners: Arc<Listeners>,
+
+    /// Last measured disk usage.
+    disk_usage: DiskUsageCache,
+
+    /// Reloads that took longer than `options.slow_reload`.
+    slow_reloads: AtomicU64,
+
+    /// Private directory holding the files of an in-memory index.
+    _scratch: Option<ScratchDir>,
 }
 
 impl SkillIndexer {
     /// Create a new indexer for the given skills directory.
     pub fn new(skills_dir: impl AsRef<Path>) -> Self {
+        Self::with_options(skills_dir, IndexerOptions::default())
+    }
+
+    /// Create a new indexer with custom options.
+    pub fn with_options(skills_dir: impl AsRef<Path>, options: IndexerOptions) -> Self {
+        let skills_dir = skills_dir.as_ref().to_path_buf();
+        let ignore = SkillsIgnore::load(&skills_dir);
+
         Self {
-            skills_dir: skills_dir.as_ref().to_path_buf(),
+            skills_dir,
+            options,
             index: Arc::new(RwLock::new(CombinedIndex::new())),
+            ignore: RwLock::new(ignore),
+            summarizer: Some(Arc::new(ExtractiveSummarizer::default())),
+            listeners: Arc::default(),
+            disk_usage: DiskUsageCache::default(),
+            slow_reloads: AtomicU64::new(0),
+            _scratch: None,
+        }
+    }
+
+    /// Index skills described in code, for tests and ephemeral skill sets.
+    ///
+    /// The fixtures go into a [`MemoryStore`], mirrored into a private
+    /// temporary directory that is removed with the indexer.
+    pub fn from_memory(fixtures: Vec<SkillFixture>) -> Result<Self, IndexError> {
+        let store = MemoryStore::new();
+        for fixture in &fixtures {
+            store
+                .add_fixture(fixture)
+                .map_err(|e| IndexError::ValidationError(e.to_string()))?;
         }
+
+        let scratch = ScratchDir::new()
+            .map_err(|e| IndexError::WriteError(format!("Failed to create directory: {}", e)))?;
+        StoreMirror::open(Arc::new(store), scratch.path())
+            .map_err(|e| IndexError::WriteError(e.to_string()))?;
+
+        let mut indexer = Self::new(scratch.path());
+        indexer._scratch = Some(scratch);
+        indexer.reload()?;
+        Ok(indexer)
+    }
+
+    /// Summarize skills with `summarizer` instead of the extractive default.
+    pub fn with_summarizer(mut self, summarizer: Arc<dyn Summarizer>) -> Self {
+        self.summarizer = Some(summarizer);
+        self
+    }
+
+    /// Don't summarize skills.
+    pub fn without_summaries(mut self) -> Self {
+        self.summarizer = None;
+        self
     }
 
     /// Get the skills directory path.
@@ -117,207 +478,595 @@ impl SkillIndexer {
         &self.skills_dir
     }
 
+    /// Get the indexing options.
+    pub fn options(&self) -> &IndexerOptions {
+        &self.options
+    }
+
+    /// Apply injected read faults before reading `path`.
+    #[cfg(any(test, feature = "testing"))]
+    fn before_read(&self, path: &Path) -> std::io::Result<()> {
+        self.options.faults.before_read(path)
+    }
+
+    #[cfg(not(any(test, feature = "testing")))]
+    fn before_read(&self, _path: &Path) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    /// Check whether a path is excluded by `.skillsignore`.
+    ///
+    /// The path may be absolute or relative to the skills directory.
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        self.ignore.read().is_ignored(path, is_dir)
+    }
+
     /// Reload both indexes from disk.
     ///
     /// This performs an atomic update of both indexes to ensure consistency.
     /// Readers will see either the old state or the new state, never a mix.
     pub fn reload(&self) -> Result<(), IndexError> {
+        self.reload_with_progress(|_, _| {})
+    }
+
+    /// Reload like [`Self::reload`], calling `progress` with the number of
+    /// skills scanned so far and the total after each skill's files are
+    /// indexed.
+    pub fn reload_with_progress(&self, progress: impl Fn(usize, usize)) -> Result<(), IndexError> {
         info!("Reloading skill indexes from {:?}", self.skills_dir);
+        let started = Instant::now();
+
+        // Pick up any edits to .skillsignore before scanning
+        *self.ignore.write() = SkillsIgnore::load(&self.skills_dir);
 
         // Build new indexes outside the lock
-        let skill_index = self.build_skill_index()?;
-        let content_index = self.build_content_index(&skill_index)?;
+        let mut skill_index = self.build_skill_index()?;
+        let mut walk_errors = Vec::new();
+        let mut warnings = Vec::new();
+        let content_index =
+            self.build_content_index(&skill_index, &mut walk_errors, &mut warnings, &progress)?;
+        skill_index.validation_errors.extend(walk_errors);
+        skill_index.validation_warnings.extend(warnings);
 
         // Capture counts before moving into the combined index
         let skill_count = skill_index.len();
         let content_count = content_index.len();
 
         // Atomic update: replace both indexes in a single write operation
-        let combined = CombinedIndex {
-            skill_index,
-            content_index,
+        let changes = {
+            let mut index = self.index.write();
+            index.skill_index = skill_index;
+            index.content_index = content_index;
+            index.bump(|_| true)
         };
-        *self.index.write() = combined;
 
         info!(
             "Index reload complete: {} skills, {} content entries",
             skill_count, content_count
         );
 
+        let elapsed = started.elapsed();
+        let threshold = self.options.slow_reload;
+        if !threshold.is_zero() && elapsed > threshold {
+            self.slow_reloads.fetch_add(1, Ordering::Relaxed);
+            warn!(
+                "Slow reload: {:?} (over {:?}) for {} skills, {} content entries",
+                elapsed, threshold, skill_count, content_count
+            );
+        }
+
+        self.notify(&changes);
         Ok(())
     }
 
+    /// Number of reloads so far that were slower than
+    /// [`IndexerOptions::slow_reload`].
+    pub fn slow_reloads(&self) -> u64 {
+        self.slow_reloads.load(Ordering::Relaxed)
+    }
+
+    /// Rescan disk without swapping and report how the index differs.
+    ///
+    /// Useful for catching changes the file watcher missed, e.g. on network
+    /// mounts. The scan uses the current `.skillsignore` rules.
+    pub fn diff_with_disk(&self) -> Result<IndexDiff, IndexError> {
+        let skill_index = self.build_skill_index()?;
+        let (mut walk_errors, mut warnings) = (Vec::new(), Vec::new());
+        let content_index =
+            self.build_content_index(&skill_index, &mut walk_errors, &mut warnings, &|_, _| {})?;
+
+        let index = self.index.read();
+        Ok(IndexDiff::between(
+            (&index.skill_index, &index.content_index),
+            (&skill_index, &content_index),
+        ))
+    }
+
     /// Get the current skill index.
     pub fn get_skill_index(&self) -> SkillIndex {
         self.index.read().skill_index.clone()
     }
 
+    /// Run `f` on the current skill index without cloning it.
+    ///
+    /// `f` runs under the index read lock, so reloads wait for it; keep it
+    /// short.
+    pub fn with_skill_index<R>(&self, f: impl FnOnce(&SkillIndex) -> R) -> R {
+        f(&self.index.read().skill_index)
+    }
+
+    /// Run `f` on the current skill and content indexes without cloning
+    /// them, under the same read lock as [`Self::with_skill_index`].
+    pub fn with_indexes<R>(&self, f: impl FnOnce(&SkillIndex, &ContentIndex) -> R) -> R {
+        let index = self.index.read();
+        f(&index.skill_index, &index.content_index)
+    }
+
+    /// Word, token, sub-skill and reference totals of every skill, with
+    /// when its files last changed, as of the current index.
+    pub fn skill_stats(&self) -> HashMap<String, SkillStats> {
+        self.index.read().stats.clone()
+    }
+
+    /// The model-written summary cached for `skill`, if it was made from
+    /// a main document with content hash `hash`.
+    pub fn cached_summary(&self, skill: &str, hash: &str) -> Option<CachedSummary> {
+        let index = self.index.read();
+        let cached = index.summaries.get(skill)?;
+        (cached.hash == hash).then(|| cached.clone())
+    }
+
+    /// Cache a model-written summary of `skill`, replacing any before it.
+    /// It's kept across reloads until the skill is removed.
+    pub fn cache_summary(&self, skill: &str, summary: CachedSummary) {
+        self.index
+            .write()
+            .summaries
+            .insert(skill.to_string(), summary);
+    }
+
+    /// Approximate memory held by the current indexes.
+    pub fn memory(&self) -> IndexMemory {
+        self.with_indexes(IndexMemory::measure)
+    }
+
     /// Get the current content index.
     pub fn get_content_index(&self) -> ContentIndex {
         self.index.read().content_index.clone()
     }
 
-    // ========================================================================
-    // Incremental Index Updates
-    // ========================================================================
+    /// Content hash of every skill, as of the current version.
+    pub fn manifest(&self) -> IndexManifest {
+        self.index.read().manifest.clone()
+    }
 
-    /// Update a single skill in the index without rebuilding everything.
-    ///
-    /// This is more efficient than `reload()` when only one skill has changed.
-    pub fn update_skill(&self, name: &str) -> Result<(), IndexError> {
-        let skill_dir = self.skills_dir.join(name);
+    /// How the manifest changed in the latest index update.
+    pub fn last_changes(&self) -> ManifestDiff {
+        self.index.read().changes.clone()
+    }
 
-        // Check if skill directory exists
-        if !skill_dir.is_dir() {
-            // Skill was deleted, remove it from index
-            return self.remove_skill(name);
+    /// Call `listener` after every index update that adds, removes or
+    /// changes a skill, with what changed.
+    ///
+    /// Listeners run on the updating thread after the index lock is
+    /// released; slow work belongs on another thread. The listener is
+    /// removed when the returned subscription is dropped.
+    pub fn on_change(&self, listener: ChangeListener) -> ChangeSubscription {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        self.listeners.write().push((id, listener));
+        ChangeSubscription {
+            listeners: Some(Arc::downgrade(&self.listeners)),
+            id,
         }
+    }
 
-        // Load the skill metadata
-        let meta_path = skill_dir.join("_meta.json");
-        if !meta_path.exists() {
-            debug!("Skill {} missing _meta.json, removing from index", name);
-            return self.remove_skill(name);
+    fn notify(&self, changes: &ManifestDiff) {
+        if changes.is_empty() {
+            return;
         }
+        let listeners: Vec<_> = self
+            .listeners
+            .read()
+            .iter()
+            .map(|(_, listener)| Arc::clone(listener))
+            .collect();
+        for listener in listeners {
+            listener(changes);
+        }
+    }
 
-        let meta = self.load_meta(&meta_path)?;
+    /// Space taken by each indexed skill, checked against the configured
+    /// quota.
+    ///
+    /// Measuring walks every skill directory, so the result is reused until
+    /// the index changes, for a minute at most.
+    pub fn disk_usage(&self) -> DiskUsage {
+        let version = self.version();
+        self.disk_usage.get_or_measure(version, || {
+            let names = self.skill_names();
+            let skills = names
+                .iter()
+                .map(|name| self.measure_skill(name, &names))
+                .collect();
+            DiskUsage::new(skills, self.options.quota)
+        })
+    }
 
-        // Validate metadata
-        if let Err(validation_errors) = validate_meta(&meta) {
-            for err in validation_errors {
-                debug!("Validation error for {}: {}", name, err);
-            }
-        }
+    /// Measure a single skill now, bypassing the cache.
+    pub fn skill_disk_usage(&self, name: &str) -> SkillDiskUsage {
+        let mut usage = self.measure_skill(name, &self.skill_names());
+        usage.over_quota = self
+            .options
+            .quota
+            .per_skill
+            .is_some_and(|limit| usage.bytes > limit);
+        usage
+    }
 
-        // Build content entries for this skill
-        let mut content_entries = Vec::new();
+    fn skill_names(&self) -> Vec<String> {
+        let index = self.index.read();
+        index
+            .skill_index
+            .skills
+            .iter()
+            .map(|s| s.name.clone())
+            .collect()
+    }
 
-        // Index main SKILL.md
-        let skill_md = skill_dir.join("SKILL.md");
-        if skill_md.exists() {
-            if let Ok(content) = fs::read_to_string(&skill_md) {
-                content_entries.push(ContentIndexEntry::new(
-                    name.to_string(),
-                    None,
-                    "SKILL.md".to_string(),
-                    content,
-                ));
-            }
+    /// Check that writing `writes`, pairs of a path in skill `name` and its
+    /// new size, keeps the skill and all skills within their quotas.
+    ///
+    /// Existing files at those paths count as replaced. Writes that shrink
+    /// usage are always allowed, so an over-quota skill can be trimmed.
+    pub fn check_quota(&self, name: &str, writes: &[(PathBuf, u64)]) -> Result<(), IndexError> {
+        let quota = self.options.quota;
+        if quota.per_skill.is_none() && quota.total.is_none() {
+            return Ok(());
         }
 
-        // Index sub-skills
-        if let Some(ref sub_skills) = meta.sub_skills {
-            for sub in sub_skills {
-                let sub_path = skill_dir.join(&sub.file);
-                if sub_path.exists() {
-                    if let Ok(content) = fs::read_to_string(&sub_path) {
-                        content_entries.push(ContentIndexEntry::new(
-                            name.to_string(),
-                            Some(sub.name.clone()),
-                            sub.file.clone(),
-                            content,
-                        ));
-                    }
-                }
+        let (mut added, mut replaced) = (0u64, 0u64);
+        for (path, bytes) in writes {
+            added += bytes;
+            replaced += fs::metadata(path)
+                .ok()
+                .filter(|m| m.is_file())
+                .map_or(0, |m| m.len());
+        }
+        if added <= replaced {
+            return Ok(());
+        }
+        let growth = added - replaced;
+
+        if let Some(limit) = quota.per_skill {
+            let current = self.skill_disk_usage(name).bytes;
+            if current + growth > limit {
+                return Err(IndexError::QuotaExceeded(format!(
+                    "Skill '{}' would use {}, over its {} quota (currently {})",
+                    name,
+                    format_size(current + growth),
+                    format_size(limit),
+                    format_size(current)
+                )));
             }
         }
 
-        // Index references directory if present
-        let refs_dir = skill_dir.join("references");
-        if refs_dir.is_dir() {
-            for entry in WalkDir::new(&refs_dir)
-                .follow_links(true)
-                .into_iter()
-                .filter_map(|e| e.ok())
-            {
-                let path = entry.path();
-                if !path.is_file() {
-                    continue;
-                }
-
-                let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
-                if ext != "md" && ext != "markdown" {
-                    continue;
-                }
-
-                if let Ok(content) = fs::read_to_string(path) {
-                    let relative = path.strip_prefix(&skill_dir).unwrap_or(path);
-                    content_entries.push(ContentIndexEntry::new(
-                        name.to_string(),
-                        None,
-                        relative.to_string_lossy().to_string(),
-                        content,
-                    ));
-                }
+        if let Some(limit) = quota.total {
+            let current = self.disk_usage().total_bytes;
+            if current + growth > limit {
+                return Err(IndexError::QuotaExceeded(format!(
+                    "Skills would use {} in total, over the {} quota (currently {})",
+                    format_size(current + growth),
+                    format_size(limit),
+                    format_size(current)
+                )));
             }
         }
 
-        // Atomically update the index
-        {
-            let mut index = self.index.write();
-
-            // Remove old entries for this skill
-            index.skill_index.skills.retain(|s| s.name != name);
-            index.content_index.entries.retain(|_key, entry| entry.domain != name);
-
-            // Add updated entries
-            index.skill_index.skills.push(meta);
-            index.skill_index.skills.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(())
+    }
 
-            for entry in content_entries {
-                index.content_index.insert(entry);
+    /// Size of a skill's file or directory, leaving out skills nested in it.
+    fn measure_skill(&self, name: &str, skills: &[String]) -> SkillDiskUsage {
+        let (bytes, files) = match self.flat_skill_path(name) {
+            Some(path) => (fs::metadata(path).map(|m| m.len()).unwrap_or(0), 1),
+            None => {
+                let skill_dir = self.skills_dir.join(name);
+                let nested: Vec<PathBuf> = skills
+                    .iter()
+                    .filter(|other| {
+                        other
+                            .strip_prefix(name)
+                            .is_some_and(|rest| rest.starts_with(CATEGORY_SEPARATOR))
+                    })
+                    .map(|other| self.skills_dir.join(other))
+                    .collect();
+                let (bytes, files) =
+                    measure_dir(&skill_dir, &|dir| nested.iter().any(|n| n == dir));
+
+                // Shared assets count fully against every skill using them
+                let assets = AssetStore::new(&self.skills_dir);
+                read_asset_map(&skill_dir)
+                    .values()
+                    .filter_map(|hash| fs::metadata(assets.path(hash)).ok())
+                    .fold((bytes, files), |(bytes, files), m| {
+                        (bytes + m.len(), files + 1)
+                    })
             }
+        };
+
+        SkillDiskUsage {
+            name: name.to_string(),
+            bytes,
+            files,
+            over_quota: false,
         }
+    }
 
-        debug!("Incrementally updated skill: {}", name);
-        Ok(())
+    /// Copy both indexes, as of one version, for searching elsewhere.
+    pub fn snapshot(&self) -> IndexSnapshot {
+        let index = self.index.read();
+        IndexSnapshot::new(
+            index.version,
+            index.skill_index.clone(),
+            index.content_index.clone(),
+        )
     }
 
-    /// Remove a skill from the index.
-    pub fn remove_skill(&self, name: &str) -> Result<(), IndexError> {
-        let mut index = self.index.write();
+    /// Hash of an indexed file's content, by content index key.
+    ///
+    /// Reflects the file as of the last index update, not the disk.
+    pub fn indexed_hash(&self, key: &str) -> Option<String> {
+        self.index
+            .read()
+            .content_index
+            .get(key)
+            .map(|entry| entry.hash.clone())
+    }
 
-        let before_skills = index.skill_index.skills.len();
-        let before_content = index.content_index.entries.len();
+    /// Get the indexed summary of a skill, if it has one.
+    pub fn indexed_summary(&self, name: &str) -> Option<String> {
+        self.index
+            .read()
+            .content_index
+            .get(name)
+            .and_then(|entry| entry.summary.clone())
+    }
 
-        // Remove skill metadata
-        index.skill_index.skills.retain(|s| s.name != name);
+    /// Get the keywords extracted from a skill's main document.
+    pub fn indexed_keywords(&self, name: &str) -> Vec<String> {
+        self.index
+            .read()
+            .content_index
+            .get(name)
+            .map(|entry| entry.keywords.clone())
+            .unwrap_or_default()
+    }
 
-        // Remove content entries
-        index.content_index.entries.retain(|_key, entry| entry.domain != name);
+    /// Get the index version.
+    ///
+    /// The version changes whenever the index does, so callers can tell
+    /// whether data derived from an earlier read is still current.
+    pub fn version(&self) -> u64 {
+        self.index.read().version
+    }
 
-        let removed_skills = before_skills - index.skill_index.skills.len();
-        let removed_content = before_content - index.content_index.entries.len();
+    // ========================================================================
+    // Incremental Index Updates
+    // ========================================================================
+
+    /// Update a single skill in the index without rebuilding everything.
+    ///
+    /// This is more efficient than `reload()` when only one skill has changed.
+    pub fn update_skill(&self, name: &str) -> Result<(), IndexError> {
+        let skill_dir = self.skills_dir.join(name);
+
+        // A flat-file skill is only used when there's no directory of the same name
+        let flat_path = self.flat_skill_path(name);
+        let (skill_path, relative, is_dir) = match &flat_path {
+            Some(path) => (path.clone(), format!("{}.md", name), false),
+            None if skill_dir.is_dir() => (skill_dir.clone(), name.to_string(), true),
+            // Skill was deleted, remove it (and anything in it, if it was a category)
+            None => return self.remove_skill_tree(name),
+        };
+
+        if self.is_ignored(Path::new(&relative), is_dir) {
+            debug!(
+                "Skill {} is excluded by .skillsignore, removing from index",
+                name
+            );
+            return self.remove_skill_tree(name);
+        }
+
+        if !self.options.follow_links && is_symlink(&skill_path) {
+            debug!(
+                "Skill {} is a symlink and links are not followed, removing from index",
+                name
+            );
+            return self.remove_skill_tree(name);
+        }
+
+        // Load the skill metadata
+        let meta = match &flat_path {
+            Some(path) => match self.load_flat_meta(path)? {
+                Some(meta) => meta,
+                None => {
+                    debug!(
+                        "Skill file {} has no frontmatter, removing from index",
+                        relative
+                    );
+                    return self.remove_skill(name);
+                }
+            },
+            None => {
+                let meta_path = skill_dir.join("_meta.json");
+                if !meta_path.exists() {
+                    debug!("Skill {} missing _meta.json, removing from index", name);
+                    return self.remove_skill(name);
+                }
+                self.load_meta(&meta_path)?
+            }
+        };
+
+        let mut skill_errors = Vec::new();
+
+        // Validate metadata
+        if let Err(validation_errors) = validate_meta(&meta) {
+            for err in validation_errors {
+                debug!("Validation error for {}: {}", name, err);
+                skill_errors.push(format!("{}: {}", name, err));
+            }
+        }
+
+        // Build content entries for this skill
+        let mut walk_errors = Vec::new();
+        let mut skill_warnings = Vec::new();
+        let content_entries =
+            self.build_skill_entries(&meta, &mut walk_errors, &mut skill_warnings);
+        for err in walk_errors {
+            warn!("{}", err);
+            skill_errors.push(err);
+        }
+
+        // Atomically update the index
+        let changes = {
+            let mut index = self.index.write();
+
+            // Remove old entries for this skill
+            index.skill_index.skills.retain(|s| s.name != name);
+            index
+                .content_index
+                .entries
+                .retain(|_key, entry| entry.domain != name);
+
+            // Replace this skill's errors; other skills' errors are untouched
+            let prefix = format!("{}: ", name);
+            index
+                .skill_index
+                .validation_errors
+                .retain(|e| !e.starts_with(&prefix));
+            index.skill_index.validation_errors.extend(skill_errors);
+            index
+                .skill_index
+                .validation_warnings
+                .retain(|w| !w.starts_with(&prefix));
+            index.skill_index.validation_warnings.extend(skill_warnings);
+
+            // Add updated entries
+            index.skill_index.skills.push(meta);
+            index.skill_index.skills.sort_by(|a, b| a.name.cmp(&b.name));
+
+            for entry in content_entries {
+                index.content_index.insert(entry);
+            }
+
+            index.bump(|skill| skill == name)
+        };
+
+        debug!("Incrementally updated skill: {}", name);
+        self.notify(&changes);
+        Ok(())
+    }
+
+    /// Remove a skill from the index.
+    pub fn remove_skill(&self, name: &str) -> Result<(), IndexError> {
+        self.remove_matching(name, |domain| domain == name)
+    }
+
+    /// Remove a skill and every skill namespaced under it.
+    ///
+    /// Used when a directory disappears, since it may have been a category.
+    fn remove_skill_tree(&self, name: &str) -> Result<(), IndexError> {
+        let prefix = format!("{}{}", name, CATEGORY_SEPARATOR);
+        self.remove_matching(name, |domain| domain == name || domain.starts_with(&prefix))
+    }
+
+    /// Remove all skills whose name matches `matches`.
+    fn remove_matching(
+        &self,
+        name: &str,
+        matches: impl Fn(&str) -> bool,
+    ) -> Result<(), IndexError> {
+        let mut guard = self.index.write();
+        let index = &mut *guard;
+
+        let before_skills = index.skill_index.skills.len();
+        let before_content = index.content_index.entries.len();
+
+        // Remove skill metadata
+        index.skill_index.skills.retain(|s| !matches(&s.name));
+
+        // Remove content entries
+        index
+            .content_index
+            .entries
+            .retain(|_key, entry| !matches(&entry.domain));
+
+        let removed_skills = before_skills - index.skill_index.skills.len();
+        let removed_content = before_content - index.content_index.entries.len();
+
+        let changes = (removed_skills > 0 || removed_content > 0).then(|| index.bump(&matches));
+        drop(guard);
 
         debug!(
             "Removed skill {} from index ({} skills, {} content entries removed)",
             name, removed_skills, removed_content
         );
 
+        if let Some(changes) = changes {
+            self.notify(&changes);
+        }
         Ok(())
     }
 
     /// Determine which skill was affected by a file change.
     ///
-    /// Returns the skill name if the path is within a skill directory.
+    /// Returns the skill name if the path is within a skill directory or is
+    /// a flat-file skill (`name.md`) at the root. Inside category folders the
+    /// deepest indexed skill, or directory with `_meta.json`, wins.
     pub fn skill_from_path(&self, path: &Path) -> Option<String> {
         // Try to get the path relative to skills_dir
         let relative = path.strip_prefix(&self.skills_dir).ok()?;
 
-        // The first component should be the skill name
-        let skill_name = relative.components().next()?;
+        let parts: Vec<&str> = relative
+            .components()
+            .map(|c| match c {
+                std::path::Component::Normal(name) => name.to_str(),
+                _ => None,
+            })
+            .collect::<Option<_>>()?;
 
-        match skill_name {
-            std::path::Component::Normal(name) => {
-                let name_str = name.to_str()?;
-                // Skip hidden directories
-                if name_str.starts_with('.') || name_str.starts_with('_') {
-                    return None;
-                }
-                Some(name_str.to_string())
+        // The first component should be the skill name or a category
+        let first = *parts.first()?;
+
+        // Skip hidden directories
+        if first.starts_with('.') || first.starts_with('_') {
+            return None;
+        }
+
+        if parts.len() == 1 && is_markdown(Path::new(first)) {
+            return Path::new(first)
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .map(str::to_string);
+        }
+
+        let index = self.index.read();
+        let separator = CATEGORY_SEPARATOR.to_string();
+        for len in (2..=parts.len()).rev() {
+            let candidate = parts[..len].join(&separator);
+            if index.skill_index.find(&candidate).is_some()
+                || self
+                    .skills_dir
+                    .join(&candidate)
+                    .join("_meta.json")
+                    .is_file()
+            {
+                return Some(candidate);
             }
-            _ => None,
         }
+
+        Some(first.to_string())
     }
 
     /// Get metadata for a specific skill.
@@ -325,236 +1074,1533 @@ impl SkillIndexer {
         self.index.read().skill_index.find(name).cloned()
     }
 
-    /// Check if a skill exists.
+    /// Name of the skill that `name` is an alias of, if any.
+    ///
+    /// Real skill names take precedence, so this is `None` whenever a
+    /// skill called `name` exists.
+    pub fn resolve_alias(&self, name: &str) -> Option<String> {
+        let index = self.index.read();
+        index
+            .skill_index
+            .find_by_alias(name)
+            .map(|meta| meta.name.clone())
+    }
+
+    /// Check if a skill exists, as a directory or a flat file.
     pub fn skill_exists(&self, name: &str) -> bool {
-        self.skills_dir.join(name).is_dir()
+        self.skills_dir.join(name).is_dir() || self.flat_skill_path(name).is_some()
     }
 
-    /// Check if a skill has a references directory.
-    pub fn has_references(&self, name: &str) -> bool {
-        self.skills_dir.join(name).join("references").is_dir()
+    /// Path of a flat-file skill (`name.md` at the skills root).
+    ///
+    /// Returns `None` when the file doesn't exist or a skill directory of
+    /// the same name takes precedence.
+    pub fn flat_skill_path(&self, name: &str) -> Option<PathBuf> {
+        // Flat files live at the root only, never inside categories
+        if name.is_empty()
+            || name.contains(CATEGORY_SEPARATOR)
+            || self.skills_dir.join(name).is_dir()
+        {
+            return None;
+        }
+
+        let path = self.skills_dir.join(format!("{}.md", name));
+        path.is_file().then_some(path)
     }
 
-    /// Read main SKILL.md content for a skill.
-    pub fn read_skill_content(&self, name: &str) -> Result<SkillContent, IndexError> {
+    /// Check if a skill has a references directory or mapped reference assets.
+    pub fn has_references(&self, name: &str) -> bool {
         let skill_dir = self.skills_dir.join(name);
-        let skill_md = skill_dir.join("SKILL.md");
+        skill_dir.join("references").is_dir()
+            || read_asset_map(&skill_dir)
+                .keys()
+                .any(|relative| relative.starts_with("references/"))
+    }
 
-        if !skill_md.exists() {
+    /// List the markdown documents in a skill's `references/` directory.
+    ///
+    /// Paths are relative to `references/` and sorted. A skill without a
+    /// references directory yields an empty list.
+    pub fn list_references(&self, domain: &str) -> Result<Vec<ReferenceInfo>, IndexError> {
+        if self.get_skill_meta(domain).is_none() {
             return Err(IndexError::NotFound(format!(
-                "SKILL.md not found for '{}'",
-                name
+                "Skill '{}' not found",
+                domain
             )));
         }
 
-        let content = fs::read_to_string(&skill_md).map_err(|e| {
-            IndexError::ReadError(format!("Failed to read {}: {}", skill_md.display(), e))
-        })?;
+        let refs_dir = self.skills_dir.join(domain).join("references");
+        if !refs_dir.is_dir() {
+            return Ok(Vec::new());
+        }
 
-        let meta = self.get_skill_meta(name);
-        let sub_skills = meta
-            .as_ref()
-            .and_then(|m| m.sub_skills.as_ref())
-            .map(|subs| subs.iter().map(|s| s.name.clone()).collect())
-            .unwrap_or_default();
+        let mut errors = Vec::new();
+        let references = self
+            .collect_markdown_files(domain, &refs_dir, &mut errors)
+            .into_iter()
+            .map(|path| {
+                let relative = path.strip_prefix(&refs_dir).unwrap_or(&path);
+                ReferenceInfo {
+                    path: normalize_separators(&relative.to_string_lossy()),
+                    size: fs::metadata(&path).map(|m| m.len()).unwrap_or(0),
+                }
+            })
+            .collect();
 
-        let has_references = self.has_references(name);
+        for err in errors {
+            warn!("{}", err);
+        }
 
-        Ok(SkillContent::new(name.to_string(), content)
-            .with_sub_skills(sub_skills)
-            .with_references(has_references))
+        Ok(references)
     }
 
-    /// Read sub-skill content.
-    pub fn read_sub_skill_content(
-        &self,
-        domain: &str,
-        sub_skill: &str,
-    ) -> Result<SubSkillContent, IndexError> {
-        let meta = self
-            .get_skill_meta(domain)
-            .ok_or_else(|| IndexError::NotFound(format!("Skill '{}' not found", domain)))?;
-
-        let sub_meta = meta.find_sub_skill(sub_skill).ok_or_else(|| {
-            IndexError::NotFound(format!(
-                "Sub-skill '{}' not found in '{}'",
-                sub_skill, domain
-            ))
-        })?;
-
-        // Validate that the sub-skill file path doesn't escape the skill directory
-        let skill_dir = self.skills_dir.join(domain);
-        let file_path = validate_sub_skill_path(&skill_dir, &sub_meta.file)?;
+    /// Describe the scripts in a skill's `scripts/` directory.
+    ///
+    /// Paths are relative to the skill directory and sorted. Only the start
+    /// of each file is read, for language detection and the docstring.
+    pub fn list_scripts(&self, domain: &str) -> Result<Vec<SkillScript>, IndexError> {
+        if self.get_skill_meta(domain).is_none() {
+            return Err(IndexError::NotFound(format!(
+                "Skill '{}' not found",
+                domain
+            )));
+        }
 
-        let content = fs::read_to_string(&file_path).map_err(|e| {
-            IndexError::ReadError(format!("Failed to read {}: {}", file_path.display(), e))
-        })?;
+        let mut errors = Vec::new();
+        let scripts = self.collect_scripts(domain, &mut errors);
+        for err in errors {
+            warn!("{}", err);
+        }
 
-        Ok(SubSkillContent::new(
-            domain.to_string(),
-            sub_skill.to_string(),
-            content,
-        ))
+        Ok(scripts)
     }
 
-    /// Build the skill metadata index by scanning directories.
-    fn build_skill_index(&self) -> Result<SkillIndex, IndexError> {
-        let mut skills = Vec::new();
-        let mut errors = Vec::new();
-
-        if !self.skills_dir.exists() {
+    /// Describe the images in a skill's `references/` directory.
+    ///
+    /// Paths are relative to the skill directory and sorted. Dimensions are
+    /// read from each file's header; images are never decoded.
+    pub fn list_images(&self, domain: &str) -> Result<Vec<ImageAsset>, IndexError> {
+        if self.get_skill_meta(domain).is_none() {
             return Err(IndexError::NotFound(format!(
-                "Skills directory not found: {:?}",
-                self.skills_dir
+                "Skill '{}' not found",
+                domain
             )));
         }
 
-        // Read each subdirectory as a potential skill
-        let entries = fs::read_dir(&self.skills_dir).map_err(|e| {
-            IndexError::ReadError(format!(
-                "Failed to read skills directory {:?}: {}",
-                self.skills_dir, e
-            ))
-        })?;
+        let mut errors = Vec::new();
+        let images = self.collect_images(domain, &mut errors);
+        for err in errors {
+            warn!("{}", err);
+        }
 
-        for entry in entries.flatten() {
-            let path = entry.path();
+        Ok(images)
+    }
 
-            // Skip non-directories and hidden files
-            if !path.is_dir() {
-                continue;
+    /// Read a markdown document from a skill's `references/` directory.
+    ///
+    /// `path` is relative to `references/`. Content beyond
+    /// [`IndexerOptions::max_file_size`] is cut off and flagged as truncated.
+    pub fn read_reference(&self, domain: &str, path: &str) -> Result<ReferenceContent, IndexError> {
+        if !is_markdown(Path::new(path)) {
+            return Err(IndexError::ValidationError(format!(
+                "Reference path '{}' is not a markdown file",
+                path
+            )));
+        }
+
+        let file_path = self.resolve_skill_file(domain, &format!("references/{}", path))?;
+
+        let read_error = |e: std::io::Error| {
+            IndexError::ReadError(format!("Failed to read {}: {}", file_path.display(), e))
+        };
+        let size = fs::metadata(&file_path).map_err(read_error)?.len();
+        let limit = self.options.max_file_size;
+
+        let mut bytes = Vec::new();
+        fs::File::open(&file_path)
+            .and_then(|f| f.take(limit).read_to_end(&mut bytes))
+            .map_err(read_error)?;
+
+        let truncated = size > limit;
+        if truncated {
+            // Don't split a multi-byte character at the cut
+            if let Err(e) = std::str::from_utf8(&bytes) {
+                if e.error_len().is_none() {
+                    bytes.truncate(e.valid_up_to());
+                }
             }
+        }
 
-            let name = path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or_default();
+        Ok(ReferenceContent {
+            domain: domain.to_string(),
+            path: normalize_separators(path),
+            content: String::from_utf8_lossy(&bytes).into_owned(),
+            truncated,
+        })
+    }
 
-            if name.starts_with('.') || name.starts_with('_') {
-                continue;
+    /// List the built-in and custom skill templates, sorted by name.
+    ///
+    /// Custom templates are read from `_templates/*.md` and replace built-in
+    /// templates of the same name. Files that fail to parse are skipped with
+    /// a warning.
+    pub fn list_templates(&self) -> Vec<TemplateDefinition> {
+        let mut templates: Vec<TemplateDefinition> =
+            SkillTemplate::ALL.iter().map(|t| t.definition()).collect();
+
+        let dir = self.skills_dir.join(TEMPLATES_DIR);
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => {
+                templates.sort_by(|a, b| a.name.cmp(&b.name));
+                return templates;
             }
+        };
 
-            // Try to load _meta.json
-            let meta_path = path.join("_meta.json");
-            if !meta_path.exists() {
-                errors.push(format!("{}: Missing _meta.json", name));
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            if !is_markdown(&path) || stem.starts_with('.') || !path.is_file() {
                 continue;
             }
 
-            match self.load_meta(&meta_path) {
-                Ok(meta) => {
-                    // Validate the metadata
-                    if let Err(validation_errors) = validate_meta(&meta) {
-                        for err in validation_errors {
-                            errors.push(format!("{}: {}", name, err));
-                        }
-                    }
-                    skills.push(meta);
-                }
-                Err(e) => {
-                    errors.push(format!("{}: {}", name, e));
+            let parsed = fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|text| TemplateDefinition::parse(stem, &text));
+            match parsed {
+                Ok(template) => {
+                    templates.retain(|t| t.name != template.name);
+                    templates.push(template);
                 }
+                Err(e) => warn!("Skipping template {}: {}", path.display(), e),
             }
         }
 
-        // Sort skills by name
-        skills.sort_by(|a, b| a.name.cmp(&b.name));
-
-        debug!("Built skill index: {} skills, {} errors", skills.len(), errors.len());
+        templates.sort_by(|a, b| a.name.cmp(&b.name));
+        templates
+    }
 
-        Ok(SkillIndex::with_skills(skills, errors))
+    /// Look up a skill template by name.
+    ///
+    /// Built-in names may use `-` or `_` interchangeably.
+    pub fn load_template(&self, name: &str) -> Result<TemplateDefinition, IndexError> {
+        let templates = self.list_templates();
+        let builtin = SkillTemplate::from_name(name).map(|t| t.name());
+
+        templates
+            .iter()
+            .find(|t| t.name == name)
+            .or_else(|| templates.iter().find(|t| Some(t.name.as_str()) == builtin))
+            .cloned()
+            .ok_or_else(|| IndexError::NotFound(format!("Template '{}' not found", name)))
     }
 
-    /// Build the content index for full-text search.
-    fn build_content_index(&self, skill_index: &SkillIndex) -> Result<ContentIndex, IndexError> {
-        let mut content_index = ContentIndex::new();
+    /// Check that a namespaced skill can be created under `category`.
+    ///
+    /// Categories hold skill directories only, can't be skills themselves and
+    /// nest at most [`MAX_CATEGORY_DEPTH`] levels deep.
+    pub fn check_category(&self, category: &str, flat: bool) -> Result<(), IndexError> {
+        if flat {
+            return Err(IndexError::ValidationError(
+                "Flat-file skills must be at the top level".to_string(),
+            ));
+        }
 
-        for skill in &skill_index.skills {
-            // Index main SKILL.md
-            let skill_md = self.skills_dir.join(&skill.name).join("SKILL.md");
-            if skill_md.exists() {
-                if let Ok(content) = fs::read_to_string(&skill_md) {
-                    content_index.insert(ContentIndexEntry::new(
-                        skill.name.clone(),
-                        None,
-                        "SKILL.md".to_string(),
-                        content,
-                    ));
-                }
-            }
+        if category.split(CATEGORY_SEPARATOR).count() > MAX_CATEGORY_DEPTH {
+            return Err(IndexError::ValidationError(format!(
+                "Categories nest at most {} levels deep",
+                MAX_CATEGORY_DEPTH
+            )));
+        }
 
-            // Index sub-skills
-            if let Some(sub_skills) = &skill.sub_skills {
-                for sub in sub_skills {
-                    let sub_path = self.skills_dir.join(&skill.name).join(&sub.file);
-                    if sub_path.exists() {
-                        if let Ok(content) = fs::read_to_string(&sub_path) {
-                            content_index.insert(ContentIndexEntry::new(
-                                skill.name.clone(),
-                                Some(sub.name.clone()),
-                                sub.file.clone(),
-                                content,
-                            ));
-                        }
-                    }
-                }
+        let prefixes = category
+            .match_indices(CATEGORY_SEPARATOR)
+            .map(|(end, _)| &category[..end])
+            .chain([category]);
+        for prefix in prefixes {
+            let dir = self.skills_dir.join(prefix);
+            if dir.join("_meta.json").exists() || dir.join("SKILL.md").exists() {
+                return Err(IndexError::ValidationError(format!(
+                    "'{}' is a skill, not a category",
+                    prefix
+                )));
             }
+        }
+
+        Ok(())
+    }
 
-            // Index references directory if present
-            let refs_dir = self.skills_dir.join(&skill.name).join("references");
-            if refs_dir.is_dir() {
-                self.index_directory(&mut content_index, &skill.name, &refs_dir);
+    /// Write a new skill to disk and reload the index.
+    ///
+    /// Creates a skill directory with `_meta.json`, `SKILL.md` and any
+    /// sub-skill files, or a single `name.md` with frontmatter when `flat`
+    /// is set. Returns the created files relative to the skills directory.
+    ///
+    /// Every path is checked before anything is written, and a skill
+    /// directory is assembled under a hidden staging name and renamed into
+    /// place, so a failure leaves no partial skill behind.
+    pub fn create_skill(
+        &self,
+        meta: &SkillMeta,
+        skill: &RenderedTemplate,
+        flat: bool,
+    ) -> Result<Vec<String>, IndexError> {
+        match self.prepare_create(meta, skill, flat)? {
+            PreparedSkill::Flat {
+                file,
+                path,
+                rendered,
+            } => {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent).map_err(|e| {
+                        IndexError::WriteError(format!(
+                            "Failed to create {}: {}",
+                            parent.display(),
+                            e
+                        ))
+                    })?;
+                }
+                fs::write(&path, &rendered).map_err(|e| {
+                    IndexError::WriteError(format!("Failed to write {}: {}", path.display(), e))
+                })?;
+                self.reload()?;
+                Ok(vec![file])
             }
+            PreparedSkill::Dir { meta, files } => self.install_skill(&meta, &files),
         }
+    }
 
-        debug!("Built content index: {} entries", content_index.len());
-
-        Ok(content_index)
+    /// Make every check [`create_skill`](Self::create_skill) does without
+    /// writing anything. Returns the files it would create.
+    pub fn check_create(
+        &self,
+        meta: &SkillMeta,
+        skill: &RenderedTemplate,
+        flat: bool,
+    ) -> Result<Vec<String>, IndexError> {
+        match self.prepare_create(meta, skill, flat)? {
+            PreparedSkill::Flat { file, .. } => Ok(vec![file]),
+            PreparedSkill::Dir { meta, files } => self.check_install(&meta, &files),
+        }
     }
 
-    /// Index all markdown files in a directory.
-    fn index_directory(&self, index: &mut ContentIndex, domain: &str, dir: &Path) {
-        for entry in WalkDir::new(dir)
-            .follow_links(true)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
-            let path = entry.path();
+    /// Check a new skill and lay out what creating it writes.
+    fn prepare_create(
+        &self,
+        meta: &SkillMeta,
+        skill: &RenderedTemplate,
+        flat: bool,
+    ) -> Result<PreparedSkill, IndexError> {
+        check_skill_name(&meta.name).map_err(IndexError::ValidationError)?;
+        if self.skill_exists(&meta.name) {
+            return Err(IndexError::AlreadyExists(format!(
+                "Skill '{}' already exists",
+                meta.name
+            )));
+        }
+        if let Some((category, _)) = meta.name.rsplit_once(CATEGORY_SEPARATOR) {
+            self.check_category(category, flat)?;
+        }
 
-            if !path.is_file() {
-                continue;
+        let mut meta = meta.clone();
+
+        if flat {
+            if !skill.sub_skills.is_empty() {
+                return Err(IndexError::ValidationError(
+                    "Flat-file skills can't have sub-skills".to_string(),
+                ));
             }
+            let file = format!("{}.md", meta.name);
+            let path = self.skills_dir.join(&file);
+            let rendered = render_flat_skill(&meta, &skill.content);
+            self.check_quota(&meta.name, &[(path.clone(), rendered.len() as u64)])?;
+            return Ok(PreparedSkill::Flat {
+                file,
+                path,
+                rendered,
+            });
+        }
 
-            let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
-            if ext != "md" && ext != "markdown" {
-                continue;
+        let mut files = vec![("SKILL.md".to_string(), skill.content.clone().into_bytes())];
+        for (sub, content) in &skill.sub_skills {
+            let file = check_relative_path(&sub.file)?;
+            let taken = file.eq_ignore_ascii_case("_meta.json")
+                || files.iter().any(|(f, _)| f.eq_ignore_ascii_case(&file));
+            if taken {
+                return Err(IndexError::ValidationError(format!(
+                    "Sub-skill '{}' uses a file that is already taken: {}",
+                    sub.name, file
+                )));
             }
+            files.push((file, content.clone().into_bytes()));
+        }
+        if !skill.sub_skills.is_empty() {
+            meta.sub_skills = Some(skill.sub_skills.iter().map(|(s, _)| s.clone()).collect());
+        }
 
-            if let Ok(content) = fs::read_to_string(path) {
-                let relative = path
-                    .strip_prefix(&self.skills_dir.join(domain))
-                    .unwrap_or(path);
+        Ok(PreparedSkill::Dir {
+            meta: Box::new(meta),
+            files,
+        })
+    }
 
-                index.insert(ContentIndexEntry::new(
-                    domain.to_string(),
-                    None,
-                    relative.to_string_lossy().to_string(),
-                    content,
-                ));
+    /// Create a skill directory from `meta` and a set of files.
+    ///
+    /// `files` pairs a path relative to the skill directory with its
+    /// contents; `_meta.json` is written from `meta`. The directory is
+    /// staged and renamed into place, so a failed install leaves nothing
+    /// behind. Returns the created files, relative to the skills directory.
+    pub fn install_skill(
+        &self,
+        meta: &SkillMeta,
+        files: &[(String, Vec<u8>)],
+    ) -> Result<Vec<String>, IndexError> {
+        let targets = self.install_targets(meta, files)?;
+        let skill_dir = self.skills_dir.join(&meta.name);
+
+        let write = |path: &Path, content: &[u8]| {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(|e| {
+                    IndexError::WriteError(format!("Failed to create {}: {}", parent.display(), e))
+                })?;
             }
+            fs::write(path, content).map_err(|e| {
+                IndexError::WriteError(format!("Failed to write {}: {}", path.display(), e))
+            })
+        };
+
+        let leaf = meta
+            .name
+            .rsplit(CATEGORY_SEPARATOR)
+            .next()
+            .unwrap_or(&meta.name);
+        let staging = skill_dir.with_file_name(format!(".{}.tmp", leaf));
+        if staging.exists() {
+            let _ = fs::remove_dir_all(&staging);
         }
-    }
 
-    /// Load and parse _meta.json file.
-    fn load_meta(&self, path: &Path) -> Result<SkillMeta, IndexError> {
-        let content = fs::read_to_string(path)
-            .map_err(|e| IndexError::ReadError(format!("Failed to read {:?}: {}", path, e)))?;
+        let staged = targets
+            .iter()
+            .try_for_each(|(file, content)| write(&staging.join(file), content))
+            .and_then(|_| {
+                fs::rename(&staging, &skill_dir).map_err(|e| {
+                    IndexError::WriteError(format!(
+                        "Failed to create {}: {}",
+                        skill_dir.display(),
+                        e
+                    ))
+                })
+            });
+        if let Err(e) = staged {
+            let _ = fs::remove_dir_all(&staging);
+            return Err(e);
+        }
 
-        serde_json::from_str(&content).map_err(|e| {
-            IndexError::ParseError(format!("Failed to parse {:?}: {}", path, e))
-        })
+        let created = targets
+            .into_iter()
+            .map(|(file, _)| format!("{}/{}", meta.name, file))
+            .collect();
+
+        self.reload()?;
+        Ok(created)
     }
-}
+
+    /// Make every check [`install_skill`](Self::install_skill) does without
+    /// writing anything. Returns the files it would create.
+    pub fn check_install(
+        &self,
+        meta: &SkillMeta,
+        files: &[(String, Vec<u8>)],
+    ) -> Result<Vec<String>, IndexError> {
+        Ok(self
+            .install_targets(meta, files)?
+            .into_iter()
+            .map(|(file, _)| format!("{}/{}", meta.name, file))
+            .collect())
+    }
+
+    /// Check a skill directory can be installed, returning each file to
+    /// write relative to it, `_meta.json` first.
+    fn install_targets<'a>(
+        &self,
+        meta: &SkillMeta,
+        files: &'a [(String, Vec<u8>)],
+    ) -> Result<Vec<InstallTarget<'a>>, IndexError> {
+        check_skill_name(&meta.name).map_err(IndexError::ValidationError)?;
+        if self.skill_exists(&meta.name) {
+            return Err(IndexError::AlreadyExists(format!(
+                "Skill '{}' already exists",
+                meta.name
+            )));
+        }
+        if let Some((category, _)) = meta.name.rsplit_once(CATEGORY_SEPARATOR) {
+            self.check_category(category, false)?;
+        }
+
+        let json = serde_json::to_string_pretty(meta)
+            .map_err(|e| IndexError::WriteError(format!("Failed to serialize meta: {}", e)))?;
+        let mut targets = vec![("_meta.json".to_string(), Cow::Owned(json.into_bytes()))];
+        for (file, content) in files {
+            let file = check_relative_path(file)?;
+            if targets.iter().any(|(f, _)| f.eq_ignore_ascii_case(&file)) {
+                return Err(IndexError::ValidationError(format!(
+                    "File given more than once: {}",
+                    file
+                )));
+            }
+            targets.push((file, Cow::Borrowed(content.as_slice())));
+        }
+
+        let skill_dir = self.skills_dir.join(&meta.name);
+        let writes: Vec<(PathBuf, u64)> = targets
+            .iter()
+            .map(|(file, content)| (skill_dir.join(file), content.len() as u64))
+            .collect();
+        self.check_quota(&meta.name, &writes)?;
+
+        Ok(targets)
+    }
+
+    /// Locate the markdown documents in the content index on disk.
+    ///
+    /// Returns `(domain, file, path)` for each document, sorted, limited
+    /// to one skill if `domain` is given. Indexed content is lowercased, so
+    /// anything that needs the original text reads it from `path`.
+    pub fn indexed_markdown_files(&self, domain: Option<&str>) -> Vec<(String, String, PathBuf)> {
+        let index = self.index.read();
+        let mut files: Vec<(String, String, PathBuf)> = index
+            .content_index
+            .entries
+            .values()
+            .filter(|entry| domain.is_none_or(|d| entry.domain == d))
+            .filter(|entry| entry.file.ends_with(".md") || entry.file.ends_with(".markdown"))
+            .map(|entry| {
+                let path = self
+                    .flat_skill_path(&entry.domain)
+                    .unwrap_or_else(|| self.skills_dir.join(&entry.domain).join(&entry.file));
+                (entry.domain.clone(), entry.file.clone(), path)
+            })
+            .collect();
+
+        files.sort();
+        files.dedup();
+        files
+    }
+
+    /// Write files into a skill's `references/` directory, replacing any
+    /// already there, and reindex the skill.
+    ///
+    /// Images go to the shared asset store and are mapped into the skill
+    /// rather than copied into it.
+    ///
+    /// `files` pairs a path relative to `references/` with its contents.
+    /// Paths are checked like sub-skill paths and must not resolve outside
+    /// the skill through symbolic links. Either every file is within the
+    /// quota and written, or none is. Returns the written paths, relative to
+    /// the skill directory.
+    pub fn add_references(
+        &self,
+        name: &str,
+        files: &[(String, Vec<u8>)],
+    ) -> Result<Vec<String>, IndexError> {
+        let skill_dir = self.skills_dir.join(name);
+        if self.get_skill_meta(name).is_none() || !skill_dir.is_dir() {
+            return Err(IndexError::NotFound(format!(
+                "Skill '{}' not found (flat-file skills have no references)",
+                name
+            )));
+        }
+        let canonical_skill_dir = skill_dir.canonicalize().map_err(|e| {
+            IndexError::ReadError(format!("Failed to resolve {}: {}", skill_dir.display(), e))
+        })?;
+
+        let mut targets: Vec<(String, PathBuf)> = Vec::new();
+        for (file, _) in files {
+            let relative = format!("references/{}", check_relative_path(file)?);
+            if relative.ends_with('/') {
+                return Err(IndexError::ValidationError(format!(
+                    "File path names a directory: {}",
+                    file
+                )));
+            }
+            if targets
+                .iter()
+                .any(|(taken, _)| taken.eq_ignore_ascii_case(&relative))
+            {
+                return Err(IndexError::ValidationError(format!(
+                    "File uploaded twice: {}",
+                    file
+                )));
+            }
+
+            // The deepest existing ancestor decides where the file really lands
+            let path = skill_dir.join(&relative);
+            let existing = path
+                .ancestors()
+                .skip(1)
+                .find(|a| a.exists())
+                .and_then(|a| a.canonicalize().ok());
+            if !existing.is_some_and(|a| a.starts_with(&canonical_skill_dir)) || path.is_dir() {
+                return Err(IndexError::ValidationError(format!(
+                    "File path escapes skill directory: {}",
+                    file
+                )));
+            }
+            targets.push((relative, path));
+        }
+
+        let writes: Vec<(PathBuf, u64)> = targets
+            .iter()
+            .zip(files)
+            .map(|((_, path), (_, bytes))| (path.clone(), bytes.len() as u64))
+            .collect();
+        self.check_quota(name, &writes)?;
+
+        let assets = AssetStore::new(&self.skills_dir);
+        let mut asset_map = read_asset_map(&skill_dir);
+        let mapped = asset_map.clone();
+        for ((relative, path), (_, bytes)) in targets.iter().zip(files) {
+            if is_binary_asset(relative) {
+                let hash = assets.put(bytes).map_err(|e| {
+                    IndexError::WriteError(format!("Failed to store {}: {}", relative, e))
+                })?;
+                if path.is_file() {
+                    fs::remove_file(path).map_err(|e| {
+                        IndexError::WriteError(format!("Failed to replace {}: {}", relative, e))
+                    })?;
+                }
+                asset_map.insert(relative.clone(), hash);
+            } else {
+                write_file_atomic(path, bytes)?;
+                asset_map.remove(relative);
+            }
+        }
+        if asset_map != mapped {
+            write_asset_map(&skill_dir, &asset_map).map_err(|e| {
+                IndexError::WriteError(format!("Failed to write {}: {}", ASSET_MAP_FILE, e))
+            })?;
+        }
+
+        self.update_skill(name)?;
+        Ok(targets.into_iter().map(|(relative, _)| relative).collect())
+    }
+
+    /// Resolve a file inside a skill directory for direct reading.
+    ///
+    /// `file` is relative to the skill directory. The path must not escape
+    /// the skill directory or be excluded by `.skillsignore`. Mapped assets
+    /// resolve to their shared blob.
+    pub fn resolve_skill_file(&self, name: &str, file: &str) -> Result<PathBuf, IndexError> {
+        if self.get_skill_meta(name).is_none() {
+            return Err(IndexError::NotFound(format!("Skill '{}' not found", name)));
+        }
+
+        let skill_dir = self.skills_dir.join(name);
+        let normalized = check_relative_path(file)?;
+        if let Some(hash) = read_asset_map(&skill_dir).get(&normalized) {
+            let blob = AssetStore::new(&self.skills_dir).path(hash);
+            if blob.is_file() && !self.is_ignored(&skill_dir.join(&normalized), false) {
+                return Ok(blob);
+            }
+        }
+
+        let path = validate_sub_skill_path(&skill_dir, file)?;
+
+        if !path.is_file() || self.is_ignored(&skill_dir.join(normalize_separators(file)), false) {
+            return Err(IndexError::NotFound(format!(
+                "File '{}' not found in '{}'",
+                file, name
+            )));
+        }
+
+        Ok(path)
+    }
+
+    /// Read main SKILL.md content for a skill.
+    pub fn read_skill_content(&self, name: &str) -> Result<SkillContent, IndexError> {
+        if let Some(path) = self.flat_skill_path(name) {
+            let (text, _) = read_lossy(&path).map_err(|e| {
+                IndexError::ReadError(format!("Failed to read {}: {}", path.display(), e))
+            })?;
+            let body = split_frontmatter(&text)
+                .map(|(_, body)| body)
+                .unwrap_or(&text);
+            return Ok(SkillContent::new(name.to_string(), body.to_string()));
+        }
+
+        let skill_dir = self.skills_dir.join(name);
+        let skill_md = skill_dir.join("SKILL.md");
+
+        if !skill_md.exists() {
+            return Err(IndexError::NotFound(format!(
+                "SKILL.md not found for '{}'",
+                name
+            )));
+        }
+
+        let (content, lossy) = read_lossy(&skill_md).map_err(|e| {
+            IndexError::ReadError(format!("Failed to read {}: {}", skill_md.display(), e))
+        })?;
+        if lossy {
+            warn!(
+                "{} is not valid UTF-8; invalid bytes were replaced",
+                skill_md.display()
+            );
+        }
+
+        let meta = self.get_skill_meta(name);
+        let sub_skills = meta
+            .as_ref()
+            .map(|m| m.sub_skill_paths())
+            .unwrap_or_default();
+
+        let has_references = self.has_references(name);
+
+        let mut errors = Vec::new();
+        let scripts = self.collect_scripts(name, &mut errors);
+        let images = self.collect_images(name, &mut errors);
+        for err in errors {
+            warn!("{}", err);
+        }
+
+        Ok(SkillContent::new(name.to_string(), content)
+            .with_sub_skills(sub_skills)
+            .with_references(has_references)
+            .with_scripts(scripts)
+            .with_images(images))
+    }
+
+    /// Read sub-skill content.
+    ///
+    /// `sub_skill` is either a sub-skill name from metadata, a path to a
+    /// nested one (e.g. `react/hooks`), or a markdown file path relative to
+    /// the skill directory (e.g. `react/advanced.md`), so links inside
+    /// SKILL.md can be followed to undeclared files. A sub-skill that refers
+    /// to another skill's sub-skill is read from there.
+    pub fn read_sub_skill_content(
+        &self,
+        domain: &str,
+        sub_skill: &str,
+    ) -> Result<SubSkillContent, IndexError> {
+        let file_path = self.resolve_sub_skill_file(domain, sub_skill)?;
+
+        let (content, lossy) = read_lossy(&file_path).map_err(|e| {
+            IndexError::ReadError(format!("Failed to read {}: {}", file_path.display(), e))
+        })?;
+        if lossy {
+            warn!(
+                "{} is not valid UTF-8; invalid bytes were replaced",
+                file_path.display()
+            );
+        }
+
+        Ok(SubSkillContent::new(
+            domain.to_string(),
+            sub_skill.to_string(),
+            content,
+        ))
+    }
+
+    /// Resolve the markdown file behind a sub-skill, following references
+    /// to other skills' sub-skills.
+    ///
+    /// Fails if a reference's target doesn't exist or the references loop.
+    pub fn resolve_sub_skill_file(
+        &self,
+        domain: &str,
+        sub_skill: &str,
+    ) -> Result<PathBuf, IndexError> {
+        self.follow_sub_skill(domain, sub_skill, &mut Vec::new())
+    }
+
+    /// Resolve a sub-skill's file; `chain` holds the `domain:sub-skill`
+    /// references already followed to get here.
+    fn follow_sub_skill(
+        &self,
+        domain: &str,
+        sub_skill: &str,
+        chain: &mut Vec<String>,
+    ) -> Result<PathBuf, IndexError> {
+        let meta = self
+            .get_skill_meta(domain)
+            .ok_or_else(|| IndexError::NotFound(format!("Skill '{}' not found", domain)))?;
+
+        let file_path = match meta.find_sub_skill(sub_skill) {
+            Some(sub_meta) if sub_meta.reference.is_some() => {
+                let (target, target_sub) = sub_meta.reference_target().ok_or_else(|| {
+                    IndexError::ValidationError(format!(
+                        "Sub-skill '{}' of '{}' has an invalid ref '{}'",
+                        sub_skill,
+                        domain,
+                        sub_meta.reference.as_deref().unwrap_or_default()
+                    ))
+                })?;
+                let target = self
+                    .resolve_alias(target)
+                    .unwrap_or_else(|| target.to_string());
+                chain.push(format!(
+                    "{}{}{}",
+                    domain, SUB_SKILL_REF_SEPARATOR, sub_skill
+                ));
+                let next = format!("{}{}{}", target, SUB_SKILL_REF_SEPARATOR, target_sub);
+                if chain.contains(&next) {
+                    chain.push(next);
+                    return Err(IndexError::ValidationError(format!(
+                        "Sub-skill references form a cycle: {}",
+                        chain.join(" -> ")
+                    )));
+                }
+                return self.follow_sub_skill(&target, target_sub, chain);
+            }
+            Some(sub_meta) => {
+                // Validate that the sub-skill file path doesn't escape the skill directory
+                let skill_dir = self.skills_dir.join(domain);
+                validate_sub_skill_path(&skill_dir, &sub_meta.file)?
+            }
+            // Without an extension a slash-separated name is a nested
+            // sub-skill id, not a file
+            None if is_file_reference(sub_skill) && Path::new(sub_skill).extension().is_some() => {
+                if !is_markdown(Path::new(sub_skill)) {
+                    return Err(IndexError::ValidationError(format!(
+                        "Sub-skill path '{}' is not a markdown file",
+                        sub_skill
+                    )));
+                }
+                self.resolve_skill_file(domain, sub_skill)?
+            }
+            None => {
+                return Err(IndexError::NotFound(format!(
+                    "Sub-skill '{}' not found in '{}'",
+                    sub_skill, domain
+                )))
+            }
+        };
+
+        Ok(file_path)
+    }
+
+    /// Build the skill metadata index by scanning directories.
+    ///
+    /// A directory with neither `_meta.json` nor `SKILL.md` but with
+    /// subdirectories is a category: its skills are namespaced by path,
+    /// e.g. `frontend/forms`, up to [`MAX_CATEGORY_DEPTH`] levels deep.
+    fn build_skill_index(&self) -> Result<SkillIndex, IndexError> {
+        let mut skills = Vec::new();
+        let mut errors = Vec::new();
+
+        if !self.skills_dir.exists() {
+            return Err(IndexError::NotFound(format!(
+                "Skills directory not found: {:?}",
+                self.skills_dir
+            )));
+        }
+
+        let canonical_root = self
+            .skills_dir
+            .canonicalize()
+            .unwrap_or_else(|_| self.skills_dir.clone());
+        let mut seen_dirs: HashMap<PathBuf, String> = HashMap::new();
+        let mut flat_files = Vec::new();
+
+        // Directories to scan, with the category prefix for skills inside them
+        let mut pending = VecDeque::from([(self.skills_dir.clone(), String::new())]);
+
+        while let Some((dir, category)) = pending.pop_front() {
+            // Read each subdirectory as a potential skill
+            let entries = match fs::read_dir(&dir) {
+                Ok(entries) => entries,
+                Err(e) if category.is_empty() => {
+                    return Err(IndexError::ReadError(format!(
+                        "Failed to read skills directory {:?}: {}",
+                        dir, e
+                    )))
+                }
+                Err(e) => {
+                    errors.push(format!("{}: Failed to read category: {}", category, e));
+                    continue;
+                }
+            };
+
+            // Sort entries so duplicate resolution is deterministic
+            let mut entries: Vec<_> = entries.flatten().collect();
+            entries.sort_by_key(|e| e.file_name());
+
+            for entry in entries {
+                let path = entry.path();
+
+                let linked = entry.file_type().map(|t| t.is_symlink()).unwrap_or(false);
+                if linked && !self.options.follow_links {
+                    debug!("Skipping symlinked skill {:?} (links not followed)", path);
+                    continue;
+                }
+
+                // Markdown files at the root may be flat-file skills; skip other files
+                if !path.is_dir() {
+                    if category.is_empty() && path.is_file() && is_markdown(&path) {
+                        flat_files.push(path);
+                    }
+                    continue;
+                }
+
+                let dir_name = path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or_default();
+
+                if dir_name.starts_with('.') || dir_name.starts_with('_') {
+                    continue;
+                }
+
+                let name = if category.is_empty() {
+                    dir_name.to_string()
+                } else {
+                    format!("{}{}{}", category, CATEGORY_SEPARATOR, dir_name)
+                };
+                let name = name.as_str();
+
+                if self.is_ignored(Path::new(name), true) {
+                    debug!("Skipping {} (excluded by .skillsignore)", name);
+                    continue;
+                }
+
+                // Resolve symlinks so the same directory is never indexed twice
+                let canonical = path.canonicalize().unwrap_or_else(|_| path.clone());
+                if canonical_root.starts_with(&canonical) {
+                    errors.push(format!(
+                        "{}: Symlink cycle (resolves to {:?}, which contains the skills directory)",
+                        name, canonical
+                    ));
+                    continue;
+                }
+                if let Some(first) = seen_dirs.get(&canonical) {
+                    errors.push(format!(
+                        "{}: Duplicate of '{}' (both resolve to {:?})",
+                        name, first, canonical
+                    ));
+                    continue;
+                }
+                seen_dirs.insert(canonical, name.to_string());
+
+                // Try to load _meta.json
+                let meta_path = path.join("_meta.json");
+                if !meta_path.exists() {
+                    if is_category_dir(&path) {
+                        let depth = name.split(CATEGORY_SEPARATOR).count();
+                        if depth > MAX_CATEGORY_DEPTH {
+                            errors.push(format!(
+                                "{}: Categories nest more than {} levels deep",
+                                name, MAX_CATEGORY_DEPTH
+                            ));
+                        } else {
+                            pending.push_back((path, name.to_string()));
+                        }
+                    } else {
+                        errors.push(format!("{}: Missing _meta.json", name));
+                    }
+                    continue;
+                }
+
+                match self.load_meta(&meta_path) {
+                    Ok(meta) => {
+                        // Validate the metadata
+                        if let Err(validation_errors) = validate_meta(&meta) {
+                            for err in validation_errors {
+                                errors.push(format!("{}: {}", name, err));
+                            }
+                        }
+                        // Skills are found on disk by name, so it must match the path
+                        if meta.name != name {
+                            errors.push(format!(
+                                "{}: name '{}' does not match directory path '{}'",
+                                name, meta.name, name
+                            ));
+                            // A nested skill under another name would be filed
+                            // in the wrong category and never found on disk
+                            if !category.is_empty() {
+                                continue;
+                            }
+                        }
+                        skills.push(meta);
+                    }
+                    Err(e) => {
+                        errors.push(format!("{}: {}", name, e));
+                    }
+                }
+            }
+        }
+
+        for path in flat_files {
+            let file_name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or_default();
+
+            if file_name.starts_with('.') || file_name.starts_with('_') {
+                continue;
+            }
+
+            if self.is_ignored(Path::new(file_name), false) {
+                debug!("Skipping {} (excluded by .skillsignore)", file_name);
+                continue;
+            }
+
+            match self.load_flat_meta(&path) {
+                Ok(Some(meta)) => {
+                    if path.with_extension("").is_dir() {
+                        errors.push(format!(
+                            "{}: Both a skill directory and {} exist; using the directory",
+                            meta.name, file_name
+                        ));
+                        continue;
+                    }
+                    if let Err(validation_errors) = validate_meta(&meta) {
+                        for err in validation_errors {
+                            errors.push(format!("{}: {}", meta.name, err));
+                        }
+                    }
+                    skills.push(meta);
+                }
+                // Plain markdown such as a README is not a skill
+                Ok(None) => debug!("Skipping {} (no frontmatter)", file_name),
+                Err(e) => errors.push(format!("{}: {}", file_name, e)),
+            }
+        }
+
+        // Surface problems with the ignore file alongside metadata errors
+        errors.extend(self.ignore.read().errors().iter().cloned());
+
+        // Sort skills by name
+        skills.sort_by(|a, b| a.name.cmp(&b.name));
+
+        debug!(
+            "Built skill index: {} skills, {} errors",
+            skills.len(),
+            errors.len()
+        );
+
+        Ok(SkillIndex::with_skills(skills, errors))
+    }
+
+    /// Summarize a skill's main document with the configured summarizer.
+    fn summarize(&self, name: &str, markdown: &str) -> Option<String> {
+        self.summarizer.as_ref()?.summarize(name, markdown)
+    }
+
+    /// Build the content index for full-text search.
+    ///
+    /// Problems found while walking reference directories (such as symlink
+    /// cycles) are appended to `errors`; documents whose text could not be
+    /// extracted are reported in `warnings`. `progress` is called after
+    /// each skill with the number scanned so far and the total.
+    fn build_content_index(
+        &self,
+        skill_index: &SkillIndex,
+        errors: &mut Vec<String>,
+        warnings: &mut Vec<String>,
+        progress: &dyn Fn(usize, usize),
+    ) -> Result<ContentIndex, IndexError> {
+        let mut content_index = ContentIndex::new();
+
+        let total = skill_index.skills.len();
+        for (scanned, skill) in skill_index.skills.iter().enumerate() {
+            for entry in self.build_skill_entries(skill, errors, warnings) {
+                content_index.insert(entry);
+            }
+            progress(scanned + 1, total);
+        }
+
+        debug!("Built content index: {} entries", content_index.len());
+
+        Ok(content_index)
+    }
+
+    /// Build the content index entries for a single skill.
+    ///
+    /// Covers a synthetic metadata entry, the main SKILL.md, declared
+    /// sub-skill files, and documents under `references/` (see
+    /// [`ReferenceFormat`]) that aren't already indexed as sub-skills.
+    fn build_skill_entries(
+        &self,
+        skill: &SkillMeta,
+        errors: &mut Vec<String>,
+        warnings: &mut Vec<String>,
+    ) -> Vec<ContentIndexEntry> {
+        let skill_dir = self.skills_dir.join(&skill.name);
+        let mut entries = vec![ContentIndexEntry::from_meta(skill)];
+
+        // A flat-file skill is a single document without sub-skills or references
+        if let Some(path) = self.flat_skill_path(&skill.name) {
+            let file = format!("{}.md", skill.name);
+            if let Some(text) = self.read_for_index(&skill.name, &path, &file, errors) {
+                let body = split_frontmatter(&text)
+                    .map(|(_, body)| body)
+                    .unwrap_or(&text);
+                let summary = self.summarize(&skill.name, body);
+                entries.push(
+                    ContentIndexEntry::new(skill.name.clone(), None, file, body.to_string())
+                        .with_summary(summary),
+                );
+            }
+            return self.stamp_modified(skill, entries);
+        }
+
+        // Index main SKILL.md
+        let skill_md = skill_dir.join("SKILL.md");
+        if skill_md.exists() && !self.is_ignored(&skill_md, false) {
+            if let Some(content) = self.read_for_index(&skill.name, &skill_md, "SKILL.md", errors) {
+                let summary = self.summarize(&skill.name, &content);
+                entries.push(
+                    ContentIndexEntry::new(
+                        skill.name.clone(),
+                        None,
+                        "SKILL.md".to_string(),
+                        content,
+                    )
+                    .with_summary(summary),
+                );
+            }
+        }
+
+        // Index sub-skills
+        let mut sub_skill_files = HashSet::new();
+        for (path, sub) in skill.all_sub_skills() {
+            // References are indexed with the skill they point at
+            if sub.reference.is_some() {
+                continue;
+            }
+            let sub_path = skill_dir.join(&sub.file);
+            if sub_path.exists() && !self.is_ignored(&sub_path, false) {
+                if let Some(content) =
+                    self.read_for_index(&skill.name, &sub_path, &sub.file, errors)
+                {
+                    sub_skill_files.insert(sub.file.as_str());
+                    entries.push(ContentIndexEntry::new(
+                        skill.name.clone(),
+                        Some(path),
+                        sub.file.clone(),
+                        content,
+                    ));
+                }
+            }
+        }
+
+        // Index script names and docstrings so agents can find bundled tools
+        for script in self.collect_scripts(&skill.name, errors) {
+            let text = script.index_text();
+            entries.push(ContentIndexEntry::new(
+                skill.name.clone(),
+                None,
+                script.path,
+                text,
+            ));
+        }
+
+        // Index references directory if present
+        let refs_dir = skill_dir.join("references");
+        if refs_dir.is_dir() {
+            let indexable = |path: &Path| ReferenceFormat::from_path(path).is_some();
+            for path in self.collect_files(&skill.name, &refs_dir, errors, indexable) {
+                let relative = path.strip_prefix(&skill_dir).unwrap_or(&path);
+                let relative = normalize_separators(&relative.to_string_lossy());
+
+                // Sub-skills often live under references/; don't index them twice
+                if sub_skill_files.contains(relative.as_str()) {
+                    continue;
+                }
+
+                let format = ReferenceFormat::from_path(&path).unwrap_or(ReferenceFormat::Markdown);
+                let content = if format == ReferenceFormat::Markdown {
+                    self.read_for_index(&skill.name, &path, &relative, errors)
+                } else {
+                    self.extract_for_index(&skill.name, &path, &relative, format, errors, warnings)
+                };
+                let Some(content) = content else {
+                    continue;
+                };
+
+                entries.push(ContentIndexEntry::new(
+                    skill.name.clone(),
+                    None,
+                    relative,
+                    content,
+                ));
+            }
+        }
+
+        self.stamp_modified(skill, entries)
+    }
+
+    /// Record when each entry's file, or `_meta.json` for the metadata
+    /// entry, was last modified.
+    fn stamp_modified(
+        &self,
+        skill: &SkillMeta,
+        mut entries: Vec<ContentIndexEntry>,
+    ) -> Vec<ContentIndexEntry> {
+        let flat_path = self.flat_skill_path(&skill.name);
+        let skill_dir = self.skills_dir.join(&skill.name);
+        for entry in &mut entries {
+            let path = flat_path
+                .clone()
+                .unwrap_or_else(|| skill_dir.join(&entry.file));
+            entry.modified = fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .ok()
+                .map(chrono::DateTime::from);
+        }
+        entries
+    }
+
+    /// Read a file for the content index.
+    ///
+    /// Files over [`IndexerOptions::max_file_size`] are skipped and invalid
+    /// UTF-8 is decoded lossily; both are reported in `errors`.
+    fn read_for_index(
+        &self,
+        domain: &str,
+        path: &Path,
+        file: &str,
+        errors: &mut Vec<String>,
+    ) -> Option<String> {
+        let size = match fs::metadata(path) {
+            Ok(m) => m.len(),
+            Err(e) => {
+                errors.push(format!("{}: Failed to read {}: {}", domain, file, e));
+                return None;
+            }
+        };
+
+        if size > self.options.max_file_size {
+            errors.push(format!(
+                "{}: Skipped {} ({} bytes exceeds the {} byte index limit)",
+                domain, file, size, self.options.max_file_size
+            ));
+            return None;
+        }
+
+        match self.before_read(path).and_then(|_| read_lossy(path)) {
+            Ok((content, false)) => Some(content),
+            Ok((content, true)) => {
+                errors.push(format!(
+                    "{}: {} is not valid UTF-8; invalid bytes were replaced",
+                    domain, file
+                ));
+                Some(content)
+            }
+            Err(e) => {
+                errors.push(format!("{}: Failed to read {}: {}", domain, file, e));
+                None
+            }
+        }
+    }
+
+    /// Read a non-markdown reference and extract its text for the index.
+    ///
+    /// Size and read problems go to `errors` as for markdown; a document
+    /// that can't be converted is reported in `warnings` and left out.
+    fn extract_for_index(
+        &self,
+        domain: &str,
+        path: &Path,
+        file: &str,
+        format: ReferenceFormat,
+        errors: &mut Vec<String>,
+        warnings: &mut Vec<String>,
+    ) -> Option<String> {
+        let bytes = if format.is_text() {
+            self.read_for_index(domain, path, file, errors)?
+                .into_bytes()
+        } else {
+            let size = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+            if size > self.options.max_file_size {
+                errors.push(format!(
+                    "{}: Skipped {} ({} bytes exceeds the {} byte index limit)",
+                    domain, file, size, self.options.max_file_size
+                ));
+                return None;
+            }
+            match self.before_read(path).and_then(|_| fs::read(path)) {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    errors.push(format!("{}: Failed to read {}: {}", domain, file, e));
+                    return None;
+                }
+            }
+        };
+
+        match format.extract(&bytes) {
+            Ok(text) => Some(text),
+            Err(e) => {
+                warn!("{}: Failed to extract {}: {}", domain, file, e);
+                warnings.push(format!("{}: Failed to extract {}: {}", domain, file, e));
+                None
+            }
+        }
+    }
+
+    /// Collect the markdown files under a directory.
+    ///
+    /// Honors `.skillsignore` and [`IndexerOptions::follow_links`]. A file
+    /// reachable through several paths (e.g. a symlink next to its target)
+    /// is returned once, and symlink cycles are reported in `errors`
+    /// instead of being walked.
+    fn collect_markdown_files(
+        &self,
+        domain: &str,
+        dir: &Path,
+        errors: &mut Vec<String>,
+    ) -> Vec<PathBuf> {
+        self.collect_files(domain, dir, errors, is_markdown)
+    }
+
+    /// Describe the scripts under a skill's `scripts/` directory.
+    ///
+    /// Unreadable scripts are reported in `errors` and left out.
+    fn collect_scripts(&self, domain: &str, errors: &mut Vec<String>) -> Vec<SkillScript> {
+        let skill_dir = self.skills_dir.join(domain);
+        let scripts_dir = skill_dir.join("scripts");
+        if !scripts_dir.is_dir() {
+            return Vec::new();
+        }
+
+        self.collect_files(domain, &scripts_dir, errors, is_script_file)
+            .into_iter()
+            .filter_map(|path| {
+                let relative = path.strip_prefix(&skill_dir).unwrap_or(&path);
+                let relative = normalize_separators(&relative.to_string_lossy());
+
+                match read_head(&path, SCRIPT_HEAD_BYTES) {
+                    Ok((size, head)) => Some(SkillScript::from_source(
+                        relative,
+                        &String::from_utf8_lossy(&head),
+                        size,
+                    )),
+                    Err(e) => {
+                        errors.push(format!("{}: Failed to read {}: {}", domain, relative, e));
+                        None
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Describe the images under a skill's `references/` directory.
+    ///
+    /// Unreadable images are reported in `errors` and left out.
+    fn collect_images(&self, domain: &str, errors: &mut Vec<String>) -> Vec<ImageAsset> {
+        let skill_dir = self.skills_dir.join(domain);
+        let refs_dir = skill_dir.join("references");
+        // Uploaded images may live only in the asset map
+        let files = if refs_dir.is_dir() {
+            let is_image = |path: &Path| image_format(path).is_some();
+            self.collect_files(domain, &refs_dir, errors, is_image)
+        } else {
+            Vec::new()
+        };
+
+        let assets = AssetStore::new(&self.skills_dir);
+        let mapped = read_asset_map(&skill_dir)
+            .into_iter()
+            .filter(|(relative, _)| relative.starts_with("references/"))
+            .map(|(relative, hash)| (skill_dir.join(relative), Some(assets.path(&hash))));
+
+        files
+            .into_iter()
+            .map(|path| (path, None))
+            .chain(mapped)
+            .filter_map(|(path, blob)| {
+                let format = image_format(&path)?;
+                let relative = path.strip_prefix(&skill_dir).unwrap_or(&path);
+                let relative = normalize_separators(&relative.to_string_lossy());
+
+                match read_head(blob.as_deref().unwrap_or(&path), IMAGE_HEAD_BYTES) {
+                    Ok((size, head)) => {
+                        Some(ImageAsset::from_header(relative, format, &head, size))
+                    }
+                    Err(e) => {
+                        errors.push(format!("{}: Failed to read {}: {}", domain, relative, e));
+                        None
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Collect the files under a directory that pass `filter`.
+    ///
+    /// The walk behind [`Self::collect_markdown_files`] and
+    /// [`Self::collect_scripts`].
+    fn collect_files(
+        &self,
+        domain: &str,
+        dir: &Path,
+        errors: &mut Vec<String>,
+        filter: impl Fn(&Path) -> bool,
+    ) -> Vec<PathBuf> {
+        let mut seen = HashSet::new();
+        let mut files = Vec::new();
+
+        for entry in WalkDir::new(dir)
+            .follow_links(self.options.follow_links)
+            .sort_by_file_name()
+        {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    match (e.loop_ancestor(), e.path()) {
+                        (Some(ancestor), Some(path)) => errors.push(format!(
+                            "{}: Symlink cycle at {:?} (points back to {:?})",
+                            domain, path, ancestor
+                        )),
+                        _ => debug!("Skipping unreadable entry under {:?}: {}", dir, e),
+                    }
+                    continue;
+                }
+            };
+
+            let path = entry.path();
+
+            // With links not followed, symlinks report their own file type and are skipped here
+            if !entry.file_type().is_file() || self.is_ignored(path, false) {
+                continue;
+            }
+
+            if !filter(path) {
+                continue;
+            }
+
+            let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+            if !seen.insert(canonical) {
+                debug!("Skipping {:?} (already indexed via another path)", path);
+                continue;
+            }
+
+            files.push(path.to_path_buf());
+        }
+
+        files
+    }
+
+    /// Load metadata from a flat-file skill's frontmatter.
+    ///
+    /// Returns `Ok(None)` for markdown without a frontmatter block.
+    fn load_flat_meta(&self, path: &Path) -> Result<Option<SkillMeta>, IndexError> {
+        let (text, _) = self
+            .before_read(path)
+            .and_then(|_| read_lossy(path))
+            .map_err(|e| IndexError::ReadError(format!("Failed to read {:?}: {}", path, e)))?;
+
+        if split_frontmatter(&text).is_none() {
+            return Ok(None);
+        }
+
+        let stem = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default();
+        let (meta, _) = parse_flat_skill(stem, &text)
+            .map_err(|e| IndexError::ParseError(format!("Failed to parse {:?}: {}", path, e)))?;
+
+        // Skills are looked up by file name, so the two must agree
+        if meta.name != stem {
+            return Err(IndexError::ValidationError(format!(
+                "name '{}' does not match file name '{}.md'",
+                meta.name, stem
+            )));
+        }
+
+        Ok(Some(meta))
+    }
+
+    /// Load and parse _meta.json file.
+    fn load_meta(&self, path: &Path) -> Result<SkillMeta, IndexError> {
+        let content = self
+            .before_read(path)
+            .and_then(|_| fs::read_to_string(path))
+            .map_err(|e| IndexError::ReadError(format!("Failed to read {:?}: {}", path, e)))?;
+
+        let mut meta: SkillMeta = serde_json::from_str(&content)
+            .map_err(|e| IndexError::ParseError(format!("Failed to parse {:?}: {}", path, e)))?;
+
+        // Metadata written on Windows may use backslashes in sub-skill paths
+        fn normalize(subs: &mut [SubSkillMeta]) {
+            for sub in subs {
+                sub.file = normalize_separators(&sub.file);
+                normalize(&mut sub.children);
+            }
+        }
+        normalize(meta.sub_skills.as_deref_mut().unwrap_or_default());
+
+        Ok(meta)
+    }
+}
+
+/// Check whether a directory without `_meta.json` groups skills.
+///
+/// A category holds no `SKILL.md` of its own and has at least one
+/// subdirectory; anything else is a skill with missing metadata.
+fn is_category_dir(path: &Path) -> bool {
+    if path.join("SKILL.md").exists() {
+        return false;
+    }
+
+    fs::read_dir(path)
+        .map(|entries| entries.flatten().any(|e| e.path().is_dir()))
+        .unwrap_or(false)
+}
+
+/// Check whether a file under `scripts/` is a script rather than a build
+/// artifact or hidden file.
+fn is_script_file(path: &Path) -> bool {
+    let hidden = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .is_none_or(|n| n.starts_with('.'));
+    let cached = path.components().any(|c| c.as_os_str() == "__pycache__");
+    let compiled = matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("pyc") | Some("pyo")
+    );
+
+    !hidden && !cached && !compiled
+}
+
+/// Image format of a path, by extension.
+fn image_format(path: &Path) -> Option<ImageFormat> {
+    ImageFormat::from_extension(path.file_name()?.to_str()?)
+}
+
+/// Read up to `limit` bytes from the start of a file, with its full size.
+fn read_head(path: &Path, limit: u64) -> std::io::Result<(u64, Vec<u8>)> {
+    let file = fs::File::open(path)?;
+    let size = file.metadata()?.len();
+    let mut head = Vec::new();
+    file.take(limit).read_to_end(&mut head)?;
+    Ok((size, head))
+}
+
+/// Check whether a path has a markdown extension.
+fn is_markdown(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("md") | Some("markdown")
+    )
+}
+
+/// Check whether a sub-skill identifier is a relative file path rather than
+/// a sub-skill name from metadata.
+fn is_file_reference(sub_skill: &str) -> bool {
+    sub_skill.contains(['/', '\\']) || is_markdown(Path::new(sub_skill))
+}
+
+/// Check whether a path is itself a symbolic link.
+fn is_symlink(path: &Path) -> bool {
+    fs::symlink_metadata(path)
+        .map(|m| m.file_type().is_symlink())
+        .unwrap_or(false)
+}
 
 /// Errors that can occur during indexing.
 #[derive(Debug, thiserror::Error)]
@@ -563,74 +2609,1213 @@ pub enum IndexError {
     #[error("Not found: {0}")]
     NotFound(String),
 
-    /// Failed to read a file from disk.
-    #[error("Read error: {0}")]
-    ReadError(String),
+    /// Failed to read a file from disk.
+    #[error("Read error: {0}")]
+    ReadError(String),
+
+    /// Failed to parse a file (e.g., invalid JSON).
+    #[error("Parse error: {0}")]
+    ParseError(String),
+
+    /// The skill metadata failed validation.
+    #[error("Validation error: {0}")]
+    ValidationError(String),
+
+    /// A skill or file to be created already exists.
+    #[error("Already exists: {0}")]
+    AlreadyExists(String),
+
+    /// Failed to write a file to disk.
+    #[error("Write error: {0}")]
+    WriteError(String),
+
+    /// A write would take a skill, or all skills, over their size quota.
+    #[error("Quota exceeded: {0}")]
+    QuotaExceeded(String),
+
+    /// The skill is pinned against the change.
+    #[error("Pinned: {0}")]
+    Pinned(String),
+}
+
+impl IndexError {
+    /// Kind of failure, for error responses.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            IndexError::NotFound(_) => ErrorCode::NotFound,
+            IndexError::ValidationError(_) => ErrorCode::InvalidParams,
+            IndexError::AlreadyExists(_) => ErrorCode::AlreadyExists,
+            IndexError::QuotaExceeded(_) => ErrorCode::QuotaExceeded,
+            IndexError::Pinned(_) => ErrorCode::Forbidden,
+            IndexError::ReadError(_) | IndexError::ParseError(_) | IndexError::WriteError(_) => {
+                ErrorCode::Internal
+            }
+        }
+    }
+}
+
+impl From<IndexError> for ErrorResponse {
+    fn from(e: IndexError) -> Self {
+        ErrorResponse::new(e.to_string()).with_code(e.code())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_test_skill(dir: &Path, name: &str, description: &str) {
+        let skill_dir = dir.join(name);
+        fs::create_dir_all(&skill_dir).unwrap();
+
+        // Create _meta.json
+        let meta = format!(
+            r#"{{"name": "{}", "description": "{}"}}"#,
+            name, description
+        );
+        fs::write(skill_dir.join("_meta.json"), meta).unwrap();
+
+        // Create SKILL.md
+        let content = format!("# {}\n\n{}", name, description);
+        fs::write(skill_dir.join("SKILL.md"), content).unwrap();
+    }
+
+    #[test]
+    fn test_from_memory() {
+        let indexer = SkillIndexer::from_memory(vec![
+            SkillFixture::new("forms", "Form handling")
+                .with_tags(["frontend"])
+                .with_sub_skill("validation", "# Validation\n\nCheck inputs."),
+            SkillFixture::new("charts", "Plotting data").with_content("# Charts\n\nBar charts."),
+        ])
+        .unwrap();
+        let dir = indexer.skills_dir().to_path_buf();
+
+        assert_eq!(indexer.get_skill_index().len(), 2);
+        assert_eq!(
+            indexer.get_skill_meta("forms").unwrap().tags,
+            vec!["frontend"]
+        );
+        assert!(indexer
+            .read_sub_skill_content("forms", "validation")
+            .unwrap()
+            .content
+            .contains("Check inputs."));
+        assert!(indexer
+            .read_skill_content("charts")
+            .unwrap()
+            .content
+            .contains("Bar charts."));
+
+        drop(indexer);
+        assert!(!dir.exists());
+
+        let bad = SkillFixture::new("forms", "Form handling").with_file("../escape.md", "x");
+        assert!(SkillIndexer::from_memory(vec![bad]).is_err());
+    }
+
+    #[test]
+    fn test_indexer_basic() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_skill(temp_dir.path(), "test-skill", "A test skill");
+
+        let indexer = SkillIndexer::new(temp_dir.path());
+        indexer.reload().unwrap();
+
+        let index = indexer.get_skill_index();
+        assert_eq!(index.len(), 1);
+        assert!(index.find("test-skill").is_some());
+    }
+
+    #[test]
+    fn test_slow_reloads_are_counted() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_skill(temp_dir.path(), "test-skill", "A test skill");
+
+        let indexer = SkillIndexer::new(temp_dir.path());
+        indexer.reload().unwrap();
+        assert_eq!(indexer.slow_reloads(), 0);
+
+        let options = IndexerOptions::default().slow_reload(Duration::from_nanos(1));
+        let indexer = SkillIndexer::with_options(temp_dir.path(), options);
+        indexer.reload().unwrap();
+        indexer.reload().unwrap();
+        assert_eq!(indexer.slow_reloads(), 2);
+    }
+
+    #[test]
+    fn test_reload_progress() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_skill(temp_dir.path(), "forms", "Form handling");
+        create_test_skill(temp_dir.path(), "charts", "Plotting data");
+
+        let indexer = SkillIndexer::new(temp_dir.path());
+        let seen = parking_lot::Mutex::new(Vec::new());
+        indexer
+            .reload_with_progress(|scanned, total| seen.lock().push((scanned, total)))
+            .unwrap();
+        assert_eq!(seen.into_inner(), vec![(1, 2), (2, 2)]);
+    }
+
+    #[test]
+    fn test_skill_stats_follow_updates() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_skill(temp_dir.path(), "forms", "Form handling");
+        create_test_skill(temp_dir.path(), "charts", "Plotting data");
+
+        let indexer = SkillIndexer::new(temp_dir.path());
+        indexer.reload().unwrap();
+        let stats = indexer.skill_stats();
+        assert_eq!(stats["forms"].total_words, 4);
+        assert_eq!(stats["forms"].reference_count, 0);
+        assert!(stats["forms"].last_modified.is_some());
+
+        let refs = temp_dir.path().join("forms/references");
+        fs::create_dir_all(&refs).unwrap();
+        fs::write(refs.join("api.md"), "Field list here").unwrap();
+        indexer.update_skill("forms").unwrap();
+        let stats = indexer.skill_stats();
+        assert_eq!(stats["forms"].total_words, 7);
+        assert_eq!(stats["forms"].reference_count, 1);
+
+        indexer.remove_skill("charts").unwrap();
+        assert!(!indexer.skill_stats().contains_key("charts"));
+    }
+
+    #[test]
+    fn test_manifest_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_skill(temp_dir.path(), "forms", "Form handling");
+        create_test_skill(temp_dir.path(), "charts", "Plotting data");
+
+        let indexer = SkillIndexer::new(temp_dir.path());
+        let seen = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let sink = Arc::clone(&seen);
+        let subscription = indexer.on_change(Arc::new(move |changes: &ManifestDiff| {
+            sink.lock().push(changes.clone())
+        }));
+
+        indexer.reload().unwrap();
+        assert_eq!(indexer.last_changes().added, vec!["charts", "forms"]);
+        assert_eq!(indexer.manifest().version, indexer.version());
+
+        // An unchanged reload bumps the version but notifies no one
+        indexer.reload().unwrap();
+        assert!(indexer.last_changes().is_empty());
+        assert_eq!(seen.lock().len(), 1);
+
+        let charts = indexer.manifest().skills["charts"].clone();
+        fs::write(temp_dir.path().join("forms/SKILL.md"), "# Forms\n\nNew.").unwrap();
+        indexer.update_skill("forms").unwrap();
+        assert_eq!(indexer.last_changes().changed, vec!["forms"]);
+        assert_eq!(indexer.manifest().skills["charts"], charts);
+
+        indexer.remove_skill("charts").unwrap();
+        assert_eq!(indexer.last_changes().removed, vec!["charts"]);
+        assert_eq!(seen.lock().len(), 3);
+
+        // Dropping the subscription removes the listener
+        drop(subscription);
+        fs::write(temp_dir.path().join("forms/SKILL.md"), "# Forms\n\nNewer.").unwrap();
+        indexer.update_skill("forms").unwrap();
+        assert_eq!(indexer.last_changes().changed, vec!["forms"]);
+        assert_eq!(seen.lock().len(), 3);
+    }
+
+    #[test]
+    fn test_disk_usage() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_skill(temp_dir.path(), "forms", "Form handling");
+        create_test_skill(temp_dir.path(), "design", "Design assets");
+        let references = temp_dir.path().join("design/references");
+        fs::create_dir_all(&references).unwrap();
+        fs::write(references.join("screenshot.png"), vec![0u8; 4096]).unwrap();
+        fs::write(
+            temp_dir.path().join("notes.md"),
+            "---\nname: notes\ndescription: Flat skill\n---\n\n# Notes",
+        )
+        .unwrap();
+
+        let indexer = SkillIndexer::with_options(
+            temp_dir.path(),
+            IndexerOptions::default().skill_quota(1024),
+        );
+        indexer.reload().unwrap();
+
+        let usage = indexer.disk_usage();
+        assert_eq!(usage.skills.len(), 3);
+        let design = usage.skill("design").unwrap();
+        assert_eq!(design.files, 3);
+        assert!(design.bytes > 4096 && design.over_quota);
+        assert!(!usage.skill("forms").unwrap().over_quota);
+        assert_eq!(usage.skill("notes").unwrap().files, 1);
+        assert_eq!(usage.warnings.len(), 1);
+
+        // Reused until the index changes
+        fs::remove_file(references.join("screenshot.png")).unwrap();
+        assert_eq!(indexer.disk_usage().skill("design"), Some(design));
+        assert!(!indexer.skill_disk_usage("design").over_quota);
+        indexer.update_skill("design").unwrap();
+        assert!(indexer.disk_usage().warnings.is_empty());
+    }
+
+    #[test]
+    fn test_quota_enforced_on_create() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_skill(temp_dir.path(), "forms", "Form handling");
+        let options = IndexerOptions::default()
+            .skill_quota(1024)
+            .total_quota(1500);
+        let indexer = SkillIndexer::with_options(temp_dir.path(), options);
+        indexer.reload().unwrap();
+
+        let meta = |name: &str| SkillMeta {
+            name: name.to_string(),
+            description: "Big".to_string(),
+            ..Default::default()
+        };
+        let skill = |bytes: usize| RenderedTemplate {
+            content: "x".repeat(bytes),
+            tags: Vec::new(),
+            sub_skills: Vec::new(),
+        };
+
+        let err = indexer
+            .create_skill(&meta("big"), &skill(4096), false)
+            .unwrap_err();
+        assert!(matches!(err, IndexError::QuotaExceeded(_)));
+        assert!(err
+            .to_string()
+            .contains("over its 1.0 KiB quota (currently 0 B)"));
+        assert!(!temp_dir.path().join("big").exists());
+        assert!(matches!(
+            indexer.check_create(&meta("big"), &skill(4096), false),
+            Err(IndexError::QuotaExceeded(_))
+        ));
+        assert_eq!(
+            indexer
+                .check_create(&meta("small"), &skill(512), true)
+                .unwrap(),
+            vec!["small.md"]
+        );
+        assert!(!temp_dir.path().join("small.md").exists());
+
+        indexer
+            .create_skill(&meta("small"), &skill(512), true)
+            .unwrap();
+        let err = indexer
+            .create_skill(&meta("more"), &skill(900), false)
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("Quota exceeded: Skills would use"));
+
+        // Shrinking an over-quota skill is always allowed
+        let forms_md = temp_dir.path().join("forms/SKILL.md");
+        fs::write(&forms_md, "x".repeat(4096)).unwrap();
+        assert!(indexer
+            .check_quota("forms", &[(forms_md.clone(), 10)])
+            .is_ok());
+        assert!(indexer.check_quota("forms", &[(forms_md, 5000)]).is_err());
+    }
+
+    #[test]
+    fn test_add_references() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_skill(temp_dir.path(), "forms", "Form handling");
+        let indexer = SkillIndexer::new(temp_dir.path());
+        indexer.reload().unwrap();
+
+        let written = indexer
+            .add_references(
+                "forms",
+                &[("guide.md".to_string(), b"# Guide\n\nSteps.".to_vec())],
+            )
+            .unwrap();
+        assert_eq!(written, vec!["references/guide.md"]);
+        assert!(indexer.has_references("forms"));
+        assert!(indexer.indexed_hash("forms:references/guide.md").is_some());
+
+        let twice = [
+            ("a.md".to_string(), Vec::new()),
+            ("A.md".to_string(), Vec::new()),
+        ];
+        assert!(indexer.add_references("forms", &twice).is_err());
+        assert!(!temp_dir.path().join("forms/references/a.md").exists());
+        assert!(indexer
+            .add_references("forms", &[("../../x.md".to_string(), Vec::new())])
+            .is_err());
+        assert!(matches!(
+            indexer.add_references("missing", &[("a.md".to_string(), Vec::new())]),
+            Err(IndexError::NotFound(_))
+        ));
+
+        #[cfg(unix)]
+        {
+            let outside = TempDir::new().unwrap();
+            std::os::unix::fs::symlink(
+                outside.path(),
+                temp_dir.path().join("forms/references/linked"),
+            )
+            .unwrap();
+            assert!(indexer
+                .add_references("forms", &[("linked/x.md".to_string(), Vec::new())])
+                .is_err());
+            assert!(!outside.path().join("x.md").exists());
+        }
+    }
+
+    #[test]
+    fn test_add_references_shares_images() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_skill(temp_dir.path(), "forms", "Form handling");
+        create_test_skill(temp_dir.path(), "design", "Design system");
+        let indexer = SkillIndexer::new(temp_dir.path());
+        indexer.reload().unwrap();
+
+        let image = || vec![("logo.png".to_string(), b"\x89PNG\r\n\x1a\n".to_vec())];
+        indexer.add_references("forms", &image()).unwrap();
+        indexer.add_references("design", &image()).unwrap();
+
+        let assets = AssetStore::new(temp_dir.path());
+        assert_eq!(assets.blobs().len(), 1);
+        assert!(!temp_dir.path().join("forms/references/logo.png").exists());
+
+        let forms = indexer
+            .resolve_skill_file("forms", "references/logo.png")
+            .unwrap();
+        let design = indexer
+            .resolve_skill_file("design", "references/logo.png")
+            .unwrap();
+        assert_eq!(forms, design);
+        assert_eq!(fs::read(&forms).unwrap(), b"\x89PNG\r\n\x1a\n");
+        assert!(indexer.skill_disk_usage("forms").bytes >= 8);
+        assert!(indexer.has_references("design"));
+        let images = indexer.list_images("forms").unwrap();
+        assert_eq!(images.len(), 1);
+        assert_eq!(images[0].path, "references/logo.png");
+
+        // Replacing the image with another only changes that skill's map
+        indexer
+            .add_references("forms", &[("logo.png".to_string(), b"other".to_vec())])
+            .unwrap();
+        assert_eq!(assets.blobs().len(), 2);
+        assert_eq!(
+            fs::read(
+                indexer
+                    .resolve_skill_file("design", "references/logo.png")
+                    .unwrap()
+            )
+            .unwrap(),
+            b"\x89PNG\r\n\x1a\n"
+        );
+    }
+
+    #[test]
+    fn test_read_skill_content() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_skill(temp_dir.path(), "forms", "Form handling patterns");
+
+        let indexer = SkillIndexer::new(temp_dir.path());
+        indexer.reload().unwrap();
+
+        let content = indexer.read_skill_content("forms").unwrap();
+        assert_eq!(content.name, "forms");
+        assert!(content.content.contains("Form handling patterns"));
+    }
+
+    #[test]
+    fn test_missing_skill() {
+        let temp_dir = TempDir::new().unwrap();
+        let indexer = SkillIndexer::new(temp_dir.path());
+        indexer.reload().unwrap();
 
-    /// Failed to parse a file (e.g., invalid JSON).
-    #[error("Parse error: {0}")]
-    ParseError(String),
+        let result = indexer.read_skill_content("nonexistent");
+        assert!(result.is_err());
+    }
 
-    /// The skill metadata failed validation.
-    #[error("Validation error: {0}")]
-    ValidationError(String),
-}
+    #[test]
+    fn test_skillsignore_excludes_skills_and_files() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_skill(temp_dir.path(), "forms", "Form handling patterns");
+        create_test_skill(temp_dir.path(), "scratch", "Work in progress");
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::fs;
-    use tempfile::TempDir;
+        let refs = temp_dir.path().join("forms/references");
+        fs::create_dir_all(refs.join("generated")).unwrap();
+        fs::write(refs.join("guide.md"), "# Guide").unwrap();
+        fs::write(refs.join("generated/api.md"), "# Generated").unwrap();
 
-    fn create_test_skill(dir: &Path, name: &str, description: &str) {
-        let skill_dir = dir.join(name);
+        fs::write(
+            temp_dir.path().join(".skillsignore"),
+            "scratch/
+*/references/generated/
+",
+        )
+        .unwrap();
+
+        let indexer = SkillIndexer::new(temp_dir.path());
+        indexer.reload().unwrap();
+
+        let index = indexer.get_skill_index();
+        assert_eq!(index.len(), 1);
+        assert!(index.find("scratch").is_none());
+
+        let content_index = indexer.get_content_index();
+        let files: Vec<&str> = content_index.iter().map(|(_, e)| e.file.as_str()).collect();
+        assert!(files.iter().any(|f| f.ends_with("guide.md")));
+        assert!(!files.iter().any(|f| f.ends_with("api.md")));
+
+        // Incremental updates honor the same rules
+        indexer.update_skill("scratch").unwrap();
+        assert!(indexer.get_skill_meta("scratch").is_none());
+    }
+
+    #[test]
+    fn test_sub_skill_backslash_paths_normalized() {
+        let temp_dir = TempDir::new().unwrap();
+        let skill_dir = temp_dir.path().join("forms");
+        fs::create_dir_all(skill_dir.join("react")).unwrap();
+        fs::write(
+            skill_dir.join("_meta.json"),
+            r#"{"name": "forms", "description": "Forms", "sub_skills": [{"name": "react", "file": "react\\SKILL.md"}]}"#,
+        )
+        .unwrap();
+        fs::write(skill_dir.join("SKILL.md"), "# Forms").unwrap();
+        fs::write(skill_dir.join("react/SKILL.md"), "# React forms").unwrap();
+
+        let indexer = SkillIndexer::new(temp_dir.path());
+        indexer.reload().unwrap();
+
+        let meta = indexer.get_skill_meta("forms").unwrap();
+        assert_eq!(meta.sub_skills.unwrap()[0].file, "react/SKILL.md");
+
+        let content = indexer.read_sub_skill_content("forms", "react").unwrap();
+        assert!(content.content.contains("React forms"));
+    }
+
+    #[test]
+    fn test_read_sub_skill_by_path() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_skill(temp_dir.path(), "forms", "Form handling patterns");
+        create_test_skill(temp_dir.path(), "other", "Other skill");
+        let react = temp_dir.path().join("forms/react");
+        fs::create_dir_all(&react).unwrap();
+        fs::write(react.join("advanced.md"), "# Advanced React forms").unwrap();
+        fs::write(react.join("notes.txt"), "not markdown").unwrap();
+
+        let indexer = SkillIndexer::new(temp_dir.path());
+        indexer.reload().unwrap();
+
+        let content = indexer
+            .read_sub_skill_content("forms", "react/advanced.md")
+            .unwrap();
+        assert_eq!(content.sub_skill, "react/advanced.md");
+        assert!(content.content.contains("Advanced React forms"));
+
+        let content = indexer
+            .read_sub_skill_content("forms", "react\\advanced.md")
+            .unwrap();
+        assert!(content.content.contains("Advanced React forms"));
+
+        assert!(matches!(
+            indexer.read_sub_skill_content("forms", "../other/SKILL.md"),
+            Err(IndexError::ValidationError(_))
+        ));
+        assert!(matches!(
+            indexer.read_sub_skill_content("forms", "react/notes.txt"),
+            Err(IndexError::ValidationError(_))
+        ));
+        assert!(matches!(
+            indexer.read_sub_skill_content("forms", "react/missing.md"),
+            Err(IndexError::NotFound(_))
+        ));
+        assert!(matches!(
+            indexer.read_sub_skill_content("forms", "react"),
+            Err(IndexError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_list_and_read_references() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_skill(temp_dir.path(), "forms", "Form handling patterns");
+        create_test_skill(temp_dir.path(), "bare", "No references");
+        let refs = temp_dir.path().join("forms/references");
+        fs::create_dir_all(refs.join("guides")).unwrap();
+        fs::write(refs.join("api.md"), "# API reference").unwrap();
+        fs::write(refs.join("guides/setup.md"), "# Setup").unwrap();
+        fs::write(refs.join("data.json"), "{}").unwrap();
+
+        let indexer = SkillIndexer::new(temp_dir.path());
+        indexer.reload().unwrap();
+
+        let refs_list = indexer.list_references("forms").unwrap();
+        let paths: Vec<&str> = refs_list.iter().map(|r| r.path.as_str()).collect();
+        assert_eq!(paths, ["api.md", "guides/setup.md"]);
+        assert_eq!(refs_list[0].size, 15);
+        assert!(indexer.list_references("bare").unwrap().is_empty());
+        assert!(indexer.list_references("missing").is_err());
+
+        let doc = indexer.read_reference("forms", "guides\\setup.md").unwrap();
+        assert_eq!(doc.path, "guides/setup.md");
+        assert_eq!(doc.content, "# Setup");
+        assert!(!doc.truncated);
+
+        assert!(matches!(
+            indexer.read_reference("forms", "../SKILL.md"),
+            Err(IndexError::ValidationError(_))
+        ));
+        assert!(matches!(
+            indexer.read_reference("forms", "data.json"),
+            Err(IndexError::ValidationError(_))
+        ));
+        assert!(matches!(
+            indexer.read_reference("forms", "missing.md"),
+            Err(IndexError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_read_reference_truncates_at_size_limit() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_skill(temp_dir.path(), "forms", "Form handling patterns");
+        let refs = temp_dir.path().join("forms/references");
+        fs::create_dir_all(&refs).unwrap();
+        // 'é' is two bytes; the limit falls in the middle of the third one
+        fs::write(refs.join("long.md"), "ééééé").unwrap();
+
+        let indexer =
+            SkillIndexer::with_options(temp_dir.path(), IndexerOptions::default().max_file_size(5));
+        indexer.reload().unwrap();
+
+        let doc = indexer.read_reference("forms", "long.md").unwrap();
+        assert!(doc.truncated);
+        assert_eq!(doc.content, "éé");
+    }
+
+    #[test]
+    fn test_sub_skill_path_rejects_reserved_names() {
+        let temp_dir = TempDir::new().unwrap();
+
+        for file in ["con.md", "react/AUX.md", "nul\\SKILL.md", "react./SKILL.md"] {
+            let result = validate_sub_skill_path(temp_dir.path(), file);
+            assert!(
+                matches!(result, Err(IndexError::ValidationError(_))),
+                "expected {} to be rejected",
+                file
+            );
+        }
+
+        let result = validate_sub_skill_path(temp_dir.path(), "C:\\skills\\SKILL.md");
+        assert!(matches!(result, Err(IndexError::ValidationError(_))));
+        let result = validate_sub_skill_path(temp_dir.path(), "\\server\\share\\SKILL.md");
+        assert!(matches!(result, Err(IndexError::ValidationError(_))));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_reference_paths_use_forward_slashes_on_windows() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_skill(temp_dir.path(), "forms", "Form handling patterns");
+        let nested = temp_dir
+            .path()
+            .join("forms")
+            .join("references")
+            .join("guides");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(nested.join("intro.md"), "# Intro").unwrap();
+
+        let indexer = SkillIndexer::new(temp_dir.path());
+        indexer.reload().unwrap();
+
+        let content_index = indexer.get_content_index();
+        assert!(content_index
+            .get("forms:references/guides/intro.md")
+            .is_some());
+    }
+
+    #[test]
+    fn test_version_changes_with_index() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_skill(temp_dir.path(), "forms", "Form handling patterns");
+
+        let indexer = SkillIndexer::new(temp_dir.path());
+        assert_eq!(indexer.version(), 0);
+
+        indexer.reload().unwrap();
+        let loaded = indexer.version();
+        assert!(loaded > 0);
+
+        indexer.update_skill("forms").unwrap();
+        assert!(indexer.version() > loaded);
+
+        // Removing a skill that isn't indexed changes nothing
+        let before = indexer.version();
+        indexer.remove_skill("missing").unwrap();
+        assert_eq!(indexer.version(), before);
+    }
+
+    #[test]
+    fn test_diff_with_disk() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_skill(temp_dir.path(), "forms", "Form handling patterns");
+        create_test_skill(temp_dir.path(), "api", "API design");
+
+        let indexer = SkillIndexer::new(temp_dir.path());
+        indexer.reload().unwrap();
+        assert!(indexer.diff_with_disk().unwrap().is_empty());
+
+        let version = indexer.version();
+        fs::write(temp_dir.path().join("forms/SKILL.md"), "# Forms\n\nEdited").unwrap();
+        fs::remove_dir_all(temp_dir.path().join("api")).unwrap();
+        create_test_skill(temp_dir.path(), "testing", "Test patterns");
+
+        let diff = indexer.diff_with_disk().unwrap();
+        assert_eq!(diff.skills_missing_from_index, vec!["testing".to_string()]);
+        assert_eq!(diff.skills_missing_from_disk, vec!["api".to_string()]);
+        assert!(diff.skills_changed.is_empty());
+        assert_eq!(diff.entries_changed, vec!["forms".to_string()]);
+        assert!(diff
+            .entries_missing_from_index
+            .contains(&"testing".to_string()));
+        assert!(diff
+            .entries_missing_from_disk
+            .contains(&"api:_meta.json".to_string()));
+
+        // Dry run: the index is untouched
+        assert_eq!(indexer.version(), version);
+        assert!(indexer.get_skill_index().find("api").is_some());
+    }
+
+    #[test]
+    fn test_flat_file_skills() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_skill(temp_dir.path(), "forms", "Form handling patterns");
+        fs::write(
+            temp_dir.path().join("api.md"),
+            "---\nname: api\ndescription: API design\ntags: [rest]\n---\n\n# API\n\nUse nouns.",
+        )
+        .unwrap();
+        fs::write(temp_dir.path().join("README.md"), "# Skills repository").unwrap();
+        fs::write(
+            temp_dir.path().join("forms.md"),
+            "---\nname: forms\ndescription: Shadowed\n---\n",
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("misnamed.md"),
+            "---\nname: other\ndescription: Wrong name\n---\n",
+        )
+        .unwrap();
+
+        let indexer = SkillIndexer::new(temp_dir.path());
+        indexer.reload().unwrap();
+
+        let index = indexer.get_skill_index();
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.find("api").unwrap().tags, vec!["rest"]);
+        assert_eq!(
+            index.find("forms").unwrap().description,
+            "Form handling patterns"
+        );
+        assert!(index
+            .validation_errors
+            .iter()
+            .any(|e| e.contains("forms.md")));
+        assert!(index
+            .validation_errors
+            .iter()
+            .any(|e| e.contains("misnamed.md")));
+
+        // The body is indexed and served without the frontmatter
+        let entry = indexer.get_content_index().get("api").cloned().unwrap();
+        assert_eq!(entry.file, "api.md");
+        let content = indexer.read_skill_content("api").unwrap();
+        assert_eq!(content.content, "# API\n\nUse nouns.");
+        assert!(content.sub_skills.is_empty());
+
+        assert!(indexer.skill_exists("api"));
+        assert_eq!(indexer.flat_skill_path("forms"), None);
+        assert_eq!(
+            indexer.skill_from_path(&temp_dir.path().join("api.md")),
+            Some("api".to_string())
+        );
+
+        // Incremental updates pick up edits and deletions
+        fs::write(
+            temp_dir.path().join("api.md"),
+            "---\ndescription: REST API design\n---\nBody",
+        )
+        .unwrap();
+        indexer.update_skill("api").unwrap();
+        assert_eq!(
+            indexer.get_skill_meta("api").unwrap().description,
+            "REST API design"
+        );
+
+        fs::remove_file(temp_dir.path().join("api.md")).unwrap();
+        indexer.update_skill("api").unwrap();
+        assert!(indexer.get_skill_meta("api").is_none());
+    }
+
+    /// Create a skill at a nested path, named after that path.
+    fn create_nested_skill(dir: &Path, path: &str, name: &str) {
+        let skill_dir = dir.join(path);
         fs::create_dir_all(&skill_dir).unwrap();
+        fs::write(
+            skill_dir.join("_meta.json"),
+            format!(
+                r#"{{"name": "{}", "description": "About {}", "tags": ["test"]}}"#,
+                name, path
+            ),
+        )
+        .unwrap();
+        fs::write(skill_dir.join("SKILL.md"), format!("# {}", path)).unwrap();
+    }
 
-        // Create _meta.json
-        let meta = format!(
-            r#"{{"name": "{}", "description": "{}"}}"#,
-            name, description
+    #[test]
+    fn test_nested_categories() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_skill(temp_dir.path(), "forms", "Form handling patterns");
+        create_nested_skill(temp_dir.path(), "frontend/react", "frontend/react");
+        create_nested_skill(temp_dir.path(), "backend/auth/oauth", "backend/auth/oauth");
+        create_nested_skill(temp_dir.path(), "backend/jobs", "jobs");
+
+        let indexer = SkillIndexer::new(temp_dir.path());
+        indexer.reload().unwrap();
+
+        let index = indexer.get_skill_index();
+        let names: Vec<&str> = index.skills.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["backend/auth/oauth", "forms", "frontend/react"]);
+        assert!(indexer.get_skill_meta("jobs").is_none());
+        assert!(index
+            .validation_errors
+            .iter()
+            .any(|e| e.contains("name 'jobs' does not match directory path 'backend/jobs'")));
+
+        let categories = index.categories();
+        assert_eq!(categories.len(), 2);
+        assert_eq!(categories[0].name, "backend/auth");
+        assert_eq!(categories[1].skills, vec!["frontend/react"]);
+
+        // Content and file lookups go through the namespaced path
+        let content = indexer.read_skill_content("frontend/react").unwrap();
+        assert_eq!(content.content, "# frontend/react");
+        assert!(indexer
+            .get_content_index()
+            .get("backend/auth/oauth")
+            .is_some());
+
+        assert_eq!(
+            indexer.skill_from_path(&temp_dir.path().join("frontend/react/SKILL.md")),
+            Some("frontend/react".to_string())
         );
-        fs::write(skill_dir.join("_meta.json"), meta).unwrap();
 
-        // Create SKILL.md
-        let content = format!("# {}\n\n{}", name, description);
-        fs::write(skill_dir.join("SKILL.md"), content).unwrap();
+        // Removing a whole category drops every skill in it
+        fs::remove_dir_all(temp_dir.path().join("backend")).unwrap();
+        indexer.update_skill("backend").unwrap();
+        assert!(indexer.get_skill_meta("backend/auth/oauth").is_none());
+        assert!(indexer.get_skill_meta("frontend/react").is_some());
     }
 
     #[test]
-    fn test_indexer_basic() {
+    fn test_scripts_listed_and_indexed() {
         let temp_dir = TempDir::new().unwrap();
-        create_test_skill(temp_dir.path(), "test-skill", "A test skill");
+        create_test_skill(temp_dir.path(), "pdf", "PDF tools");
+
+        let scripts = temp_dir.path().join("pdf/scripts");
+        fs::create_dir_all(scripts.join("__pycache__")).unwrap();
+        fs::write(
+            scripts.join("fill_form.py"),
+            "\"\"\"Fill a PDF form from JSON.\"\"\"\n",
+        )
+        .unwrap();
+        fs::write(
+            scripts.join("render"),
+            "#!/bin/bash\n# Render pages to PNG\n",
+        )
+        .unwrap();
+        fs::write(
+            scripts.join("__pycache__/fill_form.cpython-312.pyc"),
+            [0u8, 1, 2],
+        )
+        .unwrap();
+        fs::write(scripts.join(".DS_Store"), "").unwrap();
+
+        let indexer = SkillIndexer::new(temp_dir.path());
+        indexer.reload().unwrap();
+
+        let listed = indexer.list_scripts("pdf").unwrap();
+        let paths: Vec<&str> = listed.iter().map(|s| s.path.as_str()).collect();
+        assert_eq!(paths, vec!["scripts/fill_form.py", "scripts/render"]);
+        assert_eq!(listed[1].language, crate::models::ScriptLanguage::Shell);
+        assert_eq!(
+            listed[1].description.as_deref(),
+            Some("Render pages to PNG")
+        );
+
+        let content = indexer.read_skill_content("pdf").unwrap();
+        assert_eq!(content.scripts, listed);
+
+        // Docstrings are searchable
+        let entry = indexer
+            .get_content_index()
+            .get("pdf:scripts/fill_form.py")
+            .cloned()
+            .unwrap();
+        assert_eq!(entry.content, "fill_form.py\n\nfill a pdf form from json.");
+    }
+
+    #[test]
+    fn test_notebook_references_indexed() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_skill(temp_dir.path(), "data", "Data analysis");
+        let refs = temp_dir.path().join("data/references");
+        fs::create_dir_all(&refs).unwrap();
+        fs::write(
+            refs.join("walkthrough.ipynb"),
+            r##"{"cells": [{"cell_type": "markdown", "source": "# Cleaning"}, {"cell_type": "code", "source": "df.dropna()"}]}"##,
+        )
+        .unwrap();
+        fs::write(refs.join("broken.ipynb"), "{").unwrap();
 
         let indexer = SkillIndexer::new(temp_dir.path());
         indexer.reload().unwrap();
 
+        let content_index = indexer.get_content_index();
+        let entry = content_index
+            .get("data:references/walkthrough.ipynb")
+            .unwrap();
+        assert!(entry.content.contains("df.dropna()"));
+        assert_eq!(entry.headings, vec!["Cell 1", "Cleaning", "Cell 2"]);
+        assert!(content_index.get("data:references/broken.ipynb").is_none());
+
         let index = indexer.get_skill_index();
-        assert_eq!(index.len(), 1);
-        assert!(index.find("test-skill").is_some());
+        assert!(index.validation_errors.is_empty());
+        assert!(index
+            .validation_warnings
+            .iter()
+            .any(|w| w.contains("Failed to extract references/broken.ipynb")));
     }
 
     #[test]
-    fn test_read_skill_content() {
+    fn test_main_documents_summarized() {
         let temp_dir = TempDir::new().unwrap();
         create_test_skill(temp_dir.path(), "forms", "Form handling patterns");
+        fs::write(
+            temp_dir.path().join("forms/SKILL.md"),
+            "# Forms\n\nUse this skill to build accessible forms.\n\n```\ncode\n```\n",
+        )
+        .unwrap();
 
         let indexer = SkillIndexer::new(temp_dir.path());
         indexer.reload().unwrap();
+        assert_eq!(
+            indexer.indexed_summary("forms").as_deref(),
+            Some("Use this skill to build accessible forms.")
+        );
+
+        let indexer = SkillIndexer::new(temp_dir.path()).without_summaries();
+        indexer.reload().unwrap();
+        assert_eq!(indexer.indexed_summary("forms"), None);
+    }
+
+    #[test]
+    fn test_images_listed_with_dimensions() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_skill(temp_dir.path(), "forms", "Form handling patterns");
+        let shots = temp_dir.path().join("forms/references/screenshots");
+        fs::create_dir_all(&shots).unwrap();
+        fs::write(shots.join("form.gif"), b"GIF89a\x20\x03\x58\x02").unwrap();
+        fs::write(shots.join("broken.png"), b"not a png").unwrap();
+        fs::write(shots.join("notes.txt"), "ignored").unwrap();
+
+        let indexer = SkillIndexer::new(temp_dir.path());
+        indexer.reload().unwrap();
+
+        let images = indexer.list_images("forms").unwrap();
+        assert_eq!(images.len(), 2);
+        assert_eq!(images[0].path, "references/screenshots/broken.png");
+        assert_eq!(images[0].width, None);
+        assert_eq!(images[1].format, ImageFormat::Gif);
+        assert_eq!((images[1].width, images[1].height), (Some(800), Some(600)));
 
         let content = indexer.read_skill_content("forms").unwrap();
-        assert_eq!(content.name, "forms");
-        assert!(content.content.contains("Form handling patterns"));
+        assert_eq!(content.images, images);
+        assert!(indexer.list_images("missing").is_err());
     }
 
     #[test]
-    fn test_missing_skill() {
+    fn test_invalid_utf8_indexed_lossily() {
         let temp_dir = TempDir::new().unwrap();
+        create_test_skill(temp_dir.path(), "forms", "Form handling patterns");
+        fs::write(
+            temp_dir.path().join("forms/SKILL.md"),
+            b"# Forms\n\nCaf\xe9 validation",
+        )
+        .unwrap();
+
         let indexer = SkillIndexer::new(temp_dir.path());
         indexer.reload().unwrap();
 
-        let result = indexer.read_skill_content("nonexistent");
-        assert!(result.is_err());
+        let entry = indexer.get_content_index().get("forms").cloned().unwrap();
+        assert!(entry.content.contains("validation"));
+        assert!(entry.content.contains('\u{FFFD}'));
+
+        let index = indexer.get_skill_index();
+        assert!(index
+            .validation_errors
+            .iter()
+            .any(|e| e.contains("forms: SKILL.md is not valid UTF-8")));
+
+        let content = indexer.read_skill_content("forms").unwrap();
+        assert!(content.content.contains("validation"));
+    }
+
+    #[test]
+    fn test_max_file_size_skips_large_files() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_skill(temp_dir.path(), "forms", "Form handling patterns");
+        let refs = temp_dir.path().join("forms/references");
+        fs::create_dir_all(&refs).unwrap();
+        fs::write(refs.join("huge.md"), "x".repeat(2048)).unwrap();
+
+        let indexer = SkillIndexer::with_options(
+            temp_dir.path(),
+            IndexerOptions::default().max_file_size(1024),
+        );
+        indexer.reload().unwrap();
+
+        assert!(indexer.get_content_index().get("forms").is_some());
+        assert!(indexer
+            .get_content_index()
+            .get("forms:references/huge.md")
+            .is_none());
+        assert!(indexer
+            .get_skill_index()
+            .validation_errors
+            .iter()
+            .any(|e| e.contains("Skipped references/huge.md")));
+
+        // Incremental updates record the same error without duplicating it
+        indexer.update_skill("forms").unwrap();
+        let skipped = indexer
+            .get_skill_index()
+            .validation_errors
+            .iter()
+            .filter(|e| e.contains("Skipped references/huge.md"))
+            .count();
+        assert_eq!(skipped, 1);
+
+        // Still readable directly
+        let path = indexer
+            .resolve_skill_file("forms", "references/huge.md")
+            .unwrap();
+        assert_eq!(fs::metadata(path).unwrap().len(), 2048);
+    }
+
+    #[test]
+    fn test_resolve_skill_file_rejects_escapes() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_skill(temp_dir.path(), "forms", "Form handling patterns");
+        create_test_skill(temp_dir.path(), "other", "Other skill");
+
+        let indexer = SkillIndexer::new(temp_dir.path());
+        indexer.reload().unwrap();
+
+        assert!(indexer.resolve_skill_file("forms", "SKILL.md").is_ok());
+        assert!(matches!(
+            indexer.resolve_skill_file("forms", "../other/SKILL.md"),
+            Err(IndexError::ValidationError(_))
+        ));
+        assert!(matches!(
+            indexer.resolve_skill_file("forms", "missing.md"),
+            Err(IndexError::NotFound(_))
+        ));
+        assert!(matches!(
+            indexer.resolve_skill_file("missing", "SKILL.md"),
+            Err(IndexError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_sub_skills_under_references_indexed_once() {
+        let temp_dir = TempDir::new().unwrap();
+        let skill_dir = temp_dir.path().join("forms");
+        fs::create_dir_all(skill_dir.join("references")).unwrap();
+        fs::write(
+            skill_dir.join("_meta.json"),
+            r#"{"name": "forms", "description": "Forms", "sub_skills": [{"name": "react", "file": "references/react.md"}]}"#,
+        )
+        .unwrap();
+        fs::write(skill_dir.join("SKILL.md"), "# Forms").unwrap();
+        fs::write(skill_dir.join("references/react.md"), "# React").unwrap();
+        fs::write(skill_dir.join("references/extra.md"), "# Extra").unwrap();
+
+        let indexer = SkillIndexer::new(temp_dir.path());
+        indexer.reload().unwrap();
+
+        let content_index = indexer.get_content_index();
+        let mut keys: Vec<&String> = content_index.entries.keys().collect();
+        keys.sort();
+        assert_eq!(
+            keys,
+            vec![
+                "forms",
+                "forms:_meta.json",
+                "forms:react",
+                "forms:references/extra.md"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nested_sub_skills() {
+        let temp_dir = TempDir::new().unwrap();
+        let skill_dir = temp_dir.path().join("forms");
+        fs::create_dir_all(skill_dir.join("react")).unwrap();
+        fs::write(
+            skill_dir.join("_meta.json"),
+            r#"{"name": "forms", "description": "Forms", "sub_skills": [{"name": "react", "file": "react/SKILL.md", "children": [{"name": "hooks", "file": "react\\hooks.md"}]}]}"#,
+        )
+        .unwrap();
+        fs::write(skill_dir.join("SKILL.md"), "# Forms").unwrap();
+        fs::write(skill_dir.join("react/SKILL.md"), "# React").unwrap();
+        fs::write(skill_dir.join("react/hooks.md"), "# Hooks").unwrap();
+
+        let indexer = SkillIndexer::new(temp_dir.path());
+        indexer.reload().unwrap();
+
+        let meta = indexer.get_skill_meta("forms").unwrap();
+        assert_eq!(
+            meta.find_sub_skill("react/hooks").unwrap().file,
+            "react/hooks.md"
+        );
+
+        let content = indexer.read_skill_content("forms").unwrap();
+        assert_eq!(content.sub_skills, vec!["react", "react/hooks"]);
+
+        let sub = indexer
+            .read_sub_skill_content("forms", "react/hooks")
+            .unwrap();
+        assert_eq!(sub.content, "# Hooks");
+        assert!(matches!(
+            indexer.read_sub_skill_content("forms", "react/missing"),
+            Err(IndexError::NotFound(_))
+        ));
+
+        let content_index = indexer.get_content_index();
+        assert!(content_index.entries.contains_key("forms:react/hooks"));
+    }
+
+    #[test]
+    fn test_sub_skill_references_read_from_target() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_skill(temp_dir.path(), "forms", "Forms");
+        let forms = temp_dir.path().join("forms");
+        fs::write(
+            forms.join("_meta.json"),
+            r#"{"name": "forms", "description": "Forms", "sub_skills": [{"name": "react", "file": "react.md"}]}"#,
+        )
+        .unwrap();
+        fs::write(forms.join("react.md"), "# React forms").unwrap();
+        create_test_skill(temp_dir.path(), "ui", "UI");
+        fs::write(
+            temp_dir.path().join("ui/_meta.json"),
+            r#"{"name": "ui", "description": "UI", "sub_skills": [{"name": "forms", "ref": "forms:react"}, {"name": "loop", "ref": "ui:loop"}]}"#,
+        )
+        .unwrap();
+
+        let indexer = SkillIndexer::new(temp_dir.path());
+        indexer.reload().unwrap();
+
+        let sub = indexer.read_sub_skill_content("ui", "forms").unwrap();
+        assert_eq!(sub.domain, "ui");
+        assert_eq!(sub.content, "# React forms");
+
+        let err = indexer.read_sub_skill_content("ui", "loop").unwrap_err();
+        assert!(err.to_string().contains("cycle: ui:loop -> ui:loop"));
+
+        // The shared file is only indexed under the skill that owns it
+        let content_index = indexer.get_content_index();
+        assert!(content_index.entries.contains_key("forms:react"));
+        assert!(!content_index.entries.contains_key("ui:forms"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinked_skills_deduped_and_cycles_reported() {
+        use std::os::unix::fs::symlink;
+
+        let monorepo = TempDir::new().unwrap();
+        create_test_skill(monorepo.path(), "forms", "Shared form patterns");
+        let refs = monorepo.path().join("forms/references");
+        fs::create_dir_all(&refs).unwrap();
+        fs::write(refs.join("guide.md"), "# Guide").unwrap();
+        symlink(&refs, refs.join("loop")).unwrap();
+        symlink(refs.join("guide.md"), refs.join("guide-alias.md")).unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        symlink(monorepo.path().join("forms"), temp_dir.path().join("forms")).unwrap();
+        symlink(
+            monorepo.path().join("forms"),
+            temp_dir.path().join("forms-alias"),
+        )
+        .unwrap();
+        symlink(temp_dir.path(), temp_dir.path().join("self")).unwrap();
+
+        let indexer = SkillIndexer::new(temp_dir.path());
+        indexer.reload().unwrap();
+
+        let index = indexer.get_skill_index();
+        assert_eq!(index.len(), 1);
+        assert!(index.find("forms").is_some());
+        assert!(index
+            .validation_errors
+            .iter()
+            .any(|e| e.starts_with("forms-alias: Duplicate of 'forms'")));
+        assert!(index
+            .validation_errors
+            .iter()
+            .any(|e| e.starts_with("self: Symlink cycle")));
+        assert!(index
+            .validation_errors
+            .iter()
+            .any(|e| e.starts_with("forms: Symlink cycle")));
+
+        let content_index = indexer.get_content_index();
+        let reference_files = content_index
+            .iter()
+            .filter(|(_, e)| e.file.starts_with("references"))
+            .count();
+        assert_eq!(reference_files, 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinks_skipped_when_not_followed() {
+        use std::os::unix::fs::symlink;
+
+        let monorepo = TempDir::new().unwrap();
+        create_test_skill(monorepo.path(), "forms", "Shared form patterns");
+
+        let temp_dir = TempDir::new().unwrap();
+        create_test_skill(temp_dir.path(), "local", "Local skill");
+        symlink(monorepo.path().join("forms"), temp_dir.path().join("forms")).unwrap();
+
+        let indexer = SkillIndexer::with_options(
+            temp_dir.path(),
+            IndexerOptions::default().follow_links(false),
+        );
+        indexer.reload().unwrap();
+
+        let index = indexer.get_skill_index();
+        assert_eq!(index.len(), 1);
+        assert!(index.find("local").is_some());
+
+        indexer.update_skill("forms").unwrap();
+        assert!(indexer.get_skill_meta("forms").is_none());
     }
 }
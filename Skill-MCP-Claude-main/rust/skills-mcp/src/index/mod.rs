@@ -3,8 +3,26 @@
 //! Responsible for scanning skill directories, building metadata indexes,
 //! and creating content indexes for full-text search.
 
-mod indexer;
+mod assets;
+mod disk;
+mod extract;
 mod file_watcher;
+mod indexer;
+mod reload;
+mod skills_ignore;
+mod summary;
 
-pub use indexer::{IndexError, SkillIndexer};
+pub(crate) use assets::{read_asset_map, AssetStore};
+pub use assets::{ASSETS_DIR, ASSET_MAP_FILE};
+pub use extract::ReferenceFormat;
 pub use file_watcher::{FileWatcher, WatchError};
+pub use indexer::{
+    CachedSummary, ChangeListener, ChangeSubscription, IndexError, IndexerOptions, SkillIndexer,
+    MAX_CATEGORY_DEPTH,
+};
+pub use reload::{ReloadJob, ReloadJobs, ReloadState};
+pub use skills_ignore::SkillsIgnore;
+pub(crate) use summary::model_summary;
+#[cfg(feature = "llm")]
+pub use summary::LlmSummarizer;
+pub use summary::{ExtractiveSummarizer, Summarizer, DEFAULT_SUMMARY_WORDS};
@@ -0,0 +1,322 @@
+//! Index reloads run as background jobs.
+//!
+//! A full reload rescans every skill, which is too slow to run on an async
+//! worker. [`ReloadJobs`] runs reloads on the blocking pool, one at a time.
+//! A request that arrives while a reload is running queues a single
+//! follow-up, since the running scan may have missed the change that
+//! prompted it; further requests join that follow-up instead of queueing
+//! more.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::Serialize;
+use tokio::sync::Notify;
+use tracing::{error, info};
+
+use super::{IndexError, SkillIndexer};
+
+/// Number of finished jobs kept for polling.
+const MAX_FINISHED_JOBS: usize = 32;
+
+/// Where a reload job is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReloadState {
+    /// Waiting for the running reload to finish.
+    Queued,
+    /// Rescanning the skills directory.
+    Running,
+    /// The new index is live.
+    Succeeded,
+    /// The reload failed; the previous index is still live.
+    Failed,
+}
+
+/// A requested reload and its outcome.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReloadJob {
+    /// Id to poll the job by.
+    pub id: u64,
+    /// Where the job is.
+    pub state: ReloadState,
+    /// When the job was first requested.
+    pub requested_at: DateTime<Utc>,
+    /// When the job finished.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<DateTime<Utc>>,
+    /// Number of skills in the index after a successful reload.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skill_count: Option<usize>,
+    /// Number of content entries in the index after a successful reload.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_entries: Option<usize>,
+    /// Why the reload failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ReloadJob {
+    fn new(id: u64) -> Self {
+        Self {
+            id,
+            state: ReloadState::Queued,
+            requested_at: Utc::now(),
+            finished_at: None,
+            skill_count: None,
+            content_entries: None,
+            error: None,
+        }
+    }
+
+    /// Whether the job has succeeded or failed.
+    pub fn is_finished(&self) -> bool {
+        matches!(self.state, ReloadState::Succeeded | ReloadState::Failed)
+    }
+}
+
+/// Bookkeeping of the running, queued and recently finished jobs.
+#[derive(Debug, Default)]
+struct Jobs {
+    next_id: u64,
+    running: Option<u64>,
+    queued: Option<u64>,
+    /// Every unfinished job and the latest finished ones, oldest first.
+    jobs: VecDeque<ReloadJob>,
+}
+
+impl Jobs {
+    /// Start a job, or join the queued one. Returns the job and whether
+    /// the caller must run it.
+    fn request(&mut self) -> (ReloadJob, bool) {
+        if let Some(id) = self.queued {
+            return (
+                self.get(id).cloned().unwrap_or_else(|| ReloadJob::new(id)),
+                false,
+            );
+        }
+
+        self.next_id += 1;
+        let mut job = ReloadJob::new(self.next_id);
+        let start = self.running.is_none();
+        if start {
+            job.state = ReloadState::Running;
+            self.running = Some(job.id);
+        } else {
+            self.queued = Some(job.id);
+        }
+        self.jobs.push_back(job.clone());
+        (job, start)
+    }
+
+    /// Record the outcome of the running job and start the queued one, if
+    /// any, returning its id.
+    fn finish(&mut self, id: u64, outcome: Result<(usize, usize), String>) -> Option<u64> {
+        if let Some(job) = self.get_mut(id) {
+            job.finished_at = Some(Utc::now());
+            match outcome {
+                Ok((skills, content)) => {
+                    job.state = ReloadState::Succeeded;
+                    job.skill_count = Some(skills);
+                    job.content_entries = Some(content);
+                }
+                Err(e) => {
+                    job.state = ReloadState::Failed;
+                    job.error = Some(e);
+                }
+            }
+        }
+
+        self.running = self.queued.take();
+        if let Some(next) = self.running.and_then(|next| self.get_mut(next)) {
+            next.state = ReloadState::Running;
+        }
+        self.prune();
+        self.running
+    }
+
+    fn get(&self, id: u64) -> Option<&ReloadJob> {
+        self.jobs.iter().find(|job| job.id == id)
+    }
+
+    fn get_mut(&mut self, id: u64) -> Option<&mut ReloadJob> {
+        self.jobs.iter_mut().find(|job| job.id == id)
+    }
+
+    /// Drop the oldest finished jobs beyond [`MAX_FINISHED_JOBS`].
+    fn prune(&mut self) {
+        let mut excess = self
+            .jobs
+            .iter()
+            .filter(|job| job.is_finished())
+            .count()
+            .saturating_sub(MAX_FINISHED_JOBS);
+        self.jobs.retain(|job| {
+            if excess > 0 && job.is_finished() {
+                excess -= 1;
+                false
+            } else {
+                true
+            }
+        });
+    }
+}
+
+struct Inner {
+    indexer: Arc<SkillIndexer>,
+    jobs: Mutex<Jobs>,
+    /// Woken whenever a job finishes.
+    finished: Notify,
+}
+
+/// Runs index reloads off the async workers, coalescing concurrent
+/// requests.
+#[derive(Clone)]
+pub struct ReloadJobs {
+    inner: Arc<Inner>,
+}
+
+impl ReloadJobs {
+    /// Reload `indexer` through this queue.
+    pub fn new(indexer: Arc<SkillIndexer>) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                indexer,
+                jobs: Mutex::new(Jobs::default()),
+                finished: Notify::new(),
+            }),
+        }
+    }
+
+    /// Request a reload and return its job without waiting for it.
+    ///
+    /// Starts a reload when none is running. Otherwise the request is
+    /// coalesced into the single reload queued behind the running one.
+    /// Must be called from within a Tokio runtime.
+    pub fn request(&self) -> ReloadJob {
+        let (job, start) = self.inner.jobs.lock().request();
+        if start {
+            let inner = Arc::clone(&self.inner);
+            tokio::task::spawn_blocking(move || run(&inner, job.id));
+        }
+        job
+    }
+
+    /// The job with `id`, if it is unfinished or recently finished.
+    pub fn get(&self, id: u64) -> Option<ReloadJob> {
+        self.inner.jobs.lock().get(id).cloned()
+    }
+
+    /// Wait for the job with `id` to finish and return it.
+    ///
+    /// Returns `None` for unknown jobs.
+    pub async fn wait(&self, id: u64) -> Option<ReloadJob> {
+        loop {
+            // Registered before checking, so a finish in between still wakes us
+            let finished = self.inner.finished.notified();
+            let job = self.get(id)?;
+            if job.is_finished() {
+                return Some(job);
+            }
+            finished.await;
+        }
+    }
+
+    /// Request a reload and wait for it to finish.
+    pub async fn reload(&self) -> ReloadJob {
+        let job = self.request();
+        self.wait(job.id).await.unwrap_or(job)
+    }
+}
+
+/// Run job `id`, then every job queued behind it.
+fn run(inner: &Inner, mut id: u64) {
+    loop {
+        info!("Running reload job {}", id);
+        let outcome = inner
+            .indexer
+            .reload()
+            .map(|()| {
+                (
+                    inner.indexer.get_skill_index().len(),
+                    inner.indexer.get_content_index().len(),
+                )
+            })
+            .map_err(|e: IndexError| {
+                error!("Reload job {} failed: {}", id, e);
+                e.to_string()
+            });
+
+        let next = inner.jobs.lock().finish(id, outcome);
+        inner.finished.notify_waiters();
+        match next {
+            Some(next) => id = next,
+            None => break,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::SkillFixture;
+
+    #[test]
+    fn test_requests_coalesce_behind_running_job() {
+        let mut jobs = Jobs::default();
+
+        let (first, start) = jobs.request();
+        assert!(start);
+        assert_eq!(first.state, ReloadState::Running);
+
+        // Requests during a run share one queued follow-up
+        let (second, start) = jobs.request();
+        assert!(!start);
+        assert_eq!(second.state, ReloadState::Queued);
+        let (third, _) = jobs.request();
+        assert_eq!(third.id, second.id);
+
+        assert_eq!(jobs.finish(first.id, Ok((2, 5))), Some(second.id));
+        assert_eq!(jobs.get(first.id).unwrap().skill_count, Some(2));
+        assert_eq!(jobs.get(second.id).unwrap().state, ReloadState::Running);
+
+        assert_eq!(jobs.finish(second.id, Err("boom".to_string())), None);
+        let failed = jobs.get(second.id).unwrap();
+        assert_eq!(failed.state, ReloadState::Failed);
+        assert_eq!(failed.error.as_deref(), Some("boom"));
+
+        // Idle again, so the next request runs right away
+        assert!(jobs.request().1);
+    }
+
+    #[test]
+    fn test_finished_jobs_are_pruned() {
+        let mut jobs = Jobs::default();
+        for _ in 0..MAX_FINISHED_JOBS + 3 {
+            let (job, _) = jobs.request();
+            jobs.finish(job.id, Ok((0, 0)));
+        }
+
+        assert_eq!(jobs.jobs.len(), MAX_FINISHED_JOBS);
+        assert!(jobs.get(1).is_none());
+        assert!(jobs.get(jobs.next_id).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_reload_runs_in_background() {
+        let indexer =
+            SkillIndexer::from_memory(vec![SkillFixture::new("forms", "Form handling")]).unwrap();
+        let reloads = ReloadJobs::new(Arc::new(indexer));
+
+        let job = reloads.request();
+        let done = reloads.wait(job.id).await.unwrap();
+        assert_eq!(done.state, ReloadState::Succeeded);
+        assert_eq!(done.skill_count, Some(1));
+        assert!(done.finished_at.is_some());
+
+        assert_eq!(reloads.reload().await.state, ReloadState::Succeeded);
+        assert!(reloads.wait(999).await.is_none());
+    }
+}
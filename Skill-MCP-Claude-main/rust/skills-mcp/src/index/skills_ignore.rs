@@ -0,0 +1,141 @@
+//! `.skillsignore` support for excluding paths from the index.
+
+use std::path::{Path, PathBuf};
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use tracing::warn;
+
+/// Path exclusion rules loaded from a `.skillsignore` file.
+///
+/// The file lives at the root of the skills directory and uses gitignore
+/// syntax. Patterns are matched against paths relative to that root, so
+/// `scratch/` excludes a whole skill and `*/references/generated/` excludes
+/// a subdirectory inside every skill.
+#[derive(Debug, Clone)]
+pub struct SkillsIgnore {
+    root: PathBuf,
+    matcher: Gitignore,
+    errors: Vec<String>,
+}
+
+impl SkillsIgnore {
+    /// Name of the ignore file at the skills root.
+    pub const FILE_NAME: &'static str = ".skillsignore";
+
+    /// Load ignore rules for a skills directory.
+    ///
+    /// A missing file yields an empty rule set. Invalid patterns are skipped
+    /// and reported through [`SkillsIgnore::errors`].
+    pub fn load(skills_dir: &Path) -> Self {
+        let mut builder = GitignoreBuilder::new(skills_dir);
+        let mut errors = Vec::new();
+
+        let ignore_file = skills_dir.join(Self::FILE_NAME);
+        if ignore_file.is_file() {
+            if let Some(e) = builder.add(&ignore_file) {
+                warn!("Problem reading {:?}: {}", ignore_file, e);
+                errors.push(format!("{}: {}", Self::FILE_NAME, e));
+            }
+        }
+
+        let matcher = builder.build().unwrap_or_else(|e| {
+            errors.push(format!("{}: {}", Self::FILE_NAME, e));
+            Gitignore::empty()
+        });
+
+        Self {
+            root: skills_dir.to_path_buf(),
+            matcher,
+            errors,
+        }
+    }
+
+    /// Check whether a path is excluded.
+    ///
+    /// Accepts paths either relative to the skills root or absolute paths
+    /// under it. A path is excluded if it or any of its parent directories
+    /// matches. Paths outside the skills root are never excluded.
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        if self.matcher.is_empty() {
+            return false;
+        }
+
+        let relative = if path.is_absolute() {
+            match path.strip_prefix(&self.root) {
+                Ok(rel) => rel,
+                Err(_) => return false,
+            }
+        } else {
+            path
+        };
+
+        if relative.as_os_str().is_empty() {
+            return false;
+        }
+
+        self.matcher
+            .matched_path_or_any_parents(relative, is_dir)
+            .is_ignore()
+    }
+
+    /// Number of exclusion patterns loaded (negated `!` patterns are not counted).
+    pub fn len(&self) -> usize {
+        self.matcher.num_ignores() as usize
+    }
+
+    /// Check whether no patterns were loaded.
+    pub fn is_empty(&self) -> bool {
+        self.matcher.is_empty()
+    }
+
+    /// Problems encountered while parsing the ignore file.
+    pub fn errors(&self) -> &[String] {
+        &self.errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_missing_file_ignores_nothing() {
+        let temp_dir = TempDir::new().unwrap();
+        let ignore = SkillsIgnore::load(temp_dir.path());
+
+        assert!(ignore.is_empty());
+        assert!(!ignore.is_ignored(Path::new("forms"), true));
+    }
+
+    #[test]
+    fn test_gitignore_patterns() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join(SkillsIgnore::FILE_NAME),
+            "# scratch work\nscratch/\n*.tmp.md\n*/references/generated/\n!keep.tmp.md\n",
+        )
+        .unwrap();
+
+        let ignore = SkillsIgnore::load(temp_dir.path());
+        assert_eq!(ignore.len(), 3);
+
+        assert!(ignore.is_ignored(Path::new("scratch"), true));
+        assert!(ignore.is_ignored(Path::new("scratch/SKILL.md"), false));
+        assert!(ignore.is_ignored(Path::new("forms/notes.tmp.md"), false));
+        assert!(ignore.is_ignored(Path::new("forms/references/generated/api.md"), false));
+        assert!(!ignore.is_ignored(Path::new("forms/keep.tmp.md"), false));
+        assert!(!ignore.is_ignored(Path::new("forms/SKILL.md"), false));
+    }
+
+    #[test]
+    fn test_absolute_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join(SkillsIgnore::FILE_NAME), "drafts/\n").unwrap();
+
+        let ignore = SkillsIgnore::load(temp_dir.path());
+        assert!(ignore.is_ignored(&temp_dir.path().join("drafts/SKILL.md"), false));
+        assert!(!ignore.is_ignored(Path::new("/elsewhere/drafts/SKILL.md"), false));
+    }
+}
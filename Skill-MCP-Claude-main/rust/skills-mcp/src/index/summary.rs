@@ -0,0 +1,356 @@
+//! Short summaries of skills for listings and search results.
+//!
+//! Summaries are made at index time from a skill's main document. The
+//! default summarizer is extractive: it keeps the sentences that best
+//! cover the document's frequent terms, in their original order. With the
+//! `llm` feature, [`LlmSummarizer`] asks a language model instead.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+use crate::llm::{CompletionRequest, LlmBackend, LlmError};
+
+/// Target summary length in words.
+pub const DEFAULT_SUMMARY_WORDS: usize = 100;
+
+/// Words too common to say what a document is about.
+const STOPWORDS: &[&str] = &[
+    "about", "after", "also", "been", "before", "being", "both", "each", "from", "have", "here",
+    "into", "just", "like", "make", "more", "most", "must", "only", "other", "over", "same",
+    "should", "some", "such", "than", "that", "their", "them", "then", "there", "these", "they",
+    "this", "those", "through", "under", "very", "want", "were", "what", "when", "where", "which",
+    "while", "will", "with", "would", "your",
+];
+
+/// Produces a summary of a skill's main document.
+pub trait Summarizer: Send + Sync {
+    /// Summarize `markdown`, the main document of skill `name`.
+    ///
+    /// Returns `None` when there is no prose to summarize.
+    fn summarize(&self, name: &str, markdown: &str) -> Option<String>;
+}
+
+/// Picks the most representative sentences of a document.
+#[derive(Debug, Clone, Copy)]
+pub struct ExtractiveSummarizer {
+    max_words: usize,
+}
+
+impl ExtractiveSummarizer {
+    /// Create a summarizer that keeps about `max_words` words.
+    pub fn new(max_words: usize) -> Self {
+        Self {
+            max_words: max_words.max(1),
+        }
+    }
+}
+
+impl Default for ExtractiveSummarizer {
+    fn default() -> Self {
+        Self::new(DEFAULT_SUMMARY_WORDS)
+    }
+}
+
+impl Summarizer for ExtractiveSummarizer {
+    fn summarize(&self, _name: &str, markdown: &str) -> Option<String> {
+        let sentences = prose_sentences(markdown);
+        if sentences.is_empty() {
+            return None;
+        }
+
+        // Term frequencies over the whole document
+        let mut frequency: HashMap<String, usize> = HashMap::new();
+        for sentence in &sentences {
+            for term in terms(sentence) {
+                *frequency.entry(term).or_default() += 1;
+            }
+        }
+
+        // Sentences covering frequent terms score higher, as do early ones
+        let mut ranked: Vec<(usize, f64)> = sentences
+            .iter()
+            .enumerate()
+            .map(|(i, sentence)| {
+                let unique: HashSet<String> = terms(sentence).collect();
+                let coverage: usize = unique.iter().map(|t| frequency[t]).sum();
+                let words = sentence.split_whitespace().count().max(1) as f64;
+                let position = 1.0 / (1.0 + i as f64);
+                (i, coverage as f64 / words.sqrt() + position * 2.0)
+            })
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+
+        let mut chosen = Vec::new();
+        let mut words = 0;
+        for (i, _) in ranked {
+            let length = sentences[i].split_whitespace().count();
+            if words > 0 && words + length > self.max_words {
+                continue;
+            }
+            chosen.push(i);
+            words += length;
+            if words >= self.max_words {
+                break;
+            }
+        }
+        chosen.sort_unstable();
+
+        let summary = chosen
+            .iter()
+            .map(|&i| sentences[i].as_str())
+            .collect::<Vec<_>>()
+            .join(" ");
+        Some(truncate_words(&summary, self.max_words))
+    }
+}
+
+/// Summarizes with a language model, falling back to extraction.
+///
+/// Summaries are cached by document content, so reloading an unchanged
+/// skill doesn't call the model again.
+#[cfg(feature = "llm")]
+pub struct LlmSummarizer {
+    backend: std::sync::Arc<dyn crate::llm::LlmBackend>,
+    fallback: ExtractiveSummarizer,
+    cache: parking_lot::Mutex<HashMap<String, String>>,
+}
+
+#[cfg(feature = "llm")]
+impl LlmSummarizer {
+    /// Create a summarizer backed by `backend`.
+    pub fn new(backend: std::sync::Arc<dyn crate::llm::LlmBackend>) -> Self {
+        Self {
+            backend,
+            fallback: ExtractiveSummarizer::default(),
+            cache: parking_lot::Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+#[cfg(feature = "llm")]
+impl Summarizer for LlmSummarizer {
+    fn summarize(&self, name: &str, markdown: &str) -> Option<String> {
+        let key = crate::models::content_hash(markdown);
+        if let Some(cached) = self.cache.lock().get(&key) {
+            return Some(cached.clone());
+        }
+
+        match model_summary(self.backend.as_ref(), name, markdown) {
+            Ok(summary) => {
+                self.cache.lock().insert(key, summary.clone());
+                Some(summary)
+            }
+            Err(LlmError::InvalidResponse(_)) => self.fallback.summarize(name, markdown),
+            Err(e) => {
+                tracing::warn!(
+                    "Summarizing {} with {} failed: {}",
+                    name,
+                    self.backend.name(),
+                    e
+                );
+                self.fallback.summarize(name, markdown)
+            }
+        }
+    }
+}
+
+/// How much of a document is sent to a model to summarize.
+const MAX_PROMPT_CHARS: usize = 12_000;
+
+/// Ask `backend` to summarize `markdown`, the main document of skill
+/// `name`, in at most [`DEFAULT_SUMMARY_WORDS`] words.
+pub(crate) fn model_summary(
+    backend: &dyn LlmBackend,
+    name: &str,
+    markdown: &str,
+) -> Result<String, LlmError> {
+    let document: String = markdown.chars().take(MAX_PROMPT_CHARS).collect();
+    let request = CompletionRequest::new(
+        format!(
+            "Summarize instruction documents for a search listing in at most {} words of \
+             plain prose. Say what the skill helps with and when to use it. Reply with the \
+             summary only.",
+            DEFAULT_SUMMARY_WORDS
+        ),
+        format!("Skill: {}\n\n{}", name, document),
+    );
+
+    let reply = backend.complete(&request)?;
+    if reply.trim().is_empty() {
+        return Err(LlmError::InvalidResponse("empty summary".to_string()));
+    }
+    Ok(truncate_words(reply.trim(), DEFAULT_SUMMARY_WORDS))
+}
+
+/// Split a markdown document into prose sentences.
+///
+/// Headings, code blocks, tables, HTML and frontmatter are skipped; inline
+/// markup is removed.
+fn prose_sentences(markdown: &str) -> Vec<String> {
+    static LINK: OnceLock<Regex> = OnceLock::new();
+    static SENTENCE_END: OnceLock<Regex> = OnceLock::new();
+    let link = LINK.get_or_init(|| Regex::new(r"!?\[([^\]]*)\]\([^)]*\)").unwrap());
+    let sentence_end = SENTENCE_END.get_or_init(|| Regex::new(r"[.!?](\s+|$)").unwrap());
+
+    let body = crate::models::split_frontmatter(markdown)
+        .map(|(_, body)| body)
+        .unwrap_or(markdown);
+
+    let mut paragraphs: Vec<String> = vec![String::new()];
+    let mut in_fence = false;
+    for line in body.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        let skipped = in_fence
+            || trimmed.starts_with('#')
+            || trimmed.starts_with('|')
+            || trimmed.starts_with('<')
+            || trimmed.starts_with("---");
+        if skipped || trimmed.is_empty() {
+            paragraphs.push(String::new());
+            continue;
+        }
+
+        // Each list item stands alone rather than running into the next
+        if trimmed.starts_with(['-', '*', '+']) && trimmed[1..].starts_with(' ') {
+            paragraphs.push(String::new());
+        }
+
+        let text = trimmed.trim_start_matches(['-', '*', '+', '>', ' ']);
+        let text = link.replace_all(text, "$1");
+        let current = paragraphs.last_mut().unwrap();
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(&text.replace(['`', '*'], ""));
+    }
+
+    let mut sentences = Vec::new();
+    for paragraph in paragraphs.iter().filter(|p| !p.is_empty()) {
+        let mut start = 0;
+        for m in sentence_end.find_iter(paragraph) {
+            let sentence = paragraph[start..m.start() + 1].trim();
+            if sentence.split_whitespace().count() >= 3 {
+                sentences.push(sentence.to_string());
+            }
+            start = m.end();
+        }
+        // A trailing fragment without end punctuation, such as a list item
+        let rest = paragraph[start..].trim();
+        if rest.split_whitespace().count() >= 3 {
+            sentences.push(format!("{}.", rest.trim_end_matches([':', ',', ';'])));
+        }
+    }
+
+    sentences
+}
+
+/// Lowercase content words of a sentence.
+fn terms(sentence: &str) -> impl Iterator<Item = String> + '_ {
+    sentence
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.len() > 3)
+        .map(str::to_lowercase)
+        .filter(|w| !STOPWORDS.contains(&w.as_str()))
+}
+
+/// Keep at most `max` words, marking the cut with an ellipsis.
+fn truncate_words(text: &str, max: usize) -> String {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    if words.len() <= max {
+        return words.join(" ");
+    }
+    format!(
+        "{}…",
+        words[..max]
+            .join(" ")
+            .trim_end_matches([',', ';', ':', '.'])
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extractive_summary_prefers_central_sentences() {
+        let markdown = "---\nname: forms\n---\n# Forms\n\n\
+            This skill helps you build accessible forms with validation.\n\n\
+            ## Setup\n\n```js\nconst x = 1;\n```\n\n\
+            | a | b |\n|---|---|\n\n\
+            Weather is nice today and unrelated to anything here.\n\
+            Form validation should run on blur and on submit so forms stay accessible.\n\n\
+            - Label every field in the [forms guide](https://example.com)\n";
+
+        let summary = ExtractiveSummarizer::new(25)
+            .summarize("forms", markdown)
+            .unwrap();
+
+        assert!(summary.starts_with("This skill helps you build accessible forms"));
+        assert!(summary.contains("Form validation should run on blur"));
+        assert!(!summary.contains("Weather"));
+        assert!(!summary.contains("const"));
+        assert!(summary.split_whitespace().count() <= 25);
+    }
+
+    #[test]
+    fn test_summary_is_truncated_and_none_without_prose() {
+        let long = format!("# Title\n\n{}.\n", "word ".repeat(300).trim());
+        let summary = ExtractiveSummarizer::default()
+            .summarize("x", &long)
+            .unwrap();
+        assert_eq!(summary.split_whitespace().count(), DEFAULT_SUMMARY_WORDS);
+        assert!(summary.ends_with('…'));
+
+        assert_eq!(
+            ExtractiveSummarizer::default().summarize("x", "# Only\n\n```\ncode\n```\n"),
+            None
+        );
+    }
+
+    #[cfg(feature = "llm")]
+    #[test]
+    fn test_llm_summarizer_caches_and_falls_back() {
+        use crate::llm::{CompletionRequest, LlmBackend, LlmError};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        struct Counting(AtomicUsize, bool);
+
+        impl LlmBackend for Counting {
+            fn name(&self) -> &str {
+                "counting"
+            }
+
+            fn complete(&self, _request: &CompletionRequest) -> Result<String, LlmError> {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                if self.1 {
+                    Ok("Builds forms.".to_string())
+                } else {
+                    Err(LlmError::Request("down".to_string()))
+                }
+            }
+        }
+
+        let text = "This skill helps you build accessible forms.";
+        let backend = Arc::new(Counting(AtomicUsize::new(0), true));
+        let summarizer = LlmSummarizer::new(backend.clone());
+        assert_eq!(
+            summarizer.summarize("forms", text).unwrap(),
+            "Builds forms."
+        );
+        assert_eq!(
+            summarizer.summarize("forms", text).unwrap(),
+            "Builds forms."
+        );
+        assert_eq!(backend.0.load(Ordering::SeqCst), 1);
+
+        let summarizer = LlmSummarizer::new(Arc::new(Counting(AtomicUsize::new(0), false)));
+        assert_eq!(summarizer.summarize("forms", text).unwrap(), text);
+    }
+}
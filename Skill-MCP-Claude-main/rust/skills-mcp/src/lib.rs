@@ -8,6 +8,9 @@
 //! - **Validation**: Schema validation for skill metadata
 //! - **MCP Server**: Model Context Protocol server for Claude integration
 //! - **HTTP API**: REST API for skill management
+//! - **Storage**: Pluggable stores for embedding the library in other apps
+//! - **Import**: Installing skills from GitHub repositories
+//! - **Browser search**: Snapshot search compiled to WebAssembly (`wasm` feature)
 //!
 //! # Architecture
 //!
@@ -96,23 +99,47 @@
 #![warn(missing_docs)]
 #![warn(clippy::all)]
 
+#[cfg(feature = "native")]
 pub mod api;
+#[cfg(feature = "native")]
+pub mod import;
+#[cfg(feature = "native")]
 pub mod index;
+#[cfg(feature = "native")]
+pub mod llm;
+#[cfg(feature = "native")]
+pub mod maintenance;
+#[cfg(feature = "native")]
 pub mod mcp;
 pub mod models;
 pub mod search;
+#[cfg(feature = "native")]
+pub mod store;
+#[cfg(all(feature = "native", any(test, feature = "testing")))]
+pub mod testing;
+#[cfg(feature = "tui")]
+pub mod tui;
+#[cfg(feature = "native")]
 pub mod validation;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 /// Re-export commonly used types.
 pub mod prelude {
+    #[cfg(feature = "native")]
     pub use crate::api::ApiServer;
+    #[cfg(feature = "native")]
     pub use crate::index::SkillIndexer;
+    #[cfg(feature = "native")]
     pub use crate::mcp::McpServer;
     pub use crate::models::{
-        MatchType, SearchOptions, SearchResult, SearchResults, SkillContent, SkillIndex,
-        SkillMeta, SubSkillContent, SubSkillMeta, UsageStats, ValidationResult,
+        MatchType, SearchOptions, SearchResult, SearchResults, SkillContent, SkillIndex, SkillMeta,
+        SubSkillContent, SubSkillMeta, UsageStats, ValidationResult,
     };
+    pub use crate::search::IndexSnapshot;
+    #[cfg(feature = "native")]
     pub use crate::search::SearchService;
+    #[cfg(feature = "native")]
     pub use crate::validation::{validate_meta, validate_skills};
 }
 
@@ -0,0 +1,112 @@
+//! Pluggable language model backends.
+//!
+//! Features that draft text (metadata suggestions, summaries, answers)
+//! call a [`LlmBackend`] and work without one where they can. Any model can
+//! be plugged in by implementing the trait; an OpenAI-compatible HTTP
+//! backend is included with the `llm` feature.
+//!
+//! Backends are blocking; async callers should use `spawn_blocking`.
+
+#[cfg(feature = "llm")]
+mod openai;
+
+#[cfg(feature = "llm")]
+pub use openai::OpenAiBackend;
+
+/// A single prompt for a backend.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompletionRequest {
+    /// Instructions that frame the task.
+    pub system: String,
+    /// The task itself.
+    pub prompt: String,
+    /// Upper bound on the length of the reply.
+    pub max_tokens: u32,
+}
+
+impl CompletionRequest {
+    /// Default reply length bound.
+    pub const DEFAULT_MAX_TOKENS: u32 = 512;
+
+    /// Create a request with the default reply length bound.
+    pub fn new(system: impl Into<String>, prompt: impl Into<String>) -> Self {
+        Self {
+            system: system.into(),
+            prompt: prompt.into(),
+            max_tokens: Self::DEFAULT_MAX_TOKENS,
+        }
+    }
+
+    /// Set the reply length bound.
+    pub fn with_max_tokens(mut self, max_tokens: u32) -> Self {
+        self.max_tokens = max_tokens;
+        self
+    }
+}
+
+/// A language model that completes prompts.
+pub trait LlmBackend: Send + Sync {
+    /// Short name for logs and responses, e.g. the model id.
+    fn name(&self) -> &str;
+
+    /// Complete a prompt, returning the model's reply text.
+    fn complete(&self, request: &CompletionRequest) -> Result<String, LlmError>;
+}
+
+/// Errors from a language model backend.
+#[derive(Debug, thiserror::Error)]
+pub enum LlmError {
+    /// No backend is configured.
+    #[error("No LLM backend configured")]
+    NotConfigured,
+
+    /// The backend could not be reached or rejected the request.
+    #[error("LLM request failed: {0}")]
+    Request(String),
+
+    /// The reply was not in the expected shape.
+    #[error("Invalid LLM response: {0}")]
+    InvalidResponse(String),
+}
+
+/// Parse the first JSON object in a reply.
+///
+/// Models often wrap JSON in prose or code fences; everything outside the
+/// outermost braces is ignored.
+pub fn parse_json_reply<T: serde::de::DeserializeOwned>(reply: &str) -> Result<T, LlmError> {
+    let start = reply.find('{');
+    let end = reply.rfind('}');
+    let json = match (start, end) {
+        (Some(start), Some(end)) if start < end => &reply[start..=end],
+        _ => {
+            return Err(LlmError::InvalidResponse(
+                "no JSON object in reply".to_string(),
+            ))
+        }
+    };
+
+    serde_json::from_str(json).map_err(|e| LlmError::InvalidResponse(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_json_reply() {
+        #[derive(serde::Deserialize)]
+        struct Reply {
+            answer: u32,
+        }
+
+        let reply: Reply =
+            parse_json_reply("Sure! ```json\n{\"answer\": 42}\n``` Hope that helps.").unwrap();
+        assert_eq!(reply.answer, 42);
+
+        assert!(matches!(
+            parse_json_reply::<Reply>("no json here"),
+            Err(LlmError::InvalidResponse(_))
+        ));
+        assert!(parse_json_reply::<Reply>("{\"answer\": \"x\"}").is_err());
+    }
+}
@@ -0,0 +1,157 @@
+//! Backend for OpenAI-compatible chat completion APIs.
+//!
+//! Works with any server exposing `POST {base_url}/chat/completions`,
+//! including local ones such as Ollama and llama.cpp.
+//!
+//! Each call runs on its own thread and short-lived runtime, so the
+//! backend can be called from anywhere, even code already running on a
+//! runtime (which it blocks). Idle connections aren't pooled, as they would
+//! outlive the runtime that opened them.
+
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use super::{CompletionRequest, LlmBackend, LlmError};
+
+/// A chat completion endpoint and model.
+pub struct OpenAiBackend {
+    client: reqwest::Client,
+    base_url: String,
+    model: String,
+    api_key: Option<String>,
+}
+
+impl OpenAiBackend {
+    /// Default time limit per request.
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+    /// Create a backend for `model` served at `base_url` (e.g.
+    /// `https://api.openai.com/v1`).
+    pub fn new(base_url: impl Into<String>, model: impl Into<String>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Self::DEFAULT_TIMEOUT)
+            .pool_max_idle_per_host(0)
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            model: model.into(),
+            api_key: None,
+        }
+    }
+
+    /// Send a bearer token with each request.
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+}
+
+impl LlmBackend for OpenAiBackend {
+    fn name(&self) -> &str {
+        &self.model
+    }
+
+    fn complete(&self, request: &CompletionRequest) -> Result<String, LlmError> {
+        let body = json!({
+            "model": self.model,
+            "max_tokens": request.max_tokens,
+            "messages": [
+                {"role": "system", "content": request.system},
+                {"role": "user", "content": request.prompt},
+            ],
+        });
+
+        let mut call = self
+            .client
+            .post(format!("{}/chat/completions", self.base_url))
+            .json(&body);
+        if let Some(key) = &self.api_key {
+            call = call.bearer_auth(key);
+        }
+
+        let send = || -> Result<Value, LlmError> {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .map_err(|e| LlmError::Request(format!("Failed to start runtime: {}", e)))?;
+
+            runtime.block_on(async {
+                let response = call
+                    .send()
+                    .await
+                    .map_err(|e| LlmError::Request(e.to_string()))?;
+                let status = response.status();
+                if !status.is_success() {
+                    let detail = response.text().await.unwrap_or_default();
+                    return Err(LlmError::Request(format!(
+                        "HTTP {}: {}",
+                        status,
+                        detail.trim()
+                    )));
+                }
+
+                response
+                    .json()
+                    .await
+                    .map_err(|e| LlmError::InvalidResponse(e.to_string()))
+            })
+        };
+        let reply = std::thread::scope(|scope| scope.spawn(send).join())
+            .map_err(|_| LlmError::Request("LLM request thread panicked".to_string()))??;
+
+        reply["choices"][0]["message"]["content"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| {
+                LlmError::InvalidResponse("missing choices[0].message.content".to_string())
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::post, Json, Router};
+
+    /// Serve a fake chat completion API on a background runtime.
+    fn serve() -> String {
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            runtime.block_on(async move {
+                let app = Router::new().route(
+                    "/v1/chat/completions",
+                    post(|Json(body): Json<Value>| async move {
+                        let prompt = body["messages"][1]["content"].as_str().unwrap_or("");
+                        Json(json!({
+                            "choices": [{"message": {"content": format!("echo: {}", prompt)}}]
+                        }))
+                    }),
+                );
+                let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+                tx.send(listener.local_addr().unwrap()).unwrap();
+                axum::serve(listener, app).await.unwrap();
+            });
+        });
+        format!("http://{}/v1/", rx.recv().unwrap())
+    }
+
+    #[test]
+    fn test_complete() {
+        let backend = OpenAiBackend::new(serve(), "test-model").with_api_key("secret");
+        let request = CompletionRequest::new("Be brief.", "hello");
+
+        assert_eq!(backend.name(), "test-model");
+        assert_eq!(backend.complete(&request).unwrap(), "echo: hello");
+
+        let unreachable = OpenAiBackend::new("http://127.0.0.1:1", "test-model");
+        assert!(matches!(
+            unreachable.complete(&request),
+            Err(LlmError::Request(_))
+        ));
+    }
+}
@@ -0,0 +1,156 @@
+//! Line diffs for previewing whole-file rewrites.
+
+/// Largest table of line pairs compared; beyond it the differing middle of
+/// the files is shown as one hunk.
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// Unified diff hunks, without context lines, turning `old` into `new`.
+///
+/// Returns an empty string when the texts have the same lines.
+pub fn line_diff(old: &str, new: &str) -> String {
+    let old_lines = split_lines(old);
+    let new_lines = split_lines(new);
+
+    // Only the differing middle needs comparing
+    let prefix = old_lines
+        .iter()
+        .zip(&new_lines)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = old_lines[prefix..]
+        .iter()
+        .rev()
+        .zip(new_lines[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let a = &old_lines[prefix..old_lines.len() - suffix];
+    let b = &new_lines[prefix..new_lines.len() - suffix];
+
+    let mut ops = Vec::with_capacity(a.len() + b.len());
+    if a.len().saturating_mul(b.len()) > MAX_DIFF_CELLS {
+        ops.extend(a.iter().map(|line| Op::Delete(line)));
+        ops.extend(b.iter().map(|line| Op::Insert(line)));
+    } else {
+        ops = diff_ops(a, b);
+    }
+
+    let mut diff = String::new();
+    let mut old_line = prefix + 1;
+    let mut new_line = prefix + 1;
+    let mut i = 0;
+    while i < ops.len() {
+        if let Op::Keep = ops[i] {
+            old_line += 1;
+            new_line += 1;
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        while i < ops.len() && !matches!(ops[i], Op::Keep) {
+            i += 1;
+        }
+        let hunk = &ops[start..i];
+        let deleted: Vec<&str> = hunk
+            .iter()
+            .filter_map(|op| match op {
+                Op::Delete(line) => Some(*line),
+                _ => None,
+            })
+            .collect();
+        let inserted: Vec<&str> = hunk
+            .iter()
+            .filter_map(|op| match op {
+                Op::Insert(line) => Some(*line),
+                _ => None,
+            })
+            .collect();
+
+        // An empty side is numbered by the line before it, as in `diff -u`
+        let position = |line: usize, count: usize| if count == 0 { line - 1 } else { line };
+        diff.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            position(old_line, deleted.len()),
+            deleted.len(),
+            position(new_line, inserted.len()),
+            inserted.len()
+        ));
+        for line in &deleted {
+            diff.push_str(&format!("-{}\n", line));
+        }
+        for line in &inserted {
+            diff.push_str(&format!("+{}\n", line));
+        }
+
+        old_line += deleted.len();
+        new_line += inserted.len();
+    }
+
+    diff
+}
+
+enum Op<'a> {
+    Keep,
+    Delete(&'a str),
+    Insert(&'a str),
+}
+
+/// Edit script from a longest common subsequence of lines.
+fn diff_ops<'a>(a: &[&'a str], b: &[&'a str]) -> Vec<Op<'a>> {
+    // lengths[i][j] is the LCS length of a[i..] and b[j..]
+    let width = b.len() + 1;
+    let mut lengths = vec![0u32; (a.len() + 1) * width];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lengths[i * width + j] = if a[i] == b[j] {
+                lengths[(i + 1) * width + j + 1] + 1
+            } else {
+                lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::with_capacity(a.len() + b.len());
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            ops.push(Op::Keep);
+            i += 1;
+            j += 1;
+        } else if lengths[(i + 1) * width + j] >= lengths[i * width + j + 1] {
+            ops.push(Op::Delete(a[i]));
+            i += 1;
+        } else {
+            ops.push(Op::Insert(b[j]));
+            j += 1;
+        }
+    }
+    ops.extend(a[i..].iter().map(|line| Op::Delete(line)));
+    ops.extend(b[j..].iter().map(|line| Op::Insert(line)));
+    ops
+}
+
+/// Lines of `text`, without an empty entry after a final newline.
+fn split_lines(text: &str) -> Vec<&str> {
+    let mut lines: Vec<&str> = text.split('\n').collect();
+    if text.is_empty() || text.ends_with('\n') {
+        lines.pop();
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_diff() {
+        assert_eq!(line_diff("a\nb\n", "a\nb\n"), "");
+        assert_eq!(
+            line_diff("a\nb\nc\nd\n", "a\nB\nc\nd\ne\n"),
+            "@@ -2,1 +2,1 @@\n-b\n+B\n@@ -4,0 +5,1 @@\n+e\n"
+        );
+        assert_eq!(line_diff("", "new\n"), "@@ -0,0 +1,1 @@\n+new\n");
+        assert_eq!(line_diff("x\ny\n", "y\n"), "@@ -1,1 +0,0 @@\n-x\n");
+    }
+}
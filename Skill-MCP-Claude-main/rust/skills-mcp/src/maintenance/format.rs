@@ -0,0 +1,143 @@
+//! Fixing the heading hierarchy of skill documents.
+
+use std::fs;
+
+use serde::Serialize;
+
+use crate::index::SkillIndexer;
+use crate::validation::{check_headings, fix_headings};
+
+use super::replace::apply_changes;
+use super::{check_pinned, line_diff, FileChange, MaintenanceError};
+
+/// Every change a format would make.
+#[derive(Debug, Clone, Serialize)]
+pub struct FormatPlan {
+    /// Markdown files checked.
+    pub files_scanned: usize,
+    /// Heading problems fixed across all files.
+    pub fixes: usize,
+    /// Files that would change, by skill and file.
+    pub changes: Vec<FileChange>,
+    /// Pinned skills with files to fix that were left alone.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub pinned: Vec<String>,
+}
+
+/// Work out heading fixes for one skill's markdown files, or every
+/// skill's, without writing.
+///
+/// Headings that skip levels are moved up, and sub-skill documents get an
+/// H1 matching the sub-skill's name. Pinned skills are skipped, and listed
+/// in the plan, unless `force` is set.
+pub fn plan_format(
+    indexer: &SkillIndexer,
+    skill: Option<&str>,
+    force: bool,
+) -> Result<FormatPlan, MaintenanceError> {
+    if let Some(name) = skill.filter(|name| !indexer.skill_exists(name)) {
+        return Err(MaintenanceError::NotFound(format!(
+            "Skill '{}' not found",
+            name
+        )));
+    }
+
+    let files = indexer.indexed_markdown_files(skill);
+    let mut plan = FormatPlan {
+        files_scanned: files.len(),
+        fixes: 0,
+        changes: Vec::new(),
+        pinned: Vec::new(),
+    };
+
+    for (skill, file, path) in files {
+        let original = fs::read_to_string(&path)
+            .map_err(|e| MaintenanceError::Io(format!("{}: {}", path.display(), e)))?;
+
+        let meta = indexer.get_skill_meta(&skill);
+        let title = meta
+            .as_ref()
+            .and_then(|meta| meta.find_sub_skill_by_file(&file))
+            .map(|sub| sub.name.as_str());
+        let fixes = check_headings(&file, &original, title).len();
+        if fixes == 0 {
+            continue;
+        }
+        if check_pinned(indexer, &skill, force).is_err() {
+            if !plan.pinned.contains(&skill) {
+                plan.pinned.push(skill);
+            }
+            continue;
+        }
+
+        let updated = fix_headings(&original, title);
+        let diff = line_diff(&original, &updated);
+        plan.fixes += fixes;
+        plan.changes.push(FileChange::new(
+            skill, file, path, &original, updated, fixes, diff,
+        ));
+    }
+
+    Ok(plan)
+}
+
+/// Write a planned format, returning the version id of the saved
+/// originals.
+///
+/// Like [`apply_replace`](super::apply_replace), nothing is written if any
+/// file changed since planning, and files already written are restored if
+/// a write fails.
+pub fn apply_format(indexer: &SkillIndexer, plan: &FormatPlan) -> Result<String, MaintenanceError> {
+    apply_changes(indexer, &plan.changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_plan_and_apply_format() {
+        let temp = TempDir::new().unwrap();
+        let skill_dir = temp.path().join("forms");
+        fs::create_dir_all(skill_dir.join("references")).unwrap();
+        fs::write(
+            skill_dir.join("_meta.json"),
+            r#"{"name": "forms", "description": "Forms",
+                "sub_skills": [{"name": "validation", "file": "references/validation.md"}]}"#,
+        )
+        .unwrap();
+        fs::write(skill_dir.join("SKILL.md"), "# Forms\n\n### Layout\n").unwrap();
+        fs::write(
+            skill_dir.join("references/validation.md"),
+            "Check inputs.\n",
+        )
+        .unwrap();
+
+        let indexer = SkillIndexer::new(temp.path());
+        indexer.reload().unwrap();
+
+        assert!(matches!(
+            plan_format(&indexer, Some("missing"), false),
+            Err(MaintenanceError::NotFound(_))
+        ));
+
+        let plan = plan_format(&indexer, Some("forms"), false).unwrap();
+        assert_eq!(plan.files_scanned, 2);
+        assert_eq!(plan.fixes, 2);
+        assert_eq!(plan.changes.len(), 2);
+
+        apply_format(&indexer, &plan).unwrap();
+        assert_eq!(
+            fs::read_to_string(skill_dir.join("SKILL.md")).unwrap(),
+            "# Forms\n\n## Layout\n"
+        );
+        assert_eq!(
+            fs::read_to_string(skill_dir.join("references/validation.md")).unwrap(),
+            "# Validation\n\nCheck inputs.\n"
+        );
+
+        let plan = plan_format(&indexer, None, false).unwrap();
+        assert!(plan.changes.is_empty());
+    }
+}
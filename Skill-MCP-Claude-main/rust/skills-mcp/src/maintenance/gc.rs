@@ -0,0 +1,501 @@
+//! Pruning records that refer to skills which no longer exist.
+//!
+//! Saved versions of a deleted or merged-away skill are kept for the
+//! retention period, so the skill can still be restored from them, then
+//! removed. Usage counts aren't timestamped and are dropped as soon as
+//! their skill is gone. Shared asset blobs no skill or saved version maps
+//! are removed once they're older than the retention.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use chrono::{NaiveDateTime, Utc};
+use parking_lot::RwLock;
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::index::{read_asset_map, AssetStore, SkillIndexer, ASSETS_DIR, ASSET_MAP_FILE};
+use crate::models::{UsageStats, CATEGORY_SEPARATOR};
+
+use super::{MaintenanceError, VERSIONS_DIR};
+
+/// Format of version directory names.
+const VERSION_FORMAT: &str = "%Y%m%dT%H%M%S%.3fZ";
+
+/// Default age past which orphaned versions are pruned.
+pub const DEFAULT_GC_RETENTION: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Saved files of one missing skill in one version.
+#[derive(Debug, Clone, Serialize)]
+pub struct OrphanedVersion {
+    /// Version directory name.
+    pub version: String,
+    /// Skill the files belonged to.
+    pub skill: String,
+    /// Number of saved files.
+    pub files: usize,
+    /// Their total size.
+    pub bytes: u64,
+}
+
+/// Everything a garbage collection would remove.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GcPlan {
+    /// Saved versions of missing skills, older than the retention.
+    pub versions: Vec<OrphanedVersion>,
+    /// Skills with usage counts that no longer exist.
+    pub stats_keys: Vec<String>,
+    /// Hashes of asset blobs nothing maps, older than the retention.
+    pub assets: Vec<String>,
+    /// Total size of the saved files and blobs removed.
+    pub bytes: u64,
+    #[serde(skip)]
+    files: Vec<PathBuf>,
+}
+
+impl GcPlan {
+    /// Whether there is nothing to remove.
+    pub fn is_empty(&self) -> bool {
+        self.versions.is_empty() && self.stats_keys.is_empty() && self.assets.is_empty()
+    }
+}
+
+/// Find records of skills that no longer exist.
+///
+/// A saved file is orphaned when the skill it was saved from is neither
+/// indexed nor on disk; it's only pruned once its version is older than
+/// `retention`. Version directories not named by a maintenance operation
+/// are left alone.
+pub fn plan_gc(
+    indexer: &SkillIndexer,
+    stats: &UsageStats,
+    retention: Duration,
+) -> Result<GcPlan, MaintenanceError> {
+    let mut plan = GcPlan::default();
+    let cutoff = chrono::Duration::from_std(retention)
+        .ok()
+        .and_then(|retention| Utc::now().naive_utc().checked_sub_signed(retention))
+        .unwrap_or(NaiveDateTime::MIN);
+
+    let versions_dir = indexer.skills_dir().join(VERSIONS_DIR);
+    for (version, version_dir) in expired_versions(&versions_dir, cutoff)? {
+        let mut orphans: BTreeMap<String, OrphanedVersion> = BTreeMap::new();
+        for (path, bytes) in saved_files(&version_dir)? {
+            let Some(skill) = saved_skill(indexer, &version_dir, &path) else {
+                continue;
+            };
+            if skill_exists(indexer, &skill) {
+                continue;
+            }
+
+            let orphan = orphans
+                .entry(skill.clone())
+                .or_insert_with(|| OrphanedVersion {
+                    version: version.clone(),
+                    skill,
+                    files: 0,
+                    bytes: 0,
+                });
+            orphan.files += 1;
+            orphan.bytes += bytes;
+            plan.bytes += bytes;
+            plan.files.push(path);
+        }
+        plan.versions.extend(orphans.into_values());
+    }
+
+    plan.stats_keys = stats
+        .skill_loads
+        .keys()
+        .filter(|id| !skill_exists(indexer, skill_of(id)))
+        .cloned()
+        .collect();
+    plan.stats_keys.sort();
+
+    let referenced = referenced_assets(indexer.skills_dir());
+    let cutoff = SystemTime::now()
+        .checked_sub(retention)
+        .unwrap_or(SystemTime::UNIX_EPOCH);
+    for (hash, path) in AssetStore::new(indexer.skills_dir()).blobs() {
+        let Ok(metadata) = fs::metadata(&path) else {
+            continue;
+        };
+        let expired = metadata.modified().is_ok_and(|at| at < cutoff);
+        if expired && !referenced.contains(&hash) {
+            plan.bytes += metadata.len();
+            plan.assets.push(hash);
+        }
+    }
+
+    Ok(plan)
+}
+
+/// Remove what a plan found, then any version directories left empty.
+///
+/// Files whose skill has come back since the plan was made are kept, as
+/// are blobs mapped again since.
+pub fn apply_gc(
+    indexer: &SkillIndexer,
+    stats: &mut UsageStats,
+    plan: &GcPlan,
+) -> Result<(), MaintenanceError> {
+    let versions_dir = indexer.skills_dir().join(VERSIONS_DIR);
+
+    for path in &plan.files {
+        let version_dir = path
+            .strip_prefix(&versions_dir)
+            .ok()
+            .and_then(|relative| relative.components().next())
+            .map(|version| versions_dir.join(version));
+        let keep = version_dir
+            .as_deref()
+            .and_then(|dir| saved_skill(indexer, dir, path))
+            .is_none_or(|skill| skill_exists(indexer, &skill));
+        if keep {
+            continue;
+        }
+
+        match fs::remove_file(path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(MaintenanceError::Io(format!("{}: {}", path.display(), e))),
+        }
+        if let Some(version_dir) = version_dir {
+            remove_empty_dirs(path.parent(), &version_dir);
+        }
+    }
+
+    if !plan.assets.is_empty() {
+        let referenced = referenced_assets(indexer.skills_dir());
+        let assets = AssetStore::new(indexer.skills_dir());
+        for hash in plan
+            .assets
+            .iter()
+            .filter(|hash| !referenced.contains(*hash))
+        {
+            let path = assets.path(hash);
+            match fs::remove_file(&path) {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(MaintenanceError::Io(format!("{}: {}", path.display(), e))),
+            }
+            remove_empty_dirs(path.parent(), &indexer.skills_dir().join(ASSETS_DIR));
+        }
+    }
+
+    for id in &plan.stats_keys {
+        if !skill_exists(indexer, skill_of(id)) {
+            stats.skill_loads.remove(id);
+            for search in &mut stats.searches {
+                search.loaded_skills.retain(|loaded| loaded != id);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Handle returned by [`schedule_gc`]; collection stops when it's dropped.
+pub struct GcSchedule {
+    stopped: Arc<AtomicBool>,
+}
+
+impl Drop for GcSchedule {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::Relaxed);
+    }
+}
+
+impl std::fmt::Debug for GcSchedule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GcSchedule").finish_non_exhaustive()
+    }
+}
+
+/// Collect garbage every `interval` on a background thread, logging what
+/// was removed.
+pub fn schedule_gc(
+    indexer: Arc<SkillIndexer>,
+    stats: Arc<RwLock<UsageStats>>,
+    interval: Duration,
+    retention: Duration,
+) -> GcSchedule {
+    let stopped = Arc::new(AtomicBool::new(false));
+    let flag = Arc::clone(&stopped);
+
+    thread::spawn(move || {
+        while !flag.load(Ordering::Relaxed) {
+            thread::sleep(interval);
+            if flag.load(Ordering::Relaxed) {
+                break;
+            }
+
+            let planned = plan_gc(&indexer, &stats.read(), retention);
+            let collected = planned.and_then(|plan| {
+                apply_gc(&indexer, &mut stats.write(), &plan)?;
+                Ok(plan)
+            });
+            match collected {
+                Ok(plan) if plan.is_empty() => {}
+                Ok(plan) => info!(
+                    "Garbage collection removed {} orphaned versions and {} assets ({} bytes) \
+                     and {} stats keys",
+                    plan.versions.len(),
+                    plan.assets.len(),
+                    plan.bytes,
+                    plan.stats_keys.len()
+                ),
+                Err(e) => warn!("Garbage collection failed: {}", e),
+            }
+        }
+    });
+
+    GcSchedule { stopped }
+}
+
+/// Skill name of a stats key, which may name a sub-skill as `skill:sub`.
+fn skill_of(id: &str) -> &str {
+    id.split_once(':').map_or(id, |(skill, _)| skill)
+}
+
+fn skill_exists(indexer: &SkillIndexer, name: &str) -> bool {
+    indexer.get_skill_meta(name).is_some() || indexer.skill_exists(name)
+}
+
+/// Hashes of blobs mapped by any asset map under `skills_dir`, including
+/// those in saved versions.
+fn referenced_assets(skills_dir: &Path) -> BTreeSet<String> {
+    let mut referenced = BTreeSet::new();
+    let mut pending = vec![skills_dir.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() && entry.file_name() != ASSETS_DIR {
+                pending.push(entry.path());
+            } else if file_type.is_file() && entry.file_name() == ASSET_MAP_FILE {
+                referenced.extend(read_asset_map(&dir).into_values());
+            }
+        }
+    }
+
+    referenced
+}
+
+/// Version directories saved before `cutoff`, oldest first.
+fn expired_versions(
+    versions_dir: &Path,
+    cutoff: NaiveDateTime,
+) -> Result<Vec<(String, PathBuf)>, MaintenanceError> {
+    let entries = match fs::read_dir(versions_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(MaintenanceError::Io(format!(
+                "{}: {}",
+                versions_dir.display(),
+                e
+            )))
+        }
+    };
+
+    let mut versions = Vec::new();
+    for entry in entries.flatten() {
+        let Ok(name) = entry.file_name().into_string() else {
+            continue;
+        };
+        let saved_at = NaiveDateTime::parse_from_str(&name, VERSION_FORMAT);
+        if entry.path().is_dir() && saved_at.is_ok_and(|at| at < cutoff) {
+            versions.push((name, entry.path()));
+        }
+    }
+    versions.sort();
+    Ok(versions)
+}
+
+/// Every file under `dir`, with its size.
+fn saved_files(dir: &Path) -> Result<Vec<(PathBuf, u64)>, MaintenanceError> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let entries = fs::read_dir(&dir)
+            .map_err(|e| MaintenanceError::Io(format!("{}: {}", dir.display(), e)))?;
+        for entry in entries.flatten() {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                pending.push(entry.path());
+            } else if file_type.is_file() {
+                let bytes = entry.metadata().map(|m| m.len()).unwrap_or(0);
+                files.push((entry.path(), bytes));
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// The skill a saved file was copied from.
+///
+/// Inside category folders the deepest directory saved with a
+/// `_meta.json` wins, since the original may be gone.
+fn saved_skill(indexer: &SkillIndexer, version_dir: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(version_dir).ok()?;
+    let parts: Vec<&str> = relative
+        .components()
+        .map(|c| c.as_os_str().to_str())
+        .collect::<Option<_>>()?;
+
+    let separator = CATEGORY_SEPARATOR.to_string();
+    for len in (2..parts.len()).rev() {
+        let candidate = parts[..len].join(&separator);
+        if version_dir.join(&candidate).join("_meta.json").is_file() {
+            return Some(candidate);
+        }
+    }
+
+    indexer.skill_from_path(&indexer.skills_dir().join(relative))
+}
+
+/// Remove `dir` and its parents while they're empty, stopping at `root`,
+/// which is removed too once empty.
+fn remove_empty_dirs(dir: Option<&Path>, root: &Path) {
+    let mut dir = dir;
+    while let Some(current) = dir {
+        if !current.starts_with(root) || fs::remove_dir(current).is_err() {
+            break;
+        }
+        if current == root {
+            break;
+        }
+        dir = current.parent();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn save(root: &Path, version: &str, file: &str, content: &str) {
+        let path = root.join(VERSIONS_DIR).join(version).join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_gc_prunes_orphans_past_retention() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::create_dir_all(root.join("forms")).unwrap();
+        fs::write(root.join("forms/SKILL.md"), "# Forms").unwrap();
+
+        let old = "20200101T000000.000Z";
+        let recent = Utc::now().format(VERSION_FORMAT).to_string();
+        save(root, old, "forms/SKILL.md", "# Old forms");
+        save(root, old, "charts/SKILL.md", "# Charts");
+        save(root, old, "charts/_meta.json", "{}");
+        save(root, &recent, "tables/SKILL.md", "# Tables");
+        save(root, "manual-backup", "gone/SKILL.md", "# Gone");
+
+        let indexer = SkillIndexer::new(root);
+        indexer.reload().unwrap();
+
+        let mut stats = UsageStats::new();
+        stats.record_skill_load("forms");
+        stats.record_skill_load("charts:bars");
+
+        let plan = plan_gc(&indexer, &stats, DEFAULT_GC_RETENTION).unwrap();
+        assert_eq!(plan.versions.len(), 1);
+        assert_eq!(plan.versions[0].skill, "charts");
+        assert_eq!(plan.versions[0].files, 2);
+        assert_eq!(plan.bytes, "# Charts".len() as u64 + 2);
+        assert_eq!(plan.stats_keys, vec!["charts:bars"]);
+
+        apply_gc(&indexer, &mut stats, &plan).unwrap();
+        let versions = root.join(VERSIONS_DIR);
+        assert!(!versions.join(old).join("charts").exists());
+        assert!(versions.join(old).join("forms/SKILL.md").is_file());
+        assert!(versions.join(&recent).join("tables/SKILL.md").is_file());
+        assert!(versions.join("manual-backup/gone/SKILL.md").is_file());
+        assert_eq!(stats.skill_loads.len(), 1);
+
+        // Nothing is left once the last saved skill goes
+        fs::remove_dir_all(root.join("forms")).unwrap();
+        indexer.reload().unwrap();
+        let plan = plan_gc(&indexer, &stats, DEFAULT_GC_RETENTION).unwrap();
+        apply_gc(&indexer, &mut stats, &plan).unwrap();
+        assert!(!versions.join(old).exists());
+        assert!(stats.skill_loads.is_empty());
+    }
+
+    #[test]
+    fn test_gc_prunes_unmapped_assets() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::create_dir_all(root.join("forms")).unwrap();
+        fs::write(root.join("forms/SKILL.md"), "# Forms").unwrap();
+        fs::write(
+            root.join("forms/_meta.json"),
+            r#"{"name": "forms", "description": "Forms"}"#,
+        )
+        .unwrap();
+
+        let indexer = SkillIndexer::new(root);
+        indexer.reload().unwrap();
+        indexer
+            .add_references("forms", &[("form.png".to_string(), b"used".to_vec())])
+            .unwrap();
+        let unused = AssetStore::new(root).put(b"unused").unwrap();
+
+        let mut stats = UsageStats::new();
+        let plan = plan_gc(&indexer, &stats, DEFAULT_GC_RETENTION).unwrap();
+        assert!(plan.assets.is_empty());
+
+        let plan = plan_gc(&indexer, &stats, Duration::ZERO).unwrap();
+        assert_eq!(plan.assets, vec![unused.clone()]);
+        assert_eq!(plan.bytes, "unused".len() as u64);
+
+        apply_gc(&indexer, &mut stats, &plan).unwrap();
+        assert!(!AssetStore::new(root).path(&unused).exists());
+        assert_eq!(AssetStore::new(root).blobs().len(), 1);
+        assert!(indexer
+            .resolve_skill_file("forms", "references/form.png")
+            .unwrap()
+            .is_file());
+    }
+
+    #[test]
+    fn test_gc_keeps_restored_skills() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        save(root, "20200101T000000.000Z", "charts/SKILL.md", "# Charts");
+
+        let indexer = SkillIndexer::new(root);
+        indexer.reload().unwrap();
+        let mut stats = UsageStats::new();
+        let plan = plan_gc(&indexer, &stats, Duration::ZERO).unwrap();
+        assert_eq!(plan.versions.len(), 1);
+
+        fs::create_dir_all(root.join("charts")).unwrap();
+        fs::write(root.join("charts/SKILL.md"), "# Charts").unwrap();
+        apply_gc(&indexer, &mut stats, &plan).unwrap();
+        assert!(root
+            .join(VERSIONS_DIR)
+            .join("20200101T000000.000Z/charts/SKILL.md")
+            .is_file());
+    }
+}
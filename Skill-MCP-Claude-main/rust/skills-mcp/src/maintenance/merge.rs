@@ -0,0 +1,556 @@
+//! Combining several skills into one.
+
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::index::SkillIndexer;
+use crate::models::{content_hash, title_case, SkillMeta, SubSkillMeta, CATEGORY_SEPARATOR};
+use crate::validation::check_skill_name;
+
+use super::{
+    check_pinned, line_diff, relink, save_version, write_atomic, MaintenanceError, Undo,
+    VERSIONS_DIR,
+};
+
+/// A file copied from a source skill into the target.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MovedFile {
+    /// Original path, relative to the skills directory.
+    pub from: String,
+    /// New path, relative to the skills directory.
+    pub to: String,
+}
+
+/// Changes to one of the target's files.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileDiff {
+    /// File relative to the skills directory.
+    pub file: String,
+    /// Changed lines as unified diff hunks.
+    pub diff: String,
+}
+
+/// Everything a merge would do.
+#[derive(Debug, Clone, Serialize)]
+pub struct MergePlan {
+    /// Skill the sources are merged into.
+    pub target: String,
+    /// Skills merged away, which become aliases of the target.
+    pub sources: Vec<String>,
+    /// Whether the target is a new skill.
+    pub created: bool,
+    /// Metadata of the merged skill.
+    pub meta: SkillMeta,
+    /// Supporting files carried over from the sources.
+    pub moved: Vec<MovedFile>,
+    /// Changes to the target's `_meta.json` and `SKILL.md`.
+    pub diffs: Vec<FileDiff>,
+    #[serde(skip)]
+    meta_json: String,
+    #[serde(skip)]
+    content: String,
+    /// Files the plan was made from, with their hashes.
+    #[serde(skip)]
+    checked: Vec<(PathBuf, String)>,
+}
+
+/// Work out a merge of `sources` into `target` without writing.
+///
+/// The target may be an existing directory skill, which keeps its content
+/// and gains the sources, or a new name. Each source's SKILL.md becomes a
+/// section of the target's, with its headings moved one level down, and
+/// its other files move to a subdirectory named after it; sub-skills,
+/// tags and aliases are combined, and the source names become aliases.
+/// `description` replaces the target's description; a new target without
+/// one joins the sources' descriptions. Pinned skills can only take part
+/// with `force`.
+pub fn plan_merge(
+    indexer: &SkillIndexer,
+    sources: &[String],
+    target: &str,
+    description: Option<&str>,
+    force: bool,
+) -> Result<MergePlan, MaintenanceError> {
+    check_skill_name(target).map_err(MaintenanceError::Invalid)?;
+    check_pinned(indexer, target, force)?;
+
+    let mut merged: Vec<SkillMeta> = Vec::new();
+    for name in sources {
+        if merged.iter().any(|m| &m.name == name) {
+            return Err(MaintenanceError::Invalid(format!(
+                "Skill '{}' is listed twice",
+                name
+            )));
+        }
+        let meta = indexer
+            .get_skill_meta(name)
+            .ok_or_else(|| MaintenanceError::NotFound(format!("Skill '{}' not found", name)))?;
+        check_pinned(indexer, name, force)?;
+        if name != target {
+            merged.push(meta);
+        }
+    }
+    if merged.is_empty() {
+        return Err(MaintenanceError::Invalid(format!(
+            "No other skills to merge into '{}'",
+            target
+        )));
+    }
+
+    let skills_dir = indexer.skills_dir();
+    let target_dir = skills_dir.join(target);
+    let existing = indexer.get_skill_meta(target);
+    match &existing {
+        Some(_) if indexer.flat_skill_path(target).is_some() => {
+            return Err(MaintenanceError::Invalid(format!(
+                "Can't merge into flat-file skill '{}'",
+                target
+            )));
+        }
+        Some(_) => {}
+        None if indexer.skill_exists(target) => {
+            return Err(MaintenanceError::Invalid(format!(
+                "'{}' exists but isn't a loaded skill",
+                target
+            )));
+        }
+        None => {
+            if let Some((category, _)) = target.rsplit_once(CATEGORY_SEPARATOR) {
+                indexer
+                    .check_category(category, false)
+                    .map_err(|e| MaintenanceError::Invalid(e.to_string()))?;
+            }
+        }
+    }
+
+    let mut checked = Vec::new();
+    let mut read = |path: PathBuf| -> Result<String, MaintenanceError> {
+        let text = fs::read_to_string(&path)
+            .map_err(|e| MaintenanceError::Io(format!("{}: {}", path.display(), e)))?;
+        checked.push((path, content_hash(&text)));
+        Ok(text)
+    };
+
+    let (mut meta, old_meta_json, old_content) = match &existing {
+        Some(meta) => {
+            let meta_path = target_dir.join("_meta.json");
+            let meta_json = if meta_path.exists() {
+                read(meta_path)?
+            } else {
+                String::new()
+            };
+            (meta.clone(), meta_json, read(target_dir.join("SKILL.md"))?)
+        }
+        None => {
+            let first = &merged[0];
+            let description = merged
+                .iter()
+                .map(|m| m.description.trim())
+                .filter(|d| !d.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ");
+            let meta = SkillMeta {
+                name: target.to_string(),
+                description,
+                source: first.source.clone(),
+                language: first.language.clone(),
+                status: first.status,
+                owner: first.owner.clone(),
+                ..Default::default()
+            };
+            (meta, String::new(), String::new())
+        }
+    };
+    if let Some(description) = description {
+        meta.description = description.to_string();
+    }
+
+    let mut content = if existing.is_some() {
+        old_content.trim_end().to_string()
+    } else {
+        let leaf = target.rsplit(CATEGORY_SEPARATOR).next().unwrap_or(target);
+        format!("# {}\n\n{}", title_case(leaf), meta.description.trim())
+    };
+    let mut sub_skills = meta.sub_skills.take().unwrap_or_default();
+    let mut moved = Vec::new();
+
+    for source in &merged {
+        let folder = source.name.replace(CATEGORY_SEPARATOR, "-");
+        let flat = indexer.flat_skill_path(&source.name);
+
+        let body = match &flat {
+            Some(path) => {
+                let text = read(path.clone())?;
+                crate::models::split_frontmatter(&text)
+                    .map(|(_, body)| body.to_string())
+                    .unwrap_or(text)
+            }
+            None => {
+                let source_dir = skills_dir.join(&source.name);
+                let meta_path = source_dir.join("_meta.json");
+                if meta_path.exists() {
+                    read(meta_path)?;
+                }
+                moved.extend(supporting_files(skills_dir, &source_dir, target, &folder)?);
+                read(source_dir.join("SKILL.md"))?
+            }
+        };
+        let prefix = flat.is_none().then_some(folder.as_str());
+        content.push_str("\n\n");
+        content.push_str(&section(&source.name, &body, prefix));
+
+        for tag in &source.tags {
+            if !meta.tags.contains(tag) {
+                meta.tags.push(tag.clone());
+            }
+        }
+        for alias in std::iter::once(&source.name).chain(&source.aliases) {
+            if alias != target && !meta.aliases.contains(alias) {
+                meta.aliases.push(alias.clone());
+            }
+        }
+        for sub in source.sub_skills.iter().flatten() {
+            let taken = |name: &str| sub_skills.iter().any(|s: &SubSkillMeta| s.name == name);
+            let name = if taken(&sub.name) {
+                format!("{}-{}", folder, sub.name)
+            } else {
+                sub.name.clone()
+            };
+            if taken(&name) {
+                return Err(MaintenanceError::Invalid(format!(
+                    "Sub-skill '{}' of '{}' clashes with an existing sub-skill",
+                    sub.name, source.name
+                )));
+            }
+            sub_skills.push(SubSkillMeta {
+                name,
+                ..sub.moved_under(&folder)
+            });
+        }
+    }
+    content.push('\n');
+    meta.aliases.retain(|alias| alias != target);
+    if !sub_skills.is_empty() {
+        meta.sub_skills = Some(sub_skills);
+    }
+
+    for file in &moved {
+        if skills_dir.join(&file.to).exists() {
+            return Err(MaintenanceError::Invalid(format!(
+                "{} already exists in the target",
+                file.to
+            )));
+        }
+    }
+
+    let meta_json = serde_json::to_string_pretty(&meta)
+        .map_err(|e| MaintenanceError::Io(format!("Failed to serialize meta: {}", e)))?;
+    let diffs = vec![
+        FileDiff {
+            file: format!("{}/_meta.json", target),
+            diff: line_diff(&old_meta_json, &meta_json),
+        },
+        FileDiff {
+            file: format!("{}/SKILL.md", target),
+            diff: line_diff(&old_content, &content),
+        },
+    ];
+
+    Ok(MergePlan {
+        target: target.to_string(),
+        sources: merged.into_iter().map(|m| m.name).collect(),
+        created: existing.is_none(),
+        meta,
+        moved,
+        diffs,
+        meta_json,
+        content,
+        checked,
+    })
+}
+
+/// Carry out a planned merge, returning the version id the replaced and
+/// merged-away files were saved under.
+///
+/// Nothing is written if any file changed since planning. The target's old
+/// files are copied and the sources moved into `.versions/<version>/`; if
+/// a step fails, the steps before it are undone.
+pub fn apply_merge(indexer: &SkillIndexer, plan: &MergePlan) -> Result<String, MaintenanceError> {
+    let skills_dir = indexer.skills_dir();
+    let target_dir = skills_dir.join(&plan.target);
+
+    for (path, hash) in &plan.checked {
+        let current = fs::read_to_string(path).unwrap_or_default();
+        if &content_hash(&current) != hash {
+            return Err(MaintenanceError::Conflict(path.display().to_string()));
+        }
+    }
+    if plan.created && target_dir.exists() {
+        return Err(MaintenanceError::Conflict(plan.target.clone()));
+    }
+
+    let version = chrono::Utc::now().format("%Y%m%dT%H%M%S%.3fZ").to_string();
+    let version_dir = skills_dir.join(VERSIONS_DIR).join(&version);
+
+    let mut undo = Vec::new();
+    let result = merge_files(skills_dir, &version_dir, &target_dir, plan, &mut undo);
+    if let Err(e) = result {
+        Undo::revert_all(undo);
+        return Err(e);
+    }
+
+    Ok(version)
+}
+
+fn merge_files(
+    skills_dir: &Path,
+    version_dir: &Path,
+    target_dir: &Path,
+    plan: &MergePlan,
+    undo: &mut Vec<Undo>,
+) -> Result<(), MaintenanceError> {
+    let io =
+        |path: &Path, e: std::io::Error| MaintenanceError::Io(format!("{}: {}", path.display(), e));
+
+    if plan.created {
+        fs::create_dir_all(target_dir).map_err(|e| io(target_dir, e))?;
+        undo.push(Undo::CreatedDir(target_dir.to_path_buf()));
+    }
+
+    for file in &plan.moved {
+        let from = skills_dir.join(&file.from);
+        let to = skills_dir.join(&file.to);
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent).map_err(|e| io(parent, e))?;
+        }
+        fs::copy(&from, &to).map_err(|e| io(&to, e))?;
+        undo.push(Undo::Created(to));
+    }
+
+    for (name, text) in [("_meta.json", &plan.meta_json), ("SKILL.md", &plan.content)] {
+        let path = target_dir.join(name);
+        if path.exists() {
+            let saved = save_version(skills_dir, version_dir, &path)?;
+            write_atomic(&path, text)?;
+            undo.push(Undo::Overwrote { path, saved });
+        } else {
+            write_atomic(&path, text)?;
+            undo.push(Undo::Created(path));
+        }
+    }
+
+    for source in &plan.sources {
+        let from = match skills_dir.join(source) {
+            dir if dir.is_dir() => dir,
+            _ => skills_dir.join(format!("{}.md", source)),
+        };
+        let relative = from.strip_prefix(skills_dir).unwrap_or(&from);
+        let to = version_dir.join(relative);
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent).map_err(|e| io(parent, e))?;
+        }
+        fs::rename(&from, &to).map_err(|e| io(&from, e))?;
+        undo.push(Undo::Moved { from, to });
+    }
+
+    Ok(())
+}
+
+/// Files of a skill directory other than its `_meta.json` and `SKILL.md`,
+/// with where they go under the target.
+fn supporting_files(
+    skills_dir: &Path,
+    source_dir: &Path,
+    target: &str,
+    folder: &str,
+) -> Result<Vec<MovedFile>, MaintenanceError> {
+    let mut files = Vec::new();
+    for entry in WalkDir::new(source_dir).min_depth(1).sort_by_file_name() {
+        let entry = entry.map_err(|e| MaintenanceError::Io(e.to_string()))?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let relative = entry
+            .path()
+            .strip_prefix(source_dir)
+            .unwrap_or(entry.path());
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        if relative == "_meta.json" || relative == "SKILL.md" {
+            continue;
+        }
+        let from = entry
+            .path()
+            .strip_prefix(skills_dir)
+            .unwrap_or(entry.path());
+        files.push(MovedFile {
+            from: from.to_string_lossy().replace('\\', "/"),
+            to: format!("{}/{}/{}", target, folder, relative),
+        });
+    }
+    Ok(files)
+}
+
+/// A source's SKILL.md as a `##` section of the merged document.
+///
+/// A leading `# Title` becomes the section heading, or the skill name is
+/// used. Other headings move one level down, and when the source's files
+/// move to `prefix/`, relative links are pointed there.
+fn section(name: &str, body: &str, prefix: Option<&str>) -> String {
+    let body = body.trim_start();
+    let (title, body) = match body.strip_prefix("# ") {
+        Some(rest) => {
+            let (title, body) = rest.split_once('\n').unwrap_or((rest, ""));
+            (title.trim().to_string(), body)
+        }
+        None => {
+            let leaf = name.rsplit(CATEGORY_SEPARATOR).next().unwrap_or(name);
+            (title_case(leaf), body)
+        }
+    };
+
+    let mut out = format!("## {}\n", title);
+    let mut in_fence = false;
+    for line in body.trim().lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        }
+        let demote = !in_fence
+            && line.starts_with('#')
+            && line.trim_start_matches('#').starts_with(' ')
+            && line.len() - line.trim_start_matches('#').len() < 6;
+
+        out.push('\n');
+        if demote {
+            out.push('#');
+        }
+        match prefix {
+            Some(prefix) if !in_fence => out.push_str(&relink(line, prefix)),
+            _ => out.push_str(line),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn create_skill(dir: &Path, meta: &str, content: &str) {
+        let meta: SkillMeta = serde_json::from_str(meta).unwrap();
+        let skill_dir = dir.join(&meta.name);
+        fs::create_dir_all(&skill_dir).unwrap();
+        fs::write(
+            skill_dir.join("_meta.json"),
+            serde_json::to_string(&meta).unwrap(),
+        )
+        .unwrap();
+        fs::write(skill_dir.join("SKILL.md"), content).unwrap();
+    }
+
+    #[test]
+    fn test_section() {
+        let body = "# Form Validation\n\nSee [rules](./rules.md) and [docs](https://x.io).\n\n\
+                    ## Usage\n\n```\n# not a heading\n```\n";
+        assert_eq!(
+            section("validation", body, Some("validation")),
+            "## Form Validation\n\nSee [rules](validation/rules.md) and [docs](https://x.io).\n\n\
+             ### Usage\n\n```\n# not a heading\n```"
+        );
+        assert_eq!(
+            section("frontend/tips", "Plain text.", None),
+            "## Tips\n\nPlain text."
+        );
+    }
+
+    #[test]
+    fn test_plan_and_apply_merge() {
+        let temp = TempDir::new().unwrap();
+        create_skill(
+            temp.path(),
+            r#"{"name": "forms", "description": "Forms.", "tags": ["forms"]}"#,
+            "# Forms\n\nBasics.\n",
+        );
+        create_skill(
+            temp.path(),
+            r#"{"name": "validation", "description": "Validation.", "tags": ["forms", "zod"],
+                "aliases": ["checks"],
+                "sub_skills": [{"name": "zod", "file": "zod.md", "triggers": ["schema"]}]}"#,
+            "# Validation\n\nUse [zod](zod.md).\n",
+        );
+        fs::write(temp.path().join("validation/zod.md"), "# Zod\n").unwrap();
+        fs::write(
+            temp.path().join("inputs.md"),
+            "---\nname: inputs\ndescription: Inputs.\n---\n# Inputs\n\nText fields.\n",
+        )
+        .unwrap();
+
+        let indexer = SkillIndexer::new(temp.path());
+        indexer.reload().unwrap();
+
+        let sources = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        assert!(matches!(
+            plan_merge(&indexer, &sources(&["forms"]), "forms", None, false),
+            Err(MaintenanceError::Invalid(_))
+        ));
+        assert!(matches!(
+            plan_merge(&indexer, &sources(&["missing"]), "forms", None, false),
+            Err(MaintenanceError::NotFound(_))
+        ));
+        assert!(plan_merge(&indexer, &sources(&["forms"]), "inputs", None, false).is_err());
+
+        let plan = plan_merge(
+            &indexer,
+            &sources(&["forms", "validation", "inputs"]),
+            "forms",
+            None,
+            false,
+        )
+        .unwrap();
+        assert!(!plan.created);
+        assert_eq!(plan.sources, vec!["validation", "inputs"]);
+        assert_eq!(plan.meta.tags, vec!["forms", "zod"]);
+        assert_eq!(plan.meta.aliases, vec!["validation", "checks", "inputs"]);
+        let subs = plan.meta.sub_skills.clone().unwrap();
+        assert_eq!(subs[0].file, "validation/zod.md");
+        assert_eq!(
+            plan.moved,
+            vec![MovedFile {
+                from: "validation/zod.md".to_string(),
+                to: "forms/validation/zod.md".to_string(),
+            }]
+        );
+        assert_eq!(
+            plan.diffs[1].diff,
+            "@@ -3,0 +4,8 @@\n+\n+## Validation\n+\n+Use [zod](validation/zod.md).\n\
+             +\n+## Inputs\n+\n+Text fields.\n"
+        );
+
+        // Planning writes nothing
+        assert!(temp.path().join("validation").exists());
+
+        let version = apply_merge(&indexer, &plan).unwrap();
+        indexer.reload().unwrap();
+
+        assert!(!temp.path().join("validation").exists());
+        assert!(!temp.path().join("inputs.md").exists());
+        let saved = temp.path().join(VERSIONS_DIR).join(&version);
+        assert!(saved.join("validation/zod.md").is_file());
+        assert!(saved.join("inputs.md").is_file());
+        assert_eq!(
+            fs::read_to_string(saved.join("forms/SKILL.md")).unwrap(),
+            "# Forms\n\nBasics.\n"
+        );
+
+        assert_eq!(indexer.resolve_alias("checks").as_deref(), Some("forms"));
+        assert_eq!(indexer.resolve_alias("forms"), None);
+        let sub = indexer.read_sub_skill_content("forms", "zod").unwrap();
+        assert_eq!(sub.content, "# Zod\n");
+
+        // The plan is stale now
+        assert!(apply_merge(&indexer, &plan).is_err());
+    }
+}
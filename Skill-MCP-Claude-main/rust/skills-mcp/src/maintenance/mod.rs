@@ -0,0 +1,150 @@
+//! Bulk maintenance operations across skills.
+//!
+//! These rewrite skill files in place, so every operation previews its
+//! changes first and keeps a copy of each file it overwrites under
+//! `.versions/` in the skills directory.
+
+mod diff;
+mod format;
+mod gc;
+mod merge;
+mod replace;
+mod split;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+use crate::index::SkillIndexer;
+
+pub use diff::line_diff;
+pub use format::{apply_format, plan_format, FormatPlan};
+pub use gc::{
+    apply_gc, plan_gc, schedule_gc, GcPlan, GcSchedule, OrphanedVersion, DEFAULT_GC_RETENTION,
+};
+pub use merge::{apply_merge, plan_merge, FileDiff, MergePlan, MovedFile};
+pub use replace::{apply_replace, plan_replace, FileChange, ReplacePlan, VERSIONS_DIR};
+pub use split::{apply_split, plan_split, SplitPlan};
+
+/// Errors from maintenance operations.
+#[derive(Debug, thiserror::Error)]
+pub enum MaintenanceError {
+    /// The request cannot be carried out as given.
+    #[error("Invalid request: {0}")]
+    Invalid(String),
+
+    /// A skill named in the request doesn't exist.
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    /// A file changed on disk between planning and applying.
+    #[error("File changed since it was planned: {0}")]
+    Conflict(String),
+
+    /// Reading or writing a file failed.
+    #[error("I/O error: {0}")]
+    Io(String),
+
+    /// A skill the operation would change is pinned.
+    #[error("Pinned: {0}")]
+    Pinned(String),
+}
+
+/// Refuse to change a pinned skill unless forced.
+fn check_pinned(indexer: &SkillIndexer, name: &str, force: bool) -> Result<(), MaintenanceError> {
+    if !force && indexer.get_skill_meta(name).is_some_and(|meta| meta.pinned) {
+        return Err(MaintenanceError::Pinned(format!(
+            "Skill '{}' is pinned; force the operation to change it",
+            name
+        )));
+    }
+    Ok(())
+}
+
+/// Copy a file into the version directory, keeping its path under the
+/// skills directory.
+fn save_version(
+    skills_dir: &Path,
+    version_dir: &Path,
+    path: &Path,
+) -> Result<PathBuf, MaintenanceError> {
+    let relative = path.strip_prefix(skills_dir).map_err(|_| {
+        MaintenanceError::Invalid(format!(
+            "{} is outside the skills directory",
+            path.display()
+        ))
+    })?;
+    let saved = version_dir.join(relative);
+
+    if let Some(parent) = saved.parent() {
+        fs::create_dir_all(parent).map_err(|e| MaintenanceError::Io(e.to_string()))?;
+    }
+    fs::copy(path, &saved).map_err(|e| MaintenanceError::Io(e.to_string()))?;
+    Ok(saved)
+}
+
+/// Write through a hidden temporary file and rename it into place.
+fn write_atomic(path: &Path, content: &str) -> Result<(), MaintenanceError> {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let temp = path.with_file_name(format!(".{}.tmp", name));
+
+    fs::write(&temp, content)
+        .and_then(|_| fs::rename(&temp, path))
+        .map_err(|e| {
+            let _ = fs::remove_file(&temp);
+            MaintenanceError::Io(format!("{}: {}", path.display(), e))
+        })
+}
+
+/// A completed step of an operation, kept so it can be reverted.
+enum Undo {
+    Created(PathBuf),
+    CreatedDir(PathBuf),
+    Overwrote { path: PathBuf, saved: PathBuf },
+    Moved { from: PathBuf, to: PathBuf },
+}
+
+impl Undo {
+    fn revert(self) -> std::io::Result<()> {
+        match self {
+            Undo::Created(path) => fs::remove_file(path),
+            Undo::CreatedDir(path) => fs::remove_dir_all(path),
+            Undo::Overwrote { path, saved } => fs::copy(saved, path).map(|_| ()),
+            Undo::Moved { from, to } => fs::rename(to, from),
+        }
+    }
+
+    /// Revert completed steps, most recent first.
+    fn revert_all(steps: Vec<Undo>) {
+        for step in steps.into_iter().rev() {
+            if let Err(e) = step.revert() {
+                tracing::error!("Failed to undo step: {}", e);
+            }
+        }
+    }
+}
+
+/// Point relative markdown links in `line` into `prefix/`.
+fn relink(line: &str, prefix: &str) -> String {
+    static LINK: OnceLock<Regex> = OnceLock::new();
+    let link = LINK.get_or_init(|| Regex::new(r"\]\(([^)\s]+)").unwrap());
+
+    link.replace_all(line, |captures: &regex::Captures<'_>| {
+        let target = &captures[1];
+        let external = target.contains("://")
+            || target.starts_with('#')
+            || target.starts_with('/')
+            || target.starts_with("mailto:");
+        if external {
+            captures[0].to_string()
+        } else {
+            format!("]({}/{}", prefix, target.trim_start_matches("./"))
+        }
+    })
+    .into_owned()
+}
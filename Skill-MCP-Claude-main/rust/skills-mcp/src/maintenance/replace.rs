@@ -0,0 +1,387 @@
+//! Regex search and replace across all skill documents.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+use serde::Serialize;
+
+use crate::index::SkillIndexer;
+use crate::models::content_hash;
+
+use super::{check_pinned, save_version, write_atomic, MaintenanceError};
+
+/// Directory under the skills root holding copies of overwritten files.
+///
+/// Each apply gets its own subdirectory named by timestamp, mirroring the
+/// skills directory layout. The leading dot keeps it out of the index.
+pub const VERSIONS_DIR: &str = ".versions";
+
+/// Longest pattern accepted.
+const MAX_PATTERN_LEN: usize = 1000;
+
+/// A pending rewrite of one file.
+#[derive(Debug, Clone, Serialize)]
+pub struct FileChange {
+    /// Skill the file belongs to.
+    pub skill: String,
+    /// File relative to the skill directory.
+    pub file: String,
+    /// Number of matches replaced, or of heading problems fixed by a
+    /// format.
+    pub replacements: usize,
+    /// Changed lines as unified diff hunks.
+    pub diff: String,
+    #[serde(skip)]
+    path: PathBuf,
+    #[serde(skip)]
+    original_hash: String,
+    #[serde(skip)]
+    updated: String,
+}
+
+impl FileChange {
+    /// A rewrite of `path`, planned from `original`.
+    pub(super) fn new(
+        skill: String,
+        file: String,
+        path: PathBuf,
+        original: &str,
+        updated: String,
+        replacements: usize,
+        diff: String,
+    ) -> Self {
+        Self {
+            skill,
+            file,
+            replacements,
+            diff,
+            path,
+            original_hash: content_hash(original),
+            updated,
+        }
+    }
+}
+
+/// Every change a replace would make.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplacePlan {
+    /// Regex that was searched for.
+    pub pattern: String,
+    /// Replacement, which may refer to groups as `$1` or `${name}`.
+    pub replacement: String,
+    /// Markdown files searched.
+    pub files_scanned: usize,
+    /// Matches across all files.
+    pub replacements: usize,
+    /// Files that would change, by skill and file.
+    pub changes: Vec<FileChange>,
+    /// Pinned skills with matches that were left alone.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub pinned: Vec<String>,
+}
+
+/// Work out a replace across every indexed markdown file without writing.
+///
+/// Pinned skills are skipped, and listed in the plan, unless `force` is set.
+pub fn plan_replace(
+    indexer: &SkillIndexer,
+    pattern: &str,
+    replacement: &str,
+    force: bool,
+) -> Result<ReplacePlan, MaintenanceError> {
+    if pattern.is_empty() {
+        return Err(MaintenanceError::Invalid(
+            "Pattern must not be empty".to_string(),
+        ));
+    }
+    if pattern.len() > MAX_PATTERN_LEN {
+        return Err(MaintenanceError::Invalid(format!(
+            "Pattern is longer than {} characters",
+            MAX_PATTERN_LEN
+        )));
+    }
+    let regex = Regex::new(pattern)
+        .map_err(|e| MaintenanceError::Invalid(format!("Invalid pattern: {}", e)))?;
+    if regex.is_match("") {
+        return Err(MaintenanceError::Invalid(
+            "Pattern must not match empty text".to_string(),
+        ));
+    }
+
+    let files = indexer.indexed_markdown_files(None);
+    let mut plan = ReplacePlan {
+        pattern: pattern.to_string(),
+        replacement: replacement.to_string(),
+        files_scanned: files.len(),
+        replacements: 0,
+        changes: Vec::new(),
+        pinned: Vec::new(),
+    };
+
+    for (skill, file, path) in files {
+        let original = fs::read_to_string(&path)
+            .map_err(|e| MaintenanceError::Io(format!("{}: {}", path.display(), e)))?;
+
+        let Some((updated, replacements, diff)) = rewrite(&regex, replacement, &original) else {
+            continue;
+        };
+        if check_pinned(indexer, &skill, force).is_err() {
+            if !plan.pinned.contains(&skill) {
+                plan.pinned.push(skill);
+            }
+            continue;
+        }
+
+        plan.replacements += replacements;
+        plan.changes.push(FileChange::new(
+            skill,
+            file,
+            path,
+            &original,
+            updated,
+            replacements,
+            diff,
+        ));
+    }
+
+    Ok(plan)
+}
+
+/// Write a planned replace, returning the version id of the saved originals.
+///
+/// Every file is first checked against the content it was planned from,
+/// so nothing is written if any of them changed in the meantime. Each file
+/// is replaced atomically; if a write fails, files already written are
+/// restored.
+pub fn apply_replace(
+    indexer: &SkillIndexer,
+    plan: &ReplacePlan,
+) -> Result<String, MaintenanceError> {
+    apply_changes(indexer, &plan.changes)
+}
+
+/// Write planned file changes as [`apply_replace`] does, returning the
+/// version id of the saved originals.
+pub(super) fn apply_changes(
+    indexer: &SkillIndexer,
+    changes: &[FileChange],
+) -> Result<String, MaintenanceError> {
+    let skills_dir = indexer.skills_dir();
+
+    for change in changes {
+        let current = fs::read_to_string(&change.path)
+            .map_err(|e| MaintenanceError::Io(format!("{}: {}", change.path.display(), e)))?;
+        if content_hash(&current) != change.original_hash {
+            return Err(MaintenanceError::Conflict(format!(
+                "{}/{}",
+                change.skill, change.file
+            )));
+        }
+    }
+
+    let version = chrono::Utc::now().format("%Y%m%dT%H%M%S%.3fZ").to_string();
+    let version_dir = skills_dir.join(VERSIONS_DIR).join(&version);
+
+    let mut written: Vec<(&Path, PathBuf)> = Vec::new();
+    for change in changes {
+        let result = save_version(skills_dir, &version_dir, &change.path).and_then(|saved| {
+            write_atomic(&change.path, &change.updated)?;
+            Ok(saved)
+        });
+
+        match result {
+            Ok(saved) => written.push((&change.path, saved)),
+            Err(e) => {
+                for (path, saved) in written {
+                    let restored = fs::read_to_string(&saved)
+                        .map_err(|e| MaintenanceError::Io(e.to_string()))
+                        .and_then(|original| write_atomic(path, &original));
+                    if let Err(restore_error) = restored {
+                        tracing::error!("Failed to restore {}: {}", path.display(), restore_error);
+                    }
+                }
+                return Err(e);
+            }
+        }
+    }
+
+    Ok(version)
+}
+
+/// Replace matches in `text`, returning the new text, the match count and
+/// a diff, or `None` if nothing matched.
+///
+/// Matches on the same or adjacent lines share a hunk.
+fn rewrite(regex: &Regex, replacement: &str, text: &str) -> Option<(String, usize, String)> {
+    // Line-aligned spans covering each match, merged where they touch
+    let mut spans: Vec<(usize, usize, Vec<regex::Captures<'_>>)> = Vec::new();
+    for captures in regex.captures_iter(text) {
+        let m = captures.get(0).unwrap();
+        let start = text[..m.start()].rfind('\n').map_or(0, |i| i + 1);
+        let end = text[m.end()..]
+            .find('\n')
+            .map_or(text.len(), |i| m.end() + i);
+
+        match spans.last_mut() {
+            Some(span) if start <= span.1 + 1 => {
+                span.1 = span.1.max(end);
+                span.2.push(captures);
+            }
+            _ => spans.push((start, end, vec![captures])),
+        }
+    }
+    if spans.is_empty() {
+        return None;
+    }
+
+    let mut updated = String::with_capacity(text.len());
+    let mut diff = String::new();
+    let mut count = 0;
+    let mut copied = 0;
+    let mut line_shift: isize = 0;
+
+    for (start, end, matches) in spans {
+        let mut chunk = String::new();
+        let mut at = start;
+        for captures in &matches {
+            let m = captures.get(0).unwrap();
+            chunk.push_str(&text[at..m.start()]);
+            captures.expand(replacement, &mut chunk);
+            at = m.end();
+        }
+        chunk.push_str(&text[at..end]);
+        count += matches.len();
+
+        let old = &text[start..end];
+        let old_line = text[..start].matches('\n').count() + 1;
+        let old_count = old.split('\n').count();
+        let new_count = chunk.split('\n').count();
+        let new_line = old_line as isize + line_shift;
+        line_shift += new_count as isize - old_count as isize;
+
+        diff.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            old_line, old_count, new_line, new_count
+        ));
+        for line in old.split('\n') {
+            diff.push_str(&format!("-{}\n", line));
+        }
+        for line in chunk.split('\n') {
+            diff.push_str(&format!("+{}\n", line));
+        }
+
+        updated.push_str(&text[copied..start]);
+        updated.push_str(&chunk);
+        copied = end;
+    }
+    updated.push_str(&text[copied..]);
+
+    (updated != text).then_some((updated, count, diff))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn create_skill(dir: &Path, name: &str, content: &str) {
+        let skill_dir = dir.join(name);
+        fs::create_dir_all(&skill_dir).unwrap();
+        fs::write(
+            skill_dir.join("_meta.json"),
+            format!(r#"{{"name": "{}", "description": "A skill"}}"#, name),
+        )
+        .unwrap();
+        fs::write(skill_dir.join("SKILL.md"), content).unwrap();
+    }
+
+    #[test]
+    fn test_rewrite_diff() {
+        let regex = Regex::new(r"Acme(\w*)").unwrap();
+        let text = "# Title\nUse AcmeDB.\nAcmeCLI too.\n\nplain\nAcme\n";
+
+        let (updated, count, diff) = rewrite(&regex, "Nova$1", text).unwrap();
+        assert_eq!(
+            updated,
+            "# Title\nUse NovaDB.\nNovaCLI too.\n\nplain\nNova\n"
+        );
+        assert_eq!(count, 3);
+        assert_eq!(
+            diff,
+            "@@ -2,2 +2,2 @@\n-Use AcmeDB.\n-AcmeCLI too.\n+Use NovaDB.\n+NovaCLI too.\n\
+             @@ -6,1 +6,1 @@\n-Acme\n+Nova\n"
+        );
+
+        assert!(rewrite(&regex, "x", "nothing here").is_none());
+        assert!(rewrite(&regex, "Acme", "Acme").is_none());
+    }
+
+    #[test]
+    fn test_plan_and_apply_replace() {
+        let temp = TempDir::new().unwrap();
+        create_skill(temp.path(), "db", "# DB\n\nConnect with AcmeDB.\n");
+        create_skill(temp.path(), "cli", "# CLI\n\nNothing to see.\n");
+
+        let indexer = SkillIndexer::new(temp.path());
+        indexer.reload().unwrap();
+
+        assert!(plan_replace(&indexer, "", "x", false).is_err());
+        assert!(plan_replace(&indexer, "a*", "x", false).is_err());
+        assert!(plan_replace(&indexer, "(", "x", false).is_err());
+
+        let plan = plan_replace(&indexer, "AcmeDB", "NovaDB", false).unwrap();
+        assert_eq!(plan.files_scanned, 2);
+        assert_eq!(plan.replacements, 1);
+        assert_eq!(plan.changes.len(), 1);
+        assert_eq!(plan.changes[0].skill, "db");
+
+        // Planning writes nothing
+        let skill_md = temp.path().join("db").join("SKILL.md");
+        assert!(fs::read_to_string(&skill_md).unwrap().contains("AcmeDB"));
+
+        let version = apply_replace(&indexer, &plan).unwrap();
+        assert_eq!(
+            fs::read_to_string(&skill_md).unwrap(),
+            "# DB\n\nConnect with NovaDB.\n"
+        );
+        let saved = temp
+            .path()
+            .join(VERSIONS_DIR)
+            .join(&version)
+            .join("db")
+            .join("SKILL.md");
+        assert!(fs::read_to_string(saved).unwrap().contains("AcmeDB"));
+
+        // The plan is stale now that the file changed
+        assert!(matches!(
+            apply_replace(&indexer, &plan),
+            Err(MaintenanceError::Conflict(_))
+        ));
+
+        // Saved versions aren't indexed
+        indexer.reload().unwrap();
+        assert_eq!(indexer.indexed_markdown_files(None).len(), 2);
+    }
+
+    #[test]
+    fn test_replace_skips_pinned() {
+        let temp = TempDir::new().unwrap();
+        create_skill(temp.path(), "db", "# DB\n\nConnect with AcmeDB.\n");
+        fs::write(
+            temp.path().join("db/_meta.json"),
+            r#"{"name": "db", "description": "A skill", "pinned": true}"#,
+        )
+        .unwrap();
+        let indexer = SkillIndexer::new(temp.path());
+        indexer.reload().unwrap();
+
+        let plan = plan_replace(&indexer, "AcmeDB", "NovaDB", false).unwrap();
+        assert!(plan.changes.is_empty());
+        assert_eq!(plan.pinned, vec!["db"]);
+
+        let forced = plan_replace(&indexer, "AcmeDB", "NovaDB", true).unwrap();
+        assert_eq!(forced.changes.len(), 1);
+        assert!(forced.pinned.is_empty());
+    }
+}
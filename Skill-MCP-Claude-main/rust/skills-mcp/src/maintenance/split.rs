@@ -0,0 +1,464 @@
+//! Splitting an oversized skill into sub-skills.
+
+use std::fs;
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::index::SkillIndexer;
+use crate::models::{content_hash, sub_skill_table, title_case, SkillMeta, SubSkillMeta};
+
+use super::{
+    check_pinned, line_diff, relink, save_version, write_atomic, FileDiff, MaintenanceError, Undo,
+};
+
+/// Fewest top-level sections worth splitting into sub-skills.
+const MIN_SECTIONS: usize = 2;
+
+/// Longest description kept for a sub-skill in the table of contents.
+const MAX_DESCRIPTION_CHARS: usize = 100;
+
+/// Everything a split would do.
+#[derive(Debug, Clone, Serialize)]
+pub struct SplitPlan {
+    /// Skill being split.
+    pub skill: String,
+    /// Sub-skills created from its sections.
+    pub sub_skills: Vec<SubSkillMeta>,
+    /// Metadata after the split.
+    pub meta: SkillMeta,
+    /// Changes to `_meta.json` and `SKILL.md`, then the new files.
+    pub diffs: Vec<FileDiff>,
+    #[serde(skip)]
+    files: Vec<(PathBuf, String)>,
+    /// Files the plan was made from, with their hashes.
+    #[serde(skip)]
+    checked: Vec<(PathBuf, String)>,
+}
+
+/// Work out a split of a skill's SKILL.md at its top-level headings.
+///
+/// Top-level headings are the shallowest ones below a lone leading `#`
+/// title. Each section becomes `references/<slug>.md` with its headings
+/// moved up to match; the title and any introduction stay in SKILL.md,
+/// followed by a table of contents of all sub-skills, which replaces an
+/// existing `Sub-Skills` section. A pinned skill is only split with `force`.
+pub fn plan_split(
+    indexer: &SkillIndexer,
+    name: &str,
+    force: bool,
+) -> Result<SplitPlan, MaintenanceError> {
+    let mut meta = indexer
+        .get_skill_meta(name)
+        .ok_or_else(|| MaintenanceError::NotFound(format!("Skill '{}' not found", name)))?;
+    check_pinned(indexer, name, force)?;
+    if indexer.flat_skill_path(name).is_some() {
+        return Err(MaintenanceError::Invalid(format!(
+            "'{}' is a flat-file skill; give it a directory before splitting",
+            name
+        )));
+    }
+
+    let skill_dir = indexer.skills_dir().join(name);
+    let mut checked = Vec::new();
+    let mut read = |path: PathBuf| -> Result<String, MaintenanceError> {
+        let text = fs::read_to_string(&path)
+            .map_err(|e| MaintenanceError::Io(format!("{}: {}", path.display(), e)))?;
+        checked.push((path, content_hash(&text)));
+        Ok(text)
+    };
+    let meta_path = skill_dir.join("_meta.json");
+    let old_meta_json = if meta_path.exists() {
+        read(meta_path.clone())?
+    } else {
+        String::new()
+    };
+    let skill_md = skill_dir.join("SKILL.md");
+    let old_content = read(skill_md.clone())?;
+
+    let (preamble, level, sections) = top_level_sections(&old_content);
+    let sections: Vec<Section> = sections
+        .into_iter()
+        .filter(|s| !s.heading.eq_ignore_ascii_case("sub-skills"))
+        .collect();
+    if sections.len() < MIN_SECTIONS {
+        return Err(MaintenanceError::Invalid(format!(
+            "'{}' has fewer than {} top-level sections to split",
+            name, MIN_SECTIONS
+        )));
+    }
+
+    let mut sub_skills = meta.sub_skills.take().unwrap_or_default();
+    let mut rows: Vec<(String, String)> = sub_skills
+        .iter()
+        .map(|sub| {
+            let description = indexer
+                .read_sub_skill_content(name, &sub.name)
+                .map(|c| describe(&c.content))
+                .unwrap_or_default();
+            (sub.name.clone(), description)
+        })
+        .collect();
+
+    let mut created = Vec::new();
+    let mut files = Vec::new();
+    let mut diffs = Vec::new();
+    for (i, section) in sections.iter().enumerate() {
+        let base = match slug(&section.heading) {
+            slug if slug.is_empty() => format!("section-{}", i + 1),
+            slug => slug,
+        };
+        let mut slug = base.clone();
+        let mut n = 2;
+        while sub_skills.iter().any(|s| s.name == slug) {
+            slug = format!("{}-{}", base, n);
+            n += 1;
+        }
+
+        let file = format!("references/{}.md", slug);
+        let path = skill_dir.join(&file);
+        if path.exists() {
+            return Err(MaintenanceError::Invalid(format!(
+                "{}/{} already exists",
+                name, file
+            )));
+        }
+
+        let content = sub_skill_document(section, level);
+        diffs.push(FileDiff {
+            file: format!("{}/{}", name, file),
+            diff: line_diff("", &content),
+        });
+        rows.push((slug.clone(), describe(&section.body)));
+        let sub = SubSkillMeta {
+            name: slug,
+            file,
+            triggers: Vec::new(),
+            children: Vec::new(),
+            reference: None,
+        };
+        sub_skills.push(sub.clone());
+        created.push(sub);
+        files.push((path, content));
+    }
+    meta.sub_skills = Some(sub_skills);
+
+    let preamble = preamble.trim();
+    let mut content = if preamble.is_empty() {
+        let leaf = name.rsplit('/').next().unwrap_or(name);
+        format!("# {}\n\n{}", title_case(leaf), meta.description.trim())
+    } else {
+        preamble.to_string()
+    };
+    let rows: Vec<(&str, &str)> = rows
+        .iter()
+        .map(|(name, description)| (name.as_str(), description.as_str()))
+        .collect();
+    content.push_str("\n\n");
+    content.push_str(&sub_skill_table(&rows));
+
+    let meta_json = serde_json::to_string_pretty(&meta)
+        .map_err(|e| MaintenanceError::Io(format!("Failed to serialize meta: {}", e)))?;
+    diffs.splice(
+        0..0,
+        [
+            FileDiff {
+                file: format!("{}/_meta.json", name),
+                diff: line_diff(&old_meta_json, &meta_json),
+            },
+            FileDiff {
+                file: format!("{}/SKILL.md", name),
+                diff: line_diff(&old_content, &content),
+            },
+        ],
+    );
+    files.splice(0..0, [(meta_path, meta_json), (skill_md, content)]);
+
+    Ok(SplitPlan {
+        skill: name.to_string(),
+        sub_skills: created,
+        meta,
+        diffs,
+        files,
+        checked,
+    })
+}
+
+/// Carry out a planned split, returning the version id the old
+/// `_meta.json` and `SKILL.md` were saved under.
+///
+/// Nothing is written if either changed since planning or a sub-skill
+/// file appeared in the meantime; if a write fails, earlier ones are
+/// undone.
+pub fn apply_split(indexer: &SkillIndexer, plan: &SplitPlan) -> Result<String, MaintenanceError> {
+    for (path, hash) in &plan.checked {
+        let current = fs::read_to_string(path).unwrap_or_default();
+        if &content_hash(&current) != hash {
+            return Err(MaintenanceError::Conflict(path.display().to_string()));
+        }
+    }
+    // The first two files are the skill's own, the rest new sub-skills
+    for (path, _) in &plan.files[2..] {
+        if path.exists() {
+            return Err(MaintenanceError::Conflict(path.display().to_string()));
+        }
+    }
+
+    let skills_dir = indexer.skills_dir();
+    let version = chrono::Utc::now().format("%Y%m%dT%H%M%S%.3fZ").to_string();
+    let version_dir = skills_dir.join(super::VERSIONS_DIR).join(&version);
+
+    let mut undo = Vec::new();
+    let result = plan.files.iter().try_for_each(|(path, text)| {
+        if path.exists() {
+            let saved = save_version(skills_dir, &version_dir, path)?;
+            write_atomic(path, text)?;
+            undo.push(Undo::Overwrote {
+                path: path.clone(),
+                saved,
+            });
+        } else {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| MaintenanceError::Io(format!("{}: {}", parent.display(), e)))?;
+            }
+            write_atomic(path, text)?;
+            undo.push(Undo::Created(path.clone()));
+        }
+        Ok(())
+    });
+    if let Err(e) = result {
+        Undo::revert_all(undo);
+        return Err(e);
+    }
+
+    Ok(version)
+}
+
+/// A top-level section of a document.
+#[derive(Debug, PartialEq)]
+struct Section {
+    heading: String,
+    body: String,
+}
+
+/// Split markdown at its top-level headings.
+///
+/// Returns the text before the first section, the heading level split
+/// at, and the sections. Headings in fenced code blocks are ignored.
+fn top_level_sections(markdown: &str) -> (String, usize, Vec<Section>) {
+    let mut headings = Vec::new();
+    let mut in_fence = false;
+    for (i, line) in markdown.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        }
+        if let Some(level) = heading_level(line).filter(|_| !in_fence) {
+            headings.push((i, level));
+        }
+    }
+
+    // A lone leading `#` heading is the document's title
+    let titled = headings.first().is_some_and(|&(_, level)| level == 1)
+        && headings.iter().filter(|&&(_, level)| level == 1).count() == 1;
+    let Some(level) = headings
+        .iter()
+        .skip(usize::from(titled))
+        .map(|&(_, level)| level)
+        .min()
+    else {
+        return (markdown.to_string(), 0, Vec::new());
+    };
+
+    let mut preamble = String::new();
+    let mut sections: Vec<Section> = Vec::new();
+    let starts: Vec<usize> = headings
+        .iter()
+        .skip(usize::from(titled))
+        .filter(|&&(_, l)| l == level)
+        .map(|&(i, _)| i)
+        .collect();
+    for (i, line) in markdown.lines().enumerate() {
+        if starts.contains(&i) {
+            sections.push(Section {
+                heading: line.trim_start_matches('#').trim().to_string(),
+                body: String::new(),
+            });
+            continue;
+        }
+        let text = match sections.last_mut() {
+            Some(section) => &mut section.body,
+            None => &mut preamble,
+        };
+        text.push_str(line);
+        text.push('\n');
+    }
+
+    (preamble, level, sections)
+}
+
+/// Level of an ATX heading line, if it is one.
+fn heading_level(line: &str) -> Option<usize> {
+    let hashes = line.len() - line.trim_start_matches('#').len();
+    let rest = &line[hashes..];
+    ((1..=6).contains(&hashes) && rest.starts_with(' ') && !rest.trim().is_empty())
+        .then_some(hashes)
+}
+
+/// A section as a document of its own, with the section heading as its
+/// title and the headings below it moved up to match.
+///
+/// Relative links are pointed back up, since the file moves into
+/// `references/`.
+fn sub_skill_document(section: &Section, level: usize) -> String {
+    let mut out = format!("# {}\n", section.heading);
+    let mut in_fence = false;
+    for line in section.body.trim().lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        }
+        out.push('\n');
+        if in_fence {
+            out.push_str(line);
+            continue;
+        }
+        match heading_level(line) {
+            Some(depth) => {
+                let promoted = depth.saturating_sub(level - 1).max(2);
+                out.push_str(&"#".repeat(promoted));
+                out.push_str(&relink(&line[depth..], ".."));
+            }
+            None => out.push_str(&relink(line, "..")),
+        }
+    }
+    out.push('\n');
+    out
+}
+
+/// A one-line description of a document for the table of contents: its
+/// first line of prose, shortened.
+fn describe(markdown: &str) -> String {
+    let line = markdown
+        .lines()
+        .map(str::trim)
+        .find(|line| {
+            !line.is_empty()
+                && !line.starts_with('#')
+                && !line.starts_with("```")
+                && !line.starts_with('|')
+        })
+        .unwrap_or("");
+    let line = line.trim_start_matches(['-', '*', '>', ' ']);
+    match line.char_indices().nth(MAX_DESCRIPTION_CHARS) {
+        Some((end, _)) => format!("{}…", line[..end].trim_end()),
+        None => line.to_string(),
+    }
+}
+
+/// `Form Validation & Errors` becomes `form-validation-errors`.
+fn slug(heading: &str) -> String {
+    let mut slug = String::new();
+    for c in heading.chars().flat_map(char::to_lowercase) {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const GUIDE: &str = "# Forms\n\nEverything about forms.\n\n\
+        ## Validation Rules\n\nCheck input early. See [zod](zod.md).\n\n### Async\n\nDebounce.\n\n\
+        ## Layout & Styling\n\n```\n## not a heading\n```\n";
+
+    #[test]
+    fn test_top_level_sections() {
+        let (preamble, level, sections) = top_level_sections(GUIDE);
+        assert_eq!(preamble, "# Forms\n\nEverything about forms.\n\n");
+        assert_eq!(level, 2);
+        assert_eq!(
+            sections
+                .iter()
+                .map(|s| s.heading.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Validation Rules", "Layout & Styling"]
+        );
+
+        assert_eq!(
+            sub_skill_document(&sections[0], level),
+            "# Validation Rules\n\nCheck input early. See [zod](../zod.md).\n\n## Async\n\nDebounce.\n"
+        );
+        assert_eq!(slug(&sections[1].heading), "layout-styling");
+
+        // Without a lone title, the shallowest headings split
+        let (_, level, sections) = top_level_sections("# A\n\ntext\n\n# B\n\n## B1\n");
+        assert_eq!(level, 1);
+        assert_eq!(sections.len(), 2);
+    }
+
+    #[test]
+    fn test_plan_and_apply_split() {
+        let temp = TempDir::new().unwrap();
+        let skill_dir = temp.path().join("forms");
+        fs::create_dir_all(&skill_dir).unwrap();
+        fs::write(
+            skill_dir.join("_meta.json"),
+            r#"{"name": "forms", "description": "Forms", "tags": ["forms"], "pinned": true}"#,
+        )
+        .unwrap();
+        fs::write(skill_dir.join("SKILL.md"), GUIDE).unwrap();
+
+        let indexer = SkillIndexer::new(temp.path());
+        indexer.reload().unwrap();
+
+        assert!(matches!(
+            plan_split(&indexer, "forms", false),
+            Err(MaintenanceError::Pinned(_))
+        ));
+        let plan = plan_split(&indexer, "forms", true).unwrap();
+        let names: Vec<&str> = plan.sub_skills.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["validation-rules", "layout-styling"]);
+        assert_eq!(plan.diffs.len(), 4);
+        assert_eq!(plan.diffs[3].file, "forms/references/layout-styling.md");
+
+        // Planning writes nothing
+        assert!(!skill_dir.join("references").exists());
+
+        apply_split(&indexer, &plan).unwrap();
+        indexer.reload().unwrap();
+
+        let content = fs::read_to_string(skill_dir.join("SKILL.md")).unwrap();
+        assert!(content.starts_with("# Forms\n\nEverything about forms.\n\n## Sub-Skills\n"));
+        assert!(content.contains("| validation-rules | Check input early. See [zod](zod.md). |\n"));
+        assert!(!content.contains("Debounce"));
+
+        let meta = indexer.get_skill_meta("forms").unwrap();
+        assert_eq!(meta.tags, vec!["forms"]);
+        assert_eq!(
+            meta.sub_skill_names(),
+            vec!["validation-rules", "layout-styling"]
+        );
+        let sub = indexer
+            .read_sub_skill_content("forms", "layout-styling")
+            .unwrap();
+        assert!(sub.content.contains("## not a heading"));
+
+        // Only the table of contents is left to split
+        assert!(matches!(
+            plan_split(&indexer, "forms", true),
+            Err(MaintenanceError::Invalid(_))
+        ));
+        assert!(matches!(
+            plan_split(&indexer, "missing", false),
+            Err(MaintenanceError::NotFound(_))
+        ));
+    }
+}
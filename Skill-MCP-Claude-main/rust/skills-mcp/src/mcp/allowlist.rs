@@ -0,0 +1,97 @@
+//! Which tools the MCP server exposes.
+//!
+//! By default every tool is listed and callable. An allowlist of tool names
+//! and `prefix*` patterns (e.g. `list_skills,get_skill,search_*`) narrows
+//! that set for every client: other tools are left out of `tools/list` and
+//! refused by `tools/call`. Tools added later stay hidden until a pattern
+//! matches them.
+
+use std::str::FromStr;
+
+use super::dispatch::TOOL_NAMES;
+
+/// Tools the server exposes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ToolAllowlist {
+    /// Names and `prefix*` patterns; every tool when `None`.
+    patterns: Option<Vec<String>>,
+}
+
+impl ToolAllowlist {
+    /// Expose every tool.
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// Expose only the tools matching `patterns`.
+    ///
+    /// Fails on patterns that match no tool, which are usually typos.
+    pub fn only<I, S>(patterns: I) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let patterns: Vec<String> = patterns.into_iter().map(Into::into).collect();
+        for pattern in &patterns {
+            if !TOOL_NAMES.iter().any(|tool| matches(pattern, tool)) {
+                return Err(format!("No tool matches '{}'", pattern));
+            }
+        }
+        Ok(Self {
+            patterns: Some(patterns),
+        })
+    }
+
+    /// Whether `tool` is exposed.
+    pub fn allows(&self, tool: &str) -> bool {
+        match &self.patterns {
+            Some(patterns) => patterns.iter().any(|pattern| matches(pattern, tool)),
+            None => true,
+        }
+    }
+}
+
+impl FromStr for ToolAllowlist {
+    type Err = String;
+
+    /// Parse a comma-separated list of tool names and `prefix*` patterns.
+    fn from_str(list: &str) -> Result<Self, Self::Err> {
+        let patterns: Vec<&str> = list
+            .split(',')
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .collect();
+        if patterns.is_empty() {
+            return Err("The tool allowlist is empty".to_string());
+        }
+        Self::only(patterns)
+    }
+}
+
+/// Whether `tool` is `pattern`, or starts with it when it ends in `*`.
+fn matches(pattern: &str, tool: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => tool.starts_with(prefix),
+        None => tool == pattern,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allowlist() {
+        assert!(ToolAllowlist::all().allows("reload_index"));
+
+        let allowlist: ToolAllowlist = "list_skills, get_skill,search_*".parse().unwrap();
+        assert!(allowlist.allows("list_skills"));
+        assert!(allowlist.allows("search_content"));
+        assert!(allowlist.allows("search_batch"));
+        assert!(!allowlist.allows("get_skills_batch"));
+        assert!(!allowlist.allows("reload_index"));
+
+        assert!("serch_*".parse::<ToolAllowlist>().is_err());
+        assert!(" , ".parse::<ToolAllowlist>().is_err());
+    }
+}
@@ -0,0 +1,158 @@
+//! Answering questions from retrieved skill passages.
+//!
+//! Passages are numbered in the prompt and the model is asked to cite them
+//! as `[n]`; citations are read back from those markers, so only passages
+//! the answer actually refers to are cited.
+
+use std::sync::OnceLock;
+
+use regex::Regex;
+use serde::Serialize;
+
+use crate::llm::{CompletionRequest, LlmBackend, LlmError};
+use crate::search::Passage;
+
+/// Reply length bound for answers.
+const MAX_ANSWER_TOKENS: u32 = 800;
+
+const SYSTEM_PROMPT: &str = "You answer questions using only the numbered passages from \
+skill documents that follow the question. Cite every passage you rely on with its number in \
+square brackets, such as [1]. If the passages don't answer the question, say so instead of \
+guessing.";
+
+/// A passage an answer refers to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Citation {
+    /// Number of the passage as cited in the answer, starting at 1.
+    pub index: usize,
+    /// Skill the passage comes from.
+    pub skill: String,
+    /// File relative to the skill directory.
+    pub file: String,
+    /// Section heading, if the passage has one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub section: Option<String>,
+}
+
+/// An answer with the passages it cites.
+#[derive(Debug, Clone, Serialize)]
+pub struct Answer {
+    /// Answer text, with `[n]` citation markers.
+    pub text: String,
+    /// Cited passages, in passage order.
+    pub citations: Vec<Citation>,
+    /// Backend that wrote the answer.
+    pub model: String,
+}
+
+/// Lay out passages as numbered context for a prompt.
+pub fn build_context(passages: &[Passage]) -> String {
+    passages
+        .iter()
+        .enumerate()
+        .map(|(i, passage)| {
+            let mut label = format!("[{}] {}/{}", i + 1, passage.skill, passage.file);
+            if let Some(section) = &passage.section {
+                label.push_str(" § ");
+                label.push_str(section);
+            }
+            format!("{}\n{}", label, passage.text)
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Ask a backend to answer `question` from `passages`.
+pub fn answer_question(
+    backend: &dyn LlmBackend,
+    question: &str,
+    passages: &[Passage],
+) -> Result<Answer, LlmError> {
+    let prompt = format!(
+        "Question: {}\n\nPassages:\n\n{}",
+        question.trim(),
+        build_context(passages)
+    );
+    let request = CompletionRequest::new(SYSTEM_PROMPT, prompt).with_max_tokens(MAX_ANSWER_TOKENS);
+
+    let text = backend.complete(&request)?.trim().to_string();
+    if text.is_empty() {
+        return Err(LlmError::InvalidResponse("empty answer".to_string()));
+    }
+
+    Ok(Answer {
+        citations: citations(&text, passages),
+        text,
+        model: backend.name().to_string(),
+    })
+}
+
+/// Resolve the `[n]` markers in an answer to passages.
+///
+/// Markers outside the passage range are ignored; each passage is cited
+/// once.
+fn citations(answer: &str, passages: &[Passage]) -> Vec<Citation> {
+    static MARKER: OnceLock<Regex> = OnceLock::new();
+    let marker = MARKER.get_or_init(|| Regex::new(r"\[(\d+(?:\s*,\s*\d+)*)\]").unwrap());
+
+    let mut cited: Vec<usize> = marker
+        .captures_iter(answer)
+        .flat_map(|c| {
+            c[1].split(',')
+                .filter_map(|n| n.trim().parse::<usize>().ok())
+                .collect::<Vec<_>>()
+        })
+        .filter(|&n| n >= 1 && n <= passages.len())
+        .collect();
+    cited.sort_unstable();
+    cited.dedup();
+
+    cited
+        .into_iter()
+        .map(|index| {
+            let passage = &passages[index - 1];
+            Citation {
+                index,
+                skill: passage.skill.clone(),
+                file: passage.file.clone(),
+                section: passage.section.clone(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn passage(skill: &str, section: Option<&str>) -> Passage {
+        Passage {
+            skill: skill.to_string(),
+            file: "SKILL.md".to_string(),
+            section: section.map(str::to_string),
+            text: format!("About {}.", skill),
+            score: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_build_context() {
+        let context = build_context(&[passage("forms", Some("Validation")), passage("a11y", None)]);
+        assert_eq!(
+            context,
+            "[1] forms/SKILL.md § Validation\nAbout forms.\n\n[2] a11y/SKILL.md\nAbout a11y."
+        );
+    }
+
+    #[test]
+    fn test_citations() {
+        let passages = [passage("forms", Some("Validation")), passage("a11y", None)];
+        let cited = citations("Validate on blur [2]. See [1, 2] and [7].", &passages);
+
+        assert_eq!(cited.len(), 2);
+        assert_eq!(cited[0].index, 1);
+        assert_eq!(cited[0].section.as_deref(), Some("Validation"));
+        assert_eq!(cited[1].skill, "a11y");
+        assert!(citations("No markers.", &passages).is_empty());
+    }
+}
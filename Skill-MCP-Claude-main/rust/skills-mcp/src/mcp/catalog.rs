@@ -0,0 +1,245 @@
+//! Descriptions and input schemas of the tools, as listed to MCP clients.
+//!
+//! Input schemas are generated from the request types in [`super::tools`],
+//! so the arguments a tool advertises are the ones it deserializes.
+
+use schemars::gen::SchemaSettings;
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+#[cfg(feature = "scripts")]
+use super::tools::RunSkillScriptRequest;
+use super::tools::{
+    AskSkillsRequest, CreateSkillRequest, DeleteSkillRequest, GetLoadedSkillsRequest,
+    GetReferenceRequest, GetSkillRequest, GetSkillsBatchRequest, GetSubSkillRequest,
+    ListReferencesRequest, MarkSkillLoadedRequest, RouteTaskRequest, ScaffoldSkillRequest,
+    SearchBatchRequest, SearchContentRequest, SearchSkillsRequest, SuggestMetadataRequest,
+    SummarizeSkillRequest, UpdateSkillRequest,
+};
+
+/// A tool as advertised by `tools/list`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ToolDefinition {
+    /// Name passed to `tools/call`.
+    pub name: &'static str,
+    /// What the tool does, for the model choosing tools.
+    pub description: &'static str,
+    /// JSON Schema of the tool's arguments.
+    #[serde(rename = "inputSchema")]
+    pub input_schema: Value,
+}
+
+impl ToolDefinition {
+    fn new(name: &'static str, description: &'static str, input_schema: Value) -> Self {
+        Self {
+            name,
+            description,
+            input_schema,
+        }
+    }
+}
+
+/// JSON Schema of a tool's request type, generated from its fields and
+/// their doc comments.
+///
+/// Subschemas are inlined so each tool's schema stands alone, and the
+/// request's own title and description are dropped in favor of the tool's.
+fn schema<T: JsonSchema>() -> Value {
+    let generator = SchemaSettings::draft07()
+        .with(|settings| {
+            settings.inline_subschemas = true;
+            settings.option_add_null_type = false;
+            settings.meta_schema = None;
+        })
+        .into_generator();
+    let mut schema = serde_json::to_value(generator.into_root_schema_for::<T>())
+        .unwrap_or_else(|_| json!({"type": "object"}));
+    if let Some(object) = schema.as_object_mut() {
+        object.remove("title");
+        object.remove("description");
+    }
+    schema
+}
+
+/// Schema of a tool that takes no arguments.
+fn no_arguments() -> Value {
+    json!({"type": "object", "properties": {}})
+}
+
+/// Every tool [`super::call_tool`] accepts, in [`super::TOOL_NAMES`] order.
+pub fn tool_definitions() -> Vec<ToolDefinition> {
+    vec![
+        ToolDefinition::new(
+            "list_skills",
+            "List every skill with its description, tags, sub-skills and quality score.",
+            no_arguments(),
+        ),
+        ToolDefinition::new(
+            "get_skill",
+            "Load a skill's main SKILL.md content. Large documents come in chunks; pass \
+             next_offset back as content_offset for the rest.",
+            schema::<GetSkillRequest>(),
+        ),
+        ToolDefinition::new(
+            "get_sub_skill",
+            "Load one sub-skill of a skill, by name, slash-separated path or markdown file.",
+            schema::<GetSubSkillRequest>(),
+        ),
+        ToolDefinition::new(
+            "get_skills_batch",
+            "Load several skills and sub-skills in one call.",
+            schema::<GetSkillsBatchRequest>(),
+        ),
+        ToolDefinition::new(
+            "search_skills",
+            "Search skills by name, description, tags and triggers.",
+            schema::<SearchSkillsRequest>(),
+        ),
+        ToolDefinition::new(
+            "search_content",
+            "Full-text search of skill documents, with snippets.",
+            schema::<SearchContentRequest>(),
+        ),
+        ToolDefinition::new(
+            "search_batch",
+            "Run several searches in one call.",
+            schema::<SearchBatchRequest>(),
+        ),
+        ToolDefinition::new(
+            "create_skill",
+            "Create a skill from a built-in or _templates/ template.",
+            schema::<CreateSkillRequest>(),
+        ),
+        ToolDefinition::new(
+            "scaffold_skill",
+            "Create a skill with stub sub-skills from an outline.",
+            schema::<ScaffoldSkillRequest>(),
+        ),
+        ToolDefinition::new(
+            "update_skill",
+            "Edit a skill's description, tags or SKILL.md content.",
+            schema::<UpdateSkillRequest>(),
+        ),
+        ToolDefinition::new(
+            "delete_skill",
+            "Delete a skill and its files.",
+            schema::<DeleteSkillRequest>(),
+        ),
+        ToolDefinition::new(
+            "reload_index",
+            "Reload the skill index from disk, reporting progress against a progressToken.",
+            no_arguments(),
+        ),
+        ToolDefinition::new(
+            "diff_index",
+            "Report how the index differs from disk, without reloading.",
+            no_arguments(),
+        ),
+        ToolDefinition::new(
+            "get_stats",
+            "Usage statistics of tools, skills and searches.",
+            no_arguments(),
+        ),
+        ToolDefinition::new(
+            "validate_skills",
+            "Check every skill's structure and metadata.",
+            no_arguments(),
+        ),
+        ToolDefinition::new(
+            "list_references",
+            "List the documents in a skill's references directory.",
+            schema::<ListReferencesRequest>(),
+        ),
+        ToolDefinition::new(
+            "get_reference",
+            "Read one of a skill's reference documents.",
+            schema::<GetReferenceRequest>(),
+        ),
+        #[cfg(feature = "scripts")]
+        ToolDefinition::new(
+            "run_skill_script",
+            "Run a script bundled with a skill in the sandbox.",
+            schema::<RunSkillScriptRequest>(),
+        ),
+        ToolDefinition::new(
+            "suggest_metadata",
+            "Draft descriptions, tags and triggers for skills with weak metadata, for review.",
+            schema::<SuggestMetadataRequest>(),
+        ),
+        ToolDefinition::new(
+            "ask_skills",
+            "Answer a question from the most relevant skill passages.",
+            schema::<AskSkillsRequest>(),
+        ),
+        ToolDefinition::new(
+            "route_task",
+            "Pick the skill and sub-skill to load for a task, with confidence and reasons.",
+            schema::<RouteTaskRequest>(),
+        ),
+        ToolDefinition::new(
+            "mark_skill_loaded",
+            "Record skills already injected into a session's context.",
+            schema::<MarkSkillLoadedRequest>(),
+        ),
+        ToolDefinition::new(
+            "get_loaded_skills",
+            "List the skills a session has loaded.",
+            schema::<GetLoadedSkillsRequest>(),
+        ),
+        ToolDefinition::new(
+            "summarize_skill",
+            "Summarize a skill with the client's model through sampling, caching the result.",
+            schema::<SummarizeSkillRequest>(),
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::TOOL_NAMES;
+
+    #[test]
+    fn test_every_tool_is_defined() {
+        let names: Vec<&str> = tool_definitions().iter().map(|t| t.name).collect();
+        assert_eq!(names, TOOL_NAMES);
+
+        for tool in tool_definitions() {
+            assert_eq!(tool.input_schema["type"], "object", "{}", tool.name);
+        }
+    }
+
+    #[test]
+    fn test_search_params_are_shared() {
+        let search = tool_definitions()
+            .into_iter()
+            .find(|t| t.name == "search_content")
+            .unwrap();
+        let properties = &search.input_schema["properties"];
+        assert!(properties["group_by_domain"].is_object());
+        assert!(properties["min_confidence"].is_object());
+        assert_eq!(search.input_schema["required"], json!(["query"]));
+    }
+
+    #[test]
+    fn test_schemas_follow_request_types() {
+        let get_skill = schema::<GetSkillRequest>();
+        assert!(get_skill.get("title").is_none());
+        assert_eq!(get_skill["required"], json!(["name"]));
+        assert_eq!(get_skill["properties"]["name"]["type"], "string");
+        assert_eq!(
+            get_skill["properties"]["name"]["description"],
+            "Name of the skill to retrieve."
+        );
+        // Optional fields are plain types, not nullable ones
+        assert_eq!(get_skill["properties"]["session_id"]["type"], "string");
+
+        // Nested types are inlined
+        let scaffold = schema::<ScaffoldSkillRequest>();
+        let outline = &scaffold["properties"]["outline"]["items"];
+        assert_eq!(outline["type"], "object");
+        assert_eq!(outline["required"], json!(["description", "name"]));
+        assert!(scaffold.get("definitions").is_none());
+    }
+}
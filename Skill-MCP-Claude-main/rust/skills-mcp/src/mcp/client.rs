@@ -0,0 +1,305 @@
+//! Per-client state in the MCP server.
+//!
+//! Clients are known by the name they give at `initialize`
+//! (`clientInfo.name`); clients that give none share [`ANONYMOUS_CLIENT`].
+//! A client's [`ClientSession`] is shared by all of its connections and
+//! holds its [`ClientPolicy`], which skill domains it sees and whether it
+//! may change skills, and its own call counts, kept apart from the
+//! server-wide usage stats.
+//!
+//! Domain filters apply to listing, search, routing and question answering,
+//! and to every tool that names a skill. Index-wide tools such as
+//! validate_skills and get_stats are not filtered.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::models::{
+    ErrorCode, ErrorResponse, SkillIndex, CATEGORY_SEPARATOR, SUB_SKILL_REF_SEPARATOR,
+};
+
+/// Client name used when `initialize` gives none.
+pub const ANONYMOUS_CLIENT: &str = "anonymous";
+
+/// Tools that change skills on disk, refused to read-only clients.
+pub const MUTATING_TOOLS: &[&str] = &[
+    "create_skill",
+    "scaffold_skill",
+    "update_skill",
+    "delete_skill",
+];
+
+/// Arguments naming the skill a tool acts on.
+const SKILL_ARGUMENTS: &[&str] = &["name", "domain", "skill"];
+
+/// What a client may see and do.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClientPolicy {
+    /// Skills and category folders (e.g. `frontend`) the client sees;
+    /// every skill when empty.
+    pub domains: Vec<String>,
+
+    /// Refuse [`MUTATING_TOOLS`].
+    pub read_only: bool,
+}
+
+impl ClientPolicy {
+    /// See every skill and allow every tool.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Let the client see a skill, or every skill in a category folder.
+    pub fn domain(mut self, domain: impl Into<String>) -> Self {
+        self.domains.push(domain.into());
+        self
+    }
+
+    /// Refuse tools that change skills.
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Whether the client sees `skill`.
+    pub fn allows(&self, skill: &str) -> bool {
+        self.domains.is_empty()
+            || self.domains.iter().any(|domain| {
+                let domain = domain.trim_matches(CATEGORY_SEPARATOR);
+                skill == domain
+                    || skill
+                        .strip_prefix(domain)
+                        .is_some_and(|rest| rest.starts_with(CATEGORY_SEPARATOR))
+            })
+    }
+
+    /// Skills in `index` the client sees, or `None` if it sees them all.
+    pub fn visible_skills(&self, index: &SkillIndex) -> Option<Vec<String>> {
+        if self.domains.is_empty() {
+            return None;
+        }
+        Some(
+            index
+                .skills
+                .iter()
+                .filter(|s| self.allows(&s.name))
+                .map(|s| s.name.clone())
+                .collect(),
+        )
+    }
+
+    /// Refuse a call this client may not make: a mutating tool when
+    /// read-only, or one naming a skill outside its domains.
+    pub fn check_call(&self, tool: &str, arguments: &Value) -> Result<(), ErrorResponse> {
+        if self.read_only && MUTATING_TOOLS.contains(&tool) {
+            return Err(ErrorResponse::new(format!(
+                "{} is not allowed for read-only clients",
+                tool
+            ))
+            .with_code(ErrorCode::Forbidden));
+        }
+        if self.domains.is_empty() {
+            return Ok(());
+        }
+
+        let named = SKILL_ARGUMENTS
+            .iter()
+            .filter_map(|key| arguments.get(key))
+            .chain(
+                arguments["requests"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .map(|r| &r["domain"]),
+            )
+            .chain(arguments["skills"].as_array().into_iter().flatten());
+        for skill in named.filter_map(Value::as_str) {
+            // Loaded-skill ids name sub-skills as `domain:sub-skill`
+            let domain = skill
+                .split_once(SUB_SKILL_REF_SEPARATOR)
+                .map_or(skill, |(domain, _)| domain);
+            if !self.allows(domain) {
+                return Err(ErrorResponse::new(format!(
+                    "Skill '{}' is not available to this client",
+                    domain
+                ))
+                .with_code(ErrorCode::Forbidden));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A client's own tool use.
+#[derive(Debug, Clone, Serialize)]
+pub struct ClientStats {
+    /// Calls of each tool.
+    pub tool_calls: BTreeMap<String, u64>,
+    /// Calls refused by the client's policy.
+    pub refused_calls: u64,
+    /// When the client first connected.
+    pub first_seen: DateTime<Utc>,
+    /// When the client last called a tool.
+    pub last_seen: DateTime<Utc>,
+}
+
+impl ClientStats {
+    fn new() -> Self {
+        let now = Utc::now();
+        Self {
+            tool_calls: BTreeMap::new(),
+            refused_calls: 0,
+            first_seen: now,
+            last_seen: now,
+        }
+    }
+}
+
+/// One client's state, shared by all of its connections.
+#[derive(Debug)]
+pub struct ClientSession {
+    id: String,
+    policy: ClientPolicy,
+    stats: Mutex<ClientStats>,
+}
+
+impl ClientSession {
+    /// The client's name.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// What the client may see and do.
+    pub fn policy(&self) -> &ClientPolicy {
+        &self.policy
+    }
+
+    /// The client's tool use so far.
+    pub fn stats(&self) -> ClientStats {
+        self.stats.lock().clone()
+    }
+
+    /// Record a call of `tool`, or its refusal.
+    pub(crate) fn record_call(&self, tool: &str, refused: bool) {
+        let mut stats = self.stats.lock();
+        if refused {
+            stats.refused_calls += 1;
+        } else {
+            *stats.tool_calls.entry(tool.to_string()).or_insert(0) += 1;
+        }
+        stats.last_seen = Utc::now();
+    }
+}
+
+/// Sessions of the clients seen so far, by name, and the policies they get.
+#[derive(Debug, Default)]
+pub struct ClientSessions {
+    default_policy: ClientPolicy,
+    policies: HashMap<String, ClientPolicy>,
+    sessions: Mutex<HashMap<String, Arc<ClientSession>>>,
+}
+
+impl ClientSessions {
+    /// No policies: every client sees every skill and may use every tool.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply `policy` to the client named `client`.
+    pub fn set_policy(&mut self, client: impl Into<String>, policy: ClientPolicy) {
+        self.policies.insert(client.into(), policy);
+    }
+
+    /// Apply `policy` to clients without one of their own.
+    pub fn set_default_policy(&mut self, policy: ClientPolicy) {
+        self.default_policy = policy;
+    }
+
+    /// The session of `client`, started on first use.
+    pub fn session(&self, client: &str) -> Arc<ClientSession> {
+        let mut sessions = self.sessions.lock();
+        let session = sessions.entry(client.to_string()).or_insert_with(|| {
+            let policy = self.policies.get(client).unwrap_or(&self.default_policy);
+            Arc::new(ClientSession {
+                id: client.to_string(),
+                policy: policy.clone(),
+                stats: Mutex::new(ClientStats::new()),
+            })
+        });
+        Arc::clone(session)
+    }
+
+    /// The session of `client`, if it has connected.
+    pub fn get(&self, client: &str) -> Option<Arc<ClientSession>> {
+        self.sessions.lock().get(client).cloned()
+    }
+
+    /// Names of the clients seen so far, sorted.
+    pub fn clients(&self) -> Vec<String> {
+        let mut clients: Vec<String> = self.sessions.lock().keys().cloned().collect();
+        clients.sort();
+        clients
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_policy_domains() {
+        let policy = ClientPolicy::new().domain("forms").domain("frontend/");
+        assert!(policy.allows("forms"));
+        assert!(policy.allows("frontend/react"));
+        assert!(!policy.allows("frontend-tools"));
+        assert!(!policy.allows("api"));
+        assert!(ClientPolicy::new().allows("api"));
+
+        assert!(policy
+            .check_call("get_skill", &json!({"name": "forms"}))
+            .is_ok());
+        assert!(policy
+            .check_call("get_skill", &json!({"name": "api"}))
+            .is_err());
+        let batch = json!({"requests": [{"domain": "forms"}, {"domain": "api"}]});
+        assert!(policy.check_call("get_skills_batch", &batch).is_err());
+        let loaded = json!({"skills": ["frontend/react:hooks"]});
+        assert!(policy.check_call("mark_skill_loaded", &loaded).is_ok());
+    }
+
+    #[test]
+    fn test_read_only() {
+        let policy = ClientPolicy::new().read_only(true);
+        let error = policy
+            .check_call("delete_skill", &json!({"name": "forms"}))
+            .unwrap_err();
+        assert!(error.error.contains("read-only"), "{}", error.error);
+        assert!(policy
+            .check_call("get_skill", &json!({"name": "forms"}))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_sessions_by_client() {
+        let mut clients = ClientSessions::new();
+        clients.set_policy("viewer", ClientPolicy::new().read_only(true));
+
+        let viewer = clients.session("viewer");
+        viewer.record_call("get_skill", false);
+        viewer.record_call("delete_skill", true);
+        clients.session("viewer").record_call("get_skill", false);
+
+        let stats = clients.get("viewer").unwrap().stats();
+        assert_eq!(stats.tool_calls.get("get_skill"), Some(&2));
+        assert_eq!(stats.refused_calls, 1);
+        assert!(viewer.policy().read_only);
+        assert!(!clients.session("editor").policy().read_only);
+        assert_eq!(clients.clients(), vec!["editor", "viewer"]);
+    }
+}
@@ -0,0 +1,309 @@
+//! Tool calls by name, with JSON arguments and results as they travel over
+//! MCP.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::llm::LlmBackend;
+use crate::models::{ErrorCode, ErrorResponse};
+
+use super::client::{ClientPolicy, ClientSession};
+use super::tools::*;
+
+/// Names of the tools [`call_tool`] accepts.
+pub const TOOL_NAMES: &[&str] = &[
+    "list_skills",
+    "get_skill",
+    "get_sub_skill",
+    "get_skills_batch",
+    "search_skills",
+    "search_content",
+    "search_batch",
+    "create_skill",
+    "scaffold_skill",
+    "update_skill",
+    "delete_skill",
+    "reload_index",
+    "diff_index",
+    "get_stats",
+    "validate_skills",
+    "list_references",
+    "get_reference",
+    #[cfg(feature = "scripts")]
+    "run_skill_script",
+    "suggest_metadata",
+    "ask_skills",
+    "route_task",
+    "mark_skill_loaded",
+    "get_loaded_skills",
+    "summarize_skill",
+];
+
+/// Run the tool `name` with `arguments` (an object, or null for none) and
+/// return its result as JSON.
+pub fn call_tool(
+    ctx: &ServiceContext,
+    name: &str,
+    arguments: Value,
+) -> Result<Value, ErrorResponse> {
+    dispatch(
+        ctx,
+        &ClientPolicy::default(),
+        name,
+        arguments,
+        &|_, _| {},
+        None,
+    )
+}
+
+/// Run the tool `name` like [`call_tool`], returning the text MCP clients
+/// receive: the result serialized as JSON, or compact text as is.
+///
+/// Results are written straight from the tool's response, without building
+/// a [`Value`] tree first, which matters for large listings and searches.
+pub fn call_tool_text(
+    ctx: &ServiceContext,
+    name: &str,
+    arguments: Value,
+) -> Result<String, ErrorResponse> {
+    dispatch::<ToolText>(
+        ctx,
+        &ClientPolicy::default(),
+        name,
+        arguments,
+        &|_, _| {},
+        None,
+    )
+    .map(|text| text.0)
+}
+
+/// Run the tool `name` for `client` like [`call_tool_text`], within the
+/// client's policy, and count the call in the client's stats.
+///
+/// Long-running tools, such as reload_index, call `progress` with the work
+/// done so far and the total. Tools that write text, such as
+/// summarize_skill, prefer `sampler`, the client's own model, to the
+/// server's LLM backend.
+pub fn call_tool_as(
+    ctx: &ServiceContext,
+    client: &ClientSession,
+    name: &str,
+    arguments: Value,
+    progress: &dyn Fn(usize, usize),
+    sampler: Option<&dyn LlmBackend>,
+) -> Result<String, ErrorResponse> {
+    let policy = client.policy();
+    if let Err(e) = policy.check_call(name, &arguments) {
+        client.record_call(name, true);
+        return Err(e);
+    }
+    client.record_call(name, false);
+    dispatch::<ToolText>(ctx, policy, name, arguments, progress, sampler).map(|text| text.0)
+}
+
+/// How a tool's response is encoded.
+trait Encode: Sized {
+    fn encode(result: impl Serialize) -> Result<Self, ErrorResponse>;
+}
+
+impl Encode for Value {
+    fn encode(result: impl Serialize) -> Result<Self, ErrorResponse> {
+        serde_json::to_value(result).map_err(encode_error)
+    }
+}
+
+/// A response as `tools/call` text.
+struct ToolText(String);
+
+impl Encode for ToolText {
+    fn encode(result: impl Serialize) -> Result<Self, ErrorResponse> {
+        let json = serde_json::to_string(&result).map_err(encode_error)?;
+        // Compact text output serializes as a JSON string; pass it through
+        // unquoted
+        if json.starts_with('"') {
+            return serde_json::from_str(&json)
+                .map(ToolText)
+                .map_err(encode_error);
+        }
+        Ok(ToolText(json))
+    }
+}
+
+fn encode_error(e: serde_json::Error) -> ErrorResponse {
+    ErrorResponse::new(format!("Failed to encode result: {}", e)).with_code(ErrorCode::Internal)
+}
+
+fn dispatch<E: Encode>(
+    ctx: &ServiceContext,
+    policy: &ClientPolicy,
+    name: &str,
+    arguments: Value,
+    progress: &dyn Fn(usize, usize),
+    sampler: Option<&dyn LlmBackend>,
+) -> Result<E, ErrorResponse> {
+    if !ctx.tools.allows(name) {
+        return Err(
+            ErrorResponse::new(format!("Tool {} is not enabled on this server", name))
+                .with_code(ErrorCode::Forbidden),
+        );
+    }
+    let arguments = if arguments.is_null() {
+        Value::Object(Default::default())
+    } else {
+        arguments
+    };
+    // Skills outside a restricted client's domains are left out of
+    // listings and searches
+    let visible = || {
+        ctx.indexer
+            .with_skill_index(|index| policy.visible_skills(index))
+    };
+    match name {
+        "list_skills" => {
+            let mut response = list_skills(ctx);
+            if !policy.domains.is_empty() {
+                response.skills.retain(|s| policy.allows(&s.name));
+                response.total = response.skills.len();
+                for category in &mut response.categories {
+                    category.skills.retain(|s| policy.allows(s));
+                }
+                response.categories.retain(|c| !c.skills.is_empty());
+            }
+            E::encode(response)
+        }
+        "get_skill" => E::encode(get_skill(ctx, parse(name, arguments)?)?),
+        "get_sub_skill" => E::encode(get_sub_skill(ctx, parse(name, arguments)?)?),
+        "get_skills_batch" => E::encode(get_skills_batch(ctx, parse(name, arguments)?)?),
+        "search_skills" => {
+            let mut req: SearchSkillsRequest = parse(name, arguments)?;
+            req.params.domains = visible();
+            E::encode(search_skills(ctx, req)?)
+        }
+        "search_content" => {
+            let mut req: SearchContentRequest = parse(name, arguments)?;
+            req.params.domains = visible();
+            E::encode(search_content(ctx, req)?)
+        }
+        "search_batch" => {
+            let mut req: SearchBatchRequest = parse(name, arguments)?;
+            req.params.domains = visible();
+            E::encode(search_batch(ctx, req)?)
+        }
+        "create_skill" => E::encode(create_skill(ctx, parse(name, arguments)?)?),
+        "scaffold_skill" => E::encode(scaffold_skill(ctx, parse(name, arguments)?)?),
+        "update_skill" => E::encode(update_skill(ctx, parse(name, arguments)?)?),
+        "delete_skill" => E::encode(delete_skill(ctx, parse(name, arguments)?)?),
+        "reload_index" => E::encode(reload_index_with_progress(ctx, progress)),
+        "diff_index" => E::encode(diff_index(ctx)?),
+        "get_stats" => E::encode(get_stats(ctx)),
+        "validate_skills" => E::encode(validate_skills_tool(ctx)),
+        "list_references" => E::encode(list_references(ctx, parse(name, arguments)?)?),
+        "get_reference" => E::encode(get_reference(ctx, parse(name, arguments)?)?),
+        #[cfg(feature = "scripts")]
+        "run_skill_script" => E::encode(run_skill_script(ctx, parse(name, arguments)?)?),
+        "suggest_metadata" => E::encode(suggest_metadata(ctx, parse(name, arguments)?)?),
+        "ask_skills" => {
+            let mut req: AskSkillsRequest = parse(name, arguments)?;
+            req.domains = visible();
+            E::encode(ask_skills(ctx, req)?)
+        }
+        "route_task" => {
+            let mut response = route_task(ctx, parse(name, arguments)?)?;
+            if !policy.domains.is_empty() {
+                // Exploration hints span every skill; leave them out
+                let routing = &mut response.routing;
+                routing.routes.retain(|r| policy.allows(&r.skill));
+                routing.no_confident_match |= routing.routes.is_empty();
+                routing.explore = None;
+            }
+            E::encode(response)
+        }
+        "mark_skill_loaded" => E::encode(mark_skill_loaded(ctx, parse(name, arguments)?)?),
+        "get_loaded_skills" => E::encode(get_loaded_skills(ctx, parse(name, arguments)?)),
+        "summarize_skill" => E::encode(summarize_skill(ctx, parse(name, arguments)?, sampler)?),
+        _ => Err(ErrorResponse::invalid_params(format!(
+            "Unknown tool: {}",
+            name
+        ))),
+    }
+}
+
+fn parse<T: DeserializeOwned>(name: &str, arguments: Value) -> Result<T, ErrorResponse> {
+    serde_json::from_value(arguments).map_err(|e| {
+        ErrorResponse::invalid_params(format!("Invalid arguments for {}: {}", name, e))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::SkillIndexer;
+    use crate::store::SkillFixture;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_call_tool() {
+        let indexer =
+            SkillIndexer::from_memory(vec![SkillFixture::new("forms", "Form handling")]).unwrap();
+        let ctx = ServiceContext::new(Arc::new(indexer));
+
+        let listed = call_tool(&ctx, "list_skills", Value::Null).unwrap();
+        assert_eq!(listed["total"], 1);
+
+        let skill = call_tool(&ctx, "get_skill", json!({"name": "forms"})).unwrap();
+        assert!(skill["content"].as_str().unwrap().contains("Form handling"));
+
+        let error = call_tool(&ctx, "get_skill", json!({})).unwrap_err();
+        assert!(error.error.starts_with("Invalid arguments for get_skill"));
+        assert_eq!(error.error_code, Some(ErrorCode::InvalidParams));
+        let error = call_tool(&ctx, "nope", Value::Null).unwrap_err();
+        assert_eq!(error.error, "Unknown tool: nope");
+        let error = call_tool(&ctx, "get_skill", json!({"name": "nope"})).unwrap_err();
+        assert_eq!(error.error_code, Some(ErrorCode::NotFound));
+
+        // Every listed tool is dispatched, whatever it makes of no arguments
+        for name in TOOL_NAMES {
+            let result = call_tool(&ctx, name, Value::Null);
+            assert!(
+                !result.is_err_and(|e| e.error.starts_with("Unknown tool")),
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_call_tool_text_matches_call_tool() {
+        let indexer = SkillIndexer::from_memory(vec![
+            SkillFixture::new("forms", "Form handling").with_tags(["frontend"])
+        ])
+        .unwrap();
+        let ctx = ServiceContext::new(Arc::new(indexer));
+
+        for (name, arguments) in [
+            ("list_skills", Value::Null),
+            ("get_skill", json!({"name": "forms"})),
+        ] {
+            let text = call_tool_text(&ctx, name, arguments.clone()).unwrap();
+            let value = call_tool(&ctx, name, arguments).unwrap();
+            assert_eq!(
+                serde_json::from_str::<Value>(&text).unwrap(),
+                value,
+                "{}",
+                name
+            );
+        }
+
+        // Compact text is passed through unquoted
+        let text = call_tool_text(
+            &ctx,
+            "search_skills",
+            json!({"query": "form", "format": "llm"}),
+        )
+        .unwrap();
+        assert!(!text.starts_with('"'));
+        assert!(text.contains("forms"));
+    }
+}
@@ -0,0 +1,581 @@
+//! Streamable HTTP transport.
+//!
+//! MCP is served at a single endpoint, [`MCP_PATH`]. Clients POST JSON-RPC
+//! messages and get the responses back as JSON, and may hold a GET open as
+//! an event stream to receive resource and progress notifications. Requests
+//! to the client, such as `sampling/createMessage`, go out on the stream
+//! too, and the client POSTs its responses.
+//! `initialize` starts a session whose id comes back in the
+//! [`SESSION_HEADER`] header; every later request carries it, and DELETE
+//! ends the session. Each session has its own [`Connection`] state, so many
+//! clients can share one server. Sessions idle for longer than
+//! [`HttpOptions::session_idle_timeout`] end on their own, and past
+//! [`HttpOptions::max_sessions`] a new session ends the one idle longest.
+//!
+//! Requests from browsers must come from an allowed origin, so a web page
+//! can't reach a local server through DNS rebinding.
+
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use axum::body::{Body, Bytes};
+use axum::extract::State;
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::Router;
+use parking_lot::Mutex;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tokio::sync::mpsc;
+use tokio_util::io::ReaderStream;
+use tracing::{debug, warn};
+
+use crate::index::ChangeSubscription;
+use crate::models::ManifestDiff;
+
+use super::protocol::{error_codes, handle_message, Connection};
+use super::resources::change_notifications;
+use super::tools::ServiceContext;
+
+/// Path of the MCP endpoint.
+pub const MCP_PATH: &str = "/mcp";
+
+/// Header carrying the session id.
+pub const SESSION_HEADER: &str = "mcp-session-id";
+
+/// Buffer between a session's notifications and its event stream.
+const EVENT_BUFFER_BYTES: usize = 16 * 1024;
+
+/// Session limits and allowed origins of the HTTP transport.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpOptions {
+    /// Sessions without a request for this long are ended.
+    pub session_idle_timeout: Duration,
+    /// Most sessions open at once.
+    pub max_sessions: usize,
+    /// Origins, such as `https://app.example.com`, allowed besides
+    /// localhost. Requests without an `Origin` header aren't from a
+    /// browser and are always allowed.
+    pub allowed_origins: Vec<String>,
+}
+
+impl HttpOptions {
+    /// Default idle time before a session ends (30 minutes).
+    pub const DEFAULT_SESSION_IDLE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+    /// Default most sessions open at once.
+    pub const DEFAULT_MAX_SESSIONS: usize = 1000;
+
+    /// Whether a request with `Origin: origin` may be served.
+    fn allows_origin(&self, origin: &str) -> bool {
+        let host = origin
+            .split_once("://")
+            .map_or(origin, |(_, rest)| rest)
+            .trim_end_matches('/');
+        let host = match host.rsplit_once(':') {
+            Some((host, port)) if port.bytes().all(|b| b.is_ascii_digit()) => host,
+            _ => host,
+        };
+        matches!(host, "localhost" | "127.0.0.1" | "[::1]")
+            || self
+                .allowed_origins
+                .iter()
+                .any(|allowed| allowed.trim_end_matches('/') == origin)
+    }
+}
+
+impl Default for HttpOptions {
+    fn default() -> Self {
+        Self {
+            session_idle_timeout: Self::DEFAULT_SESSION_IDLE_TIMEOUT,
+            max_sessions: Self::DEFAULT_MAX_SESSIONS,
+            allowed_origins: Vec::new(),
+        }
+    }
+}
+
+/// A session's connection state, whose notifications go to its open event
+/// stream if the client holds one, and when it was last used.
+struct Session {
+    connection: Arc<Connection>,
+    last_seen: Instant,
+}
+
+type Sessions = Arc<Mutex<HashMap<String, Session>>>;
+
+#[derive(Clone)]
+struct HttpState {
+    ctx: Arc<ServiceContext>,
+    options: Arc<HttpOptions>,
+    sessions: Sessions,
+    /// Keeps the index change listener registered while the router lives.
+    _changes: Arc<ChangeSubscription>,
+}
+
+/// Router serving MCP over streamable HTTP at [`MCP_PATH`].
+///
+/// Index changes are sent to every session with an open event stream
+/// until the router is dropped.
+pub fn router(ctx: Arc<ServiceContext>, options: HttpOptions) -> Router {
+    let sessions: Sessions = Arc::default();
+
+    let listening = Arc::clone(&sessions);
+    let changes = ctx.indexer.on_change(Arc::new(move |diff: &ManifestDiff| {
+        for session in listening.lock().values() {
+            let connection = &session.connection;
+            for notification in change_notifications(diff, &connection.subscriptions) {
+                connection.notify(notification);
+            }
+        }
+    }));
+
+    Router::new()
+        .route(
+            MCP_PATH,
+            post(post_messages)
+                .get(open_event_stream)
+                .delete(end_session),
+        )
+        .with_state(HttpState {
+            ctx,
+            options: Arc::new(options),
+            sessions,
+            _changes: Arc::new(changes),
+        })
+}
+
+/// Refuse requests from browser origins that aren't allowed.
+fn check_origin(state: &HttpState, headers: &HeaderMap) -> Result<(), RequestError> {
+    match headers.get(header::ORIGIN).map(|v| v.to_str()) {
+        None => Ok(()),
+        Some(Ok(origin)) if state.options.allows_origin(origin) => Ok(()),
+        Some(_) => Err(RequestError(StatusCode::FORBIDDEN, "Origin not allowed")),
+    }
+}
+
+/// Start a session, first ending idle ones and, when at the limit, the
+/// one idle longest.
+fn open_session(state: &HttpState) -> (String, Arc<Connection>) {
+    let id = new_session_id();
+    let connection = Arc::new(Connection::default());
+
+    let mut sessions = state.sessions.lock();
+    let timeout = state.options.session_idle_timeout;
+    sessions.retain(|_, session| session.last_seen.elapsed() < timeout);
+    if sessions.len() >= state.options.max_sessions {
+        let idlest = sessions
+            .iter()
+            .min_by_key(|(_, session)| session.last_seen)
+            .map(|(id, _)| id.clone());
+        if let Some(idlest) = idlest {
+            sessions.remove(&idlest);
+            debug!("Ended MCP session {} to make room", idlest);
+        }
+    }
+    sessions.insert(
+        id.clone(),
+        Session {
+            connection: Arc::clone(&connection),
+            last_seen: Instant::now(),
+        },
+    );
+    debug!("Opened MCP session {}", id);
+    (id, connection)
+}
+
+/// POST: handle one message or a batch, returning the responses as JSON.
+async fn post_messages(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    if let Err(e) = check_origin(&state, &headers) {
+        return e.into_response();
+    }
+    let message: Value = match serde_json::from_slice(&body) {
+        Ok(message) => message,
+        Err(e) => {
+            return rpc_error(
+                StatusCode::BAD_REQUEST,
+                error_codes::PARSE_ERROR,
+                &format!("Parse error: {}", e),
+            )
+        }
+    };
+    let batch = message.is_array();
+    let messages = match message {
+        Value::Array(messages) => messages,
+        message => vec![message],
+    };
+
+    // initialize opens a session; everything else must name one
+    let initializing = messages.iter().any(|m| m["method"] == "initialize");
+    let (session_id, session) = if initializing {
+        open_session(&state)
+    } else {
+        match find_session(&state, &headers) {
+            Ok(found) => found,
+            Err(e) => return e.into_response(),
+        }
+    };
+
+    let ctx = Arc::clone(&state.ctx);
+    let handled = tokio::task::spawn_blocking(move || {
+        messages
+            .iter()
+            .filter_map(|m| handle_message(&ctx, &session, &m.to_string()))
+            .collect::<Vec<Value>>()
+    })
+    .await;
+    let mut responses = match handled {
+        Ok(responses) => responses,
+        Err(e) => {
+            return rpc_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error_codes::INTERNAL_ERROR,
+                &e.to_string(),
+            )
+        }
+    };
+
+    // Notifications and responses alone get no body
+    if responses.is_empty() {
+        return StatusCode::ACCEPTED.into_response();
+    }
+    let body = if batch {
+        Value::Array(responses)
+    } else {
+        responses.remove(0)
+    };
+
+    (
+        [
+            (header::CONTENT_TYPE, "application/json".to_string()),
+            (header::HeaderName::from_static(SESSION_HEADER), session_id),
+        ],
+        body.to_string(),
+    )
+        .into_response()
+}
+
+/// GET: stream the session's notifications as server-sent events.
+///
+/// A session has one stream; opening another closes the previous one.
+async fn open_event_stream(State(state): State<HttpState>, headers: HeaderMap) -> Response {
+    if let Err(e) = check_origin(&state, &headers) {
+        return e.into_response();
+    }
+    let (_, session) = match find_session(&state, &headers) {
+        Ok(found) => found,
+        Err(e) => return e.into_response(),
+    };
+
+    let (sender, mut events) = mpsc::unbounded_channel::<Value>();
+    session.set_notifications(sender);
+
+    let (mut writer, reader) = tokio::io::duplex(EVENT_BUFFER_BYTES);
+    tokio::spawn(async move {
+        use tokio::io::AsyncWriteExt;
+
+        while let Some(event) = events.recv().await {
+            let frame = format!("event: message\ndata: {}\n\n", event);
+            // The client went away
+            if writer.write_all(frame.as_bytes()).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "text/event-stream")
+        .header(header::CACHE_CONTROL, "no-cache")
+        .body(Body::from_stream(ReaderStream::new(reader)))
+        .unwrap_or_else(|e| {
+            warn!("Failed to open event stream: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })
+}
+
+/// DELETE: end the session and close its event stream.
+async fn end_session(State(state): State<HttpState>, headers: HeaderMap) -> Response {
+    if let Err(e) = check_origin(&state, &headers) {
+        return e.into_response();
+    }
+    let (id, _) = match find_session(&state, &headers) {
+        Ok(found) => found,
+        Err(e) => return e.into_response(),
+    };
+    state.sessions.lock().remove(&id);
+    debug!("Closed MCP session {}", id);
+    StatusCode::NO_CONTENT.into_response()
+}
+
+/// The session named by the request's [`SESSION_HEADER`].
+///
+/// A missing header is a bad request; an unknown or expired id is not
+/// found, which tells the client to initialize again. Finding a session
+/// counts as using it.
+fn find_session(
+    state: &HttpState,
+    headers: &HeaderMap,
+) -> Result<(String, Arc<Connection>), RequestError> {
+    let Some(id) = headers.get(SESSION_HEADER).and_then(|v| v.to_str().ok()) else {
+        return Err(RequestError(
+            StatusCode::BAD_REQUEST,
+            "Missing Mcp-Session-Id header",
+        ));
+    };
+    let mut sessions = state.sessions.lock();
+    let timeout = state.options.session_idle_timeout;
+    if sessions
+        .get(id)
+        .is_some_and(|session| session.last_seen.elapsed() >= timeout)
+    {
+        sessions.remove(id);
+        debug!("MCP session {} expired", id);
+    }
+    match sessions.get_mut(id) {
+        Some(session) => {
+            session.last_seen = Instant::now();
+            Ok((id.to_string(), Arc::clone(&session.connection)))
+        }
+        None => Err(RequestError(
+            StatusCode::NOT_FOUND,
+            "Unknown session; initialize again",
+        )),
+    }
+}
+
+/// An invalid request refused before it reaches a session.
+struct RequestError(StatusCode, &'static str);
+
+impl IntoResponse for RequestError {
+    fn into_response(self) -> Response {
+        rpc_error(self.0, error_codes::INVALID_REQUEST, self.1)
+    }
+}
+
+fn rpc_error(status: StatusCode, code: i64, message: &str) -> Response {
+    let body = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": null,
+        "error": {"code": code, "message": message},
+    });
+    (status, axum::Json(body)).into_response()
+}
+
+/// A hard-to-guess session id: a hash of the time, a counter and a
+/// per-process random seed.
+fn new_session_id() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let mut seed = std::collections::hash_map::RandomState::new().build_hasher();
+    seed.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since| since.as_nanos())
+        .unwrap_or_default();
+
+    let mut hasher = Sha256::new();
+    hasher.update(seed.finish().to_le_bytes());
+    hasher.update(nanos.to_le_bytes());
+    hasher.finalize()[..16]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::SkillIndexer;
+    use crate::store::SkillFixture;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    fn app_with(options: HttpOptions) -> Router {
+        let indexer =
+            SkillIndexer::from_memory(vec![SkillFixture::new("forms", "Form handling")]).unwrap();
+        router(Arc::new(ServiceContext::new(Arc::new(indexer))), options)
+    }
+
+    fn app() -> Router {
+        app_with(HttpOptions::default())
+    }
+
+    fn post_json(session: Option<&str>, body: Value) -> Request<Body> {
+        let mut request = Request::builder()
+            .method("POST")
+            .uri(MCP_PATH)
+            .header(header::CONTENT_TYPE, "application/json")
+            .header(header::ACCEPT, "application/json, text/event-stream");
+        if let Some(session) = session {
+            request = request.header(SESSION_HEADER, session);
+        }
+        request.body(Body::from(body.to_string())).unwrap()
+    }
+
+    async fn json_body(response: Response) -> Value {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_session_lifecycle() {
+        let app = app();
+
+        let initialize = serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "initialize",
+                                            "params": {"protocolVersion": "2025-03-26"}});
+        let response = app
+            .clone()
+            .oneshot(post_json(None, initialize))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let session = response.headers()[SESSION_HEADER]
+            .to_str()
+            .unwrap()
+            .to_string();
+        assert_eq!(session.len(), 32);
+        assert_eq!(
+            json_body(response).await["result"]["protocolVersion"],
+            "2025-03-26"
+        );
+
+        let initialized =
+            serde_json::json!({"jsonrpc": "2.0", "method": "notifications/initialized"});
+        let response = app
+            .clone()
+            .oneshot(post_json(Some(&session), initialized))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+        let batch = serde_json::json!([
+            {"jsonrpc": "2.0", "id": 2, "method": "tools/list"},
+            {"jsonrpc": "2.0", "id": 3, "method": "tools/call",
+             "params": {"name": "get_skill", "arguments": {"name": "forms"}}},
+        ]);
+        let response = app
+            .clone()
+            .oneshot(post_json(Some(&session), batch))
+            .await
+            .unwrap();
+        let responses = json_body(response).await;
+        assert_eq!(responses[0]["id"], 2);
+        assert_eq!(responses[1]["result"]["isError"], false);
+
+        let end = Request::builder()
+            .method("DELETE")
+            .uri(MCP_PATH)
+            .header(SESSION_HEADER, &session)
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(end).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        let ping = serde_json::json!({"jsonrpc": "2.0", "id": 4, "method": "ping"});
+        let response = app
+            .clone()
+            .oneshot(post_json(Some(&session), ping.clone()))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let response = app.oneshot(post_json(None, ping)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    async fn initialize(app: &Router) -> String {
+        let initialize = serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "initialize"});
+        let response = app
+            .clone()
+            .oneshot(post_json(None, initialize))
+            .await
+            .unwrap();
+        response.headers()[SESSION_HEADER]
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    async fn ping(app: &Router, session: &str) -> StatusCode {
+        let ping = serde_json::json!({"jsonrpc": "2.0", "id": 2, "method": "ping"});
+        let response = app
+            .clone()
+            .oneshot(post_json(Some(session), ping))
+            .await
+            .unwrap();
+        response.status()
+    }
+
+    #[tokio::test]
+    async fn test_session_limits() {
+        let app = app_with(HttpOptions {
+            max_sessions: 2,
+            ..HttpOptions::default()
+        });
+        let first = initialize(&app).await;
+        let second = initialize(&app).await;
+        assert_eq!(ping(&app, &second).await, StatusCode::OK);
+        assert_eq!(ping(&app, &first).await, StatusCode::OK);
+
+        // The third ends the one idle longest
+        let third = initialize(&app).await;
+        assert_eq!(ping(&app, &second).await, StatusCode::NOT_FOUND);
+        assert_eq!(ping(&app, &first).await, StatusCode::OK);
+        assert_eq!(ping(&app, &third).await, StatusCode::OK);
+
+        let app = app_with(HttpOptions {
+            session_idle_timeout: Duration::from_millis(10),
+            ..HttpOptions::default()
+        });
+        let session = initialize(&app).await;
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(ping(&app, &session).await, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_origin_check() {
+        let app = app_with(HttpOptions {
+            allowed_origins: vec!["https://app.example.com".to_string()],
+            ..HttpOptions::default()
+        });
+        let initialize = serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "initialize"});
+        let from = |origin: &str| {
+            let mut request = post_json(None, initialize.clone());
+            request
+                .headers_mut()
+                .insert(header::ORIGIN, origin.parse().unwrap());
+            request
+        };
+
+        for origin in [
+            "http://localhost:5173",
+            "http://127.0.0.1",
+            "https://app.example.com",
+        ] {
+            let response = app.clone().oneshot(from(origin)).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK, "{}", origin);
+        }
+        for origin in [
+            "http://evil.example",
+            "http://localhost.evil.example",
+            "null",
+        ] {
+            let response = app.clone().oneshot(from(origin)).await.unwrap();
+            assert_eq!(response.status(), StatusCode::FORBIDDEN, "{}", origin);
+        }
+    }
+
+    #[test]
+    fn test_session_ids_differ() {
+        assert_ne!(new_session_id(), new_session_id());
+    }
+}
@@ -0,0 +1,235 @@
+//! Per-session JSONL traces of skill loads and searches.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+/// Longest accepted session id.
+const MAX_SESSION_ID_LENGTH: usize = 128;
+
+/// Number of top result ids kept per search event.
+const TRACE_TOP_RESULTS: usize = 5;
+
+/// Check that a session id is safe to use as a file name.
+pub fn is_valid_session_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_SESSION_ID_LENGTH
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+        && !id.starts_with('.')
+}
+
+/// What happened in a trace event.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum TraceKind {
+    /// A skill, sub-skill or reference was loaded.
+    SkillLoad {
+        /// Skill id, e.g. `forms` or `forms:react`.
+        skill: String,
+    },
+    /// A search ran.
+    Search {
+        /// Tool that ran the search.
+        tool: String,
+        /// Query string.
+        query: String,
+        /// Total matches before the limit.
+        total_matches: usize,
+        /// Ids of the top results, best first.
+        results: Vec<String>,
+    },
+}
+
+/// One line of a session trace.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceEvent {
+    /// When the event was recorded.
+    pub timestamp: DateTime<Utc>,
+    /// Session the event belongs to.
+    pub session_id: String,
+    /// The event itself.
+    #[serde(flatten)]
+    pub kind: TraceKind,
+}
+
+impl TraceEvent {
+    /// Create a skill load event.
+    pub fn skill_load(session_id: &str, skill: impl Into<String>) -> Self {
+        Self::new(
+            session_id,
+            TraceKind::SkillLoad {
+                skill: skill.into(),
+            },
+        )
+    }
+
+    /// Create a search event from the ids of the ranked results.
+    pub fn search(
+        session_id: &str,
+        tool: &str,
+        query: &str,
+        total_matches: usize,
+        results: impl IntoIterator<Item = String>,
+    ) -> Self {
+        Self::new(
+            session_id,
+            TraceKind::Search {
+                tool: tool.to_string(),
+                query: query.to_string(),
+                total_matches,
+                results: results.into_iter().take(TRACE_TOP_RESULTS).collect(),
+            },
+        )
+    }
+
+    fn new(session_id: &str, kind: TraceKind) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            session_id: session_id.to_string(),
+            kind,
+        }
+    }
+}
+
+/// Writes each session's events to `<dir>/<session_id>.jsonl`.
+#[derive(Debug)]
+pub struct Journal {
+    dir: PathBuf,
+    write_lock: Mutex<()>,
+}
+
+impl Journal {
+    /// Open a journal directory, creating it if needed.
+    pub fn open(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+
+        Ok(Self {
+            dir,
+            write_lock: Mutex::new(()),
+        })
+    }
+
+    /// Journal directory.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path_for(&self, session_id: &str) -> PathBuf {
+        self.dir.join(format!("{}.jsonl", session_id))
+    }
+
+    /// Append an event. Failures are logged, never returned, so journaling
+    /// cannot break the tool call being traced.
+    pub fn record(&self, event: &TraceEvent) {
+        if !is_valid_session_id(&event.session_id) {
+            warn!("Not journaling invalid session id {:?}", event.session_id);
+            return;
+        }
+
+        let result = serde_json::to_string(event)
+            .map_err(io::Error::other)
+            .and_then(|line| {
+                let _guard = self.write_lock.lock();
+                let mut file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(self.path_for(&event.session_id))?;
+                writeln!(file, "{}", line)
+            });
+
+        if let Err(e) = result {
+            warn!("Failed to journal event for {}: {}", event.session_id, e);
+        }
+    }
+
+    /// Read a session's trace, oldest first.
+    ///
+    /// Returns `Ok(None)` if the session has no trace. Unparseable lines,
+    /// such as one cut short by a crash, are skipped.
+    pub fn trace(&self, session_id: &str) -> io::Result<Option<Vec<TraceEvent>>> {
+        if !is_valid_session_id(session_id) {
+            return Ok(None);
+        }
+
+        let file = match File::open(self.path_for(session_id)) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        let mut events = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            match serde_json::from_str(&line) {
+                Ok(event) => events.push(event),
+                Err(e) => warn!("Skipping bad trace line for {}: {}", session_id, e),
+            }
+        }
+
+        Ok(Some(events))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_session_id_validation() {
+        assert!(is_valid_session_id("abc-123_x.y"));
+        assert!(!is_valid_session_id(""));
+        assert!(!is_valid_session_id("../etc"));
+        assert!(!is_valid_session_id(".hidden"));
+        assert!(!is_valid_session_id("a/b"));
+        assert!(!is_valid_session_id(&"a".repeat(129)));
+    }
+
+    #[test]
+    fn test_record_and_read_trace() {
+        let temp_dir = TempDir::new().unwrap();
+        let journal = Journal::open(temp_dir.path().join("traces")).unwrap();
+
+        journal.record(&TraceEvent::search(
+            "s1",
+            "search_skills",
+            "forms",
+            12,
+            (0..10).map(|i| format!("skill-{}", i)),
+        ));
+        journal.record(&TraceEvent::skill_load("s1", "forms"));
+        journal.record(&TraceEvent::skill_load("s2", "api"));
+        journal.record(&TraceEvent::skill_load("../bad", "api"));
+
+        let trace = journal.trace("s1").unwrap().unwrap();
+        assert_eq!(trace.len(), 2);
+        match &trace[0].kind {
+            TraceKind::Search {
+                results,
+                total_matches,
+                ..
+            } => {
+                assert_eq!(*total_matches, 12);
+                assert_eq!(results.len(), TRACE_TOP_RESULTS);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+        assert_eq!(
+            trace[1].kind,
+            TraceKind::SkillLoad {
+                skill: "forms".to_string()
+            }
+        );
+
+        assert!(journal.trace("missing").unwrap().is_none());
+        assert!(journal.trace("../bad").unwrap().is_none());
+    }
+}
@@ -1,21 +1,85 @@
 //! MCP Server implementation.
 //!
-//! This module will contain the MCP protocol handlers and tool registration
-//! once the Rust MCP SDK is integrated.
+//! [`McpServer`] speaks JSON-RPC 2.0 over stdio, or over streamable HTTP
+//! with [`McpServer::run_http`], answering `initialize`, `tools/list` and
+//! `tools/call`; [`call_tool`] routes tool calls by name.
+//! Each skill is also a resource at `skill://<name>`, and clients are
+//! notified when the skills directory changes. Clients of the TypeScript
+//! server can ask for its camelCase field names with
+//! [`CAMEL_CASE_CAPABILITY`]. Each client, known by the name it gives at
+//! `initialize`, has a [`ClientSession`] with its own call counts and a
+//! [`ClientPolicy`] limiting the skills it sees and whether it may edit them.
+//! A [`ToolAllowlist`] limits the tools every client sees.
 //!
-//! Tools to implement:
+//! Tools:
 //! - list_skills: Enumerate available skill domains
 //! - get_skill: Load main SKILL.md content
 //! - get_sub_skill: Retrieve specific sub-skill content
 //! - get_skills_batch: Fetch multiple skills in one call
 //! - search_skills: Query by metadata (names, tags, triggers)
 //! - search_content: Full-text markdown search with snippets
+//! - search_batch: Run several searches in one call
+//! - create_skill: Create a skill from a built-in or `_templates/` template
+//! - scaffold_skill: Create a skill with stub sub-skills from an outline
+//! - update_skill: Edit a skill's description, tags or SKILL.md content
+//! - delete_skill: Delete a skill and its files
 //! - reload_index: Refresh skill index from disk
+//! - diff_index: Report how the index differs from disk without reloading
 //! - get_stats: Return usage statistics
 //! - validate_skills: Check skill structure and metadata
+//! - list_references: List documents in a skill's references directory
+//! - get_reference: Read a reference document
+//! - run_skill_script: Run a bundled script in a sandbox (`scripts` feature,
+//!   off unless configured)
+//! - suggest_metadata: Draft descriptions and tags for review (needs an LLM
+//!   backend)
+//! - ask_skills: Answer a question from retrieved passages, with citations
+//!   when an LLM backend is configured
+//! - route_task: Map a user's task to the skill and sub-skill to load, with
+//!   a confidence and reason, or exploration hints when none is confident
+//! - mark_skill_loaded: Record skills a session has injected into context
+//! - get_loaded_skills: List the skills a session has loaded
+//! - summarize_skill: Summarize a skill with the client's model through MCP
+//!   sampling, or the LLM backend, caching the summary in the index
 
-pub mod tools;
+mod allowlist;
+mod ask;
+mod catalog;
+mod client;
+mod dispatch;
+mod http;
+mod journal;
+mod protocol;
+mod resources;
+mod sampling;
+#[cfg(feature = "scripts")]
+mod sandbox;
 mod server;
+mod session;
+mod suggest;
+pub mod tools;
 
-pub use server::McpServer;
+pub use allowlist::ToolAllowlist;
+pub use ask::{Answer, Citation};
+pub use catalog::{tool_definitions, ToolDefinition};
+pub use client::{
+    ClientPolicy, ClientSession, ClientSessions, ClientStats, ANONYMOUS_CLIENT, MUTATING_TOOLS,
+};
+pub use dispatch::{call_tool, call_tool_as, call_tool_text, TOOL_NAMES};
+pub use http::{router as http_router, HttpOptions, MCP_PATH, SESSION_HEADER};
+pub use journal::{is_valid_session_id, Journal, TraceEvent, TraceKind};
+pub use protocol::{
+    error_codes, handle_message, Connection, RpcError, CAMEL_CASE_CAPABILITY, PROTOCOL_VERSION,
+    SERVER_NAME, SUPPORTED_PROTOCOL_VERSIONS,
+};
+pub use resources::{
+    change_notifications, list_resources, read_resource, skill_from_uri, skill_uri, Resource,
+    Subscriptions, SKILL_URI_PREFIX,
+};
+pub use sampling::{SamplingBackend, SAMPLING_TIMEOUT};
+#[cfg(feature = "scripts")]
+pub use sandbox::{RunStatus, SandboxError, ScriptRun, ScriptSandbox};
+pub use server::{McpError, McpServer};
+pub use session::{SessionStore, DEFAULT_SESSION};
+pub use suggest::{weak_metadata_reasons, MetadataProposal};
 pub use tools::*;
@@ -0,0 +1,800 @@
+//! JSON-RPC 2.0 messages of the MCP protocol.
+//!
+//! Each message is handled on its own: [`handle_message`] takes one line as
+//! received and returns the line to send back, if any. Tool failures are
+//! reported inside a successful `tools/call` result with `isError` set, as
+//! MCP expects, so the model can read them, with their [`ErrorCode`] in
+//! `structuredContent`; JSON-RPC errors are kept for malformed requests,
+//! including calls to unknown tools or with invalid arguments. The only
+//! state kept between messages is the [`Connection`]: its resource
+//! subscriptions, the client, field case and capabilities it named at
+//! `initialize`, and requests sent to the client, such as
+//! `sampling/createMessage`, awaiting its response.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
+
+use parking_lot::Mutex;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::debug;
+
+use crate::llm::LlmBackend;
+use crate::models::{ErrorCode, FieldCase};
+
+use super::catalog::tool_definitions;
+use super::client::{ClientSession, ANONYMOUS_CLIENT};
+use super::dispatch::call_tool_as;
+use super::resources::{list_resources, read_resource, Subscriptions};
+use super::sampling::SamplingBackend;
+use super::tools::ServiceContext;
+
+/// Protocol version offered when the client asks for one we don't know.
+pub const PROTOCOL_VERSION: &str = "2024-11-05";
+
+/// Protocol versions this server speaks, oldest first.
+pub const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2024-11-05", "2025-03-26", "2025-06-18"];
+
+/// Name the server reports to clients.
+pub const SERVER_NAME: &str = "skills-mcp";
+
+/// Experimental capability a client sets to `true` at `initialize` to get
+/// tool results with the TypeScript server's camelCase field names.
+pub const CAMEL_CASE_CAPABILITY: &str = "camelCase";
+
+/// JSON-RPC error codes.
+pub mod error_codes {
+    /// The message was not valid JSON.
+    pub const PARSE_ERROR: i64 = -32700;
+    /// The message was not a valid request.
+    pub const INVALID_REQUEST: i64 = -32600;
+    /// The method does not exist.
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+    /// The method's parameters were invalid.
+    pub const INVALID_PARAMS: i64 = -32602;
+    /// The server failed while handling a valid request.
+    pub const INTERNAL_ERROR: i64 = -32603;
+    /// The requested resource does not exist.
+    pub const RESOURCE_NOT_FOUND: i64 = -32002;
+}
+
+/// An incoming request or notification.
+#[derive(Debug, Deserialize)]
+struct Request {
+    jsonrpc: String,
+    /// Absent for notifications, which get no response.
+    #[serde(default)]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+/// A JSON-RPC error object.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RpcError {
+    /// One of [`error_codes`].
+    pub code: i64,
+    /// What went wrong.
+    pub message: String,
+}
+
+impl RpcError {
+    pub(crate) fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+/// State kept for one client connection between messages.
+#[derive(Debug, Default)]
+pub struct Connection {
+    /// Resource URIs the client has subscribed to.
+    pub subscriptions: Subscriptions,
+    /// Whether the client asked for [`CAMEL_CASE_CAPABILITY`].
+    camel_case: AtomicBool,
+    /// Session of the client named at `initialize`.
+    client: Mutex<Option<Arc<ClientSession>>>,
+    /// Where notifications sent while a request is handled go, if the
+    /// transport can deliver them.
+    notifications: Mutex<Option<UnboundedSender<Value>>>,
+    /// Whether the client offered `sampling` at `initialize`.
+    sampling: AtomicBool,
+    /// Requests sent to the client awaiting its response, by id.
+    pending: Mutex<HashMap<String, mpsc::Sender<Result<Value, RpcError>>>>,
+    /// Id of the next request sent to the client.
+    next_request: AtomicU64,
+}
+
+impl Connection {
+    /// Session of the client on this connection; [`ANONYMOUS_CLIENT`]'s
+    /// until `initialize` names one.
+    pub fn client(&self, ctx: &ServiceContext) -> Arc<ClientSession> {
+        let mut client = self.client.lock();
+        Arc::clone(client.get_or_insert_with(|| ctx.clients.session(ANONYMOUS_CLIENT)))
+    }
+
+    /// Field case of tool results on this connection.
+    pub fn field_case(&self) -> FieldCase {
+        if self.camel_case.load(Ordering::Relaxed) {
+            FieldCase::Camel
+        } else {
+            FieldCase::Snake
+        }
+    }
+
+    /// Deliver notifications sent while requests are handled, such as
+    /// `notifications/progress`, to `sender`. Without one they are dropped.
+    pub fn set_notifications(&self, sender: UnboundedSender<Value>) {
+        *self.notifications.lock() = Some(sender);
+    }
+
+    /// Send `notification` to the client, if the transport can deliver it.
+    pub fn notify(&self, notification: Value) {
+        if let Some(sender) = self.notifications.lock().as_ref() {
+            let _ = sender.send(notification);
+        }
+    }
+
+    /// Whether the client can sample its model for the server, with
+    /// `sampling/createMessage`.
+    pub fn can_sample(&self) -> bool {
+        self.sampling.load(Ordering::Relaxed)
+    }
+
+    /// Send a request to the client and wait up to `timeout` for its
+    /// result.
+    ///
+    /// The request goes out with the notifications, and blocks the calling
+    /// thread until the client's response reaches [`handle_message`] or
+    /// [`Self::take_response`] on another.
+    pub fn request(
+        &self,
+        method: &str,
+        params: Value,
+        timeout: Duration,
+    ) -> Result<Value, RpcError> {
+        let sender = self.notifications.lock().clone().ok_or_else(|| {
+            RpcError::new(
+                error_codes::INTERNAL_ERROR,
+                "The transport can't send requests to the client",
+            )
+        })?;
+
+        let id = self.next_request.fetch_add(1, Ordering::Relaxed);
+        let (waiting, response) = mpsc::channel();
+        self.pending.lock().insert(id.to_string(), waiting);
+        debug!("Sending {} to the client as request {}", method, id);
+        let request = json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params});
+        if sender.send(request).is_err() {
+            self.pending.lock().remove(&id.to_string());
+            return Err(RpcError::new(
+                error_codes::INTERNAL_ERROR,
+                "The client disconnected",
+            ));
+        }
+
+        response.recv_timeout(timeout).unwrap_or_else(|_| {
+            self.pending.lock().remove(&id.to_string());
+            Err(RpcError::new(
+                error_codes::INTERNAL_ERROR,
+                format!(
+                    "The client didn't answer {} within {}s",
+                    method,
+                    timeout.as_secs()
+                ),
+            ))
+        })
+    }
+
+    /// Hand `message` to the request it answers, if it is the client's
+    /// response to one sent with [`Self::request`].
+    pub fn take_response(&self, message: &Value) -> bool {
+        if message.get("method").is_some() {
+            return false;
+        }
+        let Some(id) = message.get("id") else {
+            return false;
+        };
+        let Some(waiting) = self.pending.lock().remove(&id.to_string()) else {
+            return false;
+        };
+
+        let outcome = match message.get("error") {
+            Some(error) => Err(RpcError::new(
+                error["code"]
+                    .as_i64()
+                    .unwrap_or(error_codes::INTERNAL_ERROR),
+                error["message"]
+                    .as_str()
+                    .unwrap_or("The client returned an error"),
+            )),
+            None => Ok(message.get("result").cloned().unwrap_or(Value::Null)),
+        };
+        let _ = waiting.send(outcome);
+        true
+    }
+}
+
+/// Parameters of `tools/call`.
+#[derive(Debug, Deserialize)]
+struct CallToolParams {
+    name: String,
+    #[serde(default)]
+    arguments: Value,
+    /// Request metadata, such as the `progressToken` to report progress
+    /// against.
+    #[serde(default, rename = "_meta")]
+    meta: Value,
+}
+
+/// Parameters of `resources/read`, `resources/subscribe` and
+/// `resources/unsubscribe`.
+#[derive(Debug, Deserialize)]
+struct ResourceParams {
+    uri: String,
+}
+
+/// Handle one message and return the response to send, if any.
+///
+/// Notifications, such as `notifications/initialized`, get no response,
+/// and nor do the client's responses to requests sent to it.
+pub fn handle_message(
+    ctx: &ServiceContext,
+    connection: &Connection,
+    message: &str,
+) -> Option<Value> {
+    let value: Value = match serde_json::from_str(message) {
+        Ok(value) => value,
+        Err(e) => {
+            let error = RpcError::new(error_codes::PARSE_ERROR, format!("Parse error: {}", e));
+            return Some(error_response(Value::Null, error));
+        }
+    };
+
+    if value.get("method").is_none()
+        && (value.get("result").is_some() || value.get("error").is_some())
+    {
+        if !connection.take_response(&value) {
+            debug!("Dropped response to unknown request {}", value["id"]);
+        }
+        return None;
+    }
+
+    let id = value.get("id").cloned();
+    let request: Request = match serde_json::from_value(value) {
+        Ok(request) => request,
+        Err(e) => {
+            let error = RpcError::new(
+                error_codes::INVALID_REQUEST,
+                format!("Invalid request: {}", e),
+            );
+            return Some(error_response(id.unwrap_or(Value::Null), error));
+        }
+    };
+    if request.jsonrpc != "2.0" {
+        let error = RpcError::new(error_codes::INVALID_REQUEST, "jsonrpc must be \"2.0\"");
+        return Some(error_response(id.unwrap_or(Value::Null), error));
+    }
+
+    let Some(id) = request.id else {
+        debug!("Notification {}", request.method);
+        return None;
+    };
+
+    Some(
+        match handle_request(ctx, connection, &request.method, request.params) {
+            Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+            Err(error) => error_response(id, error),
+        },
+    )
+}
+
+fn handle_request(
+    ctx: &ServiceContext,
+    connection: &Connection,
+    method: &str,
+    params: Value,
+) -> Result<Value, RpcError> {
+    let subscriptions = &connection.subscriptions;
+    match method {
+        "initialize" => Ok(initialize(ctx, connection, &params)),
+        "ping" => Ok(json!({})),
+        "tools/list" => {
+            let mut tools = tool_definitions();
+            tools.retain(|tool| ctx.tools.allows(tool.name));
+            Ok(json!({"tools": tools}))
+        }
+        "tools/call" => {
+            let params: CallToolParams = parse_params(params)?;
+            let progress_token = params.meta.get("progressToken").cloned();
+            call(
+                ctx,
+                connection,
+                &params.name,
+                params.arguments,
+                progress_token,
+            )
+        }
+        "resources/list" => {
+            let client = connection.client(ctx);
+            Ok(json!({"resources": list_resources(ctx, client.policy())}))
+        }
+        "resources/read" => {
+            let params: ResourceParams = parse_params(params)?;
+            read_resource(ctx, &connection.client(ctx), &params.uri)
+        }
+        "resources/subscribe" => {
+            let params: ResourceParams = parse_params(params)?;
+            subscriptions.subscribe(&params.uri);
+            Ok(json!({}))
+        }
+        "resources/unsubscribe" => {
+            let params: ResourceParams = parse_params(params)?;
+            subscriptions.unsubscribe(&params.uri);
+            Ok(json!({}))
+        }
+        _ => Err(RpcError::new(
+            error_codes::METHOD_NOT_FOUND,
+            format!("Method not found: {}", method),
+        )),
+    }
+}
+
+fn parse_params<T: serde::de::DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params).map_err(|e| {
+        RpcError::new(
+            error_codes::INVALID_PARAMS,
+            format!("Invalid params: {}", e),
+        )
+    })
+}
+
+/// Agree on a protocol version and field case, start the client's
+/// session, and describe the server.
+fn initialize(ctx: &ServiceContext, connection: &Connection, params: &Value) -> Value {
+    let requested = params.get("protocolVersion").and_then(Value::as_str);
+    let version = requested
+        .filter(|v| SUPPORTED_PROTOCOL_VERSIONS.contains(v))
+        .unwrap_or(PROTOCOL_VERSION);
+    let camel_case = params["capabilities"]["experimental"][CAMEL_CASE_CAPABILITY] == true;
+    connection.camel_case.store(camel_case, Ordering::Relaxed);
+    let sampling = params["capabilities"].get("sampling").is_some();
+    connection.sampling.store(sampling, Ordering::Relaxed);
+    let client = params["clientInfo"]["name"]
+        .as_str()
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or(ANONYMOUS_CLIENT);
+    *connection.client.lock() = Some(ctx.clients.session(client));
+
+    json!({
+        "protocolVersion": version,
+        "capabilities": {
+            "tools": {"listChanged": false},
+            "resources": {"subscribe": true, "listChanged": true},
+            "experimental": {CAMEL_CASE_CAPABILITY: {}},
+        },
+        "serverInfo": {"name": SERVER_NAME, "version": crate::VERSION},
+    })
+}
+
+/// Run a tool for the connection's client and wrap its outcome as a
+/// `tools/call` result, with JSON output in the connection's field case.
+///
+/// When the request gave a progress token, long-running tools report
+/// their progress against it as `notifications/progress`. Tools that
+/// write text sample the client's model when it offered `sampling`.
+///
+/// Unknown tools and invalid arguments are `INVALID_PARAMS` errors; other
+/// failures are results with `isError` set.
+fn call(
+    ctx: &ServiceContext,
+    connection: &Connection,
+    name: &str,
+    arguments: Value,
+    progress_token: Option<Value>,
+) -> Result<Value, RpcError> {
+    let client = connection.client(ctx);
+    let case = connection.field_case();
+    let progress = |done: usize, total: usize| {
+        if let Some(token) = &progress_token {
+            connection.notify(progress_notification(token, done, total));
+        }
+    };
+    let sampler = connection
+        .can_sample()
+        .then(|| SamplingBackend::new(connection));
+    let sampler = sampler.as_ref().map(|s| s as &dyn LlmBackend);
+    let mut text = match call_tool_as(ctx, &client, name, arguments, &progress, sampler) {
+        Ok(text) => text,
+        Err(e) if e.error_code == Some(ErrorCode::InvalidParams) => {
+            return Err(RpcError::new(error_codes::INVALID_PARAMS, e.error));
+        }
+        Err(e) => {
+            let mut result = json!({
+                "content": [{"type": "text", "text": &e.error}],
+                "isError": true,
+            });
+            if e.error_code.is_some() {
+                result["structuredContent"] = json!(e);
+            }
+            return Ok(result);
+        }
+    };
+    if case != FieldCase::Snake {
+        if let Ok(mut value) = serde_json::from_str::<Value>(&text) {
+            case.apply(&mut value);
+            text = value.to_string();
+        }
+    }
+
+    Ok(json!({
+        "content": [{"type": "text", "text": text}],
+        "isError": false,
+    }))
+}
+
+/// A `notifications/progress` message: `done` of `total` against `token`.
+pub fn progress_notification(token: &Value, done: usize, total: usize) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/progress",
+        "params": {"progressToken": token, "progress": done, "total": total},
+    })
+}
+
+fn error_response(id: Value, error: RpcError) -> Value {
+    json!({"jsonrpc": "2.0", "id": id, "error": error})
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::SkillIndexer;
+    use crate::mcp::ClientPolicy;
+    use crate::store::SkillFixture;
+    use std::sync::Arc;
+
+    fn ctx() -> ServiceContext {
+        let indexer =
+            SkillIndexer::from_memory(vec![SkillFixture::new("forms", "Form handling")]).unwrap();
+        ServiceContext::new(Arc::new(indexer))
+    }
+
+    fn send(ctx: &ServiceContext, message: Value) -> Value {
+        handle_message(ctx, &Connection::default(), &message.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_initialize_and_list_tools() {
+        let ctx = ctx();
+
+        let response = send(
+            &ctx,
+            json!({"jsonrpc": "2.0", "id": 1, "method": "initialize",
+                   "params": {"protocolVersion": "2025-03-26", "capabilities": {}}}),
+        );
+        assert_eq!(response["id"], 1);
+        assert_eq!(response["result"]["protocolVersion"], "2025-03-26");
+        assert_eq!(response["result"]["serverInfo"]["name"], SERVER_NAME);
+
+        let response = send(
+            &ctx,
+            json!({"jsonrpc": "2.0", "id": 2, "method": "initialize",
+                   "params": {"protocolVersion": "1999-01-01"}}),
+        );
+        assert_eq!(response["result"]["protocolVersion"], PROTOCOL_VERSION);
+
+        let initialized = json!({"jsonrpc": "2.0", "method": "notifications/initialized"});
+        let connection = Connection::default();
+        assert!(handle_message(&ctx, &connection, &initialized.to_string()).is_none());
+
+        let response = send(
+            &ctx,
+            json!({"jsonrpc": "2.0", "id": "t", "method": "tools/list"}),
+        );
+        let tools = response["result"]["tools"].as_array().unwrap();
+        assert!(tools.iter().any(|t| t["name"] == "search_skills"));
+        assert!(tools.iter().all(|t| t["inputSchema"]["type"] == "object"));
+    }
+
+    #[test]
+    fn test_tools_call() {
+        let ctx = ctx();
+
+        let response = send(
+            &ctx,
+            json!({"jsonrpc": "2.0", "id": 3, "method": "tools/call",
+                   "params": {"name": "get_skill", "arguments": {"name": "forms"}}}),
+        );
+        let result = &response["result"];
+        assert_eq!(result["isError"], false);
+        let skill: Value =
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert!(skill["content"].as_str().unwrap().contains("Form handling"));
+
+        // Tool failures are results the model can read
+        let response = send(
+            &ctx,
+            json!({"jsonrpc": "2.0", "id": 4, "method": "tools/call",
+                   "params": {"name": "get_skill", "arguments": {"name": "nope"}}}),
+        );
+        assert_eq!(response["result"]["isError"], true);
+        assert_eq!(
+            response["result"]["structuredContent"]["error_code"],
+            "not_found"
+        );
+
+        // Bad calls are protocol errors
+        let response = send(
+            &ctx,
+            json!({"jsonrpc": "2.0", "id": 5, "method": "tools/call",
+                   "params": {"name": "get_skill", "arguments": {"name": 7}}}),
+        );
+        assert_eq!(response["error"]["code"], error_codes::INVALID_PARAMS);
+        let response = send(
+            &ctx,
+            json!({"jsonrpc": "2.0", "id": 6, "method": "tools/call",
+                   "params": {"name": "no_such_tool"}}),
+        );
+        assert_eq!(response["error"]["code"], error_codes::INVALID_PARAMS);
+    }
+
+    #[test]
+    fn test_camel_case_results() {
+        let ctx = ctx();
+        let connection = Connection::default();
+        let send =
+            |message: Value| handle_message(&ctx, &connection, &message.to_string()).unwrap();
+        let get_skill = json!({"jsonrpc": "2.0", "id": 2, "method": "tools/call",
+                               "params": {"name": "get_skill", "arguments": {"name": "forms"}}});
+        let skill = |response: Value| -> Value {
+            serde_json::from_str(response["result"]["content"][0]["text"].as_str().unwrap())
+                .unwrap()
+        };
+
+        let skill_json = skill(send(get_skill.clone()));
+        assert!(skill_json.get("has_references").is_some());
+
+        let capabilities = json!({"experimental": {CAMEL_CASE_CAPABILITY: true}});
+        let response = send(json!({"jsonrpc": "2.0", "id": 1, "method": "initialize",
+                                   "params": {"capabilities": capabilities}}));
+        assert!(response["result"]["capabilities"]["experimental"]
+            .get(CAMEL_CASE_CAPABILITY)
+            .is_some());
+        assert_eq!(connection.field_case(), FieldCase::Camel);
+
+        let skill_json = skill(send(get_skill));
+        assert_eq!(skill_json["hasReferences"], false);
+        assert!(skill_json.get("subSkills").is_some());
+        assert!(skill_json.get("has_references").is_none());
+    }
+
+    #[test]
+    fn test_reload_progress() {
+        let ctx = ctx();
+        let connection = Connection::default();
+        let reload = |meta: Value| {
+            let message = json!({"jsonrpc": "2.0", "id": 1, "method": "tools/call",
+                                 "params": {"name": "reload_index", "_meta": meta}});
+            handle_message(&ctx, &connection, &message.to_string()).unwrap()
+        };
+
+        // Without a transport to deliver them, notifications are dropped
+        reload(json!({"progressToken": "reload-1"}));
+
+        let (sender, mut notifications) = tokio::sync::mpsc::unbounded_channel();
+        connection.set_notifications(sender);
+        let response = reload(json!({}));
+        assert_eq!(response["result"]["isError"], false);
+        assert!(notifications.try_recv().is_err());
+
+        reload(json!({"progressToken": "reload-2"}));
+        let progress = notifications.try_recv().unwrap();
+        assert_eq!(progress["method"], "notifications/progress");
+        assert_eq!(progress["params"]["progressToken"], "reload-2");
+        assert_eq!(progress["params"]["progress"], 1);
+        assert_eq!(progress["params"]["total"], 1);
+        assert!(notifications.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_tool_allowlist() {
+        let allowlist = "list_skills,search_*".parse().unwrap();
+        let ctx = ctx().with_tool_allowlist(allowlist);
+
+        let response = send(
+            &ctx,
+            json!({"jsonrpc": "2.0", "id": 1, "method": "tools/list"}),
+        );
+        let tools: Vec<&str> = response["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|tool| tool["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            tools,
+            vec![
+                "list_skills",
+                "search_skills",
+                "search_content",
+                "search_batch"
+            ]
+        );
+
+        let response = send(
+            &ctx,
+            json!({"jsonrpc": "2.0", "id": 2, "method": "tools/call",
+                   "params": {"name": "reload_index"}}),
+        );
+        assert_eq!(response["result"]["isError"], true);
+        let text = response["result"]["content"][0]["text"].as_str().unwrap();
+        assert!(text.contains("not enabled"), "{}", text);
+    }
+
+    #[test]
+    fn test_client_sessions() {
+        let indexer = SkillIndexer::from_memory(vec![
+            SkillFixture::new("forms", "Form handling"),
+            SkillFixture::new("api", "API design"),
+        ])
+        .unwrap();
+        let ctx = ServiceContext::new(Arc::new(indexer)).with_client_policy(
+            "viewer",
+            ClientPolicy::new().domain("forms").read_only(true),
+        );
+        let connection = Connection::default();
+        let call = |tool: &str, arguments: Value| {
+            let message = json!({"jsonrpc": "2.0", "id": 2, "method": "tools/call",
+                                 "params": {"name": tool, "arguments": arguments}});
+            let response = handle_message(&ctx, &connection, &message.to_string()).unwrap();
+            response["result"].clone()
+        };
+
+        let initialize = json!({"jsonrpc": "2.0", "id": 1, "method": "initialize",
+                                "params": {"clientInfo": {"name": "viewer", "version": "1.0"}}});
+        handle_message(&ctx, &connection, &initialize.to_string()).unwrap();
+
+        let listed = call("list_skills", json!({}));
+        let listed: Value =
+            serde_json::from_str(listed["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(listed["total"], 1);
+        assert_eq!(listed["skills"][0]["name"], "forms");
+
+        assert_eq!(call("get_skill", json!({"name": "api"}))["isError"], true);
+        assert_eq!(
+            call("delete_skill", json!({"name": "forms"}))["isError"],
+            true
+        );
+        assert_eq!(
+            call("get_skill", json!({"name": "forms"}))["isError"],
+            false
+        );
+
+        let stats = ctx.clients.get("viewer").unwrap().stats();
+        assert_eq!(stats.tool_calls.get("list_skills"), Some(&1));
+        assert_eq!(stats.tool_calls.get("get_skill"), Some(&1));
+        assert_eq!(stats.refused_calls, 2);
+
+        // Other clients see everything
+        let other = Connection::default();
+        let message = json!({"jsonrpc": "2.0", "id": 3, "method": "tools/call",
+                             "params": {"name": "get_skill", "arguments": {"name": "api"}}});
+        let response = handle_message(&ctx, &other, &message.to_string()).unwrap();
+        assert_eq!(response["result"]["isError"], false);
+        assert_eq!(ctx.clients.clients(), vec![ANONYMOUS_CLIENT, "viewer"]);
+    }
+
+    #[test]
+    fn test_resources() {
+        let ctx = ctx();
+        let connection = Connection::default();
+        let subscriptions = &connection.subscriptions;
+        let send =
+            |message: Value| handle_message(&ctx, &connection, &message.to_string()).unwrap();
+
+        let response = send(json!({"jsonrpc": "2.0", "id": 1, "method": "resources/list"}));
+        let resources = &response["result"]["resources"];
+        assert_eq!(resources[0]["uri"], "skill://forms");
+        assert_eq!(resources[0]["mimeType"], "text/markdown");
+        let hash = resources[0]["contentHash"].as_str().unwrap().to_string();
+
+        let response = send(
+            json!({"jsonrpc": "2.0", "id": 2, "method": "resources/read",
+                                   "params": {"uri": "skill://forms"}}),
+        );
+        let text = response["result"]["contents"][0]["text"].as_str().unwrap();
+        assert!(text.contains("Form handling"));
+        assert_eq!(response["result"]["contents"][0]["contentHash"], hash);
+
+        let response = send(
+            json!({"jsonrpc": "2.0", "id": 3, "method": "resources/read",
+                                   "params": {"uri": "skill://nope"}}),
+        );
+        assert_eq!(response["error"]["code"], error_codes::RESOURCE_NOT_FOUND);
+
+        send(
+            json!({"jsonrpc": "2.0", "id": 4, "method": "resources/subscribe",
+                    "params": {"uri": "skill://forms"}}),
+        );
+        assert!(subscriptions.contains("skill://forms"));
+        send(
+            json!({"jsonrpc": "2.0", "id": 5, "method": "resources/unsubscribe",
+                    "params": {"uri": "skill://forms"}}),
+        );
+        assert!(!subscriptions.contains("skill://forms"));
+    }
+
+    #[test]
+    fn test_resources_for_client() {
+        let indexer = SkillIndexer::from_memory(vec![
+            SkillFixture::new("forms", "Form handling"),
+            SkillFixture::new("api", "API design"),
+        ])
+        .unwrap();
+        let ctx = ServiceContext::new(Arc::new(indexer))
+            .with_client_policy("viewer", ClientPolicy::new().domain("forms"));
+        let connection = Connection::default();
+        let send =
+            |message: Value| handle_message(&ctx, &connection, &message.to_string()).unwrap();
+
+        let initialize = json!({"jsonrpc": "2.0", "id": 1, "method": "initialize",
+                                "params": {"clientInfo": {"name": "viewer", "version": "1.0"}}});
+        send(initialize);
+
+        let response = send(json!({"jsonrpc": "2.0", "id": 2, "method": "resources/list"}));
+        let resources = response["result"]["resources"].as_array().unwrap();
+        assert_eq!(resources.len(), 1);
+        assert_eq!(resources[0]["uri"], "skill://forms");
+
+        let response = send(
+            json!({"jsonrpc": "2.0", "id": 3, "method": "resources/read",
+                   "params": {"uri": "skill://api"}}),
+        );
+        assert_eq!(response["error"]["code"], error_codes::RESOURCE_NOT_FOUND);
+        let response = send(
+            json!({"jsonrpc": "2.0", "id": 4, "method": "resources/read",
+                   "params": {"uri": "skill://forms"}}),
+        );
+        assert!(response["result"]["contents"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("Form handling"));
+
+        let loads = ctx.stats.read().loads_by_skill();
+        assert_eq!(loads.get("forms"), Some(&1));
+        assert_eq!(loads.get("api"), None);
+    }
+
+    #[test]
+    fn test_protocol_errors() {
+        let ctx = ctx();
+
+        let response = handle_message(&ctx, &Connection::default(), "{not json").unwrap();
+        assert_eq!(response["error"]["code"], error_codes::PARSE_ERROR);
+        assert_eq!(response["id"], Value::Null);
+
+        let response = send(
+            &ctx,
+            json!({"jsonrpc": "2.0", "id": 5, "method": "prompts/list"}),
+        );
+        assert_eq!(response["error"]["code"], error_codes::METHOD_NOT_FOUND);
+        assert_eq!(response["id"], 5);
+
+        let response = send(
+            &ctx,
+            json!({"jsonrpc": "2.0", "id": 6, "method": "tools/call", "params": {}}),
+        );
+        assert_eq!(response["error"]["code"], error_codes::INVALID_PARAMS);
+
+        let response = send(&ctx, json!({"jsonrpc": "1.0", "id": 7, "method": "ping"}));
+        assert_eq!(response["error"]["code"], error_codes::INVALID_REQUEST);
+    }
+}
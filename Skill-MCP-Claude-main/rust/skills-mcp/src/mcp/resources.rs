@@ -0,0 +1,216 @@
+//! Skills exposed as MCP resources.
+//!
+//! Each skill's main document is a resource at `skill://<name>`. Clients
+//! see only the skills their [`ClientPolicy`] allows. They may subscribe to
+//! resources; when the index changes, [`change_notifications`] tells them
+//! the list changed or which subscribed skills were updated.
+
+use std::collections::HashSet;
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::models::ManifestDiff;
+
+use super::client::{ClientPolicy, ClientSession};
+use super::protocol::{error_codes, RpcError};
+use super::tools::ServiceContext;
+
+/// Scheme of skill resource URIs.
+pub const SKILL_URI_PREFIX: &str = "skill://";
+
+/// MIME type of skill documents.
+const MARKDOWN: &str = "text/markdown";
+
+/// A resource as advertised by `resources/list`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Resource {
+    /// `skill://<name>`.
+    pub uri: String,
+    /// Skill name.
+    pub name: String,
+    /// Skill description.
+    pub description: String,
+    /// Always `text/markdown`.
+    #[serde(rename = "mimeType")]
+    pub mime_type: &'static str,
+    /// Hash of SKILL.md as last indexed, matching `contentHash` in
+    /// `resources/read`.
+    #[serde(rename = "contentHash", skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+}
+
+/// URI of a skill's resource.
+pub fn skill_uri(name: &str) -> String {
+    format!("{}{}", SKILL_URI_PREFIX, name)
+}
+
+/// Skill a resource URI points at.
+pub fn skill_from_uri(uri: &str) -> Option<&str> {
+    uri.strip_prefix(SKILL_URI_PREFIX)
+        .filter(|name| !name.is_empty())
+}
+
+/// Resource URIs a client has subscribed to on one connection.
+#[derive(Debug, Default)]
+pub struct Subscriptions {
+    uris: Mutex<HashSet<String>>,
+}
+
+impl Subscriptions {
+    /// Start sending updates for `uri`.
+    pub fn subscribe(&self, uri: &str) {
+        self.uris.lock().insert(uri.to_string());
+    }
+
+    /// Stop sending updates for `uri`.
+    pub fn unsubscribe(&self, uri: &str) {
+        self.uris.lock().remove(uri);
+    }
+
+    /// Whether updates for `uri` are wanted.
+    pub fn contains(&self, uri: &str) -> bool {
+        self.uris.lock().contains(uri)
+    }
+}
+
+/// Every skill `policy` allows, as a resource.
+pub fn list_resources(ctx: &ServiceContext, policy: &ClientPolicy) -> Vec<Resource> {
+    ctx.indexer.with_indexes(|index, content| {
+        index
+            .skills
+            .iter()
+            .filter(|skill| policy.allows(&skill.name))
+            .map(|skill| Resource {
+                uri: skill_uri(&skill.name),
+                name: skill.name.clone(),
+                description: skill.description.clone(),
+                mime_type: MARKDOWN,
+                content_hash: content.get(&skill.name).map(|e| e.hash.clone()),
+            })
+            .collect()
+    })
+}
+
+/// Contents of the skill resource at `uri`, as a `resources/read` result
+/// for `client`.
+///
+/// Skills hidden from the client are reported as not found, like skills
+/// that don't exist.
+pub fn read_resource(
+    ctx: &ServiceContext,
+    client: &ClientSession,
+    uri: &str,
+) -> Result<Value, RpcError> {
+    let name = skill_from_uri(uri).ok_or_else(|| {
+        RpcError::new(
+            error_codes::INVALID_PARAMS,
+            format!("Unknown resource URI: {}", uri),
+        )
+    })?;
+    let name = ctx
+        .indexer
+        .resolve_alias(name)
+        .unwrap_or_else(|| name.to_string());
+    if !client.policy().allows(&name) || !ctx.indexer.skill_exists(&name) {
+        return Err(RpcError::new(
+            error_codes::RESOURCE_NOT_FOUND,
+            format!("Resource not found: {}", uri),
+        ));
+    }
+
+    let content = ctx
+        .indexer
+        .read_skill_content(&name)
+        .map_err(|e| RpcError::new(error_codes::INTERNAL_ERROR, e.to_string()))?;
+    ctx.track_skill_load(Some(client.id()), &name);
+
+    Ok(json!({
+        "contents": [{
+            "uri": uri,
+            "mimeType": MARKDOWN,
+            "text": content.content,
+            "contentHash": content.content_hash,
+        }],
+    }))
+}
+
+/// Notifications telling a client about an index change: the resource
+/// list changed when skills were added or removed, and each subscribed
+/// skill that changed or was removed was updated.
+pub fn change_notifications(changes: &ManifestDiff, subscriptions: &Subscriptions) -> Vec<Value> {
+    let mut notifications = Vec::new();
+
+    if !changes.added.is_empty() || !changes.removed.is_empty() {
+        notifications.push(json!({
+            "jsonrpc": "2.0",
+            "method": "notifications/resources/list_changed",
+        }));
+    }
+
+    for name in changes.changed.iter().chain(&changes.removed) {
+        let uri = skill_uri(name);
+        if subscriptions.contains(&uri) {
+            notifications.push(json!({
+                "jsonrpc": "2.0",
+                "method": "notifications/resources/updated",
+                "params": {"uri": uri},
+            }));
+        }
+    }
+
+    notifications
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skill_uris() {
+        assert_eq!(skill_uri("frontend/forms"), "skill://frontend/forms");
+        assert_eq!(
+            skill_from_uri("skill://frontend/forms"),
+            Some("frontend/forms")
+        );
+        assert_eq!(skill_from_uri("skill://"), None);
+        assert_eq!(skill_from_uri("file:///forms"), None);
+    }
+
+    #[test]
+    fn test_change_notifications() {
+        let subscriptions = Subscriptions::default();
+        subscriptions.subscribe("skill://forms");
+
+        let changes = ManifestDiff {
+            changed: vec!["forms".to_string(), "react".to_string()],
+            ..Default::default()
+        };
+        let notifications = change_notifications(&changes, &subscriptions);
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(
+            notifications[0]["method"],
+            "notifications/resources/updated"
+        );
+        assert_eq!(notifications[0]["params"]["uri"], "skill://forms");
+
+        let changes = ManifestDiff {
+            added: vec!["testing".to_string()],
+            ..Default::default()
+        };
+        let notifications = change_notifications(&changes, &subscriptions);
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(
+            notifications[0]["method"],
+            "notifications/resources/list_changed"
+        );
+
+        subscriptions.unsubscribe("skill://forms");
+        let changes = ManifestDiff {
+            changed: vec!["forms".to_string()],
+            ..Default::default()
+        };
+        assert!(change_notifications(&changes, &subscriptions).is_empty());
+    }
+}
@@ -0,0 +1,136 @@
+//! The client's own model, through MCP sampling.
+//!
+//! A client that offers the `sampling` capability at `initialize` lets the
+//! server ask its model for completions with `sampling/createMessage`.
+//! [`SamplingBackend`] wraps that as an [`LlmBackend`], so tools that
+//! write text can use the model the client is already talking to, subject
+//! to whatever review the client puts in front of sampling requests.
+
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use crate::llm::{CompletionRequest, LlmBackend, LlmError};
+
+use super::protocol::Connection;
+
+/// How long to wait for the client to answer a sampling request; clients
+/// may ask their user to approve it first.
+pub const SAMPLING_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Name reported for the client's model until a reply names it.
+const CLIENT_MODEL: &str = "client";
+
+/// Completes prompts with the model of the client on a [`Connection`].
+pub struct SamplingBackend<'a> {
+    connection: &'a Connection,
+    timeout: Duration,
+    /// Model named in the first reply.
+    model: OnceLock<String>,
+}
+
+impl<'a> SamplingBackend<'a> {
+    /// Sample from the client on `connection`, waiting up to
+    /// [`SAMPLING_TIMEOUT`] for each reply.
+    pub fn new(connection: &'a Connection) -> Self {
+        Self {
+            connection,
+            timeout: SAMPLING_TIMEOUT,
+            model: OnceLock::new(),
+        }
+    }
+
+    /// Set how long to wait for each reply.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+impl LlmBackend for SamplingBackend<'_> {
+    fn name(&self) -> &str {
+        self.model.get().map_or(CLIENT_MODEL, String::as_str)
+    }
+
+    fn complete(&self, request: &CompletionRequest) -> Result<String, LlmError> {
+        let params = json!({
+            "messages": [{
+                "role": "user",
+                "content": {"type": "text", "text": request.prompt},
+            }],
+            "systemPrompt": request.system,
+            "maxTokens": request.max_tokens,
+            "includeContext": "none",
+        });
+        let result = self
+            .connection
+            .request("sampling/createMessage", params, self.timeout)
+            .map_err(|e| LlmError::Request(e.message))?;
+
+        if let Some(model) = result.get("model").and_then(Value::as_str) {
+            let _ = self.model.set(model.to_string());
+        }
+        match &result["content"] {
+            content if content["type"] == "text" => content["text"]
+                .as_str()
+                .map(str::to_string)
+                .ok_or_else(|| LlmError::InvalidResponse("text content without text".to_string())),
+            content => Err(LlmError::InvalidResponse(format!(
+                "expected text content, got {}",
+                content["type"]
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sampling_backend() {
+        let connection = Connection::default();
+        let request = CompletionRequest::new("Be brief.", "Summarize forms.");
+
+        // Without a transport there is no way to reach the client
+        let backend = SamplingBackend::new(&connection);
+        assert!(matches!(
+            backend.complete(&request),
+            Err(LlmError::Request(_))
+        ));
+
+        let (sender, mut outgoing) = tokio::sync::mpsc::unbounded_channel();
+        connection.set_notifications(sender);
+        std::thread::scope(|scope| {
+            let client = scope.spawn(|| {
+                let sent = loop {
+                    if let Ok(sent) = outgoing.try_recv() {
+                        break sent;
+                    }
+                    std::thread::yield_now();
+                };
+                assert_eq!(sent["method"], "sampling/createMessage");
+                assert_eq!(sent["params"]["systemPrompt"], "Be brief.");
+                assert_eq!(
+                    sent["params"]["messages"][0]["content"]["text"],
+                    "Summarize forms."
+                );
+                assert!(connection.take_response(&json!({
+                    "jsonrpc": "2.0",
+                    "id": sent["id"],
+                    "result": {"role": "assistant", "model": "client-model",
+                               "content": {"type": "text", "text": "Builds forms."}},
+                })));
+            });
+            assert_eq!(backend.complete(&request).unwrap(), "Builds forms.");
+            client.join().unwrap();
+        });
+        assert_eq!(backend.name(), "client-model");
+
+        // A client that never answers
+        let backend = backend.with_timeout(Duration::from_millis(10));
+        let error = backend.complete(&request).unwrap_err();
+        assert!(error.to_string().contains("didn't answer"), "{}", error);
+    }
+}
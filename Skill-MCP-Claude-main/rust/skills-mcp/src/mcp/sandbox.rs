@@ -0,0 +1,554 @@
+//! Sandboxed execution of scripts bundled with skills.
+//!
+//! Only built with the `scripts` feature, and only reachable when a
+//! [`ScriptSandbox`] is configured on the service context. Each run is a
+//! subprocess with the skill directory as its working directory, a cleared
+//! environment apart from an allowlist, no stdin, capped output and a hard
+//! timeout. Runs are recorded in the Creation Station `runs` table when a
+//! database path is set.
+
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tracing::{debug, warn};
+
+use crate::models::{ScriptLanguage, SkillScript};
+
+/// Runtime label stored with recorded runs.
+const RUNTIME: &str = "skill-script";
+
+/// How often a running script is polled for completion.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// How long to wait for output after the script exits.
+///
+/// Processes the script started in the background can hold its pipes open
+/// indefinitely; whatever was captured by then is returned.
+const OUTPUT_GRACE: Duration = Duration::from_millis(200);
+
+/// Configuration for running skill scripts.
+#[derive(Debug, Clone)]
+pub struct ScriptSandbox {
+    /// Time after which the script is killed.
+    pub timeout: Duration,
+    /// Bytes kept from each of stdout and stderr; the rest is discarded.
+    pub max_output_bytes: usize,
+    /// Environment variables passed through to scripts.
+    pub env_allowlist: Vec<String>,
+    /// Environment the allowlisted variables are taken from; the server's
+    /// own environment when unset.
+    pub parent_env: Option<HashMap<String, OsString>>,
+    /// Creation Station database to record runs in.
+    pub runs_db: Option<PathBuf>,
+}
+
+impl ScriptSandbox {
+    /// Default time limit per run.
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+    /// Default output cap per stream (64 KiB).
+    pub const DEFAULT_MAX_OUTPUT_BYTES: usize = 64 * 1024;
+
+    /// Environment variables passed through by default.
+    pub const DEFAULT_ENV_ALLOWLIST: &'static [&'static str] = &["PATH", "LANG", "LC_ALL", "TZ"];
+
+    /// Most arguments accepted per run.
+    pub const MAX_ARGS: usize = 32;
+
+    /// Set the time limit per run.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set the output cap per stream.
+    pub fn with_max_output_bytes(mut self, bytes: usize) -> Self {
+        self.max_output_bytes = bytes;
+        self
+    }
+
+    /// Set the environment variables passed through to scripts.
+    pub fn with_env_allowlist(mut self, vars: Vec<String>) -> Self {
+        self.env_allowlist = vars;
+        self
+    }
+
+    /// Take allowlisted variables from `env` instead of the server's own
+    /// environment.
+    pub fn with_parent_env(mut self, env: HashMap<String, OsString>) -> Self {
+        self.parent_env = Some(env);
+        self
+    }
+
+    /// Record runs in a Creation Station database.
+    pub fn with_runs_db(mut self, path: impl Into<PathBuf>) -> Self {
+        self.runs_db = Some(path.into());
+        self
+    }
+
+    /// Run a script with `skill_dir` as its working directory.
+    ///
+    /// `script_path` must already be resolved inside the skill directory.
+    /// A non-zero exit or timeout is a completed run, not an error.
+    pub fn run(
+        &self,
+        skill: &str,
+        skill_dir: &Path,
+        script_path: &Path,
+        script: &SkillScript,
+        args: &[String],
+    ) -> Result<ScriptRun, SandboxError> {
+        let (program, prefix) = interpreter(script.language).ok_or_else(|| {
+            SandboxError::Unsupported(format!(
+                "No interpreter for {} ({:?})",
+                script.path, script.language
+            ))
+        })?;
+        validate_args(args)?;
+
+        let mut command = Command::new(program);
+        command
+            .args(prefix)
+            .arg(script_path)
+            .args(args)
+            .current_dir(skill_dir)
+            .env_clear()
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        // Its own process group, so a timeout also kills what it started
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut command, 0);
+        for key in &self.env_allowlist {
+            let value = match &self.parent_env {
+                Some(env) => env.get(key).cloned(),
+                None => std::env::var_os(key),
+            };
+            if let Some(value) = value {
+                command.env(key, value);
+            }
+        }
+
+        let started_at = Utc::now();
+        let start = Instant::now();
+        let mut child = command
+            .spawn()
+            .map_err(|e| SandboxError::Spawn(format!("Failed to start {}: {}", program, e)))?;
+
+        let cap = self.max_output_bytes;
+        let stdout = child.stdout.take().map(|s| CappedOutput::capture(s, cap));
+        let stderr = child.stderr.take().map(|s| CappedOutput::capture(s, cap));
+
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break Some(status),
+                Ok(None) if start.elapsed() >= self.timeout => {
+                    debug!("Killing {} after {:?}", script.path, self.timeout);
+                    kill(&mut child);
+                    break None;
+                }
+                Ok(None) => thread::sleep(POLL_INTERVAL),
+                Err(e) => return Err(SandboxError::Spawn(e.to_string())),
+            }
+        };
+        let duration = start.elapsed();
+
+        let deadline = Instant::now() + OUTPUT_GRACE;
+        let collect =
+            |output: Option<CappedOutput>| output.map(|o| o.finish(deadline)).unwrap_or_default();
+        let (stdout, stdout_truncated) = collect(stdout);
+        let (stderr, stderr_truncated) = collect(stderr);
+
+        let exit_code = status.and_then(|s| s.code());
+        let run_status = match status {
+            None => RunStatus::TimedOut,
+            Some(s) if s.success() => RunStatus::Succeeded,
+            Some(_) => RunStatus::Failed,
+        };
+
+        let mut run = ScriptRun {
+            skill: skill.to_string(),
+            script: script.path.clone(),
+            status: run_status,
+            exit_code,
+            stdout,
+            stderr,
+            stdout_truncated,
+            stderr_truncated,
+            duration_ms: duration.as_secs_f64() * 1000.0,
+            run_id: None,
+        };
+        run.run_id = self.record(&run, args, started_at);
+
+        Ok(run)
+    }
+
+    /// Record a run, logging rather than failing if the database is unusable.
+    fn record(&self, run: &ScriptRun, args: &[String], started_at: DateTime<Utc>) -> Option<i64> {
+        use creation_station_db::{
+            connect, init_db, insert_run, insert_run_output, NewRun, NewRunOutput,
+        };
+
+        let path = self.runs_db.as_ref()?;
+
+        let record = || -> Result<i64, Box<dyn std::error::Error>> {
+            init_db(path)?;
+            let conn = connect(path)?;
+
+            let mut prompt = run.script.clone();
+            for arg in args {
+                prompt.push(' ');
+                prompt.push_str(arg);
+            }
+
+            let run_id = insert_run(
+                &conn,
+                &NewRun {
+                    runtime: RUNTIME.to_string(),
+                    prompt_text: prompt,
+                    settings_json: Some(
+                        serde_json::json!({
+                            "timeout_ms": self.timeout.as_millis() as u64,
+                            "max_output_bytes": self.max_output_bytes,
+                        })
+                        .to_string(),
+                    ),
+                    selected_skills_json: Some(serde_json::json!([run.skill]).to_string()),
+                    status: run.status.as_str().to_string(),
+                    started_at: Some(started_at),
+                    finished_at: Some(Utc::now()),
+                    latency_ms: Some(run.duration_ms as i64),
+                    error_text: (run.status == RunStatus::TimedOut)
+                        .then(|| format!("Timed out after {:?}", self.timeout)),
+                    ..Default::default()
+                },
+            )?;
+            insert_run_output(
+                &conn,
+                run_id,
+                &NewRunOutput {
+                    stdout_text: Some(run.stdout.clone()),
+                    stderr_text: Some(run.stderr.clone()),
+                    return_code: run.exit_code.map(i64::from),
+                    ..Default::default()
+                },
+            )?;
+            Ok(run_id)
+        };
+
+        record()
+            .map_err(|e| warn!("Failed to record script run in {:?}: {}", path, e))
+            .ok()
+    }
+}
+
+impl Default for ScriptSandbox {
+    fn default() -> Self {
+        Self {
+            timeout: Self::DEFAULT_TIMEOUT,
+            max_output_bytes: Self::DEFAULT_MAX_OUTPUT_BYTES,
+            env_allowlist: Self::DEFAULT_ENV_ALLOWLIST
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            parent_env: None,
+            runs_db: None,
+        }
+    }
+}
+
+/// How a script run ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    /// Exited with status zero.
+    Succeeded,
+    /// Exited with a non-zero status or was killed by a signal.
+    Failed,
+    /// Killed after exceeding the time limit.
+    TimedOut,
+}
+
+impl RunStatus {
+    /// Status string stored in the runs table.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RunStatus::Succeeded => "succeeded",
+            RunStatus::Failed => "failed",
+            RunStatus::TimedOut => "timed_out",
+        }
+    }
+}
+
+/// Outcome of one script run.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScriptRun {
+    /// Skill the script belongs to.
+    pub skill: String,
+    /// Script path relative to the skill directory.
+    pub script: String,
+    /// How the run ended.
+    pub status: RunStatus,
+    /// Exit code, if the process exited normally.
+    pub exit_code: Option<i32>,
+    /// Captured standard output, up to the cap.
+    pub stdout: String,
+    /// Captured standard error, up to the cap.
+    pub stderr: String,
+    /// Whether stdout was cut off at the cap.
+    pub stdout_truncated: bool,
+    /// Whether stderr was cut off at the cap.
+    pub stderr_truncated: bool,
+    /// Wall-clock run time in milliseconds.
+    pub duration_ms: f64,
+    /// Row id in the runs table, when recorded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<i64>,
+}
+
+/// Errors that prevent a script from running.
+#[derive(Debug, thiserror::Error)]
+pub enum SandboxError {
+    /// The script's language has no known interpreter.
+    #[error("Unsupported script: {0}")]
+    Unsupported(String),
+
+    /// An argument was rejected.
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+
+    /// The interpreter could not be started or waited on.
+    #[error("Spawn error: {0}")]
+    Spawn(String),
+}
+
+/// Interpreter and leading arguments for a script language.
+fn interpreter(language: ScriptLanguage) -> Option<(&'static str, &'static [&'static str])> {
+    match language {
+        ScriptLanguage::Python => Some(("python3", &[])),
+        ScriptLanguage::Shell => Some(("sh", &[])),
+        ScriptLanguage::JavaScript => Some(("node", &[])),
+        // Deno denies file, network and env access unless granted
+        ScriptLanguage::TypeScript => Some(("deno", &["run"])),
+        ScriptLanguage::Ruby => Some(("ruby", &[])),
+        ScriptLanguage::PowerShell => Some(("pwsh", &["-NoProfile", "-NonInteractive", "-File"])),
+        ScriptLanguage::Other => None,
+    }
+}
+
+/// Reject arguments that could point a script outside its skill directory.
+fn validate_args(args: &[String]) -> Result<(), SandboxError> {
+    if args.len() > ScriptSandbox::MAX_ARGS {
+        return Err(SandboxError::InvalidArgument(format!(
+            "Too many arguments (max {})",
+            ScriptSandbox::MAX_ARGS
+        )));
+    }
+
+    for arg in args {
+        let path_like = arg.split_once('=').map(|(_, v)| v).unwrap_or(arg);
+        let absolute = path_like.starts_with(['/', '\\', '~'])
+            || (path_like.len() >= 2 && path_like.as_bytes()[1] == b':');
+        if arg.contains('\0') || absolute || path_like.split(['/', '\\']).any(|c| c == "..") {
+            return Err(SandboxError::InvalidArgument(format!(
+                "'{}' may point outside the skill directory",
+                arg
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Kill a script along with anything it started in the background.
+fn kill(child: &mut Child) {
+    // SAFETY: killpg only sends a signal. The child leads its own process
+    // group and hasn't been reaped, so the group id can't have been reused.
+    #[cfg(unix)]
+    unsafe {
+        libc::killpg(child.id() as libc::pid_t, libc::SIGKILL);
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// Output captured from one stream by a background reader thread.
+struct CappedOutput {
+    buffer: Arc<parking_lot::Mutex<(Vec<u8>, bool)>>,
+    done: std::sync::mpsc::Receiver<()>,
+}
+
+impl CappedOutput {
+    /// Read a stream to the end, keeping at most `cap` bytes.
+    ///
+    /// The rest is drained so the child never blocks on a full pipe.
+    fn capture(mut stream: impl Read + Send + 'static, cap: usize) -> Self {
+        let buffer = Arc::new(parking_lot::Mutex::new((Vec::new(), false)));
+        let (tx, done) = std::sync::mpsc::channel();
+
+        let shared = Arc::clone(&buffer);
+        thread::spawn(move || {
+            let mut buf = [0u8; 8192];
+            loop {
+                match stream.read(&mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => {
+                        let mut guard = shared.lock();
+                        let (kept, truncated) = &mut *guard;
+                        let room = cap.saturating_sub(kept.len());
+                        kept.extend_from_slice(&buf[..n.min(room)]);
+                        *truncated |= n > room;
+                    }
+                }
+            }
+            let _ = tx.send(());
+        });
+
+        Self { buffer, done }
+    }
+
+    /// Wait until the stream closes or `deadline` passes, then take the output.
+    fn finish(self, deadline: Instant) -> (String, bool) {
+        let _ = self
+            .done
+            .recv_timeout(deadline.saturating_duration_since(Instant::now()));
+        let (kept, truncated) = std::mem::take(&mut *self.buffer.lock());
+        (String::from_utf8_lossy(&kept).into_owned(), truncated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn shell_script(dir: &Path, name: &str, body: &str) -> (PathBuf, SkillScript) {
+        let path = dir.join("scripts").join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, body).unwrap();
+        let script = SkillScript::from_source(format!("scripts/{}", name), body, body.len() as u64);
+        (path, script)
+    }
+
+    #[test]
+    fn test_run_captures_output_in_skill_dir() {
+        let temp = TempDir::new().unwrap();
+        let (path, script) = shell_script(
+            temp.path(),
+            "hello.sh",
+            "pwd\necho \"$1\" >&2\necho \"$SECRET $TZ\"\nexit 3\n",
+        );
+        let cwd = temp.path().canonicalize().unwrap();
+        let mut env: HashMap<String, OsString> = std::env::vars_os()
+            .filter_map(|(key, value)| Some((key.into_string().ok()?, value)))
+            .collect();
+        env.insert("SECRET".to_string(), "leaked".into());
+        env.insert("TZ".to_string(), "Test/Zone".into());
+
+        let run = ScriptSandbox::default()
+            .with_parent_env(env)
+            .run("demo", &cwd, &path, &script, &["hi".to_string()])
+            .unwrap();
+
+        assert_eq!(run.status, RunStatus::Failed);
+        assert_eq!(run.exit_code, Some(3));
+        assert!(run.stdout.starts_with(cwd.to_str().unwrap()));
+        assert!(!run.stdout.contains("leaked"));
+        assert!(run.stdout.contains("Test/Zone"));
+        assert_eq!(run.stderr, "hi\n");
+        assert!(run.run_id.is_none());
+    }
+
+    #[test]
+    fn test_timeout_and_output_cap() {
+        let temp = TempDir::new().unwrap();
+        let (path, script) = shell_script(temp.path(), "slow.sh", "echo 0123456789\nsleep 5\n");
+        let start = Instant::now();
+
+        let run = ScriptSandbox::default()
+            .with_timeout(Duration::from_millis(200))
+            .with_max_output_bytes(4)
+            .run("demo", temp.path(), &path, &script, &[])
+            .unwrap();
+
+        assert_eq!(run.status, RunStatus::TimedOut);
+        assert_eq!(run.stdout, "0123");
+        assert!(run.stdout_truncated);
+        assert!(run.duration_ms < 5000.0);
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_timeout_kills_background_processes() {
+        let temp = TempDir::new().unwrap();
+        let (path, script) = shell_script(
+            temp.path(),
+            "fork.sh",
+            "sleep 30 &\necho $! > sleep.pid\nwait\n",
+        );
+
+        let run = ScriptSandbox::default()
+            .with_timeout(Duration::from_millis(300))
+            .run("demo", temp.path(), &path, &script, &[])
+            .unwrap();
+        assert_eq!(run.status, RunStatus::TimedOut);
+
+        // Gone, or a zombie waiting for init to reap it
+        let pid = fs::read_to_string(temp.path().join("sleep.pid")).unwrap();
+        let running = || match fs::read_to_string(format!("/proc/{}/stat", pid.trim())) {
+            Ok(stat) => !stat.rsplit(") ").next().unwrap().starts_with('Z'),
+            Err(_) => false,
+        };
+        let start = Instant::now();
+        while running() && start.elapsed() < Duration::from_secs(2) {
+            thread::sleep(POLL_INTERVAL);
+        }
+        assert!(!running());
+    }
+
+    #[test]
+    fn test_rejects_escaping_args_and_unknown_languages() {
+        let temp = TempDir::new().unwrap();
+        let (path, script) = shell_script(temp.path(), "ok.sh", "true\n");
+        let sandbox = ScriptSandbox::default();
+
+        for arg in ["/etc/passwd", "../other", "--out=../x", "~/.ssh"] {
+            let result = sandbox.run("demo", temp.path(), &path, &script, &[arg.to_string()]);
+            assert!(
+                matches!(result, Err(SandboxError::InvalidArgument(_))),
+                "{}",
+                arg
+            );
+        }
+
+        let (path, script) = shell_script(temp.path(), "data.bin", "xx");
+        let result = sandbox.run("demo", temp.path(), &path, &script, &[]);
+        assert!(matches!(result, Err(SandboxError::Unsupported(_))));
+    }
+
+    #[test]
+    fn test_runs_recorded() {
+        let temp = TempDir::new().unwrap();
+        let (path, script) = shell_script(temp.path(), "ok.sh", "echo done\n");
+        let db = temp.path().join("runs.db");
+
+        let run = ScriptSandbox::default()
+            .with_runs_db(&db)
+            .run("demo", temp.path(), &path, &script, &["a".to_string()])
+            .unwrap();
+
+        assert_eq!(run.status, RunStatus::Succeeded);
+        assert!(run.run_id.is_some());
+        assert!(db.is_file());
+    }
+}
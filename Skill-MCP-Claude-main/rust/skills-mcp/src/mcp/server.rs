@@ -1,26 +1,54 @@
 //! MCP Server implementation.
 //!
-//! This is a placeholder that will be fully implemented once the
-//! Rust MCP SDK is available and integrated.
+//! Speaks JSON-RPC 2.0 over stdio, one message per line, as MCP clients
+//! such as Claude Desktop expect. Logs must go to stderr, since stdout
+//! carries the protocol.
+//!
+//! [`McpServer::run_http`] serves the same protocol over streamable HTTP
+//! instead, for clients that connect over the network.
+//!
+//! The skills directory is watched while the server runs, and clients are
+//! notified when skills they can see change on disk.
 
+use std::collections::VecDeque;
+use std::net::SocketAddr;
 use std::sync::Arc;
 
-use tracing::info;
+use serde_json::Value;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader, Lines};
+use tokio::sync::mpsc::UnboundedReceiver;
+use tracing::{info, warn};
 
-use crate::index::SkillIndexer;
-use super::tools::ServiceContext;
+use super::allowlist::ToolAllowlist;
+use super::client::ClientPolicy;
+use super::http::{HttpOptions, MCP_PATH};
+use super::journal::Journal;
+use super::protocol::{handle_message, Connection};
+use super::resources::change_notifications;
+use super::tools::{BatchLimits, ConfidenceThresholds, ServiceContext};
+use crate::index::{FileWatcher, IndexerOptions, SkillIndexer};
+use crate::models::ManifestDiff;
 
 /// MCP Server for the Skills service.
 ///
 /// Handles MCP protocol communication and routes tool calls to handlers.
 pub struct McpServer {
     ctx: ServiceContext,
+    /// Whether [`Self::run`] watches the skills directory.
+    watch: bool,
+    /// Session limits and allowed origins of [`Self::run_http`].
+    http: HttpOptions,
 }
 
 impl McpServer {
     /// Create a new MCP server.
     pub fn new(skills_dir: impl AsRef<std::path::Path>) -> Self {
-        let indexer = Arc::new(SkillIndexer::new(skills_dir));
+        Self::with_options(skills_dir, IndexerOptions::default())
+    }
+
+    /// Create a new MCP server with custom indexing options.
+    pub fn with_options(skills_dir: impl AsRef<std::path::Path>, options: IndexerOptions) -> Self {
+        let indexer = Arc::new(SkillIndexer::with_options(skills_dir, options));
 
         // Initial index load
         if let Err(e) = indexer.reload() {
@@ -29,7 +57,90 @@ impl McpServer {
 
         let ctx = ServiceContext::new(indexer);
 
-        Self { ctx }
+        Self {
+            ctx,
+            watch: true,
+            http: HttpOptions::default(),
+        }
+    }
+
+    /// Don't watch the skills directory; changes on disk are only picked
+    /// up by reload_index.
+    pub fn without_file_watcher(mut self) -> Self {
+        self.watch = false;
+        self
+    }
+
+    /// Set the get_skills_batch limits.
+    pub fn with_batch_limits(mut self, limits: BatchLimits) -> Self {
+        self.ctx = self.ctx.with_batch_limits(limits);
+        self
+    }
+
+    /// Set the confidence thresholds of route_task and the search tools.
+    pub fn with_confidence_thresholds(mut self, thresholds: ConfidenceThresholds) -> Self {
+        self.ctx = self.ctx.with_confidence_thresholds(thresholds);
+        self
+    }
+
+    /// Log and count searches slower than `threshold`.
+    pub fn with_slow_search(mut self, threshold: std::time::Duration) -> Self {
+        self.ctx = self.ctx.with_slow_search(threshold);
+        self
+    }
+
+    /// Rerank the top search results within `budget`.
+    pub fn with_reranker(
+        mut self,
+        reranker: Arc<dyn crate::search::Reranker>,
+        budget: crate::search::RerankBudget,
+    ) -> Self {
+        self.ctx = self.ctx.with_reranker(reranker, budget);
+        self
+    }
+
+    /// Journal each session's skill loads and searches.
+    pub fn with_journal(mut self, journal: Journal) -> Self {
+        self.ctx = self.ctx.with_journal(journal);
+        self
+    }
+
+    /// Enable run_skill_script with the given sandbox.
+    #[cfg(feature = "scripts")]
+    pub fn with_script_sandbox(mut self, sandbox: super::sandbox::ScriptSandbox) -> Self {
+        self.ctx = self.ctx.with_script_sandbox(sandbox);
+        self
+    }
+
+    /// Apply `policy` to the MCP client named `client`, as given in
+    /// `clientInfo.name` at initialize.
+    pub fn with_client_policy(mut self, client: impl Into<String>, policy: ClientPolicy) -> Self {
+        self.ctx = self.ctx.with_client_policy(client, policy);
+        self
+    }
+
+    /// Apply `policy` to MCP clients without one of their own.
+    pub fn with_default_client_policy(mut self, policy: ClientPolicy) -> Self {
+        self.ctx = self.ctx.with_default_client_policy(policy);
+        self
+    }
+
+    /// List and accept only the tools in `allowlist`.
+    pub fn with_tool_allowlist(mut self, allowlist: ToolAllowlist) -> Self {
+        self.ctx = self.ctx.with_tool_allowlist(allowlist);
+        self
+    }
+
+    /// Use a language model for drafting tools.
+    pub fn with_llm(mut self, backend: std::sync::Arc<dyn crate::llm::LlmBackend>) -> Self {
+        self.ctx = self.ctx.with_llm(backend);
+        self
+    }
+
+    /// Set the session limits and allowed origins of the HTTP transport.
+    pub fn with_http_options(mut self, options: HttpOptions) -> Self {
+        self.http = options;
+        self
     }
 
     /// Get the service context.
@@ -37,24 +148,43 @@ impl McpServer {
         &self.ctx
     }
 
-    /// Start the MCP server.
-    ///
-    /// This will be implemented to handle stdio transport and MCP protocol
-    /// once the Rust MCP SDK is integrated.
+    /// Serve MCP over stdin and stdout until stdin closes or Ctrl+C.
     pub async fn run(&self) -> Result<(), McpError> {
-        info!("Starting MCP server...");
+        info!("Starting MCP server on stdio");
+        let _watcher = if self.watch {
+            self.start_watcher()
+        } else {
+            None
+        };
+
+        let stdin = BufReader::new(tokio::io::stdin());
+        tokio::select! {
+            result = self.serve(stdin, tokio::io::stdout()) => result?,
+            signal = tokio::signal::ctrl_c() => {
+                signal.map_err(|e| McpError::Runtime(e.to_string()))?;
+            }
+        }
 
-        // TODO: Implement MCP protocol handling
-        // 1. Set up stdio transport
-        // 2. Register tools with MCP runtime
-        // 3. Handle incoming requests
-        // 4. Route to appropriate tool handlers
+        info!("Shutting down MCP server...");
+        Ok(())
+    }
 
-        // Placeholder - will be replaced with actual MCP runtime
-        info!("MCP server running (placeholder implementation)");
+    /// Serve MCP over streamable HTTP on `addr` until Ctrl+C.
+    pub async fn run_http(self, addr: SocketAddr) -> Result<(), McpError> {
+        let listener = tokio::net::TcpListener::bind(addr)
+            .await
+            .map_err(|e| McpError::Init(format!("Failed to bind {}: {}", addr, e)))?;
+        info!("Starting MCP server on http://{}{}", addr, MCP_PATH);
+        let _watcher = if self.watch {
+            self.start_watcher()
+        } else {
+            None
+        };
 
-        // Keep running until shutdown signal
-        tokio::signal::ctrl_c()
+        axum::serve(listener, self.into_http_router())
+            .with_graceful_shutdown(async {
+                let _ = tokio::signal::ctrl_c().await;
+            })
             .await
             .map_err(|e| McpError::Runtime(e.to_string()))?;
 
@@ -62,21 +192,204 @@ impl McpServer {
         Ok(())
     }
 
+    /// Router serving MCP over streamable HTTP, for mounting in another
+    /// axum app.
+    pub fn into_http_router(self) -> axum::Router {
+        super::http::router(Arc::new(self.ctx), self.http)
+    }
+
+    /// Watch the skills directory, keeping the index current. The watcher
+    /// stops when dropped.
+    fn start_watcher(&self) -> Option<FileWatcher> {
+        let started = FileWatcher::new(Arc::clone(&self.ctx.indexer)).and_then(|mut watcher| {
+            watcher.watch(self.ctx.indexer.skills_dir())?;
+            Ok(watcher)
+        });
+
+        match started {
+            Ok(watcher) => Some(watcher),
+            Err(e) => {
+                warn!("Not watching skills directory: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Answer newline-delimited JSON-RPC messages from `reader` on `writer`
+    /// until `reader` is exhausted.
+    ///
+    /// Messages are handled one at a time, in order. Between them, index
+    /// changes are sent as `notifications/resources/list_changed` and, for
+    /// subscribed skills, `notifications/resources/updated`; notifications
+    /// sent while a message is handled, such as `notifications/progress`,
+    /// are written as they come. So are requests to the client, such as
+    /// `sampling/createMessage`, whose responses are read while the message
+    /// waits on them; anything else the client sends meanwhile waits its
+    /// turn. Tool handlers block, so this must run on a multi-threaded
+    /// runtime.
+    pub async fn serve<R, W>(&self, reader: R, mut writer: W) -> Result<(), McpError>
+    where
+        R: AsyncBufRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let connection = Connection::default();
+        let (notifications_tx, mut notifications) = tokio::sync::mpsc::unbounded_channel();
+        connection.set_notifications(notifications_tx);
+
+        // The listener is removed when this connection ends
+        let (changes_tx, mut changes) = tokio::sync::mpsc::unbounded_channel();
+        let _subscription = self
+            .ctx
+            .indexer
+            .on_change(Arc::new(move |diff: &ManifestDiff| {
+                let _ = changes_tx.send(diff.clone());
+            }));
+
+        let mut incoming = Incoming {
+            lines: reader.lines(),
+            deferred: VecDeque::new(),
+        };
+        loop {
+            let outgoing = tokio::select! {
+                line = incoming.next() => {
+                    let Some(line) = line.map_err(read_error)? else {
+                        break;
+                    };
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    let response = tokio::task::block_in_place(|| {
+                        self.handle_streaming(
+                            &connection,
+                            &line,
+                            &mut incoming,
+                            &mut notifications,
+                            &mut writer,
+                        )
+                    })?;
+                    response.into_iter().collect()
+                }
+                Some(diff) = changes.recv() => {
+                    change_notifications(&diff, &connection.subscriptions)
+                }
+            };
+
+            for message in outgoing {
+                write_message(&mut writer, &message).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Handle `line` on its own thread, writing the notifications and
+    /// requests it sends while it runs, and return its response. Responses
+    /// to its requests are read from `incoming` and handed back to it.
+    /// Blocks the calling thread.
+    fn handle_streaming<R: AsyncBufRead + Unpin, W: AsyncWrite + Unpin>(
+        &self,
+        connection: &Connection,
+        line: &str,
+        incoming: &mut Incoming<R>,
+        notifications: &mut UnboundedReceiver<Value>,
+        writer: &mut W,
+    ) -> Result<Option<Value>, McpError> {
+        let runtime = tokio::runtime::Handle::current();
+        std::thread::scope(|scope| {
+            let (done, mut handled) = tokio::sync::oneshot::channel();
+            scope.spawn(move || {
+                let _ = done.send(handle_message(&self.ctx, connection, line));
+            });
+
+            runtime.block_on(async {
+                let mut closed = false;
+                loop {
+                    tokio::select! {
+                        biased;
+                        Some(notification) = notifications.recv() => {
+                            write_message(writer, &notification).await?;
+                        }
+                        line = incoming.lines.next_line(), if !closed => {
+                            match line.map_err(read_error)? {
+                                Some(line) if is_response(connection, &line) => {}
+                                Some(line) => incoming.deferred.push_back(line),
+                                None => closed = true,
+                            }
+                        }
+                        response = &mut handled => {
+                            // Notifications sent just before the response
+                            while let Ok(notification) = notifications.try_recv() {
+                                write_message(writer, &notification).await?;
+                            }
+                            return Ok(response.ok().flatten());
+                        }
+                    }
+                }
+            })
+        })
+    }
+
     /// Reload the skill index.
     pub fn reload(&self) -> Result<(), crate::index::IndexError> {
         self.ctx.indexer.reload()
     }
 }
 
+/// Lines from the client, with those that arrived while a message was
+/// handled set aside until it's done.
+struct Incoming<R> {
+    lines: Lines<R>,
+    deferred: VecDeque<String>,
+}
+
+impl<R: AsyncBufRead + Unpin> Incoming<R> {
+    /// The next line to handle: set aside first, then from the client.
+    async fn next(&mut self) -> std::io::Result<Option<String>> {
+        if let Some(line) = self.deferred.pop_front() {
+            return Ok(Some(line));
+        }
+        self.lines.next_line().await
+    }
+}
+
+/// Whether `line` is the client's response to a request sent on
+/// `connection`, and if so hand it over.
+fn is_response(connection: &Connection, line: &str) -> bool {
+    serde_json::from_str::<Value>(line).is_ok_and(|message| connection.take_response(&message))
+}
+
+fn read_error(e: std::io::Error) -> McpError {
+    McpError::Runtime(format!("Failed to read message: {}", e))
+}
+
+/// Write one message and its newline, and flush.
+async fn write_message<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    message: &Value,
+) -> Result<(), McpError> {
+    let mut out = message.to_string();
+    out.push('\n');
+    let write_error =
+        |e: std::io::Error| McpError::Runtime(format!("Failed to write response: {}", e));
+    writer
+        .write_all(out.as_bytes())
+        .await
+        .map_err(write_error)?;
+    writer.flush().await.map_err(write_error)
+}
+
 /// MCP server errors.
 #[derive(Debug, thiserror::Error)]
 pub enum McpError {
+    /// The server could not start listening.
     #[error("Failed to initialize: {0}")]
     Init(String),
 
+    /// Reading, writing or serving failed.
     #[error("Runtime error: {0}")]
     Runtime(String),
 
+    /// A message broke the MCP protocol.
     #[error("Protocol error: {0}")]
     Protocol(String),
 }
@@ -108,4 +421,173 @@ mod tests {
         let index = ctx.indexer.get_skill_index();
         assert_eq!(index.len(), 1);
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_serve_over_lines() {
+        let temp_dir = TempDir::new().unwrap();
+        let server = McpServer::new(temp_dir.path());
+
+        let input = concat!(
+            r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#,
+            "\n",
+            r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+            "\n\n",
+            r#"{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"list_skills"}}"#,
+            "\n",
+        );
+        let mut output = Vec::new();
+        server.serve(input.as_bytes(), &mut output).await.unwrap();
+
+        let responses: Vec<serde_json::Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0]["id"], 1);
+        assert_eq!(responses[1]["result"]["isError"], false);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_reload_progress_precedes_response() {
+        let temp_dir = TempDir::new().unwrap();
+        for name in ["forms", "charts"] {
+            let skill_dir = temp_dir.path().join(name);
+            fs::create_dir_all(&skill_dir).unwrap();
+            fs::write(
+                skill_dir.join("_meta.json"),
+                format!(r#"{{"name": "{}", "description": "Test"}}"#, name),
+            )
+            .unwrap();
+            fs::write(skill_dir.join("SKILL.md"), "# Test").unwrap();
+        }
+        let server = McpServer::new(temp_dir.path()).without_file_watcher();
+
+        let input = concat!(
+            r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"reload_index","#,
+            r#""_meta":{"progressToken":7}}}"#,
+            "\n",
+        );
+        let mut output = Vec::new();
+        server.serve(input.as_bytes(), &mut output).await.unwrap();
+
+        let messages: Vec<serde_json::Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[0]["method"], "notifications/progress");
+        assert_eq!(messages[0]["params"]["progressToken"], 7);
+        assert_eq!(messages[1]["params"]["progress"], 2);
+        assert_eq!(messages[1]["params"]["total"], 2);
+        assert_eq!(messages[2]["id"], 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_notifies_subscribers_of_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let skill_dir = temp_dir.path().join("test-skill");
+        fs::create_dir_all(&skill_dir).unwrap();
+        fs::write(
+            skill_dir.join("_meta.json"),
+            r#"{"name": "test-skill", "description": "Test"}"#,
+        )
+        .unwrap();
+        fs::write(skill_dir.join("SKILL.md"), "# Test").unwrap();
+        let server = McpServer::new(temp_dir.path()).without_file_watcher();
+
+        let (client, connection) = tokio::io::duplex(4096);
+        let (reader, writer) = tokio::io::split(connection);
+        let client = async {
+            let (client_reader, mut client_writer) = tokio::io::split(client);
+            let mut lines = BufReader::new(client_reader).lines();
+            let subscribe = r#"{"jsonrpc":"2.0","id":1,"method":"resources/subscribe","params":{"uri":"skill://test-skill"}}"#;
+            client_writer
+                .write_all(format!("{}\n", subscribe).as_bytes())
+                .await
+                .unwrap();
+            let ack = lines.next_line().await.unwrap().unwrap();
+
+            fs::write(skill_dir.join("SKILL.md"), "# Test\n\nChanged.").unwrap();
+            server.reload().unwrap();
+            let update = lines.next_line().await.unwrap().unwrap();
+            (ack, update)
+        };
+
+        let (served, (ack, update)) =
+            tokio::join!(server.serve(BufReader::new(reader), writer), client);
+        served.unwrap();
+
+        let ack: serde_json::Value = serde_json::from_str(&ack).unwrap();
+        assert_eq!(ack["id"], 1);
+        let update: serde_json::Value = serde_json::from_str(&update).unwrap();
+        assert_eq!(update["method"], "notifications/resources/updated");
+        assert_eq!(update["params"]["uri"], "skill://test-skill");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_sampling_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let skill_dir = temp_dir.path().join("test-skill");
+        fs::create_dir_all(&skill_dir).unwrap();
+        fs::write(
+            skill_dir.join("_meta.json"),
+            r#"{"name": "test-skill", "description": "Test"}"#,
+        )
+        .unwrap();
+        fs::write(skill_dir.join("SKILL.md"), "# Test\n\nHow to test.").unwrap();
+        let server = McpServer::new(temp_dir.path()).without_file_watcher();
+
+        async fn next<R: AsyncBufRead + Unpin>(lines: &mut Lines<R>) -> Value {
+            let line = lines.next_line().await.unwrap().unwrap();
+            serde_json::from_str(&line).unwrap()
+        }
+
+        let (client, connection) = tokio::io::duplex(4096);
+        let (reader, writer) = tokio::io::split(connection);
+        let client = async {
+            let (client_reader, mut client_writer) = tokio::io::split(client);
+            let mut lines = BufReader::new(client_reader).lines();
+            let initialize = r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"capabilities":{"sampling":{}}}}"#;
+            let summarize = r#"{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"summarize_skill","arguments":{"name":"test-skill"}}}"#;
+            let ping = r#"{"jsonrpc":"2.0","id":3,"method":"ping"}"#;
+            for message in [initialize, summarize, ping] {
+                client_writer
+                    .write_all(format!("{}\n", message).as_bytes())
+                    .await
+                    .unwrap();
+            }
+            next(&mut lines).await;
+
+            // The ping waits for the tool call, which waits for the client
+            let request = next(&mut lines).await;
+            let response = serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": {"role": "assistant", "model": "client-model",
+                           "content": {"type": "text", "text": "Explains testing."}},
+            });
+            client_writer
+                .write_all(format!("{}\n", response).as_bytes())
+                .await
+                .unwrap();
+            let result = next(&mut lines).await;
+            (request, result, next(&mut lines).await)
+        };
+
+        // Handling a message blocks its task, so the server gets its own
+        let served =
+            tokio::spawn(async move { server.serve(BufReader::new(reader), writer).await });
+        let (request, result, pong) = client.await;
+        served.await.unwrap().unwrap();
+
+        assert_eq!(request["method"], "sampling/createMessage");
+        assert_eq!(result["id"], 2);
+        let text = result["result"]["content"][0]["text"].as_str().unwrap();
+        let summary: Value = serde_json::from_str(text).unwrap();
+        assert_eq!(summary["summary"], "Explains testing.");
+        assert_eq!(summary["model"], "client-model");
+        assert_eq!(pong["id"], 3);
+    }
 }
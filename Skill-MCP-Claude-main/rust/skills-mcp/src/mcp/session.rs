@@ -0,0 +1,115 @@
+//! Per-session state shared across tool calls.
+
+use std::collections::{BTreeSet, HashMap};
+
+use parking_lot::RwLock;
+
+use crate::models::{GroupedSearchResults, SearchResults};
+
+/// Session used when a request does not name one.
+pub const DEFAULT_SESSION: &str = "default";
+
+/// Skills each session has already injected into its context.
+///
+/// Entries are skill ids as shown in search results: `domain` for a main
+/// skill and `domain:sub_skill` for a sub-skill.
+#[derive(Debug, Default)]
+pub struct SessionStore {
+    loaded: RwLock<HashMap<String, BTreeSet<String>>>,
+}
+
+impl SessionStore {
+    /// Create an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record skills as loaded in a session, returning its full loaded set.
+    pub fn mark_loaded(
+        &self,
+        session: &str,
+        skills: impl IntoIterator<Item = String>,
+    ) -> Vec<String> {
+        let mut loaded = self.loaded.write();
+        let set = loaded.entry(session.to_string()).or_default();
+        set.extend(skills);
+        set.iter().cloned().collect()
+    }
+
+    /// Skills loaded in a session, sorted.
+    pub fn loaded(&self, session: &str) -> Vec<String> {
+        self.loaded
+            .read()
+            .get(session)
+            .map(|set| set.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Forget everything a session loaded.
+    pub fn clear(&self, session: &str) {
+        self.loaded.write().remove(session);
+    }
+
+    /// Flag results the session has already loaded.
+    pub fn flag_results(&self, session: &str, results: &mut SearchResults) {
+        let loaded = self.loaded.read();
+        let Some(set) = loaded.get(session) else {
+            return;
+        };
+
+        for result in &mut results.results {
+            result.already_loaded = set.contains(&result.display_id());
+        }
+    }
+
+    /// Flag grouped hits the session has already loaded.
+    pub fn flag_grouped(&self, session: &str, results: &mut GroupedSearchResults) {
+        let loaded = self.loaded.read();
+        let Some(set) = loaded.get(session) else {
+            return;
+        };
+
+        for hit in results.groups.iter_mut().flat_map(|g| g.hits.iter_mut()) {
+            hit.already_loaded = set.contains(&hit.display_id());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{MatchType, SearchResult};
+
+    #[test]
+    fn test_sessions_are_separate() {
+        let store = SessionStore::new();
+        store.mark_loaded("a", vec!["forms".to_string()]);
+        let loaded = store.mark_loaded("a", vec!["api:rest".to_string(), "forms".to_string()]);
+
+        assert_eq!(loaded, vec!["api:rest".to_string(), "forms".to_string()]);
+        assert!(store.loaded("b").is_empty());
+
+        store.clear("a");
+        assert!(store.loaded("a").is_empty());
+    }
+
+    #[test]
+    fn test_flag_results() {
+        let store = SessionStore::new();
+        store.mark_loaded(DEFAULT_SESSION, vec!["forms".to_string()]);
+
+        let mut results = SearchResults::new(
+            "forms".to_string(),
+            vec![
+                SearchResult::new("forms".to_string(), 2.0, MatchType::Name),
+                SearchResult::new("forms".to_string(), 1.0, MatchType::Content)
+                    .with_sub_skill("react".to_string()),
+            ],
+            None,
+        );
+        store.flag_results(DEFAULT_SESSION, &mut results);
+
+        assert!(results.results[0].already_loaded);
+        assert!(!results.results[1].already_loaded);
+    }
+}
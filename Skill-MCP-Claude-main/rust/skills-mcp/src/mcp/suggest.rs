@@ -0,0 +1,209 @@
+//! Drafting skill metadata with a language model.
+//!
+//! Drafts are proposals only: nothing here writes `_meta.json`. A person
+//! reviews the proposal and applies what they agree with.
+
+use serde::{Deserialize, Serialize};
+
+use crate::llm::{parse_json_reply, CompletionRequest, LlmBackend, LlmError};
+use crate::models::SkillMeta;
+
+/// Descriptions shorter than this are considered weak.
+pub const MIN_DESCRIPTION_CHARS: usize = 20;
+
+/// Skills with fewer tags than this are considered weakly tagged.
+pub const MIN_TAGS: usize = 2;
+
+/// Most tags or triggers kept from a draft.
+const MAX_SUGGESTIONS: usize = 8;
+
+/// Longest description kept from a draft.
+const MAX_DESCRIPTION_CHARS: usize = 200;
+
+/// How much of SKILL.md is sent to the model.
+const MAX_PROMPT_CONTENT_CHARS: usize = 8000;
+
+const SYSTEM_PROMPT: &str = "You write metadata for instruction documents that teach an \
+AI assistant a skill. Reply with only a JSON object of the form \
+{\"description\": string, \"tags\": [string], \"triggers\": [string]}. The description is one \
+sentence under 200 characters saying what the skill helps with. Tags are 3 to 8 lowercase \
+keywords. Triggers are 3 to 8 short phrases a user might say when they need the skill.";
+
+/// Current and drafted metadata for one skill.
+#[derive(Debug, Clone, Serialize)]
+pub struct MetadataProposal {
+    /// Skill the proposal is for.
+    pub skill: String,
+    /// Why the current metadata was considered weak.
+    pub reasons: Vec<String>,
+    /// Current description.
+    pub current_description: String,
+    /// Current tags.
+    pub current_tags: Vec<String>,
+    /// Drafted description.
+    pub description: String,
+    /// Drafted tags.
+    pub tags: Vec<String>,
+    /// Drafted trigger phrases, for tags or sub-skill triggers.
+    pub triggers: Vec<String>,
+    /// Backend that drafted the proposal.
+    pub model: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Draft {
+    description: String,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    triggers: Vec<String>,
+}
+
+/// Explain what is missing or weak in a skill's metadata.
+///
+/// An empty result means the metadata looks complete.
+pub fn weak_metadata_reasons(meta: &SkillMeta) -> Vec<String> {
+    let mut reasons = Vec::new();
+    let description = meta.description.trim();
+
+    if description.is_empty() {
+        reasons.push("Missing description".to_string());
+    } else if description.chars().count() < MIN_DESCRIPTION_CHARS {
+        reasons.push(format!(
+            "Description is under {} characters",
+            MIN_DESCRIPTION_CHARS
+        ));
+    } else if description.eq_ignore_ascii_case(&meta.name) {
+        reasons.push("Description only repeats the name".to_string());
+    }
+
+    if meta.tags.is_empty() {
+        reasons.push("No tags".to_string());
+    } else if meta.tags.len() < MIN_TAGS {
+        reasons.push(format!("Fewer than {} tags", MIN_TAGS));
+    }
+
+    reasons
+}
+
+/// Ask a backend to draft metadata from a skill's SKILL.md.
+pub fn draft_metadata(
+    backend: &dyn LlmBackend,
+    meta: &SkillMeta,
+    content: &str,
+    reasons: Vec<String>,
+) -> Result<MetadataProposal, LlmError> {
+    let content: String = content.chars().take(MAX_PROMPT_CONTENT_CHARS).collect();
+    let prompt = format!(
+        "Skill name: {}\nCurrent description: {}\nCurrent tags: {}\n\nSKILL.md:\n{}",
+        meta.name,
+        meta.description,
+        meta.tags.join(", "),
+        content
+    );
+
+    let reply = backend.complete(&CompletionRequest::new(SYSTEM_PROMPT, prompt))?;
+    let draft: Draft = parse_json_reply(&reply)?;
+
+    let description = draft.description.trim();
+    if description.is_empty() {
+        return Err(LlmError::InvalidResponse("empty description".to_string()));
+    }
+
+    Ok(MetadataProposal {
+        skill: meta.name.clone(),
+        reasons,
+        current_description: meta.description.clone(),
+        current_tags: meta.tags.clone(),
+        description: truncate_chars(description, MAX_DESCRIPTION_CHARS),
+        tags: normalize(draft.tags, |t| {
+            t.to_lowercase()
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join("-")
+        }),
+        triggers: normalize(draft.triggers, |t| t.trim().to_string()),
+        model: backend.name().to_string(),
+    })
+}
+
+/// Clean up drafted phrases, dropping blanks and duplicates.
+fn normalize(items: Vec<String>, clean: impl Fn(&str) -> String) -> Vec<String> {
+    let mut kept: Vec<String> = Vec::new();
+    for item in items {
+        let item = clean(item.trim());
+        if !item.is_empty() && !kept.contains(&item) {
+            kept.push(item);
+        }
+    }
+    kept.truncate(MAX_SUGGESTIONS);
+    kept
+}
+
+fn truncate_chars(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((at, _)) => text[..at].trim_end().to_string(),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Canned(&'static str);
+
+    impl LlmBackend for Canned {
+        fn name(&self) -> &str {
+            "canned"
+        }
+
+        fn complete(&self, request: &CompletionRequest) -> Result<String, LlmError> {
+            assert!(request.prompt.contains("Skill name: forms"));
+            Ok(self.0.to_string())
+        }
+    }
+
+    fn meta(description: &str, tags: &[&str]) -> SkillMeta {
+        SkillMeta {
+            name: "forms".to_string(),
+            description: description.to_string(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_weak_metadata_reasons() {
+        assert_eq!(
+            weak_metadata_reasons(&meta("", &[])),
+            vec!["Missing description", "No tags"]
+        );
+        assert_eq!(weak_metadata_reasons(&meta("Forms", &["a"])).len(), 2);
+        assert!(weak_metadata_reasons(&meta("Build accessible web forms", &["a", "b"])).is_empty());
+    }
+
+    #[test]
+    fn test_draft_metadata_normalizes_reply() {
+        let backend = Canned(
+            "```json\n{\"description\": \"Build accessible forms.\", \
+             \"tags\": [\"Forms\", \"form validation\", \"forms\", \" \"], \
+             \"triggers\": [\"make a signup form\"]}\n```",
+        );
+        let proposal = draft_metadata(
+            &backend,
+            &meta("", &[]),
+            "# Forms",
+            vec!["No tags".to_string()],
+        )
+        .unwrap();
+
+        assert_eq!(proposal.description, "Build accessible forms.");
+        assert_eq!(proposal.tags, vec!["forms", "form-validation"]);
+        assert_eq!(proposal.triggers, vec!["make a signup form"]);
+        assert_eq!(proposal.model, "canned");
+
+        let backend = Canned("{\"description\": \"  \"}");
+        assert!(draft_metadata(&backend, &meta("", &[]), "", Vec::new()).is_err());
+    }
+}
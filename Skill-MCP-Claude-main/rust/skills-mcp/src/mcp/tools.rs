@@ -3,46 +3,307 @@
 //! Each function here corresponds to an MCP tool that will be registered
 //! with the MCP server.
 
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Instant;
 
+use rayon::prelude::*;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::index::SkillIndexer;
+use crate::import::{GitHubImporter, UpdateChecker};
+use crate::index::{model_summary, CachedSummary, ReloadJobs, SkillIndexer};
+use crate::llm::LlmBackend;
 use crate::models::*;
-use crate::search::SearchService;
-use crate::validation::validate_skills;
+use crate::search::{
+    Passage, RerankBudget, Reranker, Routing, SearchService, DEFAULT_MIN_ROUTE_CONFIDENCE,
+    DEFAULT_ROUTES,
+};
+use crate::validation::{
+    check_skill_fields, check_skill_name, check_skill_path, validate_meta, validate_skills,
+    LinkChecker, SkillValidator,
+};
+
+use super::allowlist::ToolAllowlist;
+use super::ask::{answer_question, Citation};
+use super::client::{ClientPolicy, ClientSessions};
+use super::journal::{Journal, TraceEvent};
+#[cfg(feature = "scripts")]
+use super::sandbox::{ScriptRun, ScriptSandbox};
+use super::session::{SessionStore, DEFAULT_SESSION};
+use super::suggest::{draft_metadata, weak_metadata_reasons, MetadataProposal};
+
+/// Limits applied to get_skills_batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchLimits {
+    /// Maximum number of items per batch.
+    pub max_items: usize,
+    /// Number of items loaded concurrently.
+    pub parallelism: usize,
+    /// Maximum total content bytes per response; later items are replaced
+    /// with truncation markers.
+    pub max_response_bytes: usize,
+}
+
+impl BatchLimits {
+    /// Default maximum number of items per batch.
+    pub const DEFAULT_MAX_ITEMS: usize = 50;
+
+    /// Default number of items loaded concurrently.
+    pub const DEFAULT_PARALLELISM: usize = 4;
+
+    /// Default total content size per response (2 MiB).
+    pub const DEFAULT_MAX_RESPONSE_BYTES: usize = 2 * 1024 * 1024;
+}
+
+impl Default for BatchLimits {
+    fn default() -> Self {
+        Self {
+            max_items: Self::DEFAULT_MAX_ITEMS,
+            parallelism: Self::DEFAULT_PARALLELISM,
+            max_response_bytes: Self::DEFAULT_MAX_RESPONSE_BYTES,
+        }
+    }
+}
+
+/// Lowest confidence, from 0 to 1, a match needs before it is returned.
+///
+/// Below it, route_task and the search tools report no confident match and
+/// suggest where to explore instead of handing back a weak top hit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConfidenceThresholds {
+    /// Minimum confidence of a route_task route.
+    pub route: f64,
+    /// Minimum confidence of the top search hit; 0 returns every hit.
+    pub search: f64,
+}
+
+impl ConfidenceThresholds {
+    /// Default minimum route confidence.
+    pub const DEFAULT_ROUTE: f64 = DEFAULT_MIN_ROUTE_CONFIDENCE;
+
+    /// Default minimum search confidence; search results are not withheld.
+    pub const DEFAULT_SEARCH: f64 = 0.0;
+}
+
+impl Default for ConfidenceThresholds {
+    fn default() -> Self {
+        Self {
+            route: Self::DEFAULT_ROUTE,
+            search: Self::DEFAULT_SEARCH,
+        }
+    }
+}
+
+/// Check a requested minimum confidence.
+fn check_confidence(confidence: f64) -> Result<f64, ErrorResponse> {
+    if (0.0..=1.0).contains(&confidence) {
+        Ok(confidence)
+    } else {
+        Err(ErrorResponse::invalid_params(format!(
+            "min_confidence must be between 0 and 1, got {}",
+            confidence
+        )))
+    }
+}
 
 /// Service context shared across all tool handlers.
 pub struct ServiceContext {
     /// The skill indexer for loading skill metadata and content.
     pub indexer: Arc<SkillIndexer>,
+    /// Background reloads of `indexer`.
+    pub reloads: ReloadJobs,
     /// The search service for querying skills.
     pub search: SearchService,
     /// Usage statistics tracker.
     pub stats: Arc<parking_lot::RwLock<UsageStats>>,
+    /// Per-session loaded-skill tracking.
+    pub sessions: SessionStore,
+    /// Per-client policies and call counts, by MCP client name.
+    pub clients: ClientSessions,
+    /// Tools listed and callable over MCP.
+    pub tools: ToolAllowlist,
+    /// Optional per-session trace of loads and searches.
+    pub journal: Option<Journal>,
+    /// Limits for get_skills_batch.
+    pub batch_limits: BatchLimits,
+    /// Confidence below which route_task and the search tools explore
+    /// instead of answering.
+    pub confidence: ConfidenceThresholds,
+    /// Set once the startup warm-up has built the search structures.
+    ready: Arc<AtomicBool>,
+    /// Worker pool sized to `batch_limits.parallelism`; the global rayon
+    /// pool is used if it could not be created.
+    batch_pool: Option<rayon::ThreadPool>,
+    /// Checker for external links, shared so its cache outlives requests.
+    pub link_checker: Arc<LinkChecker>,
+    /// Language model for drafting tools; they fail when unset.
+    pub llm: Option<Arc<dyn LlmBackend>>,
+    /// Client for importing skills from GitHub.
+    pub github: GitHubImporter,
+    /// Latest upstream commits of imported skills.
+    pub updates: Arc<UpdateChecker>,
+    /// Sandbox for run_skill_script; the tool is disabled when unset.
+    #[cfg(feature = "scripts")]
+    pub script_sandbox: Option<ScriptSandbox>,
 }
 
 impl ServiceContext {
     /// Create a new service context.
+    ///
+    /// Search structures are warmed up in the background; [`Self::is_ready`]
+    /// reports when they are built.
     pub fn new(indexer: Arc<SkillIndexer>) -> Self {
         let search = SearchService::new(Arc::clone(&indexer));
+        let ready = Arc::new(AtomicBool::new(false));
+        search.warm_up_in_background(Arc::clone(&ready));
         let stats = Arc::new(parking_lot::RwLock::new(UsageStats::new()));
+        let batch_limits = BatchLimits::default();
 
         Self {
+            reloads: ReloadJobs::new(Arc::clone(&indexer)),
             indexer,
             search,
             stats,
+            sessions: SessionStore::new(),
+            clients: ClientSessions::new(),
+            tools: ToolAllowlist::all(),
+            journal: None,
+            batch_limits,
+            confidence: ConfidenceThresholds::default(),
+            ready,
+            batch_pool: Self::build_batch_pool(&batch_limits),
+            link_checker: Arc::new(LinkChecker::new()),
+            llm: None,
+            github: GitHubImporter::new(),
+            updates: Arc::new(UpdateChecker::new()),
+            #[cfg(feature = "scripts")]
+            script_sandbox: None,
         }
     }
 
+    /// Journal every session's skill loads and searches.
+    pub fn with_journal(mut self, journal: Journal) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
+    /// Apply `policy` to the MCP client named `client`.
+    pub fn with_client_policy(mut self, client: impl Into<String>, policy: ClientPolicy) -> Self {
+        self.clients.set_policy(client, policy);
+        self
+    }
+
+    /// Apply `policy` to MCP clients without one of their own.
+    pub fn with_default_client_policy(mut self, policy: ClientPolicy) -> Self {
+        self.clients.set_default_policy(policy);
+        self
+    }
+
+    /// Expose only the tools in `allowlist` over MCP.
+    pub fn with_tool_allowlist(mut self, allowlist: ToolAllowlist) -> Self {
+        self.tools = allowlist;
+        self
+    }
+
+    /// Set the get_skills_batch limits.
+    pub fn with_batch_limits(mut self, limits: BatchLimits) -> Self {
+        self.batch_pool = Self::build_batch_pool(&limits);
+        self.batch_limits = limits;
+        self
+    }
+
+    /// Set the confidence thresholds of route_task and the search tools.
+    pub fn with_confidence_thresholds(mut self, thresholds: ConfidenceThresholds) -> Self {
+        self.confidence = thresholds;
+        self
+    }
+
+    /// Rerank the top search results within `budget`.
+    pub fn with_reranker(mut self, reranker: Arc<dyn Reranker>, budget: RerankBudget) -> Self {
+        self.search = self.search.with_reranker(reranker, budget);
+        self
+    }
+
+    /// Log and count searches slower than `threshold`; `Duration::ZERO`
+    /// turns this off.
+    pub fn with_slow_search(mut self, threshold: std::time::Duration) -> Self {
+        self.search = self.search.with_slow_search(threshold);
+        self
+    }
+
+    /// Use a custom link checker.
+    pub fn with_link_checker(mut self, checker: LinkChecker) -> Self {
+        self.link_checker = Arc::new(checker);
+        self
+    }
+
+    /// Use a language model for drafting tools such as suggest_metadata.
+    pub fn with_llm(mut self, backend: Arc<dyn LlmBackend>) -> Self {
+        self.llm = Some(backend);
+        self
+    }
+
+    /// Import skills from GitHub through this client.
+    pub fn with_github(mut self, importer: GitHubImporter) -> Self {
+        self.github = importer;
+        self
+    }
+
+    /// Enable run_skill_script with the given sandbox.
+    #[cfg(feature = "scripts")]
+    pub fn with_script_sandbox(mut self, sandbox: ScriptSandbox) -> Self {
+        self.script_sandbox = Some(sandbox);
+        self
+    }
+
+    fn build_batch_pool(limits: &BatchLimits) -> Option<rayon::ThreadPool> {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(limits.parallelism.max(1))
+            .thread_name(|i| format!("skills-batch-{}", i))
+            .build()
+            .map_err(|e| tracing::warn!("Failed to create batch worker pool: {}", e))
+            .ok()
+    }
+
+    /// Whether the startup warm-up has finished.
+    pub fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::Acquire)
+    }
+
     /// Record a tool call for statistics.
     pub fn track_tool_call(&self, tool_name: &str) {
         self.stats.write().record_tool_call(tool_name);
     }
 
-    /// Record a skill load for statistics.
-    pub fn track_skill_load(&self, skill_name: &str) {
+    /// Record a skill load for statistics and the session journal.
+    pub fn track_skill_load(&self, session_id: Option<&str>, skill_name: &str) {
         self.stats.write().record_skill_load(skill_name);
+
+        if let Some(journal) = &self.journal {
+            let session = session_id.unwrap_or(DEFAULT_SESSION);
+            journal.record(&TraceEvent::skill_load(session, skill_name));
+        }
+    }
+
+    /// Record a search for statistics and the session journal.
+    pub fn track_search(&self, session_id: Option<&str>, tool_name: &str, results: &SearchResults) {
+        self.stats
+            .write()
+            .record_search(results.query.clone(), results.total_matches);
+
+        if let Some(journal) = &self.journal {
+            let session = session_id.unwrap_or(DEFAULT_SESSION);
+            journal.record(&TraceEvent::search(
+                session,
+                tool_name,
+                &results.query,
+                results.total_matches,
+                results.results.iter().map(|r| r.display_id()),
+            ));
+        }
     }
 }
 
@@ -57,6 +318,9 @@ pub struct ListSkillsResponse {
     pub skills: Vec<SkillSummary>,
     /// Total number of skills.
     pub total: usize,
+    /// Namespaced skills grouped by category folder.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub categories: Vec<SkillCategory>,
 }
 
 /// Summary info for a skill.
@@ -64,55 +328,154 @@ pub struct ListSkillsResponse {
 pub struct SkillSummary {
     /// Skill name/identifier.
     pub name: String,
+    /// Category folder of a namespaced skill (`frontend` for `frontend/forms`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
     /// Short description of the skill.
     pub description: String,
     /// Tags for categorization.
     pub tags: Vec<String>,
     /// Names of sub-skills within this skill.
     pub sub_skills: Vec<String>,
+    /// Hash of SKILL.md as last indexed; compare with a cached copy to
+    /// decide whether get_skill is needed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+    /// Composite quality score from 0 (weakest) to 100.
+    pub quality_score: u8,
+    /// Word, token and file totals across the skill and its sub-skills.
+    pub stats: SkillStats,
+    /// About 100 words on what the skill covers, if summaries are on.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    /// Salient terms extracted from SKILL.md, most salient first.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub keywords: Vec<String>,
 }
 
 /// List all available skill domains.
 pub fn list_skills(ctx: &ServiceContext) -> ListSkillsResponse {
     ctx.track_tool_call("list_skills");
 
-    let index = ctx.indexer.get_skill_index();
-
-    let skills: Vec<SkillSummary> = index
-        .skills
-        .iter()
-        .map(|s| SkillSummary {
-            name: s.name.clone(),
-            description: s.description.clone(),
-            tags: s.tags.clone(),
-            sub_skills: s.sub_skill_names().iter().map(|n| n.to_string()).collect(),
-        })
-        .collect();
-
-    let total = skills.len();
+    let quality = SkillValidator::new(Arc::clone(&ctx.indexer)).quality_all();
+    let stats = ctx.indexer.skill_stats();
+
+    // Summaries are built straight from the live index, not a clone of it
+    ctx.indexer.with_indexes(|index, content| {
+        let skills: Vec<SkillSummary> = index
+            .skills
+            .iter()
+            .map(|s| {
+                let entry = content.get(&s.name);
+                SkillSummary {
+                    name: s.name.clone(),
+                    category: s.category().map(str::to_string),
+                    description: s.description.clone(),
+                    tags: s.tags.clone(),
+                    sub_skills: s.sub_skill_names().iter().map(|n| n.to_string()).collect(),
+                    content_hash: entry.map(|e| e.hash.clone()),
+                    quality_score: quality.get(&s.name).map(|q| q.score).unwrap_or(0),
+                    stats: stats.get(&s.name).cloned().unwrap_or_default(),
+                    summary: entry.and_then(|e| e.summary.clone()),
+                    keywords: entry.map(|e| e.keywords.clone()).unwrap_or_default(),
+                }
+            })
+            .collect();
 
-    ListSkillsResponse { skills, total }
+        ListSkillsResponse {
+            total: skills.len(),
+            skills,
+            categories: index.categories(),
+        }
+    })
 }
 
 // ============================================================================
 // Tool: get_skill
 // ============================================================================
 
+/// SKILL.md content larger than this is returned in chunks, as the HTTP API
+/// streams it.
+pub const MAX_CONTENT_CHUNK_BYTES: usize = 256 * 1024;
+
 /// Request for get_skill tool.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct GetSkillRequest {
     /// Name of the skill to retrieve.
     pub name: String,
+    /// `content_hash` from a previous response. If the skill still has
+    /// this hash, only an unchanged marker is returned.
+    #[serde(default)]
+    pub if_none_match: Option<String>,
+    /// Session to attribute the load to.
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// Byte offset to continue a chunked skill from: the `next_offset` of
+    /// the previous chunk.
+    #[serde(default)]
+    pub content_offset: usize,
+}
+
+/// Response for get_skill tool.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum GetSkillResponse {
+    /// The skill content.
+    Content(SkillContent),
+    /// Part of a skill too large to return at once.
+    Chunk(SkillContentChunk),
+    /// The client's copy is current.
+    Unchanged(SkillUnchanged),
+}
+
+impl GetSkillResponse {
+    /// The whole skill content, unless it was chunked or unchanged.
+    pub fn into_content(self) -> Option<SkillContent> {
+        match self {
+            GetSkillResponse::Content(content) => Some(content),
+            GetSkillResponse::Chunk(_) | GetSkillResponse::Unchanged(_) => None,
+        }
+    }
 }
 
 /// Get the main SKILL.md content for a skill.
-pub fn get_skill(ctx: &ServiceContext, req: GetSkillRequest) -> Result<SkillContent, ErrorResponse> {
+pub fn get_skill(
+    ctx: &ServiceContext,
+    req: GetSkillRequest,
+) -> Result<GetSkillResponse, ErrorResponse> {
     ctx.track_tool_call("get_skill");
-    ctx.track_skill_load(&req.name);
 
-    ctx.indexer
-        .read_skill_content(&req.name)
-        .map_err(|e| ErrorResponse::new(e.to_string()))
+    // Skills merged into another one live on as its aliases
+    let name = ctx.indexer.resolve_alias(&req.name).unwrap_or(req.name);
+    let content = ctx
+        .indexer
+        .read_skill_content(&name)
+        .map_err(ErrorResponse::from)?;
+
+    if req.if_none_match.as_deref() == Some(content.content_hash.as_str()) {
+        return Ok(GetSkillResponse::Unchanged(SkillUnchanged::new(
+            content.name,
+            content.content_hash,
+        )));
+    }
+
+    // Later chunks continue the same load
+    if req.content_offset == 0 {
+        ctx.track_skill_load(req.session_id.as_deref(), &name);
+        if content.content.len() <= MAX_CONTENT_CHUNK_BYTES {
+            return Ok(GetSkillResponse::Content(content));
+        }
+    }
+
+    content
+        .into_chunk(req.content_offset, MAX_CONTENT_CHUNK_BYTES)
+        .map(GetSkillResponse::Chunk)
+        .ok_or_else(|| {
+            ErrorResponse::invalid_params(format!(
+                "content_offset {} is not a chunk boundary of '{}'",
+                req.content_offset, name
+            ))
+        })
 }
 
 // ============================================================================
@@ -120,12 +483,18 @@ pub fn get_skill(ctx: &ServiceContext, req: GetSkillRequest) -> Result<SkillCont
 // ============================================================================
 
 /// Request for get_sub_skill tool.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct GetSubSkillRequest {
     /// Parent skill domain name.
     pub domain: String,
-    /// Name of the sub-skill to retrieve.
+    /// Name of the sub-skill to retrieve, a slash-separated path to a nested
+    /// one (e.g. `react/hooks`), or a markdown file path relative to the
+    /// skill directory (e.g. `react/advanced.md`).
+    #[serde(alias = "subSkill")]
     pub sub_skill: String,
+    /// Session to attribute the load to.
+    #[serde(default)]
+    pub session_id: Option<String>,
 }
 
 /// Get sub-skill content.
@@ -134,212 +503,1522 @@ pub fn get_sub_skill(
     req: GetSubSkillRequest,
 ) -> Result<SubSkillContent, ErrorResponse> {
     ctx.track_tool_call("get_sub_skill");
-    ctx.track_skill_load(&format!("{}:{}", req.domain, req.sub_skill));
+    let domain = ctx.indexer.resolve_alias(&req.domain).unwrap_or(req.domain);
+    ctx.track_skill_load(
+        req.session_id.as_deref(),
+        &format!("{}:{}", domain, req.sub_skill),
+    );
 
     ctx.indexer
-        .read_sub_skill_content(&req.domain, &req.sub_skill)
-        .map_err(|e| ErrorResponse::new(e.to_string()))
+        .read_sub_skill_content(&domain, &req.sub_skill)
+        .map_err(ErrorResponse::from)
 }
 
 // ============================================================================
-// Tool: get_skills_batch
+// Tool: list_references
 // ============================================================================
 
-/// Request for get_skills_batch tool.
-#[derive(Debug, Deserialize)]
-pub struct GetSkillsBatchRequest {
-    /// List of skill/sub-skill requests to process.
-    pub requests: Vec<BatchRequest>,
+/// Request for list_references tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ListReferencesRequest {
+    /// Skill domain name.
+    pub domain: String,
 }
 
-/// Response for get_skills_batch tool.
+/// Response for list_references tool.
 #[derive(Debug, Serialize)]
-pub struct GetSkillsBatchResponse {
-    /// Results for each requested skill.
-    pub results: Vec<BatchResponseItem>,
+pub struct ListReferencesResponse {
+    /// Skill domain name.
+    pub domain: String,
+    /// Reference documents, relative to the references directory.
+    pub references: Vec<ReferenceInfo>,
+    /// Total number of reference documents.
+    pub total: usize,
 }
 
-/// Load multiple skills in a single request.
-pub fn get_skills_batch(ctx: &ServiceContext, req: GetSkillsBatchRequest) -> GetSkillsBatchResponse {
-    ctx.track_tool_call("get_skills_batch");
+/// List the reference documents of a skill.
+pub fn list_references(
+    ctx: &ServiceContext,
+    req: ListReferencesRequest,
+) -> Result<ListReferencesResponse, ErrorResponse> {
+    ctx.track_tool_call("list_references");
+
+    let references = ctx
+        .indexer
+        .list_references(&req.domain)
+        .map_err(ErrorResponse::from)?;
+    let total = references.len();
+
+    Ok(ListReferencesResponse {
+        domain: req.domain,
+        references,
+        total,
+    })
+}
 
-    let results: Vec<BatchResponseItem> = req
-        .requests
-        .into_iter()
-        .map(|r| {
-            if let Some(sub_skill) = r.sub_skill {
-                ctx.track_skill_load(&format!("{}:{}", r.domain, sub_skill));
+// ============================================================================
+// Tool: get_reference
+// ============================================================================
 
-                match ctx.indexer.read_sub_skill_content(&r.domain, &sub_skill) {
-                    Ok(content) => BatchResponseItem::SubSkill(content),
-                    Err(e) => BatchResponseItem::error(r.domain, e.to_string()),
-                }
-            } else {
-                ctx.track_skill_load(&r.domain);
+/// Request for get_reference tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetReferenceRequest {
+    /// Skill domain name.
+    pub domain: String,
+    /// Path relative to the skill's references directory.
+    pub path: String,
+    /// Session to attribute the load to.
+    #[serde(default)]
+    pub session_id: Option<String>,
+}
 
-                match ctx.indexer.read_skill_content(&r.domain) {
-                    Ok(content) => BatchResponseItem::Skill(content),
-                    Err(e) => BatchResponseItem::error(r.domain, e.to_string()),
-                }
-            }
-        })
-        .collect();
+/// Get a reference document's content.
+pub fn get_reference(
+    ctx: &ServiceContext,
+    req: GetReferenceRequest,
+) -> Result<ReferenceContent, ErrorResponse> {
+    ctx.track_tool_call("get_reference");
+    ctx.track_skill_load(
+        req.session_id.as_deref(),
+        &format!("{}:references/{}", req.domain, req.path),
+    );
 
-    GetSkillsBatchResponse { results }
+    ctx.indexer
+        .read_reference(&req.domain, &req.path)
+        .map_err(ErrorResponse::from)
 }
 
 // ============================================================================
-// Tool: search_skills
+// Tool: run_skill_script
 // ============================================================================
 
-/// Request for search_skills tool.
-#[derive(Debug, Deserialize)]
-pub struct SearchSkillsRequest {
-    /// Search query string.
-    pub query: String,
-    /// Maximum number of results to return.
+/// Request for run_skill_script tool.
+#[cfg(feature = "scripts")]
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RunSkillScriptRequest {
+    /// Skill the script belongs to.
+    pub skill: String,
+    /// Script path, either `scripts/x.py` or just `x.py`.
+    pub script: String,
+    /// Arguments passed to the script.
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+/// Run a skill's bundled script in the configured sandbox.
+///
+/// Disabled unless the context has a [`ScriptSandbox`].
+#[cfg(feature = "scripts")]
+pub fn run_skill_script(
+    ctx: &ServiceContext,
+    req: RunSkillScriptRequest,
+) -> Result<ScriptRun, ErrorResponse> {
+    ctx.track_tool_call("run_skill_script");
+
+    let sandbox = ctx.script_sandbox.as_ref().ok_or_else(|| {
+        ErrorResponse::new("Script execution is disabled").with_code(ErrorCode::Unavailable)
+    })?;
+
+    let scripts = ctx
+        .indexer
+        .list_scripts(&req.skill)
+        .map_err(ErrorResponse::from)?;
+    let wanted = req.script.trim_start_matches("./");
+    let script = scripts
+        .iter()
+        .find(|s| s.path == wanted || s.path.strip_prefix("scripts/") == Some(wanted))
+        .ok_or_else(|| {
+            ErrorResponse::not_found(format!(
+                "Script '{}' not found in skill '{}'",
+                req.script, req.skill
+            ))
+        })?;
+
+    let script_path = ctx
+        .indexer
+        .resolve_skill_file(&req.skill, &script.path)
+        .map_err(ErrorResponse::from)?;
+    let skill_dir = ctx
+        .indexer
+        .skills_dir()
+        .join(&req.skill)
+        .canonicalize()
+        .map_err(|e| ErrorResponse::new(format!("Failed to resolve skill directory: {}", e)))?;
+
+    sandbox
+        .run(&req.skill, &skill_dir, &script_path, script, &req.args)
+        .map_err(|e| ErrorResponse::new(e.to_string()))
+}
+
+// ============================================================================
+// Tool: suggest_metadata
+// ============================================================================
+
+/// Default number of skills drafted when no skill is named.
+pub const DEFAULT_SUGGEST_LIMIT: usize = 10;
+
+/// Request for suggest_metadata tool.
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct SuggestMetadataRequest {
+    /// Skill to draft metadata for. When unset, skills with missing or
+    /// weak metadata are drafted.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Most skills to draft when `name` is unset.
     #[serde(default)]
     pub limit: Option<usize>,
 }
 
-/// Search skills by metadata.
-pub fn search_skills(ctx: &ServiceContext, req: SearchSkillsRequest) -> SearchResults {
-    ctx.track_tool_call("search_skills");
+/// Response for suggest_metadata tool.
+#[derive(Debug, Serialize)]
+pub struct SuggestMetadataResponse {
+    /// Drafted metadata awaiting review. Nothing is written to disk.
+    pub proposals: Vec<MetadataProposal>,
+    /// Skills that could not be drafted, as `skill: error`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
 
-    let options = SearchOptions {
-        limit: req.limit.or(Some(10)),
-        ..Default::default()
+/// Draft descriptions, tags and triggers from SKILL.md content.
+///
+/// Needs an LLM backend. Proposals are returned for a person to approve;
+/// skill files are never modified.
+pub fn suggest_metadata(
+    ctx: &ServiceContext,
+    req: SuggestMetadataRequest,
+) -> Result<SuggestMetadataResponse, ErrorResponse> {
+    ctx.track_tool_call("suggest_metadata");
+
+    let backend = ctx.llm.as_ref().ok_or_else(|| {
+        ErrorResponse::new(crate::llm::LlmError::NotConfigured.to_string())
+            .with_code(ErrorCode::Unavailable)
+    })?;
+
+    let candidates: Vec<(SkillMeta, Vec<String>)> = match &req.name {
+        Some(name) => {
+            let meta = ctx
+                .indexer
+                .get_skill_meta(name)
+                .ok_or_else(|| ErrorResponse::not_found(format!("Skill '{}' not found", name)))?;
+            let reasons = weak_metadata_reasons(&meta);
+            vec![(meta, reasons)]
+        }
+        None => ctx
+            .indexer
+            .get_skill_index()
+            .skills
+            .into_iter()
+            .map(|meta| {
+                let reasons = weak_metadata_reasons(&meta);
+                (meta, reasons)
+            })
+            .filter(|(_, reasons)| !reasons.is_empty())
+            .take(req.limit.unwrap_or(DEFAULT_SUGGEST_LIMIT))
+            .collect(),
     };
 
-    let results = ctx.search.search_skills(&req.query, options);
+    let mut response = SuggestMetadataResponse {
+        proposals: Vec::new(),
+        errors: Vec::new(),
+    };
 
-    ctx.stats
-        .write()
-        .record_search(req.query, results.total_matches);
+    for (meta, reasons) in candidates {
+        let drafted = ctx
+            .indexer
+            .read_skill_content(&meta.name)
+            .map_err(|e| e.to_string())
+            .and_then(|content| {
+                draft_metadata(backend.as_ref(), &meta, &content.content, reasons)
+                    .map_err(|e| e.to_string())
+            });
+
+        match drafted {
+            Ok(proposal) => response.proposals.push(proposal),
+            Err(e) => response.errors.push(format!("{}: {}", meta.name, e)),
+        }
+    }
 
-    results
+    Ok(response)
 }
 
 // ============================================================================
-// Tool: search_content
+// Tool: ask_skills
 // ============================================================================
 
-/// Request for search_content tool.
-#[derive(Debug, Deserialize)]
-pub struct SearchContentRequest {
-    /// Search query string for full-text search.
-    pub query: String,
-    /// Maximum number of results to return.
+/// Default number of passages retrieved for a question.
+pub const DEFAULT_ASK_PASSAGES: usize = 5;
+
+/// Request for ask_skills tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct AskSkillsRequest {
+    /// Natural-language question.
+    pub question: String,
+    /// Most passages to retrieve.
     #[serde(default)]
     pub limit: Option<usize>,
+    /// Skills a restricted client sees; set by the server, not the caller.
+    #[serde(skip)]
+    pub domains: Option<Vec<String>>,
 }
 
-/// Search content by full-text matching.
-pub fn search_content(ctx: &ServiceContext, req: SearchContentRequest) -> SearchResults {
-    ctx.track_tool_call("search_content");
+/// Response for ask_skills tool.
+#[derive(Debug, Serialize)]
+pub struct AskSkillsResponse {
+    /// The question asked.
+    pub question: String,
+    /// Retrieved passages, best first. Citations number them from 1.
+    pub passages: Vec<Passage>,
+    /// Answer drawn from the passages, when an LLM backend is configured.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub answer: Option<String>,
+    /// Passages the answer cites.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub citations: Vec<Citation>,
+    /// Backend that wrote the answer.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Why no answer was written despite a configured backend.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Answer a question from the most relevant skill passages.
+///
+/// Without an LLM backend only the passages are returned. If the backend
+/// fails, the passages are still returned along with the error.
+pub fn ask_skills(
+    ctx: &ServiceContext,
+    req: AskSkillsRequest,
+) -> Result<AskSkillsResponse, ErrorResponse> {
+    ctx.track_tool_call("ask_skills");
+
+    let question = req.question.trim();
+    if question.is_empty() {
+        return Err(ErrorResponse::invalid_params("Question must not be empty"));
+    }
 
     let options = SearchOptions {
-        limit: req.limit.or(Some(10)),
+        domains: req.domains,
         ..Default::default()
     };
+    let passages = ctx.search.passages_in(
+        question,
+        req.limit.unwrap_or(DEFAULT_ASK_PASSAGES),
+        &options,
+    );
+
+    let mut response = AskSkillsResponse {
+        question: question.to_string(),
+        passages,
+        answer: None,
+        citations: Vec::new(),
+        model: None,
+        error: None,
+    };
 
-    let results = ctx.search.search_content(&req.query, options);
+    let Some(backend) = &ctx.llm else {
+        return Ok(response);
+    };
+    if response.passages.is_empty() {
+        return Ok(response);
+    }
 
-    ctx.stats
-        .write()
-        .record_search(req.query, results.total_matches);
+    match answer_question(backend.as_ref(), question, &response.passages) {
+        Ok(answer) => {
+            response.answer = Some(answer.text);
+            response.citations = answer.citations;
+            response.model = Some(answer.model);
+        }
+        Err(e) => response.error = Some(e.to_string()),
+    }
 
-    results
+    Ok(response)
 }
 
 // ============================================================================
-// Tool: reload_index
+// Tool: summarize_skill
 // ============================================================================
 
-/// Response for reload_index tool.
+/// Request for summarize_skill tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SummarizeSkillRequest {
+    /// Skill to summarize.
+    pub name: String,
+    /// Write a new summary even if one is cached.
+    #[serde(default)]
+    pub refresh: bool,
+}
+
+/// Response for summarize_skill tool.
 #[derive(Debug, Serialize)]
-pub struct ReloadIndexResponse {
-    /// Whether the reload succeeded.
-    pub success: bool,
-    /// Number of skills in the index after reload.
-    pub skill_count: usize,
-    /// Number of content entries in the index after reload.
-    pub content_entries: usize,
-    /// Error message if reload failed.
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub error: Option<String>,
+pub struct SummarizeSkillResponse {
+    /// Skill summarized.
+    pub name: String,
+    /// Short summary of the skill's main document.
+    pub summary: String,
+    /// Model that wrote the summary.
+    pub model: String,
+    /// Whether the summary was cached rather than written for this call.
+    pub cached: bool,
 }
 
-/// Reload the skill index from disk.
-pub fn reload_index(ctx: &ServiceContext) -> ReloadIndexResponse {
-    ctx.track_tool_call("reload_index");
+/// Summarize a skill's main document with a language model.
+///
+/// `sampler` is the client's own model, reached through MCP sampling; the
+/// server's LLM backend is used when the client can't sample. Summaries are
+/// cached in the index until SKILL.md changes, so asking again is free
+/// unless `refresh` is set.
+pub fn summarize_skill(
+    ctx: &ServiceContext,
+    req: SummarizeSkillRequest,
+    sampler: Option<&dyn LlmBackend>,
+) -> Result<SummarizeSkillResponse, ErrorResponse> {
+    ctx.track_tool_call("summarize_skill");
 
-    match ctx.indexer.reload() {
-        Ok(()) => {
-            let skill_index = ctx.indexer.get_skill_index();
-            let content_index = ctx.indexer.get_content_index();
+    let content = ctx
+        .indexer
+        .read_skill_content(&req.name)
+        .map_err(ErrorResponse::from)?;
+    let hash = content_hash(&content.content);
+
+    let cached = (!req.refresh)
+        .then(|| ctx.indexer.cached_summary(&req.name, &hash))
+        .flatten();
+    if let Some(cached) = cached {
+        return Ok(SummarizeSkillResponse {
+            name: req.name,
+            summary: cached.summary,
+            model: cached.model,
+            cached: true,
+        });
+    }
 
-            ReloadIndexResponse {
-                success: true,
-                skill_count: skill_index.len(),
-                content_entries: content_index.len(),
-                error: None,
-            }
-        }
-        Err(e) => ReloadIndexResponse {
-            success: false,
-            skill_count: 0,
-            content_entries: 0,
-            error: Some(e.to_string()),
+    let backend = sampler.or(ctx.llm.as_deref()).ok_or_else(|| {
+        ErrorResponse::new("The client doesn't support sampling and no LLM backend is configured")
+            .with_code(ErrorCode::Unavailable)
+    })?;
+    let summary = model_summary(backend, &req.name, &content.content)
+        .map_err(|e| ErrorResponse::new(e.to_string()).with_code(ErrorCode::Unavailable))?;
+    let model = backend.name().to_string();
+
+    ctx.indexer.cache_summary(
+        &req.name,
+        CachedSummary {
+            summary: summary.clone(),
+            model: model.clone(),
+            hash,
         },
-    }
+    );
+
+    Ok(SummarizeSkillResponse {
+        name: req.name,
+        summary,
+        model,
+        cached: false,
+    })
 }
 
 // ============================================================================
-// Tool: get_stats
+// Tool: route_task
 // ============================================================================
 
-/// Get usage statistics.
-pub fn get_stats(ctx: &ServiceContext) -> UsageStats {
-    ctx.track_tool_call("get_stats");
-    ctx.stats.read().clone()
+/// Request for route_task tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RouteTaskRequest {
+    /// The user's task, in their own words.
+    pub task: String,
+    /// Most routes to return (default 3).
+    #[serde(default)]
+    pub limit: Option<usize>,
+    /// Lowest confidence, from 0 to 1, a route needs; overrides the
+    /// server's threshold.
+    #[serde(default)]
+    pub min_confidence: Option<f64>,
+    /// Session to attribute the routing to.
+    #[serde(default)]
+    pub session_id: Option<String>,
 }
 
-// ============================================================================
-// Tool: validate_skills
-// ============================================================================
-
-/// Validate all skills.
-pub fn validate_skills_tool(ctx: &ServiceContext) -> ValidationResult {
-    ctx.track_tool_call("validate_skills");
-    validate_skills(Arc::clone(&ctx.indexer))
+/// Response for route_task tool.
+#[derive(Debug, Serialize)]
+pub struct RouteTaskResponse {
+    /// The task routed.
+    pub task: String,
+    /// Skills to load for the task, best first; when none is confident,
+    /// where to explore instead.
+    #[serde(flatten)]
+    pub routing: Routing,
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::fs;
-    use tempfile::TempDir;
-
-    fn create_test_context() -> (TempDir, ServiceContext) {
-        let temp_dir = TempDir::new().unwrap();
+/// Decide which skill, and which of its sub-skills, should handle a task.
+///
+/// Meant to be called once per user request in place of several searches:
+/// the top route is the skill to load, with a confidence and the evidence
+/// behind it. Rather than a weak guess, a task without a confident route
+/// gets `no_confident_match` and suggestions for exploring.
+pub fn route_task(
+    ctx: &ServiceContext,
+    req: RouteTaskRequest,
+) -> Result<RouteTaskResponse, ErrorResponse> {
+    ctx.track_tool_call("route_task");
 
-        // Create a test skill
-        let skill_dir = temp_dir.path().join("test-skill");
-        fs::create_dir_all(&skill_dir).unwrap();
-        fs::write(
-            skill_dir.join("_meta.json"),
-            r#"{"name": "test-skill", "description": "A test skill"}"#,
-        )
-        .unwrap();
-        fs::write(skill_dir.join("SKILL.md"), "# Test Skill\n\nContent here.").unwrap();
+    let task = req.task.trim();
+    if task.is_empty() {
+        return Err(ErrorResponse::invalid_params("Task must not be empty"));
+    }
 
-        let indexer = Arc::new(SkillIndexer::new(temp_dir.path()));
-        indexer.reload().unwrap();
+    let min_confidence = check_confidence(req.min_confidence.unwrap_or(ctx.confidence.route))?;
 
-        let ctx = ServiceContext::new(indexer);
+    let routing = ctx
+        .search
+        .route_task(task, req.limit.unwrap_or(DEFAULT_ROUTES), min_confidence);
 
-        (temp_dir, ctx)
+    let routes = &routing.routes;
+    ctx.stats
+        .write()
+        .record_search(task.to_string(), routes.len());
+    if let Some(journal) = &ctx.journal {
+        journal.record(&TraceEvent::search(
+            req.session_id.as_deref().unwrap_or(DEFAULT_SESSION),
+            "route_task",
+            task,
+            routes.len(),
+            routes.iter().map(|r| r.id()),
+        ));
+    }
+
+    Ok(RouteTaskResponse {
+        task: task.to_string(),
+        routing,
+    })
+}
+
+// ============================================================================
+// Tool: get_skills_batch
+// ============================================================================
+
+/// Request for get_skills_batch tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetSkillsBatchRequest {
+    /// List of skill/sub-skill requests to process.
+    pub requests: Vec<BatchRequest>,
+    /// Session to attribute the loads to.
+    #[serde(default)]
+    pub session_id: Option<String>,
+}
+
+/// Response for get_skills_batch tool.
+#[derive(Debug, Serialize)]
+pub struct GetSkillsBatchResponse {
+    /// Results for each requested skill, in request order.
+    pub results: Vec<TimedBatchItem>,
+    /// Total content bytes returned.
+    pub total_bytes: usize,
+    /// Whether items were left out to stay under the response size limit.
+    pub truncated: bool,
+}
+
+/// Load multiple skills in a single request.
+///
+/// Items load concurrently. Results keep request order; once their
+/// combined content would exceed the response size limit, that item and
+/// every later one are replaced with truncation markers.
+pub fn get_skills_batch(
+    ctx: &ServiceContext,
+    req: GetSkillsBatchRequest,
+) -> Result<GetSkillsBatchResponse, ErrorResponse> {
+    ctx.track_tool_call("get_skills_batch");
+
+    let limits = ctx.batch_limits;
+    if req.requests.len() > limits.max_items {
+        return Err(ErrorResponse::invalid_params(format!(
+            "Too many batch items: {} (max {})",
+            req.requests.len(),
+            limits.max_items
+        )));
+    }
+
+    let load = || -> Vec<TimedBatchItem> {
+        req.requests
+            .par_iter()
+            .map(|r| load_batch_item(ctx, req.session_id.as_deref(), r))
+            .collect()
+    };
+    let loaded = match &ctx.batch_pool {
+        Some(pool) => pool.install(load),
+        None => load(),
+    };
+
+    let mut total_bytes = 0;
+    let mut truncated = false;
+    let results = loaded
+        .into_iter()
+        .zip(&req.requests)
+        .map(|(timed, request)| {
+            let len = timed.item.content_len();
+            if !truncated && total_bytes + len <= limits.max_response_bytes {
+                total_bytes += len;
+                return timed;
+            }
+
+            truncated = true;
+            TimedBatchItem {
+                item: BatchResponseItem::truncated(
+                    request,
+                    format!(
+                        "Response size limit of {} bytes reached",
+                        limits.max_response_bytes
+                    ),
+                ),
+                duration_ms: timed.duration_ms,
+            }
+        })
+        .collect();
+
+    Ok(GetSkillsBatchResponse {
+        results,
+        total_bytes,
+        truncated,
+    })
+}
+
+fn load_batch_item(
+    ctx: &ServiceContext,
+    session_id: Option<&str>,
+    r: &BatchRequest,
+) -> TimedBatchItem {
+    let start = Instant::now();
+
+    let item = if let Some(sub_skill) = &r.sub_skill {
+        ctx.track_skill_load(session_id, &format!("{}:{}", r.domain, sub_skill));
+
+        match ctx.indexer.read_sub_skill_content(&r.domain, sub_skill) {
+            Ok(content) => BatchResponseItem::SubSkill(content),
+            Err(e) => BatchResponseItem::error(r.domain.clone(), e.to_string()),
+        }
+    } else {
+        ctx.track_skill_load(session_id, &r.domain);
+
+        match ctx.indexer.read_skill_content(&r.domain) {
+            Ok(content) => BatchResponseItem::Skill(content),
+            Err(e) => BatchResponseItem::error(r.domain.clone(), e.to_string()),
+        }
+    };
+
+    TimedBatchItem {
+        item,
+        duration_ms: start.elapsed().as_secs_f64() * 1000.0,
+    }
+}
+
+// ============================================================================
+// Tool: search_skills
+// ============================================================================
+
+/// Paging and matching parameters shared by the search tools.
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct SearchParams {
+    /// Maximum number of results to return.
+    #[serde(default)]
+    pub limit: Option<usize>,
+    /// Number of results to skip.
+    #[serde(default)]
+    pub offset: Option<usize>,
+    /// Cursor from a previous page's `next_cursor`; overrides `offset`.
+    #[serde(default)]
+    pub cursor: Option<String>,
+    /// How many query terms must match: `all`, a count, or a percentage
+    /// such as `75%`. Defaults to any one term.
+    #[serde(default)]
+    pub min_should_match: Option<String>,
+    /// Only skills with this source (e.g., "official", "community").
+    #[serde(default)]
+    pub source: Option<String>,
+    /// Only skills in this language.
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Only skills with this status: draft, published or deprecated.
+    #[serde(default)]
+    pub status: Option<String>,
+    /// Only skills owned by this person or team.
+    #[serde(default)]
+    pub owner: Option<String>,
+    /// Tag filter expression, e.g. `react AND (forms OR validation) NOT deprecated`.
+    #[serde(default)]
+    pub tags: Option<String>,
+    /// Only skills in this category folder (e.g. `frontend`) or below it.
+    #[serde(default)]
+    pub category: Option<String>,
+    /// Spelling correction when nothing matches: `suggest` returns
+    /// alternatives, `auto` retries with the best correction.
+    #[serde(default)]
+    pub spelling: Option<String>,
+    /// Lowest confidence, from 0 to 1, the top hit needs; below it the
+    /// results are withheld as no confident match. Overrides the server's
+    /// threshold.
+    #[serde(default)]
+    pub min_confidence: Option<f64>,
+    /// Response format: `json` (default) or `llm` for compact text with
+    /// one `id | score | snippet` line per result.
+    #[serde(default)]
+    pub format: Option<String>,
+    /// Session whose loaded skills are flagged with `already_loaded`.
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// Skills a restricted client sees; set by the server, not the caller.
+    #[serde(skip)]
+    pub domains: Option<Vec<String>>,
+}
+
+impl SearchParams {
+    fn session(&self) -> &str {
+        self.session_id.as_deref().unwrap_or(DEFAULT_SESSION)
+    }
+
+    /// Parse the requested response format.
+    fn format(&self) -> Result<SearchFormat, ErrorResponse> {
+        self.format
+            .as_deref()
+            .map(str::parse::<SearchFormat>)
+            .transpose()
+            .map(Option::unwrap_or_default)
+            .map_err(ErrorResponse::invalid_params)
+    }
+
+    /// Build search options, resolving the cursor against the current index.
+    fn to_options(&self, ctx: &ServiceContext) -> Result<SearchOptions, ErrorResponse> {
+        let offset = match &self.cursor {
+            Some(cursor) => Some(
+                ctx.search
+                    .resolve_cursor(cursor)
+                    .map_err(|e| ErrorResponse::invalid_params(e.to_string()))?,
+            ),
+            None => self.offset,
+        };
+
+        let min_should_match = self
+            .min_should_match
+            .as_deref()
+            .map(str::parse::<MinimumShouldMatch>)
+            .transpose()
+            .map_err(ErrorResponse::invalid_params)?;
+
+        let status = self
+            .status
+            .as_deref()
+            .map(str::parse::<SkillStatus>)
+            .transpose()
+            .map_err(ErrorResponse::invalid_params)?;
+
+        let tags = self
+            .tags
+            .as_deref()
+            .map(str::parse::<TagExpr>)
+            .transpose()
+            .map_err(ErrorResponse::invalid_params)?;
+
+        let spelling = self
+            .spelling
+            .as_deref()
+            .map(str::parse::<SpellingMode>)
+            .transpose()
+            .map_err(ErrorResponse::invalid_params)?;
+
+        let min_confidence =
+            check_confidence(self.min_confidence.unwrap_or(ctx.confidence.search))?;
+
+        Ok(SearchOptions {
+            limit: self.limit.or(Some(10)),
+            offset,
+            min_should_match,
+            source: self.source.clone(),
+            language: self.language.clone(),
+            status,
+            owner: self.owner.clone(),
+            tags,
+            category: self.category.clone(),
+            domains: self.domains.clone(),
+            spelling,
+            min_confidence: Some(min_confidence).filter(|c| *c > 0.0),
+            ..Default::default()
+        })
+    }
+}
+
+/// Response for the search tools: structured results, or compact text
+/// when `format` is `llm`.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum SearchOutput<T> {
+    /// Structured results.
+    Json(T),
+    /// Compact plain-text rendering.
+    Text(String),
+}
+
+impl<T> SearchOutput<T> {
+    fn render(format: SearchFormat, value: T, text: impl FnOnce(&T) -> String) -> Self {
+        match format {
+            SearchFormat::Json => SearchOutput::Json(value),
+            SearchFormat::Llm => SearchOutput::Text(text(&value)),
+        }
+    }
+
+    /// The structured results, if JSON was requested.
+    pub fn into_json(self) -> Option<T> {
+        match self {
+            SearchOutput::Json(value) => Some(value),
+            SearchOutput::Text(_) => None,
+        }
+    }
+}
+
+/// Request for search_skills tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SearchSkillsRequest {
+    /// Search query string.
+    pub query: String,
+    /// Paging and matching parameters.
+    #[serde(flatten)]
+    pub params: SearchParams,
+}
+
+/// Search skills by metadata.
+pub fn search_skills(
+    ctx: &ServiceContext,
+    req: SearchSkillsRequest,
+) -> Result<SearchOutput<SearchResults>, ErrorResponse> {
+    ctx.track_tool_call("search_skills");
+
+    let format = req.params.format()?;
+    let options = req.params.to_options(ctx)?;
+
+    let mut results = ctx.search.search_skills(&req.query, options);
+    ctx.sessions
+        .flag_results(req.params.session(), &mut results);
+    ctx.track_search(req.params.session_id.as_deref(), "search_skills", &results);
+
+    Ok(SearchOutput::render(
+        format,
+        results,
+        SearchResults::to_compact_text,
+    ))
+}
+
+// ============================================================================
+// Tool: search_content
+// ============================================================================
+
+/// Request for search_content tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SearchContentRequest {
+    /// Search query string for full-text search.
+    pub query: String,
+    /// Paging and matching parameters.
+    #[serde(flatten)]
+    pub params: SearchParams,
+    /// Return each domain once with its top hits nested. `limit` then
+    /// applies to domains, and paging parameters are ignored.
+    #[serde(default)]
+    pub group_by_domain: bool,
+    /// Hits kept per domain when grouping (default 3).
+    #[serde(default)]
+    pub per_domain: Option<usize>,
+}
+
+/// Response for search_content tool.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum SearchContentResponse {
+    /// Flat ranked list of file-level matches.
+    Flat(SearchResults),
+    /// Matches grouped by skill domain.
+    Grouped(GroupedSearchResults),
+}
+
+impl SearchContentResponse {
+    fn to_compact_text(&self) -> String {
+        match self {
+            SearchContentResponse::Flat(results) => results.to_compact_text(),
+            SearchContentResponse::Grouped(grouped) => grouped.to_compact_text(),
+        }
+    }
+}
+
+/// Search content by full-text matching.
+pub fn search_content(
+    ctx: &ServiceContext,
+    req: SearchContentRequest,
+) -> Result<SearchOutput<SearchContentResponse>, ErrorResponse> {
+    ctx.track_tool_call("search_content");
+
+    let format = req.params.format()?;
+    let mut options = req.params.to_options(ctx)?;
+
+    if req.group_by_domain {
+        options.offset = None;
+        let per_domain = req.per_domain.unwrap_or(3);
+
+        let mut grouped = ctx
+            .search
+            .search_content_grouped(&req.query, options, per_domain);
+        ctx.sessions
+            .flag_grouped(req.params.session(), &mut grouped);
+
+        let total: usize = grouped.groups.iter().map(|g| g.total_matches).sum();
+        ctx.stats.write().record_search(req.query.clone(), total);
+
+        if let Some(journal) = &ctx.journal {
+            journal.record(&TraceEvent::search(
+                req.params.session(),
+                "search_content",
+                &req.query,
+                total,
+                grouped.groups.iter().map(|g| g.domain.clone()),
+            ));
+        }
+
+        return Ok(SearchOutput::render(
+            format,
+            SearchContentResponse::Grouped(grouped),
+            SearchContentResponse::to_compact_text,
+        ));
+    }
+
+    let mut results = ctx.search.search_content(&req.query, options);
+    ctx.sessions
+        .flag_results(req.params.session(), &mut results);
+    ctx.track_search(req.params.session_id.as_deref(), "search_content", &results);
+
+    Ok(SearchOutput::render(
+        format,
+        SearchContentResponse::Flat(results),
+        SearchContentResponse::to_compact_text,
+    ))
+}
+
+// ============================================================================
+// Tool: search_batch
+// ============================================================================
+
+/// Request for search_batch tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SearchBatchRequest {
+    /// Search query strings.
+    pub queries: Vec<String>,
+    /// Which index to search (defaults to skill metadata).
+    #[serde(default)]
+    pub scope: SearchScope,
+    /// Matching parameters applied to every query. `limit` is per query;
+    /// paging is not supported.
+    #[serde(flatten)]
+    pub params: SearchParams,
+}
+
+/// Response for search_batch tool.
+#[derive(Debug, Serialize)]
+pub struct SearchBatchResponse {
+    /// Results for each distinct query, keyed by query.
+    pub results: BTreeMap<String, SearchResults>,
+}
+
+impl SearchBatchResponse {
+    fn to_compact_text(&self) -> String {
+        self.results
+            .values()
+            .map(SearchResults::to_compact_text)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Run several searches in one call.
+pub fn search_batch(
+    ctx: &ServiceContext,
+    req: SearchBatchRequest,
+) -> Result<SearchOutput<SearchBatchResponse>, ErrorResponse> {
+    ctx.track_tool_call("search_batch");
+
+    check_batch_queries(&req.queries).map_err(ErrorResponse::invalid_params)?;
+    let format = req.params.format()?;
+    let mut options = req.params.to_options(ctx)?;
+    options.offset = None;
+
+    let mut results = ctx.search.search_batch(&req.queries, req.scope, &options);
+    for result in results.values_mut() {
+        ctx.sessions.flag_results(req.params.session(), result);
+    }
+
+    for result in results.values() {
+        ctx.track_search(req.params.session_id.as_deref(), "search_batch", result);
+    }
+
+    Ok(SearchOutput::render(
+        format,
+        SearchBatchResponse { results },
+        SearchBatchResponse::to_compact_text,
+    ))
+}
+
+// ============================================================================
+// Tool: mark_skill_loaded
+// ============================================================================
+
+/// Request for mark_skill_loaded tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct MarkSkillLoadedRequest {
+    /// Session the skills were loaded into (defaults to a shared session).
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// Skill ids: `domain`, or `domain:sub_skill` for a sub-skill.
+    pub skills: Vec<String>,
+}
+
+/// Response for mark_skill_loaded and get_loaded_skills tools.
+#[derive(Debug, Serialize)]
+pub struct LoadedSkillsResponse {
+    /// The session.
+    pub session_id: String,
+    /// Every skill recorded as loaded in the session, sorted.
+    pub skills: Vec<String>,
+}
+
+/// Record skills a session has already injected into its context.
+pub fn mark_skill_loaded(
+    ctx: &ServiceContext,
+    req: MarkSkillLoadedRequest,
+) -> Result<LoadedSkillsResponse, ErrorResponse> {
+    ctx.track_tool_call("mark_skill_loaded");
+
+    let index = ctx.indexer.get_skill_index();
+    for id in &req.skills {
+        let (domain, sub_skill) = match id.split_once(':') {
+            Some((domain, sub)) => (domain, Some(sub)),
+            None => (id.as_str(), None),
+        };
+
+        let known = index.find(domain).is_some_and(|meta| match sub_skill {
+            Some(sub) => meta.sub_skill_names().contains(&sub),
+            None => true,
+        });
+        if !known {
+            return Err(ErrorResponse::not_found(format!(
+                "Skill '{}' not found",
+                id
+            )));
+        }
+    }
+
+    let session_id = req
+        .session_id
+        .unwrap_or_else(|| DEFAULT_SESSION.to_string());
+    let skills = ctx.sessions.mark_loaded(&session_id, req.skills);
+
+    Ok(LoadedSkillsResponse { session_id, skills })
+}
+
+// ============================================================================
+// Tool: get_loaded_skills
+// ============================================================================
+
+/// Request for get_loaded_skills tool.
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct GetLoadedSkillsRequest {
+    /// Session to list (defaults to a shared session).
+    #[serde(default)]
+    pub session_id: Option<String>,
+}
+
+/// List the skills a session has recorded as loaded.
+pub fn get_loaded_skills(
+    ctx: &ServiceContext,
+    req: GetLoadedSkillsRequest,
+) -> LoadedSkillsResponse {
+    ctx.track_tool_call("get_loaded_skills");
+
+    let session_id = req
+        .session_id
+        .unwrap_or_else(|| DEFAULT_SESSION.to_string());
+    let skills = ctx.sessions.loaded(&session_id);
+
+    LoadedSkillsResponse { session_id, skills }
+}
+
+// ============================================================================
+// Tool: diff_index
+// ============================================================================
+
+/// Response for diff_index tool.
+#[derive(Debug, Serialize)]
+pub struct DiffIndexResponse {
+    /// Whether the in-memory index matches disk.
+    pub in_sync: bool,
+    /// What differs.
+    #[serde(flatten)]
+    pub diff: IndexDiff,
+}
+
+/// Rescan disk without touching the index and report any divergence.
+pub fn diff_index(ctx: &ServiceContext) -> Result<DiffIndexResponse, ErrorResponse> {
+    ctx.track_tool_call("diff_index");
+
+    let diff = ctx.indexer.diff_with_disk().map_err(ErrorResponse::from)?;
+
+    Ok(DiffIndexResponse {
+        in_sync: diff.is_empty(),
+        diff,
+    })
+}
+
+// ============================================================================
+// Tool: create_skill
+// ============================================================================
+
+/// Request for create_skill tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateSkillRequest {
+    /// Name of the new skill, optionally namespaced like `frontend/forms`.
+    pub name: String,
+    /// Skill description.
+    pub description: String,
+    /// Tags; the template's tags are used when empty.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Built-in or `_templates/` template name. Defaults to `standard`.
+    #[serde(default)]
+    pub template: Option<String>,
+    /// Values for the template's placeholders.
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+    /// Write a single `name.md` with frontmatter instead of a directory.
+    #[serde(default)]
+    pub flat: bool,
+}
+
+/// Response for create_skill tool.
+#[derive(Debug, Serialize)]
+pub struct CreateSkillResponse {
+    /// Name of the created skill.
+    pub name: String,
+    /// Template the skill was created from.
+    pub template: String,
+    /// Created files, relative to the skills directory.
+    pub files: Vec<String>,
+}
+
+/// Create a skill from a template.
+pub fn create_skill(
+    ctx: &ServiceContext,
+    req: CreateSkillRequest,
+) -> Result<CreateSkillResponse, ErrorResponse> {
+    ctx.track_tool_call("create_skill");
+
+    if req.description.trim().is_empty() {
+        return Err(ErrorResponse::invalid_params(
+            "Description must not be empty",
+        ));
+    }
+
+    let template_name = req
+        .template
+        .as_deref()
+        .unwrap_or(SkillTemplate::default().name());
+    let template = ctx
+        .indexer
+        .load_template(template_name)
+        .map_err(ErrorResponse::from)?;
+    let skill = template.render(&req.name, &req.description, &req.tags, &req.variables)?;
+
+    let meta = SkillMeta {
+        name: req.name.clone(),
+        description: req.description,
+        tags: skill.tags.clone(),
+        ..Default::default()
+    };
+    let files = ctx
+        .indexer
+        .create_skill(&meta, &skill, req.flat)
+        .map_err(ErrorResponse::from)?;
+
+    Ok(CreateSkillResponse {
+        name: req.name,
+        template: template.name,
+        files,
+    })
+}
+
+// ============================================================================
+// Tool: scaffold_skill
+// ============================================================================
+
+/// Request for scaffold_skill tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ScaffoldSkillRequest {
+    /// Name of the new skill, optionally namespaced like `frontend/forms`.
+    pub name: String,
+    /// Skill description.
+    pub description: String,
+    /// Tags; the template's tags are used when empty.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Sub-skills to create, in order.
+    pub outline: Vec<OutlineEntry>,
+    /// Template for SKILL.md. Defaults to `standard`.
+    #[serde(default)]
+    pub template: Option<String>,
+    /// Values for the template's placeholders.
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+}
+
+/// Response for scaffold_skill tool.
+#[derive(Debug, Serialize)]
+pub struct ScaffoldSkillResponse {
+    /// Name of the created skill.
+    pub name: String,
+    /// Created sub-skills with their files.
+    pub sub_skills: Vec<SubSkillMeta>,
+    /// Created files, relative to the skills directory.
+    pub files: Vec<String>,
+}
+
+/// Create a skill with stub sub-skills from an outline.
+///
+/// The outline and resulting metadata are validated in full before
+/// anything is written, and the skill directory appears all at once.
+pub fn scaffold_skill(
+    ctx: &ServiceContext,
+    req: ScaffoldSkillRequest,
+) -> Result<ScaffoldSkillResponse, ErrorResponse> {
+    ctx.track_tool_call("scaffold_skill");
+
+    let template_name = req
+        .template
+        .as_deref()
+        .unwrap_or(SkillTemplate::default().name());
+    let template = ctx
+        .indexer
+        .load_template(template_name)
+        .map_err(ErrorResponse::from)?
+        .with_outline(&req.outline)?;
+    let skill = template.render(&req.name, &req.description, &req.tags, &req.variables)?;
+
+    let sub_skills: Vec<SubSkillMeta> = skill.sub_skills.iter().map(|(s, _)| s.clone()).collect();
+    let meta = SkillMeta {
+        name: req.name.clone(),
+        description: req.description,
+        tags: skill.tags.clone(),
+        sub_skills: Some(sub_skills.clone()),
+        ..Default::default()
+    };
+    validate_meta(&meta).map_err(|errors| ErrorResponse::invalid_params(errors.join("; ")))?;
+
+    let files = ctx
+        .indexer
+        .create_skill(&meta, &skill, false)
+        .map_err(ErrorResponse::from)?;
+
+    Ok(ScaffoldSkillResponse {
+        name: req.name,
+        sub_skills,
+        files,
+    })
+}
+
+// ============================================================================
+// Tool: update_skill
+// ============================================================================
+
+/// Request for update_skill tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UpdateSkillRequest {
+    /// Name of the skill to update.
+    pub name: String,
+    /// New description.
+    #[serde(default)]
+    pub description: Option<String>,
+    /// New tags, replacing the current ones.
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    /// New SKILL.md content.
+    #[serde(default)]
+    pub content: Option<String>,
+}
+
+/// Response for update_skill tool.
+#[derive(Debug, Serialize)]
+pub struct UpdateSkillResponse {
+    /// Name of the updated skill.
+    pub name: String,
+    /// Written files, relative to the skills directory.
+    pub files: Vec<String>,
+}
+
+/// Edit a skill's description, tags or content.
+///
+/// Applies the same name, field and path checks as `PUT /api/skills/:name`.
+pub fn update_skill(
+    ctx: &ServiceContext,
+    req: UpdateSkillRequest,
+) -> Result<UpdateSkillResponse, ErrorResponse> {
+    ctx.track_tool_call("update_skill");
+
+    check_skill_name(&req.name).map_err(ErrorResponse::invalid_params)?;
+    check_skill_fields(
+        req.description.as_deref(),
+        req.content.as_deref(),
+        req.tags.as_deref(),
+    )
+    .map_err(ErrorResponse::invalid_params)?;
+
+    let skills_dir = ctx.indexer.skills_dir();
+    let files = match ctx.indexer.flat_skill_path(&req.name) {
+        Some(path) => {
+            check_skill_path(&path, skills_dir).map_err(ErrorResponse::new)?;
+            let text = fs::read_to_string(&path).map_err(|e| {
+                ErrorResponse::new(format!("Failed to read {}.md: {}", req.name, e))
+            })?;
+            let (mut meta, body) = parse_flat_skill(&req.name, &text).map_err(|e| {
+                ErrorResponse::new(format!("Failed to parse {}.md: {}", req.name, e))
+            })?;
+
+            if let Some(description) = req.description {
+                meta.description = description;
+            }
+            if let Some(tags) = req.tags {
+                meta.tags = tags;
+            }
+            let content = req.content.as_deref().unwrap_or(body);
+
+            let rendered = render_flat_skill(&meta, content);
+            ctx.indexer
+                .check_quota(&req.name, &[(path.clone(), rendered.len() as u64)])
+                .map_err(ErrorResponse::from)?;
+            fs::write(&path, rendered).map_err(|e| {
+                ErrorResponse::new(format!("Failed to write {}.md: {}", req.name, e))
+            })?;
+            vec![format!("{}.md", req.name)]
+        }
+        None => {
+            let skill_dir = skills_dir.join(&req.name);
+            check_skill_path(&skill_dir, skills_dir).map_err(ErrorResponse::new)?;
+            if !skill_dir.is_dir() {
+                return Err(ErrorResponse::not_found(format!(
+                    "Skill '{}' not found",
+                    req.name
+                )));
+            }
+
+            let meta_path = skill_dir.join("_meta.json");
+            let meta_content = fs::read_to_string(&meta_path)
+                .map_err(|e| ErrorResponse::new(format!("Failed to read _meta.json: {}", e)))?;
+            let mut meta: SkillMeta = serde_json::from_str(&meta_content)
+                .map_err(|e| ErrorResponse::new(format!("Failed to parse _meta.json: {}", e)))?;
+
+            if let Some(description) = req.description {
+                meta.description = description;
+            }
+            if let Some(tags) = req.tags {
+                meta.tags = tags;
+            }
+
+            let meta_json = serde_json::to_string_pretty(&meta)
+                .map_err(|e| ErrorResponse::new(e.to_string()))?;
+            let skill_md = skill_dir.join("SKILL.md");
+            let mut writes = vec![(meta_path.clone(), meta_json.len() as u64)];
+            if let Some(content) = &req.content {
+                writes.push((skill_md.clone(), content.len() as u64));
+            }
+            ctx.indexer
+                .check_quota(&req.name, &writes)
+                .map_err(ErrorResponse::from)?;
+
+            fs::write(&meta_path, meta_json)
+                .map_err(|e| ErrorResponse::new(format!("Failed to write _meta.json: {}", e)))?;
+            let mut files = vec![format!("{}/_meta.json", req.name)];
+            if let Some(content) = req.content {
+                fs::write(&skill_md, content)
+                    .map_err(|e| ErrorResponse::new(format!("Failed to write SKILL.md: {}", e)))?;
+                files.push(format!("{}/SKILL.md", req.name));
+            }
+            files
+        }
+    };
+
+    ctx.indexer
+        .update_skill(&req.name)
+        .map_err(ErrorResponse::from)?;
+
+    Ok(UpdateSkillResponse {
+        name: req.name,
+        files,
+    })
+}
+
+// ============================================================================
+// Tool: delete_skill
+// ============================================================================
+
+/// Request for delete_skill tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct DeleteSkillRequest {
+    /// Name of the skill to delete.
+    pub name: String,
+}
+
+/// Response for delete_skill tool.
+#[derive(Debug, Serialize)]
+pub struct DeleteSkillResponse {
+    /// Name of the deleted skill.
+    pub name: String,
+    /// Deleted file or directory, relative to the skills directory.
+    pub deleted: String,
+}
+
+/// Delete a skill and its files.
+///
+/// Applies the same name and path checks as `DELETE /api/skills/:name`.
+pub fn delete_skill(
+    ctx: &ServiceContext,
+    req: DeleteSkillRequest,
+) -> Result<DeleteSkillResponse, ErrorResponse> {
+    ctx.track_tool_call("delete_skill");
+
+    check_skill_name(&req.name).map_err(ErrorResponse::invalid_params)?;
+
+    let skills_dir = ctx.indexer.skills_dir();
+    let deleted = match ctx.indexer.flat_skill_path(&req.name) {
+        Some(path) => {
+            check_skill_path(&path, skills_dir).map_err(ErrorResponse::new)?;
+            fs::remove_file(&path)
+                .map_err(|e| ErrorResponse::new(format!("Failed to delete skill: {}", e)))?;
+            format!("{}.md", req.name)
+        }
+        None => {
+            let skill_dir = skills_dir.join(&req.name);
+            check_skill_path(&skill_dir, skills_dir).map_err(ErrorResponse::new)?;
+            if !skill_dir.is_dir() {
+                return Err(ErrorResponse::not_found(format!(
+                    "Skill '{}' not found",
+                    req.name
+                )));
+            }
+            fs::remove_dir_all(&skill_dir)
+                .map_err(|e| ErrorResponse::new(format!("Failed to delete skill: {}", e)))?;
+            format!("{}/", req.name)
+        }
+    };
+
+    ctx.indexer
+        .remove_skill(&req.name)
+        .map_err(ErrorResponse::from)?;
+
+    Ok(DeleteSkillResponse {
+        name: req.name,
+        deleted,
+    })
+}
+
+// ============================================================================
+// Tool: reload_index
+// ============================================================================
+
+/// Response for reload_index tool.
+#[derive(Debug, Serialize)]
+pub struct ReloadIndexResponse {
+    /// Whether the reload succeeded.
+    pub success: bool,
+    /// Number of skills in the index after reload.
+    pub skill_count: usize,
+    /// Number of content entries in the index after reload.
+    pub content_entries: usize,
+    /// Error message if reload failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Reload the skill index from disk.
+pub fn reload_index(ctx: &ServiceContext) -> ReloadIndexResponse {
+    reload_index_with_progress(ctx, |_, _| {})
+}
+
+/// Reload like [`reload_index`], calling `progress` with the number of
+/// skills scanned so far and the total.
+pub fn reload_index_with_progress(
+    ctx: &ServiceContext,
+    progress: impl Fn(usize, usize),
+) -> ReloadIndexResponse {
+    ctx.track_tool_call("reload_index");
+
+    match ctx.indexer.reload_with_progress(progress) {
+        Ok(()) => {
+            let skill_index = ctx.indexer.get_skill_index();
+            let content_index = ctx.indexer.get_content_index();
+
+            ReloadIndexResponse {
+                success: true,
+                skill_count: skill_index.len(),
+                content_entries: content_index.len(),
+                error: None,
+            }
+        }
+        Err(e) => ReloadIndexResponse {
+            success: false,
+            skill_count: 0,
+            content_entries: 0,
+            error: Some(e.to_string()),
+        },
+    }
+}
+
+// ============================================================================
+// Tool: get_stats
+// ============================================================================
+
+/// Get usage statistics.
+pub fn get_stats(ctx: &ServiceContext) -> UsageStats {
+    ctx.track_tool_call("get_stats");
+    let mut stats = ctx.stats.read().clone();
+    stats.slow_searches = ctx.search.slow_searches();
+    stats.slow_reloads = ctx.indexer.slow_reloads();
+    stats
+}
+
+// ============================================================================
+// Tool: validate_skills
+// ============================================================================
+
+/// Validate all skills.
+pub fn validate_skills_tool(ctx: &ServiceContext) -> ValidationResult {
+    ctx.track_tool_call("validate_skills");
+    validate_skills(Arc::clone(&ctx.indexer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_test_context() -> (TempDir, ServiceContext) {
+        let temp_dir = TempDir::new().unwrap();
+
+        // Create a test skill
+        let skill_dir = temp_dir.path().join("test-skill");
+        fs::create_dir_all(&skill_dir).unwrap();
+        fs::write(
+            skill_dir.join("_meta.json"),
+            r#"{"name": "test-skill", "description": "A test skill"}"#,
+        )
+        .unwrap();
+        fs::write(skill_dir.join("SKILL.md"), "# Test Skill\n\nContent here.").unwrap();
+
+        let indexer = Arc::new(SkillIndexer::new(temp_dir.path()));
+        indexer.reload().unwrap();
+
+        let ctx = ServiceContext::new(indexer);
+
+        (temp_dir, ctx)
     }
 
     #[test]
@@ -349,6 +2028,7 @@ mod tests {
         let response = list_skills(&ctx);
         assert_eq!(response.total, 1);
         assert_eq!(response.skills[0].name, "test-skill");
+        assert!(response.skills[0].quality_score > 0);
     }
 
     #[test]
@@ -357,26 +2037,395 @@ mod tests {
 
         let req = GetSkillRequest {
             name: "test-skill".to_string(),
+            if_none_match: None,
+            session_id: None,
+            content_offset: 0,
         };
 
-        let response = get_skill(&ctx, req).unwrap();
+        let response = get_skill(&ctx, req).unwrap().into_content().unwrap();
         assert_eq!(response.name, "test-skill");
         assert!(response.content.contains("Test Skill"));
     }
 
+    #[test]
+    fn test_get_skill_if_none_match() {
+        let (temp, ctx) = create_test_context();
+
+        let listed = list_skills(&ctx);
+        let hash = listed.skills[0].content_hash.clone().unwrap();
+
+        let req = GetSkillRequest {
+            name: "test-skill".to_string(),
+            if_none_match: Some(hash.clone()),
+            session_id: None,
+            content_offset: 0,
+        };
+        match get_skill(&ctx, req).unwrap() {
+            GetSkillResponse::Unchanged(marker) => assert_eq!(marker.content_hash, hash),
+            _ => panic!("expected unchanged marker"),
+        }
+
+        fs::write(temp.path().join("test-skill/SKILL.md"), "# Changed").unwrap();
+        let req = GetSkillRequest {
+            name: "test-skill".to_string(),
+            if_none_match: Some(hash.clone()),
+            session_id: None,
+            content_offset: 0,
+        };
+        let content = get_skill(&ctx, req).unwrap().into_content().unwrap();
+        assert_ne!(content.content_hash, hash);
+    }
+
+    #[test]
+    fn test_get_skill_in_chunks() {
+        let (temp, ctx) = create_test_context();
+        let line = "Zürich forms need validated inputs.\n";
+        let body = line.repeat(2 * MAX_CONTENT_CHUNK_BYTES / line.len());
+        fs::write(temp.path().join("test-skill/SKILL.md"), &body).unwrap();
+        let whole = ctx.indexer.read_skill_content("test-skill").unwrap();
+
+        let request = |offset: usize| GetSkillRequest {
+            name: "test-skill".to_string(),
+            if_none_match: None,
+            session_id: None,
+            content_offset: offset,
+        };
+        let mut content = String::new();
+        let mut offset = Some(0);
+        while let Some(next) = offset {
+            let GetSkillResponse::Chunk(chunk) = get_skill(&ctx, request(next)).unwrap() else {
+                panic!("expected a chunk");
+            };
+            assert_eq!(chunk.content_offset, next);
+            assert_eq!(chunk.content_length, whole.content.len());
+            assert_eq!(chunk.content.content_hash, whole.content_hash);
+            assert!(chunk.content.content.len() <= MAX_CONTENT_CHUNK_BYTES);
+            content.push_str(&chunk.content.content);
+            offset = chunk.next_offset;
+        }
+        assert_eq!(content, whole.content);
+
+        // Offsets must fall on a character boundary within the content
+        let inside = whole.content.find('ü').unwrap() + 1;
+        let err = get_skill(&ctx, request(inside)).unwrap_err();
+        assert_eq!(err.error_code, Some(ErrorCode::InvalidParams));
+        assert!(get_skill(&ctx, request(whole.content.len() + 1)).is_err());
+    }
+
     #[test]
     fn test_search_skills() {
         let (_temp, ctx) = create_test_context();
 
         let req = SearchSkillsRequest {
             query: "test".to_string(),
-            limit: None,
+            params: SearchParams::default(),
         };
 
-        let response = search_skills(&ctx, req);
+        let response = search_skills(&ctx, req).unwrap().into_json().unwrap();
         assert!(!response.is_empty());
     }
 
+    #[test]
+    fn test_get_skills_batch_limits() {
+        let (_temp, ctx) = create_test_context();
+        let request = || GetSkillsBatchRequest {
+            requests: vec![
+                BatchRequest::skill("test-skill".to_string()),
+                BatchRequest::skill("missing".to_string()),
+                BatchRequest::skill("test-skill".to_string()),
+            ],
+            session_id: None,
+        };
+
+        let response = get_skills_batch(&ctx, request()).unwrap();
+        assert_eq!(response.results.len(), 3);
+        assert!(!response.truncated);
+        assert!(matches!(
+            response.results[0].item,
+            BatchResponseItem::Skill(_)
+        ));
+        assert!(response.results[1].item.is_error());
+
+        let size = response.results[0].item.content_len();
+        let ctx = ctx.with_batch_limits(BatchLimits {
+            max_items: 3,
+            parallelism: 2,
+            max_response_bytes: size + 1,
+        });
+        let response = get_skills_batch(&ctx, request()).unwrap();
+        assert!(response.truncated);
+        assert_eq!(response.total_bytes, size);
+        assert!(response.results[1].item.is_error());
+        assert!(response.results[2].item.is_truncated());
+
+        let ctx = ctx.with_batch_limits(BatchLimits {
+            max_items: 2,
+            ..BatchLimits::default()
+        });
+        assert!(get_skills_batch(&ctx, request()).is_err());
+    }
+
+    #[test]
+    fn test_loaded_skills_flag_search_results() {
+        let (_temp, ctx) = create_test_context();
+
+        let marked = mark_skill_loaded(
+            &ctx,
+            MarkSkillLoadedRequest {
+                session_id: Some("s1".to_string()),
+                skills: vec!["test-skill".to_string()],
+            },
+        )
+        .unwrap();
+        assert_eq!(marked.skills, vec!["test-skill".to_string()]);
+
+        let unknown = MarkSkillLoadedRequest {
+            session_id: Some("s1".to_string()),
+            skills: vec!["test-skill:missing".to_string()],
+        };
+        assert!(mark_skill_loaded(&ctx, unknown).is_err());
+
+        let search = |session: &str| {
+            let req: SearchSkillsRequest = serde_json::from_value(serde_json::json!({
+                "query": "test",
+                "session_id": session
+            }))
+            .unwrap();
+            search_skills(&ctx, req).unwrap().into_json().unwrap()
+        };
+        assert!(search("s1").results[0].already_loaded);
+        assert!(!search("s2").results[0].already_loaded);
+
+        let listed = get_loaded_skills(
+            &ctx,
+            GetLoadedSkillsRequest {
+                session_id: Some("s2".to_string()),
+            },
+        );
+        assert!(listed.skills.is_empty());
+    }
+
+    #[test]
+    fn test_journal_records_session_trace() {
+        let (temp, ctx) = create_test_context();
+        let ctx = ctx.with_journal(Journal::open(temp.path().join(".traces")).unwrap());
+
+        let req: SearchSkillsRequest = serde_json::from_value(serde_json::json!({
+            "query": "test",
+            "session_id": "s1"
+        }))
+        .unwrap();
+        search_skills(&ctx, req).unwrap();
+
+        let req = GetSkillRequest {
+            name: "test-skill".to_string(),
+            if_none_match: None,
+            session_id: Some("s1".to_string()),
+            content_offset: 0,
+        };
+        get_skill(&ctx, req).unwrap();
+
+        let trace = ctx.journal.as_ref().unwrap().trace("s1").unwrap().unwrap();
+        assert_eq!(trace.len(), 2);
+        assert!(matches!(
+            &trace[0].kind,
+            crate::mcp::TraceKind::Search { tool, results, .. }
+                if tool == "search_skills" && results == &["test-skill".to_string()]
+        ));
+        assert!(matches!(
+            &trace[1].kind,
+            crate::mcp::TraceKind::SkillLoad { skill } if skill == "test-skill"
+        ));
+    }
+
+    #[test]
+    fn test_diff_index() {
+        let (temp, ctx) = create_test_context();
+        assert!(diff_index(&ctx).unwrap().in_sync);
+
+        fs::write(temp.path().join("test-skill/SKILL.md"), "# Edited").unwrap();
+        let response = diff_index(&ctx).unwrap();
+        assert!(!response.in_sync);
+        assert_eq!(
+            response.diff.entries_changed,
+            vec!["test-skill".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_search_llm_format() {
+        let (_temp, ctx) = create_test_context();
+
+        let req: SearchSkillsRequest = serde_json::from_value(serde_json::json!({
+            "query": "test",
+            "format": "llm"
+        }))
+        .unwrap();
+        match search_skills(&ctx, req).unwrap() {
+            SearchOutput::Text(text) => {
+                assert!(text.starts_with("query: test (1-1 of 1)\n"));
+                assert!(text.contains("test-skill | "));
+            }
+            SearchOutput::Json(_) => panic!("expected text output"),
+        }
+
+        let req: SearchBatchRequest = serde_json::from_value(serde_json::json!({
+            "queries": ["test", "missing"],
+            "format": "llm"
+        }))
+        .unwrap();
+        let output = serde_json::to_value(search_batch(&ctx, req).unwrap()).unwrap();
+        let text = output.as_str().unwrap();
+        assert!(text.contains("query: missing (no results)"));
+        assert!(text.contains("query: test ("));
+
+        let req: SearchSkillsRequest = serde_json::from_value(serde_json::json!({
+            "query": "test",
+            "format": "xml"
+        }))
+        .unwrap();
+        assert!(search_skills(&ctx, req).is_err());
+    }
+
+    #[test]
+    fn test_search_batch_limits() {
+        let (_temp, ctx) = create_test_context();
+        let batch = |queries: Vec<String>| SearchBatchRequest {
+            queries,
+            scope: SearchScope::default(),
+            params: SearchParams::default(),
+        };
+
+        for queries in [
+            vec![],
+            vec!["test".to_string(), String::new()],
+            vec!["x".repeat(MAX_SEARCH_QUERY_LENGTH + 1)],
+            vec!["test".to_string(); MAX_BATCH_QUERIES + 1],
+        ] {
+            let err = search_batch(&ctx, batch(queries)).unwrap_err();
+            assert_eq!(err.error_code, Some(ErrorCode::InvalidParams));
+        }
+
+        let queries = vec!["test".to_string(); MAX_BATCH_QUERIES];
+        assert!(search_batch(&ctx, batch(queries)).is_ok());
+    }
+
+    #[test]
+    fn test_search_rejects_bad_cursor() {
+        let (_temp, ctx) = create_test_context();
+
+        let req = SearchContentRequest {
+            query: "content".to_string(),
+            params: SearchParams {
+                cursor: Some("bogus".to_string()),
+                ..Default::default()
+            },
+            group_by_domain: false,
+            per_domain: None,
+        };
+
+        assert!(search_content(&ctx, req).is_err());
+    }
+
+    #[test]
+    fn test_search_min_should_match_param() {
+        let (_temp, ctx) = create_test_context();
+
+        let req: SearchSkillsRequest = serde_json::from_value(serde_json::json!({
+            "query": "test unrelated",
+            "min_should_match": "all"
+        }))
+        .unwrap();
+        assert!(search_skills(&ctx, req)
+            .unwrap()
+            .into_json()
+            .unwrap()
+            .is_empty());
+
+        let req: SearchSkillsRequest = serde_json::from_value(serde_json::json!({
+            "query": "test unrelated",
+            "min_should_match": "most"
+        }))
+        .unwrap();
+        assert!(search_skills(&ctx, req).is_err());
+    }
+
+    #[test]
+    fn test_search_content_grouped() {
+        let (temp, ctx) = create_test_context();
+        let refs = temp.path().join("test-skill/references");
+        fs::create_dir_all(&refs).unwrap();
+        fs::write(refs.join("a.md"), "More content here").unwrap();
+        fs::write(refs.join("b.md"), "Even more content").unwrap();
+        ctx.indexer.reload().unwrap();
+
+        let req = SearchContentRequest {
+            query: "content".to_string(),
+            params: SearchParams::default(),
+            group_by_domain: true,
+            per_domain: Some(2),
+        };
+
+        match search_content(&ctx, req).unwrap().into_json().unwrap() {
+            SearchContentResponse::Grouped(grouped) => {
+                assert_eq!(grouped.groups.len(), 1);
+                assert_eq!(grouped.groups[0].domain, "test-skill");
+                assert_eq!(grouped.groups[0].total_matches, 3);
+                assert_eq!(grouped.groups[0].hits.len(), 2);
+            }
+            SearchContentResponse::Flat(_) => panic!("expected grouped results"),
+        }
+    }
+
+    #[test]
+    fn test_references_tools() {
+        let (temp, ctx) = create_test_context();
+        let refs = temp.path().join("test-skill/references");
+        fs::create_dir_all(&refs).unwrap();
+        fs::write(refs.join("api.md"), "# API").unwrap();
+
+        let listed = list_references(
+            &ctx,
+            ListReferencesRequest {
+                domain: "test-skill".to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(listed.total, 1);
+
+        let doc = get_reference(
+            &ctx,
+            GetReferenceRequest {
+                domain: "test-skill".to_string(),
+                path: listed.references[0].path.clone(),
+                session_id: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(doc.content, "# API");
+
+        let escaped = get_reference(
+            &ctx,
+            GetReferenceRequest {
+                domain: "test-skill".to_string(),
+                path: "../_meta.json".to_string(),
+                session_id: None,
+            },
+        );
+        assert!(escaped.is_err());
+    }
+
+    #[test]
+    fn test_warm_up_sets_ready() {
+        let (_temp, ctx) = create_test_context();
+
+        let started = Instant::now();
+        while !ctx.is_ready() && started.elapsed() < std::time::Duration::from_secs(5) {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert!(ctx.is_ready());
+    }
+
     #[test]
     fn test_stats_tracking() {
         let (_temp, ctx) = create_test_context();
@@ -388,6 +2437,9 @@ mod tests {
             &ctx,
             GetSkillRequest {
                 name: "test-skill".to_string(),
+                if_none_match: None,
+                session_id: None,
+                content_offset: 0,
             },
         )
         .unwrap();
@@ -397,4 +2449,514 @@ mod tests {
         assert_eq!(*stats.tool_calls.get("get_skill").unwrap(), 1);
         assert_eq!(*stats.skill_loads.get("test-skill").unwrap(), 1);
     }
+
+    #[test]
+    fn test_suggest_metadata() {
+        struct Canned;
+
+        impl LlmBackend for Canned {
+            fn name(&self) -> &str {
+                "canned"
+            }
+
+            fn complete(
+                &self,
+                _request: &crate::llm::CompletionRequest,
+            ) -> Result<String, crate::llm::LlmError> {
+                Ok(
+                    r#"{"description": "Shows how tests are set up.", "tags": ["testing"]}"#
+                        .to_string(),
+                )
+            }
+        }
+
+        let (temp, ctx) = create_test_context();
+        let before = fs::read_to_string(temp.path().join("test-skill/_meta.json")).unwrap();
+
+        let err = suggest_metadata(&ctx, SuggestMetadataRequest::default()).unwrap_err();
+        assert!(err.error.contains("No LLM backend"));
+
+        let ctx = ctx.with_llm(Arc::new(Canned));
+        let response = suggest_metadata(&ctx, SuggestMetadataRequest::default()).unwrap();
+        assert_eq!(response.proposals.len(), 1);
+        let proposal = &response.proposals[0];
+        assert_eq!(proposal.skill, "test-skill");
+        assert_eq!(proposal.current_description, "A test skill");
+        assert_eq!(proposal.description, "Shows how tests are set up.");
+        assert!(proposal.reasons.contains(&"No tags".to_string()));
+
+        // Proposals are never written back
+        let after = fs::read_to_string(temp.path().join("test-skill/_meta.json")).unwrap();
+        assert_eq!(before, after);
+
+        let req = SuggestMetadataRequest {
+            name: Some("missing".to_string()),
+            limit: None,
+        };
+        assert!(suggest_metadata(&ctx, req).is_err());
+    }
+
+    #[test]
+    fn test_ask_skills() {
+        struct Canned(bool);
+
+        impl LlmBackend for Canned {
+            fn name(&self) -> &str {
+                "canned"
+            }
+
+            fn complete(
+                &self,
+                request: &crate::llm::CompletionRequest,
+            ) -> Result<String, crate::llm::LlmError> {
+                assert!(request
+                    .prompt
+                    .contains("[1] test-skill/SKILL.md § Test Skill"));
+                if self.0 {
+                    Ok("The content is here [1].".to_string())
+                } else {
+                    Err(crate::llm::LlmError::Request("down".to_string()))
+                }
+            }
+        }
+
+        let (_temp, ctx) = create_test_context();
+        let req = || AskSkillsRequest {
+            question: "Where is the content?".to_string(),
+            limit: None,
+            domains: None,
+        };
+
+        // Passages only without a backend
+        let response = ask_skills(&ctx, req()).unwrap();
+        assert_eq!(response.passages.len(), 1);
+        assert_eq!(response.passages[0].skill, "test-skill");
+        assert_eq!(response.passages[0].text, "Content here.");
+        assert!(response.answer.is_none());
+
+        let blank = AskSkillsRequest {
+            question: "  ".to_string(),
+            limit: None,
+            domains: None,
+        };
+        assert!(ask_skills(&ctx, blank).is_err());
+
+        let ctx = ctx.with_llm(Arc::new(Canned(true)));
+        let answered = ask_skills(&ctx, req()).unwrap();
+        assert_eq!(answered.answer.as_deref(), Some("The content is here [1]."));
+        assert_eq!(answered.citations.len(), 1);
+        assert_eq!(answered.citations[0].section.as_deref(), Some("Test Skill"));
+        assert_eq!(answered.model.as_deref(), Some("canned"));
+
+        let ctx = ctx.with_llm(Arc::new(Canned(false)));
+        let failed = ask_skills(&ctx, req()).unwrap();
+        assert!(failed.answer.is_none());
+        assert_eq!(failed.passages.len(), 1);
+        assert!(failed.error.unwrap().contains("down"));
+    }
+
+    #[test]
+    fn test_summarize_skill() {
+        struct Counting(&'static str, std::sync::atomic::AtomicUsize);
+
+        impl LlmBackend for Counting {
+            fn name(&self) -> &str {
+                self.0
+            }
+
+            fn complete(
+                &self,
+                request: &crate::llm::CompletionRequest,
+            ) -> Result<String, crate::llm::LlmError> {
+                assert!(request.prompt.contains("Content here."));
+                self.1.fetch_add(1, Ordering::SeqCst);
+                Ok(format!("Summary by {}.", self.0))
+            }
+        }
+
+        let (temp, ctx) = create_test_context();
+        let req = |refresh| SummarizeSkillRequest {
+            name: "test-skill".to_string(),
+            refresh,
+        };
+        let err = summarize_skill(&ctx, req(false), None).unwrap_err();
+        assert!(err.error.contains("sampling"));
+
+        let client = Counting("client", Default::default());
+        let sampled = summarize_skill(&ctx, req(false), Some(&client)).unwrap();
+        assert_eq!(sampled.summary, "Summary by client.");
+        assert_eq!(sampled.model, "client");
+        assert!(!sampled.cached);
+
+        // Cached until SKILL.md changes, without a model to ask
+        let cached = summarize_skill(&ctx, req(false), None).unwrap();
+        assert!(cached.cached);
+        assert_eq!(cached.summary, "Summary by client.");
+        summarize_skill(&ctx, req(true), Some(&client)).unwrap();
+        assert_eq!(client.1.load(Ordering::SeqCst), 2);
+
+        fs::write(
+            temp.path().join("test-skill/SKILL.md"),
+            "# Test Skill\n\nContent here. More now.",
+        )
+        .unwrap();
+        ctx.indexer.reload().unwrap();
+        let ctx = ctx.with_llm(Arc::new(Counting("server", Default::default())));
+        let resummarized = summarize_skill(&ctx, req(false), None).unwrap();
+        assert_eq!(resummarized.model, "server");
+        assert!(!resummarized.cached);
+
+        let missing = SummarizeSkillRequest {
+            name: "missing".to_string(),
+            refresh: false,
+        };
+        assert!(summarize_skill(&ctx, missing, Some(&client)).is_err());
+    }
+
+    #[test]
+    fn test_route_task() {
+        let (temp, _) = create_test_context();
+        let forms = temp.path().join("forms");
+        fs::create_dir_all(&forms).unwrap();
+        fs::write(
+            forms.join("_meta.json"),
+            r#"{"name": "forms", "description": "Form handling patterns", "tags": ["frontend"], "sub_skills": [
+                {"name": "react", "file": "react.md", "triggers": ["useForm", "!vue"]},
+                {"name": "vue", "file": "vue.md", "triggers": ["v-model"]}
+            ]}"#,
+        )
+        .unwrap();
+        fs::write(forms.join("SKILL.md"), "# Forms\n\nBuilding forms.").unwrap();
+        fs::write(
+            forms.join("react.md"),
+            "# React\n\nValidate fields with useForm.",
+        )
+        .unwrap();
+        fs::write(
+            forms.join("vue.md"),
+            "# Vue\n\nTwo-way binding with v-model.",
+        )
+        .unwrap();
+        let indexer = Arc::new(SkillIndexer::new(temp.path()));
+        indexer.reload().unwrap();
+        let ctx = ServiceContext::new(indexer);
+
+        let req = |task: &str| RouteTaskRequest {
+            task: task.to_string(),
+            limit: None,
+            min_confidence: None,
+            session_id: None,
+        };
+
+        let response = route_task(&ctx, req("Add useForm validation to the signup page")).unwrap();
+        let top = &response.routing.routes[0];
+        assert_eq!(
+            (top.skill.as_str(), top.sub_skill.as_deref()),
+            ("forms", Some("react"))
+        );
+        assert!(top.reason.contains("trigger 'useForm'"));
+        assert!(top.confidence > 0.5 && top.confidence <= 1.0);
+
+        // A negative trigger sends the task elsewhere
+        let response = route_task(&ctx, req("Bind inputs with v-model in vue")).unwrap();
+        assert_eq!(response.routing.routes[0].sub_skill.as_deref(), Some("vue"));
+
+        let response = route_task(&ctx, req("quantum chromodynamics")).unwrap();
+        assert!(response.routing.routes.is_empty());
+        assert!(response.routing.no_confident_match);
+        assert!(route_task(&ctx, req(" ")).is_err());
+        assert_eq!(ctx.stats.read().tool_calls.get("route_task"), Some(&4));
+    }
+
+    #[test]
+    fn test_confidence_thresholds() {
+        let (temp, _) = create_test_context();
+        let forms = temp.path().join("forms");
+        fs::create_dir_all(&forms).unwrap();
+        fs::write(
+            forms.join("_meta.json"),
+            r#"{"name": "forms", "description": "Form handling patterns", "tags": ["frontend"]}"#,
+        )
+        .unwrap();
+        fs::write(forms.join("SKILL.md"), "# Forms\n\nBuilding forms.").unwrap();
+        let indexer = Arc::new(SkillIndexer::new(temp.path()));
+        indexer.reload().unwrap();
+        let ctx = ServiceContext::new(indexer);
+
+        // The route exists but is not confident enough
+        let strict = ctx.with_confidence_thresholds(ConfidenceThresholds {
+            route: 0.99,
+            search: 0.99,
+        });
+        let response = route_task(
+            &strict,
+            RouteTaskRequest {
+                task: "Build a form".to_string(),
+                limit: None,
+                min_confidence: None,
+                session_id: None,
+            },
+        )
+        .unwrap();
+        assert!(response.routing.routes.is_empty());
+        assert!(response.routing.no_confident_match);
+        let explore = response.routing.explore.unwrap();
+        assert_eq!(explore.candidates, vec!["forms".to_string()]);
+        assert_eq!(explore.tags, vec!["frontend".to_string()]);
+
+        let search = |min_confidence: Option<f64>| {
+            search_skills(
+                &strict,
+                SearchSkillsRequest {
+                    query: "forms".to_string(),
+                    params: SearchParams {
+                        min_confidence,
+                        ..Default::default()
+                    },
+                },
+            )
+        };
+        let results = search(None).unwrap().into_json().unwrap();
+        assert!(results.is_empty());
+        assert!(results.no_confident_match);
+        assert_eq!(
+            results.explore.unwrap().candidates,
+            vec!["forms".to_string()]
+        );
+
+        // A per-call threshold overrides the server's
+        let results = search(Some(0.0)).unwrap().into_json().unwrap();
+        assert_eq!(results.top().unwrap().domain, "forms");
+        assert!(!results.no_confident_match);
+
+        assert!(search(Some(1.5)).is_err());
+    }
+
+    #[test]
+    fn test_create_skill_from_templates() {
+        let (temp, ctx) = create_test_context();
+
+        let req = |name: &str, template: Option<&str>| CreateSkillRequest {
+            name: name.to_string(),
+            description: "Work with APIs.".to_string(),
+            tags: Vec::new(),
+            template: template.map(str::to_string),
+            variables: HashMap::new(),
+            flat: false,
+        };
+
+        let response = create_skill(&ctx, req("api-client", Some("with-sub-skills"))).unwrap();
+        assert_eq!(response.template, "with_sub_skills");
+        assert_eq!(
+            response.files,
+            vec![
+                "api-client/_meta.json",
+                "api-client/SKILL.md",
+                "api-client/references/example.md"
+            ]
+        );
+        let meta = ctx.indexer.get_skill_meta("api-client").unwrap();
+        assert_eq!(meta.sub_skills.unwrap()[0].name, "example");
+
+        // Custom templates from _templates/
+        let templates = temp.path().join(TEMPLATES_DIR);
+        fs::create_dir_all(&templates).unwrap();
+        fs::write(
+            templates.join("rest.md"),
+            "---\ndescription: REST API\ntags: [api]\n---\n# {{title}}\n\nBase: {{base_url}}\n",
+        )
+        .unwrap();
+
+        assert!(create_skill(&ctx, req("stripe", Some("rest"))).is_err());
+
+        let mut with_vars = req("stripe", Some("rest"));
+        with_vars
+            .variables
+            .insert("base_url".to_string(), "https://api.stripe.com".to_string());
+        create_skill(&ctx, with_vars).unwrap();
+        assert_eq!(
+            fs::read_to_string(temp.path().join("stripe/SKILL.md")).unwrap(),
+            "# Stripe\n\nBase: https://api.stripe.com\n"
+        );
+        assert_eq!(
+            ctx.indexer.get_skill_meta("stripe").unwrap().tags,
+            vec!["api"]
+        );
+
+        // Templates aren't skills
+        assert!(ctx.indexer.get_skill_meta("_templates").is_none());
+
+        assert!(create_skill(&ctx, req("stripe", None)).is_err());
+        assert!(create_skill(&ctx, req("other", Some("missing"))).is_err());
+        assert!(create_skill(&ctx, req("../escape", None)).is_err());
+    }
+
+    #[test]
+    fn test_scaffold_skill() {
+        let (temp, ctx) = create_test_context();
+
+        let outline: Vec<OutlineEntry> = serde_json::from_str(
+            r#"[
+                {"name": "react", "description": "Forms in React", "triggers": ["jsx"]},
+                {"name": "vue", "description": "Forms in Vue"}
+            ]"#,
+        )
+        .unwrap();
+        let req = |name: &str, outline: Vec<OutlineEntry>| ScaffoldSkillRequest {
+            name: name.to_string(),
+            description: "Building forms.".to_string(),
+            tags: vec!["forms".to_string()],
+            outline,
+            template: None,
+            variables: HashMap::new(),
+        };
+
+        let response = scaffold_skill(&ctx, req("forms", outline.clone())).unwrap();
+        assert_eq!(
+            response.files,
+            vec![
+                "forms/_meta.json",
+                "forms/SKILL.md",
+                "forms/references/react.md",
+                "forms/references/vue.md"
+            ]
+        );
+        assert_eq!(response.sub_skills[0].triggers, vec!["jsx"]);
+
+        let sub = get_sub_skill(
+            &ctx,
+            GetSubSkillRequest {
+                domain: "forms".to_string(),
+                sub_skill: "vue".to_string(),
+                session_id: None,
+            },
+        )
+        .unwrap();
+        assert!(sub.content.starts_with("# Vue\n\nForms in Vue"));
+
+        // Invalid outlines and names write nothing
+        let mut bad = outline.clone();
+        bad[1].name = "Vue Forms".to_string();
+        assert!(scaffold_skill(&ctx, req("tables", bad)).is_err());
+        assert!(scaffold_skill(&ctx, req("tables", Vec::new())).is_err());
+        assert!(scaffold_skill(&ctx, req("Tables", outline.clone())).is_err());
+        assert!(!temp.path().join("tables").exists());
+        assert!(!temp.path().join("Tables").exists());
+
+        assert!(scaffold_skill(&ctx, req("forms", outline)).is_err());
+        let leftovers: Vec<_> = fs::read_dir(temp.path())
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().ends_with(".tmp"))
+            .collect();
+        assert!(leftovers.is_empty());
+    }
+
+    #[test]
+    fn test_update_and_delete_skill() {
+        let (temp, ctx) = create_test_context();
+
+        let response = update_skill(
+            &ctx,
+            UpdateSkillRequest {
+                name: "test-skill".to_string(),
+                description: Some("An updated skill".to_string()),
+                tags: Some(vec!["testing".to_string()]),
+                content: Some("# Updated\n\nNew content.".to_string()),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            response.files,
+            vec!["test-skill/_meta.json", "test-skill/SKILL.md"]
+        );
+        let meta = ctx.indexer.get_skill_meta("test-skill").unwrap();
+        assert_eq!(meta.description, "An updated skill");
+        assert_eq!(meta.tags, vec!["testing"]);
+        assert_eq!(
+            fs::read_to_string(temp.path().join("test-skill/SKILL.md")).unwrap(),
+            "# Updated\n\nNew content."
+        );
+
+        // Flat skills keep their frontmatter and body together
+        fs::write(
+            temp.path().join("notes.md"),
+            "---\ndescription: Notes\n---\n# Notes\n",
+        )
+        .unwrap();
+        ctx.indexer.reload().unwrap();
+        let req = |name: &str| UpdateSkillRequest {
+            name: name.to_string(),
+            description: None,
+            tags: Some(vec!["notes".to_string()]),
+            content: None,
+        };
+        assert_eq!(
+            update_skill(&ctx, req("notes")).unwrap().files,
+            vec!["notes.md"]
+        );
+        let text = fs::read_to_string(temp.path().join("notes.md")).unwrap();
+        assert!(text.contains("description: \"Notes\""));
+        assert!(text.ends_with("# Notes\n"));
+        assert_eq!(
+            ctx.indexer.get_skill_meta("notes").unwrap().tags,
+            vec!["notes"]
+        );
+
+        // Traversal, unknown skills and oversized fields are rejected
+        assert!(update_skill(&ctx, req("../outside")).is_err());
+        assert!(update_skill(&ctx, req("missing")).is_err());
+        let mut long = req("test-skill");
+        long.description = Some("x".repeat(crate::validation::MAX_DESCRIPTION_LENGTH + 1));
+        assert!(update_skill(&ctx, long).is_err());
+
+        let delete = |name: &str| {
+            delete_skill(
+                &ctx,
+                DeleteSkillRequest {
+                    name: name.to_string(),
+                },
+            )
+        };
+        assert!(delete("../test-skill").is_err());
+        assert_eq!(delete("notes").unwrap().deleted, "notes.md");
+        assert_eq!(delete("test-skill").unwrap().deleted, "test-skill/");
+        assert!(!temp.path().join("test-skill").exists());
+        assert!(!ctx.indexer.skill_exists("test-skill"));
+        assert!(ctx.indexer.get_skill_meta("test-skill").is_none());
+        assert!(delete("test-skill").is_err());
+    }
+
+    #[cfg(feature = "scripts")]
+    #[test]
+    fn test_run_skill_script() {
+        let (temp, ctx) = create_test_context();
+        let scripts = temp.path().join("test-skill").join("scripts");
+        fs::create_dir_all(&scripts).unwrap();
+        fs::write(
+            scripts.join("greet.sh"),
+            "# Say hello.\necho \"hello $1\"\n",
+        )
+        .unwrap();
+
+        let req = || RunSkillScriptRequest {
+            skill: "test-skill".to_string(),
+            script: "greet.sh".to_string(),
+            args: vec!["world".to_string()],
+        };
+
+        let err = run_skill_script(&ctx, req()).unwrap_err();
+        assert!(err.error.contains("disabled"));
+
+        let ctx = ctx.with_script_sandbox(ScriptSandbox::default());
+        let run = run_skill_script(&ctx, req()).unwrap();
+        assert_eq!(run.stdout, "hello world\n");
+        assert_eq!(run.script, "scripts/greet.sh");
+
+        let missing = RunSkillScriptRequest {
+            script: "scripts/missing.sh".to_string(),
+            ..req()
+        };
+        assert!(run_skill_script(&ctx, missing).is_err());
+    }
 }
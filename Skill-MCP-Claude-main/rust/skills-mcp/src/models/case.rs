@@ -0,0 +1,144 @@
+//! Field name case for clients of the TypeScript server.
+//!
+//! The TypeScript server wrote fields in camelCase (`subSkills`,
+//! `hasReferences`) where the models use snake_case. Models read either
+//! spelling through serde aliases; [`FieldCase::Camel`] rewrites responses
+//! for clients that still expect the old names.
+//!
+//! Only the fields the TypeScript server had are renamed. Fields added
+//! since never had a camelCase spelling, and other keys, like those of
+//! stats maps or a previewed JSON file, belong to the data.
+
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Model fields the TypeScript server wrote in camelCase.
+const LEGACY_FIELDS: &[&str] = &[
+    "file_count",
+    "has_references",
+    "last_updated",
+    "match_type",
+    "result_count",
+    "skill_count",
+    "skill_loads",
+    "skills_checked",
+    "start_time",
+    "sub_skill",
+    "sub_skills",
+    "tool_calls",
+    "validation_errors",
+    "word_count",
+];
+
+/// How field names are written in responses.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldCase {
+    /// `sub_skills`, as the models serialize.
+    #[default]
+    Snake,
+    /// `subSkills`, as the TypeScript server wrote them.
+    Camel,
+}
+
+impl FieldCase {
+    /// Rewrite the field names in `value` into this case.
+    pub fn apply(self, value: &mut Value) {
+        if self == FieldCase::Camel {
+            camel_case_keys(value);
+        }
+    }
+}
+
+/// Rename the TypeScript server's fields in `value`, at any depth, from
+/// snake_case to camelCase.
+pub fn camel_case_keys(value: &mut Value) {
+    match value {
+        Value::Array(items) => items.iter_mut().for_each(camel_case_keys),
+        Value::Object(map) => {
+            let entries = std::mem::take(map);
+            for (key, mut value) in entries {
+                camel_case_keys(&mut value);
+                let key = if LEGACY_FIELDS.contains(&key.as_str()) {
+                    camel_case(&key).unwrap_or(key)
+                } else {
+                    key
+                };
+                map.insert(key, value);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// `key` in camelCase, or `None` if it isn't a snake_case identifier with
+/// more than one word.
+fn camel_case(key: &str) -> Option<String> {
+    let identifier = key.starts_with(|c: char| c.is_ascii_lowercase())
+        && key
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if !identifier || !key.contains('_') {
+        return None;
+    }
+
+    let mut out = String::with_capacity(key.len());
+    let mut upper = false;
+    for c in key.chars() {
+        match c {
+            '_' => upper = true,
+            c if upper => {
+                out.push(c.to_ascii_uppercase());
+                upper = false;
+            }
+            c => out.push(c),
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_camel_case() {
+        assert_eq!(camel_case("sub_skills").as_deref(), Some("subSkills"));
+        assert_eq!(
+            camel_case("has_references").as_deref(),
+            Some("hasReferences")
+        );
+        assert_eq!(camel_case("name"), None);
+        assert_eq!(camel_case("references/api_notes.md"), None);
+        assert_eq!(camel_case("Sub_skills"), None);
+    }
+
+    #[test]
+    fn test_camel_case_keys() {
+        let mut value = json!({
+            "sub_skills": [{"sub_skill": "react", "word_count": 3}],
+            "has_references": true,
+            "tool_calls": {"get_skill": 2},
+            "results": {"my_query": {"total_matches": 1}},
+            "files": {"references/api_notes.md": 10},
+            "preview": {"rows": [{"user_id": 7}]},
+        });
+        FieldCase::Camel.apply(&mut value);
+        assert_eq!(
+            value,
+            json!({
+                "subSkills": [{"subSkill": "react", "wordCount": 3}],
+                "hasReferences": true,
+                "toolCalls": {"get_skill": 2},
+                "results": {"my_query": {"total_matches": 1}},
+                "files": {"references/api_notes.md": 10},
+                "preview": {"rows": [{"user_id": 7}]},
+            })
+        );
+
+        let mut value = json!({"sub_skills": []});
+        FieldCase::Snake.apply(&mut value);
+        assert_eq!(value, json!({"sub_skills": []}));
+    }
+}
@@ -1,6 +1,17 @@
 //! Content retrieval types.
 
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::{ImageAsset, SkillScript};
+
+/// Hex SHA-256 of some content, used to detect unchanged skills.
+pub fn content_hash(content: &str) -> String {
+    Sha256::digest(content.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
 
 /// Full skill content response.
 ///
@@ -13,12 +24,26 @@ pub struct SkillContent {
     /// SKILL.md content.
     pub content: String,
 
-    /// Available sub-skill names.
-    #[serde(default)]
+    /// Available sub-skills, nested ones by path (e.g. `react/hooks`).
+    #[serde(default, alias = "subSkills")]
     pub sub_skills: Vec<String>,
 
     /// Whether this skill has a references directory.
+    #[serde(alias = "hasReferences")]
     pub has_references: bool,
+
+    /// Scripts bundled in the skill's `scripts/` directory.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scripts: Vec<SkillScript>,
+
+    /// Images in the skill's `references/` directory, with dimensions.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<ImageAsset>,
+
+    /// Hash of `content`; pass it back as `if_none_match` to skip
+    /// re-fetching an unchanged skill.
+    #[serde(default, alias = "contentHash")]
+    pub content_hash: String,
 }
 
 impl SkillContent {
@@ -26,9 +51,12 @@ impl SkillContent {
     pub fn new(name: String, content: String) -> Self {
         Self {
             name,
+            content_hash: content_hash(&content),
             content,
             sub_skills: Vec::new(),
             has_references: false,
+            scripts: Vec::new(),
+            images: Vec::new(),
         }
     }
 
@@ -43,6 +71,92 @@ impl SkillContent {
         self.has_references = has_references;
         self
     }
+
+    /// Set bundled scripts.
+    pub fn with_scripts(mut self, scripts: Vec<SkillScript>) -> Self {
+        self.scripts = scripts;
+        self
+    }
+
+    /// Set image assets.
+    pub fn with_images(mut self, images: Vec<ImageAsset>) -> Self {
+        self.images = images;
+        self
+    }
+
+    /// Keep at most `max_bytes` of the content starting at byte `offset`,
+    /// ending on a character boundary.
+    ///
+    /// Returns `None` if `offset` is past the end or inside a character.
+    pub fn into_chunk(mut self, offset: usize, max_bytes: usize) -> Option<SkillContentChunk> {
+        let content_length = self.content.len();
+        if !self.content.is_char_boundary(offset) {
+            return None;
+        }
+
+        let mut end = offset.saturating_add(max_bytes).min(content_length);
+        while !self.content.is_char_boundary(end) {
+            end -= 1;
+        }
+        // Always make progress, even if one character is over the limit
+        if end == offset && offset < content_length {
+            end = self.content[offset..]
+                .chars()
+                .next()
+                .map_or(content_length, |c| offset + c.len_utf8());
+        }
+
+        self.content = self.content[offset..end].to_string();
+        Some(SkillContentChunk {
+            content: self,
+            content_offset: offset,
+            content_length,
+            next_offset: (end < content_length).then_some(end),
+        })
+    }
+}
+
+/// Part of a skill whose content is too large to return at once.
+#[derive(Debug, Clone, Serialize)]
+pub struct SkillContentChunk {
+    /// The skill, with `content` cut down to this chunk. `content_hash` is
+    /// still the hash of the whole content.
+    #[serde(flatten)]
+    pub content: SkillContent,
+
+    /// Byte offset of this chunk within the whole content.
+    pub content_offset: usize,
+
+    /// Size of the whole content in bytes.
+    pub content_length: usize,
+
+    /// Offset of the next chunk; absent on the last one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_offset: Option<usize>,
+}
+
+/// Marker returned instead of skill content the client already has.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillUnchanged {
+    /// Skill name/identifier.
+    pub name: String,
+
+    /// Hash of the current content, equal to the one the client sent.
+    pub content_hash: String,
+
+    /// Always true.
+    pub unchanged: bool,
+}
+
+impl SkillUnchanged {
+    /// Create an unchanged marker.
+    pub fn new(name: String, content_hash: String) -> Self {
+        Self {
+            name,
+            content_hash,
+            unchanged: true,
+        }
+    }
 }
 
 /// Sub-skill content response.
@@ -54,6 +168,7 @@ pub struct SubSkillContent {
     pub domain: String,
 
     /// Sub-skill name.
+    #[serde(alias = "subSkill")]
     pub sub_skill: String,
 
     /// Sub-skill markdown content.
@@ -71,16 +186,44 @@ impl SubSkillContent {
     }
 }
 
+/// A document in a skill's `references/` directory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReferenceInfo {
+    /// Path relative to the `references/` directory, using `/` separators.
+    pub path: String,
+
+    /// File size in bytes.
+    pub size: u64,
+}
+
+/// Reference document content response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReferenceContent {
+    /// Parent skill domain.
+    pub domain: String,
+
+    /// Path relative to the `references/` directory.
+    pub path: String,
+
+    /// Document content, possibly truncated.
+    pub content: String,
+
+    /// Whether the content was cut off at the size limit.
+    #[serde(default)]
+    pub truncated: bool,
+}
+
 /// Batch request item for loading multiple skills.
 ///
 /// Corresponds to `BatchRequest` in TypeScript.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "native", derive(schemars::JsonSchema))]
 pub struct BatchRequest {
     /// Skill domain name.
     pub domain: String,
 
     /// Optional sub-skill name. None means load main SKILL.md.
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(default, alias = "subSkill", skip_serializing_if = "Option::is_none")]
     pub sub_skill: Option<String>,
 }
 
@@ -117,6 +260,18 @@ pub enum BatchResponseItem {
         /// The error message describing what went wrong.
         error: String,
     },
+    /// Content left out because the batch hit its response size limit.
+    Truncated {
+        /// The skill domain that was left out.
+        domain: String,
+        /// The sub-skill that was left out, if one was requested.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        sub_skill: Option<String>,
+        /// Always true; marks the item as omitted.
+        truncated: bool,
+        /// Why the item was left out.
+        reason: String,
+    },
 }
 
 impl BatchResponseItem {
@@ -125,10 +280,45 @@ impl BatchResponseItem {
         Self::Error { domain, error }
     }
 
+    /// Create a marker for an item left out of a size-limited response.
+    pub fn truncated(request: &BatchRequest, reason: String) -> Self {
+        Self::Truncated {
+            domain: request.domain.clone(),
+            sub_skill: request.sub_skill.clone(),
+            truncated: true,
+            reason,
+        }
+    }
+
     /// Check if this is an error.
     pub fn is_error(&self) -> bool {
         matches!(self, Self::Error { .. })
     }
+
+    /// Check if this item was left out by the size limit.
+    pub fn is_truncated(&self) -> bool {
+        matches!(self, Self::Truncated { .. })
+    }
+
+    /// Size of the loaded markdown in bytes; zero for errors and markers.
+    pub fn content_len(&self) -> usize {
+        match self {
+            Self::Skill(skill) => skill.content.len(),
+            Self::SubSkill(sub) => sub.content.len(),
+            Self::Error { .. } | Self::Truncated { .. } => 0,
+        }
+    }
+}
+
+/// A batch response item with its load time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimedBatchItem {
+    /// The loaded content, error, or truncation marker.
+    #[serde(flatten)]
+    pub item: BatchResponseItem,
+
+    /// Time spent loading this item, in milliseconds.
+    pub duration_ms: f64,
 }
 
 /// Response format options.
@@ -142,17 +332,27 @@ pub enum ResponseFormat {
     Json,
 }
 
-/// Template options for skill generation.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+/// What kind of failure an [`ErrorResponse`] reports, for callers that
+/// act on it rather than show it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
-pub enum SkillTemplate {
-    /// Minimal template with just essentials.
-    Minimal,
-    /// Standard template with common sections.
-    #[default]
-    Standard,
-    /// Full template with sub-skills structure.
-    WithSubSkills,
+pub enum ErrorCode {
+    /// The arguments were missing or malformed, or named no such tool.
+    InvalidParams,
+    /// The skill, sub-skill or file named doesn't exist.
+    NotFound,
+    /// What was to be created already exists.
+    AlreadyExists,
+    /// The caller may not do this: a client policy, a pinned skill or a
+    /// disabled tool stands in the way.
+    Forbidden,
+    /// The change would take a skill, or all skills, over their quota.
+    QuotaExceeded,
+    /// Something the request needs, such as an LLM backend or the script
+    /// sandbox, isn't configured or failed.
+    Unavailable,
+    /// The server failed, e.g. reading or writing files.
+    Internal,
 }
 
 /// Standard error response.
@@ -160,6 +360,9 @@ pub enum SkillTemplate {
 pub struct ErrorResponse {
     /// Error message.
     pub error: String,
+    /// Kind of failure, when known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<ErrorCode>,
 }
 
 impl ErrorResponse {
@@ -167,8 +370,26 @@ impl ErrorResponse {
     pub fn new(error: impl Into<String>) -> Self {
         Self {
             error: error.into(),
+            error_code: None,
         }
     }
+
+    /// Create an error response for arguments that were missing or
+    /// malformed.
+    pub fn invalid_params(error: impl Into<String>) -> Self {
+        Self::new(error).with_code(ErrorCode::InvalidParams)
+    }
+
+    /// Create an error response for something that doesn't exist.
+    pub fn not_found(error: impl Into<String>) -> Self {
+        Self::new(error).with_code(ErrorCode::NotFound)
+    }
+
+    /// Set the kind of failure.
+    pub fn with_code(mut self, code: ErrorCode) -> Self {
+        self.error_code = Some(code);
+        self
+    }
 }
 
 impl From<String> for ErrorResponse {
@@ -196,6 +417,16 @@ mod tests {
         assert_eq!(content.name, "forms");
         assert_eq!(content.sub_skills.len(), 2);
         assert!(content.has_references);
+        assert_eq!(content.content_hash, content_hash("# Forms\n\nContent..."));
+    }
+
+    #[test]
+    fn test_content_hash() {
+        assert_eq!(
+            content_hash(""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_ne!(content_hash("a"), content_hash("b"));
     }
 
     #[test]
@@ -217,5 +448,33 @@ mod tests {
             "content".to_string(),
         ));
         assert!(!skill.is_error());
+        assert_eq!(skill.content_len(), 7);
+
+        let marker = BatchResponseItem::truncated(
+            &BatchRequest::sub_skill("forms".to_string(), "react".to_string()),
+            "limit".to_string(),
+        );
+        assert!(marker.is_truncated());
+        assert_eq!(marker.content_len(), 0);
+
+        let timed = TimedBatchItem {
+            item: marker,
+            duration_ms: 0.0,
+        };
+        let json = serde_json::to_value(&timed).unwrap();
+        assert_eq!(json["domain"], "forms");
+        assert_eq!(json["sub_skill"], "react");
+        assert_eq!(json["truncated"], true);
+    }
+
+    #[test]
+    fn test_error_response_code() {
+        let json = serde_json::to_value(ErrorResponse::new("Failed")).unwrap();
+        assert_eq!(json, serde_json::json!({"error": "Failed"}));
+
+        let json = serde_json::to_value(ErrorResponse::not_found("Skill 'x' not found")).unwrap();
+        assert_eq!(json["error_code"], "not_found");
+        let error: ErrorResponse = serde_json::from_value(json).unwrap();
+        assert_eq!(error.error_code, Some(ErrorCode::NotFound));
     }
 }
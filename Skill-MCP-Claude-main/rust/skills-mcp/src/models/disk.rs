@@ -0,0 +1,165 @@
+//! Disk usage of skills and the quotas they're held to.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Size limits on skills, in bytes. Unset limits aren't enforced.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiskQuota {
+    /// Largest a single skill may grow.
+    pub per_skill: Option<u64>,
+
+    /// Largest all skills together may grow.
+    pub total: Option<u64>,
+}
+
+/// Space taken by one skill.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkillDiskUsage {
+    /// Skill name.
+    pub name: String,
+
+    /// Total size of the skill's files.
+    pub bytes: u64,
+
+    /// Number of files.
+    pub files: usize,
+
+    /// Whether the skill is over the per-skill quota.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub over_quota: bool,
+}
+
+/// Space taken by every skill, largest first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskUsage {
+    /// Per-skill usage, largest first.
+    pub skills: Vec<SkillDiskUsage>,
+
+    /// Total size of all skills.
+    pub total_bytes: u64,
+
+    /// Total number of files.
+    pub total_files: usize,
+
+    /// Quotas the usage was checked against.
+    pub quota: DiskQuota,
+
+    /// One line per exceeded quota.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+
+    /// When the sizes were measured.
+    pub measured_at: DateTime<Utc>,
+}
+
+impl DiskUsage {
+    /// Total up per-skill usage and check it against `quota`.
+    pub fn new(mut skills: Vec<SkillDiskUsage>, quota: DiskQuota) -> Self {
+        skills.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.name.cmp(&b.name)));
+
+        let mut warnings = Vec::new();
+        if let Some(limit) = quota.per_skill {
+            for skill in &mut skills {
+                skill.over_quota = skill.bytes > limit;
+                if skill.over_quota {
+                    warnings.push(format!(
+                        "Skill '{}' uses {}, over its {} quota",
+                        skill.name,
+                        format_size(skill.bytes),
+                        format_size(limit)
+                    ));
+                }
+            }
+        }
+
+        let total_bytes = skills.iter().map(|s| s.bytes).sum();
+        let total_files = skills.iter().map(|s| s.files).sum();
+        if let Some(limit) = quota.total.filter(|&limit| total_bytes > limit) {
+            warnings.push(format!(
+                "Skills use {} in total, over the {} quota",
+                format_size(total_bytes),
+                format_size(limit)
+            ));
+        }
+
+        Self {
+            skills,
+            total_bytes,
+            total_files,
+            quota,
+            warnings,
+            measured_at: Utc::now(),
+        }
+    }
+
+    /// Usage of a single skill.
+    pub fn skill(&self, name: &str) -> Option<&SkillDiskUsage> {
+        self.skills.iter().find(|s| s.name == name)
+    }
+}
+
+/// Format a byte count for messages, e.g. `1.5 MiB`.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn skill(name: &str, bytes: u64) -> SkillDiskUsage {
+        SkillDiskUsage {
+            name: name.to_string(),
+            bytes,
+            files: 1,
+            over_quota: false,
+        }
+    }
+
+    #[test]
+    fn test_disk_usage_quota() {
+        let quota = DiskQuota {
+            per_skill: Some(1024 * 1024),
+            total: Some(2 * 1024 * 1024),
+        };
+        let usage = DiskUsage::new(
+            vec![skill("forms", 300), skill("design", 50 * 1024 * 1024)],
+            quota,
+        );
+
+        assert_eq!(usage.skills[0].name, "design");
+        assert!(usage.skills[0].over_quota);
+        assert!(!usage.skill("forms").unwrap().over_quota);
+        assert_eq!(usage.total_bytes, 50 * 1024 * 1024 + 300);
+        assert_eq!(usage.total_files, 2);
+        assert_eq!(
+            usage.warnings,
+            vec![
+                "Skill 'design' uses 50.0 MiB, over its 1.0 MiB quota",
+                "Skills use 50.0 MiB in total, over the 2.0 MiB quota",
+            ]
+        );
+
+        let unlimited = DiskUsage::new(vec![skill("design", u64::MAX / 2)], DiskQuota::default());
+        assert!(unlimited.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(5 * 1024 * 1024), "5.0 MiB");
+    }
+}
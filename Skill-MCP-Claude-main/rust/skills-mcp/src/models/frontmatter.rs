@@ -0,0 +1,269 @@
+//! Frontmatter for flat-file skills.
+//!
+//! A flat-file skill is a single `name.md` at the skills root whose metadata
+//! lives in a leading `---` block instead of `_meta.json`:
+//!
+//! ```text
+//! ---
+//! name: forms
+//! description: Form handling patterns
+//! tags: [validation, input]
+//! ---
+//!
+//! # Forms
+//! ```
+//!
+//! Only the subset of YAML that skill metadata needs is understood: scalar
+//! `key: value` pairs (plain, single- or double-quoted) and lists written
+//! either inline (`[a, b]`) or as `- item` lines. Unknown keys are ignored.
+
+use super::SkillMeta;
+
+/// Delimiter line that opens and closes a frontmatter block.
+const DELIMITER: &str = "---";
+
+/// Split a document into its frontmatter block and body.
+///
+/// Returns `None` when the document doesn't start with a `---` line or the
+/// block is never closed.
+pub fn split_frontmatter(text: &str) -> Option<(&str, &str)> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let rest = text
+        .strip_prefix(DELIMITER)
+        .and_then(|r| r.strip_prefix("\r\n").or_else(|| r.strip_prefix('\n')))?;
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == DELIMITER {
+            let body = &rest[offset + line.len()..];
+            let body = body
+                .strip_prefix("\r\n")
+                .or_else(|| body.strip_prefix('\n'))
+                .unwrap_or(body);
+            return Some((&rest[..offset], body));
+        }
+        offset += line.len();
+    }
+
+    None
+}
+
+/// Parse a flat-file skill into its metadata and markdown body.
+///
+/// `stem` is the file name without `.md` and is used when the frontmatter
+/// has no `name`. Sub-skills are never read: flat-file skills have none.
+pub fn parse_flat_skill<'a>(stem: &str, text: &'a str) -> Result<(SkillMeta, &'a str), String> {
+    let (block, body) =
+        split_frontmatter(text).ok_or_else(|| "Missing frontmatter block".to_string())?;
+
+    let mut meta = SkillMeta {
+        name: stem.to_string(),
+        ..Default::default()
+    };
+
+    let mut lines = block.lines().peekable();
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        let (key, value) = trimmed
+            .split_once(':')
+            .ok_or_else(|| format!("Invalid frontmatter line: '{}'", trimmed))?;
+        let key = key.trim();
+        let value = value.trim();
+
+        let list = if value.is_empty() {
+            // Block list: following `- item` lines
+            let mut items = Vec::new();
+            while let Some(next) = lines.peek() {
+                match next.trim().strip_prefix('-') {
+                    Some(item) => {
+                        items.push(parse_scalar(item.trim())?);
+                        lines.next();
+                    }
+                    None => break,
+                }
+            }
+            Some(items)
+        } else if let Some(inner) = value.strip_prefix('[') {
+            let inner = inner
+                .strip_suffix(']')
+                .ok_or_else(|| format!("Unclosed list for '{}'", key))?;
+            Some(
+                split_inline_list(inner)
+                    .into_iter()
+                    .map(parse_scalar)
+                    .collect::<Result<Vec<_>, _>>()?,
+            )
+        } else {
+            None
+        };
+
+        let scalar = || match &list {
+            Some(_) => Err(format!("'{}' must be a single value", key)),
+            None => parse_scalar(value),
+        };
+
+        match key {
+            "name" => meta.name = scalar()?,
+            "description" => meta.description = scalar()?,
+            "tags" => {
+                meta.tags = match list {
+                    Some(items) => items,
+                    None => vec![parse_scalar(value)?],
+                }
+            }
+            "source" => meta.source = Some(scalar()?),
+            "language" => meta.language = Some(scalar()?),
+            "owner" => meta.owner = Some(scalar()?),
+            "aliases" => {
+                meta.aliases = match list {
+                    Some(items) => items,
+                    None => vec![parse_scalar(value)?],
+                }
+            }
+            "status" => meta.status = Some(scalar()?.parse()?),
+            "pinned" => {
+                meta.pinned = match scalar()?.as_str() {
+                    "true" => true,
+                    "false" => false,
+                    other => {
+                        return Err(format!("'pinned' must be true or false, got '{}'", other))
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok((meta, body))
+}
+
+/// Render a flat-file skill from metadata and a markdown body.
+///
+/// Strings are written double-quoted so any description round-trips.
+pub fn render_flat_skill(meta: &SkillMeta, body: &str) -> String {
+    let quote = |s: &str| serde_json::to_string(s).unwrap_or_default();
+
+    let mut out = format!("{}\n", DELIMITER);
+    out.push_str(&format!("name: {}\n", quote(&meta.name)));
+    out.push_str(&format!("description: {}\n", quote(&meta.description)));
+    if !meta.tags.is_empty() {
+        let tags: Vec<String> = meta.tags.iter().map(|t| quote(t)).collect();
+        out.push_str(&format!("tags: [{}]\n", tags.join(", ")));
+    }
+    if let Some(source) = &meta.source {
+        out.push_str(&format!("source: {}\n", quote(source)));
+    }
+    if let Some(language) = &meta.language {
+        out.push_str(&format!("language: {}\n", quote(language)));
+    }
+    if let Some(status) = meta.status {
+        let status = serde_json::to_string(&status).unwrap_or_default();
+        out.push_str(&format!("status: {}\n", status));
+    }
+    if let Some(owner) = &meta.owner {
+        out.push_str(&format!("owner: {}\n", quote(owner)));
+    }
+    if !meta.aliases.is_empty() {
+        let aliases: Vec<String> = meta.aliases.iter().map(|a| quote(a)).collect();
+        out.push_str(&format!("aliases: [{}]\n", aliases.join(", ")));
+    }
+    if meta.pinned {
+        out.push_str("pinned: true\n");
+    }
+    out.push_str(DELIMITER);
+    out.push_str("\n\n");
+    out.push_str(body);
+    out
+}
+
+/// Parse a plain, single-quoted or double-quoted scalar.
+fn parse_scalar(value: &str) -> Result<String, String> {
+    if value.starts_with('"') {
+        serde_json::from_str(value).map_err(|e| format!("Invalid quoted string {}: {}", value, e))
+    } else if let Some(inner) = value.strip_prefix('\'') {
+        inner
+            .strip_suffix('\'')
+            .map(|s| s.replace("''", "'"))
+            .ok_or_else(|| format!("Unclosed quoted string {}", value))
+    } else {
+        // Trailing comments only count after whitespace, as in YAML
+        let value = value.split(" #").next().unwrap_or(value);
+        Ok(value.trim().to_string())
+    }
+}
+
+/// Split the inside of an inline list on commas outside quotes.
+fn split_inline_list(inner: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let mut quote = None;
+    let mut start = 0;
+
+    for (i, c) in inner.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if c == q => quote = None,
+            (None, ',') => {
+                items.push(inner[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(inner[start..].trim());
+
+    items.into_iter().filter(|s| !s.is_empty()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SkillStatus;
+
+    #[test]
+    fn test_parse_flat_skill() {
+        let text = "---\nname: forms\ndescription: \"Forms: validation, input\"\ntags: [react, 'hook, form']\nstatus: draft\nlicense: MIT\n---\n\n# Forms\n";
+        let (meta, body) = parse_flat_skill("forms", text).unwrap();
+
+        assert_eq!(meta.name, "forms");
+        assert_eq!(meta.description, "Forms: validation, input");
+        assert_eq!(meta.tags, vec!["react", "hook, form"]);
+        assert_eq!(meta.status, Some(SkillStatus::Draft));
+        assert_eq!(body, "# Forms\n");
+    }
+
+    #[test]
+    fn test_block_lists_and_defaults() {
+        let text = "---\ndescription: API design\ntags:\n  - rest\n  - http\n---\nBody";
+        let (meta, body) = parse_flat_skill("api", text).unwrap();
+
+        assert_eq!(meta.name, "api");
+        assert_eq!(meta.tags, vec!["rest", "http"]);
+        assert_eq!(body, "Body");
+
+        assert!(parse_flat_skill("api", "# No frontmatter").is_err());
+        assert!(parse_flat_skill("api", "---\nname: api\n").is_err());
+        assert!(parse_flat_skill("api", "---\npinned: yes\n---\n").is_err());
+    }
+
+    #[test]
+    fn test_render_round_trips() {
+        let meta = SkillMeta {
+            name: "forms".to_string(),
+            description: "Handles \"quoted\" text: and colons".to_string(),
+            tags: vec!["a".to_string(), "b, c".to_string()],
+            status: Some(SkillStatus::Published),
+            aliases: vec!["old-forms".to_string()],
+            pinned: true,
+            ..Default::default()
+        };
+
+        let text = render_flat_skill(&meta, "# Forms\n");
+        let (parsed, body) = parse_flat_skill("forms", &text).unwrap();
+        assert_eq!(parsed, meta);
+        assert_eq!(body, "# Forms\n");
+    }
+}
@@ -0,0 +1,200 @@
+//! Image assets bundled with skills, such as screenshots in `references/`.
+//!
+//! Dimensions are read from the file header, so listing images never
+//! decodes them. Thumbnails are generated separately by the API.
+
+use serde::{Deserialize, Serialize};
+
+/// Format of an image asset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageFormat {
+    /// PNG (`.png`).
+    Png,
+    /// JPEG (`.jpg`, `.jpeg`).
+    Jpeg,
+    /// GIF (`.gif`).
+    Gif,
+    /// WebP (`.webp`).
+    Webp,
+}
+
+impl ImageFormat {
+    /// Detect the format from a file name's extension.
+    pub fn from_extension(file_name: &str) -> Option<Self> {
+        let extension = file_name.rsplit_once('.')?.1.to_ascii_lowercase();
+        match extension.as_str() {
+            "png" => Some(Self::Png),
+            "jpg" | "jpeg" => Some(Self::Jpeg),
+            "gif" => Some(Self::Gif),
+            "webp" => Some(Self::Webp),
+            _ => None,
+        }
+    }
+
+    /// MIME type for serving the image.
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            Self::Png => "image/png",
+            Self::Jpeg => "image/jpeg",
+            Self::Gif => "image/gif",
+            Self::Webp => "image/webp",
+        }
+    }
+}
+
+/// An image bundled with a skill.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImageAsset {
+    /// Path relative to the skill directory, e.g. `references/form.png`.
+    pub path: String,
+
+    /// Format detected from the extension.
+    pub format: ImageFormat,
+
+    /// Width in pixels, if the header could be read.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+
+    /// Height in pixels, if the header could be read.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+
+    /// File size in bytes.
+    pub size: u64,
+}
+
+impl ImageAsset {
+    /// Describe an image from its path and the start of its file.
+    pub fn from_header(path: String, format: ImageFormat, header: &[u8], size: u64) -> Self {
+        let dimensions = image_dimensions(format, header);
+        Self {
+            path,
+            format,
+            width: dimensions.map(|(w, _)| w),
+            height: dimensions.map(|(_, h)| h),
+            size,
+        }
+    }
+}
+
+/// Read an image's width and height from the start of its file.
+///
+/// JPEG dimensions live in the first frame header, which can come after
+/// large EXIF blocks; `None` is returned if it isn't within `header`.
+pub fn image_dimensions(format: ImageFormat, header: &[u8]) -> Option<(u32, u32)> {
+    let be16 =
+        |b: &[u8], at: usize| Some(u16::from_be_bytes([*b.get(at)?, *b.get(at + 1)?]) as u32);
+    let le16 =
+        |b: &[u8], at: usize| Some(u16::from_le_bytes([*b.get(at)?, *b.get(at + 1)?]) as u32);
+    let be32 = |b: &[u8], at: usize| Some(u32::from_be_bytes(b.get(at..at + 4)?.try_into().ok()?));
+    let le24 = |b: &[u8], at: usize| {
+        let bytes = b.get(at..at + 3)?;
+        Some(bytes[0] as u32 | (bytes[1] as u32) << 8 | (bytes[2] as u32) << 16)
+    };
+
+    match format {
+        ImageFormat::Png => {
+            if !header.starts_with(b"\x89PNG\r\n\x1a\n") || header.get(12..16)? != b"IHDR" {
+                return None;
+            }
+            Some((be32(header, 16)?, be32(header, 20)?))
+        }
+        ImageFormat::Gif => {
+            if !header.starts_with(b"GIF8") {
+                return None;
+            }
+            Some((le16(header, 6)?, le16(header, 8)?))
+        }
+        ImageFormat::Webp => {
+            if !header.starts_with(b"RIFF") || header.get(8..12)? != b"WEBP" {
+                return None;
+            }
+            match header.get(12..16)? {
+                b"VP8 " => Some((le16(header, 26)? & 0x3fff, le16(header, 28)? & 0x3fff)),
+                b"VP8L" => {
+                    let bits = u32::from_le_bytes(header.get(21..25)?.try_into().ok()?);
+                    Some(((bits & 0x3fff) + 1, ((bits >> 14) & 0x3fff) + 1))
+                }
+                b"VP8X" => Some((le24(header, 24)? + 1, le24(header, 27)? + 1)),
+                _ => None,
+            }
+        }
+        ImageFormat::Jpeg => {
+            if !header.starts_with(&[0xff, 0xd8]) {
+                return None;
+            }
+            // Walk the marker segments to the first start-of-frame
+            let mut at = 2;
+            loop {
+                if *header.get(at)? != 0xff {
+                    return None;
+                }
+                let marker = *header.get(at + 1)?;
+                let length = be16(header, at + 2)? as usize;
+                let is_frame =
+                    matches!(marker, 0xc0..=0xcf) && !matches!(marker, 0xc4 | 0xc8 | 0xcc);
+                if is_frame {
+                    return Some((be16(header, at + 7)?, be16(header, at + 5)?));
+                }
+                at += 2 + length;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_png_and_gif_dimensions() {
+        let mut png = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR".to_vec();
+        png.extend_from_slice(&640u32.to_be_bytes());
+        png.extend_from_slice(&480u32.to_be_bytes());
+        assert_eq!(image_dimensions(ImageFormat::Png, &png), Some((640, 480)));
+
+        let gif = b"GIF89a\x20\x03\x58\x02";
+        assert_eq!(image_dimensions(ImageFormat::Gif, gif), Some((800, 600)));
+
+        // Wrong magic bytes for the extension
+        assert_eq!(image_dimensions(ImageFormat::Png, gif), None);
+    }
+
+    #[test]
+    fn test_jpeg_dimensions_after_app_segment() {
+        let mut jpeg = vec![0xff, 0xd8];
+        // APP0 segment with 4 bytes of payload
+        jpeg.extend_from_slice(&[0xff, 0xe0, 0x00, 0x06, b'J', b'F', b'I', b'F']);
+        // SOF0: length, precision, height 200, width 300
+        jpeg.extend_from_slice(&[0xff, 0xc0, 0x00, 0x11, 0x08, 0x00, 0xc8, 0x01, 0x2c]);
+
+        assert_eq!(image_dimensions(ImageFormat::Jpeg, &jpeg), Some((300, 200)));
+        assert_eq!(image_dimensions(ImageFormat::Jpeg, &jpeg[..10]), None);
+    }
+
+    #[test]
+    fn test_webp_dimensions() {
+        let mut webp = b"RIFF\x00\x00\x00\x00WEBPVP8X\x0a\x00\x00\x00\x00\x00\x00\x00".to_vec();
+        // Canvas width and height minus one, 24-bit little endian
+        webp.extend_from_slice(&[0x7f, 0x02, 0x00, 0xdf, 0x01, 0x00]);
+        assert_eq!(image_dimensions(ImageFormat::Webp, &webp), Some((640, 480)));
+    }
+
+    #[test]
+    fn test_format_from_extension() {
+        assert_eq!(
+            ImageFormat::from_extension("shot.PNG"),
+            Some(ImageFormat::Png)
+        );
+        assert_eq!(
+            ImageFormat::from_extension("a.jpeg"),
+            Some(ImageFormat::Jpeg)
+        );
+        assert_eq!(ImageFormat::from_extension("diagram.svg"), None);
+
+        let asset =
+            ImageAsset::from_header("references/x.gif".to_string(), ImageFormat::Gif, b"GIF8", 4);
+        assert_eq!((asset.width, asset.height), (None, None));
+    }
+}
@@ -2,9 +2,9 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
-use super::SkillMeta;
+use super::{content_hash, extract_keywords, MatchType, SkillMeta, DEFAULT_KEYWORDS};
 
 /// Aggregated skill metadata index.
 ///
@@ -15,10 +15,16 @@ pub struct SkillIndex {
     pub skills: Vec<SkillMeta>,
 
     /// Errors encountered during index building.
-    #[serde(default)]
+    #[serde(default, alias = "validationErrors")]
     pub validation_errors: Vec<String>,
 
+    /// Non-fatal problems found during index building, such as reference
+    /// documents whose text could not be extracted.
+    #[serde(default, alias = "validationWarnings")]
+    pub validation_warnings: Vec<String>,
+
     /// ISO timestamp of last index update.
+    #[serde(alias = "lastUpdated")]
     pub last_updated: DateTime<Utc>,
 }
 
@@ -28,6 +34,7 @@ impl SkillIndex {
         Self {
             skills: Vec::new(),
             validation_errors: Vec::new(),
+            validation_warnings: Vec::new(),
             last_updated: Utc::now(),
         }
     }
@@ -37,6 +44,7 @@ impl SkillIndex {
         Self {
             skills,
             validation_errors: errors,
+            validation_warnings: Vec::new(),
             last_updated: Utc::now(),
         }
     }
@@ -46,6 +54,19 @@ impl SkillIndex {
         self.skills.iter().find(|s| s.name == name)
     }
 
+    /// Find the skill that lists `name` as an alias.
+    ///
+    /// Returns `None` if a skill is itself called `name`, since real names
+    /// take precedence over aliases.
+    pub fn find_by_alias(&self, name: &str) -> Option<&SkillMeta> {
+        if self.find(name).is_some() {
+            return None;
+        }
+        self.skills
+            .iter()
+            .find(|s| s.aliases.iter().any(|alias| alias == name))
+    }
+
     /// Get skill count.
     pub fn len(&self) -> usize {
         self.skills.len()
@@ -60,6 +81,39 @@ impl SkillIndex {
     pub fn has_errors(&self) -> bool {
         !self.validation_errors.is_empty()
     }
+
+    /// Group namespaced skills by their immediate category.
+    ///
+    /// Categories are sorted by name; top-level skills are not included.
+    pub fn categories(&self) -> Vec<SkillCategory> {
+        let mut groups: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+        for skill in &self.skills {
+            if let Some(category) = skill.category() {
+                groups.entry(category).or_default().push(skill.name.clone());
+            }
+        }
+
+        groups
+            .into_iter()
+            .map(|(name, mut skills)| {
+                skills.sort();
+                SkillCategory {
+                    name: name.to_string(),
+                    skills,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Skills grouped under one category folder.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkillCategory {
+    /// Category path, e.g. `frontend` or `frontend/react`.
+    pub name: String,
+
+    /// Namespaced names of the skills directly in this category.
+    pub skills: Vec<String>,
 }
 
 impl Default for SkillIndex {
@@ -68,6 +122,16 @@ impl Default for SkillIndex {
     }
 }
 
+/// One metadata field of a synthetic metadata entry, scored with its own weight.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentField {
+    /// Which metadata field the text came from; its weight is the boost.
+    pub match_type: MatchType,
+
+    /// Lowercase field text.
+    pub text: String,
+}
+
 /// Single entry in the content index for full-text search.
 ///
 /// Corresponds to `ContentIndexEntry` in TypeScript.
@@ -77,7 +141,7 @@ pub struct ContentIndexEntry {
     pub domain: String,
 
     /// Sub-skill name if this is sub-skill content, None for main SKILL.md.
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(default, alias = "subSkill", skip_serializing_if = "Option::is_none")]
     pub sub_skill: Option<String>,
 
     /// Relative file path.
@@ -87,35 +151,127 @@ pub struct ContentIndexEntry {
     pub content: String,
 
     /// Word count for TF-IDF calculations.
+    #[serde(alias = "wordCount")]
     pub word_count: usize,
 
     /// Extracted markdown headings.
     #[serde(default)]
     pub headings: Vec<String>,
+
+    /// Weighted fields for synthetic metadata entries; empty for files.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<ContentField>,
+
+    /// Hash of the original (not lowercased) content.
+    #[serde(default)]
+    pub hash: String,
+
+    /// Short summary of a skill's main document, if one was made.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+
+    /// Salient terms extracted from the content, most salient first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keywords: Vec<String>,
+
+    /// When the file was last modified, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modified: Option<DateTime<Utc>>,
 }
 
 impl ContentIndexEntry {
     /// Create a new content index entry.
-    pub fn new(
-        domain: String,
-        sub_skill: Option<String>,
-        file: String,
-        content: String,
-    ) -> Self {
+    pub fn new(domain: String, sub_skill: Option<String>, file: String, content: String) -> Self {
         let word_count = content.split_whitespace().count();
         let headings = Self::extract_headings(&content);
+        let keywords = extract_keywords(&content, DEFAULT_KEYWORDS);
         let content_lower = content.to_lowercase();
 
         Self {
             domain,
             sub_skill,
             file,
+            hash: content_hash(&content),
             content: content_lower,
             word_count,
             headings,
+            fields: Vec::new(),
+            summary: None,
+            keywords,
+            modified: None,
         }
     }
 
+    /// Attach a summary of the document.
+    pub fn with_summary(mut self, summary: Option<String>) -> Self {
+        self.summary = summary;
+        self
+    }
+
+    /// Pseudo file name used for synthetic metadata entries.
+    pub const METADATA_FILE: &'static str = "_meta.json";
+
+    /// Create a synthetic entry holding a skill's name, description, tags
+    /// and triggers, so metadata ranks alongside file content.
+    pub fn from_meta(meta: &SkillMeta) -> Self {
+        let mut fields = vec![
+            ContentField {
+                match_type: MatchType::Name,
+                // Hyphenated names should match their words too
+                text: format!("{} {}", meta.name, meta.name.replace('-', " ")).to_lowercase(),
+            },
+            ContentField {
+                match_type: MatchType::Description,
+                text: meta.description.to_lowercase(),
+            },
+        ];
+
+        if !meta.tags.is_empty() {
+            fields.push(ContentField {
+                match_type: MatchType::Tags,
+                text: meta.tags.join(" ").to_lowercase(),
+            });
+        }
+
+        let triggers: Vec<&str> = meta
+            .all_sub_skills()
+            .into_iter()
+            .flat_map(|(_, s)| {
+                s.parsed_triggers()
+                    .filter(|t| !t.negative)
+                    .map(|t| t.phrase)
+            })
+            .collect();
+        if !triggers.is_empty() {
+            fields.push(ContentField {
+                match_type: MatchType::Triggers,
+                text: triggers.join(" ").to_lowercase(),
+            });
+        }
+
+        let content: Vec<&str> = fields.iter().map(|f| f.text.as_str()).collect();
+        let content = content.join("\n");
+
+        Self {
+            domain: meta.name.clone(),
+            sub_skill: None,
+            file: Self::METADATA_FILE.to_string(),
+            word_count: content.split_whitespace().count(),
+            hash: content_hash(&content),
+            content,
+            headings: Vec::new(),
+            fields,
+            summary: None,
+            keywords: Vec::new(),
+            modified: None,
+        }
+    }
+
+    /// Check if this is a synthetic metadata entry rather than a file.
+    pub fn is_metadata(&self) -> bool {
+        !self.fields.is_empty()
+    }
+
     /// Extract markdown headings from content.
     fn extract_headings(content: &str) -> Vec<String> {
         content
@@ -138,12 +294,26 @@ impl ContentIndexEntry {
     }
 
     /// Generate a unique key for this entry.
+    ///
+    /// The main document (SKILL.md, or `domain.md` for a flat-file skill) is
+    /// keyed by domain, sub-skills by `domain:sub_skill` and other files
+    /// (e.g. references) by `domain:file`.
     pub fn key(&self) -> String {
         match &self.sub_skill {
             Some(sub) => format!("{}:{}", self.domain, sub),
-            None => self.domain.clone(),
+            None if self.is_main_document() => self.domain.clone(),
+            None => format!("{}:{}", self.domain, self.file),
         }
     }
+
+    /// Check whether this entry is the skill's main document.
+    fn is_main_document(&self) -> bool {
+        self.file == "SKILL.md"
+            || self
+                .file
+                .strip_suffix(".md")
+                .is_some_and(|stem| stem == self.domain)
+    }
 }
 
 /// Full content index mapping keys to entries.
@@ -151,10 +321,12 @@ impl ContentIndexEntry {
 /// Corresponds to `ContentIndex` in TypeScript.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ContentIndex {
-    /// Map of unique keys to content entries.
+    /// Map of unique keys to content entries, serialized in key order.
+    #[serde(serialize_with = "serialize_sorted")]
     pub entries: HashMap<String, ContentIndexEntry>,
 
     /// ISO timestamp of last index update.
+    #[serde(alias = "lastUpdated")]
     pub last_updated: DateTime<Utc>,
 }
 
@@ -201,6 +373,188 @@ impl ContentIndex {
     pub fn iter(&self) -> impl Iterator<Item = (&String, &ContentIndexEntry)> {
         self.entries.iter()
     }
+
+    /// Describe every entry without its content, sorted by key.
+    pub fn summary(&self) -> ContentIndexSummary {
+        let mut entries: Vec<ContentEntrySummary> = self
+            .entries
+            .iter()
+            .map(|(key, entry)| ContentEntrySummary::new(key, entry))
+            .collect();
+        entries.sort_by(|a, b| a.key.cmp(&b.key));
+
+        ContentIndexSummary {
+            total_entries: entries.len(),
+            total_bytes: entries.iter().map(|e| e.size).sum(),
+            entries,
+            last_updated: self.last_updated,
+        }
+    }
+}
+
+/// Serialize entries in key order, so equal indexes encode identically.
+fn serialize_sorted<S: serde::Serializer>(
+    entries: &HashMap<String, ContentIndexEntry>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    entries
+        .iter()
+        .collect::<BTreeMap<_, _>>()
+        .serialize(serializer)
+}
+
+/// A content index entry without its content, for diagnostics.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentEntrySummary {
+    /// Index key.
+    pub key: String,
+
+    /// Parent skill domain.
+    pub domain: String,
+
+    /// Sub-skill name, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sub_skill: Option<String>,
+
+    /// Relative file path.
+    pub file: String,
+
+    /// Indexed content size in bytes.
+    pub size: usize,
+
+    /// Word count.
+    pub word_count: usize,
+
+    /// Number of markdown headings.
+    pub headings: usize,
+
+    /// Content hash.
+    pub hash: String,
+
+    /// Whether this is a synthetic metadata entry.
+    pub metadata: bool,
+}
+
+impl ContentEntrySummary {
+    /// Summarize an entry stored under `key`.
+    pub fn new(key: &str, entry: &ContentIndexEntry) -> Self {
+        Self {
+            key: key.to_string(),
+            domain: entry.domain.clone(),
+            sub_skill: entry.sub_skill.clone(),
+            file: entry.file.clone(),
+            size: entry.content.len(),
+            word_count: entry.word_count,
+            headings: entry.headings.len(),
+            hash: entry.hash.clone(),
+            metadata: entry.is_metadata(),
+        }
+    }
+}
+
+/// Differences between the in-memory index and a fresh scan of disk.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexDiff {
+    /// Skills on disk that the index is missing.
+    pub skills_missing_from_index: Vec<String>,
+
+    /// Indexed skills no longer on disk.
+    pub skills_missing_from_disk: Vec<String>,
+
+    /// Skills whose metadata differs between disk and index.
+    pub skills_changed: Vec<String>,
+
+    /// Content keys on disk that the index is missing.
+    pub entries_missing_from_index: Vec<String>,
+
+    /// Indexed content keys no longer on disk.
+    pub entries_missing_from_disk: Vec<String>,
+
+    /// Content keys whose hash differs between disk and index.
+    pub entries_changed: Vec<String>,
+}
+
+impl IndexDiff {
+    /// Compare an in-memory index (`memory`) with one built from disk.
+    pub fn between(
+        memory: (&SkillIndex, &ContentIndex),
+        disk: (&SkillIndex, &ContentIndex),
+    ) -> Self {
+        fn split<K: Ord + Clone + std::hash::Hash, V>(
+            memory: &HashMap<K, V>,
+            disk: &HashMap<K, V>,
+            changed: impl Fn(&V, &V) -> bool,
+        ) -> (Vec<K>, Vec<K>, Vec<K>) {
+            let mut missing_from_index: Vec<K> = disk
+                .keys()
+                .filter(|k| !memory.contains_key(k))
+                .cloned()
+                .collect();
+            let mut missing_from_disk: Vec<K> = memory
+                .keys()
+                .filter(|k| !disk.contains_key(k))
+                .cloned()
+                .collect();
+            let mut differ: Vec<K> = memory
+                .iter()
+                .filter(|(k, v)| disk.get(k).is_some_and(|d| changed(v, d)))
+                .map(|(k, _)| k.clone())
+                .collect();
+
+            missing_from_index.sort();
+            missing_from_disk.sort();
+            differ.sort();
+            (missing_from_index, missing_from_disk, differ)
+        }
+
+        let by_name = |index: &SkillIndex| -> HashMap<String, SkillMeta> {
+            index
+                .skills
+                .iter()
+                .map(|s| (s.name.clone(), s.clone()))
+                .collect()
+        };
+
+        let (skills_missing_from_index, skills_missing_from_disk, skills_changed) =
+            split(&by_name(memory.0), &by_name(disk.0), |a, b| a != b);
+        let (entries_missing_from_index, entries_missing_from_disk, entries_changed) =
+            split(&memory.1.entries, &disk.1.entries, |a, b| a.hash != b.hash);
+
+        Self {
+            skills_missing_from_index,
+            skills_missing_from_disk,
+            skills_changed,
+            entries_missing_from_index,
+            entries_missing_from_disk,
+            entries_changed,
+        }
+    }
+
+    /// Check whether the index matches disk.
+    pub fn is_empty(&self) -> bool {
+        self.skills_missing_from_index.is_empty()
+            && self.skills_missing_from_disk.is_empty()
+            && self.skills_changed.is_empty()
+            && self.entries_missing_from_index.is_empty()
+            && self.entries_missing_from_disk.is_empty()
+            && self.entries_changed.is_empty()
+    }
+}
+
+/// Content index overview without content bodies.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentIndexSummary {
+    /// Entries sorted by key.
+    pub entries: Vec<ContentEntrySummary>,
+
+    /// Number of entries.
+    pub total_entries: usize,
+
+    /// Combined indexed content size in bytes.
+    pub total_bytes: usize,
+
+    /// Timestamp of last index update.
+    pub last_updated: DateTime<Utc>,
 }
 
 #[cfg(test)]
@@ -215,6 +569,7 @@ mod tests {
             tags: vec![],
             sub_skills: None,
             source: None,
+            ..Default::default()
         };
 
         let index = SkillIndex::with_skills(vec![meta.clone()], vec![]);
@@ -224,6 +579,34 @@ mod tests {
         assert!(index.find("nonexistent").is_none());
     }
 
+    #[test]
+    fn test_content_index_summary() {
+        let mut index = ContentIndex::new();
+        index.insert(ContentIndexEntry::new(
+            "forms".to_string(),
+            None,
+            "SKILL.md".to_string(),
+            "# Forms\n\nBody".to_string(),
+        ));
+        index.insert(ContentIndexEntry::from_meta(&SkillMeta {
+            name: "forms".to_string(),
+            description: "Form patterns".to_string(),
+            ..Default::default()
+        }));
+
+        let summary = index.summary();
+        assert_eq!(summary.total_entries, 2);
+        assert_eq!(summary.entries[0].key, "forms");
+        assert_eq!(summary.entries[0].size, 13);
+        assert_eq!(summary.entries[0].headings, 1);
+        assert!(!summary.entries[0].metadata);
+        assert!(summary.entries[1].metadata);
+        assert_eq!(
+            summary.total_bytes,
+            summary.entries.iter().map(|e| e.size).sum::<usize>()
+        );
+    }
+
     #[test]
     fn test_content_index_entry() {
         let entry = ContentIndexEntry::new(
@@ -240,6 +623,25 @@ mod tests {
         assert_eq!(entry.headings, vec!["React Forms"]);
     }
 
+    #[test]
+    fn test_metadata_entry() {
+        let meta = SkillMeta {
+            name: "form-builder".to_string(),
+            description: "Dynamic form generation".to_string(),
+            tags: vec!["Forms".to_string()],
+            ..Default::default()
+        };
+
+        let entry = ContentIndexEntry::from_meta(&meta);
+
+        assert!(entry.is_metadata());
+        assert_eq!(entry.key(), "form-builder:_meta.json");
+        assert!(entry.matches("builder"));
+        assert!(entry.matches("dynamic"));
+        assert_eq!(entry.fields.len(), 3);
+        assert_eq!(entry.fields[2].text, "forms");
+    }
+
     #[test]
     fn test_content_index() {
         let mut index = ContentIndex::new();
@@ -258,12 +660,21 @@ mod tests {
             "React form patterns".to_string(),
         );
 
+        let entry3 = ContentIndexEntry::new(
+            "forms".to_string(),
+            None,
+            "references/guide.md".to_string(),
+            "Reference guide".to_string(),
+        );
+
         index.insert(entry1);
         index.insert(entry2);
+        index.insert(entry3);
 
-        assert_eq!(index.len(), 2);
+        assert_eq!(index.len(), 3);
         assert!(index.get("forms").is_some());
         assert!(index.get("forms:react").is_some());
-        assert_eq!(index.get_domain_entries("forms").len(), 2);
+        assert!(index.get("forms:references/guide.md").is_some());
+        assert_eq!(index.get_domain_entries("forms").len(), 3);
     }
 }
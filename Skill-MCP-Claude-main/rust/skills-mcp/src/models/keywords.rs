@@ -0,0 +1,163 @@
+//! Keyword extraction for content index entries.
+//!
+//! Keywords are picked RAKE-style: stopwords and punctuation split the text
+//! into candidate phrases, each word scores its co-occurrence degree over
+//! its frequency, and a phrase scores the sum of its words for every time
+//! it occurs. Long runs of words inform the word scores but aren't kept as
+//! keywords. Code blocks are skipped, so keywords describe the prose.
+
+use std::collections::HashMap;
+
+/// Keywords kept per content entry.
+pub const DEFAULT_KEYWORDS: usize = 8;
+
+/// Longest phrase kept as a keyword, in words.
+const MAX_PHRASE_WORDS: usize = 3;
+
+/// Shortest word considered.
+const MIN_WORD_CHARS: usize = 3;
+
+/// Words that split candidate phrases instead of belonging to them.
+const STOPWORDS: &[&str] = &[
+    "about", "above", "after", "again", "all", "also", "always", "and", "any", "are", "because",
+    "been", "before", "being", "below", "between", "both", "but", "can", "could", "does", "doing",
+    "done", "down", "during", "each", "either", "else", "etc", "even", "every", "few", "for",
+    "from", "further", "get", "gets", "had", "has", "have", "having", "her", "here", "him", "his",
+    "how", "however", "into", "its", "itself", "just", "like", "make", "makes", "many", "may",
+    "might", "more", "most", "much", "must", "need", "needs", "never", "new", "not", "now", "off",
+    "often", "once", "one", "only", "other", "our", "out", "over", "own", "same", "see", "should",
+    "since", "some", "such", "than", "that", "the", "their", "them", "then", "there", "these",
+    "they", "this", "those", "through", "too", "two", "under", "until", "use", "used", "uses",
+    "using", "very", "via", "want", "was", "way", "were", "what", "when", "where", "whether",
+    "which", "while", "who", "why", "will", "with", "within", "without", "would", "yes", "yet",
+    "you", "your",
+];
+
+/// Extract up to `max` keywords from `text`, most salient first.
+///
+/// Keywords are lowercase words or short phrases, e.g. `form validation`.
+pub fn extract_keywords(text: &str, max: usize) -> Vec<String> {
+    let phrases = candidate_phrases(text);
+    if phrases.is_empty() || max == 0 {
+        return Vec::new();
+    }
+
+    let mut frequency: HashMap<&str, f64> = HashMap::new();
+    let mut degree: HashMap<&str, f64> = HashMap::new();
+    for phrase in &phrases {
+        for word in phrase {
+            *frequency.entry(word.as_str()).or_default() += 1.0;
+            *degree.entry(word.as_str()).or_default() += phrase.len() as f64;
+        }
+    }
+
+    let mut scores: HashMap<String, f64> = HashMap::new();
+    for phrase in phrases.iter().filter(|p| p.len() <= MAX_PHRASE_WORDS) {
+        let score: f64 = phrase
+            .iter()
+            .map(|w| degree[w.as_str()] / frequency[w.as_str()])
+            .sum();
+        *scores.entry(phrase.join(" ")).or_default() += score;
+    }
+
+    let mut ranked: Vec<(String, f64)> = scores.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    ranked
+        .into_iter()
+        .take(max)
+        .map(|(phrase, _)| phrase)
+        .collect()
+}
+
+/// Split prose into runs of content words between stopwords and punctuation.
+fn candidate_phrases(text: &str) -> Vec<Vec<String>> {
+    let mut phrases = Vec::new();
+    let mut current: Vec<String> = Vec::new();
+    let mut in_code = false;
+
+    let mut flush = |current: &mut Vec<String>| {
+        if !current.is_empty() {
+            phrases.push(std::mem::take(current));
+        }
+    };
+
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code = !in_code;
+            flush(&mut current);
+            continue;
+        }
+        if in_code {
+            continue;
+        }
+
+        let mut word = String::new();
+        for c in line.chars().chain(std::iter::once('\n')) {
+            if c.is_alphanumeric() || (!word.is_empty() && matches!(c, '-' | '_')) {
+                word.extend(c.to_lowercase());
+                continue;
+            }
+
+            let word = std::mem::take(&mut word);
+            let word = word.trim_end_matches(['-', '_']);
+            if is_content_word(word) {
+                current.push(word.to_string());
+            } else if !word.is_empty() {
+                flush(&mut current);
+            }
+            if !c.is_whitespace() || c == '\n' {
+                flush(&mut current);
+            }
+        }
+    }
+    flush(&mut current);
+
+    phrases
+}
+
+/// Whether a lowercase word can be part of a keyword.
+fn is_content_word(word: &str) -> bool {
+    word.chars().count() >= MIN_WORD_CHARS
+        && !word.chars().all(|c| c.is_ascii_digit())
+        && !STOPWORDS.contains(&word)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_keywords_prefers_repeated_phrases() {
+        let text = "# Form Validation\n\n\
+            Form validation catches bad input early, before submit. Pair schema \
+            validation with inline errors, so each field shows what went wrong.\n\n\
+            ```js\nconst unusedIdentifier = validateEverything();\n```\n\n\
+            Inline errors and form validation belong together.";
+
+        let keywords = extract_keywords(text, 3);
+        assert_eq!(
+            keywords,
+            vec!["pair schema validation", "inline errors", "form validation"]
+        );
+
+        // Code and runs longer than a phrase aren't keywords
+        let all = extract_keywords(text, 50);
+        assert!(!all
+            .iter()
+            .any(|k| k.contains("unusedidentifier") || k.contains("catches")));
+    }
+
+    #[test]
+    fn test_extract_keywords_edge_cases() {
+        assert!(extract_keywords("", 5).is_empty());
+        assert!(extract_keywords("the and of to 42", 5).is_empty());
+        assert!(extract_keywords("react hooks", 0).is_empty());
+
+        assert!(extract_keywords("alpha beta gamma delta epsilon", 5).is_empty());
+        assert_eq!(
+            extract_keywords("react-hook-form", 1),
+            vec!["react-hook-form"]
+        );
+    }
+}
@@ -0,0 +1,211 @@
+//! Per-skill content hashes and the changes between them.
+//!
+//! A skill's hash covers its metadata and the hashes of its indexed files,
+//! so it changes whenever anything searchable about the skill does. File
+//! hashes are computed once at indexing, so rehashing a skill only touches
+//! its own entries.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{content_hash, ContentIndex, SkillIndex, SkillMeta};
+
+/// Content hash of every indexed skill, as of one index version.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexManifest {
+    /// Index version the hashes were taken at.
+    pub version: u64,
+
+    /// Skill name to content hash.
+    pub skills: BTreeMap<String, String>,
+}
+
+impl IndexManifest {
+    /// Hash every skill in the indexes.
+    pub fn build(version: u64, skill_index: &SkillIndex, content_index: &ContentIndex) -> Self {
+        let mut manifest = Self {
+            version,
+            skills: BTreeMap::new(),
+        };
+        manifest.rehash(skill_index, content_index, |_| true);
+        manifest
+    }
+
+    /// Recompute the hashes of skills whose names pass `touched`, dropping
+    /// those no longer indexed. Other skills keep their hashes.
+    pub fn rehash(
+        &mut self,
+        skill_index: &SkillIndex,
+        content_index: &ContentIndex,
+        touched: impl Fn(&str) -> bool,
+    ) {
+        self.skills.retain(|name, _| !touched(name));
+
+        let mut files: BTreeMap<&str, Vec<(&str, &str)>> = BTreeMap::new();
+        for (key, entry) in content_index.iter() {
+            if touched(&entry.domain) {
+                files
+                    .entry(entry.domain.as_str())
+                    .or_default()
+                    .push((key.as_str(), entry.hash.as_str()));
+            }
+        }
+
+        for skill in skill_index.skills.iter().filter(|s| touched(&s.name)) {
+            let files = files.remove(skill.name.as_str()).unwrap_or_default();
+            self.skills
+                .insert(skill.name.clone(), skill_hash(skill, files));
+        }
+    }
+
+    /// What changed since `previous`.
+    pub fn diff(&self, previous: &IndexManifest) -> ManifestDiff {
+        let mut diff = ManifestDiff {
+            from_version: previous.version,
+            to_version: self.version,
+            ..Default::default()
+        };
+
+        for (name, hash) in &self.skills {
+            match previous.skills.get(name) {
+                None => diff.added.push(name.clone()),
+                Some(old) if old != hash => diff.changed.push(name.clone()),
+                Some(_) => {}
+            }
+        }
+        diff.removed = previous
+            .skills
+            .keys()
+            .filter(|name| !self.skills.contains_key(*name))
+            .cloned()
+            .collect();
+
+        diff
+    }
+}
+
+/// Hash a skill's metadata together with its files' `(key, hash)` pairs.
+fn skill_hash(meta: &SkillMeta, mut files: Vec<(&str, &str)>) -> String {
+    files.sort_unstable();
+
+    let mut text = serde_json::to_string(meta).unwrap_or_default();
+    for (key, hash) in files {
+        text.push('\n');
+        text.push_str(key);
+        text.push('\t');
+        text.push_str(hash);
+    }
+    content_hash(&text)
+}
+
+/// Skills added, removed and changed between two manifests, by name.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestDiff {
+    /// Version of the earlier manifest.
+    pub from_version: u64,
+
+    /// Version of the later manifest.
+    pub to_version: u64,
+
+    /// Skills only in the later manifest.
+    pub added: Vec<String>,
+
+    /// Skills only in the earlier manifest.
+    pub removed: Vec<String>,
+
+    /// Skills in both whose hash differs.
+    pub changed: Vec<String>,
+}
+
+impl ManifestDiff {
+    /// Whether no skill changed.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// Every affected skill, sorted.
+    pub fn skills(&self) -> Vec<&str> {
+        let mut skills: Vec<&str> = self
+            .added
+            .iter()
+            .chain(&self.removed)
+            .chain(&self.changed)
+            .map(String::as_str)
+            .collect();
+        skills.sort_unstable();
+        skills
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ContentIndexEntry;
+
+    fn indexes(skills: &[(&str, &str)]) -> (SkillIndex, ContentIndex) {
+        let mut content = ContentIndex::new();
+        let metas = skills
+            .iter()
+            .map(|(name, body)| {
+                content.insert(ContentIndexEntry::new(
+                    name.to_string(),
+                    None,
+                    "SKILL.md".to_string(),
+                    body.to_string(),
+                ));
+                SkillMeta {
+                    name: name.to_string(),
+                    description: format!("{} skill", name),
+                    ..Default::default()
+                }
+            })
+            .collect();
+        (SkillIndex::with_skills(metas, Vec::new()), content)
+    }
+
+    #[test]
+    fn test_manifest_diff() {
+        let (skills, content) = indexes(&[("forms", "# Forms"), ("charts", "# Charts")]);
+        let before = IndexManifest::build(1, &skills, &content);
+        assert_eq!(before.skills.len(), 2);
+        assert_eq!(IndexManifest::build(1, &skills, &content), before);
+
+        let (skills, content) = indexes(&[("forms", "# Forms v2"), ("tables", "# Tables")]);
+        let after = IndexManifest::build(2, &skills, &content);
+        let diff = after.diff(&before);
+
+        assert_eq!((diff.from_version, diff.to_version), (1, 2));
+        assert_eq!(diff.added, vec!["tables"]);
+        assert_eq!(diff.removed, vec!["charts"]);
+        assert_eq!(diff.changed, vec!["forms"]);
+        assert_eq!(diff.skills(), vec!["charts", "forms", "tables"]);
+        assert!(after.diff(&after).is_empty());
+    }
+
+    #[test]
+    fn test_manifest_rehash() {
+        let (skills, content) = indexes(&[("forms", "# Forms"), ("charts", "# Charts")]);
+        let mut manifest = IndexManifest::build(1, &skills, &content);
+        let charts = manifest.skills["charts"].clone();
+
+        let (skills, content) = indexes(&[("forms", "# Forms v2"), ("charts", "# Charts v2")]);
+        manifest.rehash(&skills, &content, |name| name == "forms");
+
+        // Untouched skills keep their old hash
+        assert_eq!(manifest.skills["charts"], charts);
+        assert_eq!(
+            manifest,
+            IndexManifest {
+                version: 1,
+                skills: BTreeMap::from([
+                    ("charts".to_string(), charts),
+                    (
+                        "forms".to_string(),
+                        IndexManifest::build(1, &skills, &content).skills["forms"].clone()
+                    ),
+                ]),
+            }
+        );
+    }
+}
@@ -0,0 +1,156 @@
+//! Approximate memory held by the index and search structures.
+//!
+//! Sizes count the heap data the structures point at (strings, lists and
+//! map entries), estimated from their serialized size, so they are a guide
+//! for sizing instances and spotting growth rather than exact figures.
+
+use std::io;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{ContentIndex, SkillIndex};
+
+/// Memory held by the skill and content indexes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexMemory {
+    /// Number of skills.
+    pub skills: usize,
+
+    /// Skill metadata.
+    pub skill_bytes: u64,
+
+    /// Number of content index entries.
+    pub content_entries: usize,
+
+    /// Searchable text of all entries.
+    pub content_bytes: u64,
+
+    /// Entries in full: text, headings, fields, keywords and keys.
+    pub content_index_bytes: u64,
+
+    /// Skill metadata and content index together.
+    pub total_bytes: u64,
+}
+
+impl IndexMemory {
+    /// Measure `skills` and `content`.
+    pub fn measure(skills: &SkillIndex, content: &ContentIndex) -> Self {
+        let skill_bytes: u64 = skills.skills.iter().map(approximate_size).sum();
+        let content_bytes: u64 = content
+            .entries
+            .values()
+            .map(|entry| entry.content.len() as u64)
+            .sum();
+        let content_index_bytes: u64 = content
+            .entries
+            .iter()
+            .map(|(key, entry)| key.len() as u64 + approximate_size(entry))
+            .sum();
+
+        Self {
+            skills: skills.len(),
+            skill_bytes,
+            content_entries: content.len(),
+            content_bytes,
+            content_index_bytes,
+            total_bytes: skill_bytes + content_index_bytes,
+        }
+    }
+}
+
+/// Memory held by search structures built from the index.
+///
+/// Search scans the content index directly and keeps no inverted index or
+/// embeddings; the spelling vocabulary is its only term table.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchMemory {
+    /// Distinct words in the spelling vocabulary, 0 until it is built.
+    pub vocabulary_terms: usize,
+
+    /// Spelling vocabulary.
+    pub vocabulary_bytes: u64,
+
+    /// Queries held in the zero-result cache.
+    pub zero_result_queries: usize,
+}
+
+/// Approximate memory use of the index, search and the whole process.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryUsage {
+    /// Skill and content indexes.
+    pub index: IndexMemory,
+
+    /// Search structures.
+    pub search: SearchMemory,
+
+    /// Resident set size of the process, where the platform reports it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub process_rss_bytes: Option<u64>,
+
+    /// When the sizes were measured.
+    pub measured_at: DateTime<Utc>,
+}
+
+/// Approximate heap size of `value`: the length of its JSON encoding,
+/// counted without building it.
+pub fn approximate_size(value: &impl Serialize) -> u64 {
+    let mut counter = ByteCounter(0);
+    match serde_json::to_writer(&mut counter, value) {
+        Ok(()) => counter.0,
+        Err(_) => 0,
+    }
+}
+
+struct ByteCounter(u64);
+
+impl io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ContentIndexEntry, SkillMeta};
+
+    #[test]
+    fn test_index_memory() {
+        let meta = SkillMeta {
+            name: "forms".to_string(),
+            description: "Form handling".to_string(),
+            ..Default::default()
+        };
+        let skills = SkillIndex::with_skills(vec![meta], vec![]);
+        let mut content = ContentIndex::new();
+        content.insert(ContentIndexEntry::new(
+            "forms".to_string(),
+            None,
+            "SKILL.md".to_string(),
+            "# Forms\n\nValidation and layout".to_string(),
+        ));
+
+        let memory = IndexMemory::measure(&skills, &content);
+        assert_eq!(memory.skills, 1);
+        assert_eq!(memory.content_entries, 1);
+        assert_eq!(memory.content_bytes, 30);
+        assert!(memory.skill_bytes > 0);
+        assert!(memory.content_index_bytes > memory.content_bytes);
+        assert_eq!(
+            memory.total_bytes,
+            memory.skill_bytes + memory.content_index_bytes
+        );
+    }
+
+    #[test]
+    fn test_approximate_size() {
+        assert_eq!(approximate_size(&"forms"), 7);
+        assert_eq!(approximate_size(&vec![1, 2, 3]), 7);
+    }
+}
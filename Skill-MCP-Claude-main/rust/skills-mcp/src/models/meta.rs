@@ -1,5 +1,6 @@
 //! Skill metadata types matching `_meta.json` schema.
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 /// Sub-skill reference within a parent skill.
@@ -10,21 +11,191 @@ pub struct SubSkillMeta {
     /// Sub-skill identifier (e.g., "validation", "react")
     pub name: String,
 
-    /// Relative path to the sub-skill markdown file
+    /// Relative path to the sub-skill markdown file; empty for a reference
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub file: String,
 
-    /// Optional keywords for search discovery
+    /// Optional keywords for search discovery. A trigger may carry a
+    /// weight (`useForm^2`), or rule the sub-skill out when the query
+    /// mentions it (`!vue`); see [`Trigger`].
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub triggers: Vec<String>,
+
+    /// Nested sub-skills, addressed as `parent/child`. Their files are
+    /// relative to the skill directory like any other sub-skill.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<SubSkillMeta>,
+
+    /// Another skill's sub-skill this one stands for, as `domain:sub-skill`
+    /// (e.g. `forms:react`), read from there instead of from `file`.
+    #[serde(default, rename = "ref", skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+}
+
+/// Deepest nesting of sub-skills, counting top-level sub-skills as 1.
+pub const MAX_SUB_SKILL_DEPTH: usize = 4;
+
+/// Prefix of a negative trigger, e.g. `!vue`.
+pub const NEGATIVE_TRIGGER_PREFIX: char = '!';
+
+/// Separator between a trigger and its weight, e.g. `useForm^2`.
+pub const TRIGGER_WEIGHT_SEPARATOR: char = '^';
+
+/// A sub-skill trigger parsed from its `phrase^weight` or `!phrase` form.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Trigger<'a> {
+    /// The keyword or phrase itself.
+    pub phrase: &'a str,
+
+    /// Multiplier for the score of a match on this trigger.
+    pub weight: f64,
+
+    /// Whether a query mentioning the phrase is not meant for the sub-skill.
+    pub negative: bool,
+}
+
+impl<'a> Trigger<'a> {
+    /// Weight of a trigger without an explicit one.
+    pub const DEFAULT_WEIGHT: f64 = 1.0;
+
+    /// Parse a trigger as written in `_meta.json`.
+    pub fn parse(raw: &'a str) -> Result<Self, String> {
+        let raw = raw.trim();
+        let (negative, rest) = match raw.strip_prefix(NEGATIVE_TRIGGER_PREFIX) {
+            Some(rest) => (true, rest.trim_start()),
+            None => (false, raw),
+        };
+
+        let (phrase, weight) = match rest.rsplit_once(TRIGGER_WEIGHT_SEPARATOR) {
+            Some((phrase, weight)) => {
+                let weight: f64 = weight
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("invalid weight '{}' in '{}'", weight, raw))?;
+                if !weight.is_finite() || weight <= 0.0 {
+                    return Err(format!("weight must be a positive number in '{}'", raw));
+                }
+                if negative {
+                    return Err(format!("negative trigger '{}' can't have a weight", raw));
+                }
+                (phrase.trim_end(), weight)
+            }
+            None => (rest, Self::DEFAULT_WEIGHT),
+        };
+
+        if phrase.is_empty() {
+            return Err(format!("trigger '{}' has no phrase", raw));
+        }
+        Ok(Self {
+            phrase,
+            weight,
+            negative,
+        })
+    }
 }
 
+/// Separator between the domain and the sub-skill of a reference.
+pub const SUB_SKILL_REF_SEPARATOR: char = ':';
+
+impl SubSkillMeta {
+    /// This sub-skill and its children with files moved into `dir`.
+    /// References have no file of their own and stay as they are.
+    pub fn moved_under(&self, dir: &str) -> SubSkillMeta {
+        SubSkillMeta {
+            name: self.name.clone(),
+            file: if self.file.is_empty() {
+                String::new()
+            } else {
+                format!("{}/{}", dir, self.file)
+            },
+            triggers: self.triggers.clone(),
+            children: self.children.iter().map(|c| c.moved_under(dir)).collect(),
+            reference: self.reference.clone(),
+        }
+    }
+
+    /// Triggers that parse, positive and negative.
+    pub fn parsed_triggers(&self) -> impl Iterator<Item = Trigger<'_>> {
+        self.triggers.iter().filter_map(|t| Trigger::parse(t).ok())
+    }
+
+    /// Whether a negative trigger appears in the lowercased `query`.
+    pub fn excluded_by(&self, query: &str) -> bool {
+        self.parsed_triggers()
+            .any(|t| t.negative && query.contains(&t.phrase.to_lowercase()))
+    }
+
+    /// The domain and sub-skill path this entry refers to, if it is a
+    /// well-formed reference.
+    pub fn reference_target(&self) -> Option<(&str, &str)> {
+        let (domain, sub) = self
+            .reference
+            .as_deref()?
+            .split_once(SUB_SKILL_REF_SEPARATOR)?;
+        (!domain.is_empty() && !sub.is_empty()).then_some((domain, sub))
+    }
+}
+
+/// Publication status of a skill.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SkillStatus {
+    /// Work in progress, not ready for general use.
+    Draft,
+    /// Ready for use.
+    Published,
+    /// Still available but should no longer be used.
+    Deprecated,
+}
+
+impl std::str::FromStr for SkillStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "draft" => Ok(SkillStatus::Draft),
+            "published" => Ok(SkillStatus::Published),
+            "deprecated" => Ok(SkillStatus::Deprecated),
+            _ => Err(format!(
+                "Invalid status '{}' (expected draft, published or deprecated)",
+                s
+            )),
+        }
+    }
+}
+
+/// Where an imported skill came from, kept to check it for updates.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SkillOrigin {
+    /// Repository URL, e.g. `https://github.com/acme/skills`.
+    pub url: String,
+
+    /// Branch, tag or commit that was asked for.
+    #[serde(rename = "ref")]
+    pub git_ref: String,
+
+    /// Directory of the repository the skill was imported from.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub path: String,
+
+    /// Commit the skill was imported at.
+    pub commit: String,
+
+    /// When the skill was imported.
+    pub imported_at: DateTime<Utc>,
+}
+
+/// Separator between a skill's category and its own name, e.g. `frontend/forms`.
+pub const CATEGORY_SEPARATOR: char = '/';
+
 /// Primary skill metadata from `_meta.json`.
 ///
 /// Corresponds to `SkillMeta` in TypeScript and validates against `MetaSchema`.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
 pub struct SkillMeta {
     /// Skill identifier - must match directory name.
-    /// Lowercase alphanumeric with hyphens only.
+    /// Lowercase alphanumeric with hyphens only. Skills in nested category
+    /// folders are namespaced by their path, e.g. `frontend/forms`.
     pub name: String,
 
     /// Human-readable description of what the skill provides.
@@ -35,15 +206,57 @@ pub struct SkillMeta {
     pub tags: Vec<String>,
 
     /// Optional nested sub-skills for domain/router skills.
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(default, alias = "subSkills", skip_serializing_if = "Option::is_none")]
     pub sub_skills: Option<Vec<SubSkillMeta>>,
 
     /// Optional origin indicator (e.g., "community", "official").
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub source: Option<String>,
+
+    /// Optional language of the skill content (e.g., "en", "de").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+
+    /// Optional publication status.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<SkillStatus>,
+
+    /// Optional person or team responsible for the skill.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+
+    /// Former names that still resolve to this skill, e.g. after a merge.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+
+    /// Repository the skill was imported from, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<SkillOrigin>,
+
+    /// Protects the skill from syncs, updates and bulk edits unless they
+    /// are forced.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
 }
 
 impl SkillMeta {
+    /// Category of a namespaced skill (`frontend` for `frontend/forms`).
+    ///
+    /// Returns `None` for skills at the top level.
+    pub fn category(&self) -> Option<&str> {
+        self.name
+            .rsplit_once(CATEGORY_SEPARATOR)
+            .map(|(category, _)| category)
+    }
+
+    /// Check whether this skill is in a category or any of its subcategories.
+    pub fn in_category(&self, category: &str) -> bool {
+        let category = category.trim_matches(CATEGORY_SEPARATOR);
+        self.name
+            .strip_prefix(category)
+            .is_some_and(|rest| rest.starts_with(CATEGORY_SEPARATOR))
+    }
+
     /// Check if this skill has sub-skills (is a router/domain skill).
     pub fn has_sub_skills(&self) -> bool {
         self.sub_skills
@@ -60,21 +273,70 @@ impl SkillMeta {
             .unwrap_or_default()
     }
 
-    /// Find a sub-skill by name.
-    pub fn find_sub_skill(&self, name: &str) -> Option<&SubSkillMeta> {
-        self.sub_skills
-            .as_ref()
-            .and_then(|subs| subs.iter().find(|s| s.name == name))
+    /// Find a sub-skill by name, or a nested one by its slash-separated
+    /// path, e.g. `react/hooks`.
+    pub fn find_sub_skill(&self, path: &str) -> Option<&SubSkillMeta> {
+        let mut subs = self.sub_skills.as_deref()?;
+        let mut found = None;
+        for name in path.split('/') {
+            let sub = subs.iter().find(|s| s.name == name)?;
+            subs = &sub.children;
+            found = Some(sub);
+        }
+        found
+    }
+
+    /// Every sub-skill at any depth with its path, parents before children.
+    pub fn all_sub_skills(&self) -> Vec<(String, &SubSkillMeta)> {
+        fn walk<'a>(
+            prefix: &str,
+            subs: &'a [SubSkillMeta],
+            out: &mut Vec<(String, &'a SubSkillMeta)>,
+        ) {
+            for sub in subs {
+                let path = if prefix.is_empty() {
+                    sub.name.clone()
+                } else {
+                    format!("{}/{}", prefix, sub.name)
+                };
+                out.push((path.clone(), sub));
+                walk(&path, &sub.children, out);
+            }
+        }
+
+        let mut out = Vec::new();
+        walk("", self.sub_skills.as_deref().unwrap_or_default(), &mut out);
+        out
     }
 
-    /// Get all trigger words (skill-level tags + sub-skill triggers).
+    /// Paths of every sub-skill at any depth.
+    pub fn sub_skill_paths(&self) -> Vec<String> {
+        self.all_sub_skills()
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect()
+    }
+
+    /// Find the sub-skill whose document is `file`, relative to the skill
+    /// directory.
+    pub fn find_sub_skill_by_file(&self, file: &str) -> Option<&SubSkillMeta> {
+        self.all_sub_skills()
+            .into_iter()
+            .map(|(_, sub)| sub)
+            .find(|sub| !sub.file.is_empty() && sub.file == file)
+    }
+
+    /// Get all trigger words (skill-level tags + positive sub-skill
+    /// trigger phrases, without weights).
     pub fn all_triggers(&self) -> Vec<&str> {
         let mut triggers: Vec<&str> = self.tags.iter().map(|s| s.as_str()).collect();
 
-        if let Some(subs) = &self.sub_skills {
-            for sub in subs {
-                triggers.extend(sub.triggers.iter().map(|s| s.as_str()));
-            }
+        for (_, sub) in self.all_sub_skills() {
+            triggers.extend(
+                sub.parsed_triggers()
+                    .filter(|t| !t.negative)
+                    .map(|t| t.phrase),
+            );
         }
 
         triggers
@@ -116,11 +378,16 @@ mod tests {
                     "file": "validation/SKILL.md"
                 }
             ],
-            "source": "official"
+            "source": "official",
+            "language": "en",
+            "status": "published",
+            "owner": "frontend-team"
         }"#;
 
         let meta: SkillMeta = serde_json::from_str(json).unwrap();
         assert_eq!(meta.name, "forms");
+        assert_eq!(meta.status, Some(SkillStatus::Published));
+        assert_eq!(meta.owner.as_deref(), Some("frontend-team"));
         assert!(meta.has_sub_skills());
         assert_eq!(meta.sub_skill_names(), vec!["react", "validation"]);
 
@@ -128,6 +395,26 @@ mod tests {
         assert_eq!(react_sub.triggers, vec!["useForm", "react-hook-form"]);
     }
 
+    #[test]
+    fn test_categories() {
+        let nested = SkillMeta {
+            name: "frontend/react/forms".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(nested.category(), Some("frontend/react"));
+        assert!(nested.in_category("frontend"));
+        assert!(nested.in_category("frontend/react/"));
+        assert!(!nested.in_category("front"));
+        assert!(!nested.in_category("frontend/react/forms"));
+
+        let top = SkillMeta {
+            name: "forms".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(top.category(), None);
+        assert!(!top.in_category("forms"));
+    }
+
     #[test]
     fn test_all_triggers() {
         let meta = SkillMeta {
@@ -138,8 +425,11 @@ mod tests {
                 name: "react".to_string(),
                 file: "react/SKILL.md".to_string(),
                 triggers: vec!["useForm".to_string()],
+                children: Vec::new(),
+                reference: None,
             }]),
             source: None,
+            ..Default::default()
         };
 
         let triggers = meta.all_triggers();
@@ -147,4 +437,97 @@ mod tests {
         assert!(triggers.contains(&"input"));
         assert!(triggers.contains(&"useForm"));
     }
+
+    #[test]
+    fn test_nested_sub_skills() {
+        let json = r#"{
+            "name": "forms",
+            "description": "Form handling patterns",
+            "sub_skills": [
+                {
+                    "name": "react",
+                    "file": "react/SKILL.md",
+                    "children": [
+                        {
+                            "name": "hooks",
+                            "file": "react/hooks.md",
+                            "triggers": ["useForm"]
+                        }
+                    ]
+                }
+            ]
+        }"#;
+
+        let meta: SkillMeta = serde_json::from_str(json).unwrap();
+        assert_eq!(meta.sub_skill_names(), vec!["react"]);
+        assert_eq!(meta.sub_skill_paths(), vec!["react", "react/hooks"]);
+        assert_eq!(
+            meta.find_sub_skill("react/hooks").unwrap().file,
+            "react/hooks.md"
+        );
+        assert!(meta.find_sub_skill("hooks").is_none());
+        assert!(meta.find_sub_skill("react/missing").is_none());
+        assert!(meta.all_triggers().contains(&"useForm"));
+
+        let moved = meta.find_sub_skill("react").unwrap().moved_under("forms");
+        assert_eq!(moved.children[0].file, "forms/react/hooks.md");
+
+        // Leaves don't serialize an empty list of children
+        let json = serde_json::to_string(&moved.children[0]).unwrap();
+        assert!(!json.contains("children"));
+    }
+
+    #[test]
+    fn test_sub_skill_reference() {
+        let json = r#"{"name": "react", "ref": "forms:react/hooks"}"#;
+        let sub: SubSkillMeta = serde_json::from_str(json).unwrap();
+        assert!(sub.file.is_empty());
+        assert_eq!(sub.reference_target(), Some(("forms", "react/hooks")));
+        assert_eq!(sub.moved_under("ui"), sub);
+        assert_eq!(
+            serde_json::to_string(&sub).unwrap(),
+            json.replace(": ", ":").replace(", ", ",")
+        );
+
+        let sub = SubSkillMeta {
+            reference: Some("forms".to_string()),
+            ..sub
+        };
+        assert_eq!(sub.reference_target(), None);
+    }
+
+    #[test]
+    fn test_weighted_and_negative_triggers() {
+        let trigger = Trigger::parse("useForm^2").unwrap();
+        assert_eq!(
+            (trigger.phrase, trigger.weight, trigger.negative),
+            ("useForm", 2.0, false)
+        );
+        let trigger = Trigger::parse("! vue").unwrap();
+        assert_eq!(
+            (trigger.phrase, trigger.weight, trigger.negative),
+            ("vue", 1.0, true)
+        );
+        assert!(Trigger::parse("form^high").is_err());
+        assert!(Trigger::parse("form^0").is_err());
+        assert!(Trigger::parse("!vue^2").is_err());
+        assert!(Trigger::parse("!").is_err());
+
+        let sub = SubSkillMeta {
+            name: "react".to_string(),
+            file: "react.md".to_string(),
+            triggers: vec!["form^2".to_string(), "!Vue".to_string()],
+            children: Vec::new(),
+            reference: None,
+        };
+        assert!(sub.excluded_by("form in vue"));
+        assert!(!sub.excluded_by("form in react"));
+
+        let meta = SkillMeta {
+            name: "forms".to_string(),
+            sub_skills: Some(vec![sub]),
+            ..Default::default()
+        };
+        assert_eq!(meta.all_triggers(), vec!["form"]);
+    }
 }
@@ -3,14 +3,42 @@
 //! These types mirror the TypeScript definitions in `skills-mcp-server/src/types.ts`
 //! and the Zod schemas in `skills-mcp-server/src/schemas/meta.ts`.
 
-mod meta;
+mod case;
+mod content;
+mod disk;
+mod frontmatter;
+mod image;
 mod index;
+mod keywords;
+mod manifest;
+mod memory;
+mod meta;
+mod notebook;
+mod preview;
+mod quality;
+mod script;
 mod search;
+mod skill_stats;
 mod stats;
-mod content;
+mod tag_expr;
+mod template;
 
-pub use meta::*;
+pub use case::*;
+pub use content::*;
+pub use disk::*;
+pub use frontmatter::*;
+pub use image::*;
 pub use index::*;
+pub use keywords::*;
+pub use manifest::*;
+pub use memory::*;
+pub use meta::*;
+pub use notebook::*;
+pub use preview::*;
+pub use quality::*;
+pub use script::*;
 pub use search::*;
+pub use skill_stats::*;
 pub use stats::*;
-pub use content::*;
+pub use tag_expr::*;
+pub use template::*;
@@ -0,0 +1,103 @@
+//! Text extraction from Jupyter notebooks.
+//!
+//! Notebooks under `references/` are indexed as markdown built from their
+//! cells: each markdown or code cell becomes a `## Cell N` section, with
+//! code fenced in the kernel's language. Raw cells and outputs are left
+//! out, since outputs are mostly noise (tracebacks, base64 images).
+
+use serde_json::Value;
+
+/// Heading prefix for each extracted cell.
+pub const CELL_HEADING: &str = "## Cell";
+
+/// Check whether a file name has a notebook extension.
+pub fn is_notebook(file_name: &str) -> bool {
+    file_name
+        .rsplit_once('.')
+        .is_some_and(|(_, ext)| ext.eq_ignore_ascii_case("ipynb"))
+}
+
+/// Convert notebook JSON into markdown with one section per cell.
+///
+/// Cell numbers are the 1-based position among all cells, so they match
+/// what Jupyter shows even when raw cells are skipped.
+pub fn notebook_to_markdown(json: &str) -> Result<String, String> {
+    let notebook: Value =
+        serde_json::from_str(json).map_err(|e| format!("Invalid notebook JSON: {}", e))?;
+
+    let cells = notebook
+        .get("cells")
+        .and_then(Value::as_array)
+        .ok_or_else(|| "Notebook has no cells array".to_string())?;
+
+    let language = notebook
+        .pointer("/metadata/kernelspec/language")
+        .or_else(|| notebook.pointer("/metadata/language_info/name"))
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+
+    let mut sections = Vec::new();
+    for (i, cell) in cells.iter().enumerate() {
+        let source = cell_source(cell.get("source"));
+        if source.trim().is_empty() {
+            continue;
+        }
+
+        let body = match cell.get("cell_type").and_then(Value::as_str) {
+            Some("markdown") => source.trim_end().to_string(),
+            Some("code") => format!("```{}\n{}\n```", language, source.trim_end()),
+            _ => continue,
+        };
+        sections.push(format!("{} {}\n\n{}", CELL_HEADING, i + 1, body));
+    }
+
+    Ok(sections.join("\n\n"))
+}
+
+/// Join a cell's `source`, which nbformat allows as a string or a list of lines.
+fn cell_source(source: Option<&Value>) -> String {
+    match source {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Array(lines)) => lines.iter().filter_map(Value::as_str).collect(),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notebook_to_markdown() {
+        let json = r##"{
+            "metadata": {"kernelspec": {"language": "python", "name": "python3"}},
+            "cells": [
+                {"cell_type": "markdown", "source": ["# Loading data\n", "Use pandas."]},
+                {"cell_type": "raw", "source": "ignored"},
+                {"cell_type": "code", "source": "import pandas as pd\n", "outputs": [{"text": "noise"}]},
+                {"cell_type": "code", "source": []}
+            ]
+        }"##;
+
+        let text = notebook_to_markdown(json).unwrap();
+        assert_eq!(
+            text,
+            "## Cell 1\n\n# Loading data\nUse pandas.\n\n## Cell 3\n\n```python\nimport pandas as pd\n```"
+        );
+    }
+
+    #[test]
+    fn test_invalid_notebooks() {
+        assert!(notebook_to_markdown("not json").is_err());
+        assert!(notebook_to_markdown(r#"{"metadata": {}}"#).is_err());
+        assert_eq!(notebook_to_markdown(r#"{"cells": []}"#).unwrap(), "");
+    }
+
+    #[test]
+    fn test_is_notebook() {
+        assert!(is_notebook("analysis.ipynb"));
+        assert!(is_notebook("Analysis.IPYNB"));
+        assert!(!is_notebook("analysis.md"));
+        assert!(!is_notebook("ipynb"));
+    }
+}
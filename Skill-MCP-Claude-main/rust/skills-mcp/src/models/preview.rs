@@ -0,0 +1,328 @@
+//! Structured previews of data files bundled with skills.
+//!
+//! CSV and TSV files are read row by row and only the first rows are kept,
+//! so previewing a large file stays cheap. JSON has to be parsed whole and
+//! is cut down afterwards; JSON Lines is read line by line like CSV.
+
+use std::io::BufRead;
+
+use serde::Serialize;
+use serde_json::Value;
+
+/// A data file format that can be previewed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataFormat {
+    /// Comma-separated values with a header row.
+    Csv,
+    /// Tab-separated values with a header row.
+    Tsv,
+    /// A single JSON document.
+    Json,
+    /// One JSON document per line.
+    JsonLines,
+}
+
+impl DataFormat {
+    /// Detect the format from a file name's extension.
+    pub fn detect(file_name: &str) -> Option<Self> {
+        let extension = file_name.rsplit_once('.')?.1.to_ascii_lowercase();
+        match extension.as_str() {
+            "csv" => Some(Self::Csv),
+            "tsv" => Some(Self::Tsv),
+            "json" => Some(Self::Json),
+            "jsonl" | "ndjson" => Some(Self::JsonLines),
+            _ => None,
+        }
+    }
+
+    /// Whether previews read only the start of the file.
+    pub fn is_streaming(self) -> bool {
+        !matches!(self, Self::Json)
+    }
+}
+
+/// The first rows or keys of a data file.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "format", rename_all = "snake_case")]
+pub enum DataPreview {
+    /// Delimited rows; `columns` is the header row.
+    Table {
+        /// Header row.
+        columns: Vec<String>,
+        /// Data rows, at most the requested limit.
+        rows: Vec<Vec<String>>,
+        /// Whether the file has more rows than were returned.
+        truncated: bool,
+    },
+    /// A JSON document cut down to its first keys or items.
+    Json {
+        /// `object`, `array` or the scalar type.
+        kind: &'static str,
+        /// Number of keys or items at the top level, 1 for scalars.
+        length: usize,
+        /// The document with only the first keys or items kept.
+        value: Value,
+        /// Whether keys or items were left out.
+        truncated: bool,
+    },
+    /// JSON Lines records.
+    Records {
+        /// Parsed records, at most the requested limit.
+        records: Vec<Value>,
+        /// Whether the file has more records than were returned.
+        truncated: bool,
+    },
+}
+
+/// Preview data read from `reader`.
+///
+/// Only [`DataFormat::Json`] is read to the end.
+pub fn preview_reader(
+    format: DataFormat,
+    mut reader: impl BufRead,
+    limit: usize,
+) -> Result<DataPreview, String> {
+    match format {
+        DataFormat::Csv => preview_delimited(reader, b',', limit),
+        DataFormat::Tsv => preview_delimited(reader, b'\t', limit),
+        DataFormat::JsonLines => preview_json_lines(reader, limit),
+        DataFormat::Json => {
+            let mut text = String::new();
+            reader
+                .read_to_string(&mut text)
+                .map_err(|e| format!("Failed to read: {}", e))?;
+            preview_json(&text, limit)
+        }
+    }
+}
+
+/// Preview a JSON document, keeping the first `limit` keys or items.
+///
+/// Object keys are taken in sorted order.
+pub fn preview_json(text: &str, limit: usize) -> Result<DataPreview, String> {
+    let value: Value = serde_json::from_str(text).map_err(|e| format!("Invalid JSON: {}", e))?;
+
+    Ok(match value {
+        Value::Object(map) => {
+            let length = map.len();
+            let kept = map.into_iter().take(limit).collect();
+            DataPreview::Json {
+                kind: "object",
+                length,
+                value: Value::Object(kept),
+                truncated: length > limit,
+            }
+        }
+        Value::Array(items) => {
+            let length = items.len();
+            let kept = items.into_iter().take(limit).collect();
+            DataPreview::Json {
+                kind: "array",
+                length,
+                value: Value::Array(kept),
+                truncated: length > limit,
+            }
+        }
+        scalar => DataPreview::Json {
+            kind: match scalar {
+                Value::String(_) => "string",
+                Value::Number(_) => "number",
+                Value::Bool(_) => "boolean",
+                _ => "null",
+            },
+            length: 1,
+            value: scalar,
+            truncated: false,
+        },
+    })
+}
+
+/// Read the header and first `limit` rows of a delimited file.
+fn preview_delimited(
+    mut reader: impl BufRead,
+    delimiter: u8,
+    limit: usize,
+) -> Result<DataPreview, String> {
+    let mut next_record = || read_record(&mut reader, delimiter);
+
+    let columns = next_record()?.unwrap_or_default();
+    let mut rows = Vec::new();
+    let mut truncated = false;
+
+    while let Some(row) = next_record()? {
+        if rows.len() == limit {
+            truncated = true;
+            break;
+        }
+        rows.push(row);
+    }
+
+    Ok(DataPreview::Table {
+        columns,
+        rows,
+        truncated,
+    })
+}
+
+/// Read one record, following quoted fields across line breaks.
+///
+/// Quoting follows RFC 4180: fields may be wrapped in `"` and a doubled
+/// `""` inside a quoted field is a literal quote. Blank lines are skipped.
+fn read_record(reader: &mut impl BufRead, delimiter: u8) -> Result<Option<Vec<String>>, String> {
+    let mut line = String::new();
+    loop {
+        line.clear();
+        let read = reader
+            .read_line(&mut line)
+            .map_err(|e| format!("Failed to read: {}", e))?;
+        if read == 0 {
+            return Ok(None);
+        }
+        if !line.trim().is_empty() {
+            break;
+        }
+    }
+
+    let delimiter = delimiter as char;
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+
+    loop {
+        let mut chars = line.trim_end_matches(['\r', '\n']).chars().peekable();
+        while let Some(c) = chars.next() {
+            match (in_quotes, c) {
+                (true, '"') if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                (true, '"') => in_quotes = false,
+                (false, '"') if field.is_empty() => in_quotes = true,
+                (false, c) if c == delimiter => fields.push(std::mem::take(&mut field)),
+                (_, c) => field.push(c),
+            }
+        }
+
+        if !in_quotes {
+            break;
+        }
+
+        // A quoted field continues on the next line
+        field.push('\n');
+        line.clear();
+        let read = reader
+            .read_line(&mut line)
+            .map_err(|e| format!("Failed to read: {}", e))?;
+        if read == 0 {
+            return Err("Unterminated quoted field".to_string());
+        }
+    }
+
+    fields.push(field);
+    Ok(Some(fields))
+}
+
+/// Parse the first `limit` records of a JSON Lines file.
+fn preview_json_lines(reader: impl BufRead, limit: usize) -> Result<DataPreview, String> {
+    let mut records = Vec::new();
+    let mut truncated = false;
+
+    for (i, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| format!("Failed to read: {}", e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if records.len() == limit {
+            truncated = true;
+            break;
+        }
+        let record = serde_json::from_str(&line)
+            .map_err(|e| format!("Invalid JSON on line {}: {}", i + 1, e))?;
+        records.push(record);
+    }
+
+    Ok(DataPreview::Records { records, truncated })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preview_csv() {
+        let csv = "name,notes\nada,\"likes, commas\"\n\nbob,\"said \"\"hi\"\"\nthen left\"\ncy,x\n";
+        let preview = preview_reader(DataFormat::Csv, csv.as_bytes(), 2).unwrap();
+
+        assert_eq!(
+            preview,
+            DataPreview::Table {
+                columns: vec!["name".to_string(), "notes".to_string()],
+                rows: vec![
+                    vec!["ada".to_string(), "likes, commas".to_string()],
+                    vec!["bob".to_string(), "said \"hi\"\nthen left".to_string()],
+                ],
+                truncated: true,
+            }
+        );
+
+        let tsv = "a\tb\n1\t2\n";
+        let DataPreview::Table {
+            rows, truncated, ..
+        } = preview_reader(DataFormat::Tsv, tsv.as_bytes(), 10).unwrap()
+        else {
+            panic!("expected a table");
+        };
+        assert_eq!(rows, vec![vec!["1".to_string(), "2".to_string()]]);
+        assert!(!truncated);
+
+        assert!(preview_reader(DataFormat::Csv, "a\n\"open".as_bytes(), 10).is_err());
+    }
+
+    #[test]
+    fn test_preview_json() {
+        let preview = preview_json(r#"{"a": 1, "b": [1, 2], "c": null}"#, 2).unwrap();
+        assert_eq!(
+            preview,
+            DataPreview::Json {
+                kind: "object",
+                length: 3,
+                value: serde_json::json!({"a": 1, "b": [1, 2]}),
+                truncated: true,
+            }
+        );
+
+        let DataPreview::Json { kind, length, .. } = preview_json("\"text\"", 5).unwrap() else {
+            panic!("expected json");
+        };
+        assert_eq!((kind, length), ("string", 1));
+
+        assert!(preview_json("{", 5).is_err());
+    }
+
+    #[test]
+    fn test_preview_json_lines() {
+        let jsonl = "{\"id\": 1}\n\n{\"id\": 2}\n{\"id\": 3}\n";
+        let preview = preview_reader(DataFormat::JsonLines, jsonl.as_bytes(), 2).unwrap();
+        assert_eq!(
+            preview,
+            DataPreview::Records {
+                records: vec![serde_json::json!({"id": 1}), serde_json::json!({"id": 2})],
+                truncated: true,
+            }
+        );
+
+        let err = preview_reader(DataFormat::JsonLines, "{}\nnope\n".as_bytes(), 5).unwrap_err();
+        assert!(err.contains("line 2"));
+    }
+
+    #[test]
+    fn test_detect_format() {
+        assert_eq!(DataFormat::detect("data/rows.CSV"), Some(DataFormat::Csv));
+        assert_eq!(
+            DataFormat::detect("events.ndjson"),
+            Some(DataFormat::JsonLines)
+        );
+        assert_eq!(DataFormat::detect("SKILL.md"), None);
+        assert_eq!(DataFormat::detect("json"), None);
+    }
+}
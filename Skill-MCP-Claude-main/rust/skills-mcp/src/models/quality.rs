@@ -0,0 +1,229 @@
+//! Composite quality scores for triaging skills.
+//!
+//! A score is a weighted sum of independent factors, each between 0 and 1,
+//! scaled to 0–100. Factors are kept in the result so a low score can be
+//! traced to what is missing.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use super::SkillMeta;
+
+/// Weight of each factor; the weights sum to 100.
+const METADATA_WEIGHT: f64 = 20.0;
+const LENGTH_WEIGHT: f64 = 20.0;
+const STRUCTURE_WEIGHT: f64 = 15.0;
+const EXAMPLES_WEIGHT: f64 = 15.0;
+const FRESHNESS_WEIGHT: f64 = 10.0;
+const VALIDATION_WEIGHT: f64 = 20.0;
+
+/// Word count at which content length scores full marks.
+const FULL_LENGTH_WORDS: usize = 300;
+
+/// Headings at which structure scores full marks.
+const FULL_STRUCTURE_HEADINGS: usize = 3;
+
+/// Days a skill stays fully fresh, and days until freshness reaches zero.
+const FRESH_DAYS: i64 = 90;
+const STALE_DAYS: i64 = 730;
+
+/// Descriptions shorter than this count as missing.
+const MIN_DESCRIPTION_CHARS: usize = 20;
+
+/// What a quality score is computed from.
+#[derive(Debug, Clone, Copy)]
+pub struct QualitySignals<'a> {
+    /// The skill's metadata.
+    pub meta: &'a SkillMeta,
+    /// The main document's text.
+    pub content: &'a str,
+    /// When the main document was last modified, if known.
+    pub modified: Option<DateTime<Utc>>,
+    /// Validation errors for the skill.
+    pub errors: usize,
+    /// Validation warnings for the skill.
+    pub warnings: usize,
+}
+
+/// Per-factor scores, each between 0 and 1.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct QualityFactors {
+    /// Share of description, tags, owner, status and source that are set.
+    pub metadata: f64,
+    /// Content length, full at a few hundred words.
+    pub length: f64,
+    /// Markdown headings organising the content.
+    pub structure: f64,
+    /// Whether the content has code blocks or an examples section.
+    pub examples: f64,
+    /// How recently the content changed.
+    pub freshness: f64,
+    /// Penalty for validation errors and warnings.
+    pub validation: f64,
+}
+
+/// A skill's quality score and how it was reached.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct QualityScore {
+    /// Weighted score from 0 (weakest) to 100.
+    pub score: u8,
+    /// The factors behind the score.
+    pub factors: QualityFactors,
+}
+
+impl QualityScore {
+    /// Score a skill as of `now`.
+    pub fn compute(signals: &QualitySignals<'_>, now: DateTime<Utc>) -> Self {
+        let factors = QualityFactors {
+            metadata: metadata_factor(signals.meta),
+            length: length_factor(signals.content),
+            structure: structure_factor(signals.content),
+            examples: examples_factor(signals.content),
+            freshness: signals
+                .modified
+                .map(|modified| freshness_factor((now - modified).num_days()))
+                .unwrap_or(0.0),
+            validation: (1.0 - 0.5 * signals.errors as f64 - 0.1 * signals.warnings as f64)
+                .max(0.0),
+        };
+
+        let total = factors.metadata * METADATA_WEIGHT
+            + factors.length * LENGTH_WEIGHT
+            + factors.structure * STRUCTURE_WEIGHT
+            + factors.examples * EXAMPLES_WEIGHT
+            + factors.freshness * FRESHNESS_WEIGHT
+            + factors.validation * VALIDATION_WEIGHT;
+
+        Self {
+            score: total.round().clamp(0.0, 100.0) as u8,
+            factors,
+        }
+    }
+}
+
+fn metadata_factor(meta: &SkillMeta) -> f64 {
+    let present = [
+        meta.description.trim().chars().count() >= MIN_DESCRIPTION_CHARS,
+        !meta.tags.is_empty(),
+        meta.owner.is_some(),
+        meta.status.is_some(),
+        meta.source.is_some(),
+    ];
+    present.iter().filter(|p| **p).count() as f64 / present.len() as f64
+}
+
+fn length_factor(content: &str) -> f64 {
+    let words = body_lines(content)
+        .map(|line| line.split_whitespace().count())
+        .sum::<usize>();
+    (words as f64 / FULL_LENGTH_WORDS as f64).min(1.0)
+}
+
+fn structure_factor(content: &str) -> f64 {
+    let headings = body_lines(content)
+        .filter(|line| line.starts_with('#'))
+        .count();
+    (headings as f64 / FULL_STRUCTURE_HEADINGS as f64).min(1.0)
+}
+
+fn examples_factor(content: &str) -> f64 {
+    let has_examples = content.lines().any(|line| {
+        let line = line.trim_start();
+        line.starts_with("```")
+            || line.starts_with("~~~")
+            || (line.starts_with('#') && line.to_lowercase().contains("example"))
+    });
+    if has_examples {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+fn freshness_factor(age_days: i64) -> f64 {
+    if age_days <= FRESH_DAYS {
+        return 1.0;
+    }
+    (1.0 - (age_days - FRESH_DAYS) as f64 / (STALE_DAYS - FRESH_DAYS) as f64).max(0.0)
+}
+
+/// Lines outside fenced code blocks.
+fn body_lines(content: &str) -> impl Iterator<Item = &str> {
+    let mut in_fence = false;
+    content.lines().filter(move |line| {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            return false;
+        }
+        !in_fence
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SkillStatus;
+    use chrono::Duration;
+
+    #[test]
+    fn test_complete_skill_scores_full_marks() {
+        let meta = SkillMeta {
+            name: "forms".to_string(),
+            description: "Build accessible forms with validation".to_string(),
+            tags: vec!["forms".to_string()],
+            owner: Some("web-team".to_string()),
+            status: Some(SkillStatus::Published),
+            source: Some("official".to_string()),
+            ..Default::default()
+        };
+        let content = format!(
+            "# Forms\n\n## Usage\n\n{}\n\n## Examples\n\n```html\n<form></form>\n```\n",
+            "word ".repeat(300)
+        );
+        let now = Utc::now();
+        let signals = QualitySignals {
+            meta: &meta,
+            content: &content,
+            modified: Some(now - Duration::days(10)),
+            errors: 0,
+            warnings: 0,
+        };
+
+        let quality = QualityScore::compute(&signals, now);
+        assert_eq!(quality.score, 100);
+    }
+
+    #[test]
+    fn test_weak_skill_scores_low() {
+        let meta = SkillMeta {
+            name: "stub".to_string(),
+            description: "Todo".to_string(),
+            ..Default::default()
+        };
+        let now = Utc::now();
+        let signals = QualitySignals {
+            meta: &meta,
+            content: "Some notes.\n```\n# not a heading\n```\n",
+            modified: Some(now - Duration::days(1000)),
+            errors: 1,
+            warnings: 1,
+        };
+
+        let quality = QualityScore::compute(&signals, now);
+        assert_eq!(quality.factors.metadata, 0.0);
+        assert_eq!(quality.factors.structure, 0.0);
+        assert_eq!(quality.factors.examples, 1.0);
+        assert_eq!(quality.factors.freshness, 0.0);
+        assert!((quality.factors.validation - 0.4).abs() < 1e-9);
+        assert_eq!(quality.score, 23);
+    }
+
+    #[test]
+    fn test_freshness_decays_linearly() {
+        assert_eq!(freshness_factor(0), 1.0);
+        assert_eq!(freshness_factor(FRESH_DAYS), 1.0);
+        assert!((freshness_factor((FRESH_DAYS + STALE_DAYS) / 2) - 0.5).abs() < 1e-9);
+        assert_eq!(freshness_factor(STALE_DAYS * 2), 0.0);
+    }
+}
@@ -0,0 +1,287 @@
+//! Scripts bundled in a skill's `scripts/` directory.
+//!
+//! Scripts are only described here, never executed: agents read the
+//! language and docstring to decide whether to run one themselves.
+
+use serde::{Deserialize, Serialize};
+
+/// Longest docstring kept for a script, in characters.
+pub const MAX_DOCSTRING_CHARS: usize = 500;
+
+/// Language of a bundled script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScriptLanguage {
+    /// Python (`.py`).
+    Python,
+    /// POSIX shell or bash (`.sh`, `.bash`).
+    Shell,
+    /// JavaScript (`.js`, `.mjs`, `.cjs`).
+    JavaScript,
+    /// TypeScript (`.ts`).
+    TypeScript,
+    /// Ruby (`.rb`).
+    Ruby,
+    /// PowerShell (`.ps1`).
+    PowerShell,
+    /// Anything else.
+    Other,
+}
+
+impl ScriptLanguage {
+    /// Detect the language from a file name, falling back to the shebang.
+    pub fn detect(file_name: &str, first_line: &str) -> Self {
+        let extension = file_name
+            .rsplit_once('.')
+            .map(|(_, ext)| ext.to_ascii_lowercase());
+
+        match extension.as_deref() {
+            Some("py") => return Self::Python,
+            Some("sh" | "bash" | "zsh") => return Self::Shell,
+            Some("js" | "mjs" | "cjs") => return Self::JavaScript,
+            Some("ts" | "mts" | "cts") => return Self::TypeScript,
+            Some("rb") => return Self::Ruby,
+            Some("ps1") => return Self::PowerShell,
+            _ => {}
+        }
+
+        let Some(shebang) = first_line.strip_prefix("#!") else {
+            return Self::Other;
+        };
+
+        // `#!/usr/bin/env python3` names the interpreter last
+        let interpreter = shebang
+            .split_whitespace()
+            .find(|part| !part.ends_with("/env") && !part.starts_with('-'))
+            .and_then(|part| part.rsplit('/').next())
+            .unwrap_or_default();
+
+        match interpreter.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.') {
+            "python" => Self::Python,
+            "sh" | "bash" | "zsh" | "dash" => Self::Shell,
+            "node" => Self::JavaScript,
+            "deno" | "ts-node" | "tsx" => Self::TypeScript,
+            "ruby" => Self::Ruby,
+            "pwsh" => Self::PowerShell,
+            _ => Self::Other,
+        }
+    }
+
+    /// Line comment marker used for leading doc comments.
+    fn line_comment(self) -> Option<&'static str> {
+        match self {
+            Self::Python | Self::Shell | Self::Ruby | Self::PowerShell => Some("#"),
+            Self::JavaScript | Self::TypeScript => Some("//"),
+            Self::Other => None,
+        }
+    }
+}
+
+/// A script bundled with a skill.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkillScript {
+    /// Path relative to the skill directory, e.g. `scripts/fill_form.py`.
+    pub path: String,
+
+    /// File name, e.g. `fill_form.py`.
+    pub name: String,
+
+    /// Detected language.
+    pub language: ScriptLanguage,
+
+    /// First paragraph of the module docstring or leading comment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// File size in bytes.
+    pub size: u64,
+}
+
+impl SkillScript {
+    /// Describe a script from its path and the start of its source.
+    pub fn from_source(path: String, source: &str, size: u64) -> Self {
+        let name = path.rsplit('/').next().unwrap_or(&path).to_string();
+        let language = ScriptLanguage::detect(&name, source.lines().next().unwrap_or_default());
+
+        Self {
+            description: extract_docstring(language, source),
+            path,
+            name,
+            language,
+            size,
+        }
+    }
+
+    /// Text indexed for search: the file name and description.
+    pub fn index_text(&self) -> String {
+        match &self.description {
+            Some(description) => format!("{}\n\n{}", self.name, description),
+            None => self.name.clone(),
+        }
+    }
+}
+
+/// Extract the first paragraph of a script's module docstring.
+///
+/// Python `"""` / `'''` docstrings and JS/TS `/** */` blocks are read, as
+/// are leading line comments for every language that has them. Shebangs,
+/// encoding lines and `"use strict"` are skipped.
+pub fn extract_docstring(language: ScriptLanguage, source: &str) -> Option<String> {
+    let mut lines = source
+        .lines()
+        .skip_while(|l| l.starts_with("#!"))
+        .skip_while(|l| {
+            let t = l.trim();
+            t.is_empty()
+                || t.contains("-*- coding")
+                || t.starts_with("# vim:")
+                || t.trim_end_matches(';') == "\"use strict\""
+                || t.trim_end_matches(';') == "'use strict'"
+        })
+        .peekable();
+
+    let first = lines.peek()?.trim();
+    let mut text = Vec::new();
+
+    if language == ScriptLanguage::Python
+        && (first.starts_with("\"\"\"") || first.starts_with("'''"))
+    {
+        let quote = &first[..3];
+        let rest = &first[3..];
+        if let Some(end) = rest.find(quote) {
+            text.push(rest[..end].to_string());
+        } else {
+            text.push(rest.to_string());
+            lines.next();
+            for line in lines {
+                if let Some(end) = line.find(quote) {
+                    text.push(line[..end].to_string());
+                    break;
+                }
+                text.push(line.to_string());
+            }
+        }
+    } else if matches!(
+        language,
+        ScriptLanguage::JavaScript | ScriptLanguage::TypeScript
+    ) && first.starts_with("/*")
+    {
+        for line in lines {
+            let t = line.trim();
+            let done = t.contains("*/");
+            let t = t.split("*/").next().unwrap_or_default();
+            let t = t.trim_start_matches("/**").trim_start_matches("/*");
+            text.push(t.trim_start_matches('*').trim().to_string());
+            if done {
+                break;
+            }
+        }
+    } else if let Some(marker) = language.line_comment() {
+        for line in lines {
+            match line.trim().strip_prefix(marker) {
+                Some(comment) => text.push(comment.trim_start_matches(marker).trim().to_string()),
+                None => break,
+            }
+        }
+    }
+
+    // Keep the first paragraph only
+    let paragraph: Vec<&str> = text
+        .iter()
+        .map(|l| l.trim())
+        .skip_while(|l| l.is_empty())
+        .take_while(|l| !l.is_empty())
+        .collect();
+    if paragraph.is_empty() {
+        return None;
+    }
+
+    let joined = paragraph.join(" ");
+    Some(match joined.char_indices().nth(MAX_DOCSTRING_CHARS) {
+        Some((cut, _)) => format!("{}…", joined[..cut].trim_end()),
+        None => joined,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_language() {
+        assert_eq!(
+            ScriptLanguage::detect("fill.py", ""),
+            ScriptLanguage::Python
+        );
+        assert_eq!(
+            ScriptLanguage::detect("build.SH", ""),
+            ScriptLanguage::Shell
+        );
+        assert_eq!(
+            ScriptLanguage::detect("run", "#!/usr/bin/env python3"),
+            ScriptLanguage::Python
+        );
+        assert_eq!(
+            ScriptLanguage::detect("run", "#!/bin/bash -e"),
+            ScriptLanguage::Shell
+        );
+        assert_eq!(
+            ScriptLanguage::detect("run", "#!/usr/bin/env node"),
+            ScriptLanguage::JavaScript
+        );
+        assert_eq!(
+            ScriptLanguage::detect("data.csv", "a,b"),
+            ScriptLanguage::Other
+        );
+    }
+
+    #[test]
+    fn test_python_docstring() {
+        let source = "#!/usr/bin/env python3\n# -*- coding: utf-8 -*-\n\"\"\"Fill a PDF form\nfrom a JSON file.\n\nUsage: fill.py in.pdf\n\"\"\"\nimport sys\n";
+        assert_eq!(
+            extract_docstring(ScriptLanguage::Python, source).as_deref(),
+            Some("Fill a PDF form from a JSON file.")
+        );
+        assert_eq!(
+            extract_docstring(ScriptLanguage::Python, "'''One line.'''\n").as_deref(),
+            Some("One line.")
+        );
+        assert_eq!(
+            extract_docstring(ScriptLanguage::Python, "import sys\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_comment_docstrings() {
+        let shell = "#!/bin/sh\n# Rebuild the index.\n# Needs network.\n\nset -e\n";
+        assert_eq!(
+            extract_docstring(ScriptLanguage::Shell, shell).as_deref(),
+            Some("Rebuild the index. Needs network.")
+        );
+
+        let js = "'use strict';\n/**\n * Convert slides to images.\n */\nconst fs = require('fs');";
+        assert_eq!(
+            extract_docstring(ScriptLanguage::JavaScript, js).as_deref(),
+            Some("Convert slides to images.")
+        );
+
+        let ts = "// Validate a manifest\nexport {};";
+        assert_eq!(
+            extract_docstring(ScriptLanguage::TypeScript, ts).as_deref(),
+            Some("Validate a manifest")
+        );
+    }
+
+    #[test]
+    fn test_from_source() {
+        let script = SkillScript::from_source(
+            "scripts/tools/fill.py".to_string(),
+            "\"\"\"Fill forms.\"\"\"",
+            20,
+        );
+        assert_eq!(script.name, "fill.py");
+        assert_eq!(script.language, ScriptLanguage::Python);
+        assert_eq!(script.index_text(), "fill.py\n\nFill forms.");
+    }
+}
@@ -2,6 +2,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use super::{SkillMeta, SkillStatus, TagExpr};
+
 /// How a search result was matched.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -14,6 +16,8 @@ pub enum MatchType {
     Tags,
     /// Matched trigger words.
     Triggers,
+    /// Matched keywords extracted from content.
+    Keywords,
     /// Matched content body.
     Content,
 }
@@ -27,11 +31,23 @@ impl MatchType {
             MatchType::Triggers => 2.5,
             MatchType::Tags => 2.0,
             MatchType::Description => 1.5,
+            MatchType::Keywords => 1.25,
             MatchType::Content => 1.0,
         }
     }
 }
 
+/// Score at which a result's confidence is 0.5.
+pub const CONFIDENCE_MIDPOINT: f64 = 2.0;
+
+/// Calibrate a relevance score onto 0..1, reaching 0.5 at
+/// [`CONFIDENCE_MIDPOINT`]. Rounded to two decimals.
+pub fn score_confidence(score: f64) -> f64 {
+    let score = score.max(0.0);
+    let confidence = score / (score + CONFIDENCE_MIDPOINT);
+    (confidence * 100.0).round() / 100.0
+}
+
 /// A single search result.
 ///
 /// Corresponds to `SearchResult` in TypeScript.
@@ -41,15 +57,20 @@ pub struct SearchResult {
     pub domain: String,
 
     /// Sub-skill name if matched within a sub-skill.
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(default, alias = "subSkill", skip_serializing_if = "Option::is_none")]
     pub sub_skill: Option<String>,
 
     /// Relevance score (0.0 to 1.0+).
     pub score: f64,
 
-    /// How the match was found.
+    /// How the match was found. For merged results, the strongest match.
+    #[serde(alias = "matchType")]
     pub match_type: MatchType,
 
+    /// Every way this result matched, strongest first.
+    #[serde(default, alias = "matchTypes", skip_serializing_if = "Vec::is_empty")]
+    pub match_types: Vec<MatchType>,
+
     /// Optional excerpt showing match context.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub snippet: Option<String>,
@@ -57,9 +78,32 @@ pub struct SearchResult {
     /// Optional file path for content matches.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub file: Option<String>,
+
+    /// Summary of the skill, for skill-level results.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+
+    /// Whether the requesting session already loaded this skill.
+    #[serde(
+        default,
+        alias = "alreadyLoaded",
+        skip_serializing_if = "std::ops::Not::not"
+    )]
+    pub already_loaded: bool,
+
+    /// Score the reranker gave this result, if it was reranked.
+    #[serde(
+        default,
+        alias = "rerankScore",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub rerank_score: Option<f64>,
 }
 
 impl SearchResult {
+    /// Longest snippet kept in compact text output.
+    const COMPACT_SNIPPET_CHARS: usize = 160;
+
     /// Create a new search result.
     pub fn new(domain: String, score: f64, match_type: MatchType) -> Self {
         Self {
@@ -67,11 +111,39 @@ impl SearchResult {
             sub_skill: None,
             score,
             match_type,
+            match_types: vec![match_type],
             snippet: None,
             file: None,
+            summary: None,
+            already_loaded: false,
+            rerank_score: None,
         }
     }
 
+    /// Weight applied to the weaker score when merging two hits.
+    pub const SECONDARY_MATCH_WEIGHT: f64 = 0.5;
+
+    /// Merge another hit for the same domain and sub-skill into this one.
+    ///
+    /// The stronger hit supplies the match type, snippet and file. The
+    /// weaker one boosts the score by [`Self::SECONDARY_MATCH_WEIGHT`] of
+    /// its own score, so a skill matching both metadata and content ranks
+    /// above one matching only either.
+    pub fn merge(&mut self, mut other: SearchResult) {
+        // Keep the stronger hit in `self`
+        if other.score > self.score {
+            std::mem::swap(self, &mut other);
+        }
+
+        for t in std::iter::once(other.match_type).chain(other.match_types) {
+            if !self.match_types.contains(&t) {
+                self.match_types.push(t);
+            }
+        }
+
+        self.score += other.score * Self::SECONDARY_MATCH_WEIGHT;
+    }
+
     /// Set sub-skill.
     pub fn with_sub_skill(mut self, sub_skill: String) -> Self {
         self.sub_skill = Some(sub_skill);
@@ -97,6 +169,29 @@ impl SearchResult {
             None => self.domain.clone(),
         }
     }
+
+    /// One-line `id | score | file | snippet` summary for compact output.
+    pub fn to_compact_line(&self) -> String {
+        let mut line = format!("{} | {:.2}", self.display_id(), self.score);
+
+        if let Some(file) = &self.file {
+            line.push_str(" | ");
+            line.push_str(file);
+        }
+
+        if let Some(snippet) = &self.snippet {
+            let snippet = snippet.split_whitespace().collect::<Vec<_>>().join(" ");
+            line.push_str(" | ");
+            if snippet.chars().count() > Self::COMPACT_SNIPPET_CHARS {
+                line.extend(snippet.chars().take(Self::COMPACT_SNIPPET_CHARS));
+                line.push('…');
+            } else {
+                line.push_str(&snippet);
+            }
+        }
+
+        line
+    }
 }
 
 impl PartialEq for SearchResult {
@@ -123,6 +218,153 @@ impl Ord for SearchResult {
     }
 }
 
+/// Which index a search runs against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "native", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum SearchScope {
+    /// Skill metadata (names, descriptions, tags, triggers).
+    #[default]
+    Skills,
+    /// Full-text markdown content.
+    Content,
+    /// Both metadata and content.
+    All,
+}
+
+/// How many terms of a multi-term query a result must match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MinimumShouldMatch {
+    /// Every term must match.
+    All,
+    /// At least this many terms, capped at the number of terms.
+    Count(usize),
+    /// At least this percentage of terms, rounded down but never below one.
+    Percent(u8),
+}
+
+impl MinimumShouldMatch {
+    /// Number of terms required out of `terms`.
+    pub fn required(&self, terms: usize) -> usize {
+        let required = match *self {
+            MinimumShouldMatch::All => terms,
+            MinimumShouldMatch::Count(n) => n.min(terms),
+            MinimumShouldMatch::Percent(p) => terms * usize::from(p.min(100)) / 100,
+        };
+        required.max(1).min(terms)
+    }
+}
+
+impl std::str::FromStr for MinimumShouldMatch {
+    type Err = String;
+
+    /// Parse `all`, a term count like `2`, or a percentage like `75%`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("all") {
+            return Ok(MinimumShouldMatch::All);
+        }
+
+        if let Some(pct) = s.strip_suffix('%') {
+            return match pct.trim().parse::<u8>() {
+                Ok(p) if p <= 100 => Ok(MinimumShouldMatch::Percent(p)),
+                _ => Err(format!("Invalid min_should_match percentage: '{}'", s)),
+            };
+        }
+
+        s.parse::<usize>()
+            .map(MinimumShouldMatch::Count)
+            .map_err(|_| {
+                format!(
+                    "Invalid min_should_match: '{}' (use 'all', a count, or a percentage)",
+                    s
+                )
+            })
+    }
+}
+
+/// What to do when a query returns no results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpellingMode {
+    /// Return "did you mean" suggestions alongside the empty result.
+    Suggest,
+    /// Retry with the best correction and report it as `corrected_query`.
+    Auto,
+}
+
+impl std::str::FromStr for SpellingMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "suggest" => Ok(SpellingMode::Suggest),
+            "auto" => Ok(SpellingMode::Auto),
+            _ => Err(format!(
+                "Invalid spelling mode: '{}' (use 'suggest' or 'auto')",
+                s
+            )),
+        }
+    }
+}
+
+/// How search tools render their response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchFormat {
+    /// Structured JSON results.
+    #[default]
+    Json,
+    /// Compact plain text, one line per result, for LLM consumption.
+    Llm,
+}
+
+impl std::str::FromStr for SearchFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "json" => Ok(SearchFormat::Json),
+            "llm" => Ok(SearchFormat::Llm),
+            _ => Err(format!("Invalid format: '{}' (use 'json' or 'llm')", s)),
+        }
+    }
+}
+
+/// Longest search query accepted, in bytes.
+pub const MAX_SEARCH_QUERY_LENGTH: usize = 1000;
+
+/// Most queries one batch search may run.
+pub const MAX_BATCH_QUERIES: usize = 20;
+
+/// Check the queries of a batch search: at least one, no more than
+/// [`MAX_BATCH_QUERIES`], and none empty or longer than
+/// [`MAX_SEARCH_QUERY_LENGTH`].
+pub fn check_batch_queries(queries: &[String]) -> Result<(), String> {
+    if queries.is_empty() {
+        return Err("At least one query is required".to_string());
+    }
+
+    if queries.len() > MAX_BATCH_QUERIES {
+        return Err(format!("Too many queries (max {})", MAX_BATCH_QUERIES));
+    }
+
+    for q in queries {
+        if q.is_empty() {
+            return Err("Search query cannot be empty".to_string());
+        }
+
+        if q.len() > MAX_SEARCH_QUERY_LENGTH {
+            return Err(format!(
+                "Search query too long (max {} characters)",
+                MAX_SEARCH_QUERY_LENGTH
+            ));
+        }
+    }
+
+    Ok(())
+}
+
 /// Search query options.
 #[derive(Debug, Clone, Default)]
 pub struct SearchOptions {
@@ -137,6 +379,37 @@ pub struct SearchOptions {
 
     /// Filter to specific domains.
     pub domains: Option<Vec<String>>,
+
+    /// Number of ranked results to skip before the returned page.
+    pub offset: Option<usize>,
+
+    /// How many query terms a result must match. Defaults to any one term.
+    pub min_should_match: Option<MinimumShouldMatch>,
+
+    /// Only skills with this source (e.g., "official"), case-insensitive.
+    pub source: Option<String>,
+
+    /// Only skills in this language, case-insensitive.
+    pub language: Option<String>,
+
+    /// Only skills with this publication status.
+    pub status: Option<SkillStatus>,
+
+    /// Only skills owned by this person or team, case-insensitive.
+    pub owner: Option<String>,
+
+    /// Only skills whose tags satisfy this expression.
+    pub tags: Option<TagExpr>,
+
+    /// Only skills in this category (e.g. `frontend`) or its subcategories.
+    pub category: Option<String>,
+
+    /// Spelling correction for queries with no results. Off by default.
+    pub spelling: Option<SpellingMode>,
+
+    /// Lowest confidence the top hit of a first page may have; weaker
+    /// results are withheld as no confident match. Off by default.
+    pub min_confidence: Option<f64>,
 }
 
 impl SearchOptions {
@@ -159,6 +432,164 @@ impl SearchOptions {
         self.domains = Some(domains);
         self
     }
+
+    /// Skip the first `offset` ranked results.
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Require a minimum number of query terms to match.
+    pub fn min_should_match(mut self, msm: MinimumShouldMatch) -> Self {
+        self.min_should_match = Some(msm);
+        self
+    }
+
+    /// Only skills with this source.
+    pub fn source(mut self, source: impl Into<String>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    /// Only skills in this language.
+    pub fn language(mut self, language: impl Into<String>) -> Self {
+        self.language = Some(language.into());
+        self
+    }
+
+    /// Only skills with this status.
+    pub fn status(mut self, status: SkillStatus) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Only skills owned by this person or team.
+    pub fn owner(mut self, owner: impl Into<String>) -> Self {
+        self.owner = Some(owner.into());
+        self
+    }
+
+    /// Only skills whose tags satisfy an expression.
+    pub fn tags(mut self, expr: TagExpr) -> Self {
+        self.tags = Some(expr);
+        self
+    }
+
+    /// Only skills in a category or its subcategories.
+    pub fn category(mut self, category: impl Into<String>) -> Self {
+        self.category = Some(category.into());
+        self
+    }
+
+    /// Correct misspelled queries that return no results.
+    pub fn spelling(mut self, mode: SpellingMode) -> Self {
+        self.spelling = Some(mode);
+        self
+    }
+
+    /// Withhold results whose top hit is less confident than this.
+    pub fn min_confidence(mut self, confidence: f64) -> Self {
+        self.min_confidence = Some(confidence);
+        self
+    }
+
+    /// Whether any metadata filter (source, language, status, owner, tags,
+    /// category) is set.
+    pub fn has_meta_filters(&self) -> bool {
+        self.source.is_some()
+            || self.language.is_some()
+            || self.status.is_some()
+            || self.owner.is_some()
+            || self.tags.is_some()
+            || self.category.is_some()
+    }
+
+    /// Check a skill against the domain and metadata filters.
+    pub fn matches_meta(&self, meta: &SkillMeta) -> bool {
+        fn eq(filter: &Option<String>, value: &Option<String>) -> bool {
+            match (filter, value) {
+                (None, _) => true,
+                (Some(f), Some(v)) => f.eq_ignore_ascii_case(v),
+                (Some(_), None) => false,
+            }
+        }
+
+        if let Some(ref domains) = self.domains {
+            if !domains.contains(&meta.name) {
+                return false;
+            }
+        }
+
+        eq(&self.source, &meta.source)
+            && eq(&self.language, &meta.language)
+            && eq(&self.owner, &meta.owner)
+            && self.status.map(|s| meta.status == Some(s)).unwrap_or(true)
+            && self
+                .tags
+                .as_ref()
+                .map(|e| e.matches(&meta.tags))
+                .unwrap_or(true)
+            && self
+                .category
+                .as_ref()
+                .map(|c| meta.in_category(c))
+                .unwrap_or(true)
+    }
+
+    /// Whether `matched` of `terms` query terms satisfies the requirement.
+    pub fn terms_satisfied(&self, matched: usize, terms: usize) -> bool {
+        match self.min_should_match {
+            Some(msm) if terms > 1 => matched >= msm.required(terms),
+            _ => true,
+        }
+    }
+}
+
+/// Pagination cursor for search results.
+///
+/// Records where the next page starts and which index version produced the
+/// ranking. Clients treat the encoded form as opaque.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchCursor {
+    /// Offset of the first result on the next page.
+    pub offset: usize,
+
+    /// Index version the results were ranked against.
+    pub version: u64,
+}
+
+impl SearchCursor {
+    /// Create a new cursor.
+    pub fn new(offset: usize, version: u64) -> Self {
+        Self { offset, version }
+    }
+
+    /// Encode the cursor as an opaque string.
+    pub fn encode(&self) -> String {
+        format!("{}:{}", self.offset, self.version)
+            .bytes()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    /// Decode a cursor produced by [`SearchCursor::encode`].
+    pub fn decode(cursor: &str) -> Option<Self> {
+        if !cursor.len().is_multiple_of(2) || !cursor.is_ascii() {
+            return None;
+        }
+
+        let bytes = (0..cursor.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&cursor[i..i + 2], 16).ok())
+            .collect::<Option<Vec<u8>>>()?;
+        let text = String::from_utf8(bytes).ok()?;
+        let (offset, version) = text.split_once(':')?;
+
+        Some(Self {
+            offset: offset.parse().ok()?,
+            version: version.parse().ok()?,
+        })
+    }
 }
 
 /// Results from a search operation.
@@ -173,31 +604,124 @@ pub struct SearchResults {
     /// Total matches before limit applied.
     pub total_matches: usize,
 
-    /// Whether results were truncated.
+    /// Whether more results exist after this page.
     pub truncated: bool,
+
+    /// Offset of the first returned result in the full ranking.
+    #[serde(default)]
+    pub offset: usize,
+
+    /// Cursor for the next page, if there is one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+
+    /// Query actually run when the original was auto-corrected.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub corrected_query: Option<String>,
+
+    /// "Did you mean" alternatives for a query with no results.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub suggestions: Vec<String>,
+
+    /// Whether this empty result was served from the zero-result cache.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cached: bool,
+
+    /// Whether hits were withheld because the best of them fell below the
+    /// minimum confidence.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub no_confident_match: bool,
+
+    /// Where to look instead when there is no confident match.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explore: Option<Exploration>,
+
+    /// Reranker that reordered the top results, if one did.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reranked_by: Option<String>,
+}
+
+/// Where to look next when nothing matched confidently.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Exploration {
+    /// Near misses worth a look, as `skill` or `skill:sub-skill`, best first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub candidates: Vec<String>,
+
+    /// Tags to browse, taken from the near misses or, without any, from
+    /// the whole index.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
 }
 
 impl SearchResults {
     /// Create new search results.
-    pub fn new(query: String, mut results: Vec<SearchResult>, limit: Option<usize>) -> Self {
-        // Sort by score descending
-        results.sort();
+    pub fn new(query: String, results: Vec<SearchResult>, limit: Option<usize>) -> Self {
+        Self::page(query, results, 0, limit)
+    }
+
+    /// Create one page of search results.
+    ///
+    /// Results are ranked by score, with ties broken by domain, sub-skill
+    /// and file so pages are stable across calls.
+    pub fn page(
+        query: String,
+        mut results: Vec<SearchResult>,
+        offset: usize,
+        limit: Option<usize>,
+    ) -> Self {
+        results.sort_by(|a, b| {
+            a.cmp(b)
+                .then_with(|| a.domain.cmp(&b.domain))
+                .then_with(|| a.sub_skill.cmp(&b.sub_skill))
+                .then_with(|| a.file.cmp(&b.file))
+        });
 
         let total_matches = results.len();
-        let truncated = limit.map(|l| total_matches > l).unwrap_or(false);
+        let mut results: Vec<SearchResult> = results.into_iter().skip(offset).collect();
 
         if let Some(limit) = limit {
             results.truncate(limit);
         }
 
+        let truncated = offset + results.len() < total_matches;
+
         Self {
             results,
             query,
             total_matches,
             truncated,
+            offset,
+            next_cursor: None,
+            corrected_query: None,
+            suggestions: Vec::new(),
+            cached: false,
+            no_confident_match: false,
+            explore: None,
+            reranked_by: None,
         }
     }
 
+    /// Withhold every hit as no confident match, pointing at `explore`
+    /// instead.
+    pub fn withhold(&mut self, explore: Exploration) {
+        self.results.clear();
+        self.total_matches = 0;
+        self.truncated = false;
+        self.next_cursor = None;
+        self.no_confident_match = true;
+        self.explore = Some(explore);
+    }
+
+    /// Attach a next-page cursor if more results remain.
+    pub fn with_cursor(mut self, version: u64) -> Self {
+        if self.truncated && !self.results.is_empty() {
+            let next = self.offset + self.results.len();
+            self.next_cursor = Some(SearchCursor::new(next, version).encode());
+        }
+        self
+    }
+
     /// Check if any results were found.
     pub fn is_empty(&self) -> bool {
         self.results.is_empty()
@@ -212,6 +736,163 @@ impl SearchResults {
     pub fn top(&self) -> Option<&SearchResult> {
         self.results.first()
     }
+
+    /// Render as compact plain text: a header, one line per result, then
+    /// paging and spelling hints.
+    pub fn to_compact_text(&self) -> String {
+        let mut out = if self.no_confident_match {
+            format!("query: {} (no confident match)\n", self.query)
+        } else if self.is_empty() {
+            format!("query: {} (no results)\n", self.query)
+        } else {
+            format!(
+                "query: {} ({}-{} of {})\n",
+                self.query,
+                self.offset + 1,
+                self.offset + self.len(),
+                self.total_matches
+            )
+        };
+
+        if let Some(corrected) = &self.corrected_query {
+            out.push_str(&format!("corrected: {}\n", corrected));
+        }
+
+        for result in &self.results {
+            out.push_str(&result.to_compact_line());
+            out.push('\n');
+        }
+
+        if let Some(cursor) = &self.next_cursor {
+            out.push_str(&format!("next_cursor: {}\n", cursor));
+        }
+
+        if !self.suggestions.is_empty() {
+            out.push_str(&format!("did you mean: {}\n", self.suggestions.join(", ")));
+        }
+
+        if let Some(explore) = &self.explore {
+            if !explore.candidates.is_empty() {
+                out.push_str(&format!("explore: {}\n", explore.candidates.join(", ")));
+            }
+            if !explore.tags.is_empty() {
+                out.push_str(&format!("tags: {}\n", explore.tags.join(", ")));
+            }
+        }
+
+        out
+    }
+}
+
+/// Content matches for one skill domain.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DomainHits {
+    /// Skill domain name.
+    pub domain: String,
+
+    /// Score of the best match in this domain.
+    pub score: f64,
+
+    /// Number of matching files in this domain.
+    pub total_matches: usize,
+
+    /// Top file-level matches, best first.
+    pub hits: Vec<SearchResult>,
+}
+
+/// Search results grouped by skill domain.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupedSearchResults {
+    /// Domains ordered by their best match.
+    pub groups: Vec<DomainHits>,
+
+    /// Original query.
+    pub query: String,
+
+    /// Total matching domains before limit applied.
+    pub total_domains: usize,
+
+    /// Whether domains were truncated.
+    pub truncated: bool,
+}
+
+impl GroupedSearchResults {
+    /// Group ranked results by domain.
+    ///
+    /// Keeps the top `per_domain` hits of each domain and at most `limit`
+    /// domains.
+    pub fn new(results: SearchResults, per_domain: usize, limit: Option<usize>) -> Self {
+        let mut groups: Vec<DomainHits> = Vec::new();
+
+        // Results arrive ranked, so a domain's first hit is its best
+        for result in results.results {
+            match groups.iter_mut().find(|g| g.domain == result.domain) {
+                Some(group) => {
+                    group.total_matches += 1;
+                    if group.hits.len() < per_domain {
+                        group.hits.push(result);
+                    }
+                }
+                None => groups.push(DomainHits {
+                    domain: result.domain.clone(),
+                    score: result.score,
+                    total_matches: 1,
+                    hits: if per_domain > 0 {
+                        vec![result]
+                    } else {
+                        Vec::new()
+                    },
+                }),
+            }
+        }
+
+        let total_domains = groups.len();
+        let truncated = limit.map(|l| total_domains > l).unwrap_or(false);
+
+        if let Some(limit) = limit {
+            groups.truncate(limit);
+        }
+
+        Self {
+            groups,
+            query: results.query,
+            total_domains,
+            truncated,
+        }
+    }
+
+    /// Check if any results were found.
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// Render as compact plain text, one block of indented hits per domain.
+    pub fn to_compact_text(&self) -> String {
+        if self.is_empty() {
+            return format!("query: {} (no results)\n", self.query);
+        }
+
+        let mut out = format!(
+            "query: {} ({} of {} domains)\n",
+            self.query,
+            self.groups.len(),
+            self.total_domains
+        );
+
+        for group in &self.groups {
+            out.push_str(&format!(
+                "{} | {:.2} | {} files\n",
+                group.domain, group.score, group.total_matches
+            ));
+            for hit in &group.hits {
+                out.push_str("  ");
+                out.push_str(&hit.to_compact_line());
+                out.push('\n');
+            }
+        }
+
+        out
+    }
 }
 
 #[cfg(test)]
@@ -226,7 +907,7 @@ mod tests {
 
     #[test]
     fn test_search_result_ordering() {
-        let mut results = vec![
+        let mut results = [
             SearchResult::new("low".to_string(), 0.3, MatchType::Content),
             SearchResult::new("high".to_string(), 0.9, MatchType::Name),
             SearchResult::new("mid".to_string(), 0.6, MatchType::Tags),
@@ -239,6 +920,102 @@ mod tests {
         assert_eq!(results[2].domain, "low");
     }
 
+    #[test]
+    fn test_merge_combines_scores_and_match_types() {
+        let mut meta = SearchResult::new("forms".to_string(), 2.4, MatchType::Name);
+        let content = SearchResult::new("forms".to_string(), 0.2, MatchType::Content)
+            .with_file("SKILL.md".to_string())
+            .with_snippet("...forms...".to_string());
+
+        meta.merge(content);
+
+        assert_eq!(meta.match_type, MatchType::Name);
+        assert_eq!(meta.match_types, [MatchType::Name, MatchType::Content]);
+        assert!((meta.score - 2.5).abs() < 1e-9);
+        assert!(meta.snippet.is_none());
+
+        // The stronger hit keeps its details regardless of merge order
+        let mut content = SearchResult::new("api".to_string(), 0.9, MatchType::Content)
+            .with_file("SKILL.md".to_string());
+        content.merge(SearchResult::new(
+            "api".to_string(),
+            0.1,
+            MatchType::Description,
+        ));
+        content.merge(SearchResult::new(
+            "api".to_string(),
+            0.1,
+            MatchType::Content,
+        ));
+        assert_eq!(content.file.as_deref(), Some("SKILL.md"));
+        assert_eq!(
+            content.match_types,
+            [MatchType::Content, MatchType::Description]
+        );
+        assert!((content.score - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_minimum_should_match() {
+        assert_eq!("all".parse(), Ok(MinimumShouldMatch::All));
+        assert_eq!("75%".parse(), Ok(MinimumShouldMatch::Percent(75)));
+        assert_eq!("2".parse(), Ok(MinimumShouldMatch::Count(2)));
+        assert!("150%".parse::<MinimumShouldMatch>().is_err());
+        assert!("most".parse::<MinimumShouldMatch>().is_err());
+
+        assert_eq!(MinimumShouldMatch::All.required(4), 4);
+        assert_eq!(MinimumShouldMatch::Percent(75).required(4), 3);
+        assert_eq!(MinimumShouldMatch::Percent(75).required(3), 2);
+        assert_eq!(MinimumShouldMatch::Percent(10).required(3), 1);
+        assert_eq!(MinimumShouldMatch::Count(5).required(3), 3);
+
+        let options = SearchOptions::default().min_should_match(MinimumShouldMatch::All);
+        assert!(options.terms_satisfied(3, 3));
+        assert!(!options.terms_satisfied(2, 3));
+        assert!(SearchOptions::default().terms_satisfied(1, 3));
+    }
+
+    #[test]
+    fn test_matches_meta_filters() {
+        let meta = SkillMeta {
+            name: "forms".to_string(),
+            description: "Form handling".to_string(),
+            source: Some("official".to_string()),
+            language: Some("en".to_string()),
+            status: Some(SkillStatus::Published),
+            owner: Some("frontend".to_string()),
+            ..Default::default()
+        };
+
+        assert!(SearchOptions::default().matches_meta(&meta));
+        assert!(SearchOptions::default()
+            .source("Official")
+            .matches_meta(&meta));
+        assert!(!SearchOptions::default()
+            .source("community")
+            .matches_meta(&meta));
+        assert!(SearchOptions::default()
+            .language("en")
+            .status(SkillStatus::Published)
+            .owner("frontend")
+            .matches_meta(&meta));
+        assert!(!SearchOptions::default()
+            .status(SkillStatus::Draft)
+            .matches_meta(&meta));
+        assert!(!SearchOptions::default()
+            .owner("x")
+            .matches_meta(&SkillMeta::default()));
+
+        let tagged = SkillMeta {
+            tags: vec!["react".to_string(), "forms".to_string()],
+            ..meta
+        };
+        let expr: TagExpr = "react AND NOT deprecated".parse().unwrap();
+        assert!(SearchOptions::default().tags(expr).matches_meta(&tagged));
+        let expr: TagExpr = "vue OR svelte".parse().unwrap();
+        assert!(!SearchOptions::default().tags(expr).matches_meta(&tagged));
+    }
+
     #[test]
     fn test_search_results_truncation() {
         let results = vec![
@@ -253,4 +1030,138 @@ mod tests {
         assert_eq!(search_results.total_matches, 3);
         assert!(search_results.truncated);
     }
+
+    #[test]
+    fn test_search_results_paging() {
+        let results: Vec<SearchResult> = ["a", "b", "c", "d", "e"]
+            .iter()
+            .map(|d| SearchResult::new(d.to_string(), 1.0, MatchType::Name))
+            .collect();
+
+        let first =
+            SearchResults::page("q".to_string(), results.clone(), 0, Some(2)).with_cursor(7);
+        let domains: Vec<&str> = first.results.iter().map(|r| r.domain.as_str()).collect();
+        assert_eq!(domains, ["a", "b"]);
+        assert!(first.truncated);
+
+        let cursor = SearchCursor::decode(first.next_cursor.as_ref().unwrap()).unwrap();
+        assert_eq!(cursor, SearchCursor::new(2, 7));
+
+        let last = SearchResults::page("q".to_string(), results, 4, Some(2)).with_cursor(7);
+        assert_eq!(last.results.len(), 1);
+        assert_eq!(last.results[0].domain, "e");
+        assert!(!last.truncated);
+        assert!(last.next_cursor.is_none());
+    }
+
+    #[test]
+    fn test_grouped_results() {
+        let results = vec![
+            SearchResult::new("forms".to_string(), 0.9, MatchType::Content)
+                .with_file("SKILL.md".to_string()),
+            SearchResult::new("auth".to_string(), 0.8, MatchType::Content)
+                .with_file("SKILL.md".to_string()),
+            SearchResult::new("forms".to_string(), 0.7, MatchType::Content)
+                .with_file("a.md".to_string()),
+            SearchResult::new("forms".to_string(), 0.5, MatchType::Content)
+                .with_file("b.md".to_string()),
+            SearchResult::new("api".to_string(), 0.4, MatchType::Content)
+                .with_file("SKILL.md".to_string()),
+        ];
+        let results = SearchResults::new("q".to_string(), results, None);
+
+        let grouped = GroupedSearchResults::new(results, 2, Some(2));
+
+        assert_eq!(grouped.total_domains, 3);
+        assert!(grouped.truncated);
+        assert_eq!(grouped.groups.len(), 2);
+        assert_eq!(grouped.groups[0].domain, "forms");
+        assert_eq!(grouped.groups[0].total_matches, 3);
+        assert_eq!(grouped.groups[0].hits.len(), 2);
+        assert_eq!(grouped.groups[0].score, 0.9);
+        assert_eq!(grouped.groups[1].domain, "auth");
+    }
+
+    #[test]
+    fn test_cursor_decode_rejects_garbage() {
+        assert!(SearchCursor::decode("").is_none());
+        assert!(SearchCursor::decode("abc").is_none());
+        assert!(SearchCursor::decode("zz").is_none());
+        assert!(SearchCursor::decode(&hex("12")).is_none());
+        assert!(SearchCursor::decode(&hex("x:1")).is_none());
+        assert_eq!(
+            SearchCursor::decode(&hex("3:9")),
+            Some(SearchCursor::new(3, 9))
+        );
+    }
+
+    fn hex(s: &str) -> String {
+        s.bytes().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn test_compact_text() {
+        let hit = SearchResult::new("forms".to_string(), 4.5, MatchType::Content)
+            .with_sub_skill("validation".to_string())
+            .with_file("references/api.md".to_string())
+            .with_snippet("Validate\n  on   blur".to_string());
+        assert_eq!(
+            hit.to_compact_line(),
+            "forms:validation | 4.50 | references/api.md | Validate on blur"
+        );
+
+        let long = SearchResult::new("forms".to_string(), 1.0, MatchType::Content)
+            .with_snippet("x".repeat(500));
+        assert!(long.to_compact_line().ends_with('…'));
+
+        let results = SearchResults::new(
+            "forms".to_string(),
+            vec![
+                hit,
+                SearchResult::new("react".to_string(), 1.0, MatchType::Name),
+            ],
+            Some(1),
+        )
+        .with_cursor(7);
+        let text = results.to_compact_text();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "query: forms (1-1 of 2)");
+        assert!(lines[1].starts_with("forms:validation | 4.50"));
+        assert!(lines[2].starts_with("next_cursor: "));
+
+        let mut empty = SearchResults::new("frms".to_string(), vec![], None);
+        empty.suggestions = vec!["forms".to_string()];
+        assert_eq!(
+            empty.to_compact_text(),
+            "query: frms (no results)\ndid you mean: forms\n"
+        );
+    }
+
+    #[test]
+    fn test_score_confidence_and_withhold() {
+        assert_eq!(score_confidence(0.0), 0.0);
+        assert_eq!(score_confidence(CONFIDENCE_MIDPOINT), 0.5);
+        assert_eq!(score_confidence(6.0), 0.75);
+        assert_eq!(score_confidence(-1.0), 0.0);
+
+        let mut results = SearchResults::new(
+            "forms".to_string(),
+            vec![SearchResult::new(
+                "react".to_string(),
+                0.3,
+                MatchType::Content,
+            )],
+            None,
+        );
+        results.withhold(Exploration {
+            candidates: vec!["react".to_string()],
+            tags: vec!["frontend".to_string()],
+        });
+        assert!(results.is_empty());
+        assert_eq!(results.total_matches, 0);
+        assert_eq!(
+            results.to_compact_text(),
+            "query: forms (no confident match)\nexplore: react\ntags: frontend\n"
+        );
+    }
 }
@@ -0,0 +1,135 @@
+//! Per-skill totals across a skill's indexed files.
+//!
+//! Stats are computed from the content index whenever a skill is
+//! indexed, so listings can report a skill's size and freshness without
+//! reading its files.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{ContentIndex, ContentIndexEntry, SkillIndex, SkillMeta};
+
+/// Bytes of text per token, roughly, for English prose and code.
+pub const BYTES_PER_TOKEN: usize = 4;
+
+/// Totals for one skill.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkillStats {
+    /// Words in the skill's documents: SKILL.md, sub-skills and references.
+    pub total_words: usize,
+
+    /// Approximate tokens in the same documents.
+    pub approx_tokens: usize,
+
+    /// Sub-skills at any depth.
+    pub sub_skill_count: usize,
+
+    /// Documents under `references/` that aren't sub-skills.
+    pub reference_count: usize,
+
+    /// Latest modification of any indexed file, `_meta.json` included.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<DateTime<Utc>>,
+}
+
+impl SkillStats {
+    /// Total up the content index entries of `meta`.
+    ///
+    /// Script entries only hold docstrings, so they count towards
+    /// `last_modified` but not the word and token totals.
+    pub fn from_entries<'a>(
+        meta: &SkillMeta,
+        entries: impl IntoIterator<Item = &'a ContentIndexEntry>,
+    ) -> Self {
+        let mut stats = Self {
+            sub_skill_count: meta.all_sub_skills().len(),
+            ..Self::default()
+        };
+
+        for entry in entries {
+            stats.last_modified = stats.last_modified.max(entry.modified);
+            if entry.is_metadata() || entry.file.starts_with("scripts/") {
+                continue;
+            }
+            stats.total_words += entry.word_count;
+            stats.approx_tokens += entry.content.len().div_ceil(BYTES_PER_TOKEN);
+            if entry.sub_skill.is_none() && entry.file.starts_with("references/") {
+                stats.reference_count += 1;
+            }
+        }
+
+        stats
+    }
+}
+
+/// Recompute the stats of skills whose names pass `touched`, dropping
+/// those no longer indexed. Other skills keep their stats.
+pub fn update_skill_stats(
+    stats: &mut HashMap<String, SkillStats>,
+    skill_index: &SkillIndex,
+    content_index: &ContentIndex,
+    touched: impl Fn(&str) -> bool,
+) {
+    stats.retain(|name, _| !touched(name));
+
+    let mut entries: HashMap<&str, Vec<&ContentIndexEntry>> = HashMap::new();
+    for entry in content_index.entries.values() {
+        if touched(&entry.domain) {
+            entries
+                .entry(entry.domain.as_str())
+                .or_default()
+                .push(entry);
+        }
+    }
+
+    for skill in skill_index.skills.iter().filter(|s| touched(&s.name)) {
+        let files = entries.remove(skill.name.as_str()).unwrap_or_default();
+        stats.insert(skill.name.clone(), SkillStats::from_entries(skill, files));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    #[test]
+    fn test_skill_stats() {
+        let meta: SkillMeta = serde_json::from_str(
+            r#"{"name": "forms", "description": "Forms",
+                "sub_skills": [{"name": "react", "file": "references/react.md",
+                    "children": [{"name": "hooks", "file": "hooks.md"}]}]}"#,
+        )
+        .unwrap();
+
+        let old = DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z").unwrap();
+        let new = DateTime::parse_from_rfc3339("2024-06-01T00:00:00Z").unwrap();
+        let file_entry =
+            |sub: Option<&str>, file: &str, text: &str, modified: DateTime<FixedOffset>| {
+                let mut entry = ContentIndexEntry::new(
+                    "forms".to_string(),
+                    sub.map(str::to_string),
+                    file.to_string(),
+                    text.to_string(),
+                );
+                entry.modified = Some(modified.with_timezone(&Utc));
+                entry
+            };
+        let entries = [
+            ContentIndexEntry::from_meta(&meta),
+            file_entry(None, "SKILL.md", "# Forms\n\nBuild forms.", old),
+            file_entry(Some("react"), "references/react.md", "React forms", old),
+            file_entry(None, "references/api.md", "Field reference", new),
+            file_entry(None, "scripts/check.sh", "check forms script", old),
+        ];
+
+        let stats = SkillStats::from_entries(&meta, &entries);
+        assert_eq!(stats.total_words, 8);
+        assert_eq!(stats.approx_tokens, 6 + 3 + 4);
+        assert_eq!(stats.sub_skill_count, 2);
+        assert_eq!(stats.reference_count, 1);
+        assert_eq!(stats.last_modified, Some(new.with_timezone(&Utc)));
+    }
+}
@@ -15,6 +15,10 @@ pub struct SearchEntry {
 
     /// Number of results returned.
     pub result_count: usize,
+
+    /// Skills loaded soon after the search, presumably because of it.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub loaded_skills: Vec<String>,
 }
 
 impl SearchEntry {
@@ -24,6 +28,7 @@ impl SearchEntry {
             query,
             timestamp: Utc::now(),
             result_count,
+            loaded_skills: Vec::new(),
         }
     }
 }
@@ -34,15 +39,26 @@ impl SearchEntry {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UsageStats {
     /// Count of each tool invocation.
+    #[serde(alias = "toolCalls")]
     pub tool_calls: HashMap<String, u64>,
 
     /// Count of each skill loaded.
+    #[serde(alias = "skillLoads")]
     pub skill_loads: HashMap<String, u64>,
 
     /// Recent search queries (limited buffer).
     pub searches: Vec<SearchEntry>,
 
+    /// Searches slower than the configured threshold.
+    #[serde(default, alias = "slowSearches")]
+    pub slow_searches: u64,
+
+    /// Index reloads slower than the configured threshold.
+    #[serde(default, alias = "slowReloads")]
+    pub slow_reloads: u64,
+
     /// Server start time.
+    #[serde(alias = "startTime")]
     pub start_time: DateTime<Utc>,
 }
 
@@ -50,12 +66,17 @@ impl UsageStats {
     /// Maximum number of search entries to retain.
     const MAX_SEARCHES: usize = 100;
 
+    /// How long after a search a skill load is attributed to it.
+    pub const SEARCH_FOLLOW_UP_SECS: i64 = 300;
+
     /// Create new empty stats.
     pub fn new() -> Self {
         Self {
             tool_calls: HashMap::new(),
             skill_loads: HashMap::new(),
             searches: Vec::new(),
+            slow_searches: 0,
+            slow_reloads: 0,
             start_time: Utc::now(),
         }
     }
@@ -66,8 +87,21 @@ impl UsageStats {
     }
 
     /// Record a skill load.
+    ///
+    /// A load shortly after a search that found something is attributed to
+    /// that search.
     pub fn record_skill_load(&mut self, skill_name: &str) {
         *self.skill_loads.entry(skill_name.to_string()).or_insert(0) += 1;
+
+        let cutoff = Utc::now() - chrono::Duration::seconds(Self::SEARCH_FOLLOW_UP_SECS);
+        if let Some(search) = self.searches.last_mut() {
+            if search.result_count > 0
+                && search.timestamp >= cutoff
+                && !search.loaded_skills.iter().any(|s| s == skill_name)
+            {
+                search.loaded_skills.push(skill_name.to_string());
+            }
+        }
     }
 
     /// Record a search query.
@@ -90,6 +124,18 @@ impl UsageStats {
         self.skill_loads.values().sum()
     }
 
+    /// Loads of each skill, counting loads of its sub-skills.
+    pub fn loads_by_skill(&self) -> HashMap<String, u64> {
+        let mut loads: HashMap<String, u64> = HashMap::new();
+        for (id, count) in &self.skill_loads {
+            let skill = id
+                .split_once(super::SUB_SKILL_REF_SEPARATOR)
+                .map_or(id.as_str(), |(skill, _)| skill);
+            *loads.entry(skill.to_string()).or_default() += count;
+        }
+        loads
+    }
+
     /// Get uptime duration.
     pub fn uptime(&self) -> chrono::Duration {
         Utc::now() - self.start_time
@@ -127,6 +173,16 @@ impl UsageStats {
         skills
     }
 
+    /// Retained searches that led to loading a skill.
+    pub fn searches_leading_to<'a>(
+        &'a self,
+        skill_name: &'a str,
+    ) -> impl Iterator<Item = &'a SearchEntry> + 'a {
+        self.searches
+            .iter()
+            .filter(move |s| s.loaded_skills.iter().any(|l| l == skill_name))
+    }
+
     /// Get recent searches.
     pub fn recent_searches(&self, limit: usize) -> Vec<&SearchEntry> {
         self.searches.iter().rev().take(limit).collect()
@@ -139,6 +195,26 @@ impl Default for UsageStats {
     }
 }
 
+/// Time spent validating a single skill.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillTiming {
+    /// Skill name.
+    pub name: String,
+
+    /// Validation duration in milliseconds.
+    pub duration_ms: f64,
+}
+
+impl SkillTiming {
+    /// Create a new timing entry.
+    pub fn new(name: String, duration: std::time::Duration) -> Self {
+        Self {
+            name,
+            duration_ms: duration.as_secs_f64() * 1000.0,
+        }
+    }
+}
+
 /// Validation result for skill checks.
 ///
 /// Corresponds to `ValidationResult` in TypeScript.
@@ -154,7 +230,12 @@ pub struct ValidationResult {
     pub warnings: Vec<String>,
 
     /// Number of skills checked.
+    #[serde(alias = "skillsChecked")]
     pub skills_checked: usize,
+
+    /// Per-skill validation durations, slowest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub timings: Vec<SkillTiming>,
 }
 
 impl ValidationResult {
@@ -165,6 +246,7 @@ impl ValidationResult {
             errors: Vec::new(),
             warnings: Vec::new(),
             skills_checked,
+            timings: Vec::new(),
         }
     }
 
@@ -175,6 +257,7 @@ impl ValidationResult {
             errors,
             warnings: Vec::new(),
             skills_checked,
+            timings: Vec::new(),
         }
     }
 
@@ -194,8 +277,24 @@ impl ValidationResult {
         self.errors.extend(other.errors);
         self.warnings.extend(other.warnings);
         self.skills_checked += other.skills_checked;
+        for timing in other.timings {
+            self.add_timing(timing);
+        }
         self.valid = self.valid && self.errors.is_empty();
     }
+
+    /// Record how long a skill took to validate, keeping timings sorted slowest first.
+    pub fn add_timing(&mut self, timing: SkillTiming) {
+        let pos = self
+            .timings
+            .partition_point(|t| t.duration_ms >= timing.duration_ms);
+        self.timings.insert(pos, timing);
+    }
+
+    /// Get the slowest skills to validate.
+    pub fn slowest(&self, limit: usize) -> &[SkillTiming] {
+        &self.timings[..limit.min(self.timings.len())]
+    }
 }
 
 #[cfg(test)]
@@ -216,6 +315,35 @@ mod tests {
         assert_eq!(*stats.tool_calls.get("list_skills").unwrap(), 2);
         assert_eq!(stats.total_skill_loads(), 1);
         assert_eq!(stats.searches.len(), 1);
+
+        stats.record_skill_load("forms:validation");
+        stats.record_skill_load("charts");
+        let loads = stats.loads_by_skill();
+        assert_eq!(loads.get("forms"), Some(&2));
+        assert_eq!(loads.get("charts"), Some(&1));
+    }
+
+    #[test]
+    fn test_loads_attributed_to_recent_search() {
+        let mut stats = UsageStats::new();
+
+        // Loads before any search aren't attributed
+        stats.record_skill_load("forms");
+        stats.record_search("form validation".to_string(), 2);
+        stats.record_skill_load("forms");
+        stats.record_skill_load("forms");
+        stats.record_skill_load("a11y");
+
+        assert_eq!(stats.searches[0].loaded_skills, vec!["forms", "a11y"]);
+        assert_eq!(stats.searches_leading_to("forms").count(), 1);
+
+        // Searches that found nothing, or long ago, lead nowhere
+        stats.record_search("nothing".to_string(), 0);
+        stats.record_skill_load("charts");
+        stats.record_search("old".to_string(), 3);
+        stats.searches.last_mut().unwrap().timestamp -= chrono::Duration::hours(1);
+        stats.record_skill_load("charts");
+        assert_eq!(stats.searches_leading_to("charts").count(), 0);
     }
 
     #[test]
@@ -230,4 +358,27 @@ mod tests {
         result.add_warning("No tags defined".to_string());
         assert_eq!(result.warnings.len(), 1);
     }
+
+    #[test]
+    fn test_validation_timings_sorted() {
+        use std::time::Duration;
+
+        let mut result = ValidationResult::pass(3);
+        result.add_timing(SkillTiming::new(
+            "fast".to_string(),
+            Duration::from_millis(1),
+        ));
+        result.add_timing(SkillTiming::new(
+            "slow".to_string(),
+            Duration::from_millis(50),
+        ));
+        result.add_timing(SkillTiming::new(
+            "mid".to_string(),
+            Duration::from_millis(10),
+        ));
+
+        let slowest: Vec<&str> = result.slowest(2).iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(slowest, vec!["slow", "mid"]);
+        assert_eq!(result.slowest(10).len(), 3);
+    }
 }
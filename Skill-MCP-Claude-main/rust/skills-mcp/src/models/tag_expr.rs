@@ -0,0 +1,221 @@
+//! Boolean tag filter expressions.
+//!
+//! Expressions combine tag names with `AND`, `OR`, `NOT` and parentheses,
+//! e.g. `react AND (forms OR validation) NOT deprecated`. Adjacent tags
+//! without an operator are joined with `AND`, and `a NOT b` means
+//! `a AND NOT b`. Operators are case-insensitive; tags are matched
+//! case-insensitively against a skill's tags.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// A parsed tag filter expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TagExpr {
+    /// The skill has this tag.
+    Tag(String),
+    /// Both sides match.
+    And(Box<TagExpr>, Box<TagExpr>),
+    /// Either side matches.
+    Or(Box<TagExpr>, Box<TagExpr>),
+    /// The inner expression does not match.
+    Not(Box<TagExpr>),
+}
+
+impl TagExpr {
+    /// Evaluate the expression against a set of tags.
+    pub fn matches<S: AsRef<str>>(&self, tags: &[S]) -> bool {
+        match self {
+            TagExpr::Tag(tag) => tags.iter().any(|t| t.as_ref().eq_ignore_ascii_case(tag)),
+            TagExpr::And(a, b) => a.matches(tags) && b.matches(tags),
+            TagExpr::Or(a, b) => a.matches(tags) || b.matches(tags),
+            TagExpr::Not(e) => !e.matches(tags),
+        }
+    }
+}
+
+impl fmt::Display for TagExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TagExpr::Tag(tag) => write!(f, "{}", tag),
+            TagExpr::And(a, b) => write!(f, "({} AND {})", a, b),
+            TagExpr::Or(a, b) => write!(f, "({} OR {})", a, b),
+            TagExpr::Not(e) => write!(f, "NOT {}", e),
+        }
+    }
+}
+
+impl FromStr for TagExpr {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens = tokenize(s);
+        if tokens.is_empty() {
+            return Err("Tag expression is empty".to_string());
+        }
+
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser.parse_or()?;
+
+        match parser.peek() {
+            None => Ok(expr),
+            Some(token) => Err(format!("Unexpected '{}' in tag expression", token)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+    Tag(String),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::And => write!(f, "AND"),
+            Token::Or => write!(f, "OR"),
+            Token::Not => write!(f, "NOT"),
+            Token::Open => write!(f, "("),
+            Token::Close => write!(f, ")"),
+            Token::Tag(tag) => write!(f, "{}", tag),
+        }
+    }
+}
+
+fn tokenize(s: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+
+    let flush = |word: &mut String, tokens: &mut Vec<Token>| {
+        if word.is_empty() {
+            return;
+        }
+        let token = match word.to_ascii_uppercase().as_str() {
+            "AND" => Token::And,
+            "OR" => Token::Or,
+            "NOT" => Token::Not,
+            _ => Token::Tag(word.clone()),
+        };
+        tokens.push(token);
+        word.clear();
+    };
+
+    for c in s.chars() {
+        match c {
+            '(' | ')' => {
+                flush(&mut word, &mut tokens);
+                tokens.push(if c == '(' { Token::Open } else { Token::Close });
+            }
+            c if c.is_whitespace() => flush(&mut word, &mut tokens),
+            c => word.push(c),
+        }
+    }
+    flush(&mut word, &mut tokens);
+
+    tokens
+}
+
+/// Recursive-descent parser; `OR` binds loosest, then `AND`, then `NOT`.
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn parse_or(&mut self) -> Result<TagExpr, String> {
+        let mut expr = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            expr = TagExpr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> Result<TagExpr, String> {
+        let mut expr = self.parse_unary()?;
+        loop {
+            let rhs = match self.peek() {
+                Some(Token::And) => {
+                    self.pos += 1;
+                    self.parse_unary()?
+                }
+                // `a NOT b` and `a (b)` / `a b` are implicit conjunctions
+                Some(Token::Not) | Some(Token::Open) | Some(Token::Tag(_)) => self.parse_unary()?,
+                _ => break,
+            };
+            expr = TagExpr::And(Box::new(expr), Box::new(rhs));
+        }
+        Ok(expr)
+    }
+
+    fn parse_unary(&mut self) -> Result<TagExpr, String> {
+        match self.next() {
+            Some(Token::Not) => Ok(TagExpr::Not(Box::new(self.parse_unary()?))),
+            Some(Token::Open) => {
+                let expr = self.parse_or()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(expr),
+                    _ => Err("Missing ')' in tag expression".to_string()),
+                }
+            }
+            Some(Token::Tag(tag)) => Ok(TagExpr::Tag(tag)),
+            Some(token) => Err(format!("Unexpected '{}' in tag expression", token)),
+            None => Err("Tag expression ends unexpectedly".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> TagExpr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_precedence() {
+        assert_eq!(
+            parse("react AND (forms OR validation) NOT deprecated").to_string(),
+            "((react AND (forms OR validation)) AND NOT deprecated)"
+        );
+        assert_eq!(parse("a OR b AND c").to_string(), "(a OR (b AND c))");
+        assert_eq!(parse("a b").to_string(), "(a AND b)");
+        assert_eq!(parse("not a").to_string(), "NOT a");
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let expr = parse("react AND (forms OR validation) NOT deprecated");
+
+        assert!(expr.matches(&["react", "forms"]));
+        assert!(expr.matches(&["React", "Validation"]));
+        assert!(!expr.matches(&["react"]));
+        assert!(!expr.matches(&["react", "forms", "deprecated"]));
+        assert!(!expr.matches::<&str>(&[]));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!("".parse::<TagExpr>().is_err());
+        assert!("(react".parse::<TagExpr>().is_err());
+        assert!("react)".parse::<TagExpr>().is_err());
+        assert!("react AND".parse::<TagExpr>().is_err());
+        assert!("OR react".parse::<TagExpr>().is_err());
+    }
+}
@@ -0,0 +1,521 @@
+//! Skill templates.
+//!
+//! A template is a SKILL.md body with `{{placeholder}}` markers, plus the
+//! default tags and sub-skills of skills created from it. Three templates
+//! are built in (see [`SkillTemplate`]); more can be added as
+//! `_templates/<name>.md` files in the skills directory, with frontmatter
+//! describing the template:
+//!
+//! ```text
+//! ---
+//! description: Reference for a REST API
+//! tags: [api, reference]
+//! ---
+//!
+//! # {{title}}
+//!
+//! {{description}}
+//!
+//! Base URL: {{base_url}}
+//! ```
+//!
+//! `name`, `title`, `description` and `tags` are always filled in; any
+//! other placeholder needs a value when the skill is created. A custom
+//! template with the same name as a built-in one replaces it.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::{parse_flat_skill, SubSkillMeta};
+
+/// Directory under the skills root holding custom templates.
+pub const TEMPLATES_DIR: &str = "_templates";
+
+/// Placeholders filled from the skill being created.
+const BUILTIN_PLACEHOLDERS: &[&str] = &["name", "title", "description", "tags"];
+
+/// Built-in templates for skill generation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkillTemplate {
+    /// Minimal template with just essentials.
+    Minimal,
+    /// Standard template with common sections.
+    #[default]
+    Standard,
+    /// Full template with sub-skills structure.
+    WithSubSkills,
+}
+
+impl SkillTemplate {
+    /// All built-in templates.
+    pub const ALL: [SkillTemplate; 3] = [
+        SkillTemplate::Minimal,
+        SkillTemplate::Standard,
+        SkillTemplate::WithSubSkills,
+    ];
+
+    /// Name the template is referenced by.
+    pub fn name(&self) -> &'static str {
+        match self {
+            SkillTemplate::Minimal => "minimal",
+            SkillTemplate::Standard => "standard",
+            SkillTemplate::WithSubSkills => "with_sub_skills",
+        }
+    }
+
+    /// Look up a built-in template by name; `-` and `_` are interchangeable.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.replace('-', "_");
+        Self::ALL.into_iter().find(|t| t.name() == name)
+    }
+
+    /// The template's definition.
+    pub fn definition(&self) -> TemplateDefinition {
+        let mut content = String::from(
+            "# {{title}}\n\n## Overview\n\n{{description}}\n\n\
+             ## When to Use\n\n- [Add trigger conditions here]\n",
+        );
+        if *self != SkillTemplate::Minimal {
+            content.push_str(
+                "\n## Quick Start\n\n```\n// Add example code here\n```\n\n\
+                 ## Best Practices\n\n- [Add best practices here]\n\n\
+                 ## Examples\n\n[Add practical examples here]\n",
+            );
+        }
+
+        let mut sub_skills = Vec::new();
+        if *self == SkillTemplate::WithSubSkills {
+            content.push_str(
+                "\n## Sub-Skills\n\nThis skill has the following sub-skills:\n\n\
+                 | Sub-skill | Description |\n|-----------|-------------|\n\
+                 | example | Example sub-skill |\n\n\
+                 Use `get_sub_skill` to load specific sub-skill content.\n",
+            );
+            sub_skills.push(TemplateSubSkill {
+                name: "example".to_string(),
+                file: "references/example.md".to_string(),
+                content: "# Example Sub-Skill\n\nAdd content here.\n".to_string(),
+                triggers: Vec::new(),
+            });
+        }
+
+        let description = match self {
+            SkillTemplate::Minimal => "Overview and when to use the skill",
+            SkillTemplate::Standard => "Overview, quick start, best practices and examples",
+            SkillTemplate::WithSubSkills => "Standard sections plus an example sub-skill",
+        };
+
+        TemplateDefinition {
+            name: self.name().to_string(),
+            description: description.to_string(),
+            builtin: true,
+            tags: Vec::new(),
+            content,
+            sub_skills,
+        }
+    }
+}
+
+/// A sub-skill file created along with the skill.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TemplateSubSkill {
+    /// Sub-skill name.
+    pub name: String,
+    /// File relative to the skill directory.
+    pub file: String,
+    /// File content, which may hold placeholders.
+    pub content: String,
+    /// Search keywords for the sub-skill.
+    pub triggers: Vec<String>,
+}
+
+/// One sub-skill in an outline to scaffold.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "native", derive(schemars::JsonSchema))]
+pub struct OutlineEntry {
+    /// Sub-skill name, lowercase alphanumeric with hyphens.
+    pub name: String,
+    /// What the sub-skill covers.
+    pub description: String,
+    /// Search keywords for the sub-skill.
+    #[serde(default)]
+    pub triggers: Vec<String>,
+}
+
+/// A template skills can be created from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TemplateDefinition {
+    /// Name the template is referenced by.
+    pub name: String,
+    /// What the template is for.
+    pub description: String,
+    /// Whether the template is built in rather than read from `_templates/`.
+    pub builtin: bool,
+    /// Tags given to skills created without tags of their own.
+    pub tags: Vec<String>,
+    /// SKILL.md content with placeholders.
+    pub content: String,
+    /// Sub-skill files to create.
+    pub sub_skills: Vec<TemplateSubSkill>,
+}
+
+/// Summary of a template for listings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TemplateInfo {
+    /// Name the template is referenced by.
+    pub name: String,
+    /// What the template is for.
+    pub description: String,
+    /// Whether the template is built in.
+    pub builtin: bool,
+    /// Default tags.
+    pub tags: Vec<String>,
+    /// Placeholders that need a value when creating a skill.
+    pub placeholders: Vec<String>,
+    /// Sub-skills the template creates.
+    pub sub_skills: Vec<String>,
+}
+
+/// Skill files rendered from a template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderedTemplate {
+    /// SKILL.md content.
+    pub content: String,
+    /// Tags for `_meta.json`.
+    pub tags: Vec<String>,
+    /// Sub-skills for `_meta.json` with their file contents.
+    pub sub_skills: Vec<(SubSkillMeta, String)>,
+}
+
+impl TemplateDefinition {
+    /// Parse a custom template file named `name`.
+    pub fn parse(name: &str, text: &str) -> Result<Self, String> {
+        let (meta, body) = parse_flat_skill(name, text)?;
+        Ok(Self {
+            name: name.to_string(),
+            description: meta.description,
+            builtin: false,
+            tags: meta.tags,
+            content: body.to_string(),
+            sub_skills: Vec::new(),
+        })
+    }
+
+    /// Placeholders beyond the built-in ones, in order of first use.
+    pub fn placeholders(&self) -> Vec<String> {
+        let mut found: Vec<String> = Vec::new();
+        let texts =
+            std::iter::once(&self.content).chain(self.sub_skills.iter().map(|s| &s.content));
+        for text in texts {
+            for captures in placeholder_regex().captures_iter(text) {
+                let name = &captures[1];
+                if !BUILTIN_PLACEHOLDERS.contains(&name) && !found.iter().any(|f| f == name) {
+                    found.push(name.to_string());
+                }
+            }
+        }
+        found
+    }
+
+    /// Replace the template's sub-skills with stubs for `outline`.
+    ///
+    /// Each entry becomes `references/<name>.md`, and the SKILL.md
+    /// `## Sub-Skills` section is replaced (or added) with a table of the
+    /// outline. Fails if an entry's name isn't a valid identifier, is
+    /// repeated, or lacks a description.
+    pub fn with_outline(mut self, outline: &[OutlineEntry]) -> Result<Self, String> {
+        if outline.is_empty() {
+            return Err("Outline must list at least one sub-skill".to_string());
+        }
+
+        let mut errors = Vec::new();
+        for (i, entry) in outline.iter().enumerate() {
+            if !sub_skill_name_regex().is_match(&entry.name) {
+                errors.push(format!(
+                    "outline[{}].name: must be lowercase alphanumeric with hyphens, got '{}'",
+                    i, entry.name
+                ));
+            } else if outline[..i].iter().any(|e| e.name == entry.name) {
+                errors.push(format!(
+                    "outline[{}].name: duplicate name '{}'",
+                    i, entry.name
+                ));
+            }
+            if entry.description.trim().is_empty() {
+                errors.push(format!("outline[{}].description: cannot be empty", i));
+            }
+        }
+        if !errors.is_empty() {
+            return Err(errors.join("; "));
+        }
+
+        let rows: Vec<(&str, &str)> = outline
+            .iter()
+            .map(|entry| (entry.name.as_str(), entry.description.as_str()))
+            .collect();
+        let table = sub_skill_table(&rows);
+
+        let content = &self.content;
+        self.content = match content.find("\n## Sub-Skills\n") {
+            Some(i) => {
+                let before = &content[..=i];
+                let after = &content[i + 1..];
+                match after[1..].find("\n## ") {
+                    Some(next) => format!("{}{}\n{}", before, table, &after[next + 2..]),
+                    None => format!("{}{}", before, table),
+                }
+            }
+            None => format!("{}\n\n{}", content.trim_end(), table),
+        };
+
+        self.sub_skills = outline
+            .iter()
+            .map(|entry| TemplateSubSkill {
+                name: entry.name.clone(),
+                file: format!("references/{}.md", entry.name),
+                content: format!(
+                    "# {}\n\n{}\n\n## Overview\n\n[Add content here]\n",
+                    title_case(&entry.name),
+                    entry.description.trim()
+                ),
+                triggers: entry.triggers.clone(),
+            })
+            .collect();
+
+        Ok(self)
+    }
+
+    /// Summarize the template for listings.
+    pub fn info(&self) -> TemplateInfo {
+        TemplateInfo {
+            name: self.name.clone(),
+            description: self.description.clone(),
+            builtin: self.builtin,
+            tags: self.tags.clone(),
+            placeholders: self.placeholders(),
+            sub_skills: self.sub_skills.iter().map(|s| s.name.clone()).collect(),
+        }
+    }
+
+    /// Fill in the template for a new skill.
+    ///
+    /// `tags` falls back to the template's tags when empty. Fails if a
+    /// placeholder has no value in `variables`.
+    pub fn render(
+        &self,
+        name: &str,
+        description: &str,
+        tags: &[String],
+        variables: &HashMap<String, String>,
+    ) -> Result<RenderedTemplate, String> {
+        let missing: Vec<String> = self
+            .placeholders()
+            .into_iter()
+            .filter(|p| !variables.contains_key(p))
+            .collect();
+        if !missing.is_empty() {
+            return Err(format!(
+                "Template '{}' needs values for: {}",
+                self.name,
+                missing.join(", ")
+            ));
+        }
+
+        let tags = if tags.is_empty() {
+            self.tags.clone()
+        } else {
+            tags.to_vec()
+        };
+        let title = title_case(name.rsplit('/').next().unwrap_or(name));
+        let builtin = [
+            ("name", name.to_string()),
+            ("title", title),
+            ("description", description.to_string()),
+            ("tags", tags.join(", ")),
+        ];
+
+        let fill = |text: &str| {
+            placeholder_regex()
+                .replace_all(text, |captures: &regex::Captures<'_>| {
+                    let key = &captures[1];
+                    builtin
+                        .iter()
+                        .find(|(k, _)| *k == key)
+                        .map(|(_, v)| v.clone())
+                        .or_else(|| variables.get(key).cloned())
+                        .unwrap_or_default()
+                })
+                .into_owned()
+        };
+
+        Ok(RenderedTemplate {
+            content: fill(&self.content),
+            tags,
+            sub_skills: self
+                .sub_skills
+                .iter()
+                .map(|sub| {
+                    let meta = SubSkillMeta {
+                        name: sub.name.clone(),
+                        file: sub.file.clone(),
+                        triggers: sub.triggers.clone(),
+                        children: Vec::new(),
+                        reference: None,
+                    };
+                    (meta, fill(&sub.content))
+                })
+                .collect(),
+        })
+    }
+}
+
+/// A `## Sub-Skills` section listing `(name, description)` rows.
+pub(crate) fn sub_skill_table(rows: &[(&str, &str)]) -> String {
+    let mut table = String::from(
+        "## Sub-Skills\n\nThis skill has the following sub-skills:\n\n\
+         | Sub-skill | Description |\n|-----------|-------------|\n",
+    );
+    for (name, description) in rows {
+        table.push_str(&format!(
+            "| {} | {} |\n",
+            name,
+            description.trim().replace('|', "\\|").replace('\n', " ")
+        ));
+    }
+    table.push_str("\nUse `get_sub_skill` to load specific sub-skill content.\n");
+    table
+}
+
+fn placeholder_regex() -> &'static Regex {
+    static PLACEHOLDER: OnceLock<Regex> = OnceLock::new();
+    PLACEHOLDER.get_or_init(|| Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_]*)\s*\}\}").unwrap())
+}
+
+fn sub_skill_name_regex() -> &'static Regex {
+    static NAME: OnceLock<Regex> = OnceLock::new();
+    NAME.get_or_init(|| Regex::new(r"^[a-z0-9]([a-z0-9-]*[a-z0-9])?$").unwrap())
+}
+
+/// `my-new-skill` becomes `My New Skill`.
+pub(crate) fn title_case(name: &str) -> String {
+    name.split(['-', '_'])
+        .filter(|w| !w.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_templates() {
+        assert_eq!(
+            SkillTemplate::from_name("with-sub-skills"),
+            Some(SkillTemplate::WithSubSkills)
+        );
+        assert_eq!(SkillTemplate::from_name("fancy"), None);
+
+        let rendered = SkillTemplate::WithSubSkills
+            .definition()
+            .render("my-skill", "Does things.", &[], &HashMap::new())
+            .unwrap();
+        assert!(rendered
+            .content
+            .starts_with("# My Skill\n\n## Overview\n\nDoes things."));
+        assert!(rendered.content.contains("## Sub-Skills"));
+        assert_eq!(rendered.sub_skills[0].0.file, "references/example.md");
+
+        let minimal = SkillTemplate::Minimal.definition();
+        assert!(!minimal.content.contains("Quick Start"));
+        assert!(minimal.placeholders().is_empty());
+    }
+
+    #[test]
+    fn test_custom_template() {
+        let text = "---\ndescription: REST API reference\ntags: [api]\n---\n\
+            # {{ title }}\n\n{{description}}\n\nBase URL: {{base_url}} ({{tags}})\n";
+        let template = TemplateDefinition::parse("api", text).unwrap();
+
+        assert_eq!(template.description, "REST API reference");
+        assert!(!template.builtin);
+        assert_eq!(template.info().placeholders, vec!["base_url"]);
+
+        let err = template
+            .render("stripe", "Payments API.", &[], &HashMap::new())
+            .unwrap_err();
+        assert!(err.contains("base_url"));
+
+        let variables = HashMap::from([("base_url".to_string(), "https://api".to_string())]);
+        let rendered = template
+            .render("stripe", "Payments API.", &[], &variables)
+            .unwrap();
+        assert_eq!(
+            rendered.content,
+            "# Stripe\n\nPayments API.\n\nBase URL: https://api (api)\n"
+        );
+        assert_eq!(rendered.tags, vec!["api"]);
+    }
+
+    #[test]
+    fn test_with_outline() {
+        let entry = |name: &str, description: &str| OutlineEntry {
+            name: name.to_string(),
+            description: description.to_string(),
+            triggers: vec!["kw".to_string()],
+        };
+        let outline = [
+            entry("react", "Forms in React"),
+            entry("vue", "Forms in Vue"),
+        ];
+
+        let template = SkillTemplate::WithSubSkills
+            .definition()
+            .with_outline(&outline)
+            .unwrap();
+        assert_eq!(template.sub_skills.len(), 2);
+        assert_eq!(template.sub_skills[1].file, "references/vue.md");
+        assert!(template
+            .content
+            .contains("| react | Forms in React |\n| vue | Forms in Vue |\n"));
+        assert!(!template.content.contains("| example |"));
+        assert_eq!(template.content.matches("## Sub-Skills").count(), 1);
+
+        let rendered = template
+            .render("forms", "Forms.", &[], &HashMap::new())
+            .unwrap();
+        assert_eq!(rendered.sub_skills[0].0.triggers, vec!["kw"]);
+        assert!(rendered.sub_skills[0]
+            .1
+            .starts_with("# React\n\nForms in React\n"));
+
+        // Added after the sections of templates without one
+        let minimal = SkillTemplate::Minimal
+            .definition()
+            .with_outline(&outline)
+            .unwrap();
+        assert!(minimal
+            .content
+            .ends_with("- [Add trigger conditions here]\n\n## Sub-Skills\n\nThis skill has the following sub-skills:\n\n| Sub-skill | Description |\n|-----------|-------------|\n| react | Forms in React |\n| vue | Forms in Vue |\n\nUse `get_sub_skill` to load specific sub-skill content.\n"));
+
+        let standard = SkillTemplate::Standard.definition();
+        assert!(standard.clone().with_outline(&[]).is_err());
+        let err = standard
+            .with_outline(&[entry("React", "x"), entry("vue", " "), entry("vue", "y")])
+            .unwrap_err();
+        assert!(err.contains("outline[0].name"));
+        assert!(err.contains("outline[1].description"));
+        assert!(err.contains("outline[2].name: duplicate"));
+    }
+}
@@ -0,0 +1,51 @@
+//! Where to look when a query or task has no confident match.
+
+// Only the search service suggests exploration
+#![cfg_attr(not(feature = "native"), allow(dead_code))]
+
+use std::collections::{HashMap, HashSet};
+
+use crate::models::{Exploration, SkillIndex, SkillStatus};
+
+/// Most near misses and tags suggested.
+pub const EXPLORE_LIMIT: usize = 5;
+
+/// Suggest where to look next, given near misses as `skill` or
+/// `skill:sub-skill`, best first.
+///
+/// Tags are the most common among the near misses' skills, or among all
+/// live skills when there are no near misses.
+pub(crate) fn explore(skill_index: &SkillIndex, mut candidates: Vec<String>) -> Exploration {
+    let mut seen = HashSet::new();
+    candidates.retain(|c| seen.insert(c.clone()));
+    candidates.truncate(EXPLORE_LIMIT);
+
+    let domains: Vec<&str> = candidates
+        .iter()
+        .map(|c| c.split(':').next().unwrap_or(c))
+        .collect();
+
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for skill in skill_index
+        .skills
+        .iter()
+        .filter(|s| s.status != Some(SkillStatus::Deprecated))
+        .filter(|s| domains.is_empty() || domains.contains(&s.name.as_str()))
+    {
+        for tag in &skill.tags {
+            *counts.entry(tag.as_str()).or_default() += 1;
+        }
+    }
+
+    let mut tags: Vec<(&str, usize)> = counts.into_iter().collect();
+    tags.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+    Exploration {
+        candidates,
+        tags: tags
+            .into_iter()
+            .take(EXPLORE_LIMIT)
+            .map(|(tag, _)| tag.to_string())
+            .collect(),
+    }
+}
@@ -0,0 +1,371 @@
+//! Matching and scoring over index data, without I/O.
+//!
+//! [`SearchService`](super::SearchService) runs these against the live
+//! indexer; [`IndexSnapshot`](super::IndexSnapshot) runs them against a
+//! serialized index, e.g. in the browser.
+
+use std::collections::{HashMap, HashSet};
+
+use tracing::debug;
+
+use crate::models::{
+    ContentIndex, ContentIndexEntry, MatchType, SearchOptions, SearchResult, SearchResults,
+    SkillIndex, SkillMeta, SpellingMode,
+};
+
+use super::extract_snippet;
+
+/// Context size for snippets.
+const DEFAULT_SNIPPET_CONTEXT: usize = 50;
+
+/// Collect all metadata matches, before pagination.
+pub(crate) fn skill_matches(
+    skill_index: &SkillIndex,
+    query: &str,
+    options: &SearchOptions,
+) -> Vec<SearchResult> {
+    let query_lower = query.to_lowercase();
+    let terms: Vec<&str> = query_lower.split_whitespace().collect();
+
+    let mut results = Vec::new();
+
+    for skill in &skill_index.skills {
+        if let Some(result) = match_skill(skill, &query_lower, &terms) {
+            // Apply domain and metadata filters
+            if !options.matches_meta(skill) {
+                continue;
+            }
+
+            // Apply match type filter if set
+            if let Some(ref match_types) = options.match_types {
+                if !match_types.contains(&result.match_type) {
+                    continue;
+                }
+            }
+
+            // Apply min score filter
+            if let Some(min_score) = options.min_score {
+                if result.score < min_score {
+                    continue;
+                }
+            }
+
+            // Apply minimum-should-match across all metadata fields
+            if !options.terms_satisfied(matched_skill_terms(skill, &terms), terms.len()) {
+                continue;
+            }
+
+            results.push(result);
+        }
+    }
+
+    results
+}
+
+/// Domains of the skills passing `options`' metadata filters.
+pub(crate) fn allowed_domains(
+    skill_index: &SkillIndex,
+    options: &SearchOptions,
+) -> HashSet<String> {
+    skill_index
+        .skills
+        .iter()
+        .filter(|s| options.matches_meta(s))
+        .map(|s| s.name.clone())
+        .collect()
+}
+
+/// Collect all content matches, before pagination.
+///
+/// Metadata filters need the owning skill, so callers resolve them up front
+/// into `allowed` with [`allowed_domains`]. With `include_metadata`,
+/// synthetic metadata entries compete in the same ranking as file content.
+pub(crate) fn content_matches(
+    content_index: &ContentIndex,
+    allowed: Option<&HashSet<String>>,
+    query: &str,
+    options: &SearchOptions,
+    include_metadata: bool,
+) -> Vec<SearchResult> {
+    let query_lower = query.to_lowercase();
+    let terms: Vec<&str> = query_lower.split_whitespace().collect();
+
+    let mut results = Vec::new();
+
+    for (_, entry) in content_index.iter() {
+        if entry.is_metadata() && !include_metadata {
+            continue;
+        }
+
+        // Apply domain filter
+        if let Some(ref domains) = options.domains {
+            if !domains.contains(&entry.domain) {
+                continue;
+            }
+        }
+
+        if let Some(allowed) = allowed {
+            if !allowed.contains(&entry.domain) {
+                continue;
+            }
+        }
+
+        // Check for matches
+        let term_counts: Vec<usize> = terms.iter().map(|t| entry.count_matches(t)).collect();
+        let match_count: usize = term_counts.iter().sum();
+
+        if match_count == 0 {
+            continue;
+        }
+
+        let matched_terms = term_counts.iter().filter(|&&c| c > 0).count();
+        if !options.terms_satisfied(matched_terms, terms.len()) {
+            continue;
+        }
+
+        let (score, match_type) = if entry.is_metadata() {
+            score_metadata_entry(entry, &terms)
+        } else {
+            // Calculate TF-IDF-like score
+            let tf = match_count as f64 / entry.word_count.max(1) as f64;
+            let content_score = tf * MatchType::Content.weight();
+            let keyword_score = score_keywords(entry, &terms);
+            let match_type = if keyword_score > content_score {
+                MatchType::Keywords
+            } else {
+                MatchType::Content
+            };
+            (content_score + keyword_score, match_type)
+        };
+
+        // Apply min score filter
+        if let Some(min_score) = options.min_score {
+            if score < min_score {
+                continue;
+            }
+        }
+
+        // Extract snippet
+        let snippet = extract_snippet(&entry.content, &query_lower, DEFAULT_SNIPPET_CONTEXT);
+
+        let mut result = SearchResult::new(entry.domain.clone(), score, match_type)
+            .with_file(entry.file.clone());
+        if match_type == MatchType::Keywords {
+            // The terms are in the body too, the keywords just weigh more
+            result.match_types.push(MatchType::Content);
+        }
+
+        if let Some(sub) = &entry.sub_skill {
+            result = result.with_sub_skill(sub.clone());
+        }
+
+        if let Some(snippet) = snippet {
+            result = result.with_snippet(snippet);
+        }
+
+        results.push(result);
+    }
+
+    results
+}
+
+/// Merge skill and content hits for the same domain/sub_skill, combining
+/// their scores.
+pub(crate) fn merge_matches(
+    skill_results: Vec<SearchResult>,
+    content_results: Vec<SearchResult>,
+) -> Vec<SearchResult> {
+    let mut all_results: Vec<SearchResult> = Vec::new();
+    let mut positions: HashMap<(String, Option<String>), usize> = HashMap::new();
+
+    for result in skill_results.into_iter().chain(content_results) {
+        let key = (result.domain.clone(), result.sub_skill.clone());
+        match positions.get(&key) {
+            Some(&i) => all_results[i].merge(result),
+            None => {
+                positions.insert(key, all_results.len());
+                all_results.push(result);
+            }
+        }
+    }
+
+    all_results
+}
+
+/// Apply offset, limit and next-page cursor to ranked results.
+pub(crate) fn paginate(
+    query: &str,
+    results: Vec<SearchResult>,
+    options: &SearchOptions,
+    version: u64,
+) -> SearchResults {
+    SearchResults::page(
+        query.to_string(),
+        results,
+        options.offset.unwrap_or(0),
+        options.limit,
+    )
+    .with_cursor(version)
+}
+
+/// Run `search` for `query`, applying `options.spelling` when it finds
+/// nothing.
+///
+/// `Suggest` attaches `suggest`'s alternatives to the empty result, `Auto`
+/// reruns the best correction and reports it as `corrected_query`.
+pub(crate) fn with_spelling(
+    query: &str,
+    options: &SearchOptions,
+    suggest: impl FnOnce(&str) -> Vec<String>,
+    search: impl Fn(&str) -> SearchResults,
+) -> SearchResults {
+    let mut results = search(query);
+
+    let Some(mode) = options.spelling else {
+        return results;
+    };
+    if results.total_matches > 0 {
+        return results;
+    }
+
+    let suggestions = suggest(query);
+    if mode == SpellingMode::Auto {
+        if let Some(corrected) = suggestions.first() {
+            let mut retried = search(corrected);
+            if retried.total_matches > 0 {
+                debug!("Corrected query '{}' to '{}'", query, corrected);
+                retried.query = query.to_string();
+                retried.corrected_query = Some(corrected.clone());
+                return retried;
+            }
+        }
+    }
+
+    results.suggestions = suggestions;
+    results
+}
+
+/// Score a synthetic metadata entry field by field.
+///
+/// Each field contributes its term frequency times its match type
+/// weight; the strongest field decides the reported match type.
+fn score_metadata_entry(entry: &ContentIndexEntry, terms: &[&str]) -> (f64, MatchType) {
+    let mut total = 0.0;
+    let mut best = (0.0, MatchType::Content);
+
+    for field in &entry.fields {
+        let hits: usize = terms.iter().map(|t| field.text.matches(t).count()).sum();
+        if hits == 0 {
+            continue;
+        }
+
+        let words = field.text.split_whitespace().count().max(1);
+        let score = hits as f64 / words as f64 * field.match_type.weight();
+        total += score;
+
+        if score > best.0 {
+            best = (score, field.match_type);
+        }
+    }
+
+    (total, best.1)
+}
+
+/// Score the query terms found in an entry's extracted keywords.
+///
+/// Each matched keyword counts once, over the number of keywords, so a
+/// file whose salient terms are the query ranks above one merely
+/// mentioning it.
+fn score_keywords(entry: &ContentIndexEntry, terms: &[&str]) -> f64 {
+    let matched = entry
+        .keywords
+        .iter()
+        .filter(|k| terms.iter().any(|t| k.contains(t)))
+        .count();
+    matched as f64 / entry.keywords.len().max(1) as f64 * MatchType::Keywords.weight()
+}
+
+/// Count the query terms found anywhere in a skill's metadata.
+fn matched_skill_terms(skill: &SkillMeta, terms: &[&str]) -> usize {
+    let mut haystack = format!("{} {}", skill.name, skill.description);
+    for trigger in skill.all_triggers() {
+        haystack.push(' ');
+        haystack.push_str(trigger);
+    }
+    let haystack = haystack.to_lowercase();
+
+    terms.iter().filter(|t| haystack.contains(*t)).count()
+}
+
+/// Match a skill against search terms.
+fn match_skill(skill: &SkillMeta, query: &str, terms: &[&str]) -> Option<SearchResult> {
+    let name_lower = skill.name.to_lowercase();
+    let desc_lower = skill.description.to_lowercase();
+
+    // Exact name match (highest priority)
+    if name_lower == query {
+        return Some(SearchResult::new(
+            skill.name.clone(),
+            1.0 * MatchType::Name.weight(),
+            MatchType::Name,
+        ));
+    }
+
+    // Name contains query
+    if name_lower.contains(query) {
+        return Some(SearchResult::new(
+            skill.name.clone(),
+            0.8 * MatchType::Name.weight(),
+            MatchType::Name,
+        ));
+    }
+
+    // Check tags first (before triggers, since all_triggers includes tags)
+    let tags: Vec<String> = skill.tags.iter().map(|s| s.to_lowercase()).collect();
+    for tag in &tags {
+        if tag == query || tag.contains(query) {
+            return Some(SearchResult::new(
+                skill.name.clone(),
+                0.9 * MatchType::Tags.weight(),
+                MatchType::Tags,
+            ));
+        }
+    }
+
+    // Check sub-skill triggers (only the actual triggers, not tags). A
+    // sub-skill whose negative triggers the query mentions is passed over,
+    // and the best-weighted matching trigger picks the sub-skill.
+    let mut best_trigger: Option<(f64, String)> = None;
+    for (path, sub) in skill.all_sub_skills() {
+        if sub.excluded_by(query) {
+            continue;
+        }
+        for trigger in sub.parsed_triggers().filter(|t| !t.negative) {
+            let trigger_lower = trigger.phrase.to_lowercase();
+            if trigger_lower.contains(query) || query.contains(&trigger_lower) {
+                let score = 0.9 * trigger.weight * MatchType::Triggers.weight();
+                if best_trigger.as_ref().is_none_or(|(best, _)| score > *best) {
+                    best_trigger = Some((score, path.clone()));
+                }
+            }
+        }
+    }
+    if let Some((score, path)) = best_trigger {
+        return Some(
+            SearchResult::new(skill.name.clone(), score, MatchType::Triggers).with_sub_skill(path),
+        );
+    }
+
+    // Description match
+    let term_matches: usize = terms.iter().filter(|t| desc_lower.contains(*t)).count();
+
+    if term_matches > 0 {
+        let score = (term_matches as f64 / terms.len() as f64) * MatchType::Description.weight();
+        return Some(
+            SearchResult::new(skill.name.clone(), score, MatchType::Description)
+                .with_snippet(skill.description.clone()),
+        );
+    }
+
+    None
+}
@@ -1,7 +1,30 @@
 //! Search services for skills and content.
 
+mod exploration;
+mod matching;
+mod passages;
+#[cfg(feature = "native")]
+mod rerank;
+mod routing;
+#[cfg(feature = "native")]
 mod service;
+mod single_flight;
+mod snapshot;
 mod snippet;
+mod spelling;
+mod triggers;
+mod zero_results;
 
-pub use service::SearchService;
+pub use exploration::EXPLORE_LIMIT;
+pub use passages::{question_terms, Passage, MAX_PASSAGE_CHARS};
+#[cfg(feature = "native")]
+pub use rerank::{LlmReranker, NoopReranker, RerankBudget, RerankCandidate, RerankError, Reranker};
+pub use routing::{Route, Routing, DEFAULT_MIN_ROUTE_CONFIDENCE, DEFAULT_ROUTES};
+#[cfg(feature = "native")]
+pub use service::{SearchError, SearchService};
+pub use single_flight::SingleFlight;
+pub use snapshot::{IndexSnapshot, SnapshotError, SNAPSHOT_FORMAT_VERSION, SNAPSHOT_MAGIC};
 pub use snippet::extract_snippet;
+pub use spelling::Vocabulary;
+pub use triggers::{suggest_triggers, SuggestedTriggers, TriggerSuggestion, DEFAULT_MIN_SEARCHES};
+pub use zero_results::ZeroResultCache;
@@ -0,0 +1,149 @@
+//! Passage retrieval for question answering.
+//!
+//! A question is reduced to its keywords and run against both metadata
+//! and content, then each hit document is split at its headings and the
+//! section covering the most keywords becomes the passage. Passages are
+//! ranked by the document's search score weighted by that coverage.
+
+// Sections are only read from disk by the search service
+#![cfg_attr(not(feature = "native"), allow(dead_code))]
+
+use serde::Serialize;
+
+/// Longest passage returned, in characters.
+pub const MAX_PASSAGE_CHARS: usize = 1500;
+
+/// Question words that don't help find passages.
+const QUESTION_STOPWORDS: &[&str] = &[
+    "about", "and", "are", "can", "could", "does", "for", "from", "how", "into", "should", "that",
+    "the", "their", "there", "this", "what", "when", "where", "which", "who", "why", "will",
+    "with", "would", "you", "your",
+];
+
+/// A section of a skill document relevant to a question.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Passage {
+    /// Skill the passage comes from.
+    pub skill: String,
+    /// File relative to the skill directory.
+    pub file: String,
+    /// Heading of the section, if it has one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub section: Option<String>,
+    /// Section text, truncated to [`MAX_PASSAGE_CHARS`].
+    pub text: String,
+    /// Relevance score.
+    pub score: f64,
+}
+
+/// Reduce a natural-language question to lowercase search keywords.
+pub fn question_terms(question: &str) -> Vec<String> {
+    let mut terms: Vec<String> = Vec::new();
+    for word in question.split(|c: char| !c.is_alphanumeric() && c != '-' && c != '_') {
+        let word = word.trim_matches('-').to_lowercase();
+        if word.len() > 2 && !QUESTION_STOPWORDS.contains(&word.as_str()) && !terms.contains(&word)
+        {
+            terms.push(word);
+        }
+    }
+    terms
+}
+
+/// Split markdown into `(heading, text)` sections at each heading.
+///
+/// Text before the first heading is a section without a heading. Headings
+/// inside fenced code blocks don't start sections.
+pub fn split_sections(markdown: &str) -> Vec<(Option<String>, String)> {
+    let mut sections: Vec<(Option<String>, String)> = vec![(None, String::new())];
+    let mut in_fence = false;
+
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        }
+
+        if !in_fence && trimmed.starts_with('#') {
+            let heading = trimmed.trim_start_matches('#').trim();
+            if !heading.is_empty() {
+                sections.push((Some(heading.to_string()), String::new()));
+                continue;
+            }
+        }
+
+        let text = &mut sections.last_mut().unwrap().1;
+        text.push_str(line);
+        text.push('\n');
+    }
+
+    sections
+        .into_iter()
+        .map(|(heading, text)| (heading, text.trim().to_string()))
+        .filter(|(heading, text)| heading.is_some() || !text.is_empty())
+        .collect()
+}
+
+/// Pick the section of `markdown` covering the most of `terms`.
+///
+/// Returns the section and the share of terms it contains. Ties go to the
+/// earlier section, so a document with no matching section yields its
+/// opening with coverage 0.
+pub fn best_section(markdown: &str, terms: &[String]) -> Option<(Option<String>, String, f64)> {
+    let mut best: Option<(Option<String>, String, f64)> = None;
+
+    for (heading, text) in split_sections(markdown) {
+        let haystack = format!("{} {}", heading.as_deref().unwrap_or(""), text).to_lowercase();
+        let matched = terms
+            .iter()
+            .filter(|t| haystack.contains(t.as_str()))
+            .count();
+        let coverage = matched as f64 / terms.len().max(1) as f64;
+
+        if best.as_ref().is_none_or(|(_, _, b)| coverage > *b) {
+            best = Some((heading, truncate_chars(&text, MAX_PASSAGE_CHARS), coverage));
+        }
+    }
+
+    best
+}
+
+fn truncate_chars(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((at, _)) => format!("{}…", text[..at].trim_end()),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_question_terms() {
+        assert_eq!(
+            question_terms("How do I validate a form on submit? What about on-blur validation?"),
+            vec!["validate", "form", "submit", "on-blur", "validation"]
+        );
+        assert!(question_terms("how do I?").is_empty());
+    }
+
+    #[test]
+    fn test_best_section() {
+        let markdown = "Intro text.\n\n# Forms\n\n## Setup\n\nInstall it.\n\n\
+            ## Validation\n\nValidate on submit.\n\n```\n# not a heading\n```\n";
+
+        let sections = split_sections(markdown);
+        assert_eq!(sections.len(), 4);
+        assert_eq!(sections[0], (None, "Intro text.".to_string()));
+        assert!(sections[3].1.contains("# not a heading"));
+
+        let terms = question_terms("How do I validate on submit?");
+        let (heading, text, coverage) = best_section(markdown, &terms).unwrap();
+        assert_eq!(heading.as_deref(), Some("Validation"));
+        assert!(text.starts_with("Validate on submit."));
+        assert_eq!(coverage, 1.0);
+
+        let (heading, _, coverage) = best_section(markdown, &["unrelated".to_string()]).unwrap();
+        assert_eq!((heading, coverage), (None, 0.0));
+    }
+}
@@ -0,0 +1,234 @@
+//! Pluggable reranking of the top search results.
+//!
+//! First-stage scoring is lexical and cheap. A [`Reranker`] looks again at
+//! the few results that matter most, typically with a model that reads the
+//! query and each candidate together, and reorders them. The search service
+//! runs it under a [`RerankBudget`] and keeps the first-stage order when the
+//! reranker fails or runs out of time.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::llm::{parse_json_reply, CompletionRequest, LlmBackend, LlmError};
+
+/// A result offered to a reranker.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RerankCandidate {
+    /// `skill` or `skill:sub-skill`.
+    pub id: String,
+    /// What the candidate is about: description, summary and snippet.
+    pub text: String,
+    /// First-stage score.
+    pub score: f64,
+}
+
+/// Reorders the top results of a search.
+pub trait Reranker: Send + Sync {
+    /// Short name for logs and responses.
+    fn name(&self) -> &str;
+
+    /// Score how relevant each candidate is to `query`, in candidate order.
+    /// Higher is better; the scale is up to the reranker.
+    fn score(&self, query: &str, candidates: &[RerankCandidate]) -> Result<Vec<f64>, RerankError>;
+
+    /// Whether this reranker keeps every order as it is, so the stage can
+    /// be skipped.
+    fn is_noop(&self) -> bool {
+        false
+    }
+}
+
+/// Errors from a reranker.
+#[derive(Debug, thiserror::Error)]
+pub enum RerankError {
+    /// The model behind the reranker failed.
+    #[error(transparent)]
+    Llm(#[from] LlmError),
+
+    /// The reranker did not score every candidate.
+    #[error("Reranker returned {got} scores for {expected} candidates")]
+    ScoreCount {
+        /// Number of candidates.
+        expected: usize,
+        /// Number of scores returned.
+        got: usize,
+    },
+
+    /// The reranker ran past its latency budget.
+    #[error("Reranker exceeded its {0:?} budget")]
+    TimedOut(Duration),
+
+    /// Every reranker worker was still busy with earlier searches.
+    #[error("All {0} reranker workers are busy")]
+    Busy(usize),
+
+    /// The reranker stopped without an answer.
+    #[error("Reranker failed: {0}")]
+    Failed(String),
+}
+
+/// How much reranking a search may afford.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RerankBudget {
+    /// Number of top results reranked.
+    pub top_k: usize,
+    /// Longest a search waits for the reranker.
+    pub timeout: Duration,
+    /// Most reranker calls running at once. A search that times out leaves
+    /// its call running until it finishes, so this also bounds those.
+    pub max_workers: usize,
+}
+
+impl RerankBudget {
+    /// Default number of top results reranked.
+    pub const DEFAULT_TOP_K: usize = 10;
+
+    /// Default wait for the reranker.
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_millis(1500);
+
+    /// Default number of reranker calls running at once.
+    pub const DEFAULT_MAX_WORKERS: usize = 4;
+}
+
+impl Default for RerankBudget {
+    fn default() -> Self {
+        Self {
+            top_k: Self::DEFAULT_TOP_K,
+            timeout: Self::DEFAULT_TIMEOUT,
+            max_workers: Self::DEFAULT_MAX_WORKERS,
+        }
+    }
+}
+
+/// Keeps the first-stage order. The default.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopReranker;
+
+impl Reranker for NoopReranker {
+    fn name(&self) -> &str {
+        "noop"
+    }
+
+    fn score(&self, _query: &str, candidates: &[RerankCandidate]) -> Result<Vec<f64>, RerankError> {
+        Ok(candidates.iter().map(|c| c.score).collect())
+    }
+
+    fn is_noop(&self) -> bool {
+        true
+    }
+}
+
+/// Reply length bound for relevance scores.
+const MAX_SCORE_TOKENS: u32 = 200;
+
+const SYSTEM_PROMPT: &str = "You rank search results for a developer's query. Rate how well \
+each numbered candidate serves the query, from 0 (irrelevant) to 10 (exactly what is needed). \
+Reply with JSON only, in the form {\"scores\": [7, 2, ...]}, one score per candidate in the \
+order given.";
+
+#[derive(Deserialize)]
+struct ScoresReply {
+    scores: Vec<f64>,
+}
+
+/// Cross-encoder reranker that asks a language model to score the query
+/// against every candidate in one prompt.
+pub struct LlmReranker {
+    backend: Arc<dyn LlmBackend>,
+}
+
+impl LlmReranker {
+    /// Rerank with the given backend.
+    pub fn new(backend: Arc<dyn LlmBackend>) -> Self {
+        Self { backend }
+    }
+}
+
+impl Reranker for LlmReranker {
+    fn name(&self) -> &str {
+        self.backend.name()
+    }
+
+    fn score(&self, query: &str, candidates: &[RerankCandidate]) -> Result<Vec<f64>, RerankError> {
+        let mut prompt = format!("Query: {}\n\nCandidates:\n", query);
+        for (i, candidate) in candidates.iter().enumerate() {
+            prompt.push_str(&format!(
+                "\n[{}] {}\n{}\n",
+                i + 1,
+                candidate.id,
+                candidate.text
+            ));
+        }
+
+        let request =
+            CompletionRequest::new(SYSTEM_PROMPT, prompt).with_max_tokens(MAX_SCORE_TOKENS);
+        let reply: ScoresReply = parse_json_reply(&self.backend.complete(&request)?)?;
+
+        if reply.scores.len() != candidates.len() {
+            return Err(RerankError::ScoreCount {
+                expected: candidates.len(),
+                got: reply.scores.len(),
+            });
+        }
+        Ok(reply.scores)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Canned(&'static str);
+
+    impl LlmBackend for Canned {
+        fn name(&self) -> &str {
+            "canned"
+        }
+
+        fn complete(&self, request: &CompletionRequest) -> Result<String, LlmError> {
+            assert!(request.prompt.contains("Query: validate a form"));
+            assert!(request.prompt.contains("[2] forms:react"));
+            Ok(self.0.to_string())
+        }
+    }
+
+    fn candidates() -> Vec<RerankCandidate> {
+        ["forms", "forms:react"]
+            .iter()
+            .map(|id| RerankCandidate {
+                id: id.to_string(),
+                text: "Form handling".to_string(),
+                score: 1.0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_llm_reranker_scores() {
+        let reranker = LlmReranker::new(Arc::new(Canned("{\"scores\": [3, 9]}")));
+        assert_eq!(
+            reranker.score("validate a form", &candidates()).unwrap(),
+            vec![3.0, 9.0]
+        );
+
+        let short = LlmReranker::new(Arc::new(Canned("{\"scores\": [3]}")));
+        assert!(matches!(
+            short.score("validate a form", &candidates()),
+            Err(RerankError::ScoreCount {
+                expected: 2,
+                got: 1
+            })
+        ));
+    }
+
+    #[test]
+    fn test_noop_reranker_keeps_scores() {
+        assert!(NoopReranker.is_noop());
+        assert_eq!(
+            NoopReranker.score("q", &candidates()).unwrap(),
+            vec![1.0, 1.0]
+        );
+    }
+}
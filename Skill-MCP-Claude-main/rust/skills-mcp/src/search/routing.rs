@@ -0,0 +1,207 @@
+//! Routing a free-form task to the skills that should handle it.
+//!
+//! Triggers named in the task point straight at their sub-skills; the
+//! task's keywords then rank metadata and content the way a search would.
+//! A skill's own evidence backs its sub-skills, and each skill is routed
+//! to its best-supported sub-skill, or to itself when none stands out.
+
+// Only the search service routes tasks
+#![cfg_attr(not(feature = "native"), allow(dead_code))]
+
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+
+use crate::models::{
+    score_confidence, ContentIndex, ContentIndexEntry, Exploration, MatchType, SearchOptions,
+    SkillIndex, SkillStatus,
+};
+
+use super::matching::content_matches;
+use super::question_terms;
+
+/// Default number of routes returned for a task.
+pub const DEFAULT_ROUTES: usize = 3;
+
+/// Default lowest confidence a route needs to be returned.
+pub const DEFAULT_MIN_ROUTE_CONFIDENCE: f64 = 0.3;
+
+/// Share of its skill's evidence a sub-skill needs to be routed to.
+const SUB_SKILL_SHARE: f64 = 0.5;
+
+/// Where a task should go, and why.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Route {
+    /// Skill to load.
+    pub skill: String,
+    /// Sub-skill to load instead of the skill's main document, if one fits.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub_skill: Option<String>,
+    /// How sure the routing is, from 0 to 1.
+    pub confidence: f64,
+    /// Combined score of the evidence.
+    pub score: f64,
+    /// What the route is based on, e.g. `trigger 'useForm'; content of react.md`.
+    pub reason: String,
+}
+
+impl Route {
+    /// `skill` or `skill:sub-skill`.
+    pub fn id(&self) -> String {
+        match &self.sub_skill {
+            Some(sub) => format!("{}:{}", self.skill, sub),
+            None => self.skill.clone(),
+        }
+    }
+}
+
+/// Confident routes for a task, or where to explore when there are none.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Routing {
+    /// Skills to load for the task, best first.
+    pub routes: Vec<Route>,
+    /// Whether no route reached the minimum confidence.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub no_confident_match: bool,
+    /// Rephrasings of the task drawn from the index vocabulary.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub suggestions: Vec<String>,
+    /// Where to look instead when there is no confident match.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explore: Option<Exploration>,
+}
+
+/// Score and reasons collected for one skill or sub-skill.
+#[derive(Debug, Default)]
+struct Evidence {
+    score: f64,
+    reasons: Vec<String>,
+}
+
+impl Evidence {
+    fn add(&mut self, score: f64, reason: String) {
+        self.score += score;
+        if !self.reasons.contains(&reason) {
+            self.reasons.push(reason);
+        }
+    }
+}
+
+/// Rank up to `limit` routes for `task`, best first, one per skill.
+///
+/// Deprecated skills are never routed to.
+pub(crate) fn route_task(
+    skill_index: &SkillIndex,
+    content_index: &ContentIndex,
+    task: &str,
+    limit: usize,
+) -> Vec<Route> {
+    let terms = question_terms(task);
+    if terms.is_empty() || limit == 0 {
+        return Vec::new();
+    }
+    let task_lower = task.to_lowercase();
+
+    let routable: HashSet<String> = skill_index
+        .skills
+        .iter()
+        .filter(|s| s.status != Some(SkillStatus::Deprecated))
+        .map(|s| s.name.clone())
+        .collect();
+    let mut evidence: HashMap<(String, Option<String>), Evidence> = HashMap::new();
+
+    // Triggers named in the task point straight at their sub-skill
+    for skill in skill_index
+        .skills
+        .iter()
+        .filter(|s| routable.contains(&s.name))
+    {
+        for (path, sub) in skill.all_sub_skills() {
+            if sub.excluded_by(&task_lower) {
+                continue;
+            }
+            for trigger in sub.parsed_triggers().filter(|t| !t.negative) {
+                if task_lower.contains(&trigger.phrase.to_lowercase()) {
+                    evidence
+                        .entry((skill.name.clone(), Some(path.clone())))
+                        .or_default()
+                        .add(
+                            trigger.weight * MatchType::Triggers.weight(),
+                            format!("trigger '{}'", trigger.phrase),
+                        );
+                }
+            }
+        }
+    }
+
+    // The task's keywords rank metadata and content like a search
+    let query = terms.join(" ");
+    let options = SearchOptions::default();
+    for hit in content_matches(content_index, Some(&routable), &query, &options, true) {
+        let reason = match hit.file.as_deref() {
+            Some(ContentIndexEntry::METADATA_FILE) | None => {
+                format!("{} match", field_name(hit.match_type))
+            }
+            Some(file) => format!("{} of {}", field_name(hit.match_type), file),
+        };
+        evidence
+            .entry((hit.domain, hit.sub_skill))
+            .or_default()
+            .add(hit.score, reason);
+    }
+
+    // Split each skill's own evidence from its sub-skills'
+    let mut skills: HashMap<String, (Evidence, Vec<(String, Evidence)>)> = HashMap::new();
+    for ((skill, sub_skill), found) in evidence {
+        let entry = skills.entry(skill).or_default();
+        match sub_skill {
+            Some(sub_skill) => entry.1.push((sub_skill, found)),
+            None => entry.0 = found,
+        }
+    }
+
+    let mut routes: Vec<Route> = skills
+        .into_iter()
+        .map(|(skill, (own, mut subs))| {
+            subs.sort_by(|a, b| b.1.score.total_cmp(&a.1.score).then_with(|| a.0.cmp(&b.0)));
+            let best_sub = subs
+                .into_iter()
+                .next()
+                .filter(|(_, sub)| sub.score >= own.score * SUB_SKILL_SHARE);
+
+            let (sub_skill, score, mut reasons) = match best_sub {
+                Some((name, sub)) => (Some(name), sub.score + own.score, sub.reasons),
+                None => (None, own.score, Vec::new()),
+            };
+            reasons.extend(own.reasons);
+
+            Route {
+                skill,
+                sub_skill,
+                confidence: score_confidence(score),
+                score,
+                reason: reasons.join("; "),
+            }
+        })
+        .collect();
+
+    routes.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.skill.cmp(&b.skill))
+    });
+    routes.truncate(limit);
+    routes
+}
+
+/// How a reason names the field a hit matched.
+fn field_name(match_type: MatchType) -> &'static str {
+    match match_type {
+        MatchType::Name => "name",
+        MatchType::Description => "description",
+        MatchType::Tags => "tags",
+        MatchType::Triggers => "triggers",
+        MatchType::Keywords => "keywords",
+        MatchType::Content => "content",
+    }
+}
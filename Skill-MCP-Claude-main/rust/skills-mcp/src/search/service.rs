@@ -1,232 +1,678 @@
 //! Search service implementation.
 
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use tracing::debug;
+use parking_lot::RwLock;
+use rayon::prelude::*;
+use tracing::{debug, info, warn};
 
 use crate::index::SkillIndexer;
-use crate::models::{MatchType, SearchOptions, SearchResult, SearchResults, SkillMeta};
+use crate::models::{
+    score_confidence, split_frontmatter, ContentIndexEntry, GroupedSearchResults, SearchCursor,
+    SearchMemory, SearchOptions, SearchResult, SearchResults, SearchScope, SkillIndex,
+};
+
+use super::passages::{best_section, question_terms};
+use super::{exploration, matching, routing};
+use super::{
+    NoopReranker, Passage, RerankBudget, RerankCandidate, RerankError, Reranker, Route, Routing,
+    SingleFlight, Vocabulary, ZeroResultCache,
+};
+
+/// Search errors.
+#[derive(Debug, thiserror::Error)]
+pub enum SearchError {
+    /// The cursor could not be decoded.
+    #[error("Invalid search cursor")]
+    InvalidCursor,
+
+    /// The cursor was issued against an older index version.
+    #[error("Search cursor is stale; the index has changed since it was issued")]
+    StaleCursor,
+}
+
+/// Spelling vocabulary, tagged with the index version it was built from.
+type VocabularyCache = Arc<RwLock<Option<(u64, Arc<Vocabulary>)>>>;
+
+/// One of a bounded number of reranker threads, released when dropped.
+struct RerankWorker(Arc<AtomicUsize>);
+
+impl RerankWorker {
+    /// Take a worker slot, or `None` if all `max` are in use.
+    fn acquire(workers: &Arc<AtomicUsize>, max: usize) -> Option<Self> {
+        workers
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                (n < max).then_some(n + 1)
+            })
+            .ok()
+            .map(|_| Self(Arc::clone(workers)))
+    }
+}
 
-use super::extract_snippet;
+impl Drop for RerankWorker {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
 
 /// Search service for querying skills and content.
 pub struct SearchService {
     indexer: Arc<SkillIndexer>,
+    /// Spelling vocabulary, shared with the warm-up thread.
+    vocabulary: VocabularyCache,
+    /// Recent queries that found nothing.
+    zero_results: ZeroResultCache,
+    /// Searches running now, shared with identical concurrent searches.
+    in_flight: SingleFlight<SearchResults>,
+    /// Second stage applied to the top of each first page.
+    reranker: Arc<dyn Reranker>,
+    /// How much reranking a search may afford.
+    rerank_budget: RerankBudget,
+    /// Reranker calls running now, including those searches gave up on.
+    rerank_workers: Arc<AtomicUsize>,
+    /// Searches slower than this are logged; zero turns it off.
+    slow_search: Duration,
+    /// Searches that took longer than `slow_search`.
+    slow_searches: AtomicU64,
 }
 
 impl SearchService {
-    /// Default context size for snippets.
-    const DEFAULT_SNIPPET_CONTEXT: usize = 50;
+    /// Default slow search threshold.
+    pub const DEFAULT_SLOW_SEARCH: Duration = Duration::from_millis(500);
 
     /// Create a new search service.
     pub fn new(indexer: Arc<SkillIndexer>) -> Self {
-        Self { indexer }
+        Self {
+            indexer,
+            vocabulary: Arc::new(RwLock::new(None)),
+            zero_results: ZeroResultCache::default(),
+            in_flight: SingleFlight::new(),
+            reranker: Arc::new(NoopReranker),
+            rerank_budget: RerankBudget::default(),
+            rerank_workers: Arc::new(AtomicUsize::new(0)),
+            slow_search: Self::DEFAULT_SLOW_SEARCH,
+            slow_searches: AtomicU64::new(0),
+        }
     }
 
-    /// Search skills by metadata (name, description, tags, triggers).
-    pub fn search_skills(&self, query: &str, options: SearchOptions) -> SearchResults {
-        let skill_index = self.indexer.get_skill_index();
-        let query_lower = query.to_lowercase();
-        let terms: Vec<&str> = query_lower.split_whitespace().collect();
-
-        let mut results = Vec::new();
-
-        for skill in &skill_index.skills {
-            if let Some(result) = self.match_skill(skill, &query_lower, &terms) {
-                // Apply domain filter if set
-                if let Some(ref domains) = options.domains {
-                    if !domains.contains(&skill.name) {
-                        continue;
-                    }
-                }
+    /// Rerank the top results of every first page within `budget`.
+    pub fn with_reranker(mut self, reranker: Arc<dyn Reranker>, budget: RerankBudget) -> Self {
+        self.reranker = reranker;
+        self.rerank_budget = budget;
+        self
+    }
 
-                // Apply match type filter if set
-                if let Some(ref match_types) = options.match_types {
-                    if !match_types.contains(&result.match_type) {
-                        continue;
-                    }
-                }
+    /// Set how long a search may take before it is logged as slow and
+    /// counted in [`Self::slow_searches`]. `Duration::ZERO` turns this off.
+    pub fn with_slow_search(mut self, threshold: Duration) -> Self {
+        self.set_slow_search(threshold);
+        self
+    }
 
-                // Apply min score filter
-                if let Some(min_score) = options.min_score {
-                    if result.score < min_score {
-                        continue;
-                    }
-                }
+    /// Change the slow search threshold in place.
+    pub fn set_slow_search(&mut self, threshold: Duration) {
+        self.slow_search = threshold;
+    }
 
-                results.push(result);
-            }
+    /// Number of searches so far that were slower than the slow search
+    /// threshold. Identical searches sharing one computation count once.
+    pub fn slow_searches(&self) -> u64 {
+        self.slow_searches.load(Ordering::Relaxed)
+    }
+
+    /// Set how long zero-result queries are cached. `Duration::ZERO` disables it.
+    pub fn with_zero_result_ttl(mut self, ttl: Duration) -> Self {
+        self.zero_results = ZeroResultCache::new(ttl, ZeroResultCache::DEFAULT_CAPACITY);
+        self
+    }
+
+    /// Vocabulary for spelling correction, rebuilt when the index changes.
+    pub fn vocabulary(&self) -> Arc<Vocabulary> {
+        cached_vocabulary(&self.indexer, &self.vocabulary)
+    }
+
+    /// Memory held by the spelling vocabulary and zero-result cache. The
+    /// vocabulary is only counted if a search has built it.
+    pub fn memory(&self) -> SearchMemory {
+        let (vocabulary_terms, vocabulary_bytes) = match self.vocabulary.read().as_ref() {
+            Some((_, vocabulary)) => (vocabulary.len(), vocabulary.approximate_bytes()),
+            None => (0, 0),
+        };
+        SearchMemory {
+            vocabulary_terms,
+            vocabulary_bytes,
+            zero_result_queries: self.zero_results.len(),
         }
+    }
 
-        debug!(
-            "Skill search '{}' found {} results",
-            query,
-            results.len()
-        );
+    /// Build what searches would otherwise build on first use, so the first
+    /// query is as fast as the rest.
+    pub fn warm_up(&self) {
+        warm_up(&self.indexer, &self.vocabulary);
+    }
+
+    /// Warm up on a background thread and set `ready` when done.
+    ///
+    /// Warms up on the calling thread if no thread can be started.
+    pub fn warm_up_in_background(&self, ready: Arc<AtomicBool>) {
+        let indexer = Arc::clone(&self.indexer);
+        let vocabulary = Arc::clone(&self.vocabulary);
+        let done = Arc::clone(&ready);
+
+        let spawned = std::thread::Builder::new()
+            .name("skills-warmup".to_string())
+            .spawn(move || {
+                warm_up(&indexer, &vocabulary);
+                done.store(true, Ordering::Release);
+            });
+
+        if let Err(e) = spawned {
+            warn!("Failed to start warm-up thread, warming up inline: {}", e);
+            self.warm_up();
+            ready.store(true, Ordering::Release);
+        }
+    }
+
+    /// Resolve a pagination cursor to a result offset.
+    ///
+    /// Cursors issued against an older index version are rejected, since the
+    /// ranking they page through may no longer exist.
+    pub fn resolve_cursor(&self, cursor: &str) -> Result<usize, SearchError> {
+        let cursor = SearchCursor::decode(cursor).ok_or(SearchError::InvalidCursor)?;
+
+        if cursor.version != self.indexer.version() {
+            return Err(SearchError::StaleCursor);
+        }
 
-        SearchResults::new(query.to_string(), results, options.limit)
+        Ok(cursor.offset)
+    }
+
+    /// Search skills by metadata (name, description, tags, triggers).
+    pub fn search_skills(&self, query: &str, options: SearchOptions) -> SearchResults {
+        self.search(SearchScope::Skills, query, options)
     }
 
     /// Search content by full-text matching.
     pub fn search_content(&self, query: &str, options: SearchOptions) -> SearchResults {
-        let content_index = self.indexer.get_content_index();
-        let query_lower = query.to_lowercase();
-        let terms: Vec<&str> = query_lower.split_whitespace().collect();
+        self.search(SearchScope::Content, query, options)
+    }
+
+    /// Combined search across both skills and content.
+    pub fn search_all(&self, query: &str, options: SearchOptions) -> SearchResults {
+        self.search(SearchScope::All, query, options)
+    }
+
+    /// Search a single scope.
+    ///
+    /// When the query finds nothing and `options.spelling` is set, the query
+    /// is checked against the index vocabulary: `Suggest` attaches
+    /// alternatives to the empty result, `Auto` reruns the best correction
+    /// and reports it as `corrected_query`.
+    ///
+    /// With `options.min_confidence` set, a first page whose top hit is less
+    /// confident than that is withheld as no confident match, with its hits
+    /// offered as places to explore. The top of a first page is then
+    /// reordered by the reranker, if one is configured.
+    ///
+    /// Queries that still find nothing are cached briefly, so an agent
+    /// retrying the same failing query gets the empty result and
+    /// suggestions back without another scan. Identical searches that run
+    /// at the same time, as when an agent fans out, are computed once and
+    /// share the result.
+    pub fn search(&self, scope: SearchScope, query: &str, options: SearchOptions) -> SearchResults {
+        let version = self.indexer.version();
+        let key = format!("{:?}|{}|{:?}", scope, query.trim().to_lowercase(), options);
+
+        if let Some(mut cached) = self.zero_results.get(&key, version) {
+            debug!("Zero-result cache hit for '{}'", query);
+            cached.query = query.to_string();
+            cached.cached = true;
+            return cached;
+        }
 
-        let mut results = Vec::new();
+        let flight = format!("{}|{}", version, key);
+        let (mut results, shared) = self.in_flight.run(&flight, || {
+            let started = Instant::now();
+            let mut results = self.search_uncached(scope, query, &options);
+            if let Some(min_confidence) = options.min_confidence {
+                self.withhold_unconfident(&mut results, min_confidence);
+            }
+            self.rerank(&mut results);
 
-        for (_, entry) in content_index.iter() {
-            // Apply domain filter
-            if let Some(ref domains) = options.domains {
-                if !domains.contains(&entry.domain) {
-                    continue;
+            if results.total_matches == 0 {
+                let mut remembered = results.clone();
+                if remembered.suggestions.is_empty() {
+                    remembered.suggestions = self.vocabulary().suggest(query);
                 }
+                self.zero_results.insert(key, version, remembered);
             }
+            self.note_duration(scope, query, started, results.total_matches);
+            results
+        });
+
+        if shared {
+            debug!("Shared in-flight search for '{}'", query);
+            results.query = query.to_string();
+        }
+        results
+    }
+
+    /// Withhold a first page whose top hit is less confident than
+    /// `min_confidence`.
+    fn withhold_unconfident(&self, results: &mut SearchResults, min_confidence: f64) {
+        let Some(top) = results.top() else {
+            return;
+        };
+        if results.offset > 0 || score_confidence(top.score) >= min_confidence {
+            return;
+        }
+
+        let candidates = results
+            .results
+            .iter()
+            .map(SearchResult::display_id)
+            .collect();
+        let explore = exploration::explore(&self.indexer.get_skill_index(), candidates);
+        debug!("No confident match for '{}'", results.query);
+        results.withhold(explore);
+    }
 
-            // Check for matches
-            let match_count: usize = terms.iter().map(|t| entry.count_matches(t)).sum();
+    /// Reorder the top of a first page by the reranker's scores.
+    ///
+    /// The first-stage order is kept if the reranker fails or runs past its
+    /// budget.
+    fn rerank(&self, results: &mut SearchResults) {
+        let top_k = self.rerank_budget.top_k.min(results.results.len());
+        if self.reranker.is_noop() || results.offset > 0 || top_k < 2 {
+            return;
+        }
 
-            if match_count == 0 {
-                continue;
+        let skill_index = self.indexer.get_skill_index();
+        let candidates = results.results[..top_k]
+            .iter()
+            .map(|r| rerank_candidate(&skill_index, r))
+            .collect();
+
+        let scores = match self.score_within_budget(&results.query, candidates) {
+            Ok(scores) => scores,
+            Err(e) => {
+                warn!("Keeping first-stage order for '{}': {}", results.query, e);
+                return;
             }
+        };
 
-            // Calculate TF-IDF-like score
-            let tf = match_count as f64 / entry.word_count.max(1) as f64;
-            let score = tf * MatchType::Content.weight();
+        // A stable sort keeps the first-stage order among equal scores
+        let mut top: Vec<(SearchResult, f64)> =
+            results.results.drain(..top_k).zip(scores).collect();
+        top.sort_by(|a, b| b.1.total_cmp(&a.1));
+        let reranked = top.into_iter().map(|(mut result, score)| {
+            result.rerank_score = Some(score);
+            result
+        });
+        results.results.splice(0..0, reranked);
+        results.reranked_by = Some(self.reranker.name().to_string());
+    }
 
-            // Apply min score filter
-            if let Some(min_score) = options.min_score {
-                if score < min_score {
-                    continue;
-                }
+    /// Score candidates on a worker thread, giving up after the budget's
+    /// timeout. A reranker that times out finishes in the background and
+    /// its scores are dropped. No more than the budget's `max_workers`
+    /// threads run at once; past that the search isn't reranked.
+    fn score_within_budget(
+        &self,
+        query: &str,
+        candidates: Vec<RerankCandidate>,
+    ) -> Result<Vec<f64>, RerankError> {
+        let max_workers = self.rerank_budget.max_workers;
+        let worker = RerankWorker::acquire(&self.rerank_workers, max_workers)
+            .ok_or(RerankError::Busy(max_workers))?;
+        let (tx, rx) = mpsc::channel();
+        let reranker = Arc::clone(&self.reranker);
+        let query = query.to_string();
+
+        std::thread::Builder::new()
+            .name("skills-rerank".to_string())
+            .spawn(move || {
+                let _worker = worker;
+                let _ = tx.send(reranker.score(&query, &candidates));
+            })
+            .map_err(|e| RerankError::Failed(e.to_string()))?;
+
+        match rx.recv_timeout(self.rerank_budget.timeout) {
+            Ok(scores) => scores,
+            Err(RecvTimeoutError::Timeout) => {
+                Err(RerankError::TimedOut(self.rerank_budget.timeout))
             }
+            Err(RecvTimeoutError::Disconnected) => Err(RerankError::Failed(
+                "reranker stopped without scores".to_string(),
+            )),
+        }
+    }
 
-            // Extract snippet
-            let snippet = extract_snippet(&entry.content, &query_lower, Self::DEFAULT_SNIPPET_CONTEXT);
+    fn search_uncached(
+        &self,
+        scope: SearchScope,
+        query: &str,
+        options: &SearchOptions,
+    ) -> SearchResults {
+        matching::with_spelling(
+            query,
+            options,
+            |query| self.vocabulary().suggest(query),
+            |query| self.run_search(scope, query, options),
+        )
+    }
 
-            let mut result = SearchResult::new(entry.domain.clone(), score, MatchType::Content)
-                .with_file(entry.file.clone());
+    fn run_search(
+        &self,
+        scope: SearchScope,
+        query: &str,
+        options: &SearchOptions,
+    ) -> SearchResults {
+        let mut results = match scope {
+            SearchScope::Skills => self.run_skills(query, options),
+            SearchScope::Content => self.run_content(query, options),
+            SearchScope::All => self.run_all(query, options),
+        };
 
-            if let Some(sub) = &entry.sub_skill {
-                result = result.with_sub_skill(sub.clone());
+        // Only the returned page is summarized; sub-skill hits keep their snippet
+        for result in &mut results.results {
+            if result.sub_skill.is_none() {
+                result.summary = self.indexer.indexed_summary(&result.domain);
             }
+        }
 
-            if let Some(snippet) = snippet {
-                result = result.with_snippet(snippet);
-            }
+        results
+    }
 
-            results.push(result);
-        }
+    fn run_skills(&self, query: &str, options: &SearchOptions) -> SearchResults {
+        let version = self.indexer.version();
+        let results = self.skill_matches(query, options);
 
-        debug!(
-            "Content search '{}' found {} results",
-            query,
-            results.len()
-        );
+        debug!("Skill search '{}' found {} results", query, results.len());
 
-        SearchResults::new(query.to_string(), results, options.limit)
+        matching::paginate(query, results, options, version)
     }
 
-    /// Combined search across both skills and content.
-    pub fn search_all(&self, query: &str, options: SearchOptions) -> SearchResults {
-        let skill_results = self.search_skills(query, options.clone());
-        let content_results = self.search_content(query, options.clone());
+    fn run_content(&self, query: &str, options: &SearchOptions) -> SearchResults {
+        let version = self.indexer.version();
+        let results = self.content_matches(query, options, true);
 
-        // Merge and deduplicate results
-        let mut all_results = skill_results.results;
+        debug!("Content search '{}' found {} results", query, results.len());
 
-        for content_result in content_results.results {
-            // Check if we already have a result for this domain/sub_skill
-            let exists = all_results.iter().any(|r| {
-                r.domain == content_result.domain && r.sub_skill == content_result.sub_skill
-            });
+        matching::paginate(query, results, options, version)
+    }
 
-            if !exists {
-                all_results.push(content_result);
-            }
+    /// Search content and group the hits by skill domain.
+    ///
+    /// `options.limit` caps the number of domains; each domain keeps its
+    /// top `per_domain` file-level hits.
+    pub fn search_content_grouped(
+        &self,
+        query: &str,
+        options: SearchOptions,
+        per_domain: usize,
+    ) -> GroupedSearchResults {
+        let started = Instant::now();
+        let results = self.content_matches(query, &options, true);
+        let ranked = SearchResults::new(query.to_string(), results, None);
+        self.note_duration(SearchScope::Content, query, started, ranked.total_matches);
+
+        GroupedSearchResults::new(ranked, per_domain, options.limit)
+    }
+
+    /// Log and count a search that took longer than the slow threshold.
+    fn note_duration(&self, scope: SearchScope, query: &str, started: Instant, matches: usize) {
+        let elapsed = started.elapsed();
+        if self.slow_search.is_zero() || elapsed <= self.slow_search {
+            return;
         }
+        self.slow_searches.fetch_add(1, Ordering::Relaxed);
+        warn!(
+            "Slow search: {:?} (over {:?}) for {:?} query '{}', {} matches over {} skills",
+            elapsed,
+            self.slow_search,
+            scope,
+            query,
+            matches,
+            self.indexer.with_skill_index(|index| index.len())
+        );
+    }
 
-        SearchResults::new(query.to_string(), all_results, options.limit)
+    /// Collect all metadata matches, before pagination.
+    fn skill_matches(&self, query: &str, options: &SearchOptions) -> Vec<SearchResult> {
+        matching::skill_matches(&self.indexer.get_skill_index(), query, options)
     }
 
-    /// Match a skill against search terms.
-    fn match_skill(
+    /// Collect all content matches, before pagination.
+    fn content_matches(
         &self,
-        skill: &SkillMeta,
         query: &str,
-        terms: &[&str],
-    ) -> Option<SearchResult> {
-        let name_lower = skill.name.to_lowercase();
-        let desc_lower = skill.description.to_lowercase();
-
-        // Exact name match (highest priority)
-        if name_lower == query {
-            return Some(SearchResult::new(
-                skill.name.clone(),
-                1.0 * MatchType::Name.weight(),
-                MatchType::Name,
-            ));
+        options: &SearchOptions,
+        include_metadata: bool,
+    ) -> Vec<SearchResult> {
+        let content_index = self.indexer.get_content_index();
+        let allowed = options
+            .has_meta_filters()
+            .then(|| matching::allowed_domains(&self.indexer.get_skill_index(), options));
+
+        matching::content_matches(
+            &content_index,
+            allowed.as_ref(),
+            query,
+            options,
+            include_metadata,
+        )
+    }
+
+    fn run_all(&self, query: &str, options: &SearchOptions) -> SearchResults {
+        let version = self.indexer.version();
+        let skill_results = self.skill_matches(query, options);
+        // Metadata is already covered by the skill matches
+        let content_results = self.content_matches(query, options, false);
+
+        let all_results = matching::merge_matches(skill_results, content_results);
+        matching::paginate(query, all_results, options, version)
+    }
+
+    /// Retrieve the passages best answering a natural-language question.
+    ///
+    /// The question's keywords are matched against metadata and content
+    /// together. Metadata hits stand for the skill's main document; each
+    /// hit document contributes its best-covering section.
+    pub fn passages(&self, question: &str, limit: usize) -> Vec<Passage> {
+        self.passages_in(question, limit, &SearchOptions::default())
+    }
+
+    /// Retrieve passages like [`Self::passages`], from the skills `options`
+    /// let through, e.g. only its `domains`.
+    pub fn passages_in(
+        &self,
+        question: &str,
+        limit: usize,
+        options: &SearchOptions,
+    ) -> Vec<Passage> {
+        let terms = question_terms(question);
+        if terms.is_empty() || limit == 0 {
+            return Vec::new();
         }
+        let query = terms.join(" ");
+
+        let paths: HashMap<(String, String), std::path::PathBuf> = self
+            .indexer
+            .indexed_markdown_files(None)
+            .into_iter()
+            .map(|(domain, file, path)| ((domain, file), path))
+            .collect();
+
+        // Best score per document
+        let mut documents: HashMap<(String, String), f64> = HashMap::new();
+        for hit in self.content_matches(&query, options, true) {
+            let Some(file) = hit.file else { continue };
+            let file = if file == ContentIndexEntry::METADATA_FILE {
+                let flat = format!("{}.md", hit.domain);
+                if paths.contains_key(&(hit.domain.clone(), flat.clone())) {
+                    flat
+                } else {
+                    "SKILL.md".to_string()
+                }
+            } else {
+                file
+            };
 
-        // Name contains query
-        if name_lower.contains(query) {
-            return Some(SearchResult::new(
-                skill.name.clone(),
-                0.8 * MatchType::Name.weight(),
-                MatchType::Name,
-            ));
+            let score = documents.entry((hit.domain, file)).or_default();
+            *score = score.max(hit.score);
         }
 
-        // Check tags first (before triggers, since all_triggers includes tags)
-        let tags: Vec<String> = skill.tags.iter().map(|s| s.to_lowercase()).collect();
-        for tag in &tags {
-            if tag == query || tag.contains(query) {
-                return Some(SearchResult::new(
-                    skill.name.clone(),
-                    0.9 * MatchType::Tags.weight(),
-                    MatchType::Tags,
-                ));
-            }
+        let mut passages: Vec<Passage> = documents
+            .into_par_iter()
+            .filter_map(|((skill, file), score)| {
+                let path = paths.get(&(skill.clone(), file.clone()))?;
+                let text = std::fs::read_to_string(path).ok()?;
+                let body = split_frontmatter(&text)
+                    .map(|(_, body)| body)
+                    .unwrap_or(&text);
+                let (section, text, coverage) = best_section(body, &terms)?;
+
+                Some(Passage {
+                    skill,
+                    file,
+                    section,
+                    text,
+                    score: score * (0.5 + coverage),
+                })
+            })
+            .filter(|p| !p.text.is_empty())
+            .collect();
+
+        passages.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| (&a.skill, &a.file).cmp(&(&b.skill, &b.file)))
+        });
+        passages.truncate(limit);
+        passages
+    }
+
+    /// Rank the skills, or their sub-skills, best suited to a free-form task.
+    ///
+    /// Returns up to `limit` routes, one per skill, best first. Routes less
+    /// confident than `min_confidence` are dropped; if none is left, the
+    /// routing reports no confident match and suggests where to explore,
+    /// starting with the dropped routes.
+    pub fn route_task(&self, task: &str, limit: usize, min_confidence: f64) -> Routing {
+        let skill_index = self.indexer.get_skill_index();
+        let (routes, weak): (Vec<Route>, Vec<Route>) =
+            routing::route_task(&skill_index, &self.indexer.get_content_index(), task, limit)
+                .into_iter()
+                .partition(|r| r.confidence >= min_confidence);
+
+        if !routes.is_empty() {
+            return Routing {
+                routes,
+                ..Default::default()
+            };
         }
 
-        // Check sub-skill triggers (only the actual triggers, not tags)
-        if let Some(subs) = &skill.sub_skills {
-            for sub in subs {
-                for trigger in &sub.triggers {
-                    let trigger_lower = trigger.to_lowercase();
-                    if trigger_lower == query || trigger_lower.contains(query) {
-                        return Some(SearchResult::new(
-                            skill.name.clone(),
-                            0.9 * MatchType::Triggers.weight(),
-                            MatchType::Triggers,
-                        ));
-                    }
-                }
-            }
+        Routing {
+            routes,
+            no_confident_match: true,
+            suggestions: self.vocabulary().suggest(task),
+            explore: Some(exploration::explore(
+                &skill_index,
+                weak.iter().map(Route::id).collect(),
+            )),
         }
+    }
 
-        // Description match
-        let term_matches: usize = terms
-            .iter()
-            .filter(|t| desc_lower.contains(*t))
-            .count();
-
-        if term_matches > 0 {
-            let score = (term_matches as f64 / terms.len() as f64) * MatchType::Description.weight();
-            return Some(
-                SearchResult::new(skill.name.clone(), score, MatchType::Description)
-                    .with_snippet(skill.description.clone()),
-            );
+    /// Run several queries concurrently with shared options.
+    ///
+    /// Results are keyed by query; repeated queries are only run once.
+    pub fn search_batch(
+        &self,
+        queries: &[String],
+        scope: SearchScope,
+        options: &SearchOptions,
+    ) -> BTreeMap<String, SearchResults> {
+        let mut unique: Vec<&String> = queries.iter().collect();
+        unique.sort();
+        unique.dedup();
+
+        unique
+            .into_par_iter()
+            .map(|q| (q.clone(), self.search(scope, q, options.clone())))
+            .collect()
+    }
+}
+
+/// The vocabulary for the index's current version, built if needed.
+fn cached_vocabulary(indexer: &SkillIndexer, cache: &VocabularyCache) -> Arc<Vocabulary> {
+    let version = indexer.version();
+    if let Some((v, vocab)) = cache.read().as_ref() {
+        if *v == version {
+            return Arc::clone(vocab);
         }
+    }
+
+    let vocab = Arc::new(Vocabulary::build(
+        &indexer.get_skill_index(),
+        &indexer.get_content_index(),
+    ));
+    *cache.write() = Some((version, Arc::clone(&vocab)));
+    vocab
+}
 
-        None
+/// Build the search structures that are otherwise built lazily, logging
+/// progress.
+fn warm_up(indexer: &SkillIndexer, cache: &VocabularyCache) {
+    let started = Instant::now();
+    info!(
+        "Warming up search over {} skills: building spelling vocabulary",
+        indexer.get_skill_index().len()
+    );
+
+    let vocabulary = cached_vocabulary(indexer, cache);
+
+    info!(
+        "Search warm-up done in {:?} ({} vocabulary terms)",
+        started.elapsed(),
+        vocabulary.len()
+    );
+}
+
+/// Describe a result to the reranker by its skill's description, its
+/// summary and its snippet.
+fn rerank_candidate(skill_index: &SkillIndex, result: &SearchResult) -> RerankCandidate {
+    let description = skill_index
+        .find(&result.domain)
+        .map(|s| s.description.as_str());
+    let text = [
+        description,
+        result.summary.as_deref(),
+        result.snippet.as_deref(),
+    ]
+    .into_iter()
+    .flatten()
+    .filter(|t| !t.is_empty())
+    .collect::<Vec<_>>()
+    .join("\n");
+
+    RerankCandidate {
+        id: result.display_id(),
+        text,
+        score: result.score,
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::SubSkillMeta;
+    use crate::models::{
+        MatchType, MinimumShouldMatch, SkillMeta, SkillStatus, SpellingMode, SubSkillMeta,
+    };
     use std::fs;
     use tempfile::TempDir;
 
@@ -251,6 +697,7 @@ mod tests {
             tags: vec!["validation".to_string()],
             sub_skills: None,
             source: None,
+            ..Default::default()
         };
         create_test_skill(temp_dir.path(), &meta);
 
@@ -263,6 +710,10 @@ mod tests {
         assert!(!results.is_empty());
         assert_eq!(results.top().unwrap().domain, "forms");
         assert_eq!(results.top().unwrap().match_type, MatchType::Name);
+        assert_eq!(
+            results.top().unwrap().summary.as_deref(),
+            Some("Form handling patterns.")
+        );
     }
 
     #[test]
@@ -275,6 +726,7 @@ mod tests {
             tags: vec!["schema-validation".to_string(), "input".to_string()],
             sub_skills: None,
             source: None,
+            ..Default::default()
         };
         create_test_skill(temp_dir.path(), &meta);
 
@@ -301,8 +753,11 @@ mod tests {
                 name: "react".to_string(),
                 file: "react/SKILL.md".to_string(),
                 triggers: vec!["useForm".to_string(), "react-hook-form".to_string()],
+                children: Vec::new(),
+                reference: None,
             }]),
             source: None,
+            ..Default::default()
         };
         create_test_skill(temp_dir.path(), &meta);
 
@@ -316,6 +771,389 @@ mod tests {
         assert_eq!(results.top().unwrap().match_type, MatchType::Triggers);
     }
 
+    #[test]
+    fn test_search_weighted_and_negative_triggers() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let sub = |name: &str, triggers: &[&str]| SubSkillMeta {
+            name: name.to_string(),
+            file: format!("{}/SKILL.md", name),
+            triggers: triggers.iter().map(|t| t.to_string()).collect(),
+            children: Vec::new(),
+            reference: None,
+        };
+        let meta = SkillMeta {
+            name: "frontend".to_string(),
+            description: "Form handling patterns".to_string(),
+            sub_skills: Some(vec![
+                sub("react", &["form^2", "!vue"]),
+                sub("vue", &["form", "v-model"]),
+            ]),
+            ..Default::default()
+        };
+        create_test_skill(temp_dir.path(), &meta);
+
+        let indexer = Arc::new(SkillIndexer::new(temp_dir.path()));
+        indexer.reload().unwrap();
+        let service = SearchService::new(indexer);
+
+        // The heavier trigger wins an ambiguous keyword
+        let results = service.search_skills("form", SearchOptions::default());
+        let top = results.top().unwrap();
+        assert_eq!(top.match_type, MatchType::Triggers);
+        assert_eq!(top.sub_skill.as_deref(), Some("react"));
+
+        // Unless the query rules that sub-skill out
+        let results = service.search_skills("form in vue", SearchOptions::default());
+        assert_eq!(results.top().unwrap().sub_skill.as_deref(), Some("vue"));
+    }
+
+    #[test]
+    fn test_search_batch() {
+        let temp_dir = TempDir::new().unwrap();
+
+        for (name, description) in [("forms", "Form handling patterns"), ("auth", "Login flows")] {
+            let meta = SkillMeta {
+                name: name.to_string(),
+                description: description.to_string(),
+                tags: vec![],
+                sub_skills: None,
+                source: None,
+                ..Default::default()
+            };
+            create_test_skill(temp_dir.path(), &meta);
+        }
+
+        let indexer = Arc::new(SkillIndexer::new(temp_dir.path()));
+        indexer.reload().unwrap();
+
+        let service = SearchService::new(indexer);
+        let queries = vec![
+            "forms".to_string(),
+            "login".to_string(),
+            "forms".to_string(),
+            "nonexistent".to_string(),
+        ];
+        let results =
+            service.search_batch(&queries, SearchScope::Skills, &SearchOptions::default());
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results["forms"].top().unwrap().domain, "forms");
+        assert_eq!(results["login"].top().unwrap().domain, "auth");
+        assert!(results["nonexistent"].is_empty());
+    }
+
+    #[test]
+    fn test_search_pagination_with_cursor() {
+        let temp_dir = TempDir::new().unwrap();
+
+        for name in ["forms-a", "forms-b", "forms-c"] {
+            let meta = SkillMeta {
+                name: name.to_string(),
+                description: "Form handling patterns".to_string(),
+                tags: vec![],
+                sub_skills: None,
+                source: None,
+                ..Default::default()
+            };
+            create_test_skill(temp_dir.path(), &meta);
+        }
+
+        let indexer = Arc::new(SkillIndexer::new(temp_dir.path()));
+        indexer.reload().unwrap();
+
+        let service = SearchService::new(Arc::clone(&indexer));
+        let first = service.search_skills("forms", SearchOptions::with_limit(2));
+        assert_eq!(first.len(), 2);
+        assert_eq!(first.total_matches, 3);
+
+        let offset = service
+            .resolve_cursor(first.next_cursor.as_ref().unwrap())
+            .unwrap();
+        let second = service.search_skills("forms", SearchOptions::with_limit(2).offset(offset));
+        assert_eq!(second.len(), 1);
+        assert!(second.next_cursor.is_none());
+
+        let mut seen: Vec<String> = first
+            .results
+            .iter()
+            .chain(&second.results)
+            .map(|r| r.domain.clone())
+            .collect();
+        seen.sort();
+        assert_eq!(seen, ["forms-a", "forms-b", "forms-c"]);
+
+        // A reload invalidates outstanding cursors
+        indexer.reload().unwrap();
+        assert!(matches!(
+            service.resolve_cursor(first.next_cursor.as_ref().unwrap()),
+            Err(SearchError::StaleCursor)
+        ));
+        assert!(matches!(
+            service.resolve_cursor("not-a-cursor"),
+            Err(SearchError::InvalidCursor)
+        ));
+    }
+
+    #[test]
+    fn test_search_all_merges_metadata_and_content() {
+        let temp_dir = TempDir::new().unwrap();
+
+        // "forms" matches by name and in its SKILL.md; "widgets" only in content
+        let meta = SkillMeta {
+            name: "forms".to_string(),
+            description: "Form handling patterns".to_string(),
+            tags: vec![],
+            sub_skills: None,
+            source: None,
+            ..Default::default()
+        };
+        create_test_skill(temp_dir.path(), &meta);
+        let meta = SkillMeta {
+            name: "widgets".to_string(),
+            description: "Mentions forms in passing".to_string(),
+            tags: vec![],
+            sub_skills: None,
+            source: None,
+            ..Default::default()
+        };
+        create_test_skill(temp_dir.path(), &meta);
+
+        let indexer = Arc::new(SkillIndexer::new(temp_dir.path()));
+        indexer.reload().unwrap();
+
+        let service = SearchService::new(Arc::clone(&indexer));
+        let name_only = service.search_skills("forms", SearchOptions::default());
+        let name_score = name_only
+            .results
+            .iter()
+            .find(|r| r.domain == "forms")
+            .unwrap()
+            .score;
+
+        let results = service.search_all("forms", SearchOptions::default());
+        let forms: Vec<&SearchResult> = results
+            .results
+            .iter()
+            .filter(|r| r.domain == "forms")
+            .collect();
+
+        assert_eq!(forms.len(), 1);
+        assert_eq!(forms[0].match_type, MatchType::Name);
+        assert!(forms[0].match_types.contains(&MatchType::Content));
+        assert!(forms[0].score > name_score);
+        assert_eq!(results.top().unwrap().domain, "forms");
+    }
+
+    #[test]
+    fn test_min_should_match_filters_partial_matches() {
+        let temp_dir = TempDir::new().unwrap();
+
+        for (name, description) in [
+            ("forms", "React form validation patterns"),
+            ("styling", "React component styling"),
+        ] {
+            let meta = SkillMeta {
+                name: name.to_string(),
+                description: description.to_string(),
+                tags: vec![],
+                sub_skills: None,
+                source: None,
+                ..Default::default()
+            };
+            create_test_skill(temp_dir.path(), &meta);
+        }
+
+        let indexer = Arc::new(SkillIndexer::new(temp_dir.path()));
+        indexer.reload().unwrap();
+        let service = SearchService::new(indexer);
+
+        // Any term: "react" alone pulls in the styling skill
+        let loose = service.search_skills("react form validation", SearchOptions::default());
+        assert_eq!(loose.total_matches, 2);
+
+        let strict = SearchOptions::default().min_should_match(MinimumShouldMatch::All);
+        let results = service.search_skills("react form validation", strict.clone());
+        assert_eq!(results.total_matches, 1);
+        assert_eq!(results.top().unwrap().domain, "forms");
+
+        let results = service.search_content("react form validation", strict);
+        assert!(results.results.iter().all(|r| r.domain == "forms"));
+
+        let results = service.search_skills(
+            "react form validation",
+            SearchOptions::default().min_should_match(MinimumShouldMatch::Percent(75)),
+        );
+        assert_eq!(results.total_matches, 1);
+    }
+
+    #[test]
+    fn test_search_filters_by_metadata() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let official = SkillMeta {
+            name: "forms".to_string(),
+            description: "Form handling patterns".to_string(),
+            source: Some("official".to_string()),
+            status: Some(SkillStatus::Published),
+            owner: Some("frontend".to_string()),
+            ..Default::default()
+        };
+        let community = SkillMeta {
+            name: "forms-extra".to_string(),
+            description: "More form handling patterns".to_string(),
+            source: Some("community".to_string()),
+            language: Some("de".to_string()),
+            status: Some(SkillStatus::Draft),
+            ..Default::default()
+        };
+        create_test_skill(temp_dir.path(), &official);
+        create_test_skill(temp_dir.path(), &community);
+
+        let indexer = Arc::new(SkillIndexer::new(temp_dir.path()));
+        indexer.reload().unwrap();
+        let service = SearchService::new(indexer);
+
+        let results = service.search_skills("forms", SearchOptions::default().source("official"));
+        assert_eq!(results.total_matches, 1);
+        assert_eq!(results.top().unwrap().domain, "forms");
+
+        let results = service.search_content("form", SearchOptions::default().language("de"));
+        assert!(!results.is_empty());
+        assert!(results.results.iter().all(|r| r.domain == "forms-extra"));
+
+        let results = service.search_all(
+            "form",
+            SearchOptions::default().status(SkillStatus::Published),
+        );
+        assert!(results.results.iter().all(|r| r.domain == "forms"));
+
+        let results = service.search_skills("forms", SearchOptions::default().owner("nobody"));
+        assert!(results.is_empty());
+    }
+
+    #[test]
+    fn test_search_filters_by_category() {
+        let temp_dir = TempDir::new().unwrap();
+
+        for name in [
+            "forms",
+            "frontend/forms",
+            "frontend/react/forms",
+            "backend/forms",
+        ] {
+            create_test_skill(
+                temp_dir.path(),
+                &SkillMeta {
+                    name: name.to_string(),
+                    description: "Form handling patterns".to_string(),
+                    ..Default::default()
+                },
+            );
+        }
+
+        let indexer = Arc::new(SkillIndexer::new(temp_dir.path()));
+        indexer.reload().unwrap();
+        let service = SearchService::new(indexer);
+
+        let domains = |results: SearchResults| {
+            let mut domains: Vec<String> = results.results.into_iter().map(|r| r.domain).collect();
+            domains.sort();
+            domains.dedup();
+            domains
+        };
+
+        let results = service.search_skills("forms", SearchOptions::default().category("frontend"));
+        assert_eq!(
+            domains(results),
+            vec!["frontend/forms", "frontend/react/forms"]
+        );
+
+        let results = service.search_content("form", SearchOptions::default().category("backend/"));
+        assert_eq!(domains(results), vec!["backend/forms"]);
+    }
+
+    #[test]
+    fn test_content_search_ranks_metadata() {
+        let temp_dir = TempDir::new().unwrap();
+
+        // The description says exactly what the query asks for
+        let meta = SkillMeta {
+            name: "csv-import".to_string(),
+            description: "Import spreadsheet data from CSV files".to_string(),
+            tags: vec!["spreadsheet".to_string()],
+            ..Default::default()
+        };
+        create_test_skill(temp_dir.path(), &meta);
+        fs::write(
+            temp_dir.path().join("csv-import/SKILL.md"),
+            "# Usage\n\nSee the examples.",
+        )
+        .unwrap();
+
+        // A long document that mentions the terms in passing
+        let meta = SkillMeta {
+            name: "reports".to_string(),
+            description: "Reporting dashboards".to_string(),
+            ..Default::default()
+        };
+        create_test_skill(temp_dir.path(), &meta);
+        fs::write(
+            temp_dir.path().join("reports/SKILL.md"),
+            "Import data from a spreadsheet, then build reports from it.",
+        )
+        .unwrap();
+
+        let indexer = Arc::new(SkillIndexer::new(temp_dir.path()));
+        indexer.reload().unwrap();
+        let service = SearchService::new(indexer);
+
+        let results = service.search_content("import spreadsheet data", SearchOptions::default());
+        let top = results.top().unwrap();
+        assert_eq!(top.domain, "csv-import");
+        assert_eq!(top.file.as_deref(), Some(ContentIndexEntry::METADATA_FILE));
+        assert_ne!(top.match_type, MatchType::Content);
+
+        // search_all doesn't count metadata twice
+        let all = service.search_all("spreadsheet", SearchOptions::default());
+        assert!(all
+            .results
+            .iter()
+            .all(|r| r.file.as_deref() != Some(ContentIndexEntry::METADATA_FILE)));
+    }
+
+    #[test]
+    fn test_content_search_ranks_keywords() {
+        let temp_dir = TempDir::new().unwrap();
+
+        // Sparse metadata, but the document is about form validation
+        let meta = SkillMeta {
+            name: "inputs".to_string(),
+            description: "Input helpers".to_string(),
+            ..Default::default()
+        };
+        create_test_skill(temp_dir.path(), &meta);
+        fs::write(
+            temp_dir.path().join("inputs/SKILL.md"),
+            "# Form validation\n\nForm validation. Rules for form validation.",
+        )
+        .unwrap();
+
+        let indexer = Arc::new(SkillIndexer::new(temp_dir.path()));
+        indexer.reload().unwrap();
+        assert_eq!(
+            indexer.indexed_keywords("inputs"),
+            vec!["form validation", "rules"]
+        );
+
+        let service = SearchService::new(indexer);
+        let results = service.search_content("validation", SearchOptions::default());
+        let top = results.top().unwrap();
+        assert_eq!(top.domain, "inputs");
+        assert_eq!(top.match_type, MatchType::Keywords);
+        assert!(top.match_types.contains(&MatchType::Content));
+    }
+
     #[test]
     fn test_search_no_results() {
         let temp_dir = TempDir::new().unwrap();
@@ -326,6 +1164,7 @@ mod tests {
             tags: vec![],
             sub_skills: None,
             source: None,
+            ..Default::default()
         };
         create_test_skill(temp_dir.path(), &meta);
 
@@ -337,4 +1176,270 @@ mod tests {
 
         assert!(results.is_empty());
     }
+
+    #[test]
+    fn test_spelling_correction() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let meta = SkillMeta {
+            name: "forms".to_string(),
+            description: "Form validation patterns".to_string(),
+            tags: vec![],
+            sub_skills: None,
+            source: None,
+            ..Default::default()
+        };
+        create_test_skill(temp_dir.path(), &meta);
+
+        let indexer = Arc::new(SkillIndexer::new(temp_dir.path()));
+        indexer.reload().unwrap();
+        let service = SearchService::new(indexer);
+
+        // Off by default
+        let results = service.search_skills("valdiation", SearchOptions::default());
+        assert!(results.is_empty());
+        assert!(results.suggestions.is_empty());
+
+        let options = SearchOptions::default().spelling(SpellingMode::Suggest);
+        let results = service.search_skills("valdiation", options);
+        assert!(results.is_empty());
+        assert_eq!(results.suggestions, vec!["validation".to_string()]);
+        assert!(results.corrected_query.is_none());
+
+        let options = SearchOptions::default().spelling(SpellingMode::Auto);
+        let results = service.search_skills("valdiation", options);
+        assert_eq!(results.query, "valdiation");
+        assert_eq!(results.corrected_query.as_deref(), Some("validation"));
+        assert_eq!(results.top().unwrap().domain, "forms");
+
+        // Queries with results are left alone
+        let options = SearchOptions::default().spelling(SpellingMode::Auto);
+        let results = service.search_skills("forms", options);
+        assert!(results.corrected_query.is_none());
+    }
+
+    #[test]
+    fn test_passages_pick_best_section() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let meta = SkillMeta {
+            name: "forms".to_string(),
+            description: "Form validation patterns".to_string(),
+            ..Default::default()
+        };
+        create_test_skill(temp_dir.path(), &meta);
+        fs::write(
+            temp_dir.path().join("forms").join("SKILL.md"),
+            "# Forms\n\n## Setup\n\nInstall the library.\n\n\
+             ## Validation\n\nValidate each field on blur and again on submit.\n",
+        )
+        .unwrap();
+
+        let other = SkillMeta {
+            name: "charts".to_string(),
+            description: "Drawing charts".to_string(),
+            ..Default::default()
+        };
+        create_test_skill(temp_dir.path(), &other);
+
+        let indexer = Arc::new(SkillIndexer::new(temp_dir.path()));
+        indexer.reload().unwrap();
+        let service = SearchService::new(indexer);
+
+        let passages = service.passages("When should I validate a field on blur?", 5);
+        assert_eq!(passages.len(), 1);
+        assert_eq!(passages[0].skill, "forms");
+        assert_eq!(passages[0].file, "SKILL.md");
+        assert_eq!(passages[0].section.as_deref(), Some("Validation"));
+        assert!(passages[0].text.starts_with("Validate each field"));
+
+        assert!(service.passages("how do I?", 5).is_empty());
+        assert!(service.passages("validation", 0).is_empty());
+    }
+
+    #[test]
+    fn test_slow_searches_are_counted() {
+        let temp_dir = TempDir::new().unwrap();
+        let meta = SkillMeta {
+            name: "forms".to_string(),
+            description: "Form validation patterns".to_string(),
+            ..Default::default()
+        };
+        create_test_skill(temp_dir.path(), &meta);
+
+        let indexer = Arc::new(SkillIndexer::new(temp_dir.path()));
+        indexer.reload().unwrap();
+
+        let service = SearchService::new(Arc::clone(&indexer));
+        service.search_skills("forms", SearchOptions::default());
+        assert_eq!(service.slow_searches(), 0);
+
+        let service =
+            SearchService::new(Arc::clone(&indexer)).with_slow_search(Duration::from_nanos(1));
+        service.search_skills("forms", SearchOptions::default());
+        service.search_content_grouped("validation", SearchOptions::default(), 3);
+        assert_eq!(service.slow_searches(), 2);
+
+        let service = SearchService::new(indexer).with_slow_search(Duration::ZERO);
+        service.search_skills("forms", SearchOptions::default());
+        assert_eq!(service.slow_searches(), 0);
+    }
+
+    #[test]
+    fn test_zero_result_queries_are_cached() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let meta = SkillMeta {
+            name: "forms".to_string(),
+            description: "Form validation patterns".to_string(),
+            ..Default::default()
+        };
+        create_test_skill(temp_dir.path(), &meta);
+
+        let indexer = Arc::new(SkillIndexer::new(temp_dir.path()));
+        indexer.reload().unwrap();
+        let service = SearchService::new(Arc::clone(&indexer));
+
+        let first = service.search_skills("valdiation", SearchOptions::default());
+        assert!(first.is_empty());
+        assert!(!first.cached);
+        assert!(first.suggestions.is_empty());
+
+        let repeat = service.search_skills("Valdiation", SearchOptions::default());
+        assert!(repeat.cached);
+        assert_eq!(repeat.query, "Valdiation");
+        assert_eq!(repeat.suggestions, vec!["validation".to_string()]);
+
+        // Different options are a different query
+        let limited = service.search_skills("valdiation", SearchOptions::with_limit(1));
+        assert!(!limited.cached);
+
+        // A reload invalidates the cache
+        indexer.reload().unwrap();
+        let after_reload = service.search_skills("valdiation", SearchOptions::default());
+        assert!(!after_reload.cached);
+
+        let disabled = SearchService::new(indexer).with_zero_result_ttl(Duration::ZERO);
+        disabled.search_skills("valdiation", SearchOptions::default());
+        assert!(
+            !disabled
+                .search_skills("valdiation", SearchOptions::default())
+                .cached
+        );
+    }
+
+    /// Reranker that puts one skill first, optionally after a delay.
+    struct Prefer {
+        id: String,
+        delay: Duration,
+    }
+
+    impl Reranker for Prefer {
+        fn name(&self) -> &str {
+            "prefer"
+        }
+
+        fn score(
+            &self,
+            _query: &str,
+            candidates: &[RerankCandidate],
+        ) -> Result<Vec<f64>, RerankError> {
+            std::thread::sleep(self.delay);
+            Ok(candidates
+                .iter()
+                .map(|c| if c.id == self.id { 1.0 } else { 0.0 })
+                .collect())
+        }
+    }
+
+    #[test]
+    fn test_reranker_reorders_top_results_within_budget() {
+        let temp_dir = TempDir::new().unwrap();
+        for (name, description) in [
+            ("alpha", "Form handling"),
+            ("beta", "Form validation rules"),
+        ] {
+            let meta = SkillMeta {
+                name: name.to_string(),
+                description: description.to_string(),
+                ..Default::default()
+            };
+            create_test_skill(temp_dir.path(), &meta);
+        }
+        let indexer = Arc::new(SkillIndexer::new(temp_dir.path()));
+        indexer.reload().unwrap();
+
+        let first_stage = SearchService::new(Arc::clone(&indexer))
+            .search_skills("form", SearchOptions::default());
+        assert_eq!(first_stage.len(), 2);
+        assert!(first_stage.reranked_by.is_none());
+        let runner_up = first_stage.results[1].domain.clone();
+
+        let prefer = |delay: Duration, timeout: Duration| {
+            SearchService::new(Arc::clone(&indexer)).with_reranker(
+                Arc::new(Prefer {
+                    id: runner_up.clone(),
+                    delay,
+                }),
+                RerankBudget {
+                    top_k: 5,
+                    timeout,
+                    ..Default::default()
+                },
+            )
+        };
+
+        let reranked = prefer(Duration::ZERO, Duration::from_secs(5))
+            .search_skills("form", SearchOptions::default());
+        assert_eq!(reranked.results[0].domain, runner_up);
+        assert_eq!(reranked.results[0].rerank_score, Some(1.0));
+        assert_eq!(reranked.reranked_by.as_deref(), Some("prefer"));
+
+        // A reranker past its budget leaves the first-stage order
+        let slow = prefer(Duration::from_millis(500), Duration::from_millis(20))
+            .search_skills("form", SearchOptions::default());
+        assert_eq!(slow.results[1].domain, runner_up);
+        assert!(slow.results[0].rerank_score.is_none());
+        assert!(slow.reranked_by.is_none());
+    }
+
+    #[test]
+    fn test_rerank_workers_are_bounded() {
+        let temp_dir = TempDir::new().unwrap();
+        let indexer = Arc::new(SkillIndexer::new(temp_dir.path()));
+        let service = SearchService::new(indexer).with_reranker(
+            Arc::new(Prefer {
+                id: "alpha".to_string(),
+                delay: Duration::from_millis(300),
+            }),
+            RerankBudget {
+                top_k: 5,
+                timeout: Duration::from_millis(20),
+                max_workers: 1,
+            },
+        );
+        let candidates = || {
+            vec![RerankCandidate {
+                id: "alpha".to_string(),
+                text: String::new(),
+                score: 1.0,
+            }]
+        };
+
+        // The timed-out call keeps its worker until it finishes
+        assert!(matches!(
+            service.score_within_budget("form", candidates()),
+            Err(RerankError::TimedOut(_))
+        ));
+        assert!(matches!(
+            service.score_within_budget("form", candidates()),
+            Err(RerankError::Busy(1))
+        ));
+
+        std::thread::sleep(Duration::from_millis(500));
+        assert!(matches!(
+            service.score_within_budget("form", candidates()),
+            Err(RerankError::TimedOut(_))
+        ));
+    }
 }
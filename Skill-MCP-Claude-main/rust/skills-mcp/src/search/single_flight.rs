@@ -0,0 +1,187 @@
+//! Sharing one computation among concurrent identical calls.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use parking_lot::{Condvar, Mutex};
+
+/// Where an in-flight call is.
+#[derive(Debug)]
+enum Flight<T> {
+    Running,
+    Done(T),
+    /// The leading caller panicked; waiters compute for themselves.
+    Abandoned,
+}
+
+#[derive(Debug)]
+struct Call<T> {
+    state: Mutex<Flight<T>>,
+    done: Condvar,
+}
+
+/// Runs one computation per key at a time; callers arriving while it runs
+/// wait and share its result instead of repeating the work.
+///
+/// Nothing is kept once a call finishes, so results are only shared among
+/// callers that overlap. Put the index version in the key when results
+/// depend on it.
+#[derive(Debug)]
+pub struct SingleFlight<T> {
+    calls: Mutex<HashMap<String, Arc<Call<T>>>>,
+}
+
+impl<T: Clone> SingleFlight<T> {
+    /// Create an empty group.
+    pub fn new() -> Self {
+        Self {
+            calls: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Run `compute` for `key`, or wait for the call already running for
+    /// it. Returns the result and whether it was shared from another call.
+    pub fn run(&self, key: &str, compute: impl FnOnce() -> T) -> (T, bool) {
+        let (call, leader) = {
+            let mut calls = self.calls.lock();
+            match calls.get(key) {
+                Some(call) => (Arc::clone(call), false),
+                None => {
+                    let call = Arc::new(Call {
+                        state: Mutex::new(Flight::Running),
+                        done: Condvar::new(),
+                    });
+                    calls.insert(key.to_string(), Arc::clone(&call));
+                    (call, true)
+                }
+            }
+        };
+
+        if !leader {
+            let mut state = call.state.lock();
+            while matches!(*state, Flight::Running) {
+                call.done.wait(&mut state);
+            }
+            if let Flight::Done(result) = &*state {
+                return (result.clone(), true);
+            }
+            drop(state);
+            return (compute(), false);
+        }
+
+        // Waiters are released even if `compute` panics
+        let landing = Landing {
+            group: self,
+            key,
+            call: &call,
+        };
+        let result = compute();
+        *call.state.lock() = Flight::Done(result.clone());
+        drop(landing);
+        (result, false)
+    }
+
+    /// Number of calls in flight.
+    pub fn len(&self) -> usize {
+        self.calls.lock().len()
+    }
+
+    /// Check whether no call is in flight.
+    pub fn is_empty(&self) -> bool {
+        self.calls.lock().is_empty()
+    }
+}
+
+impl<T: Clone> Default for SingleFlight<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Ends a leading call: removes it from the group and wakes its waiters.
+struct Landing<'a, T> {
+    group: &'a SingleFlight<T>,
+    key: &'a str,
+    call: &'a Call<T>,
+}
+
+impl<T> Drop for Landing<'_, T> {
+    fn drop(&mut self) {
+        self.group.calls.lock().remove(self.key);
+
+        let mut state = self.call.state.lock();
+        if matches!(*state, Flight::Running) {
+            *state = Flight::Abandoned;
+        }
+        self.call.done.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Barrier;
+    use std::time::Duration;
+
+    #[test]
+    fn test_concurrent_calls_share_one_computation() {
+        let group = Arc::new(SingleFlight::new());
+        let computed = Arc::new(AtomicUsize::new(0));
+        let barrier = Arc::new(Barrier::new(8));
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let group = Arc::clone(&group);
+                let computed = Arc::clone(&computed);
+                let barrier = Arc::clone(&barrier);
+                std::thread::spawn(move || {
+                    barrier.wait();
+                    group.run("forms", || {
+                        computed.fetch_add(1, Ordering::SeqCst);
+                        std::thread::sleep(Duration::from_millis(50));
+                        42
+                    })
+                })
+            })
+            .collect();
+        let results: Vec<(i32, bool)> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+
+        assert!(results.iter().all(|(value, _)| *value == 42));
+        let shared = results.iter().filter(|(_, shared)| *shared).count();
+        assert_eq!(computed.load(Ordering::SeqCst) + shared, 8);
+        assert!(computed.load(Ordering::SeqCst) < 8);
+        assert!(group.is_empty());
+    }
+
+    #[test]
+    fn test_finished_calls_are_not_kept() {
+        let group = SingleFlight::new();
+        assert_eq!(group.run("forms", || 1), (1, false));
+        assert_eq!(group.run("forms", || 2), (2, false));
+        assert_eq!(group.run("react", || 3), (3, false));
+    }
+
+    #[test]
+    fn test_waiters_recover_from_panicking_leader() {
+        let group = Arc::new(SingleFlight::new());
+        let started = Arc::new(Barrier::new(2));
+
+        let leader = {
+            let group = Arc::clone(&group);
+            let started = Arc::clone(&started);
+            std::thread::spawn(move || {
+                group.run("forms", || {
+                    started.wait();
+                    std::thread::sleep(Duration::from_millis(50));
+                    panic!("search failed");
+                })
+            })
+        };
+
+        started.wait();
+        assert_eq!(group.run("forms", || 7), (7, false));
+        assert!(leader.join().is_err());
+        assert!(group.is_empty());
+    }
+}
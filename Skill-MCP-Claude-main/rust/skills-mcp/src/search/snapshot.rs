@@ -0,0 +1,282 @@
+//! Search over a serialized index.
+//!
+//! # Encoding
+//!
+//! [`IndexSnapshot::to_bytes`] writes a fixed header followed by the
+//! snapshot as JSON:
+//!
+//! ```text
+//! offset  size  field
+//! 0       4     magic, "SKIX"
+//! 4       2     format version, little-endian u16
+//! 6       ..    UTF-8 JSON body
+//! ```
+//!
+//! Collections are written in a fixed order, so equal snapshots encode to
+//! equal bytes. Readers reject unknown magic and versions newer than
+//! [`SNAPSHOT_FORMAT_VERSION`].
+
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::{
+    ContentIndex, SearchOptions, SearchResult, SearchResults, SearchScope, SkillIndex,
+};
+
+use super::{matching, Vocabulary};
+
+/// First bytes of an encoded snapshot.
+pub const SNAPSHOT_MAGIC: [u8; 4] = *b"SKIX";
+
+/// Format version written by [`IndexSnapshot::to_bytes`]; this and older
+/// versions can be read.
+pub const SNAPSHOT_FORMAT_VERSION: u16 = 1;
+
+const HEADER_LEN: usize = SNAPSHOT_MAGIC.len() + 2;
+
+/// Errors decoding an encoded snapshot.
+#[derive(Debug, thiserror::Error)]
+pub enum SnapshotError {
+    /// The data doesn't start with [`SNAPSHOT_MAGIC`].
+    #[error("Not an index snapshot")]
+    NotASnapshot,
+
+    /// The snapshot was written by a newer (or unknown) format version.
+    #[error("Unsupported index snapshot format version {0} (expected at most {SNAPSHOT_FORMAT_VERSION})")]
+    UnsupportedVersion(u16),
+
+    /// The body doesn't decode.
+    #[error("Invalid index snapshot: {0}")]
+    Invalid(String),
+}
+
+/// A copy of the skill and content indexes that searches without an
+/// indexer or filesystem.
+///
+/// Served at `GET /api/search/index` so clients such as the web editor can
+/// search locally, and wrapped for JavaScript by the `wasm` feature.
+/// Results rank the same as [`SearchService`](super::SearchService)'s,
+/// without zero-result caching.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexSnapshot {
+    /// Index version the snapshot was taken at.
+    pub version: u64,
+
+    /// Skill metadata.
+    pub skill_index: SkillIndex,
+
+    /// Searchable content.
+    pub content_index: ContentIndex,
+
+    /// Spelling vocabulary, built on first use.
+    #[serde(skip)]
+    vocabulary: OnceLock<Vocabulary>,
+}
+
+impl IndexSnapshot {
+    /// A snapshot of indexes at `version`.
+    pub fn new(version: u64, skill_index: SkillIndex, content_index: ContentIndex) -> Self {
+        Self {
+            version,
+            skill_index,
+            content_index,
+            vocabulary: OnceLock::new(),
+        }
+    }
+
+    /// Encode with the versioned header described in the [module
+    /// docs](self).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_LEN);
+        bytes.extend_from_slice(&SNAPSHOT_MAGIC);
+        bytes.extend_from_slice(&SNAPSHOT_FORMAT_VERSION.to_le_bytes());
+        serde_json::to_writer(&mut bytes, self).expect("snapshots serialize to JSON");
+        bytes
+    }
+
+    /// Decode bytes written by [`IndexSnapshot::to_bytes`], checking the
+    /// header first.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SnapshotError> {
+        if bytes.len() < HEADER_LEN || bytes[..SNAPSHOT_MAGIC.len()] != SNAPSHOT_MAGIC {
+            return Err(SnapshotError::NotASnapshot);
+        }
+
+        let version = u16::from_le_bytes([bytes[4], bytes[5]]);
+        if version == 0 || version > SNAPSHOT_FORMAT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion(version));
+        }
+
+        serde_json::from_slice(&bytes[HEADER_LEN..])
+            .map_err(|e| SnapshotError::Invalid(e.to_string()))
+    }
+
+    /// Vocabulary for spelling correction.
+    pub fn vocabulary(&self) -> &Vocabulary {
+        self.vocabulary
+            .get_or_init(|| Vocabulary::build(&self.skill_index, &self.content_index))
+    }
+
+    /// Search skills by metadata (name, description, tags, triggers).
+    pub fn search_skills(&self, query: &str, options: SearchOptions) -> SearchResults {
+        self.search(SearchScope::Skills, query, options)
+    }
+
+    /// Search content by full-text matching.
+    pub fn search_content(&self, query: &str, options: SearchOptions) -> SearchResults {
+        self.search(SearchScope::Content, query, options)
+    }
+
+    /// Combined search across both skills and content.
+    pub fn search_all(&self, query: &str, options: SearchOptions) -> SearchResults {
+        self.search(SearchScope::All, query, options)
+    }
+
+    /// Search a single scope, correcting spelling as `options.spelling` asks.
+    pub fn search(&self, scope: SearchScope, query: &str, options: SearchOptions) -> SearchResults {
+        matching::with_spelling(
+            query,
+            &options,
+            |query| self.vocabulary().suggest(query),
+            |query| self.run_search(scope, query, &options),
+        )
+    }
+
+    fn run_search(
+        &self,
+        scope: SearchScope,
+        query: &str,
+        options: &SearchOptions,
+    ) -> SearchResults {
+        let results = match scope {
+            SearchScope::Skills => matching::skill_matches(&self.skill_index, query, options),
+            SearchScope::Content => self.content_matches(query, options, true),
+            SearchScope::All => matching::merge_matches(
+                matching::skill_matches(&self.skill_index, query, options),
+                // Metadata is already covered by the skill matches
+                self.content_matches(query, options, false),
+            ),
+        };
+
+        let mut results = matching::paginate(query, results, options, self.version);
+        for result in &mut results.results {
+            if result.sub_skill.is_none() {
+                result.summary = self
+                    .content_index
+                    .get(&result.domain)
+                    .and_then(|entry| entry.summary.clone());
+            }
+        }
+
+        results
+    }
+
+    fn content_matches(
+        &self,
+        query: &str,
+        options: &SearchOptions,
+        include_metadata: bool,
+    ) -> Vec<SearchResult> {
+        let allowed = options
+            .has_meta_filters()
+            .then(|| matching::allowed_domains(&self.skill_index, options));
+
+        matching::content_matches(
+            &self.content_index,
+            allowed.as_ref(),
+            query,
+            options,
+            include_metadata,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ContentIndexEntry, MatchType, SkillMeta, SpellingMode};
+
+    fn snapshot() -> IndexSnapshot {
+        let meta = SkillMeta {
+            name: "forms".to_string(),
+            description: "Form handling patterns".to_string(),
+            tags: vec!["validation".to_string()],
+            ..Default::default()
+        };
+        let mut content = ContentIndex::new();
+        content.insert(
+            ContentIndexEntry::new(
+                "forms".to_string(),
+                None,
+                "SKILL.md".to_string(),
+                "# Forms\n\nUse a schema to validate submissions.".to_string(),
+            )
+            .with_summary(Some("Form handling.".to_string())),
+        );
+
+        IndexSnapshot::new(7, SkillIndex::with_skills(vec![meta], Vec::new()), content)
+    }
+
+    #[test]
+    fn test_snapshot_search() {
+        // Snapshots travel as JSON
+        let json = serde_json::to_string(&snapshot()).unwrap();
+        let snapshot: IndexSnapshot = serde_json::from_str(&json).unwrap();
+
+        let results = snapshot.search_skills("forms", SearchOptions::default());
+        let top = results.top().unwrap();
+        assert_eq!(top.match_type, MatchType::Name);
+        assert_eq!(top.summary.as_deref(), Some("Form handling."));
+
+        let results = snapshot.search_content("schema", SearchOptions::default());
+        assert!(results
+            .top()
+            .unwrap()
+            .snippet
+            .as_deref()
+            .unwrap()
+            .contains("schema"));
+
+        let options = SearchOptions::default().spelling(SpellingMode::Auto);
+        let results = snapshot.search_all("shema", options);
+        assert_eq!(results.corrected_query.as_deref(), Some("schema"));
+        assert_eq!(results.top().unwrap().domain, "forms");
+    }
+
+    #[test]
+    fn test_snapshot_encoding() {
+        let mut snapshot = snapshot();
+        for name in ["charts", "auth", "tables"] {
+            snapshot.content_index.insert(ContentIndexEntry::new(
+                name.to_string(),
+                None,
+                "SKILL.md".to_string(),
+                format!("# {}", name),
+            ));
+        }
+
+        let bytes = snapshot.to_bytes();
+        assert_eq!(&bytes[..4], b"SKIX");
+        assert_eq!(bytes[4..6], SNAPSHOT_FORMAT_VERSION.to_le_bytes());
+
+        // Entry order doesn't depend on hashing
+        let decoded = IndexSnapshot::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.content_index.len(), 4);
+        assert_eq!(decoded.to_bytes(), bytes);
+
+        assert!(matches!(
+            IndexSnapshot::from_bytes(b"{}"),
+            Err(SnapshotError::NotASnapshot)
+        ));
+        let mut newer = bytes.clone();
+        newer[4..6].copy_from_slice(&(SNAPSHOT_FORMAT_VERSION + 1).to_le_bytes());
+        assert!(matches!(
+            IndexSnapshot::from_bytes(&newer),
+            Err(SnapshotError::UnsupportedVersion(v)) if v == SNAPSHOT_FORMAT_VERSION + 1
+        ));
+        assert!(matches!(
+            IndexSnapshot::from_bytes(&bytes[..10]),
+            Err(SnapshotError::Invalid(_))
+        ));
+    }
+}
@@ -0,0 +1,233 @@
+//! Query spelling correction against the index vocabulary.
+
+use std::collections::HashMap;
+
+use crate::models::{ContentIndex, SkillIndex};
+
+/// Shortest word considered for the vocabulary and for correction.
+const MIN_WORD_LEN: usize = 3;
+
+/// Number of alternative queries offered as suggestions.
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Word frequencies across skill metadata and content.
+#[derive(Debug, Clone, Default)]
+pub struct Vocabulary {
+    words: HashMap<String, usize>,
+}
+
+impl Vocabulary {
+    /// Build the vocabulary from the current indexes.
+    pub fn build(skill_index: &SkillIndex, content_index: &ContentIndex) -> Self {
+        let mut vocab = Self::default();
+
+        for skill in &skill_index.skills {
+            vocab.add_text(&skill.name);
+            vocab.add_text(&skill.description);
+            for trigger in skill.all_triggers() {
+                vocab.add_text(trigger);
+            }
+        }
+
+        for (_, entry) in content_index.iter() {
+            vocab.add_text(&entry.content);
+        }
+
+        vocab
+    }
+
+    fn add_text(&mut self, text: &str) {
+        for word in words(text) {
+            *self.words.entry(word).or_insert(0) += 1;
+        }
+    }
+
+    /// Number of distinct words.
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Approximate heap bytes held: each word and its count.
+    pub fn approximate_bytes(&self) -> u64 {
+        self.words
+            .keys()
+            .map(|word| (word.len() + std::mem::size_of::<(String, usize)>()) as u64)
+            .sum()
+    }
+
+    /// Check whether the vocabulary is empty.
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Check whether a word occurs in the index.
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.contains_key(&word.to_lowercase())
+    }
+
+    /// Closest known words to `term`, best first.
+    ///
+    /// Candidates are within an edit distance that grows with the term
+    /// length (1 for short terms, 2 from eight characters). Ties prefer
+    /// more frequent words.
+    pub fn candidates(&self, term: &str, limit: usize) -> Vec<&str> {
+        let term = term.to_lowercase();
+        let max_distance = if term.chars().count() >= 8 { 2 } else { 1 };
+
+        let mut scored: Vec<(usize, usize, &str)> = self
+            .words
+            .iter()
+            .filter(|(w, _)| w.len().abs_diff(term.len()) <= max_distance)
+            .filter_map(|(w, &freq)| {
+                let d = levenshtein(&term, w);
+                (d > 0 && d <= max_distance).then_some((d, freq, w.as_str()))
+            })
+            .collect();
+
+        scored.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)).then(a.2.cmp(b.2)));
+        scored.into_iter().take(limit).map(|(_, _, w)| w).collect()
+    }
+
+    /// Correct every unknown term of a query.
+    ///
+    /// Returns `None` when every term is known or no correction was found.
+    pub fn correct_query(&self, query: &str) -> Option<String> {
+        self.suggest(query).into_iter().next()
+    }
+
+    /// Alternative spellings of a query, best first.
+    ///
+    /// The first suggestion corrects every unknown term; further ones vary
+    /// the first corrected term using its next-best candidates.
+    pub fn suggest(&self, query: &str) -> Vec<String> {
+        let terms: Vec<String> = query.split_whitespace().map(|t| t.to_lowercase()).collect();
+
+        let mut options: Vec<Vec<&str>> = Vec::with_capacity(terms.len());
+        let mut first_corrected = None;
+
+        for (i, term) in terms.iter().enumerate() {
+            let correctable = term.chars().count() >= MIN_WORD_LEN
+                && !term.chars().all(|c| c.is_ascii_digit())
+                && !self.contains(term);
+
+            let candidates = if correctable {
+                self.candidates(term, MAX_SUGGESTIONS)
+            } else {
+                Vec::new()
+            };
+
+            if candidates.is_empty() {
+                options.push(vec![term.as_str()]);
+            } else {
+                first_corrected.get_or_insert(i);
+                options.push(candidates);
+            }
+        }
+
+        let Some(varied) = first_corrected else {
+            return Vec::new();
+        };
+
+        options[varied]
+            .iter()
+            .map(|alt| {
+                options
+                    .iter()
+                    .enumerate()
+                    .map(|(i, o)| if i == varied { *alt } else { o[0] })
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect()
+    }
+}
+
+/// Split text into lowercase words eligible for the vocabulary.
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_'))
+        .map(|w| w.trim_matches(|c| c == '-' || c == '_'))
+        .filter(|w| w.chars().count() >= MIN_WORD_LEN && !w.chars().all(|c| c.is_ascii_digit()))
+        .map(|w| w.to_lowercase())
+}
+
+/// Levenshtein edit distance between two strings.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ContentIndexEntry, SkillMeta};
+
+    fn vocab() -> Vocabulary {
+        let skills = SkillIndex::with_skills(
+            vec![SkillMeta {
+                name: "forms".to_string(),
+                description: "Form validation patterns".to_string(),
+                ..Default::default()
+            }],
+            vec![],
+        );
+        let mut content = ContentIndex::new();
+        content.insert(ContentIndexEntry::new(
+            "forms".to_string(),
+            None,
+            "SKILL.md".to_string(),
+            "Use react-hook-form for validation. Validation errors render inline.".to_string(),
+        ));
+
+        Vocabulary::build(&skills, &content)
+    }
+
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("forms", "forms"), 0);
+        assert_eq!(levenshtein("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_correct_query() {
+        let vocab = vocab();
+
+        assert!(vocab.contains("validation"));
+        assert!(vocab.contains("react-hook-form"));
+        assert_eq!(
+            vocab.correct_query("valdiation"),
+            Some("validation".to_string())
+        );
+        assert_eq!(
+            vocab.correct_query("react frms"),
+            Some("react forms".to_string())
+        );
+        assert_eq!(vocab.correct_query("forms validation"), None);
+        assert_eq!(vocab.correct_query("zzzzzz"), None);
+    }
+
+    #[test]
+    fn test_suggestions_vary_first_correction() {
+        let vocab = vocab();
+
+        let suggestions = vocab.suggest("form");
+        assert!(suggestions.is_empty(), "known words are not corrected");
+
+        let suggestions = vocab.suggest("formz validaton");
+        assert_eq!(suggestions.len(), 2);
+        assert!(suggestions.contains(&"forms validation".to_string()));
+        assert!(suggestions.contains(&"form validation".to_string()));
+    }
+}
@@ -0,0 +1,155 @@
+//! Mining trigger words from search history.
+//!
+//! Searches that led to loading a skill show how people actually ask for
+//! it. Words that keep appearing in those queries but aren't among the
+//! skill's name, tags or triggers are good candidates for new triggers.
+
+use std::collections::{BTreeMap, HashSet};
+
+use serde::Serialize;
+
+use crate::models::{SkillMeta, UsageStats};
+
+use super::question_terms;
+
+/// Fewest searches a word must appear in to be suggested.
+pub const DEFAULT_MIN_SEARCHES: usize = 2;
+
+/// Example queries kept per suggestion.
+const MAX_EXAMPLES: usize = 3;
+
+/// A word worth adding to a skill's triggers.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TriggerSuggestion {
+    /// Suggested trigger word.
+    pub term: String,
+    /// Searches leading to the skill that contained the word.
+    pub searches: usize,
+    /// Share of the skill's searches that contained the word.
+    pub share: f64,
+    /// A few of those queries.
+    pub examples: Vec<String>,
+}
+
+/// Trigger suggestions for one skill.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SuggestedTriggers {
+    /// Skill the suggestions are for.
+    pub skill: String,
+    /// Retained searches that led to loading the skill.
+    pub searches: usize,
+    /// Suggested words, most frequent first.
+    pub suggestions: Vec<TriggerSuggestion>,
+}
+
+/// Suggest trigger words for `meta` from searches that led to loading it.
+///
+/// Words in at least `min_searches` such searches that the name, tags and
+/// triggers don't already cover are returned, most frequent first. A word
+/// counts as covered when it shares a stem with a known word, so `forms`
+/// isn't suggested for a skill tagged `form`.
+pub fn suggest_triggers(
+    stats: &UsageStats,
+    meta: &SkillMeta,
+    min_searches: usize,
+) -> SuggestedTriggers {
+    let mut known: HashSet<String> = question_terms(&meta.name.replace(['-', '_'], " "))
+        .into_iter()
+        .collect();
+    for phrase in meta.all_triggers() {
+        known.extend(question_terms(phrase));
+        known.extend(question_terms(&phrase.replace(['-', '_'], " ")));
+    }
+
+    let mut found: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+    let mut total = 0;
+    for search in stats.searches_leading_to(&meta.name) {
+        total += 1;
+        for term in question_terms(&search.query) {
+            if !is_known(&term, &known) {
+                found.entry(term).or_default().push(&search.query);
+            }
+        }
+    }
+
+    let mut suggestions: Vec<TriggerSuggestion> = found
+        .into_iter()
+        .filter(|(_, queries)| queries.len() >= min_searches.max(1))
+        .map(|(term, queries)| {
+            let mut examples: Vec<String> = Vec::new();
+            for query in &queries {
+                if examples.len() < MAX_EXAMPLES && !examples.iter().any(|e| e == query) {
+                    examples.push(query.to_string());
+                }
+            }
+            TriggerSuggestion {
+                term,
+                searches: queries.len(),
+                share: queries.len() as f64 / total as f64,
+                examples,
+            }
+        })
+        .collect();
+    suggestions.sort_by(|a, b| b.searches.cmp(&a.searches).then(a.term.cmp(&b.term)));
+
+    SuggestedTriggers {
+        skill: meta.name.clone(),
+        searches: total,
+        suggestions,
+    }
+}
+
+/// Whether a word matches a known word or shares a stem of four or more
+/// letters with it.
+fn is_known(term: &str, known: &HashSet<String>) -> bool {
+    known.iter().any(|word| {
+        let (short, long) = if word.len() <= term.len() {
+            (word.as_str(), term)
+        } else {
+            (term, word.as_str())
+        };
+        short == long || (short.len() >= 4 && long.starts_with(short))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suggest_triggers() {
+        let meta = SkillMeta {
+            name: "form-builder".to_string(),
+            tags: vec!["validation".to_string()],
+            ..Default::default()
+        };
+
+        let mut stats = UsageStats::new();
+        for query in [
+            "signup forms wizard",
+            "multi step wizard",
+            "wizard validation",
+            "checkout wizard",
+            "signup page",
+        ] {
+            stats.record_search(query.to_string(), 1);
+            stats.record_skill_load("form-builder");
+        }
+        stats.record_search("wizard charts".to_string(), 1);
+        stats.record_skill_load("charts");
+
+        let mined = suggest_triggers(&stats, &meta, DEFAULT_MIN_SEARCHES);
+        assert_eq!(mined.searches, 5);
+        let suggestions = mined.suggestions;
+
+        let terms: Vec<&str> = suggestions.iter().map(|s| s.term.as_str()).collect();
+        assert_eq!(terms, vec!["wizard", "signup"]);
+        assert_eq!(suggestions[0].searches, 4);
+        assert_eq!(suggestions[0].share, 0.8);
+        assert_eq!(suggestions[0].examples.len(), MAX_EXAMPLES);
+        assert_eq!(
+            suggestions[1].examples,
+            vec!["signup forms wizard", "signup page"]
+        );
+    }
+}
@@ -0,0 +1,161 @@
+//! Short-lived cache of queries that returned no results.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+
+use crate::models::SearchResults;
+
+/// A cached empty result.
+#[derive(Debug, Clone)]
+struct Entry {
+    results: SearchResults,
+    version: u64,
+    stored_at: Instant,
+    hits: usize,
+}
+
+/// Remembers recent zero-result queries so repeats skip the index scan.
+///
+/// Entries expire after the TTL or as soon as the index version changes,
+/// since a reload may add the skill the query was looking for.
+#[derive(Debug)]
+pub struct ZeroResultCache {
+    ttl: Duration,
+    capacity: usize,
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+impl ZeroResultCache {
+    /// Default time a zero-result query stays cached.
+    pub const DEFAULT_TTL: Duration = Duration::from_secs(30);
+
+    /// Default maximum number of cached queries.
+    pub const DEFAULT_CAPACITY: usize = 256;
+
+    /// Create a cache. A zero TTL disables caching.
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        Self {
+            ttl,
+            capacity,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Look up a cached empty result for `key` at the given index version.
+    pub fn get(&self, key: &str, version: u64) -> Option<SearchResults> {
+        let mut entries = self.entries.lock();
+        let entry = entries.get_mut(key)?;
+
+        if entry.version != version || entry.stored_at.elapsed() >= self.ttl {
+            entries.remove(key);
+            return None;
+        }
+
+        entry.hits += 1;
+        Some(entry.results.clone())
+    }
+
+    /// Remember an empty result.
+    pub fn insert(&self, key: String, version: u64, results: SearchResults) {
+        if self.ttl.is_zero() || self.capacity == 0 {
+            return;
+        }
+
+        let mut entries = self.entries.lock();
+        if entries.len() >= self.capacity && !entries.contains_key(&key) {
+            let ttl = self.ttl;
+            entries.retain(|_, e| e.version == version && e.stored_at.elapsed() < ttl);
+
+            if entries.len() >= self.capacity {
+                let oldest = entries
+                    .iter()
+                    .min_by_key(|(_, e)| e.stored_at)
+                    .map(|(k, _)| k.clone());
+                if let Some(oldest) = oldest {
+                    entries.remove(&oldest);
+                }
+            }
+        }
+
+        entries.insert(
+            key,
+            Entry {
+                results,
+                version,
+                stored_at: Instant::now(),
+                hits: 0,
+            },
+        );
+    }
+
+    /// Number of times a cached query has been repeated.
+    pub fn hits(&self, key: &str) -> usize {
+        self.entries.lock().get(key).map_or(0, |e| e.hits)
+    }
+
+    /// Number of cached queries, including expired ones not yet evicted.
+    pub fn len(&self) -> usize {
+        self.entries.lock().len()
+    }
+
+    /// Check whether nothing is cached.
+    pub fn is_empty(&self) -> bool {
+        self.entries.lock().is_empty()
+    }
+}
+
+impl Default for ZeroResultCache {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_TTL, Self::DEFAULT_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty(query: &str) -> SearchResults {
+        SearchResults::new(query.to_string(), vec![], None)
+    }
+
+    #[test]
+    fn test_hit_until_version_changes() {
+        let cache = ZeroResultCache::default();
+        cache.insert("q".to_string(), 1, empty("q"));
+
+        assert!(cache.get("q", 1).is_some());
+        assert!(cache.get("q", 1).is_some());
+        assert_eq!(cache.hits("q"), 2);
+
+        assert!(cache.get("q", 2).is_none());
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_expiry_and_disabled() {
+        let cache = ZeroResultCache::new(Duration::from_millis(1), 8);
+        cache.insert("q".to_string(), 1, empty("q"));
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(cache.get("q", 1).is_none());
+
+        let cache = ZeroResultCache::new(Duration::ZERO, 8);
+        cache.insert("q".to_string(), 1, empty("q"));
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_capacity_evicts_oldest() {
+        let cache = ZeroResultCache::new(Duration::from_secs(60), 2);
+        cache.insert("a".to_string(), 1, empty("a"));
+        std::thread::sleep(Duration::from_millis(1));
+        cache.insert("b".to_string(), 1, empty("b"));
+        std::thread::sleep(Duration::from_millis(1));
+        cache.insert("c".to_string(), 1, empty("c"));
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get("a", 1).is_none());
+        assert!(cache.get("c", 1).is_some());
+    }
+}
@@ -0,0 +1,145 @@
+//! Synthetic skills for tests and ephemeral indexes.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::models::{SkillMeta, SubSkillMeta};
+
+/// A skill described in code, to be written to a store.
+///
+/// ```rust
+/// use skills_mcp::index::SkillIndexer;
+/// use skills_mcp::store::SkillFixture;
+///
+/// let indexer = SkillIndexer::from_memory(vec![
+///     SkillFixture::new("forms", "Form handling")
+///         .with_tags(["frontend"])
+///         .with_sub_skill("validation", "# Validation\n\nCheck inputs."),
+/// ])
+/// .unwrap();
+/// assert!(indexer.get_skill_meta("forms").is_some());
+/// ```
+#[derive(Debug, Clone)]
+pub struct SkillFixture {
+    meta: SkillMeta,
+    content: String,
+    files: Vec<(String, String)>,
+}
+
+impl SkillFixture {
+    /// A skill whose SKILL.md is a heading and the description.
+    pub fn new(name: impl Into<String>, description: impl Into<String>) -> Self {
+        let meta = SkillMeta {
+            name: name.into(),
+            description: description.into(),
+            ..Default::default()
+        };
+        let content = format!("# {}\n\n{}\n", meta.name, meta.description);
+        Self {
+            meta,
+            content,
+            files: Vec::new(),
+        }
+    }
+
+    /// Set the tags.
+    pub fn with_tags(mut self, tags: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.meta.tags = tags.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Replace the SKILL.md content.
+    pub fn with_content(mut self, content: impl Into<String>) -> Self {
+        self.content = content.into();
+        self
+    }
+
+    /// Add a sub-skill stored at `references/{name}.md`.
+    pub fn with_sub_skill(mut self, name: impl Into<String>, content: impl Into<String>) -> Self {
+        let name = name.into();
+        let file = format!("references/{}.md", name);
+        self.meta
+            .sub_skills
+            .get_or_insert_with(Vec::new)
+            .push(SubSkillMeta {
+                name,
+                file: file.clone(),
+                triggers: Vec::new(),
+                children: Vec::new(),
+                reference: None,
+            });
+        self.files.push((file, content.into()));
+        self
+    }
+
+    /// Add another file, such as a script, at `path` inside the skill.
+    pub fn with_file(mut self, path: impl Into<String>, content: impl Into<String>) -> Self {
+        self.files.push((path.into(), content.into()));
+        self
+    }
+
+    /// Replace the metadata, keeping the files.
+    pub fn with_meta(mut self, meta: SkillMeta) -> Self {
+        self.meta = meta;
+        self
+    }
+
+    /// The skill's metadata.
+    pub fn meta(&self) -> &SkillMeta {
+        &self.meta
+    }
+
+    /// Store keys and contents of every file in the skill, including
+    /// `_meta.json` and `SKILL.md`.
+    pub fn files(&self) -> Vec<(String, String)> {
+        let name = &self.meta.name;
+        let meta = serde_json::to_string_pretty(&self.meta).unwrap_or_default();
+
+        let mut files = vec![
+            (format!("{}/_meta.json", name), meta),
+            (format!("{}/SKILL.md", name), self.content.clone()),
+        ];
+        files.extend(
+            self.files
+                .iter()
+                .map(|(path, content)| (format!("{}/{}", name, path), content.clone())),
+        );
+        files
+    }
+}
+
+/// A temporary directory removed when dropped.
+pub(crate) struct ScratchDir(PathBuf);
+
+impl ScratchDir {
+    pub(crate) fn new() -> io::Result<Self> {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "skills-mcp-{}-{}",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        // A leftover from a crashed process with a reused id
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path)?;
+        Ok(Self(path))
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+impl std::fmt::Debug for ScratchDir {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ScratchDir").field(&self.0).finish()
+    }
+}
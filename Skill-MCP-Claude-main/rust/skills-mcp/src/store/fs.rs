@@ -0,0 +1,170 @@
+//! Skills stored in a local directory.
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+
+use notify::{RecursiveMode, Watcher};
+use tracing::warn;
+use walkdir::WalkDir;
+
+use super::{check_key, SkillStore, StoreEntry, StoreError, StoreWatch};
+
+/// A skills directory on the local filesystem.
+#[derive(Debug, Clone)]
+pub struct FsStore {
+    root: PathBuf,
+}
+
+impl FsStore {
+    /// Store files under `root`.
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    fn path(&self, key: &str) -> Result<PathBuf, StoreError> {
+        Ok(self.root.join(check_key(key)?))
+    }
+}
+
+fn io_error(path: &Path, e: std::io::Error) -> StoreError {
+    if e.kind() == ErrorKind::NotFound {
+        StoreError::NotFound(path.display().to_string())
+    } else {
+        StoreError::Io(format!("{}: {}", path.display(), e))
+    }
+}
+
+impl SkillStore for FsStore {
+    fn list(&self) -> Result<Vec<StoreEntry>, StoreError> {
+        if !self.root.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut entries = Vec::new();
+        for entry in WalkDir::new(&self.root).min_depth(1) {
+            let entry = entry.map_err(|e| StoreError::Io(e.to_string()))?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let Ok(relative) = entry.path().strip_prefix(&self.root) else {
+                continue;
+            };
+            let metadata = entry
+                .metadata()
+                .map_err(|e| StoreError::Io(e.to_string()))?;
+            let modified = metadata
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map(|since| since.as_nanos())
+                .unwrap_or_default();
+
+            entries.push(StoreEntry {
+                key: relative.to_string_lossy().replace('\\', "/"),
+                size: metadata.len(),
+                version: format!("{}-{}", modified, metadata.len()),
+            });
+        }
+        Ok(entries)
+    }
+
+    fn read(&self, key: &str) -> Result<Vec<u8>, StoreError> {
+        let path = self.path(key)?;
+        fs::read(&path).map_err(|e| io_error(&path, e))
+    }
+
+    fn write(&self, key: &str, contents: &[u8]) -> Result<(), StoreError> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| io_error(parent, e))?;
+        }
+
+        // Readers never see a half-written file
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let temp = path.with_file_name(format!(".{}.tmp", name));
+        fs::write(&temp, contents)
+            .and_then(|_| fs::rename(&temp, &path))
+            .map_err(|e| {
+                let _ = fs::remove_file(&temp);
+                io_error(&path, e)
+            })
+    }
+
+    fn remove(&self, key: &str) -> Result<(), StoreError> {
+        let path = self.path(key)?;
+        match fs::remove_file(&path) {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(io_error(&path, e)),
+            _ => Ok(()),
+        }
+    }
+
+    fn watch(&self, on_change: Arc<dyn Fn() + Send + Sync>) -> Result<StoreWatch, StoreError> {
+        let mut watcher =
+            notify::recommended_watcher(move |res: Result<notify::Event, _>| match res {
+                Ok(event) => {
+                    if matches!(
+                        event.kind,
+                        notify::EventKind::Create(_)
+                            | notify::EventKind::Modify(_)
+                            | notify::EventKind::Remove(_)
+                    ) {
+                        on_change();
+                    }
+                }
+                Err(e) => warn!("Watch error: {:?}", e),
+            })
+            .map_err(|e| StoreError::Io(format!("Failed to create watcher: {}", e)))?;
+
+        watcher
+            .watch(&self.root, RecursiveMode::Recursive)
+            .map_err(|e| StoreError::Io(format!("Failed to watch {:?}: {}", self.root, e)))?;
+
+        Ok(StoreWatch::new(move || drop(watcher)))
+    }
+
+    fn local_root(&self) -> Option<&Path> {
+        Some(&self.root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_fs_store() {
+        let temp = TempDir::new().unwrap();
+        let store = FsStore::new(temp.path());
+
+        store.write("forms/SKILL.md", b"# Forms\n").unwrap();
+        assert_eq!(store.read("forms/SKILL.md").unwrap(), b"# Forms\n");
+        assert!(temp.path().join("forms/SKILL.md").exists());
+
+        let entries = store.list().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].key, "forms/SKILL.md");
+        assert_eq!(entries[0].size, 8);
+
+        assert!(matches!(
+            store.read("../outside"),
+            Err(StoreError::Invalid(_))
+        ));
+        assert!(matches!(
+            store.read("missing.md"),
+            Err(StoreError::NotFound(_))
+        ));
+
+        store.remove("forms/SKILL.md").unwrap();
+        store.remove("forms/SKILL.md").unwrap();
+        assert!(store.list().unwrap().is_empty());
+    }
+}
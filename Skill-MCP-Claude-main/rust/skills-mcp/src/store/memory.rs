@@ -0,0 +1,160 @@
+//! Skills held in memory.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use parking_lot::{Mutex, RwLock};
+
+use super::{check_key, SkillFixture, SkillStore, StoreEntry, StoreError, StoreWatch};
+
+type Listeners = Arc<Mutex<HashMap<u64, Arc<dyn Fn() + Send + Sync>>>>;
+
+/// A store that keeps files in memory, for tests and for embedders that
+/// load skills from elsewhere.
+#[derive(Default)]
+pub struct MemoryStore {
+    files: RwLock<BTreeMap<String, (Vec<u8>, u64)>>,
+    next_version: AtomicU64,
+    listeners: Listeners,
+}
+
+impl MemoryStore {
+    /// An empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a file while building the store.
+    ///
+    /// # Panics
+    ///
+    /// If `key` isn't a valid store key.
+    pub fn with_file(self, key: &str, contents: impl AsRef<[u8]>) -> Self {
+        if let Err(e) = self.write(key, contents.as_ref()) {
+            panic!("{}", e);
+        }
+        self
+    }
+
+    /// Add a fixture's files while building the store.
+    ///
+    /// # Panics
+    ///
+    /// If the fixture has a file path that isn't a valid store key.
+    pub fn with_fixture(self, fixture: &SkillFixture) -> Self {
+        if let Err(e) = self.add_fixture(fixture) {
+            panic!("{}", e);
+        }
+        self
+    }
+
+    /// Write a fixture's files, replacing any with the same keys.
+    pub fn add_fixture(&self, fixture: &SkillFixture) -> Result<(), StoreError> {
+        for (key, content) in fixture.files() {
+            self.write(&key, content.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    fn changed(&self) {
+        let listeners: Vec<_> = self.listeners.lock().values().cloned().collect();
+        for listener in listeners {
+            listener();
+        }
+    }
+}
+
+impl std::fmt::Debug for MemoryStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MemoryStore")
+            .field("files", &self.files.read().len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl SkillStore for MemoryStore {
+    fn list(&self) -> Result<Vec<StoreEntry>, StoreError> {
+        Ok(self
+            .files
+            .read()
+            .iter()
+            .map(|(key, (contents, version))| StoreEntry {
+                key: key.clone(),
+                size: contents.len() as u64,
+                version: version.to_string(),
+            })
+            .collect())
+    }
+
+    fn read(&self, key: &str) -> Result<Vec<u8>, StoreError> {
+        let key = check_key(key)?;
+        self.files
+            .read()
+            .get(&key)
+            .map(|(contents, _)| contents.clone())
+            .ok_or(StoreError::NotFound(key))
+    }
+
+    fn write(&self, key: &str, contents: &[u8]) -> Result<(), StoreError> {
+        let key = check_key(key)?;
+        let version = self.next_version.fetch_add(1, Ordering::Relaxed) + 1;
+        self.files.write().insert(key, (contents.to_vec(), version));
+        self.changed();
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) -> Result<(), StoreError> {
+        let key = check_key(key)?;
+        let removed = self.files.write().remove(&key).is_some();
+        if removed {
+            self.changed();
+        }
+        Ok(())
+    }
+
+    fn watch(&self, on_change: Arc<dyn Fn() + Send + Sync>) -> Result<StoreWatch, StoreError> {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        self.listeners.lock().insert(id, on_change);
+
+        let listeners = Arc::clone(&self.listeners);
+        Ok(StoreWatch::new(move || {
+            listeners.lock().remove(&id);
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_store() {
+        let store = MemoryStore::new().with_file("forms/SKILL.md", "# Forms\n");
+        let calls = Arc::new(AtomicU64::new(0));
+        let counter = Arc::clone(&calls);
+        let watch = store
+            .watch(Arc::new(move || {
+                counter.fetch_add(1, Ordering::Relaxed);
+            }))
+            .unwrap();
+
+        let before = store.list().unwrap()[0].version.clone();
+        store.write("forms/SKILL.md", b"# Forms v2\n").unwrap();
+        assert_ne!(store.list().unwrap()[0].version, before);
+        assert_eq!(store.read("forms/SKILL.md").unwrap(), b"# Forms v2\n");
+
+        store.remove("forms/SKILL.md").unwrap();
+        store.remove("forms/SKILL.md").unwrap();
+        assert!(matches!(
+            store.read("forms/SKILL.md"),
+            Err(StoreError::NotFound(_))
+        ));
+        assert_eq!(calls.load(Ordering::Relaxed), 2);
+
+        drop(watch);
+        store.write("charts/SKILL.md", b"# Charts\n").unwrap();
+        assert_eq!(calls.load(Ordering::Relaxed), 2);
+    }
+}
@@ -0,0 +1,464 @@
+//! A working directory kept in step with a store.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Weak};
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tracing::{debug, error, warn};
+use walkdir::WalkDir;
+
+use super::{check_key, poll_changes, SkillStore, StoreError, StoreWatch, DEFAULT_POLL_INTERVAL};
+use crate::index::SkillIndexer;
+use crate::models::{parse_flat_skill, SkillMeta};
+
+/// What a [`StoreMirror`] copied in one direction or the other.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SyncReport {
+    /// Keys copied from the store into the directory.
+    pub downloaded: Vec<String>,
+
+    /// Keys copied from the directory into the store.
+    pub uploaded: Vec<String>,
+
+    /// Keys deleted from the directory or the store.
+    pub deleted: Vec<String>,
+
+    /// Keys changed in the store but left alone as their skill is pinned.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub pinned: Vec<String>,
+}
+
+impl SyncReport {
+    /// Whether nothing was copied or deleted.
+    pub fn is_empty(&self) -> bool {
+        self.downloaded.is_empty() && self.uploaded.is_empty() && self.deleted.is_empty()
+    }
+
+    fn extend(&mut self, other: SyncReport) {
+        self.downloaded.extend(other.downloaded);
+        self.uploaded.extend(other.uploaded);
+        self.deleted.extend(other.deleted);
+        self.pinned.extend(other.pinned);
+    }
+}
+
+/// State of a key at the last sync.
+struct Synced {
+    version: String,
+    hash: String,
+}
+
+/// A local directory mirroring a [`SkillStore`], for the indexer and
+/// servers to work on.
+///
+/// [`pull`](Self::pull) brings the directory up to date with the store,
+/// overwriting local edits that haven't been pushed, except to pinned
+/// skills unless [`force_pull`](Self::force_pull) is used;
+/// [`push`](Self::push) uploads files changed in the directory since the
+/// last sync and deletes ones removed there. [`sync`](Self::sync) does both,
+/// pushing first. Stores with a [`local_root`](SkillStore::local_root) are
+/// used in place and every sync is a no-op.
+pub struct StoreMirror {
+    store: Arc<dyn SkillStore>,
+    dir: PathBuf,
+    in_place: bool,
+    synced: Mutex<HashMap<String, Synced>>,
+}
+
+impl StoreMirror {
+    /// Mirror `store` into `dir`, downloading its files.
+    ///
+    /// Files already in `dir` that the store lacks are kept and uploaded by
+    /// the next push.
+    pub fn open(store: Arc<dyn SkillStore>, dir: impl AsRef<Path>) -> Result<Self, StoreError> {
+        if let Some(root) = store.local_root() {
+            let dir = root.to_path_buf();
+            return Ok(Self {
+                store,
+                dir,
+                in_place: true,
+                synced: Mutex::new(HashMap::new()),
+            });
+        }
+
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)
+            .map_err(|e| StoreError::Io(format!("{}: {}", dir.display(), e)))?;
+        let mirror = Self {
+            store,
+            dir,
+            in_place: false,
+            synced: Mutex::new(HashMap::new()),
+        };
+        mirror.pull()?;
+        Ok(mirror)
+    }
+
+    /// Directory to point the indexer and servers at.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The mirrored store.
+    pub fn store(&self) -> &Arc<dyn SkillStore> {
+        &self.store
+    }
+
+    /// Download files that changed in the store and delete ones removed
+    /// from it, leaving skills pinned in the directory as they are.
+    pub fn pull(&self) -> Result<SyncReport, StoreError> {
+        self.pull_inner(false)
+    }
+
+    /// Pull, overwriting pinned skills too.
+    pub fn force_pull(&self) -> Result<SyncReport, StoreError> {
+        self.pull_inner(true)
+    }
+
+    fn pull_inner(&self, force: bool) -> Result<SyncReport, StoreError> {
+        let mut report = SyncReport::default();
+        if self.in_place {
+            return Ok(report);
+        }
+
+        let mut synced = self.synced.lock();
+        let entries = self.store.list()?;
+        let listed: HashSet<&str> = entries.iter().map(|e| e.key.as_str()).collect();
+
+        for entry in &entries {
+            if synced
+                .get(&entry.key)
+                .is_some_and(|s| s.version == entry.version)
+            {
+                continue;
+            }
+            let key = check_key(&entry.key)?;
+            if !force && is_pinned(&self.dir, &key) {
+                report.pinned.push(key);
+                continue;
+            }
+            let contents = self.store.read(&key)?;
+            write_file(&self.dir.join(&key), &contents)?;
+            synced.insert(
+                key.clone(),
+                Synced {
+                    version: entry.version.clone(),
+                    hash: hash(&contents),
+                },
+            );
+            report.downloaded.push(key);
+        }
+
+        let gone: Vec<String> = synced
+            .keys()
+            .filter(|key| !listed.contains(key.as_str()))
+            .cloned()
+            .collect();
+        for key in gone {
+            if !force && is_pinned(&self.dir, &key) {
+                report.pinned.push(key);
+                continue;
+            }
+            synced.remove(&key);
+            remove_file(&self.dir.join(&key))?;
+            report.deleted.push(key);
+        }
+
+        if !report.is_empty() {
+            debug!(
+                "Pulled {} files, deleted {}",
+                report.downloaded.len(),
+                report.deleted.len()
+            );
+        }
+        Ok(report)
+    }
+
+    /// Upload files changed in the directory and delete ones removed from
+    /// it.
+    pub fn push(&self) -> Result<SyncReport, StoreError> {
+        let mut report = SyncReport::default();
+        if self.in_place {
+            return Ok(report);
+        }
+
+        // Work out the changes, then release the lock: stores may report
+        // the writes to a watcher that pulls straight away
+        let mut uploads = Vec::new();
+        let mut deletes = Vec::new();
+        {
+            let synced = self.synced.lock();
+            let mut present = HashSet::new();
+            for (key, path) in local_files(&self.dir)? {
+                let contents = fs::read(&path)
+                    .map_err(|e| StoreError::Io(format!("{}: {}", path.display(), e)))?;
+                let digest = hash(&contents);
+                if synced.get(&key).is_none_or(|s| s.hash != digest) {
+                    uploads.push((key.clone(), contents, digest));
+                }
+                present.insert(key);
+            }
+            deletes.extend(synced.keys().filter(|key| !present.contains(*key)).cloned());
+        }
+
+        for (key, contents, _) in &uploads {
+            self.store.write(key, contents)?;
+        }
+        for key in &deletes {
+            self.store.remove(key)?;
+        }
+
+        let versions: HashMap<String, String> = self
+            .store
+            .list()?
+            .into_iter()
+            .map(|entry| (entry.key, entry.version))
+            .collect();
+        let mut synced = self.synced.lock();
+        for (key, _, digest) in uploads {
+            if let Some(version) = versions.get(&key) {
+                synced.insert(
+                    key.clone(),
+                    Synced {
+                        version: version.clone(),
+                        hash: digest,
+                    },
+                );
+            }
+            report.uploaded.push(key);
+        }
+        for key in deletes {
+            synced.remove(&key);
+            report.deleted.push(key);
+        }
+
+        if !report.is_empty() {
+            debug!(
+                "Pushed {} files, deleted {}",
+                report.uploaded.len(),
+                report.deleted.len()
+            );
+        }
+        Ok(report)
+    }
+
+    /// Push local changes, then pull the store's.
+    pub fn sync(&self) -> Result<SyncReport, StoreError> {
+        let mut report = self.push()?;
+        report.extend(self.pull()?);
+        Ok(report)
+    }
+
+    /// Pull and reload `indexer` whenever the store changes, until the
+    /// returned handle is dropped.
+    ///
+    /// Stores that don't report changes are polled every
+    /// [`DEFAULT_POLL_INTERVAL`].
+    pub fn watch(self: &Arc<Self>, indexer: Arc<SkillIndexer>) -> Result<StoreWatch, StoreError> {
+        let mirror: Weak<Self> = Arc::downgrade(self);
+        let on_change: Arc<dyn Fn() + Send + Sync> = Arc::new(move || {
+            let Some(mirror) = mirror.upgrade() else {
+                return;
+            };
+            match mirror.pull() {
+                Ok(report) if report.is_empty() && !mirror.in_place => {}
+                Ok(_) => {
+                    if let Err(e) = indexer.reload() {
+                        error!("Failed to reload index: {}", e);
+                    }
+                }
+                Err(e) => warn!("Failed to pull store changes: {}", e),
+            }
+        });
+
+        match self.store.watch(Arc::clone(&on_change)) {
+            Err(StoreError::Unsupported(_)) => Ok(poll_changes(
+                Arc::clone(&self.store),
+                DEFAULT_POLL_INTERVAL,
+                on_change,
+            )),
+            result => result,
+        }
+    }
+}
+
+impl std::fmt::Debug for StoreMirror {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StoreMirror")
+            .field("dir", &self.dir)
+            .field("in_place", &self.in_place)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Whether `key` belongs to a skill pinned in `dir`: a directory above it
+/// with a pinned `_meta.json`, or a pinned flat-file skill.
+fn is_pinned(dir: &Path, key: &str) -> bool {
+    let path = Path::new(key);
+    if path.extension().is_some_and(|ext| ext == "md") && path.parent() == Some(Path::new("")) {
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        if let Ok(text) = fs::read_to_string(dir.join(key)) {
+            if let Ok((meta, _)) = parse_flat_skill(&stem, &text) {
+                return meta.pinned;
+            }
+        }
+    }
+
+    path.ancestors().skip(1).any(|ancestor| {
+        fs::read_to_string(dir.join(ancestor).join("_meta.json"))
+            .ok()
+            .and_then(|text| serde_json::from_str::<SkillMeta>(&text).ok())
+            .is_some_and(|meta| meta.pinned)
+    })
+}
+
+fn hash(contents: &[u8]) -> String {
+    Sha256::digest(contents)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Files under `dir` by key, leaving out temporary files from atomic writes.
+fn local_files(dir: &Path) -> Result<Vec<(String, PathBuf)>, StoreError> {
+    let mut files = Vec::new();
+    for entry in WalkDir::new(dir).min_depth(1) {
+        let entry = entry.map_err(|e| StoreError::Io(e.to_string()))?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy();
+        if name.starts_with('.') && name.ends_with(".tmp") {
+            continue;
+        }
+        let Ok(relative) = entry.path().strip_prefix(dir) else {
+            continue;
+        };
+        let key = relative.to_string_lossy().replace('\\', "/");
+        files.push((key, entry.path().to_path_buf()));
+    }
+    Ok(files)
+}
+
+fn write_file(path: &Path, contents: &[u8]) -> Result<(), StoreError> {
+    let io = |e: std::io::Error| StoreError::Io(format!("{}: {}", path.display(), e));
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(io)?;
+    }
+    fs::write(path, contents).map_err(io)
+}
+
+fn remove_file(path: &Path) -> Result<(), StoreError> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(StoreError::Io(format!("{}: {}", path.display(), e)))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::{FsStore, MemoryStore};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_mirror_round_trip() {
+        let temp = TempDir::new().unwrap();
+        let store = Arc::new(
+            MemoryStore::new()
+                .with_file("forms/SKILL.md", "# Forms\n")
+                .with_file(
+                    "forms/_meta.json",
+                    r#"{"name": "forms", "description": "Form handling", "tags": ["forms"]}"#,
+                ),
+        );
+        let mirror = Arc::new(StoreMirror::open(store.clone(), temp.path()).unwrap());
+        assert!(temp.path().join("forms/SKILL.md").exists());
+
+        // The unchanged indexer reads the mirror
+        let indexer = Arc::new(SkillIndexer::new(mirror.dir()));
+        indexer.reload().unwrap();
+        assert!(indexer.get_skill_meta("forms").is_some());
+
+        // Local edits go up
+        fs::write(temp.path().join("forms/SKILL.md"), "# Forms v2\n").unwrap();
+        fs::create_dir_all(temp.path().join("charts")).unwrap();
+        fs::write(temp.path().join("charts/SKILL.md"), "# Charts\n").unwrap();
+        let report = mirror.push().unwrap();
+        assert_eq!(report.uploaded.len(), 2);
+        assert_eq!(store.read("forms/SKILL.md").unwrap(), b"# Forms v2\n");
+        assert!(mirror.sync().unwrap().is_empty());
+
+        // Store changes come down and reload the index
+        let _watch = mirror.watch(Arc::clone(&indexer)).unwrap();
+        store.remove("charts/SKILL.md").unwrap();
+        assert!(!temp.path().join("charts/SKILL.md").exists());
+        store
+            .write(
+                "tables/_meta.json",
+                br#"{"name": "tables", "description": "Tables", "tags": ["data"]}"#,
+            )
+            .unwrap();
+        store.write("tables/SKILL.md", b"# Tables\n").unwrap();
+        assert!(indexer.get_skill_meta("tables").is_some());
+
+        // Deleting locally deletes from the store
+        fs::remove_file(temp.path().join("tables/SKILL.md")).unwrap();
+        assert_eq!(mirror.push().unwrap().deleted, vec!["tables/SKILL.md"]);
+        assert!(store.read("tables/SKILL.md").is_err());
+    }
+
+    #[test]
+    fn test_pull_skips_pinned_skills() {
+        let temp = TempDir::new().unwrap();
+        let store = Arc::new(
+            MemoryStore::new()
+                .with_file("forms/SKILL.md", "# Forms\n")
+                .with_file(
+                    "forms/_meta.json",
+                    r#"{"name": "forms", "description": "Forms", "pinned": true}"#,
+                )
+                .with_file(
+                    "charts.md",
+                    "---\ndescription: Charts\npinned: true\n---\n# Charts\n",
+                ),
+        );
+        let mirror = StoreMirror::open(store.clone(), temp.path()).unwrap();
+
+        store.write("forms/SKILL.md", b"# Forms v2\n").unwrap();
+        store.remove("charts.md").unwrap();
+        let report = mirror.pull().unwrap();
+        assert_eq!(report.pinned, vec!["forms/SKILL.md", "charts.md"]);
+        assert!(report.is_empty());
+        assert_eq!(
+            fs::read_to_string(temp.path().join("forms/SKILL.md")).unwrap(),
+            "# Forms\n"
+        );
+        assert!(temp.path().join("charts.md").exists());
+
+        let report = mirror.force_pull().unwrap();
+        assert_eq!(report.downloaded, vec!["forms/SKILL.md"]);
+        assert_eq!(report.deleted, vec!["charts.md"]);
+        assert_eq!(
+            fs::read_to_string(temp.path().join("forms/SKILL.md")).unwrap(),
+            "# Forms v2\n"
+        );
+    }
+
+    #[test]
+    fn test_local_store_is_used_in_place() {
+        let temp = TempDir::new().unwrap();
+        let store = Arc::new(FsStore::new(temp.path()));
+        store.write("forms/SKILL.md", b"# Forms\n").unwrap();
+
+        let mirror = StoreMirror::open(store, "/nonexistent/cache").unwrap();
+        assert_eq!(mirror.dir(), temp.path());
+        assert!(mirror.sync().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,271 @@
+//! Pluggable storage for skill files.
+//!
+//! A [`SkillStore`] holds the files of a skills directory under `/`-separated
+//! keys such as `forms/SKILL.md`. [`FsStore`] is the local filesystem;
+//! [`MemoryStore`] keeps everything in memory; `S3Store`, with the `s3`
+//! feature, uses an S3-compatible bucket. Embedders can implement the trait
+//! over a database or anything else that can list, read and write blobs.
+//!
+//! The indexer, search and servers work on a local directory. A
+//! [`StoreMirror`] keeps a working directory in step with a store: it
+//! downloads the store into the directory, uploads changes made there, and
+//! pulls again when the store reports a change. Stores that already live on
+//! the local filesystem are used in place.
+//!
+//! ```rust,no_run
+//! use std::sync::Arc;
+//! use skills_mcp::index::SkillIndexer;
+//! use skills_mcp::store::{MemoryStore, StoreMirror};
+//!
+//! let store = Arc::new(MemoryStore::new().with_file("forms/SKILL.md", "# Forms\n"));
+//!
+//! let mirror = Arc::new(StoreMirror::open(store, "/tmp/skills-cache").unwrap());
+//! let indexer = Arc::new(SkillIndexer::new(mirror.dir()));
+//! indexer.reload().unwrap();
+//! let _watch = mirror.watch(Arc::clone(&indexer)).unwrap();
+//! ```
+
+mod fixture;
+mod fs;
+mod memory;
+mod mirror;
+#[cfg(feature = "s3")]
+mod s3;
+
+pub(crate) use fixture::ScratchDir;
+pub use fixture::SkillFixture;
+pub use fs::FsStore;
+pub use memory::MemoryStore;
+pub use mirror::{StoreMirror, SyncReport};
+#[cfg(feature = "s3")]
+pub use s3::S3Store;
+
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use thiserror::Error;
+use tracing::warn;
+
+use crate::validation::{find_windows_reserved_component, normalize_separators};
+
+/// How often [`poll_changes`] lists a store by default.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Errors from a skill store.
+#[derive(Error, Debug)]
+pub enum StoreError {
+    /// No file is stored under the key.
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    /// The key isn't a valid relative path.
+    #[error("Invalid key: {0}")]
+    Invalid(String),
+
+    /// The store can't do what was asked, such as report changes.
+    #[error("Unsupported: {0}")]
+    Unsupported(String),
+
+    /// Reading or writing failed.
+    #[error("I/O error: {0}")]
+    Io(String),
+}
+
+/// A file in a store.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoreEntry {
+    /// `/`-separated path relative to the skills directory.
+    pub key: String,
+
+    /// Size in bytes.
+    pub size: u64,
+
+    /// Changes whenever the contents do, like an ETag or modification time.
+    pub version: String,
+}
+
+/// Storage holding the files of a skills directory.
+///
+/// Keys are relative paths with `/` separators and never contain `..`;
+/// [`check_key`] enforces the rules.
+pub trait SkillStore: Send + Sync {
+    /// Every file in the store.
+    fn list(&self) -> Result<Vec<StoreEntry>, StoreError>;
+
+    /// Contents of the file under `key`.
+    fn read(&self, key: &str) -> Result<Vec<u8>, StoreError>;
+
+    /// Store `contents` under `key`, replacing any existing file.
+    fn write(&self, key: &str, contents: &[u8]) -> Result<(), StoreError>;
+
+    /// Delete the file under `key`. Deleting a missing file succeeds.
+    fn remove(&self, key: &str) -> Result<(), StoreError>;
+
+    /// Call `on_change` whenever the store's files change, until the
+    /// returned handle is dropped.
+    ///
+    /// Stores without change notifications return
+    /// [`StoreError::Unsupported`]; [`poll_changes`] works for any store.
+    fn watch(&self, on_change: Arc<dyn Fn() + Send + Sync>) -> Result<StoreWatch, StoreError> {
+        let _ = on_change;
+        Err(StoreError::Unsupported(
+            "this store does not report changes".to_string(),
+        ))
+    }
+
+    /// The local directory holding the files, if the store is one.
+    ///
+    /// A [`StoreMirror`] uses such a directory directly instead of copying
+    /// it.
+    fn local_root(&self) -> Option<&Path> {
+        None
+    }
+}
+
+/// Handle returned by [`SkillStore::watch`]; watching stops when it's
+/// dropped.
+pub struct StoreWatch {
+    stop: Option<Box<dyn FnOnce() + Send>>,
+}
+
+impl StoreWatch {
+    /// A handle that runs `stop` when dropped.
+    pub fn new(stop: impl FnOnce() + Send + 'static) -> Self {
+        Self {
+            stop: Some(Box::new(stop)),
+        }
+    }
+}
+
+impl Drop for StoreWatch {
+    fn drop(&mut self) {
+        if let Some(stop) = self.stop.take() {
+            stop();
+        }
+    }
+}
+
+impl std::fmt::Debug for StoreWatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StoreWatch").finish_non_exhaustive()
+    }
+}
+
+/// Watch any store by listing it every `interval` and calling `on_change`
+/// when a key or version differs from the previous listing.
+pub fn poll_changes(
+    store: Arc<dyn SkillStore>,
+    interval: Duration,
+    on_change: Arc<dyn Fn() + Send + Sync>,
+) -> StoreWatch {
+    let stopped = Arc::new(AtomicBool::new(false));
+    let flag = Arc::clone(&stopped);
+
+    let snapshot = |store: &dyn SkillStore| {
+        store.list().map(|mut entries| {
+            entries.sort_by(|a, b| a.key.cmp(&b.key));
+            entries
+        })
+    };
+    // Changes made after this returns are never missed
+    let mut last = snapshot(store.as_ref()).ok();
+
+    thread::spawn(move || {
+        while !flag.load(Ordering::Relaxed) {
+            thread::sleep(interval);
+            if flag.load(Ordering::Relaxed) {
+                break;
+            }
+            match snapshot(store.as_ref()) {
+                Ok(entries) => {
+                    if last.as_ref() != Some(&entries) {
+                        last = Some(entries);
+                        on_change();
+                    }
+                }
+                Err(e) => warn!("Failed to list store: {}", e),
+            }
+        }
+    });
+
+    StoreWatch::new(move || stopped.store(true, Ordering::Relaxed))
+}
+
+/// Check that `key` is a relative path inside the store, returning it with
+/// `/` separators.
+pub fn check_key(key: &str) -> Result<String, StoreError> {
+    let normalized = normalize_separators(key);
+
+    if normalized.is_empty() || normalized.ends_with('/') {
+        return Err(StoreError::Invalid(format!("not a file path: '{}'", key)));
+    }
+    if normalized.starts_with('/') || (normalized.len() >= 2 && normalized.as_bytes()[1] == b':') {
+        return Err(StoreError::Invalid(format!("path is absolute: {}", key)));
+    }
+    if normalized
+        .split('/')
+        .any(|component| component.is_empty() || component == "." || component == "..")
+    {
+        return Err(StoreError::Invalid(format!(
+            "path has an empty, '.' or '..' component: {}",
+            key
+        )));
+    }
+    if let Some(component) = find_windows_reserved_component(&normalized) {
+        return Err(StoreError::Invalid(format!(
+            "'{}' is a reserved name on Windows: {}",
+            component, key
+        )));
+    }
+
+    Ok(normalized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_key() {
+        assert_eq!(check_key("forms/SKILL.md").unwrap(), "forms/SKILL.md");
+        assert_eq!(check_key("forms\\SKILL.md").unwrap(), "forms/SKILL.md");
+
+        for bad in [
+            "",
+            "/etc/passwd",
+            "C:/x",
+            "forms/../x",
+            "forms//x",
+            "./x",
+            "forms/",
+            "con.md",
+        ] {
+            assert!(
+                matches!(check_key(bad), Err(StoreError::Invalid(_))),
+                "{}",
+                bad
+            );
+        }
+    }
+
+    #[test]
+    fn test_poll_changes() {
+        let store = Arc::new(MemoryStore::new());
+        let (tx, rx) = std::sync::mpsc::channel();
+        let tx = parking_lot::Mutex::new(tx);
+        let watch = poll_changes(
+            store.clone(),
+            Duration::from_millis(10),
+            Arc::new(move || {
+                let _ = tx.lock().send(());
+            }),
+        );
+
+        store.write("forms/SKILL.md", b"# Forms\n").unwrap();
+        rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        drop(watch);
+    }
+}
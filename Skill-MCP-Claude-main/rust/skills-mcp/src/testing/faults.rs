@@ -0,0 +1,165 @@
+//! Failure injection for the indexer.
+//!
+//! [`Faults`] makes the indexer's file reads fail or stall and makes the
+//! file watcher handle each event many times over, so tests can drive the
+//! paths that are otherwise hard to reach: the full reload that follows a
+//! failed incremental update, and read errors reported per skill instead of
+//! failing the whole index.
+//!
+//! Indexers pick faults up from the environment, so a server started by an
+//! integration test can be broken from outside:
+//!
+//! | Variable | Effect |
+//! |----------|--------|
+//! | `SKILLS_FAULT_READ_ERROR` | Comma-separated path fragments; reads of matching files fail |
+//! | `SKILLS_FAULT_READ_ERROR_LIMIT` | Fail only this many reads, then let them through |
+//! | `SKILLS_FAULT_SLOW_READ_MS` | Delay before every read |
+//! | `SKILLS_FAULT_EVENT_STORM` | Handle every watcher event this many times |
+
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Faults to inject into an indexer, set through
+/// [`IndexerOptions::faults`](crate::index::IndexerOptions::faults).
+#[derive(Debug, Clone, Default)]
+pub struct Faults {
+    /// Reads of files whose `/`-separated path contains any of these fail.
+    pub read_errors: Vec<String>,
+
+    /// Stop failing reads after this many; unlimited when `None`.
+    pub read_error_limit: Option<u64>,
+
+    /// Delay before every read.
+    pub slow_read: Duration,
+
+    /// Handle every watcher event this many times; 0 and 1 are both once.
+    pub event_storm: usize,
+
+    /// Reads failed so far, shared by clones.
+    failed: Arc<AtomicU64>,
+}
+
+impl Faults {
+    /// No faults.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Faults from the `SKILLS_FAULT_*` variables; none when unset.
+    pub fn from_env() -> Self {
+        Self::from_vars(|name| std::env::var(name).ok().filter(|v| !v.is_empty()))
+    }
+
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> Self {
+        let number = |name| var(name).and_then(|v| v.trim().parse::<u64>().ok());
+
+        let mut faults = Self::new();
+        if let Some(paths) = var("SKILLS_FAULT_READ_ERROR") {
+            faults.read_errors = paths
+                .split(',')
+                .map(|p| p.trim().to_string())
+                .filter(|p| !p.is_empty())
+                .collect();
+        }
+        faults.read_error_limit = number("SKILLS_FAULT_READ_ERROR_LIMIT");
+        if let Some(ms) = number("SKILLS_FAULT_SLOW_READ_MS") {
+            faults.slow_read = Duration::from_millis(ms);
+        }
+        if let Some(times) = number("SKILLS_FAULT_EVENT_STORM") {
+            faults.event_storm = times as usize;
+        }
+        faults
+    }
+
+    /// Fail reads of files whose path contains `fragment`.
+    pub fn fail_reads(mut self, fragment: impl Into<String>) -> Self {
+        self.read_errors.push(fragment.into());
+        self
+    }
+
+    /// Stop failing reads after `limit` of them.
+    pub fn read_error_limit(mut self, limit: u64) -> Self {
+        self.read_error_limit = Some(limit);
+        self
+    }
+
+    /// Wait `delay` before every read.
+    pub fn slow_reads(mut self, delay: Duration) -> Self {
+        self.slow_read = delay;
+        self
+    }
+
+    /// Handle every watcher event `times` times.
+    pub fn event_storm(mut self, times: usize) -> Self {
+        self.event_storm = times;
+        self
+    }
+
+    /// Number of reads failed so far.
+    pub fn failed_reads(&self) -> u64 {
+        self.failed.load(Ordering::Relaxed)
+    }
+
+    /// Called before the indexer reads `path`: waits, then fails the read
+    /// if it matches and the limit isn't used up.
+    pub(crate) fn before_read(&self, path: &Path) -> io::Result<()> {
+        if !self.slow_read.is_zero() {
+            std::thread::sleep(self.slow_read);
+        }
+        if self.read_errors.is_empty() {
+            return Ok(());
+        }
+
+        let path = path.to_string_lossy().replace('\\', "/");
+        if !self.read_errors.iter().any(|f| path.contains(f.as_str())) {
+            return Ok(());
+        }
+        let failed = self.failed.fetch_add(1, Ordering::Relaxed);
+        if self.read_error_limit.is_some_and(|limit| failed >= limit) {
+            self.failed.fetch_sub(1, Ordering::Relaxed);
+            return Ok(());
+        }
+        Err(io::Error::other("injected read error"))
+    }
+
+    /// How many times the watcher handles each event.
+    pub(crate) fn event_repeats(&self) -> usize {
+        self.event_storm.max(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_vars() {
+        let faults = Faults::from_vars(|name| match name {
+            "SKILLS_FAULT_READ_ERROR" => Some("forms/_meta.json, notes.md,".to_string()),
+            "SKILLS_FAULT_READ_ERROR_LIMIT" => Some("2".to_string()),
+            "SKILLS_FAULT_SLOW_READ_MS" => Some("15".to_string()),
+            "SKILLS_FAULT_EVENT_STORM" => Some("oops".to_string()),
+            _ => None,
+        });
+        assert_eq!(faults.read_errors, vec!["forms/_meta.json", "notes.md"]);
+        assert_eq!(faults.read_error_limit, Some(2));
+        assert_eq!(faults.slow_read, Duration::from_millis(15));
+        assert_eq!(faults.event_repeats(), 1);
+    }
+
+    #[test]
+    fn test_read_errors_up_to_limit() {
+        let faults = Faults::new().fail_reads("forms/").read_error_limit(2);
+        let clone = faults.clone();
+
+        let reads = |faults: &Faults, path: &str| faults.before_read(Path::new(path)).is_ok();
+        assert!(reads(&faults, "/skills/other/SKILL.md"));
+        assert!(!reads(&faults, "/skills/forms/SKILL.md"));
+        assert!(!reads(&clone, "/skills/forms/_meta.json"));
+        assert!(reads(&faults, "/skills/forms/SKILL.md"));
+        assert_eq!(faults.failed_reads(), 2);
+    }
+}
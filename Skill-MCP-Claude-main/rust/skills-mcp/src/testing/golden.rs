@@ -0,0 +1,332 @@
+//! Golden-file tests of the JSON shape of HTTP endpoints and MCP tools.
+//!
+//! Each response is reduced to its [`shape`], which keeps every field name
+//! but replaces values with their types, and compared by insta with the
+//! snapshot in `snapshots/`. Renaming or dropping a field fails the test
+//! while times, hashes and temporary paths don't. After an intended
+//! change, review the new snapshots with `cargo insta review`.
+//!
+//! Endpoints that reach the network (`/import/github`, `/updates/check`,
+//! `/skills/:name/update`), take multipart uploads or return files rather
+//! than JSON are left out.
+
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use super::{test_skill, McpTestClient, SkillFixture, TestResponse, TestServer, TestSkills};
+
+const ADMIN_TOKEN: &str = "golden-admin-token";
+
+/// The fixture skills directory: `test-skill` and a `forms` skill with a
+/// sub-skill and sections.
+pub(super) fn skills() -> TestSkills {
+    TestSkills::new().with_skill(test_skill()).with_skill(
+        SkillFixture::new("forms", "Form handling and validation")
+            .with_tags(["frontend"])
+            .with_content(
+                "# Forms\n\nBuild and validate forms.\n\n## Layout\n\nGrid and spacing.\n\n\
+                 ## Submission\n\nSend data once inputs pass.\n",
+            )
+            .with_sub_skill("validation", "# Validation\n\nCheck inputs before submit.")
+            .with_file("data/fields.csv", "field,rule\nemail,required\n"),
+    )
+}
+
+/// Arguments for every tool, in an order where each call succeeds, and
+/// whether it should.
+pub(super) fn tool_cases() -> Vec<(&'static str, Value, bool)> {
+    vec![
+        ("list_skills", json!({}), true),
+        ("get_skill", json!({"name": "forms"}), true),
+        (
+            "get_sub_skill",
+            json!({"domain": "forms", "sub_skill": "validation"}),
+            true,
+        ),
+        (
+            "get_skills_batch",
+            json!({"requests": [{"domain": "forms"}, {"domain": "test-skill"}]}),
+            true,
+        ),
+        ("search_skills", json!({"query": "forms"}), true),
+        ("search_content", json!({"query": "validate"}), true),
+        (
+            "search_batch",
+            json!({"queries": ["forms", "inputs"]}),
+            true,
+        ),
+        (
+            "create_skill",
+            json!({
+                "name": "tables",
+                "description": "Table layouts.",
+                "content": "# Tables\n\nRows and columns.\n",
+            }),
+            true,
+        ),
+        (
+            "scaffold_skill",
+            json!({"name": "charts", "description": "Charts.",
+                   "outline": [{"name": "bar", "description": "Bar charts"}]}),
+            true,
+        ),
+        (
+            "update_skill",
+            json!({"name": "tables", "description": "Data tables."}),
+            true,
+        ),
+        ("delete_skill", json!({"name": "charts"}), true),
+        ("reload_index", json!({}), true),
+        ("diff_index", json!({}), true),
+        ("get_stats", json!({}), true),
+        ("validate_skills", json!({}), true),
+        ("list_references", json!({"domain": "forms"}), true),
+        (
+            "get_reference",
+            json!({"domain": "forms", "path": "validation.md"}),
+            true,
+        ),
+        // Disabled without a sandbox
+        (
+            "run_skill_script",
+            json!({"skill": "forms", "script": "check.sh"}),
+            false,
+        ),
+        // Needs an LLM backend
+        ("suggest_metadata", json!({}), false),
+        (
+            "ask_skills",
+            json!({"question": "How do I validate forms?"}),
+            true,
+        ),
+        (
+            "route_task",
+            json!({"task": "validate a signup form"}),
+            true,
+        ),
+        ("mark_skill_loaded", json!({"skills": ["forms"]}), true),
+        ("get_loaded_skills", json!({}), true),
+        // Needs a client that samples or an LLM backend
+        ("summarize_skill", json!({"name": "forms"}), false),
+    ]
+}
+
+/// The shape of `value`: objects keep their keys, scalars become the name
+/// of their type and arrays hold the merged shape of their items.
+fn shape(value: &Value) -> Value {
+    match value {
+        Value::Null => json!("null"),
+        Value::Bool(_) => json!("bool"),
+        Value::Number(_) => json!("number"),
+        Value::String(_) => json!("string"),
+        Value::Array(items) => match items.iter().map(shape).reduce(merge) {
+            Some(item) => json!([item]),
+            None => json!([]),
+        },
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(key, value)| (key.clone(), shape(value)))
+                .collect(),
+        ),
+    }
+}
+
+/// Combine two shapes: objects take the fields of both and differing
+/// types are listed together, as in `"null | string"`.
+fn merge(a: Value, b: Value) -> Value {
+    match (a, b) {
+        (a, b) if a == b => a,
+        (Value::Object(mut a), Value::Object(b)) => {
+            for (key, value) in b {
+                let merged = match a.remove(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => value,
+                };
+                a.insert(key, merged);
+            }
+            Value::Object(a)
+        }
+        (Value::Array(mut a), Value::Array(b)) => match (a.pop(), b.into_iter().next()) {
+            (Some(a), Some(b)) => json!([merge(a, b)]),
+            (a, b) => Value::Array(a.or(b).into_iter().collect()),
+        },
+        (a, b) => {
+            let mut types: Vec<String> = [a, b]
+                .iter()
+                .flat_map(|t| match t {
+                    Value::String(s) => s.split(" | ").map(str::to_string).collect(),
+                    other => vec![other.to_string()],
+                })
+                .collect();
+            types.sort();
+            types.dedup();
+            Value::String(types.join(" | "))
+        }
+    }
+}
+
+fn assert_shape(name: &str, response: TestResponse) {
+    assert!(
+        response.status.is_success(),
+        "{}: {} {}",
+        name,
+        response.status,
+        response.text()
+    );
+    insta::assert_json_snapshot!(name, shape(&response.json()));
+}
+
+#[test]
+fn test_shape() {
+    let value = json!({
+        "name": "forms",
+        "tags": ["a", "b"],
+        "sub_skills": [{"name": "x"}, {"name": "y", "file": null}, {"name": "z", "file": "z.md"}],
+        "empty": [],
+    });
+    assert_eq!(
+        shape(&value),
+        json!({
+            "name": "string",
+            "tags": ["string"],
+            "sub_skills": [{"name": "string", "file": "null | string"}],
+            "empty": [],
+        })
+    );
+    assert_eq!(
+        merge(json!("number"), json!("bool | null")),
+        json!("bool | null | number")
+    );
+    assert_eq!(merge(json!([]), json!(["string"])), json!(["string"]));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_http_response_shapes() {
+    let server = TestServer::with_server(skills(), |server| server.with_admin_token(ADMIN_TOKEN))
+        .with_bearer(ADMIN_TOKEN);
+
+    // Search warm-up runs in the background
+    for _ in 0..500 {
+        if server.state().is_ready() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_shape("http_readyz", server.get("/readyz").await);
+    assert_shape("http_list_skills", server.get("/api/skills").await);
+    assert_shape("http_get_skill", server.get("/api/skills/forms").await);
+    assert_shape(
+        "http_preview_skill_file",
+        server
+            .get("/api/skills/forms/preview/data/fields.csv")
+            .await,
+    );
+    assert_shape(
+        "http_analyze_skill",
+        server.get("/api/skills/forms/analysis").await,
+    );
+    assert_shape(
+        "http_suggested_triggers",
+        server.get("/api/skills/forms/suggested-triggers").await,
+    );
+    assert_shape("http_list_categories", server.get("/api/categories").await);
+    assert_shape("http_list_templates", server.get("/api/templates").await);
+    assert_shape("http_search", server.get("/api/search?q=forms").await);
+    assert_shape(
+        "http_search_batch",
+        server
+            .post("/api/search/batch", json!({"queries": ["forms", "inputs"]}))
+            .await,
+    );
+    assert_shape("http_search_index", server.get("/api/search/index").await);
+    assert_shape("http_manifest", server.get("/api/manifest").await);
+    assert_shape("http_disk_usage", server.get("/api/usage/disk").await);
+    assert_shape(
+        "http_check_links",
+        server.post("/api/validate/links", json!({})).await,
+    );
+
+    let job = server.post("/api/reload?wait=true", json!({})).await;
+    let id = job.json()["id"].clone();
+    assert_shape("http_reload", job);
+    assert_shape(
+        "http_reload_job",
+        server.get(&format!("/api/reload/{}", id)).await,
+    );
+
+    assert_shape("http_debug_index", server.get("/api/debug/index").await);
+    assert_shape("http_debug_diff", server.get("/api/debug/diff").await);
+    assert_shape(
+        "http_debug_index_entry",
+        server.get("/api/debug/index/forms").await,
+    );
+    assert_shape("http_debug_memory", server.get("/api/debug/memory").await);
+
+    // Maintenance and merges only plan by default
+    assert_shape(
+        "http_replace_content",
+        server
+            .post(
+                "/api/maintenance/replace",
+                json!({"pattern": "inputs", "replacement": "fields"}),
+            )
+            .await,
+    );
+    assert_shape(
+        "http_split_skill",
+        server
+            .post("/api/maintenance/split", json!({"skill": "forms"}))
+            .await,
+    );
+    assert_shape(
+        "http_collect_garbage",
+        server.post("/api/maintenance/gc", json!({})).await,
+    );
+    assert_shape(
+        "http_merge_skills",
+        server
+            .post(
+                "/api/skills/merge",
+                json!({"sources": ["test-skill"], "target": "forms"}),
+            )
+            .await,
+    );
+
+    assert_shape(
+        "http_create_skill",
+        server
+            .post(
+                "/api/skills",
+                json!({
+                    "name": "tables",
+                    "description": "Table layouts.",
+                    "content": "# Tables\n\nRows and columns.\n",
+                }),
+            )
+            .await,
+    );
+    assert_shape(
+        "http_update_skill",
+        server
+            .put("/api/skills/tables", json!({"description": "Data tables."}))
+            .await,
+    );
+    assert_shape(
+        "http_delete_skill_dry_run",
+        server.delete("/api/skills/tables?dry_run=true").await,
+    );
+}
+
+#[test]
+fn test_tool_response_shapes() {
+    let client = McpTestClient::new(skills());
+    for (tool, arguments, ok) in tool_cases() {
+        if !ok {
+            continue;
+        }
+        let result = client.call_ok(tool, arguments);
+        insta::assert_json_snapshot!(format!("tool_{}", tool), shape(&result));
+    }
+}
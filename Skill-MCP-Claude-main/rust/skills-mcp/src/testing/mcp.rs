@@ -0,0 +1,101 @@
+//! MCP tools called by name.
+
+use serde_json::Value;
+
+use crate::mcp::{call_tool, ServiceContext};
+use crate::models::ErrorResponse;
+
+use super::TestSkills;
+
+/// Calls MCP tools the way a client would, with JSON arguments and
+/// results.
+pub struct McpTestClient {
+    ctx: ServiceContext,
+    skills: Option<TestSkills>,
+}
+
+impl McpTestClient {
+    /// A client for tools over `skills`.
+    pub fn new(skills: TestSkills) -> Self {
+        Self {
+            ctx: ServiceContext::new(skills.indexer()),
+            skills: Some(skills),
+        }
+    }
+
+    /// A client for tools over an existing context, such as one with an
+    /// LLM backend or journal attached.
+    pub fn from_context(ctx: ServiceContext) -> Self {
+        Self { ctx, skills: None }
+    }
+
+    /// The context tools run against.
+    pub fn context(&self) -> &ServiceContext {
+        &self.ctx
+    }
+
+    /// The skills directory, when the client made it.
+    pub fn skills(&self) -> Option<&TestSkills> {
+        self.skills.as_ref()
+    }
+
+    /// Call `tool` with `arguments` (an object, or null for none).
+    pub fn call(&self, tool: &str, arguments: Value) -> Result<Value, ErrorResponse> {
+        call_tool(&self.ctx, tool, arguments)
+    }
+
+    /// Call `tool` and return its result.
+    ///
+    /// # Panics
+    ///
+    /// If the tool fails.
+    pub fn call_ok(&self, tool: &str, arguments: Value) -> Value {
+        match self.call(tool, arguments) {
+            Ok(result) => result,
+            Err(e) => panic!("{} failed: {}", tool, e.error),
+        }
+    }
+
+    /// Call `tool` and return its error message.
+    ///
+    /// # Panics
+    ///
+    /// If the tool succeeds.
+    pub fn call_err(&self, tool: &str, arguments: Value) -> String {
+        match self.call(tool, arguments) {
+            Ok(result) => panic!("{} succeeded: {}", tool, result),
+            Err(e) => e.error,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::test_skill;
+    use serde_json::json;
+
+    #[test]
+    fn test_mcp_test_client() {
+        let client = McpTestClient::new(TestSkills::new().with_skill(test_skill()));
+
+        let listed = client.call_ok("list_skills", Value::Null);
+        assert_eq!(listed["skills"][0]["name"], "test-skill");
+
+        let found = client.call_ok("search_skills", json!({"query": "test"}));
+        assert!(found.to_string().contains("test-skill"));
+
+        let error = client.call_err("get_skill", json!({"name": "missing"}));
+        assert!(error.contains("missing"), "{}", error);
+        assert_eq!(
+            client
+                .context()
+                .stats
+                .read()
+                .tool_calls
+                .values()
+                .sum::<u64>(),
+            3
+        );
+    }
+}
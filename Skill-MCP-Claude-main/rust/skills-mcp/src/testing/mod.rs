@@ -0,0 +1,137 @@
+//! Helpers for testing code built on this crate.
+//!
+//! Enabled by the `testing` feature, typically from `dev-dependencies`:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! skills-mcp = { version = "0.1", features = ["testing"] }
+//! ```
+//!
+//! [`TestSkills`] is a temporary skills directory filled from
+//! [`SkillFixture`]s, [`TestServer`] drives the HTTP API in process, and
+//! [`McpTestClient`] calls MCP tools by name with JSON arguments.
+//! [`McpStreamClient`] speaks the whole protocol, handshake included, to a
+//! server over an in-process stream. [`Faults`] breaks an indexer's reads
+//! and watcher events on purpose.
+//!
+//! ```rust
+//! use serde_json::json;
+//! use skills_mcp::testing::{test_skill, McpTestClient, TestServer, TestSkills};
+//!
+//! # tokio_test::block_on(async {
+//! let server = TestServer::new(TestSkills::new().with_skill(test_skill()));
+//! let response = server.get("/api/skills").await;
+//! assert_eq!(response.status, 200);
+//!
+//! let client = McpTestClient::new(TestSkills::new().with_skill(test_skill()));
+//! let skill = client.call_ok("get_skill", json!({"name": "test-skill"}));
+//! assert!(skill["content"].as_str().unwrap().contains("Content."));
+//! # });
+//! ```
+
+mod faults;
+#[cfg(test)]
+mod golden;
+mod mcp;
+mod server;
+mod stream;
+
+pub use faults::Faults;
+pub use mcp::McpTestClient;
+pub use server::{TestResponse, TestServer};
+pub use stream::McpStreamClient;
+
+pub use crate::store::SkillFixture;
+
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::index::SkillIndexer;
+use crate::store::{FsStore, ScratchDir, SkillStore};
+
+/// The skill most tests need: `test-skill`, tagged `test`, with a short
+/// SKILL.md.
+pub fn test_skill() -> SkillFixture {
+    SkillFixture::new("test-skill", "A test skill")
+        .with_tags(["test"])
+        .with_content("# Test Skill\n\nContent.")
+}
+
+/// A skills directory that is deleted when dropped.
+///
+/// Helpers panic on failure, as a test would.
+#[derive(Debug)]
+pub struct TestSkills {
+    dir: ScratchDir,
+}
+
+impl TestSkills {
+    /// An empty skills directory.
+    pub fn new() -> Self {
+        let dir = ScratchDir::new().expect("failed to create a skills directory");
+        Self { dir }
+    }
+
+    /// Add a skill while building the directory.
+    pub fn with_skill(self, fixture: SkillFixture) -> Self {
+        self.add(&fixture);
+        self
+    }
+
+    /// Write a skill's files, replacing any already there.
+    pub fn add(&self, fixture: &SkillFixture) {
+        for (key, content) in fixture.files() {
+            self.write(&key, &content);
+        }
+    }
+
+    /// Write a file at `path` relative to the directory.
+    pub fn write(&self, path: &str, content: &str) {
+        if let Err(e) = FsStore::new(self.path()).write(path, content.as_bytes()) {
+            panic!("failed to write {}: {}", path, e);
+        }
+    }
+
+    /// The directory.
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// A loaded indexer over the directory.
+    pub fn indexer(&self) -> Arc<SkillIndexer> {
+        let indexer = SkillIndexer::new(self.path());
+        if let Err(e) = indexer.reload() {
+            panic!("failed to index {}: {}", self.path().display(), e);
+        }
+        Arc::new(indexer)
+    }
+}
+
+impl Default for TestSkills {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skills_dir() {
+        let skills = TestSkills::new().with_skill(test_skill()).with_skill(
+            SkillFixture::new("forms", "Form handling")
+                .with_sub_skill("validation", "# Validation"),
+        );
+        skills.write("forms/scripts/check.py", "print('ok')\n");
+        let dir = skills.path().to_path_buf();
+
+        let indexer = skills.indexer();
+        assert_eq!(indexer.get_skill_index().len(), 2);
+        assert!(dir.join("forms/references/validation.md").exists());
+        assert!(dir.join("forms/scripts/check.py").exists());
+
+        drop(skills);
+        assert!(!dir.exists());
+    }
+}
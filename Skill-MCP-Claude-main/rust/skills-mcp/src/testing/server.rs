@@ -0,0 +1,200 @@
+//! The HTTP API driven in process.
+
+use axum::body::{Body, Bytes};
+use axum::http::{header, HeaderMap, Method, Request, StatusCode};
+use axum::Router;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use tower::ServiceExt;
+
+use std::sync::Arc;
+
+use crate::api::ApiServer;
+use crate::mcp::ServiceContext;
+
+use super::TestSkills;
+
+/// An [`ApiServer`] router over a [`TestSkills`] directory, called without
+/// a network socket.
+pub struct TestServer {
+    router: Router,
+    state: Arc<ServiceContext>,
+    skills: TestSkills,
+    bearer: Option<String>,
+}
+
+impl TestServer {
+    /// Serve `skills` with the default server settings.
+    pub fn new(skills: TestSkills) -> Self {
+        Self::with_server(skills, |server| server)
+    }
+
+    /// Serve `skills` with a server adjusted by `configure`, e.g. to set an
+    /// admin token or a journal.
+    pub fn with_server(skills: TestSkills, configure: impl FnOnce(ApiServer) -> ApiServer) -> Self {
+        let server = configure(ApiServer::new(skills.path()));
+        let state = server.state().clone();
+        let router = server.router();
+
+        Self {
+            router,
+            state,
+            skills,
+            bearer: None,
+        }
+    }
+
+    /// Send `Authorization: Bearer {token}` with every request.
+    pub fn with_bearer(mut self, token: impl Into<String>) -> Self {
+        self.bearer = Some(token.into());
+        self
+    }
+
+    /// The skills directory being served.
+    pub fn skills(&self) -> &TestSkills {
+        &self.skills
+    }
+
+    /// The server's shared state, including its indexer.
+    pub fn state(&self) -> &Arc<ServiceContext> {
+        &self.state
+    }
+
+    /// `GET uri`.
+    pub async fn get(&self, uri: &str) -> TestResponse {
+        self.send(Method::GET, uri, None).await
+    }
+
+    /// `POST uri` with a JSON body.
+    pub async fn post(&self, uri: &str, body: Value) -> TestResponse {
+        self.send(Method::POST, uri, Some(body)).await
+    }
+
+    /// `PUT uri` with a JSON body.
+    pub async fn put(&self, uri: &str, body: Value) -> TestResponse {
+        self.send(Method::PUT, uri, Some(body)).await
+    }
+
+    /// `DELETE uri`.
+    pub async fn delete(&self, uri: &str) -> TestResponse {
+        self.send(Method::DELETE, uri, None).await
+    }
+
+    /// Send a request built by hand; the bearer token is added if set and
+    /// the request has none.
+    pub async fn request(&self, mut request: Request<Body>) -> TestResponse {
+        if let Some(token) = &self.bearer {
+            if !request.headers().contains_key(header::AUTHORIZATION) {
+                let value = format!("Bearer {}", token)
+                    .parse()
+                    .expect("bearer token is a valid header value");
+                request.headers_mut().insert(header::AUTHORIZATION, value);
+            }
+        }
+
+        let response = self
+            .router
+            .clone()
+            .oneshot(request)
+            .await
+            .expect("router is infallible");
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("failed to read response body");
+
+        TestResponse {
+            status,
+            headers,
+            body,
+        }
+    }
+
+    async fn send(&self, method: Method, uri: &str, json: Option<Value>) -> TestResponse {
+        let builder = Request::builder().method(method).uri(uri);
+        let request = match json {
+            Some(json) => builder
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(json.to_string())),
+            None => builder.body(Body::empty()),
+        }
+        .expect("valid request");
+        self.request(request).await
+    }
+}
+
+/// A response read to the end.
+#[derive(Debug, Clone)]
+pub struct TestResponse {
+    /// Status code.
+    pub status: StatusCode,
+
+    /// Response headers.
+    pub headers: HeaderMap,
+
+    /// Response body.
+    pub body: Bytes,
+}
+
+impl TestResponse {
+    /// The body as JSON.
+    ///
+    /// # Panics
+    ///
+    /// If the body isn't JSON.
+    pub fn json(&self) -> Value {
+        self.json_as()
+    }
+
+    /// The body parsed as `T`.
+    ///
+    /// # Panics
+    ///
+    /// If the body doesn't parse.
+    pub fn json_as<T: DeserializeOwned>(&self) -> T {
+        match serde_json::from_slice(&self.body) {
+            Ok(value) => value,
+            Err(e) => panic!("response is not the expected JSON ({}): {}", e, self.text()),
+        }
+    }
+
+    /// The body as text, with invalid UTF-8 replaced.
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{test_skill, SkillFixture};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_test_server() {
+        let server = TestServer::new(TestSkills::new().with_skill(test_skill()));
+
+        let response = server.get("/api/skills/test-skill").await;
+        assert_eq!(response.status, StatusCode::OK);
+        assert_eq!(response.json()["name"], "test-skill");
+
+        // Skills written later show up after a reload
+        server
+            .skills()
+            .add(&SkillFixture::new("forms", "Form handling"));
+        assert_eq!(
+            server.post("/api/reload?wait=true", json!({})).await.status,
+            StatusCode::OK
+        );
+        assert!(server.state().indexer.get_skill_meta("forms").is_some());
+
+        let admin = TestServer::with_server(TestSkills::new(), |s| s.with_admin_token("secret"));
+        assert_eq!(
+            admin.get("/api/debug/index").await.status,
+            StatusCode::UNAUTHORIZED
+        );
+        let admin = admin.with_bearer("secret");
+        assert_eq!(admin.get("/api/debug/index").await.status, StatusCode::OK);
+    }
+}
@@ -0,0 +1,343 @@
+//! MCP spoken end to end over an in-process stream.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use tokio::io::{
+    AsyncBufReadExt, AsyncWriteExt, BufReader, DuplexStream, Lines, ReadHalf, WriteHalf,
+};
+use tokio::task::JoinHandle;
+
+use crate::mcp::{McpError, McpServer, PROTOCOL_VERSION};
+
+use super::TestSkills;
+
+/// Bytes buffered in each direction between client and server.
+const STREAM_BUFFER_BYTES: usize = 64 * 1024;
+
+/// How long to wait for a message before failing the test.
+const MESSAGE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// An [`McpServer`] over a [`TestSkills`] directory, driven through
+/// [`McpServer::serve`] the way Claude Desktop drives it over stdio: one
+/// JSON-RPC message per line, starting with the initialize handshake.
+///
+/// Tool handlers block, so tests need a multi-threaded runtime:
+/// `#[tokio::test(flavor = "multi_thread")]`. Helpers panic on protocol
+/// errors, as a test would.
+pub struct McpStreamClient {
+    writer: WriteHalf<DuplexStream>,
+    lines: Lines<BufReader<ReadHalf<DuplexStream>>>,
+    next_id: u64,
+    /// Notifications read while waiting for a response.
+    notifications: VecDeque<Value>,
+    initialized: Value,
+    server: JoinHandle<Result<(), McpError>>,
+    skills: TestSkills,
+}
+
+impl McpStreamClient {
+    /// Serve `skills` and complete the initialize handshake.
+    pub async fn connect(skills: TestSkills) -> Self {
+        Self::with_server(skills, |server| server).await
+    }
+
+    /// Serve `skills` with a server adjusted by `configure`, e.g. to attach
+    /// a journal, and complete the initialize handshake.
+    ///
+    /// The skills directory isn't watched; changes are picked up by
+    /// `reload_index` or the indexer.
+    pub async fn with_server(
+        skills: TestSkills,
+        configure: impl FnOnce(McpServer) -> McpServer,
+    ) -> Self {
+        let server = configure(McpServer::new(skills.path()).without_file_watcher());
+
+        let (client, connection) = tokio::io::duplex(STREAM_BUFFER_BYTES);
+        let (reader, writer) = tokio::io::split(connection);
+        let server =
+            tokio::spawn(async move { server.serve(BufReader::new(reader), writer).await });
+        let (reader, writer) = tokio::io::split(client);
+
+        let mut client = Self {
+            writer,
+            lines: BufReader::new(reader).lines(),
+            next_id: 0,
+            notifications: VecDeque::new(),
+            initialized: Value::Null,
+            server,
+            skills,
+        };
+        client.initialized = client
+            .request_ok(
+                "initialize",
+                json!({
+                    "protocolVersion": PROTOCOL_VERSION,
+                    "capabilities": {},
+                    "clientInfo": {"name": "mcp-stream-client", "version": crate::VERSION},
+                }),
+            )
+            .await;
+        client
+            .notify("notifications/initialized", Value::Null)
+            .await;
+        client
+    }
+
+    /// The skills directory being served.
+    pub fn skills(&self) -> &TestSkills {
+        &self.skills
+    }
+
+    /// The server's answer to `initialize`.
+    pub fn initialize_result(&self) -> &Value {
+        &self.initialized
+    }
+
+    /// Send a request and return the whole response, with its `result` or
+    /// `error`. Notifications arriving first are kept for
+    /// [`Self::next_notification`].
+    pub async fn request(&mut self, method: &str, params: Value) -> Value {
+        self.next_id += 1;
+        let id = self.next_id;
+        let mut message = json!({"jsonrpc": "2.0", "id": id, "method": method});
+        if !params.is_null() {
+            message["params"] = params;
+        }
+        self.send(&message).await;
+
+        loop {
+            let message = self.receive().await;
+            if message.get("id").is_none() {
+                self.notifications.push_back(message);
+                continue;
+            }
+            assert_eq!(
+                message["id"], id,
+                "response to another request: {}",
+                message
+            );
+            return message;
+        }
+    }
+
+    /// Send a request and return its `result`.
+    ///
+    /// # Panics
+    ///
+    /// If the server answers with an error.
+    pub async fn request_ok(&mut self, method: &str, params: Value) -> Value {
+        let mut response = self.request(method, params).await;
+        if let Some(error) = response.get("error") {
+            panic!("{} failed: {}", method, error);
+        }
+        response["result"].take()
+    }
+
+    /// Send a notification, which gets no response.
+    pub async fn notify(&mut self, method: &str, params: Value) {
+        let mut message = json!({"jsonrpc": "2.0", "method": method});
+        if !params.is_null() {
+            message["params"] = params;
+        }
+        self.send(&message).await;
+    }
+
+    /// The tools from `tools/list`.
+    pub async fn list_tools(&mut self) -> Vec<Value> {
+        let mut result = self.request_ok("tools/list", Value::Null).await;
+        match result["tools"].take() {
+            Value::Array(tools) => tools,
+            other => panic!("tools/list returned {}", other),
+        }
+    }
+
+    /// Call `tool` with `arguments` and return the `tools/call` result,
+    /// with its `content` and `isError`.
+    pub async fn call_tool(&mut self, tool: &str, arguments: Value) -> Value {
+        self.request_ok("tools/call", json!({"name": tool, "arguments": arguments}))
+            .await
+    }
+
+    /// Call `tool` and return its output, parsed as JSON when it is JSON
+    /// and as a string otherwise.
+    ///
+    /// # Panics
+    ///
+    /// If the tool fails.
+    pub async fn call_tool_ok(&mut self, tool: &str, arguments: Value) -> Value {
+        let result = self.call_tool(tool, arguments).await;
+        let text = tool_text(&result);
+        if result["isError"] != false {
+            panic!("{} failed: {}", tool, text);
+        }
+        serde_json::from_str(&text).unwrap_or(Value::String(text))
+    }
+
+    /// Call `tool` and return its error message.
+    ///
+    /// # Panics
+    ///
+    /// If the tool succeeds.
+    pub async fn call_tool_err(&mut self, tool: &str, arguments: Value) -> String {
+        let result = self.call_tool(tool, arguments).await;
+        let text = tool_text(&result);
+        if result["isError"] != true {
+            panic!("{} succeeded: {}", tool, text);
+        }
+        text
+    }
+
+    /// The next notification from the server, waiting for one if none has
+    /// arrived yet.
+    pub async fn next_notification(&mut self) -> Value {
+        if let Some(notification) = self.notifications.pop_front() {
+            return notification;
+        }
+        let message = self.receive().await;
+        assert!(
+            message.get("id").is_none(),
+            "unexpected response: {}",
+            message
+        );
+        message
+    }
+
+    /// Close the connection and wait for the server to finish.
+    ///
+    /// # Panics
+    ///
+    /// If the server failed.
+    pub async fn close(self) -> TestSkills {
+        let Self {
+            writer,
+            lines,
+            server,
+            skills,
+            ..
+        } = self;
+        drop(writer);
+        drop(lines);
+        match server.await {
+            Ok(Ok(())) => skills,
+            Ok(Err(e)) => panic!("server failed: {}", e),
+            Err(e) => panic!("server panicked: {}", e),
+        }
+    }
+
+    async fn send(&mut self, message: &Value) {
+        let line = format!("{}\n", message);
+        if let Err(e) = self.writer.write_all(line.as_bytes()).await {
+            panic!("failed to send {}: {}", message, e);
+        }
+    }
+
+    async fn receive(&mut self) -> Value {
+        let line = match tokio::time::timeout(MESSAGE_TIMEOUT, self.lines.next_line()).await {
+            Ok(Ok(Some(line))) => line,
+            Ok(Ok(None)) => panic!("server closed the connection"),
+            Ok(Err(e)) => panic!("failed to read from the server: {}", e),
+            Err(_) => panic!("no message from the server within {:?}", MESSAGE_TIMEOUT),
+        };
+        match serde_json::from_str(&line) {
+            Ok(message) => message,
+            Err(e) => panic!("server sent invalid JSON ({}): {}", e, line),
+        }
+    }
+}
+
+/// The text of a `tools/call` result.
+fn tool_text(result: &Value) -> String {
+    assert_eq!(result["content"][0]["type"], "text", "{}", result);
+    result["content"][0]["text"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::{error_codes, TOOL_NAMES};
+    use crate::testing::golden::{skills, tool_cases};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_every_tool_end_to_end() {
+        let mut client = McpStreamClient::connect(skills()).await;
+        assert_eq!(
+            client.initialize_result()["protocolVersion"],
+            PROTOCOL_VERSION
+        );
+
+        let tools = client.list_tools().await;
+        let listed: Vec<&str> = tools.iter().filter_map(|t| t["name"].as_str()).collect();
+        assert_eq!(listed, TOOL_NAMES);
+        for tool in &tools {
+            assert_eq!(tool["inputSchema"]["type"], "object", "{}", tool);
+        }
+
+        let cases = tool_cases();
+        for name in &listed {
+            assert!(
+                cases.iter().any(|(tool, _, _)| tool == name),
+                "no end-to-end case for {}",
+                name
+            );
+        }
+        for (tool, arguments, ok) in cases {
+            if !listed.contains(&tool) {
+                continue;
+            }
+            if ok {
+                client.call_tool_ok(tool, arguments).await;
+            } else {
+                client.call_tool_err(tool, arguments).await;
+            }
+        }
+
+        let loaded = client.call_tool_ok("get_loaded_skills", json!({})).await;
+        assert!(loaded.to_string().contains("forms"), "{}", loaded);
+        let skills = client.close().await;
+        assert!(skills.path().join("tables/_meta.json").exists());
+        assert!(!skills.path().join("charts").exists());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_protocol_errors() {
+        let mut client = McpStreamClient::connect(skills()).await;
+
+        let response = client.request("no/such/method", Value::Null).await;
+        assert_eq!(response["error"]["code"], error_codes::METHOD_NOT_FOUND);
+
+        let error = client
+            .call_tool_err("get_skill", json!({"name": "missing"}))
+            .await;
+        assert!(error.contains("missing"), "{}", error);
+
+        // The connection survives both
+        let skill = client
+            .call_tool_ok("get_skill", json!({"name": "test-skill"}))
+            .await;
+        assert!(skill["content"].as_str().unwrap().contains("Content."));
+        client.close().await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_resource_notifications() {
+        let mut client = McpStreamClient::connect(skills()).await;
+        client
+            .request_ok("resources/subscribe", json!({"uri": "skill://forms"}))
+            .await;
+
+        client
+            .skills()
+            .write("forms/SKILL.md", "# Forms\n\nChanged.");
+        client.call_tool_ok("reload_index", json!({})).await;
+
+        let notification = client.next_notification().await;
+        assert_eq!(notification["method"], "notifications/resources/updated");
+        assert_eq!(notification["params"]["uri"], "skill://forms");
+        client.close().await;
+    }
+}
@@ -0,0 +1,362 @@
+//! Browser state and key handling, independent of the terminal.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::index::SkillIndexer;
+use crate::models::{SkillMeta, ValidationResult};
+use crate::validation::{analyze_skill, SkillValidator};
+
+/// Lines scrolled by PageUp and PageDown in the preview.
+const PAGE_LINES: u16 = 10;
+
+/// Which panel fills the right-hand side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Pane {
+    /// The selected skill's SKILL.md.
+    Preview,
+    /// Validation issues for the selected skill.
+    Issues,
+}
+
+/// A skill matching the current filter.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Match {
+    /// Position in [`App::skills`].
+    pub index: usize,
+    /// Character positions in the name that matched, for highlighting.
+    pub positions: Vec<usize>,
+}
+
+/// State of the browser.
+pub(crate) struct App {
+    indexer: Arc<SkillIndexer>,
+    /// All skills, by name.
+    pub skills: Vec<SkillMeta>,
+    /// Fuzzy filter typed so far.
+    pub query: String,
+    /// Skills matching `query`, best first.
+    pub matches: Vec<Match>,
+    /// Position in `matches` of the selected skill.
+    pub selected: usize,
+    /// Panel on the right.
+    pub pane: Pane,
+    /// Lines the right panel is scrolled down.
+    pub scroll: u16,
+    /// Result of the last validation run.
+    pub validation: ValidationResult,
+    /// Message for the status bar.
+    pub status: String,
+    /// Set once the user asks to leave.
+    pub quit: bool,
+    previews: HashMap<String, String>,
+}
+
+impl App {
+    /// Load the index into a new browser.
+    pub fn new(indexer: Arc<SkillIndexer>) -> Self {
+        let mut app = Self {
+            indexer,
+            skills: Vec::new(),
+            query: String::new(),
+            matches: Vec::new(),
+            selected: 0,
+            pane: Pane::Preview,
+            scroll: 0,
+            validation: ValidationResult::pass(0),
+            status: String::new(),
+            quit: false,
+            previews: HashMap::new(),
+        };
+        app.load();
+        app
+    }
+
+    /// Reread the skills directory.
+    pub fn reload(&mut self) {
+        match self.indexer.reload() {
+            Ok(()) => {
+                self.load();
+                self.status = format!("Reloaded {} skills", self.skills.len());
+            }
+            Err(e) => self.status = format!("Reload failed: {}", e),
+        }
+    }
+
+    fn load(&mut self) {
+        let selected = self.selected_skill().map(|s| s.name.clone());
+
+        self.skills = self.indexer.get_skill_index().skills;
+        self.skills.sort_by(|a, b| a.name.cmp(&b.name));
+        self.validation = SkillValidator::new(Arc::clone(&self.indexer)).validate_all();
+        self.previews.clear();
+        self.refilter();
+
+        // Keep the same skill selected across reloads
+        if let Some(name) = selected {
+            if let Some(i) = self
+                .matches
+                .iter()
+                .position(|m| self.skills[m.index].name == name)
+            {
+                self.selected = i;
+            }
+        }
+    }
+
+    /// The skill under the cursor.
+    pub fn selected_skill(&self) -> Option<&SkillMeta> {
+        self.matches
+            .get(self.selected)
+            .map(|m| &self.skills[m.index])
+    }
+
+    /// SKILL.md of the selected skill, read on first view.
+    pub fn preview(&mut self) -> Option<&str> {
+        let name = self.selected_skill()?.name.clone();
+        let indexer = &self.indexer;
+        let text = self.previews.entry(name.clone()).or_insert_with(|| {
+            match indexer.read_skill_content(&name) {
+                Ok(content) => content.content,
+                Err(e) => format!("Failed to read '{}': {}", name, e),
+            }
+        });
+        Some(text.as_str())
+    }
+
+    /// Validation errors, warnings and readability findings for the
+    /// selected skill.
+    pub fn issues(&self) -> Vec<(IssueLevel, String)> {
+        let Some(skill) = self.selected_skill() else {
+            return Vec::new();
+        };
+        let prefix = format!("{}:", skill.name);
+        let about = |line: &&String| line.starts_with(&prefix);
+
+        let mut issues: Vec<(IssueLevel, String)> = Vec::new();
+        issues.extend(
+            self.validation
+                .errors
+                .iter()
+                .filter(about)
+                .map(|e| (IssueLevel::Error, e.clone())),
+        );
+        issues.extend(
+            self.validation
+                .warnings
+                .iter()
+                .filter(about)
+                .map(|w| (IssueLevel::Warning, w.clone())),
+        );
+        for finding in analyze_skill(&self.indexer, &skill.name).findings {
+            issues.push((
+                IssueLevel::Finding,
+                format!(
+                    "{}:{}: {} ({})",
+                    finding.file, finding.line, finding.message, finding.suggestion
+                ),
+            ));
+        }
+        issues
+    }
+
+    /// Handle a key press.
+    pub fn on_key(&mut self, key: KeyEvent) {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('c') if ctrl => self.quit = true,
+            KeyCode::Char('r') if ctrl => self.reload(),
+            KeyCode::F(5) => self.reload(),
+            KeyCode::Esc if self.query.is_empty() => self.quit = true,
+            KeyCode::Esc => {
+                self.query.clear();
+                self.refilter();
+            }
+            KeyCode::Up => self.select(self.selected.saturating_sub(1)),
+            KeyCode::Down => self.select(self.selected + 1),
+            KeyCode::Home => self.select(0),
+            KeyCode::End => self.select(self.matches.len().saturating_sub(1)),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(PAGE_LINES),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_add(PAGE_LINES),
+            KeyCode::Tab => {
+                self.pane = match self.pane {
+                    Pane::Preview => Pane::Issues,
+                    Pane::Issues => Pane::Preview,
+                };
+                self.scroll = 0;
+            }
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.refilter();
+            }
+            KeyCode::Char(c) if !ctrl => {
+                self.query.push(c);
+                self.refilter();
+            }
+            _ => {}
+        }
+    }
+
+    fn select(&mut self, position: usize) {
+        let position = position.min(self.matches.len().saturating_sub(1));
+        if position != self.selected {
+            self.selected = position;
+            self.scroll = 0;
+        }
+    }
+
+    fn refilter(&mut self) {
+        let mut scored: Vec<(i64, Match)> = self
+            .skills
+            .iter()
+            .enumerate()
+            .filter_map(|(index, skill)| {
+                if let Some((score, positions)) = fuzzy_match(&self.query, &skill.name) {
+                    return Some((score, Match { index, positions }));
+                }
+                // Descriptions and tags count for less and aren't highlighted
+                let other = format!("{} {}", skill.description, skill.tags.join(" "));
+                fuzzy_match(&self.query, &other).map(|(score, _)| {
+                    (
+                        score / 4,
+                        Match {
+                            index,
+                            positions: Vec::new(),
+                        },
+                    )
+                })
+            })
+            .collect();
+        scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.index.cmp(&b.1.index)));
+
+        self.matches = scored.into_iter().map(|(_, m)| m).collect();
+        self.selected = 0;
+        self.scroll = 0;
+    }
+}
+
+/// How serious an issue is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum IssueLevel {
+    /// Fails validation.
+    Error,
+    /// Passes validation but should be fixed.
+    Warning,
+    /// A readability problem in a document.
+    Finding,
+}
+
+/// Match `query` as a case-insensitive subsequence of `candidate`.
+///
+/// Returns a score, higher for runs of consecutive characters, matches at
+/// word starts and fewer skipped characters, with the matched character
+/// positions. An empty query matches everything equally.
+pub(crate) fn fuzzy_match(query: &str, candidate: &str) -> Option<(i64, Vec<usize>)> {
+    let mut positions = Vec::new();
+    let mut score: i64 = 0;
+    let mut wanted = query.chars().flat_map(char::to_lowercase).peekable();
+    let mut previous: Option<char> = None;
+    let mut last_match: Option<usize> = None;
+
+    for (i, c) in candidate.chars().enumerate() {
+        let Some(&want) = wanted.peek() else {
+            break;
+        };
+        if c.to_lowercase().eq(std::iter::once(want)) {
+            score += 16;
+            if last_match.is_some_and(|last| last + 1 == i) {
+                score += 8;
+            }
+            if previous.is_none_or(|p| !p.is_alphanumeric()) {
+                score += 10;
+            }
+            if let Some(last) = last_match {
+                score -= (i - last - 1) as i64;
+            }
+            positions.push(i);
+            last_match = Some(i);
+            wanted.next();
+        }
+        previous = Some(c);
+    }
+
+    if wanted.peek().is_some() {
+        return None;
+    }
+    // Shorter candidates with the same matches rank first
+    score -= candidate.chars().count() as i64 / 8;
+    Some((score, positions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_fuzzy_match() {
+        assert_eq!(fuzzy_match("fv", "form-validation").unwrap().1, vec![0, 5]);
+        assert!(fuzzy_match("xyz", "form-validation").is_none());
+        assert!(fuzzy_match("", "anything").is_some());
+
+        // Word starts and runs beat scattered letters
+        let start = fuzzy_match("val", "form-validation").unwrap().0;
+        let scattered = fuzzy_match("val", "overall-labels").unwrap().0;
+        assert!(start > scattered);
+    }
+
+    #[test]
+    fn test_filter_and_keys() {
+        let temp = TempDir::new().unwrap();
+        for (name, description) in [("forms", "Form handling"), ("charts", "Plotting data")] {
+            let dir = temp.path().join(name);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(
+                dir.join("_meta.json"),
+                format!(
+                    r#"{{"name": "{}", "description": "{}"}}"#,
+                    name, description
+                ),
+            )
+            .unwrap();
+            fs::write(dir.join("SKILL.md"), format!("# {}\n", name)).unwrap();
+        }
+        let indexer = Arc::new(SkillIndexer::new(temp.path()));
+        indexer.reload().unwrap();
+
+        let mut app = App::new(indexer);
+        assert_eq!(app.matches.len(), 2);
+        assert_eq!(app.selected_skill().unwrap().name, "charts");
+        assert_eq!(app.preview(), Some("# charts\n"));
+
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        app.on_key(key(KeyCode::Down));
+        assert_eq!(app.selected_skill().unwrap().name, "forms");
+        app.on_key(key(KeyCode::Down));
+        assert_eq!(app.selected_skill().unwrap().name, "forms");
+
+        for c in "plot".chars() {
+            app.on_key(key(KeyCode::Char(c)));
+        }
+        assert_eq!(app.matches.len(), 1);
+        assert_eq!(app.selected_skill().unwrap().name, "charts");
+        assert!(app.matches[0].positions.is_empty());
+
+        // Both skills lack tags
+        app.on_key(key(KeyCode::Tab));
+        assert_eq!(app.pane, Pane::Issues);
+        assert!(app
+            .issues()
+            .iter()
+            .any(|(level, text)| *level == IssueLevel::Warning && text.starts_with("charts:")));
+
+        app.on_key(key(KeyCode::Esc));
+        assert_eq!(app.matches.len(), 2);
+        assert!(!app.quit);
+        app.on_key(key(KeyCode::Esc));
+        assert!(app.quit);
+    }
+}
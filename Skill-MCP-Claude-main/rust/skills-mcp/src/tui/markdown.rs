@@ -0,0 +1,258 @@
+//! Markdown styled for the terminal.
+//!
+//! Covers what skill documents use: headings, fenced code, lists, quotes,
+//! rules and inline code, emphasis and links. Anything else is shown as
+//! written.
+
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span, Text};
+
+/// Style `markdown` line by line.
+pub(crate) fn render_markdown(markdown: &str) -> Text<'static> {
+    let mut lines = Vec::new();
+    let mut fence: Option<&str> = None;
+
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+                lines.push(Line::styled("└───", dim()));
+            } else {
+                lines.push(Line::from(vec![
+                    Span::styled("│ ", dim()),
+                    Span::styled(line.to_string(), Style::new().fg(Color::Yellow)),
+                ]));
+            }
+            continue;
+        }
+
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = Some(&trimmed[..3]);
+            let language = trimmed[3..].trim();
+            lines.push(Line::styled(format!("┌─── {}", language), dim()));
+            continue;
+        }
+
+        if let Some((level, title)) = heading(trimmed) {
+            let style = match level {
+                1 => Style::new()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+                2 => Style::new().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+                _ => Style::new().add_modifier(Modifier::BOLD),
+            };
+            lines.push(Line::styled(title.to_string(), style));
+            continue;
+        }
+
+        if is_rule(trimmed) {
+            lines.push(Line::styled("─".repeat(40), dim()));
+            continue;
+        }
+
+        if let Some(quote) = trimmed.strip_prefix('>') {
+            let mut spans = vec![Span::styled("▌ ", dim())];
+            spans.extend(
+                inline(quote.trim_start())
+                    .into_iter()
+                    .map(|span| span.patch_style(Style::new().add_modifier(Modifier::ITALIC))),
+            );
+            lines.push(Line::from(spans));
+            continue;
+        }
+
+        let indent = &line[..line.len() - trimmed.len()];
+        if let Some(item) = ["- ", "* ", "+ "]
+            .iter()
+            .find_map(|bullet| trimmed.strip_prefix(bullet))
+        {
+            let mut spans = vec![Span::raw(format!("{}• ", indent))];
+            spans.extend(inline(item));
+            lines.push(Line::from(spans));
+            continue;
+        }
+
+        let mut spans = Vec::new();
+        if !indent.is_empty() {
+            spans.push(Span::raw(indent.to_string()));
+        }
+        spans.extend(inline(trimmed));
+        lines.push(Line::from(spans));
+    }
+
+    Text::from(lines)
+}
+
+fn dim() -> Style {
+    Style::new().fg(Color::DarkGray)
+}
+
+/// Level and text of an ATX heading.
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let rest = &line[level..];
+    if !rest.is_empty() && !rest.starts_with(' ') {
+        return None;
+    }
+    Some((level, rest.trim().trim_end_matches('#').trim_end()))
+}
+
+fn is_rule(line: &str) -> bool {
+    let line = line.trim_end();
+    line.len() >= 3
+        && ['-', '*', '_']
+            .iter()
+            .any(|&c| line.chars().all(|l| l == c || l == ' '))
+}
+
+/// Spans for inline code, `**strong**`, `*emphasis*` and `[links](url)`.
+fn inline(text: &str) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut plain = String::new();
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        // Underscores inside words, as in snake_case, are literal
+        let in_word = plain.chars().last().is_some_and(char::is_alphanumeric);
+        let styled = match c {
+            '_' if in_word => None,
+            '`' => delimited(rest, "`", "`").map(|(inner, len)| {
+                (
+                    Span::styled(inner.to_string(), Style::new().fg(Color::Yellow)),
+                    len,
+                )
+            }),
+            '*' | '_' if rest[1..].starts_with(c) => {
+                let marker = &rest[..2];
+                delimited(rest, marker, marker).map(|(inner, len)| {
+                    (
+                        Span::styled(inner.to_string(), Style::new().add_modifier(Modifier::BOLD)),
+                        len,
+                    )
+                })
+            }
+            '*' | '_' => {
+                let marker = &rest[..1];
+                delimited(rest, marker, marker).map(|(inner, len)| {
+                    (
+                        Span::styled(
+                            inner.to_string(),
+                            Style::new().add_modifier(Modifier::ITALIC),
+                        ),
+                        len,
+                    )
+                })
+            }
+            '[' => link(rest).map(|(label, len)| {
+                (
+                    Span::styled(
+                        label.to_string(),
+                        Style::new()
+                            .fg(Color::Blue)
+                            .add_modifier(Modifier::UNDERLINED),
+                    ),
+                    len,
+                )
+            }),
+            _ => None,
+        };
+
+        match styled {
+            Some((span, len)) => {
+                if !plain.is_empty() {
+                    spans.push(Span::raw(std::mem::take(&mut plain)));
+                }
+                spans.push(span);
+                rest = &rest[len..];
+            }
+            None => {
+                plain.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+
+    if !plain.is_empty() {
+        spans.push(Span::raw(plain));
+    }
+    spans
+}
+
+/// Text between `open` at the start of `text` and the next `close`, with
+/// the length consumed.
+fn delimited<'a>(text: &'a str, open: &str, close: &str) -> Option<(&'a str, usize)> {
+    let body = &text[open.len()..];
+    let end = body.find(close)?;
+    if end == 0 {
+        return None;
+    }
+    Some((&body[..end], open.len() + end + close.len()))
+}
+
+/// Label of a `[label](target)` link at the start of `text`, with the
+/// length consumed.
+fn link(text: &str) -> Option<(&str, usize)> {
+    let close = text.find("](")?;
+    let label = &text[1..close];
+    if label.contains('[') || label.contains(']') {
+        return None;
+    }
+    let end = text[close..].find(')')?;
+    Some((label, close + end + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain(line: &Line) -> String {
+        line.spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect()
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let text = render_markdown(
+            "# Forms\n\nUse `validate()` and **always** see [docs](ref.md).\n\n```rust\n# not a heading\n```\n- item\n",
+        );
+        let lines: Vec<String> = text.lines.iter().map(plain).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "Forms",
+                "",
+                "Use validate() and always see docs.",
+                "",
+                "┌─── rust",
+                "│ # not a heading",
+                "└───",
+                "• item",
+            ]
+        );
+
+        assert!(text.lines[0].style.add_modifier.contains(Modifier::BOLD));
+        let spans = &text.lines[2].spans;
+        assert_eq!(spans[1].content, "validate()");
+        assert_eq!(spans[1].style.fg, Some(Color::Yellow));
+        assert!(spans[3].style.add_modifier.contains(Modifier::BOLD));
+        assert!(spans[5].style.add_modifier.contains(Modifier::UNDERLINED));
+    }
+
+    #[test]
+    fn test_unclosed_markers_stay_plain() {
+        let text = render_markdown("a * b and `c and [d]");
+        assert_eq!(text.lines[0].spans.len(), 1);
+        assert_eq!(plain(&text.lines[0]), "a * b and `c and [d]");
+
+        let text = render_markdown("call my_helper_fn");
+        assert_eq!(plain(&text.lines[0]), "call my_helper_fn");
+        assert_eq!(text.lines[0].spans.len(), 1);
+    }
+}
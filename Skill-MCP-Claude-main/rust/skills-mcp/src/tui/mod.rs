@@ -0,0 +1,52 @@
+//! Terminal browser for a skills directory.
+//!
+//! `skills tui` lists the index with a fuzzy filter, previews the selected
+//! skill's SKILL.md, shows its validation issues and reloads the directory
+//! on demand, for operators on a box without the web dashboard.
+//!
+//! Keys:
+//!
+//! - typing filters the list; Backspace edits and Esc clears the filter
+//! - Up/Down, Home/End select a skill
+//! - Tab switches between the preview and the issues panel
+//! - PageUp/PageDown scroll the panel
+//! - Ctrl-R or F5 reloads the index
+//! - Esc with an empty filter, or Ctrl-C, quits
+
+mod app;
+mod markdown;
+mod ui;
+
+use std::io;
+use std::sync::Arc;
+
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+
+use crate::index::SkillIndexer;
+
+use app::App;
+
+/// Run the browser until the user quits.
+///
+/// Takes over the terminal and restores it on return, including when
+/// drawing fails.
+pub fn run(indexer: Arc<SkillIndexer>) -> io::Result<()> {
+    let mut app = App::new(indexer);
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &mut app);
+    ratatui::restore();
+    result
+}
+
+fn event_loop(terminal: &mut ratatui::DefaultTerminal, app: &mut App) -> io::Result<()> {
+    while !app.quit {
+        terminal.draw(|frame| ui::draw(frame, app))?;
+        if let Event::Key(key) = event::read()? {
+            // Windows also reports releases
+            if key.kind == KeyEventKind::Press {
+                app.on_key(key);
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,142 @@
+//! Drawing the browser.
+
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::Frame;
+
+use super::app::{App, IssueLevel, Pane};
+use super::markdown::render_markdown;
+
+/// Draw the whole screen.
+pub(crate) fn draw(frame: &mut Frame, app: &mut App) {
+    let [main, status] =
+        Layout::vertical([Constraint::Min(3), Constraint::Length(1)]).areas(frame.area());
+    let [left, right] =
+        Layout::horizontal([Constraint::Percentage(35), Constraint::Percentage(65)]).areas(main);
+    let [filter, list] = Layout::vertical([Constraint::Length(3), Constraint::Min(1)]).areas(left);
+
+    draw_filter(frame, app, filter);
+    draw_list(frame, app, list);
+    match app.pane {
+        Pane::Preview => draw_preview(frame, app, right),
+        Pane::Issues => draw_issues(frame, app, right),
+    }
+    draw_status(frame, app, status);
+}
+
+fn draw_filter(frame: &mut Frame, app: &App, area: Rect) {
+    let title = format!(" Filter ({}/{}) ", app.matches.len(), app.skills.len());
+    let input = Paragraph::new(Line::from(vec![
+        Span::styled("> ", Style::new().fg(Color::DarkGray)),
+        Span::raw(app.query.clone()),
+    ]))
+    .block(Block::new().borders(Borders::ALL).title(title));
+    frame.render_widget(input, area);
+
+    // Cursor after the query, inside the border
+    let x = area.x + 3 + app.query.chars().count() as u16;
+    frame.set_cursor_position((x.min(area.right().saturating_sub(2)), area.y + 1));
+}
+
+fn draw_list(frame: &mut Frame, app: &App, area: Rect) {
+    let highlight = Style::new().fg(Color::Cyan).add_modifier(Modifier::BOLD);
+    let items: Vec<ListItem> = app
+        .matches
+        .iter()
+        .map(|m| {
+            let skill = &app.skills[m.index];
+            let name: Vec<Span> = skill
+                .name
+                .chars()
+                .enumerate()
+                .map(|(i, c)| {
+                    if m.positions.contains(&i) {
+                        Span::styled(c.to_string(), highlight)
+                    } else {
+                        Span::raw(c.to_string())
+                    }
+                })
+                .collect();
+            let description = Line::styled(
+                format!("  {}", skill.description),
+                Style::new().fg(Color::DarkGray),
+            );
+            ListItem::new(Text::from(vec![Line::from(name), description]))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(Block::new().borders(Borders::ALL).title(" Skills "))
+        .highlight_style(Style::new().bg(Color::DarkGray))
+        .highlight_symbol("▶ ");
+    let mut state = ListState::default().with_selected(Some(app.selected));
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+fn draw_preview(frame: &mut Frame, app: &mut App, area: Rect) {
+    let title = match app.selected_skill() {
+        Some(skill) => format!(" {} ", skill.name),
+        None => " Preview ".to_string(),
+    };
+    let text = app.preview().map(render_markdown).unwrap_or_default();
+    let preview = Paragraph::new(text)
+        .block(Block::new().borders(Borders::ALL).title(title))
+        .wrap(Wrap { trim: false })
+        .scroll((app.scroll, 0));
+    frame.render_widget(preview, area);
+}
+
+fn draw_issues(frame: &mut Frame, app: &App, area: Rect) {
+    let issues = app.issues();
+    let lines: Vec<Line> = if issues.is_empty() {
+        vec![Line::styled("No issues", Style::new().fg(Color::Green))]
+    } else {
+        issues
+            .into_iter()
+            .map(|(level, text)| {
+                let (label, color) = match level {
+                    IssueLevel::Error => ("error   ", Color::Red),
+                    IssueLevel::Warning => ("warning ", Color::Yellow),
+                    IssueLevel::Finding => ("finding ", Color::Blue),
+                };
+                Line::from(vec![
+                    Span::styled(label, Style::new().fg(color).add_modifier(Modifier::BOLD)),
+                    Span::raw(text),
+                ])
+            })
+            .collect()
+    };
+
+    let issues = Paragraph::new(lines)
+        .block(Block::new().borders(Borders::ALL).title(" Issues "))
+        .wrap(Wrap { trim: false })
+        .scroll((app.scroll, 0));
+    frame.render_widget(issues, area);
+}
+
+fn draw_status(frame: &mut Frame, app: &App, area: Rect) {
+    let counts = format!(
+        " {} errors, {} warnings ",
+        app.validation.errors.len(),
+        app.validation.warnings.len()
+    );
+    let counts_style = if app.validation.errors.is_empty() {
+        Style::new().fg(Color::Black).bg(Color::Green)
+    } else {
+        Style::new().fg(Color::White).bg(Color::Red)
+    };
+    let help = "↑↓ select  Tab preview/issues  PgUp/PgDn scroll  Ctrl-R reload  Esc quit";
+    let message = if app.status.is_empty() {
+        help.to_string()
+    } else {
+        format!("{}  ·  {}", app.status, help)
+    };
+
+    let line = Line::from(vec![
+        Span::styled(counts, counts_style),
+        Span::styled(format!(" {}", message), Style::new().fg(Color::DarkGray)),
+    ]);
+    frame.render_widget(Paragraph::new(line), area);
+}
@@ -0,0 +1,337 @@
+//! Readability checks for how well skill documents suit a model.
+//!
+//! These are heuristics, not validation: a flagged pattern is legal
+//! markdown that models tend to follow poorly. Each finding carries a
+//! suggestion for the author. Fenced code blocks are never flagged.
+
+use serde::Serialize;
+
+use crate::index::SkillIndexer;
+
+use super::headings::check_headings;
+
+/// Tables with more data rows than this are flagged.
+pub const MAX_TABLE_ROWS: usize = 25;
+
+/// Tables with more columns than this are flagged.
+pub const MAX_TABLE_COLUMNS: usize = 8;
+
+/// Lists nested deeper than this are flagged.
+pub const MAX_LIST_DEPTH: usize = 3;
+
+/// Files longer than this many lines are flagged.
+pub const MAX_FILE_LINES: usize = 5000;
+
+/// Paragraphs with at least this many sentences opening on a pronoun are
+/// flagged.
+const PRONOUN_SENTENCES: usize = 3;
+
+/// Sentence openers that leave the subject to be inferred.
+const VAGUE_OPENERS: &[&str] = &["it", "this", "that", "they", "these", "those", "them"];
+
+/// Kind of LLM-unfriendly pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FindingKind {
+    /// A table too large to read reliably.
+    LargeTable,
+    /// A list nested too deeply to follow.
+    DeepNesting,
+    /// A file too long to load whole.
+    LongFile,
+    /// Instructions whose subject is left to pronouns.
+    AmbiguousPronouns,
+    /// A heading more than one level below the one before it.
+    SkippedHeadingLevel,
+    /// A sub-skill document not titled with the sub-skill's name.
+    SubSkillTitle,
+}
+
+/// One flagged pattern in a skill document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Finding {
+    /// File relative to the skill directory.
+    pub file: String,
+    /// First line of the pattern, 1-based.
+    pub line: usize,
+    /// What was found.
+    pub kind: FindingKind,
+    /// Description of the problem.
+    pub message: String,
+    /// How to fix it.
+    pub suggestion: String,
+}
+
+/// Readability findings for one skill.
+#[derive(Debug, Clone, Serialize)]
+pub struct SkillAnalysis {
+    /// Skill analyzed.
+    pub skill: String,
+    /// Number of markdown documents read.
+    pub files_analyzed: usize,
+    /// Findings in file and line order.
+    pub findings: Vec<Finding>,
+}
+
+/// Analyze every markdown document of a skill.
+pub fn analyze_skill(indexer: &SkillIndexer, skill: &str) -> SkillAnalysis {
+    let mut analysis = SkillAnalysis {
+        skill: skill.to_string(),
+        files_analyzed: 0,
+        findings: Vec::new(),
+    };
+
+    let meta = indexer.get_skill_meta(skill);
+
+    for (_, file, path) in indexer.indexed_markdown_files(Some(skill)) {
+        let Ok(text) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        // Sub-skill documents must be titled with their sub-skill's name
+        let title = meta
+            .as_ref()
+            .and_then(|meta| meta.find_sub_skill_by_file(&file))
+            .map(|sub| sub.name.as_str());
+        analysis.files_analyzed += 1;
+        analysis.findings.extend(analyze(&file, &text, title));
+    }
+
+    analysis
+}
+
+/// Analyze one markdown document.
+pub fn analyze_document(file: &str, text: &str) -> Vec<Finding> {
+    analyze(file, text, None)
+}
+
+/// Analyze one markdown document, a sub-skill's when `title` is its name.
+fn analyze(file: &str, text: &str, title: Option<&str>) -> Vec<Finding> {
+    let mut findings = check_headings(file, text, title);
+
+    let line_count = text.lines().count();
+    if line_count > MAX_FILE_LINES {
+        findings.push(Finding::new(
+            file,
+            1,
+            FindingKind::LongFile,
+            format!("File has {} lines (over {})", line_count, MAX_FILE_LINES),
+            "Split it into sub-skills or reference files that can be loaded on demand",
+        ));
+    }
+
+    let mut table: Option<Block> = None;
+    let mut list: Option<Block> = None;
+    let mut list_indents: Vec<usize> = Vec::new();
+    let mut paragraph: Option<Block> = None;
+    let mut in_fence = false;
+
+    for (i, line) in text.lines().enumerate() {
+        let number = i + 1;
+        let trimmed = line.trim_start();
+
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        // Tables: count rows and the widest row
+        if trimmed.starts_with('|') {
+            let columns = trimmed.trim_end().trim_matches('|').split('|').count();
+            let block = table.get_or_insert(Block::new(number));
+            block.count += 1;
+            block.max = block.max.max(columns);
+        } else {
+            flush_table(table.take(), file, &mut findings);
+        }
+
+        // Lists: track the indent of each open level
+        let indent = line.len() - trimmed.len();
+        if is_list_item(trimmed) {
+            while list_indents.last().is_some_and(|&last| last >= indent) {
+                list_indents.pop();
+            }
+            list_indents.push(indent);
+            let block = list.get_or_insert(Block::new(number));
+            block.max = block.max.max(list_indents.len());
+        } else if trimmed.is_empty() || indent == 0 {
+            flush_list(list.take(), file, &mut findings);
+            list_indents.clear();
+        }
+
+        // Prose: count sentences opening on a pronoun per paragraph
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            flush_paragraph(paragraph.take(), file, &mut findings);
+        } else if !trimmed.starts_with('|') {
+            paragraph.get_or_insert(Block::new(number)).count += vague_sentences(trimmed);
+        }
+    }
+
+    flush_table(table, file, &mut findings);
+    flush_list(list, file, &mut findings);
+    flush_paragraph(paragraph, file, &mut findings);
+
+    findings.sort_by_key(|f| f.line);
+    findings
+}
+
+impl Finding {
+    pub(super) fn new(
+        file: &str,
+        line: usize,
+        kind: FindingKind,
+        message: String,
+        suggestion: &str,
+    ) -> Self {
+        Self {
+            file: file.to_string(),
+            line,
+            kind,
+            message,
+            suggestion: suggestion.to_string(),
+        }
+    }
+}
+
+/// A run of lines being measured: a table, list or paragraph.
+struct Block {
+    start: usize,
+    count: usize,
+    max: usize,
+}
+
+impl Block {
+    fn new(start: usize) -> Self {
+        Self {
+            start,
+            count: 0,
+            max: 0,
+        }
+    }
+}
+
+fn flush_table(table: Option<Block>, file: &str, findings: &mut Vec<Finding>) {
+    let Some(table) = table else { return };
+    // The header and divider rows aren't data
+    let rows = table.count.saturating_sub(2);
+    if rows > MAX_TABLE_ROWS || table.max > MAX_TABLE_COLUMNS {
+        findings.push(Finding::new(
+            file,
+            table.start,
+            FindingKind::LargeTable,
+            format!("Table has {} rows and {} columns", rows, table.max),
+            "Move it to a reference file, or split it into smaller tables or a list",
+        ));
+    }
+}
+
+fn flush_list(list: Option<Block>, file: &str, findings: &mut Vec<Finding>) {
+    let Some(list) = list else { return };
+    if list.max > MAX_LIST_DEPTH {
+        findings.push(Finding::new(
+            file,
+            list.start,
+            FindingKind::DeepNesting,
+            format!("List is nested {} levels deep", list.max),
+            "Flatten it into headed sections or numbered steps",
+        ));
+    }
+}
+
+fn flush_paragraph(paragraph: Option<Block>, file: &str, findings: &mut Vec<Finding>) {
+    let Some(paragraph) = paragraph else { return };
+    if paragraph.count >= PRONOUN_SENTENCES {
+        findings.push(Finding::new(
+            file,
+            paragraph.start,
+            FindingKind::AmbiguousPronouns,
+            format!("{} sentences start with a pronoun", paragraph.count),
+            "Name the file, command or value each instruction refers to",
+        ));
+    }
+}
+
+fn is_list_item(trimmed: &str) -> bool {
+    if let Some(rest) = trimmed.strip_prefix(['-', '*', '+']) {
+        return rest.starts_with(' ');
+    }
+    let digits = trimmed.chars().take_while(char::is_ascii_digit).count();
+    digits > 0 && trimmed[digits..].starts_with(". ")
+}
+
+/// Count sentences in a line that open with a vague pronoun.
+fn vague_sentences(line: &str) -> usize {
+    let line = line.trim_start_matches(['-', '*', '+', '>', ' ']);
+    line.split(['.', '!', '?', ';'])
+        .filter(|sentence| {
+            let first = sentence
+                .split_whitespace()
+                .next()
+                .unwrap_or("")
+                .trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase();
+            VAGUE_OPENERS.contains(&first.as_str())
+        })
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_large_table_and_long_file() {
+        let mut text = String::from("# Data\n\n| a | b |\n|---|---|\n");
+        for i in 0..30 {
+            text.push_str(&format!("| {} | x |\n", i));
+        }
+        text.push_str("\n| small | table |\n|---|---|\n| 1 | 2 |\n");
+
+        let findings = analyze_document("SKILL.md", &text);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].kind, FindingKind::LargeTable);
+        assert_eq!(findings[0].line, 3);
+        assert!(findings[0].message.contains("30 rows"));
+
+        let long = "line\n".repeat(MAX_FILE_LINES + 1);
+        let findings = analyze_document("big.md", &long);
+        assert_eq!(findings[0].kind, FindingKind::LongFile);
+    }
+
+    #[test]
+    fn test_deeply_nested_list() {
+        let text =
+            "Steps:\n\n- one\n  - two\n    - three\n      - four\n- back\n\n- flat\n  - ok\n";
+        let findings = analyze_document("SKILL.md", text);
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].kind, FindingKind::DeepNesting);
+        assert_eq!(findings[0].line, 3);
+        assert!(findings[0].message.contains("4 levels"));
+    }
+
+    #[test]
+    fn test_pronoun_heavy_instructions() {
+        let text = "# Setup\n\nRun the installer. It creates a config. Then edit it.\n\
+            This enables caching. They should be restarted after.\n\n\
+            Open `config.toml`. Set `cache = true`.\n";
+        let findings = analyze_document("SKILL.md", text);
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].kind, FindingKind::AmbiguousPronouns);
+        assert_eq!(findings[0].line, 3);
+    }
+
+    #[test]
+    fn test_code_blocks_are_skipped() {
+        let mut text = String::from("```\n");
+        for _ in 0..40 {
+            text.push_str("| it | is | code |\n");
+        }
+        text.push_str("```\n");
+
+        assert!(analyze_document("SKILL.md", &text).is_empty());
+    }
+}
@@ -0,0 +1,287 @@
+//! Heading hierarchy checks.
+//!
+//! Headings should step down one level at a time, so a model reading a
+//! section can tell what it belongs to, and a sub-skill document should
+//! open on an H1 naming the sub-skill. Both problems are reported as
+//! [`Finding`]s and can be fixed mechanically with [`fix_headings`].
+//! Headings in fenced code blocks and YAML frontmatter are ignored.
+
+use crate::models::title_case;
+
+use super::analysis::{Finding, FindingKind};
+
+/// Check the heading hierarchy of one markdown document.
+///
+/// `title` is the sub-skill name when the document is a sub-skill, which
+/// must then open on an H1 matching it.
+pub fn check_headings(file: &str, text: &str, title: Option<&str>) -> Vec<Finding> {
+    let lines = classify(text);
+    let mut findings = Vec::new();
+
+    if let Some(title) = title {
+        match first_content(&lines) {
+            Some((_, Line::Heading { level: 1, text })) if same_title(text, title) => {}
+            Some((i, Line::Heading { level: 1, text })) => findings.push(Finding::new(
+                file,
+                i + 1,
+                FindingKind::SubSkillTitle,
+                format!("Title '{}' doesn't match sub-skill '{}'", text, title),
+                "Title the document with the sub-skill's name, or run `skills fmt`",
+            )),
+            first => findings.push(Finding::new(
+                file,
+                first.map_or(1, |(i, _)| i + 1),
+                FindingKind::SubSkillTitle,
+                format!("Sub-skill '{}' doesn't open on an H1 title", title),
+                "Start the document with `# <sub-skill name>`, or run `skills fmt`",
+            )),
+        }
+    }
+
+    let mut previous: Option<usize> = None;
+    for (i, line) in lines.iter().enumerate() {
+        let Line::Heading { level, .. } = *line else {
+            continue;
+        };
+        if let Some(previous) = previous.filter(|&p| level > p + 1) {
+            findings.push(Finding::new(
+                file,
+                i + 1,
+                FindingKind::SkippedHeadingLevel,
+                format!("Heading jumps from H{} to H{}", previous, level),
+                "Step down one level at a time, or run `skills fmt` to relevel headings",
+            ));
+        }
+        previous = Some(level);
+    }
+
+    findings
+}
+
+/// Fix the heading hierarchy of one markdown document.
+///
+/// Headings that skip levels move up until each is at most one below its
+/// parent, with the headings under them moving along. With a `title`, a
+/// first H1 that doesn't match it is renamed, and a document without one
+/// gets it inserted before its first content. Everything else, line
+/// endings included, is left as it was.
+pub fn fix_headings(text: &str, title: Option<&str>) -> String {
+    let lines = classify(text);
+    let newline = if text.contains("\r\n") { "\r\n" } else { "\n" };
+
+    let mut retitle = None;
+    let mut insert_at = None;
+    if let Some(title) = title {
+        match first_content(&lines) {
+            Some((_, Line::Heading { level: 1, text })) if same_title(text, title) => {}
+            Some((i, Line::Heading { level: 1, .. })) => retitle = Some(i),
+            first => insert_at = Some(first.map_or(lines.len(), |(i, _)| i)),
+        }
+    }
+    let title = title.map(|t| title_case(t.rsplit('/').next().unwrap_or(t)));
+
+    // Open headings as (level in the text, level after fixing); an
+    // inserted title is the parent of everything below it
+    let mut open: Vec<(usize, usize)> = Vec::new();
+    if insert_at.is_some() {
+        open.push((1, 1));
+    }
+
+    let mut out = String::with_capacity(text.len());
+    for (i, (raw, line)) in text.split_inclusive('\n').zip(&lines).enumerate() {
+        if insert_at == Some(i) {
+            push_title(&mut out, title.as_deref().unwrap_or_default(), newline);
+        }
+        let Line::Heading { level, .. } = *line else {
+            out.push_str(raw);
+            continue;
+        };
+
+        while open.last().is_some_and(|&(original, _)| original >= level) {
+            open.pop();
+        }
+        let fixed = open
+            .last()
+            .map_or(level, |&(_, parent)| level.min(parent + 1));
+        open.push((level, fixed));
+
+        let indent = raw.len() - raw.trim_start_matches(' ').len();
+        if retitle == Some(i) {
+            let ending = &raw[raw.trim_end_matches(['\r', '\n']).len()..];
+            out.push_str(&raw[..indent]);
+            out.push_str("# ");
+            out.push_str(title.as_deref().unwrap_or_default());
+            out.push_str(ending);
+        } else {
+            out.push_str(&raw[..indent]);
+            out.push_str(&"#".repeat(fixed));
+            out.push_str(&raw[indent + level..]);
+        }
+    }
+    if insert_at == Some(lines.len()) {
+        if !out.is_empty() && !out.ends_with('\n') {
+            out.push_str(newline);
+        }
+        out.push_str("# ");
+        out.push_str(title.as_deref().unwrap_or_default());
+        out.push_str(newline);
+    }
+
+    out
+}
+
+/// What a line of a document is, as far as headings go.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Line<'a> {
+    Heading {
+        level: usize,
+        text: &'a str,
+    },
+    Blank,
+    Text,
+    /// Frontmatter or fenced code.
+    Skipped,
+}
+
+fn classify(text: &str) -> Vec<Line<'_>> {
+    let mut lines = Vec::new();
+    let mut in_frontmatter = text.lines().next().is_some_and(|l| l.trim_end() == "---");
+    let mut in_fence = false;
+
+    for (i, line) in text.lines().enumerate() {
+        if in_frontmatter {
+            if i > 0 && line.trim_end() == "---" {
+                in_frontmatter = false;
+            }
+            lines.push(Line::Skipped);
+            continue;
+        }
+
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            lines.push(Line::Skipped);
+        } else if in_fence {
+            lines.push(Line::Skipped);
+        } else if trimmed.is_empty() {
+            lines.push(Line::Blank);
+        } else if let Some((level, text)) = parse_heading(line) {
+            lines.push(Line::Heading { level, text });
+        } else {
+            lines.push(Line::Text);
+        }
+    }
+
+    lines
+}
+
+/// The level and text of an ATX heading line, if it is one.
+fn parse_heading(line: &str) -> Option<(usize, &str)> {
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    if indent > 3 {
+        return None;
+    }
+    let rest = &line[indent..];
+    let level = rest.len() - rest.trim_start_matches('#').len();
+    let after = &rest[level..];
+    if !(1..=6).contains(&level) || !(after.is_empty() || after.starts_with([' ', '\t'])) {
+        return None;
+    }
+
+    // A closing run of `#` isn't part of the text
+    let text = after.trim();
+    let text = match text.trim_end_matches('#') {
+        open if open.is_empty() || open.ends_with([' ', '\t']) => open.trim_end(),
+        _ => text,
+    };
+    Some((level, text))
+}
+
+/// The first line that isn't blank, frontmatter or code.
+fn first_content<'a>(lines: &[Line<'a>]) -> Option<(usize, Line<'a>)> {
+    lines
+        .iter()
+        .copied()
+        .enumerate()
+        .find(|(_, line)| matches!(line, Line::Heading { .. } | Line::Text))
+}
+
+/// Whether a heading names `name`, ignoring case and punctuation, so
+/// `# Form Layout` matches `form-layout`.
+fn same_title(heading: &str, name: &str) -> bool {
+    let words = |s: &str| -> Vec<String> {
+        s.split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .map(str::to_lowercase)
+            .collect()
+    };
+    words(heading) == words(name.rsplit('/').next().unwrap_or(name))
+}
+
+fn push_title(out: &mut String, title: &str, newline: &str) {
+    out.push_str("# ");
+    out.push_str(title);
+    out.push_str(newline);
+    out.push_str(newline);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skipped_heading_levels() {
+        let text = "# Guide\n\n### Setup\n\n#### Install\n\n## Usage\n\n```\n##### code\n```\n";
+        let findings = check_headings("SKILL.md", text, None);
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].kind, FindingKind::SkippedHeadingLevel);
+        assert_eq!(findings[0].line, 3);
+        assert_eq!(findings[0].message, "Heading jumps from H1 to H3");
+
+        let fixed = fix_headings(text, None);
+        assert_eq!(
+            fixed,
+            "# Guide\n\n## Setup\n\n### Install\n\n## Usage\n\n```\n##### code\n```\n"
+        );
+        assert!(check_headings("SKILL.md", &fixed, None).is_empty());
+    }
+
+    #[test]
+    fn test_sub_skill_title() {
+        let good = "# Form Layout\n\nGrid.\n";
+        assert!(check_headings("layout.md", good, Some("form-layout")).is_empty());
+        assert_eq!(fix_headings(good, Some("form-layout")), good);
+
+        let wrong = "# Layout\r\n\r\nGrid.\r\n";
+        let findings = check_headings("layout.md", wrong, Some("form-layout"));
+        assert_eq!(findings[0].kind, FindingKind::SubSkillTitle);
+        assert_eq!(
+            fix_headings(wrong, Some("form-layout")),
+            "# Form Layout\r\n\r\nGrid.\r\n"
+        );
+
+        let untitled = "---\nversion: 1\n---\n\n### Grid\n\nUse it.\n";
+        let findings = check_headings("layout.md", untitled, Some("form-layout"));
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].line, 5);
+        let fixed = fix_headings(untitled, Some("form-layout"));
+        assert_eq!(
+            fixed,
+            "---\nversion: 1\n---\n\n# Form Layout\n\n## Grid\n\nUse it.\n"
+        );
+        assert!(check_headings("layout.md", &fixed, Some("form-layout")).is_empty());
+
+        assert_eq!(fix_headings("", Some("api")), "# Api\n");
+    }
+
+    #[test]
+    fn test_parse_heading() {
+        assert_eq!(parse_heading("## Setup ##"), Some((2, "Setup")));
+        assert_eq!(parse_heading("   # C#"), Some((1, "C#")));
+        assert_eq!(parse_heading("#"), Some((1, "")));
+        assert_eq!(parse_heading("#hashtag"), None);
+        assert_eq!(parse_heading("    # indented code"), None);
+        assert_eq!(parse_heading("####### seven"), None);
+    }
+}
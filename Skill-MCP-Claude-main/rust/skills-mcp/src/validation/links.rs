@@ -0,0 +1,432 @@
+//! Health checks for external links in skill documents.
+//!
+//! Links are collected from the markdown files in the content index and
+//! checked with `HEAD` requests (falling back to `GET` for servers that
+//! reject `HEAD`). Requests to the same host are spaced out, the number in
+//! flight is capped, and results are cached so repeated checks stay cheap.
+//! Dead links are reported as validation warnings, never errors: a site
+//! being down shouldn't fail a skill.
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use regex::Regex;
+use serde::Serialize;
+use tokio::sync::Semaphore;
+use tracing::debug;
+
+use crate::index::SkillIndexer;
+use crate::models::ValidationResult;
+
+/// An external link found in a skill document.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct LinkSite {
+    /// Skill containing the link.
+    pub skill: String,
+    /// File containing the link, relative to the skill directory.
+    pub file: String,
+    /// The link itself.
+    pub url: String,
+}
+
+/// Result of checking one URL.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum LinkStatus {
+    /// The server answered with a success or redirect status.
+    Ok {
+        /// Final HTTP status.
+        status: u16,
+    },
+    /// The server answered with an error status.
+    Broken {
+        /// HTTP status.
+        status: u16,
+    },
+    /// No answer: DNS, connection, TLS or timeout failure.
+    Unreachable {
+        /// What went wrong.
+        error: String,
+    },
+}
+
+impl LinkStatus {
+    /// Whether the link should be reported.
+    pub fn is_dead(&self) -> bool {
+        !matches!(self, Self::Ok { .. })
+    }
+}
+
+/// A link that failed its check.
+#[derive(Debug, Clone, Serialize)]
+pub struct BrokenLink {
+    /// Where the link was found.
+    #[serde(flatten)]
+    pub site: LinkSite,
+    /// How the check failed.
+    pub status: LinkStatus,
+}
+
+/// Outcome of a link check run.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LinkReport {
+    /// Distinct URLs checked.
+    pub links_checked: usize,
+    /// How many of those were answered from the cache.
+    pub from_cache: usize,
+    /// Dead links, one per place they appear.
+    pub broken: Vec<BrokenLink>,
+}
+
+impl LinkReport {
+    /// Convert the report into validation warnings.
+    pub fn validation_result(&self, skills_checked: usize) -> ValidationResult {
+        let mut result = ValidationResult::pass(skills_checked);
+        for link in &self.broken {
+            let reason = match &link.status {
+                LinkStatus::Broken { status } => format!("HTTP {}", status),
+                LinkStatus::Unreachable { error } => error.clone(),
+                LinkStatus::Ok { .. } => continue,
+            };
+            result.add_warning(format!(
+                "{}: Dead link in {}: {} ({})",
+                link.site.skill, link.site.file, link.site.url, reason
+            ));
+        }
+        result
+    }
+}
+
+/// Rate-limited, caching checker for external links.
+pub struct LinkChecker {
+    client: reqwest::Client,
+    cache: Mutex<HashMap<String, (LinkStatus, Instant)>>,
+    next_slot: Mutex<HashMap<String, Instant>>,
+    permits: Arc<Semaphore>,
+    cache_ttl: Duration,
+    per_host_interval: Duration,
+}
+
+impl LinkChecker {
+    /// Default time a checked URL is trusted before being checked again.
+    pub const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+    /// Default gap between requests to the same host.
+    pub const DEFAULT_PER_HOST_INTERVAL: Duration = Duration::from_millis(500);
+
+    /// Default number of requests in flight.
+    pub const DEFAULT_CONCURRENCY: usize = 8;
+
+    /// Default time limit per request.
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+    /// Create a checker with default limits.
+    pub fn new() -> Self {
+        Self::with_timeout(Self::DEFAULT_TIMEOUT)
+    }
+
+    /// Create a checker with a custom per-request timeout.
+    pub fn with_timeout(timeout: Duration) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .user_agent(concat!(
+                "skills-mcp/",
+                env!("CARGO_PKG_VERSION"),
+                " link-checker"
+            ))
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            client,
+            cache: Mutex::new(HashMap::new()),
+            next_slot: Mutex::new(HashMap::new()),
+            permits: Arc::new(Semaphore::new(Self::DEFAULT_CONCURRENCY)),
+            cache_ttl: Self::DEFAULT_CACHE_TTL,
+            per_host_interval: Self::DEFAULT_PER_HOST_INTERVAL,
+        }
+    }
+
+    /// Set how long results are cached.
+    pub fn with_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = ttl;
+        self
+    }
+
+    /// Set the gap between requests to the same host.
+    pub fn with_per_host_interval(mut self, interval: Duration) -> Self {
+        self.per_host_interval = interval;
+        self
+    }
+
+    /// Set the number of requests in flight.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.permits = Arc::new(Semaphore::new(concurrency.max(1)));
+        self
+    }
+
+    /// Forget all cached results.
+    pub fn clear_cache(&self) {
+        self.cache.lock().clear();
+    }
+
+    /// Check every link found in the indexed skills.
+    pub async fn check_skills(self: &Arc<Self>, indexer: Arc<SkillIndexer>) -> LinkReport {
+        let sites = tokio::task::spawn_blocking(move || collect_links(&indexer))
+            .await
+            .unwrap_or_default();
+        self.check_sites(sites).await
+    }
+
+    /// Check a set of links, each distinct URL once.
+    pub async fn check_sites(self: &Arc<Self>, sites: Vec<LinkSite>) -> LinkReport {
+        let urls: BTreeSet<String> = sites.iter().map(|s| s.url.clone()).collect();
+        let mut report = LinkReport {
+            links_checked: urls.len(),
+            ..Default::default()
+        };
+
+        let mut statuses = HashMap::new();
+        let mut pending = tokio::task::JoinSet::new();
+        for url in urls {
+            if let Some(status) = self.cached(&url) {
+                report.from_cache += 1;
+                statuses.insert(url, status);
+                continue;
+            }
+
+            let checker = Arc::clone(self);
+            pending.spawn(async move {
+                let status = checker.check_url(&url).await;
+                (url, status)
+            });
+        }
+
+        while let Some(joined) = pending.join_next().await {
+            if let Ok((url, status)) = joined {
+                self.cache
+                    .lock()
+                    .insert(url.clone(), (status.clone(), Instant::now()));
+                statuses.insert(url, status);
+            }
+        }
+
+        for site in sites {
+            match statuses.get(&site.url) {
+                Some(status) if status.is_dead() => report.broken.push(BrokenLink {
+                    status: status.clone(),
+                    site,
+                }),
+                _ => {}
+            }
+        }
+
+        report
+    }
+
+    fn cached(&self, url: &str) -> Option<LinkStatus> {
+        let cache = self.cache.lock();
+        let (status, checked_at) = cache.get(url)?;
+        (checked_at.elapsed() < self.cache_ttl).then(|| status.clone())
+    }
+
+    /// Check one URL, waiting for a permit and the host's next slot.
+    async fn check_url(&self, url: &str) -> LinkStatus {
+        let _permit = self.permits.acquire().await;
+
+        let host = reqwest::Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_string))
+            .unwrap_or_default();
+        self.wait_for_host(&host).await;
+
+        debug!("Checking link {}", url);
+        let status = match self.client.head(url).send().await {
+            // Some servers don't implement HEAD; ask again with GET
+            Ok(response) if matches!(response.status().as_u16(), 403 | 405 | 501) => {
+                self.client.get(url).send().await
+            }
+            other => other,
+        };
+
+        match status {
+            Ok(response)
+                if response.status().is_client_error() || response.status().is_server_error() =>
+            {
+                LinkStatus::Broken {
+                    status: response.status().as_u16(),
+                }
+            }
+            Ok(response) => LinkStatus::Ok {
+                status: response.status().as_u16(),
+            },
+            Err(e) => LinkStatus::Unreachable {
+                error: if e.is_timeout() {
+                    "timed out".to_string()
+                } else {
+                    e.to_string()
+                },
+            },
+        }
+    }
+
+    /// Sleep until this host may be contacted again, then reserve the slot.
+    async fn wait_for_host(&self, host: &str) {
+        let wait = {
+            let mut slots = self.next_slot.lock();
+            let now = Instant::now();
+            let slot = slots.get(host).copied().filter(|t| *t > now).unwrap_or(now);
+            slots.insert(host.to_string(), slot + self.per_host_interval);
+            slot - now
+        };
+
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+impl Default for LinkChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Find the `http(s)` links in markdown, deduplicated in order.
+///
+/// Trailing sentence punctuation and unbalanced closing brackets (as in
+/// `[text](https://example.com)`) are not part of the link.
+pub fn extract_links(markdown: &str) -> Vec<String> {
+    static LINK: OnceLock<Regex> = OnceLock::new();
+    let re = LINK.get_or_init(|| Regex::new(r#"https?://[^\s<>"'`\]\[{}|\\^]+"#).unwrap());
+
+    let mut seen = BTreeSet::new();
+    let mut links = Vec::new();
+    for m in re.find_iter(markdown) {
+        let mut link = m
+            .as_str()
+            .trim_end_matches(['.', ',', ';', ':', '!', '?', '*', '_']);
+        while link.ends_with(')') && link.matches('(').count() < link.matches(')').count() {
+            link = link[..link.len() - 1].trim_end_matches(['.', ',', ';', ':', '!', '?']);
+        }
+        if seen.insert(link) {
+            links.push(link.to_string());
+        }
+    }
+    links
+}
+
+/// Collect the links in every indexed markdown document.
+pub fn collect_links(indexer: &SkillIndexer) -> Vec<LinkSite> {
+    let mut sites = BTreeSet::new();
+
+    for (skill, file, path) in indexer.indexed_markdown_files(None) {
+        let Ok(text) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+
+        for url in extract_links(&text) {
+            sites.insert(LinkSite {
+                skill: skill.clone(),
+                file: file.clone(),
+                url,
+            });
+        }
+    }
+
+    sites.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::StatusCode, routing::get, Router};
+
+    #[test]
+    fn test_extract_links() {
+        let markdown = "See [docs](https://example.com/a_(b)) and https://example.com/x.\n\
+            Also <http://example.org/path?q=1>, `https://example.com/x` and ftp://skip.me";
+
+        assert_eq!(
+            extract_links(markdown),
+            vec![
+                "https://example.com/a_(b)",
+                "https://example.com/x",
+                "http://example.org/path?q=1",
+            ]
+        );
+    }
+
+    async fn serve() -> String {
+        let app = Router::new()
+            .route("/ok", get(|| async { "fine" }))
+            .route("/gone", get(|| async { StatusCode::NOT_FOUND }))
+            .route(
+                "/get-only",
+                get(|| async { "fine" }).head(|| async { StatusCode::METHOD_NOT_ALLOWED }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    fn site(url: String) -> LinkSite {
+        LinkSite {
+            skill: "forms".to_string(),
+            file: "SKILL.md".to_string(),
+            url,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_sites_reports_dead_links_and_caches() {
+        let base = serve().await;
+        let checker = Arc::new(LinkChecker::new().with_per_host_interval(Duration::ZERO));
+
+        let sites = vec![
+            site(format!("{}/ok", base)),
+            site(format!("{}/gone", base)),
+            site(format!("{}/get-only", base)),
+            site("http://127.0.0.1:1/closed".to_string()),
+        ];
+
+        let report = checker.check_sites(sites.clone()).await;
+        assert_eq!(report.links_checked, 4);
+        assert_eq!(report.from_cache, 0);
+
+        let broken: Vec<(&str, &LinkStatus)> = report
+            .broken
+            .iter()
+            .map(|b| (b.site.url.as_str(), &b.status))
+            .collect();
+        assert_eq!(broken.len(), 2);
+        assert_eq!(broken[0].1, &LinkStatus::Broken { status: 404 });
+        assert!(matches!(broken[1].1, LinkStatus::Unreachable { .. }));
+
+        let result = report.validation_result(1);
+        assert!(result.valid);
+        assert!(result.warnings[0].contains("forms: Dead link in SKILL.md"));
+        assert!(result.warnings[0].contains("HTTP 404"));
+
+        let again = checker.check_sites(sites).await;
+        assert_eq!(again.from_cache, 4);
+        assert_eq!(again.broken.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_per_host_interval_spaces_requests() {
+        let base = serve().await;
+        let checker =
+            Arc::new(LinkChecker::new().with_per_host_interval(Duration::from_millis(100)));
+
+        let start = Instant::now();
+        let sites = (0..3).map(|i| site(format!("{}/ok?{}", base, i))).collect();
+        let report = checker.check_sites(sites).await;
+
+        assert!(report.broken.is_empty());
+        assert!(start.elapsed() >= Duration::from_millis(200));
+    }
+}
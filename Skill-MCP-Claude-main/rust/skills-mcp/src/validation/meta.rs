@@ -2,7 +2,62 @@
 
 use regex::Regex;
 
-use crate::models::SkillMeta;
+use crate::models::{SkillMeta, SubSkillMeta, Trigger, CATEGORY_SEPARATOR, MAX_SUB_SKILL_DEPTH};
+
+use super::{find_windows_reserved_component, is_windows_reserved};
+
+/// Maximum allowed description length.
+pub const MAX_DESCRIPTION_LENGTH: usize = 1000;
+
+/// Maximum allowed SKILL.md content length (1 MB).
+pub const MAX_CONTENT_LENGTH: usize = 1_000_000;
+
+/// Maximum number of tags per skill.
+pub const MAX_TAGS_COUNT: usize = 20;
+
+/// Maximum length of each tag.
+pub const MAX_TAG_LENGTH: usize = 50;
+
+/// Check the fields of a skill being created or edited against the size
+/// limits. Fields left `None` aren't checked. Returns a message saying what
+/// is wrong otherwise.
+pub fn check_skill_fields(
+    description: Option<&str>,
+    content: Option<&str>,
+    tags: Option<&[String]>,
+) -> Result<(), String> {
+    if description.is_some_and(|d| d.len() > MAX_DESCRIPTION_LENGTH) {
+        return Err(format!(
+            "Description too long (max {} characters)",
+            MAX_DESCRIPTION_LENGTH
+        ));
+    }
+
+    if content.is_some_and(|c| c.len() > MAX_CONTENT_LENGTH) {
+        return Err(format!(
+            "Content too long (max {} bytes)",
+            MAX_CONTENT_LENGTH
+        ));
+    }
+
+    let tags = tags.unwrap_or_default();
+    if tags.len() > MAX_TAGS_COUNT {
+        return Err(format!("Too many tags (max {})", MAX_TAGS_COUNT));
+    }
+    for tag in tags {
+        if tag.len() > MAX_TAG_LENGTH {
+            return Err(format!(
+                "Tag '{}' too long (max {} characters)",
+                tag, MAX_TAG_LENGTH
+            ));
+        }
+        if tag.is_empty() {
+            return Err("Tags cannot be empty".to_string());
+        }
+    }
+
+    Ok(())
+}
 
 /// Validate skill metadata.
 ///
@@ -10,15 +65,28 @@ use crate::models::SkillMeta;
 pub fn validate_meta(meta: &SkillMeta) -> Result<(), Vec<String>> {
     let mut errors = Vec::new();
 
-    // Validate name format: lowercase alphanumeric with hyphens
-    let name_regex = Regex::new(r"^[a-z0-9][a-z0-9-]*[a-z0-9]$|^[a-z0-9]$").unwrap();
-    if !name_regex.is_match(&meta.name) {
+    // Validate name format: lowercase alphanumeric with hyphens, optionally
+    // namespaced by category (frontend/forms)
+    let name_regex = Regex::new(r"^[a-z0-9]([a-z0-9-]*[a-z0-9])?$").unwrap();
+    if !meta
+        .name
+        .split(CATEGORY_SEPARATOR)
+        .all(|part| name_regex.is_match(part))
+    {
         errors.push(format!(
             "name: must be lowercase alphanumeric with hyphens, got '{}'",
             meta.name
         ));
     }
 
+    // Reject names Windows can't use as a directory
+    if meta.name.split(CATEGORY_SEPARATOR).any(is_windows_reserved) {
+        errors.push(format!(
+            "name: '{}' is a reserved name on Windows",
+            meta.name
+        ));
+    }
+
     // Validate name length
     if meta.name.is_empty() {
         errors.push("name: cannot be empty".to_string());
@@ -29,46 +97,107 @@ pub fn validate_meta(meta: &SkillMeta) -> Result<(), Vec<String>> {
         ));
     }
 
+    // Aliases are former names, so they follow the same format
+    for (i, alias) in meta.aliases.iter().enumerate() {
+        if !alias
+            .split(CATEGORY_SEPARATOR)
+            .all(|part| name_regex.is_match(part))
+        {
+            errors.push(format!(
+                "aliases[{}]: must be lowercase alphanumeric with hyphens, got '{}'",
+                i, alias
+            ));
+        } else if *alias == meta.name {
+            errors.push(format!("aliases[{}]: cannot be the skill's own name", i));
+        }
+    }
+
     // Validate description
     if meta.description.is_empty() {
         errors.push("description: cannot be empty".to_string());
     }
 
-    // Validate sub-skills if present
+    // Validate sub-skills if present, including nested ones
     if let Some(sub_skills) = &meta.sub_skills {
-        for (i, sub) in sub_skills.iter().enumerate() {
-            // Validate sub-skill name
-            if sub.name.is_empty() {
-                errors.push(format!("sub_skills[{}].name: cannot be empty", i));
-            }
+        validate_sub_skills(sub_skills, "sub_skills", 1, &mut errors);
+    }
 
-            // Validate sub-skill file
-            if sub.file.is_empty() {
-                errors.push(format!("sub_skills[{}].file: cannot be empty", i));
-            } else if !sub.file.ends_with(".md") {
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Validate sub-skills at `depth` and their children, reporting errors
+/// under the `field` path, e.g. `sub_skills[0].children`.
+fn validate_sub_skills(subs: &[SubSkillMeta], field: &str, depth: usize, errors: &mut Vec<String>) {
+    if depth > MAX_SUB_SKILL_DEPTH {
+        errors.push(format!(
+            "{}: sub-skills can be nested at most {} levels deep",
+            field, MAX_SUB_SKILL_DEPTH
+        ));
+        return;
+    }
+
+    for (i, sub) in subs.iter().enumerate() {
+        // Validate sub-skill name
+        if sub.name.is_empty() {
+            errors.push(format!("{}[{}].name: cannot be empty", field, i));
+        } else if sub.name.contains('/') {
+            // Slashes separate the levels of a sub-skill path
+            errors.push(format!(
+                "{}[{}].name: cannot contain '/', got '{}'",
+                field, i, sub.name
+            ));
+        }
+
+        // Validate sub-skill file, or the reference standing in for it
+        if let Some(reference) = &sub.reference {
+            if !sub.file.is_empty() {
                 errors.push(format!(
-                    "sub_skills[{}].file: must end with .md, got '{}'",
-                    i, sub.file
+                    "{}[{}]: cannot have both a file and a ref",
+                    field, i
                 ));
             }
-        }
-
-        // Check for duplicate sub-skill names
-        let mut seen_names = std::collections::HashSet::new();
-        for sub in sub_skills {
-            if !seen_names.insert(&sub.name) {
+            if sub.reference_target().is_none() {
                 errors.push(format!(
-                    "sub_skills: duplicate name '{}'",
-                    sub.name
+                    "{}[{}].ref: must be 'domain:sub-skill', got '{}'",
+                    field, i, reference
                 ));
             }
+        } else if sub.file.is_empty() {
+            errors.push(format!("{}[{}].file: cannot be empty", field, i));
+        } else if !sub.file.ends_with(".md") {
+            errors.push(format!(
+                "{}[{}].file: must end with .md, got '{}'",
+                field, i, sub.file
+            ));
+        } else if let Some(component) = find_windows_reserved_component(&sub.file) {
+            errors.push(format!(
+                "{}[{}].file: '{}' is a reserved name on Windows",
+                field, i, component
+            ));
+        }
+
+        for (j, trigger) in sub.triggers.iter().enumerate() {
+            if let Err(e) = Trigger::parse(trigger) {
+                errors.push(format!("{}[{}].triggers[{}]: {}", field, i, j, e));
+            }
+        }
+
+        if !sub.children.is_empty() {
+            let children = format!("{}[{}].children", field, i);
+            validate_sub_skills(&sub.children, &children, depth + 1, errors);
         }
     }
 
-    if errors.is_empty() {
-        Ok(())
-    } else {
-        Err(errors)
+    // Check for duplicate sub-skill names among siblings
+    let mut seen_names = std::collections::HashSet::new();
+    for sub in subs {
+        if !seen_names.insert(&sub.name) {
+            errors.push(format!("{}: duplicate name '{}'", field, sub.name));
+        }
     }
 }
 
@@ -101,6 +230,7 @@ mod tests {
             tags: vec![],
             sub_skills: None,
             source: None,
+            ..Default::default()
         };
 
         assert!(validate_meta(&meta).is_ok());
@@ -112,14 +242,15 @@ mod tests {
             name: "component-library".to_string(),
             description: "React component patterns".to_string(),
             tags: vec!["react".to_string(), "ui".to_string()],
-            sub_skills: Some(vec![
-                SubSkillMeta {
-                    name: "buttons".to_string(),
-                    file: "buttons/SKILL.md".to_string(),
-                    triggers: vec!["Button".to_string()],
-                },
-            ]),
+            sub_skills: Some(vec![SubSkillMeta {
+                name: "buttons".to_string(),
+                file: "buttons/SKILL.md".to_string(),
+                triggers: vec!["Button".to_string()],
+                children: Vec::new(),
+                reference: None,
+            }]),
             source: Some("official".to_string()),
+            ..Default::default()
         };
 
         assert!(validate_meta(&meta).is_ok());
@@ -133,6 +264,7 @@ mod tests {
             tags: vec![],
             sub_skills: None,
             source: None,
+            ..Default::default()
         };
 
         let result = validate_meta(&meta);
@@ -148,6 +280,7 @@ mod tests {
             tags: vec![],
             sub_skills: None,
             source: None,
+            ..Default::default()
         };
 
         let result = validate_meta(&meta);
@@ -162,6 +295,7 @@ mod tests {
             tags: vec![],
             sub_skills: None,
             source: None,
+            ..Default::default()
         };
 
         let result = validate_meta(&meta);
@@ -179,8 +313,11 @@ mod tests {
                 name: "react".to_string(),
                 file: "react/SKILL.txt".to_string(), // Wrong extension
                 triggers: vec![],
+                children: Vec::new(),
+                reference: None,
             }]),
             source: None,
+            ..Default::default()
         };
 
         let result = validate_meta(&meta);
@@ -199,14 +336,19 @@ mod tests {
                     name: "react".to_string(),
                     file: "react/SKILL.md".to_string(),
                     triggers: vec![],
+                    children: Vec::new(),
+                    reference: None,
                 },
                 SubSkillMeta {
                     name: "react".to_string(), // Duplicate
                     file: "react2/SKILL.md".to_string(),
                     triggers: vec![],
+                    children: Vec::new(),
+                    reference: None,
                 },
             ]),
             source: None,
+            ..Default::default()
         };
 
         let result = validate_meta(&meta);
@@ -214,6 +356,129 @@ mod tests {
         assert!(result.unwrap_err().iter().any(|e| e.contains("duplicate")));
     }
 
+    #[test]
+    fn test_nested_sub_skills() {
+        let leaf = |name: &str, file: &str| SubSkillMeta {
+            name: name.to_string(),
+            file: file.to_string(),
+            triggers: vec![],
+            children: Vec::new(),
+            reference: None,
+        };
+        let mut react = leaf("react", "react/SKILL.md");
+        react.children = vec![
+            leaf("hooks", "react/hooks.md"),
+            leaf("hooks", "react/hooks.txt"),
+        ];
+        let meta = SkillMeta {
+            name: "forms".to_string(),
+            description: "Test".to_string(),
+            sub_skills: Some(vec![react]),
+            ..Default::default()
+        };
+
+        let errors = validate_meta(&meta).unwrap_err();
+        assert!(errors.contains(
+            &"sub_skills[0].children[1].file: must end with .md, got 'react/hooks.txt'".to_string()
+        ));
+        assert!(errors.contains(&"sub_skills[0].children: duplicate name 'hooks'".to_string()));
+
+        // Nesting stops at MAX_SUB_SKILL_DEPTH levels
+        let mut deepest = leaf("a", "a.md");
+        for _ in 1..MAX_SUB_SKILL_DEPTH {
+            let mut parent = leaf("a", "a.md");
+            parent.children = vec![deepest];
+            deepest = parent;
+        }
+        let mut meta = SkillMeta {
+            sub_skills: Some(vec![deepest.clone()]),
+            ..meta
+        };
+        assert!(validate_meta(&meta).is_ok());
+
+        let mut parent = leaf("a", "a.md");
+        parent.children = vec![deepest];
+        meta.sub_skills = Some(vec![parent]);
+        let errors = validate_meta(&meta).unwrap_err();
+        assert!(errors[0].contains("nested at most"));
+
+        meta.sub_skills = Some(vec![leaf("react/hooks", "react/hooks.md")]);
+        assert!(validate_meta(&meta).unwrap_err()[0].contains("cannot contain '/'"));
+
+        let mut weighted = leaf("react", "react.md");
+        weighted.triggers = vec![
+            "form^2".to_string(),
+            "!vue".to_string(),
+            "jsx^often".to_string(),
+        ];
+        meta.sub_skills = Some(vec![weighted]);
+        assert_eq!(
+            validate_meta(&meta).unwrap_err(),
+            vec!["sub_skills[0].triggers[2]: invalid weight 'often' in 'jsx^often'"]
+        );
+    }
+
+    #[test]
+    fn test_sub_skill_references() {
+        let reference = |file: &str, reference: &str| SubSkillMeta {
+            name: "react".to_string(),
+            file: file.to_string(),
+            triggers: vec![],
+            children: Vec::new(),
+            reference: Some(reference.to_string()),
+        };
+        let mut meta = SkillMeta {
+            name: "ui".to_string(),
+            description: "Test".to_string(),
+            sub_skills: Some(vec![reference("", "forms:react")]),
+            ..Default::default()
+        };
+        assert!(validate_meta(&meta).is_ok());
+
+        meta.sub_skills = Some(vec![reference("react.md", "forms:react")]);
+        assert!(validate_meta(&meta).unwrap_err()[0].contains("both a file and a ref"));
+
+        meta.sub_skills = Some(vec![reference("", "forms")]);
+        assert!(validate_meta(&meta).unwrap_err()[0].contains("must be 'domain:sub-skill'"));
+    }
+
+    #[test]
+    fn test_windows_reserved_names() {
+        for name in ["con", "aux", "nul", "com1", "lpt9"] {
+            let meta = SkillMeta {
+                name: name.to_string(),
+                description: "Reserved".to_string(),
+                tags: vec![],
+                sub_skills: None,
+                source: None,
+                ..Default::default()
+            };
+
+            let result = validate_meta(&meta);
+            assert!(result.is_err(), "expected '{}' to be rejected", name);
+            assert!(result.unwrap_err()[0].contains("reserved name on Windows"));
+        }
+
+        let meta = SkillMeta {
+            name: "forms".to_string(),
+            description: "Test".to_string(),
+            tags: vec![],
+            sub_skills: Some(vec![SubSkillMeta {
+                name: "react".to_string(),
+                file: "react/CON.md".to_string(),
+                triggers: vec![],
+                children: Vec::new(),
+                reference: None,
+            }]),
+            source: None,
+            ..Default::default()
+        };
+
+        let result = validate_meta(&meta);
+        assert!(result.is_err());
+        assert!(result.unwrap_err()[0].contains("reserved name on Windows"));
+    }
+
     #[test]
     fn test_single_char_name() {
         let meta = SkillMeta {
@@ -222,8 +487,45 @@ mod tests {
             tags: vec![],
             sub_skills: None,
             source: None,
+            ..Default::default()
         };
 
         assert!(validate_meta(&meta).is_ok());
     }
+
+    #[test]
+    fn test_namespaced_names() {
+        let valid = |name: &str| {
+            validate_meta(&SkillMeta {
+                name: name.to_string(),
+                description: "Namespaced".to_string(),
+                ..Default::default()
+            })
+            .is_ok()
+        };
+
+        assert!(valid("frontend/forms"));
+        assert!(valid("backend/auth/oauth"));
+        assert!(!valid("/forms"));
+        assert!(!valid("frontend/"));
+        assert!(!valid("frontend//forms"));
+        assert!(!valid("frontend/Forms"));
+        assert!(!valid("frontend/con"));
+    }
+
+    #[test]
+    fn test_aliases() {
+        let with_aliases = |aliases: &[&str]| {
+            validate_meta(&SkillMeta {
+                name: "forms".to_string(),
+                description: "Forms".to_string(),
+                aliases: aliases.iter().map(|a| a.to_string()).collect(),
+                ..Default::default()
+            })
+        };
+
+        assert!(with_aliases(&["form-basics", "frontend/forms"]).is_ok());
+        assert!(with_aliases(&["Form Basics"]).is_err());
+        assert!(with_aliases(&["forms"]).is_err());
+    }
 }
@@ -3,8 +3,27 @@
 //! Validates skill metadata against the expected schema,
 //! matching the Zod validation in the TypeScript implementation.
 
+mod analysis;
+mod headings;
+mod links;
 mod meta;
+mod paths;
 mod skills;
 
-pub use meta::validate_meta;
+pub use analysis::{
+    analyze_document, analyze_skill, Finding, FindingKind, SkillAnalysis, MAX_FILE_LINES,
+    MAX_LIST_DEPTH, MAX_TABLE_COLUMNS, MAX_TABLE_ROWS,
+};
+pub use headings::{check_headings, fix_headings};
+pub use links::{
+    collect_links, extract_links, BrokenLink, LinkChecker, LinkReport, LinkSite, LinkStatus,
+};
+pub use meta::{
+    check_skill_fields, validate_meta, MAX_CONTENT_LENGTH, MAX_DESCRIPTION_LENGTH, MAX_TAGS_COUNT,
+    MAX_TAG_LENGTH,
+};
+pub use paths::{
+    check_skill_name, check_skill_path, find_windows_reserved_component, is_windows_reserved,
+    normalize_separators, MAX_SKILL_NAME_LENGTH,
+};
 pub use skills::{validate_skills, SkillValidator};
@@ -0,0 +1,184 @@
+//! Cross-platform path safety checks.
+
+use std::path::Path;
+
+use crate::models::CATEGORY_SEPARATOR;
+
+/// Maximum allowed skill name length.
+pub const MAX_SKILL_NAME_LENGTH: usize = 100;
+
+/// Characters that are not allowed in skill names.
+///
+/// `/` is allowed only as the category separator and checked per segment.
+const FORBIDDEN_CHARS: &[char] = &['\\', '\0', ':', '*', '?', '"', '<', '>', '|'];
+
+/// Device names reserved by Windows regardless of extension.
+const WINDOWS_RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Check whether a single path component cannot be created on Windows.
+///
+/// Reserved device names are matched case-insensitively and with any
+/// extension, so `con`, `Aux.md` and `nul.tar.gz` are all rejected. Names
+/// ending in a dot or space are rejected too, since Windows silently strips
+/// them. `.` and `..` are left to the traversal checks.
+pub fn is_windows_reserved(component: &str) -> bool {
+    if component == "." || component == ".." {
+        return false;
+    }
+
+    if component.ends_with('.') || component.ends_with(' ') {
+        return true;
+    }
+
+    let stem = component.split('.').next().unwrap_or(component).trim_end();
+    WINDOWS_RESERVED_NAMES
+        .iter()
+        .any(|reserved| stem.eq_ignore_ascii_case(reserved))
+}
+
+/// Normalize a relative path from metadata to use forward slashes.
+///
+/// Skill metadata is shared across platforms, so `react\SKILL.md` and
+/// `react/SKILL.md` must refer to the same file.
+pub fn normalize_separators(path: &str) -> String {
+    path.replace('\\', "/")
+}
+
+/// Find the first component of a relative path that is reserved on Windows.
+pub fn find_windows_reserved_component(path: &str) -> Option<&str> {
+    path.split(['/', '\\'])
+        .filter(|c| !c.is_empty())
+        .find(|c| is_windows_reserved(c))
+}
+
+/// Check that a skill name is safe to use as a path under the skills
+/// directory.
+///
+/// Namespaced names like `frontend/forms` are accepted. Returns a message
+/// saying what is wrong otherwise.
+pub fn check_skill_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("Skill name cannot be empty".to_string());
+    }
+
+    if name.len() > MAX_SKILL_NAME_LENGTH {
+        return Err(format!(
+            "Skill name too long (max {} characters)",
+            MAX_SKILL_NAME_LENGTH
+        ));
+    }
+
+    // Check for path traversal sequences
+    if name.contains("..") {
+        return Err("Skill name cannot contain '..'".to_string());
+    }
+
+    if name.chars().any(|c| FORBIDDEN_CHARS.contains(&c)) {
+        return Err("Skill name contains invalid characters".to_string());
+    }
+
+    for segment in name.split(CATEGORY_SEPARATOR) {
+        // Leading, trailing or doubled separators
+        if segment.is_empty() {
+            return Err("Skill name has an empty category segment".to_string());
+        }
+
+        // Hidden files
+        if segment.starts_with('.') {
+            return Err("Skill name cannot start with '.'".to_string());
+        }
+
+        // Names Windows can't create (CON, aux, trailing dots, ...)
+        if is_windows_reserved(segment) {
+            return Err(format!("Skill name '{}' is reserved on Windows", name));
+        }
+    }
+
+    Ok(())
+}
+
+/// Check that `skill_path` lies inside `skills_dir`.
+///
+/// Defense in depth behind [`check_skill_name`]: an existing path is
+/// resolved, symlinks included, and must stay under the skills directory;
+/// a path that doesn't exist yet must be exactly the skills directory
+/// joined with the skill's relative name.
+pub fn check_skill_path(skill_path: &Path, skills_dir: &Path) -> Result<(), String> {
+    // If skills_dir can't be canonicalized, e.g. it doesn't exist, use it as-is
+    let canonical_skills_dir = skills_dir
+        .canonicalize()
+        .unwrap_or_else(|_| skills_dir.to_path_buf());
+
+    // The relative name has several components for namespaced skills
+    let skill_name = match skill_path.strip_prefix(skills_dir) {
+        Ok(name) if !name.as_os_str().is_empty() => name,
+        _ => return Err("Invalid skill path".to_string()),
+    };
+
+    if skill_path.exists() {
+        let canonical_skill_path = skill_path
+            .canonicalize()
+            .map_err(|e| format!("Failed to resolve skill path: {}", e))?;
+        if !canonical_skill_path.starts_with(&canonical_skills_dir) {
+            return Err("Skill path is outside skills directory".to_string());
+        }
+    } else if skill_path != canonical_skills_dir.join(skill_name) {
+        return Err("Invalid skill path construction".to_string());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reserved_device_names() {
+        assert!(is_windows_reserved("CON"));
+        assert!(is_windows_reserved("con"));
+        assert!(is_windows_reserved("Aux"));
+        assert!(is_windows_reserved("nul.md"));
+        assert!(is_windows_reserved("LPT9.tar.gz"));
+        assert!(is_windows_reserved("com1"));
+
+        assert!(!is_windows_reserved("console"));
+        assert!(!is_windows_reserved("com10"));
+        assert!(!is_windows_reserved("auxiliary.md"));
+        assert!(!is_windows_reserved("forms"));
+    }
+
+    #[test]
+    fn test_trailing_dot_and_space() {
+        assert!(is_windows_reserved("forms."));
+        assert!(is_windows_reserved("forms "));
+        assert!(!is_windows_reserved("."));
+        assert!(!is_windows_reserved(".."));
+    }
+
+    #[test]
+    fn test_normalize_separators() {
+        assert_eq!(normalize_separators("react\\SKILL.md"), "react/SKILL.md");
+        assert_eq!(
+            normalize_separators("references/a\\b.md"),
+            "references/a/b.md"
+        );
+        assert_eq!(normalize_separators("SKILL.md"), "SKILL.md");
+    }
+
+    #[test]
+    fn test_find_reserved_component() {
+        assert_eq!(
+            find_windows_reserved_component("react/con.md"),
+            Some("con.md")
+        );
+        assert_eq!(
+            find_windows_reserved_component("aux\\SKILL.md"),
+            Some("aux")
+        );
+        assert_eq!(find_windows_reserved_component("react/SKILL.md"), None);
+    }
+}
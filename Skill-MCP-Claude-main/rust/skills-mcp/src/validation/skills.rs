@@ -1,12 +1,16 @@
 //! Full skill validation including file system checks.
 
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::sync::Arc;
+use std::time::Instant;
 
+use rayon::prelude::*;
+use regex::Regex;
 use tracing::debug;
 
 use crate::index::SkillIndexer;
-use crate::models::{SkillMeta, ValidationResult};
+use crate::models::{QualityScore, QualitySignals, SkillMeta, SkillTiming, ValidationResult};
 
 use super::validate_meta;
 
@@ -16,12 +20,18 @@ pub struct SkillValidator {
 }
 
 impl SkillValidator {
+    /// Number of slowest skills to log after validation.
+    const SLOWEST_REPORTED: usize = 5;
+
     /// Create a new skill validator.
     pub fn new(indexer: Arc<SkillIndexer>) -> Self {
         Self { indexer }
     }
 
     /// Validate all skills in the index.
+    ///
+    /// Skills are validated in parallel; results are merged in index order
+    /// so the output is deterministic.
     pub fn validate_all(&self) -> ValidationResult {
         let index = self.indexer.get_skill_index();
         let mut result = ValidationResult::pass(index.len());
@@ -30,10 +40,19 @@ impl SkillValidator {
         for error in &index.validation_errors {
             result.add_error(error.clone());
         }
+        for warning in &index.validation_warnings {
+            result.add_warning(warning.clone());
+        }
 
         // Validate each skill
-        for skill in &index.skills {
-            self.validate_skill(skill, &mut result);
+        let skill_results: Vec<ValidationResult> = index
+            .skills
+            .par_iter()
+            .map(|skill| self.validate_timed(skill))
+            .collect();
+
+        for skill_result in skill_results {
+            result.merge(skill_result);
         }
 
         debug!(
@@ -43,6 +62,73 @@ impl SkillValidator {
             result.warnings.len()
         );
 
+        for timing in result.slowest(Self::SLOWEST_REPORTED) {
+            debug!(
+                "Slow skill validation: {} took {:.1}ms",
+                timing.name, timing.duration_ms
+            );
+        }
+
+        result
+    }
+
+    /// Validate one indexed skill, or `None` if there is no such skill.
+    pub fn validate_one(&self, name: &str) -> Option<ValidationResult> {
+        let skill = self.indexer.get_skill_meta(name)?;
+        let mut result = self.validate_timed(&skill);
+        result.skills_checked = 1;
+        Some(result)
+    }
+
+    /// Compute a quality score for every skill in the index, by name.
+    pub fn quality_all(&self) -> HashMap<String, QualityScore> {
+        let index = self.indexer.get_skill_index();
+        let now = chrono::Utc::now();
+
+        index
+            .skills
+            .par_iter()
+            .map(|skill| (skill.name.clone(), self.quality_at(skill, now)))
+            .collect()
+    }
+
+    /// Compute a skill's quality score.
+    pub fn quality(&self, skill: &SkillMeta) -> QualityScore {
+        self.quality_at(skill, chrono::Utc::now())
+    }
+
+    fn quality_at(&self, skill: &SkillMeta, now: chrono::DateTime<chrono::Utc>) -> QualityScore {
+        let mut issues = ValidationResult::pass(0);
+        self.validate_skill(skill, &mut issues);
+
+        let main_doc = self
+            .indexer
+            .flat_skill_path(&skill.name)
+            .unwrap_or_else(|| self.indexer.skills_dir().join(&skill.name).join("SKILL.md"));
+        let content = std::fs::read_to_string(&main_doc).unwrap_or_default();
+        let modified = std::fs::metadata(&main_doc)
+            .and_then(|m| m.modified())
+            .ok()
+            .map(chrono::DateTime::<chrono::Utc>::from);
+
+        let signals = QualitySignals {
+            meta: skill,
+            content: &content,
+            modified,
+            errors: issues.errors.len(),
+            warnings: issues.warnings.len(),
+        };
+        QualityScore::compute(&signals, now)
+    }
+
+    /// Validate a single skill, recording how long it took.
+    fn validate_timed(&self, skill: &SkillMeta) -> ValidationResult {
+        let start = Instant::now();
+        let mut result = ValidationResult::pass(0);
+
+        self.validate_skill(skill, &mut result);
+        result.add_timing(SkillTiming::new(skill.name.clone(), start.elapsed()));
+
         result
     }
 
@@ -57,46 +143,87 @@ impl SkillValidator {
             }
         }
 
+        // Check for recommended fields
+        if skill.tags.is_empty() && skill.sub_skills.is_none() {
+            result.add_warning(format!(
+                "{}: No tags or sub_skills defined (reduces discoverability)",
+                skill.name
+            ));
+        }
+
+        // A flat-file skill is a single document with no files to check
+        if self.indexer.flat_skill_path(&skill.name).is_some() {
+            return;
+        }
+
         // Check SKILL.md exists
         let skill_md = skill_dir.join("SKILL.md");
         if !skill_md.exists() {
             result.add_error(format!("{}: Missing SKILL.md", skill.name));
         } else if std::fs::metadata(&skill_md).map(|m| m.len()).unwrap_or(0) == 0 {
             result.add_warning(format!("{}: SKILL.md is empty", skill.name));
+        } else {
+            self.check_script_references(skill, &skill_md, &skill_dir, result);
         }
 
         // Validate sub-skills
-        if let Some(sub_skills) = &skill.sub_skills {
-            for sub in sub_skills {
-                let sub_file = skill_dir.join(&sub.file);
-                if !sub_file.exists() {
+        for (path, sub) in skill.all_sub_skills() {
+            if let Some(reference) = &sub.reference {
+                if let Err(e) = self.indexer.resolve_sub_skill_file(&skill.name, &path) {
                     result.add_error(format!(
-                        "{}: Sub-skill file not found: {}",
-                        skill.name, sub.file
+                        "{}: Sub-skill '{}' refers to '{}': {}",
+                        skill.name, path, reference, e
                     ));
                 }
+                continue;
+            }
+            let sub_file = skill_dir.join(&sub.file);
+            if !sub_file.exists() {
+                result.add_error(format!(
+                    "{}: Sub-skill file not found: {}",
+                    skill.name, sub.file
+                ));
             }
         }
 
         // Check for orphaned sub-skill files (warning only)
         self.check_orphaned_files(skill, &skill_dir, result);
+    }
 
-        // Check for recommended fields
-        if skill.tags.is_empty() && skill.sub_skills.is_none() {
-            result.add_warning(format!(
-                "{}: No tags or sub_skills defined (reduces discoverability)",
-                skill.name
-            ));
+    /// Check that scripts mentioned in SKILL.md (`scripts/...`) exist.
+    fn check_script_references(
+        &self,
+        skill: &SkillMeta,
+        skill_md: &Path,
+        skill_dir: &Path,
+        result: &mut ValidationResult,
+    ) {
+        let Ok(content) = std::fs::read_to_string(skill_md) else {
+            return;
+        };
+
+        for script in script_references(&content) {
+            if !skill_dir.join(&script).is_file() {
+                result.add_error(format!(
+                    "{}: Referenced script not found: {}",
+                    skill.name, script
+                ));
+            }
         }
     }
 
     /// Check for sub-skill files that aren't referenced in _meta.json.
-    fn check_orphaned_files(&self, skill: &SkillMeta, skill_dir: &Path, result: &mut ValidationResult) {
+    fn check_orphaned_files(
+        &self,
+        skill: &SkillMeta,
+        skill_dir: &Path,
+        result: &mut ValidationResult,
+    ) {
         let referenced_files: std::collections::HashSet<_> = skill
-            .sub_skills
-            .as_ref()
-            .map(|subs| subs.iter().map(|s| s.file.as_str()).collect())
-            .unwrap_or_default();
+            .all_sub_skills()
+            .into_iter()
+            .map(|(_, s)| s.file.as_str())
+            .collect();
 
         // Look for .md files in subdirectories
         if let Ok(entries) = std::fs::read_dir(skill_dir) {
@@ -113,6 +240,10 @@ impl SkillValidator {
                     continue;
                 }
 
+                if self.indexer.is_ignored(&path, true) {
+                    continue;
+                }
+
                 // Check for SKILL.md in this subdirectory
                 let sub_skill_md = path.join("SKILL.md");
                 if sub_skill_md.exists() {
@@ -129,6 +260,22 @@ impl SkillValidator {
     }
 }
 
+/// Find the `scripts/...` paths mentioned in markdown, deduplicated in order.
+///
+/// Paths must start a word (so URLs are skipped) and may be written with a
+/// leading `./`. Trailing sentence punctuation is dropped.
+fn script_references(markdown: &str) -> Vec<String> {
+    let pattern = Regex::new(r#"(?:^|[\s`'"(\[])(?:\./)?(scripts/[A-Za-z0-9_./-]+)"#).unwrap();
+
+    let mut seen = HashSet::new();
+    pattern
+        .captures_iter(markdown)
+        .map(|c| c[1].trim_end_matches(['.', '/']).to_string())
+        .filter(|path| path.len() > "scripts/".len() && !path.contains(".."))
+        .filter(|path| seen.insert(path.clone()))
+        .collect()
+}
+
 /// Validate all skills using an indexer.
 pub fn validate_skills(indexer: Arc<SkillIndexer>) -> ValidationResult {
     let validator = SkillValidator::new(indexer);
@@ -157,7 +304,7 @@ mod tests {
             .unwrap();
 
             if let Some(subs) = &meta.sub_skills {
-                for sub in subs {
+                for sub in subs.iter().filter(|s| s.reference.is_none()) {
                     let sub_path = skill_dir.join(&sub.file);
                     if let Some(parent) = sub_path.parent() {
                         fs::create_dir_all(parent).unwrap();
@@ -178,6 +325,7 @@ mod tests {
             tags: vec!["validation".to_string()],
             sub_skills: None,
             source: None,
+            ..Default::default()
         };
         create_skill(temp_dir.path(), &meta, true);
 
@@ -199,15 +347,22 @@ mod tests {
             tags: vec![],
             sub_skills: None,
             source: None,
+            ..Default::default()
         };
         create_skill(temp_dir.path(), &meta, false);
 
         let indexer = Arc::new(SkillIndexer::new(temp_dir.path()));
         indexer.reload().unwrap();
 
-        let result = validate_skills(indexer);
+        let result = validate_skills(Arc::clone(&indexer));
         assert!(!result.valid);
         assert!(result.errors.iter().any(|e| e.contains("Missing SKILL.md")));
+
+        let validator = SkillValidator::new(indexer);
+        let one = validator.validate_one("forms").unwrap();
+        assert_eq!(one.skills_checked, 1);
+        assert_eq!(one.errors, result.errors);
+        assert!(validator.validate_one("missing").is_none());
     }
 
     #[test]
@@ -222,8 +377,11 @@ mod tests {
                 name: "react".to_string(),
                 file: "react/SKILL.md".to_string(),
                 triggers: vec![],
+                children: Vec::new(),
+                reference: None,
             }]),
             source: None,
+            ..Default::default()
         };
 
         // Create skill but don't create sub-skill file
@@ -241,7 +399,99 @@ mod tests {
 
         let result = validate_skills(indexer);
         assert!(!result.valid);
-        assert!(result.errors.iter().any(|e| e.contains("Sub-skill file not found")));
+        assert!(result
+            .errors
+            .iter()
+            .any(|e| e.contains("Sub-skill file not found")));
+    }
+
+    #[test]
+    fn test_validate_sub_skill_references() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let sub = |name: &str, file: &str, reference: Option<&str>| SubSkillMeta {
+            name: name.to_string(),
+            file: file.to_string(),
+            triggers: vec![],
+            children: Vec::new(),
+            reference: reference.map(str::to_string),
+        };
+        let skill = |name: &str, subs: Vec<SubSkillMeta>| SkillMeta {
+            name: name.to_string(),
+            description: "Test".to_string(),
+            tags: vec!["test".to_string()],
+            sub_skills: Some(subs),
+            ..Default::default()
+        };
+        create_skill(
+            temp_dir.path(),
+            &skill("forms", vec![sub("react", "react.md", None)]),
+            true,
+        );
+        create_skill(
+            temp_dir.path(),
+            &skill("ui", vec![sub("forms", "", Some("forms:react"))]),
+            true,
+        );
+        create_skill(
+            temp_dir.path(),
+            &skill("dangling", vec![sub("vue", "", Some("forms:vue"))]),
+            true,
+        );
+        create_skill(
+            temp_dir.path(),
+            &skill("ping", vec![sub("a", "", Some("pong:b"))]),
+            true,
+        );
+        create_skill(
+            temp_dir.path(),
+            &skill("pong", vec![sub("b", "", Some("ping:a"))]),
+            true,
+        );
+
+        let indexer = Arc::new(SkillIndexer::new(temp_dir.path()));
+        indexer.reload().unwrap();
+
+        let validator = SkillValidator::new(indexer);
+        assert!(validator.validate_one("ui").unwrap().errors.is_empty());
+
+        let errors = validator.validate_one("dangling").unwrap().errors;
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("refers to 'forms:vue'"));
+
+        let errors = validator.validate_one("ping").unwrap().errors;
+        assert!(errors[0].contains("cycle: ping:a -> pong:b -> ping:a"));
+    }
+
+    #[test]
+    fn test_validate_script_references() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let meta = SkillMeta {
+            name: "pdf".to_string(),
+            description: "PDF tools".to_string(),
+            tags: vec!["pdf".to_string()],
+            ..Default::default()
+        };
+        create_skill(temp_dir.path(), &meta, true);
+
+        let skill_dir = temp_dir.path().join("pdf");
+        fs::create_dir_all(skill_dir.join("scripts")).unwrap();
+        fs::write(skill_dir.join("scripts/fill.py"), "\"\"\"Fill forms.\"\"\"").unwrap();
+        fs::write(
+            skill_dir.join("SKILL.md"),
+            "Run `scripts/fill.py`, then ./scripts/check.sh.\nSee https://example.com/scripts/remote.py",
+        )
+        .unwrap();
+
+        let indexer = Arc::new(SkillIndexer::new(temp_dir.path()));
+        indexer.reload().unwrap();
+
+        let result = validate_skills(indexer);
+        assert_eq!(
+            result.errors,
+            vec!["pdf: Referenced script not found: scripts/check.sh".to_string()]
+        );
     }
 
     #[test]
@@ -254,6 +504,7 @@ mod tests {
             tags: vec![],
             sub_skills: None,
             source: None,
+            ..Default::default()
         };
         create_skill(temp_dir.path(), &meta, true);
 
@@ -264,4 +515,71 @@ mod tests {
         assert!(result.valid); // Warnings don't make it invalid
         assert!(result.warnings.iter().any(|w| w.contains("No tags")));
     }
+
+    #[test]
+    fn test_validate_records_per_skill_timing() {
+        let temp_dir = TempDir::new().unwrap();
+
+        for name in ["forms", "charts", "auth"] {
+            let meta = SkillMeta {
+                name: name.to_string(),
+                description: format!("{} patterns", name),
+                tags: vec!["test".to_string()],
+                sub_skills: None,
+                source: None,
+                ..Default::default()
+            };
+            create_skill(temp_dir.path(), &meta, true);
+        }
+
+        let indexer = Arc::new(SkillIndexer::new(temp_dir.path()));
+        indexer.reload().unwrap();
+
+        let result = validate_skills(indexer);
+        assert!(result.valid);
+        assert_eq!(result.skills_checked, 3);
+        assert_eq!(result.timings.len(), 3);
+        assert!(result
+            .timings
+            .windows(2)
+            .all(|w| w[0].duration_ms >= w[1].duration_ms));
+    }
+
+    #[test]
+    fn test_validate_respects_skillsignore() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let meta = SkillMeta {
+            name: "forms".to_string(),
+            description: "Form handling patterns".to_string(),
+            tags: vec!["validation".to_string()],
+            sub_skills: None,
+            source: None,
+            ..Default::default()
+        };
+        create_skill(temp_dir.path(), &meta, true);
+
+        // An unreferenced sub-skill directory that is excluded
+        let drafts = temp_dir.path().join("forms/drafts");
+        fs::create_dir_all(&drafts).unwrap();
+        fs::write(drafts.join("SKILL.md"), "# Draft").unwrap();
+
+        // A broken skill directory that is excluded entirely
+        fs::create_dir_all(temp_dir.path().join("scratch")).unwrap();
+
+        fs::write(
+            temp_dir.path().join(".skillsignore"),
+            "scratch/
+drafts/
+",
+        )
+        .unwrap();
+
+        let indexer = Arc::new(SkillIndexer::new(temp_dir.path()));
+        indexer.reload().unwrap();
+
+        let result = validate_skills(indexer);
+        assert!(result.valid, "errors: {:?}", result.errors);
+        assert!(!result.warnings.iter().any(|w| w.contains("drafts")));
+    }
 }
@@ -0,0 +1,93 @@
+//! JavaScript bindings for searching a skill index in the browser.
+//!
+//! Build without the native runtime:
+//!
+//! ```text
+//! wasm-pack build --target web -- --no-default-features --features wasm
+//! ```
+//!
+//! then load the index the API serves and search it locally:
+//!
+//! ```js
+//! const index = await (await fetch("/api/search/index?format=bin")).arrayBuffer();
+//! const search = SkillSearch.fromBytes(new Uint8Array(index));
+//! const results = JSON.parse(search.search("forms", "all", 10, 0, "suggest"));
+//! ```
+
+use wasm_bindgen::prelude::*;
+
+use crate::models::{SearchOptions, SearchScope, SpellingMode};
+use crate::search::IndexSnapshot;
+
+/// A loaded [`IndexSnapshot`].
+#[wasm_bindgen]
+pub struct SkillSearch {
+    snapshot: IndexSnapshot,
+}
+
+#[wasm_bindgen]
+impl SkillSearch {
+    /// Load the snapshot JSON served at `GET /api/search/index`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(snapshot_json: &str) -> Result<SkillSearch, JsError> {
+        let snapshot = serde_json::from_str(snapshot_json)
+            .map_err(|e| JsError::new(&format!("Invalid index snapshot: {}", e)))?;
+        Ok(Self { snapshot })
+    }
+
+    /// Load a snapshot in the versioned binary encoding, as served at
+    /// `GET /api/search/index?format=bin` or written by `skills index`.
+    #[wasm_bindgen(js_name = fromBytes)]
+    pub fn from_bytes(bytes: &[u8]) -> Result<SkillSearch, JsError> {
+        let snapshot =
+            IndexSnapshot::from_bytes(bytes).map_err(|e| JsError::new(&e.to_string()))?;
+        Ok(Self { snapshot })
+    }
+
+    /// Index version the snapshot was taken at.
+    #[wasm_bindgen(getter)]
+    pub fn version(&self) -> f64 {
+        self.snapshot.version as f64
+    }
+
+    /// Number of skills in the snapshot.
+    #[wasm_bindgen(getter, js_name = skillCount)]
+    pub fn skill_count(&self) -> usize {
+        self.snapshot.skill_index.skills.len()
+    }
+
+    /// Search `scope` (`skills`, `content` or `all`) and return the results
+    /// as JSON, shaped like `GET /api/search`'s.
+    ///
+    /// `spelling` is `suggest` or `auto`, or omitted to leave queries as
+    /// typed.
+    pub fn search(
+        &self,
+        query: &str,
+        scope: &str,
+        limit: usize,
+        offset: usize,
+        spelling: Option<String>,
+    ) -> Result<String, JsError> {
+        let scope = match scope {
+            "skills" => SearchScope::Skills,
+            "content" => SearchScope::Content,
+            "all" => SearchScope::All,
+            _ => {
+                return Err(JsError::new(&format!(
+                    "Invalid scope: '{}' (use 'skills', 'content' or 'all')",
+                    scope
+                )))
+            }
+        };
+
+        let mut options = SearchOptions::with_limit(limit).offset(offset);
+        if let Some(spelling) = spelling {
+            let mode: SpellingMode = spelling.parse().map_err(|e: String| JsError::new(&e))?;
+            options = options.spelling(mode);
+        }
+
+        let results = self.snapshot.search(scope, query, options);
+        serde_json::to_string(&results).map_err(|e| JsError::new(&e.to_string()))
+    }
+}